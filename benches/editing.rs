@@ -0,0 +1,168 @@
+use criterion::{BatchSize, BenchmarkId, Criterion, criterion_group, criterion_main};
+use dmacs::editor::Editor;
+use dmacs::editor::clipboard::ClipboardBackend;
+use std::hint::black_box;
+
+const NOTES: &str = include_str!("fixtures/notes.md");
+const JAPANESE: &str = include_str!("fixtures/japanese.md");
+// Copies of the notes fixture in the large document, about 10,000 lines
+const LARGE_COPIES: usize = 200;
+// Characters typed, deleted or undone per iteration
+const EDITS: usize = 20;
+const KILLED_LINES: usize = 10;
+
+/// The documents every operation is measured on.
+fn documents() -> Vec<(&'static str, Vec<String>, &'static str)> {
+    let notes: Vec<String> = NOTES.lines().map(String::from).collect();
+    let large = notes
+        .iter()
+        .cycle()
+        .take(notes.len() * LARGE_COPIES)
+        .cloned()
+        .collect();
+    let japanese = JAPANESE.lines().map(String::from).collect();
+    vec![
+        ("small", notes, "quick brown fox"),
+        ("large", large, "quick brown fox"),
+        ("cjk", japanese, "日本語の文章を入力する"),
+    ]
+}
+
+fn editor_with_lines(lines: &[String]) -> Editor {
+    let mut editor = Editor::new(None, None, None);
+    editor.clipboard.set_backend(ClipboardBackend::Off);
+    editor.document.set_lines(lines.to_vec());
+    editor
+}
+
+/// The end of the first line from the middle of the document on that is long
+/// enough to delete `EDITS` characters from.
+fn middle_line_end(lines: &[String]) -> (usize, usize) {
+    let y = (lines.len() / 2..lines.len())
+        .chain(0..lines.len() / 2)
+        .find(|&y| lines[y].chars().count() > EDITS)
+        .expect("fixture has a long enough line");
+    (lines[y].len(), y)
+}
+
+fn type_text(editor: &mut Editor, text: &str) {
+    for ch in text.chars().cycle().take(EDITS) {
+        editor.insert_text(&ch.to_string()).unwrap();
+    }
+}
+
+fn bench_insert(c: &mut Criterion) {
+    let mut group = c.benchmark_group("insert");
+    for (name, lines, text) in documents() {
+        let (x, y) = middle_line_end(&lines);
+        group.bench_with_input(BenchmarkId::from_parameter(name), &lines, |b, lines| {
+            b.iter_batched(
+                || {
+                    let mut editor = editor_with_lines(lines);
+                    editor.set_cursor_pos(x, y);
+                    editor
+                },
+                |mut editor| {
+                    type_text(&mut editor, text);
+                    editor.insert_newline().unwrap();
+                    black_box(editor)
+                },
+                BatchSize::LargeInput,
+            );
+        });
+    }
+    group.finish();
+}
+
+fn bench_delete(c: &mut Criterion) {
+    let mut group = c.benchmark_group("delete");
+    for (name, lines, _) in documents() {
+        let (x, y) = middle_line_end(&lines);
+        group.bench_with_input(BenchmarkId::from_parameter(name), &lines, |b, lines| {
+            b.iter_batched(
+                || {
+                    let mut editor = editor_with_lines(lines);
+                    editor.set_cursor_pos(x, y);
+                    editor
+                },
+                |mut editor| {
+                    for _ in 0..EDITS {
+                        editor.delete_char().unwrap();
+                    }
+                    // Join the next line, as Delete at the end of a line does
+                    editor.go_to_end_of_line();
+                    editor.delete_forward_char().unwrap();
+                    black_box(editor)
+                },
+                BatchSize::LargeInput,
+            );
+        });
+    }
+    group.finish();
+}
+
+fn bench_kill_yank(c: &mut Criterion) {
+    let mut group = c.benchmark_group("kill_yank");
+    for (name, lines, _) in documents() {
+        let y = lines.len() / 2;
+        group.bench_with_input(BenchmarkId::from_parameter(name), &lines, |b, lines| {
+            b.iter_batched(
+                || {
+                    let mut editor = editor_with_lines(lines);
+                    editor.set_cursor_pos(0, y);
+                    editor
+                },
+                |mut editor| {
+                    // Kill the text and the line break of each line
+                    for _ in 0..KILLED_LINES * 2 {
+                        editor.kill_line().unwrap();
+                    }
+                    editor.yank().unwrap();
+                    editor.yank().unwrap();
+                    black_box(editor)
+                },
+                BatchSize::LargeInput,
+            );
+        });
+    }
+    group.finish();
+}
+
+fn bench_undo(c: &mut Criterion) {
+    let mut group = c.benchmark_group("undo_redo");
+    for (name, lines, text) in documents() {
+        let (x, y) = middle_line_end(&lines);
+        group.bench_with_input(BenchmarkId::from_parameter(name), &lines, |b, lines| {
+            b.iter_batched(
+                || {
+                    let mut editor = editor_with_lines(lines);
+                    // Every typed character becomes an undo step of its own
+                    editor.set_undo_debounce_threshold(0);
+                    editor.set_cursor_pos(x, y);
+                    type_text(&mut editor, text);
+                    editor
+                },
+                |mut editor| {
+                    for _ in 0..EDITS {
+                        editor.undo();
+                    }
+                    for _ in 0..EDITS {
+                        editor.redo();
+                    }
+                    black_box(editor)
+                },
+                BatchSize::LargeInput,
+            );
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_insert,
+    bench_delete,
+    bench_kill_yank,
+    bench_undo
+);
+criterion_main!(benches);
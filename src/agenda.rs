@@ -0,0 +1,191 @@
+use crate::document::LineBuffer;
+use crate::editor::date_picker::parse_due;
+use crate::error::{DmacsError, Result};
+use chrono::{Datelike, Days, NaiveDate};
+use log::error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const UNCHECKED: &str = "- [ ] ";
+const CHECKED: &str = "- [x] ";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AgendaGroup {
+    Overdue,
+    Today,
+    ThisWeek,
+    Later,
+    NoDate,
+}
+
+/// Which tasks the agenda lists.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AgendaScope {
+    /// Tasks due by the end of the week.
+    #[default]
+    ThisWeek,
+    /// Every unchecked task, dated or not.
+    All,
+}
+
+impl AgendaGroup {
+    pub fn title(&self) -> &'static str {
+        match self {
+            AgendaGroup::Overdue => "Overdue",
+            AgendaGroup::Today => "Today",
+            AgendaGroup::ThisWeek => "This week",
+            AgendaGroup::Later => "Later",
+            AgendaGroup::NoDate => "No date",
+        }
+    }
+
+    /// Tasks due after the end of the current week (Sunday) are left out.
+    pub fn for_date(due: NaiveDate, today: NaiveDate) -> Option<Self> {
+        let days_to_sunday = 6 - today.weekday().num_days_from_monday() as u64;
+        let end_of_week = today.checked_add_days(Days::new(days_to_sunday))?;
+        if due < today {
+            Some(AgendaGroup::Overdue)
+        } else if due == today {
+            Some(AgendaGroup::Today)
+        } else if due <= end_of_week {
+            Some(AgendaGroup::ThisWeek)
+        } else {
+            None
+        }
+    }
+
+    /// The group of a task in the list of all tasks.
+    pub fn for_task(due: Option<NaiveDate>, today: NaiveDate) -> Self {
+        match due {
+            Some(due) => Self::for_date(due, today).unwrap_or(AgendaGroup::Later),
+            None => AgendaGroup::NoDate,
+        }
+    }
+}
+
+/// An unchecked task, with its `@due(...)` date when it has one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AgendaItem {
+    // `None` for tasks in the buffer being edited
+    pub path: Option<PathBuf>,
+    pub line: usize,
+    pub text: String,
+    pub due: Option<NaiveDate>,
+    pub group: AgendaGroup,
+}
+
+/// Collects the unchecked tasks of `lines` that fall into an agenda group.
+pub fn collect_tasks(lines: &LineBuffer, path: Option<&Path>, today: NaiveDate) -> Vec<AgendaItem> {
+    collect_tasks_in_scope(lines, path, today, AgendaScope::ThisWeek)
+}
+
+/// Collects the unchecked tasks of `lines` that `scope` lists.
+pub fn collect_tasks_in_scope(
+    lines: &LineBuffer,
+    path: Option<&Path>,
+    today: NaiveDate,
+    scope: AgendaScope,
+) -> Vec<AgendaItem> {
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.trim_start().starts_with(UNCHECKED))
+        .filter_map(|(i, line)| {
+            let due = parse_due(line);
+            let group = match scope {
+                AgendaScope::ThisWeek => AgendaGroup::for_date(due?, today)?,
+                AgendaScope::All => AgendaGroup::for_task(due, today),
+            };
+            Some(AgendaItem {
+                path: path.map(Path::to_path_buf),
+                line: i,
+                text: line.trim().to_string(),
+                due,
+                group,
+            })
+        })
+        .collect()
+}
+
+/// Collects tasks from every Markdown file under `dir`, skipping `exclude`
+/// (the file already open in the editor).
+pub fn collect_dir(dir: &Path, exclude: Option<&Path>, today: NaiveDate) -> Vec<AgendaItem> {
+    collect_dir_in_scope(dir, exclude, today, AgendaScope::ThisWeek)
+}
+
+/// Collects the tasks `scope` lists from every Markdown file under `dir`,
+/// skipping `exclude`.
+pub fn collect_dir_in_scope(
+    dir: &Path,
+    exclude: Option<&Path>,
+    today: NaiveDate,
+    scope: AgendaScope,
+) -> Vec<AgendaItem> {
+    let exclude = exclude.and_then(|path| path.canonicalize().ok());
+    let mut files = Vec::new();
+    markdown_files(dir, &mut files);
+    files.sort();
+
+    let mut items = Vec::new();
+    for file in files {
+        if exclude.is_some() && file.canonicalize().ok() == exclude {
+            continue;
+        }
+        match fs::read_to_string(&file) {
+            Ok(content) => {
+                let lines: LineBuffer = content.lines().map(str::to_string).collect();
+                items.extend(collect_tasks_in_scope(&lines, Some(&file), today, scope));
+            }
+            Err(e) => error!("Failed to read {}: {e}", file.display()),
+        }
+    }
+    items
+}
+
+fn markdown_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            markdown_files(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "md") {
+            files.push(path);
+        }
+    }
+}
+
+/// Orders items by group, then due date, keeping file order for ties.
+pub fn sort_items(items: &mut [AgendaItem]) {
+    items.sort_by_key(|item| (item.group, item.due));
+}
+
+/// Returns `line` with its unchecked checkbox checked.
+pub fn check_line(line: &str) -> Option<String> {
+    let indent = line.len() - line.trim_start().len();
+    let rest = line[indent..].strip_prefix(UNCHECKED)?;
+    Some(format!("{}{CHECKED}{rest}", &line[..indent]))
+}
+
+/// Checks off the task on line `item.line` of `item.path`, as long as the line
+/// still holds the task that was listed.
+pub fn check_off_in_file(item: &AgendaItem) -> Result<()> {
+    let Some(path) = &item.path else {
+        return Err(DmacsError::Document("Task is not in a file".to_string()));
+    };
+    let content = fs::read_to_string(path).map_err(DmacsError::Io)?;
+    let mut lines: Vec<&str> = content.split('\n').collect();
+    let checked = lines
+        .get(item.line)
+        .filter(|line| line.trim() == item.text)
+        .and_then(|line| check_line(line))
+        .ok_or_else(|| {
+            DmacsError::Document(format!(
+                "{} changed since the agenda was opened",
+                path.display()
+            ))
+        })?;
+    lines[item.line] = &checked;
+    fs::write(path, lines.join("\n")).map_err(DmacsError::Io)
+}
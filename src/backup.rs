@@ -1,12 +1,47 @@
 use crate::error::{DmacsError, Result};
+use crate::persistence;
 use chrono::{DateTime, Duration, Local, NaiveDateTime, TimeZone};
 use log::debug;
 use sha2::{Digest, Sha256};
 use std::fs;
 use std::path::PathBuf;
 
+// How long backups are kept unless configured otherwise
+const DEFAULT_MAX_AGE_DAYS: u64 = 3;
+
+/// How many backups are kept, and for how long.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    // Newest backups kept for each file; all of them if unset
+    pub max_per_file: Option<usize>,
+    pub max_age_days: u64,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            max_per_file: None,
+            max_age_days: DEFAULT_MAX_AGE_DAYS,
+        }
+    }
+}
+
+/// One saved version of a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Backup {
+    pub path: PathBuf,
+    pub created: NaiveDateTime,
+}
+
+impl Backup {
+    pub fn read(&self) -> Result<String> {
+        fs::read_to_string(&self.path).map_err(DmacsError::Io)
+    }
+}
+
 pub struct BackupManager {
     backup_dir: PathBuf,
+    retention: RetentionPolicy,
 }
 
 impl BackupManager {
@@ -15,17 +50,24 @@ impl BackupManager {
     }
 
     pub fn new_with_base_dir(base_dir: Option<PathBuf>) -> Result<Self> {
-        let base = if let Some(dir) = base_dir {
-            dir
+        let state_dir = if let Some(dir) = base_dir {
+            dir.join(".dmacs")
         } else {
-            dirs::home_dir().ok_or(DmacsError::Io(std::io::Error::new(
+            persistence::state_dir().ok_or(DmacsError::Io(std::io::Error::new(
                 std::io::ErrorKind::NotFound,
                 "Home directory not found",
             )))?
         };
-        let backup_dir = base.join(".dmacs").join("backup");
+        let backup_dir = state_dir.join("backup");
         fs::create_dir_all(&backup_dir).map_err(DmacsError::Io)?;
-        Ok(Self { backup_dir })
+        Ok(Self {
+            backup_dir,
+            retention: RetentionPolicy::default(),
+        })
+    }
+
+    pub fn with_retention(self, retention: RetentionPolicy) -> Self {
+        Self { retention, ..self }
     }
 
     pub fn save_backup(&self, filename: &str, content: &str) -> Result<()> {
@@ -56,7 +98,7 @@ impl BackupManager {
 
     pub fn clean_old_backups(&self) -> Result<()> {
         let now: DateTime<Local> = Local::now();
-        let three_days_ago = now - Duration::days(3);
+        let oldest_kept = now - Duration::days(self.retention.max_age_days as i64);
 
         for entry in fs::read_dir(&self.backup_dir).map_err(DmacsError::Io)? {
             let entry = entry.map_err(DmacsError::Io)?;
@@ -77,7 +119,7 @@ impl BackupManager {
                             if let Some(backup_timestamp) =
                                 Local.from_local_datetime(&naive_datetime).single()
                             {
-                                if backup_timestamp < three_days_ago {
+                                if backup_timestamp < oldest_kept {
                                     fs::remove_file(&path).map_err(DmacsError::Io)?;
                                     debug!("Deleted old backup: {}", path.display());
                                 }
@@ -90,6 +132,42 @@ impl BackupManager {
         Ok(())
     }
 
+    /// Removes the oldest backups of `filename` beyond the number to keep.
+    pub fn prune_backups(&self, filename: &str) -> Result<()> {
+        let Some(max_per_file) = self.retention.max_per_file else {
+            return Ok(());
+        };
+        for backup in self.list_backups(filename)?.iter().skip(max_per_file) {
+            fs::remove_file(&backup.path).map_err(DmacsError::Io)?;
+            debug!("Deleted backup beyond the limit: {}", backup.path.display());
+        }
+        Ok(())
+    }
+
+    /// Copies the backups of `from` so that they are found as backups of `to`,
+    /// e.g. after the file was saved under a new name.
+    pub fn copy_backups(&self, from: &str, to: &str) -> Result<()> {
+        let from_prefix = self.get_backup_file_prefix(from);
+        let to_prefix = self.get_backup_file_prefix(to);
+        for entry in fs::read_dir(&self.backup_dir).map_err(DmacsError::Io)? {
+            let path = entry.map_err(DmacsError::Io)?.path();
+            let Some(suffix) = path
+                .file_name()
+                .and_then(|s| s.to_str())
+                .and_then(|name| name.strip_prefix(&from_prefix))
+                .filter(|suffix| suffix.ends_with(".bak"))
+            else {
+                continue;
+            };
+            let target = self.backup_dir.join(format!("{to_prefix}{suffix}"));
+            if !target.exists() {
+                fs::copy(&path, &target).map_err(DmacsError::Io)?;
+                debug!("Copied backup {} to {}", path.display(), target.display());
+            }
+        }
+        Ok(())
+    }
+
     pub fn restore_backup(&self, filename: &str) -> Result<()> {
         if let Some(backup_to_restore) = self.find_latest_backup(filename)? {
             let content = fs::read_to_string(&backup_to_restore).map_err(DmacsError::Io)?;
@@ -104,9 +182,14 @@ impl BackupManager {
     }
 
     fn find_latest_backup(&self, filename: &str) -> Result<Option<PathBuf>> {
+        let backups = self.list_backups(filename)?;
+        Ok(backups.into_iter().next().map(|backup| backup.path))
+    }
+
+    /// The backups of `filename`, newest first.
+    pub fn list_backups(&self, filename: &str) -> Result<Vec<Backup>> {
         let prefix = self.get_backup_file_prefix(filename);
-        let mut latest_backup: Option<PathBuf> = None;
-        let mut latest_timestamp: Option<NaiveDateTime> = None;
+        let mut backups = Vec::new();
 
         for entry in fs::read_dir(&self.backup_dir).map_err(DmacsError::Io)? {
             let entry = entry.map_err(DmacsError::Io)?;
@@ -121,20 +204,21 @@ impl BackupManager {
                             .trim_start_matches('.') // The timestamp is preceded by a dot
                             .trim_end_matches(".bak");
 
-                        if let Ok(timestamp) =
+                        if let Ok(created) =
                             NaiveDateTime::parse_from_str(timestamp_part, "%Y%m%d%H%M%S")
                         {
-                            if latest_timestamp.is_none() || timestamp > latest_timestamp.unwrap() {
-                                latest_timestamp = Some(timestamp);
-                                latest_backup = Some(path.clone());
-                            }
+                            backups.push(Backup {
+                                path: path.clone(),
+                                created,
+                            });
                         }
                     }
                 }
             }
         }
 
-        Ok(latest_backup)
+        backups.sort_by_key(|b| std::cmp::Reverse(b.created));
+        Ok(backups)
     }
 
     fn get_backup_file_prefix(&self, filename: &str) -> String {
@@ -0,0 +1,150 @@
+use crate::document::LineBuffer;
+/// Normalizations that `/cleanup` applies to a whole document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CleanupRule {
+    /// Collapse runs of three or more blank lines into one.
+    BlankLines,
+    TrailingWhitespace,
+    /// Turn `*`, `+` and `•` list markers into `-`.
+    Bullets,
+    /// Drop blank lines at the end so the file ends with a single newline.
+    FinalNewline,
+}
+
+impl CleanupRule {
+    pub const ALL: [CleanupRule; 4] = [
+        CleanupRule::TrailingWhitespace,
+        CleanupRule::Bullets,
+        CleanupRule::BlankLines,
+        CleanupRule::FinalNewline,
+    ];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "blank_lines" => Some(CleanupRule::BlankLines),
+            "trailing_whitespace" => Some(CleanupRule::TrailingWhitespace),
+            "bullets" => Some(CleanupRule::Bullets),
+            "final_newline" => Some(CleanupRule::FinalNewline),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CleanupSummary {
+    pub lines_trimmed: usize,
+    pub bullets_normalized: usize,
+    pub blank_lines_removed: usize,
+}
+
+impl CleanupSummary {
+    pub fn is_empty(&self) -> bool {
+        *self == CleanupSummary::default()
+    }
+
+    pub fn describe(&self) -> String {
+        let counts = [
+            (self.lines_trimmed, "trimmed", "line"),
+            (self.bullets_normalized, "normalized", "bullet"),
+            (self.blank_lines_removed, "removed", "blank line"),
+        ];
+        let parts: Vec<String> = counts
+            .iter()
+            .filter(|(count, _, _)| *count > 0)
+            .map(|(count, verb, noun)| {
+                let plural = if *count == 1 { "" } else { "s" };
+                format!("{verb} {count} {noun}{plural}")
+            })
+            .collect();
+        if parts.is_empty() {
+            "Cleanup: nothing to change.".to_string()
+        } else {
+            format!("Cleanup: {}.", parts.join(", "))
+        }
+    }
+}
+
+pub struct Cleanup {
+    pub lines: Vec<String>,
+    pub summary: CleanupSummary,
+    /// For every input line, the index of the output line it ended up on
+    /// (or next to, if it was removed).
+    pub line_map: Vec<usize>,
+}
+
+pub fn cleanup(lines: &LineBuffer, rules: &[CleanupRule]) -> Cleanup {
+    let mut summary = CleanupSummary::default();
+    let mut cleaned: Vec<String> = lines
+        .iter()
+        .map(|line| {
+            let mut line = line.clone();
+            if rules.contains(&CleanupRule::TrailingWhitespace)
+                && line.trim_end().len() < line.len()
+            {
+                line.truncate(line.trim_end().len());
+                summary.lines_trimmed += 1;
+            }
+            if rules.contains(&CleanupRule::Bullets)
+                && let Some(normalized) = normalize_bullet(&line)
+            {
+                line = normalized;
+                summary.bullets_normalized += 1;
+            }
+            line
+        })
+        .collect();
+    let mut line_map: Vec<usize> = (0..lines.len()).collect();
+
+    if rules.contains(&CleanupRule::BlankLines) {
+        let mut kept = Vec::with_capacity(cleaned.len());
+        let mut run = 0;
+        for (index, line) in cleaned.into_iter().enumerate() {
+            let blank = line.trim().is_empty();
+            run = if blank { run + 1 } else { 0 };
+            // Keep the first line of a run; drop the rest once it reaches three
+            let run_len = if blank {
+                lines
+                    .range(index - (run - 1)..)
+                    .take_while(|line| line.trim().is_empty())
+                    .count()
+            } else {
+                0
+            };
+            if blank && run > 1 && run_len >= 3 {
+                summary.blank_lines_removed += 1;
+                line_map[index] = kept.len() - 1;
+            } else {
+                line_map[index] = kept.len();
+                kept.push(line);
+            }
+        }
+        cleaned = kept;
+    }
+
+    if rules.contains(&CleanupRule::FinalNewline) {
+        while cleaned.len() > 1 && cleaned.last().is_some_and(|line| line.trim().is_empty()) {
+            cleaned.pop();
+            summary.blank_lines_removed += 1;
+        }
+        let last = cleaned.len().saturating_sub(1);
+        for index in line_map.iter_mut() {
+            *index = (*index).min(last);
+        }
+    }
+
+    Cleanup {
+        lines: cleaned,
+        summary,
+        line_map,
+    }
+}
+
+fn normalize_bullet(line: &str) -> Option<String> {
+    let trimmed = line.trim_start();
+    let indent = &line[..line.len() - trimmed.len()];
+    ["* ", "+ ", "• "].iter().find_map(|marker| {
+        trimmed
+            .strip_prefix(marker)
+            .map(|rest| format!("{indent}- {rest}"))
+    })
+}
@@ -1,15 +1,33 @@
+use crate::backup::RetentionPolicy;
+use crate::document::SaveOptions;
 use crate::editor::actions::Action;
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use toml;
 
+const DEFAULT_INDENT_WIDTH: usize = 2;
+const DEFAULT_JUMP_LIST_SIZE: usize = 100;
+const DEFAULT_UNDO_SNAPSHOT_MINS: u64 = 5;
+const THEMES_SUBDIR: &str = "themes";
+const SNIPPETS_FILE: &str = "snippets.toml";
+/// The themes built into dmacs, the first of them being the default colors.
+pub const BUILTIN_THEMES: [&str; 2] = ["dark", "light"];
+// Read in place of ~/.dmacs/config.toml
+pub const CONFIG_ENV: &str = "DMACS_CONFIG";
+// A theme whose colors replace those of the config file
+pub const THEME_ENV: &str = "DMACS_THEME";
+
 #[derive(Deserialize, Debug, Default)]
 struct PartialConfig {
     #[serde(default)]
     colors: PartialColors,
     #[serde(default)]
     keymap: Keymap,
+    #[serde(default)]
+    editor: PartialEditorSettings,
 }
 
 #[derive(Deserialize, Debug, Default)]
@@ -17,13 +35,252 @@ struct PartialColors {
     bg: Option<String>,
     fg: Option<String>,
     bold: Option<String>,
+    guide: Option<String>,
+    cursor_line: Option<String>,
+    selection: Option<String>,
+    search: Option<String>,
+    status: Option<String>,
+    dim: Option<String>,
+    gutter: Option<String>,
+    error: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct PartialEditorSettings {
+    highlight_last_change: Option<bool>,
+    autosave_interval_secs: Option<u64>,
+    inbox_file: Option<String>,
+    pomodoro_work_mins: Option<u64>,
+    pomodoro_break_mins: Option<u64>,
+    pomodoro_bell: Option<bool>,
+    agenda_dir: Option<String>,
+    grep_root: Option<String>,
+    reopen_last_session: Option<bool>,
+    todo_keywords: Option<Vec<String>>,
+    trim_trailing_whitespace: Option<bool>,
+    ensure_final_newline: Option<bool>,
+    tabs_to_spaces: Option<bool>,
+    cleanup: Option<Vec<String>>,
+    line_ending: Option<String>,
+    max_line_bytes: Option<usize>,
+    auto_pair: Option<bool>,
+    auto_pairs: Option<Vec<String>>,
+    highlight_matching_bracket: Option<bool>,
+    ruler_column: Option<usize>,
+    indent_guides: Option<bool>,
+    highlight_cursor_line: Option<bool>,
+    highlight_cursor_column: Option<bool>,
+    outline_pages: Option<bool>,
+    word_chars: Option<String>,
+    word_classes: Option<Vec<String>>,
+    shell_timeout_secs: Option<u64>,
+    subword_filetypes: Option<Vec<String>>,
+    clipboard: Option<String>,
+    hint_bar: Option<bool>,
+    backup_max_per_file: Option<usize>,
+    backup_max_age_days: Option<u64>,
+    spellcheck: Option<String>,
+    indent_width: Option<usize>,
+    jump_list_size: Option<usize>,
+    link_placeholders: Option<bool>,
+    list_continuation: Option<bool>,
+    shorten_urls: Option<bool>,
+    templates: Option<HashMap<String, String>>,
+    abbrevs: Option<HashMap<String, String>>,
+    input_transforms: Option<Vec<String>>,
+    undo_snapshot_mins: Option<u64>,
+    show_whitespace: Option<bool>,
+    date_format: Option<String>,
+    time_format: Option<String>,
+    datetime_format: Option<String>,
+    status_segments: Option<Vec<String>>,
+    git_gutter: Option<bool>,
+    theme: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EditorSettings {
+    pub highlight_last_change: bool,
+    pub autosave_interval_secs: Option<u64>,
+    pub inbox_file: Option<String>,
+    pub pomodoro_work_mins: Option<u64>,
+    pub pomodoro_break_mins: Option<u64>,
+    pub pomodoro_bell: bool,
+    pub agenda_dir: Option<String>,
+    // Where GrepProject searches instead of the repository of the file
+    pub grep_root: Option<String>,
+    pub reopen_last_session: bool,
+    pub todo_keywords: Option<Vec<String>>,
+    pub trim_trailing_whitespace: bool,
+    pub ensure_final_newline: bool,
+    pub tabs_to_spaces: bool,
+    pub cleanup: Option<Vec<String>>,
+    pub line_ending: Option<String>,
+    pub max_line_bytes: Option<usize>,
+    pub auto_pair: bool,
+    pub auto_pairs: Option<Vec<String>>,
+    pub highlight_matching_bracket: bool,
+    pub ruler_column: Option<usize>,
+    pub indent_guides: bool,
+    pub highlight_cursor_line: bool,
+    pub highlight_cursor_column: bool,
+    pub outline_pages: bool,
+    pub word_chars: Option<String>,
+    pub word_classes: Option<Vec<String>>,
+    pub shell_timeout_secs: Option<u64>,
+    pub subword_filetypes: Option<Vec<String>>,
+    pub clipboard: Option<String>,
+    pub hint_bar: bool,
+    pub backup_max_per_file: Option<usize>,
+    pub backup_max_age_days: Option<u64>,
+    pub spellcheck: Option<String>,
+    pub indent_width: Option<usize>,
+    pub jump_list_size: Option<usize>,
+    pub link_placeholders: Option<bool>,
+    pub list_continuation: Option<bool>,
+    pub shorten_urls: bool,
+    pub templates: Option<HashMap<String, String>>,
+    // Typed words and what they expand to when a word boundary follows
+    pub abbrevs: Option<HashMap<String, String>>,
+    // Read from snippets.toml next to the config file, by trigger word
+    pub snippets: Option<HashMap<String, String>>,
+    pub input_transforms: Option<Vec<String>>,
+    pub undo_snapshot_mins: Option<u64>,
+    pub show_whitespace: bool,
+    pub date_format: Option<String>,
+    pub time_format: Option<String>,
+    pub datetime_format: Option<String>,
+    pub status_segments: Option<Vec<String>>,
+    pub git_gutter: bool,
+    pub theme: Option<String>,
+}
+
+impl EditorSettings {
+    pub fn save_options(&self) -> SaveOptions {
+        SaveOptions {
+            trim_trailing_whitespace: self.trim_trailing_whitespace,
+            ensure_final_newline: self.ensure_final_newline,
+            tabs_to_spaces: self.tabs_to_spaces,
+        }
+    }
+
+    pub fn backup_retention(&self) -> RetentionPolicy {
+        let default = RetentionPolicy::default();
+        RetentionPolicy {
+            max_per_file: self.backup_max_per_file,
+            max_age_days: self.backup_max_age_days.unwrap_or(default.max_age_days),
+        }
+    }
+
+    /// Spaces added or removed per nesting level by `Tab` and `Shift-Tab`.
+    pub fn indent_width(&self) -> usize {
+        self.indent_width
+            .filter(|&width| width > 0)
+            .unwrap_or(DEFAULT_INDENT_WIDTH)
+    }
+
+    /// Positions kept for `JumpBack` and `JumpForward`, at least one.
+    pub fn jump_list_size(&self) -> usize {
+        self.jump_list_size.unwrap_or(DEFAULT_JUMP_LIST_SIZE).max(1)
+    }
+
+    /// Whether images and links with a long target are drawn as `[img: alt]`
+    /// and `[link: text]` away from the cursor line. On unless turned off.
+    pub fn link_placeholders(&self) -> bool {
+        self.link_placeholders.unwrap_or(true)
+    }
+
+    /// Whether `Enter` on a list item starts the next item. On unless
+    /// turned off.
+    pub fn list_continuation(&self) -> bool {
+        self.list_continuation.unwrap_or(true)
+    }
+
+    /// Minutes of editing between the snapshots taken in the undo history,
+    /// `None` when they are turned off with 0.
+    pub fn undo_snapshot_interval(&self) -> Option<Duration> {
+        match self
+            .undo_snapshot_mins
+            .unwrap_or(DEFAULT_UNDO_SNAPSHOT_MINS)
+        {
+            0 => None,
+            mins => Some(Duration::from_secs(mins * 60)),
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Colors {
     pub bg: String,
     pub fg: String,
     pub bold: String,
+    pub guide: String,
+    pub cursor_line: String,
+    pub selection: String,
+    pub search: String,
+    pub status: String,
+    pub dim: String,
+    pub gutter: String,
+    pub error: String,
+}
+
+impl Colors {
+    fn merge(&mut self, colors: PartialColors) {
+        if let Some(bg) = colors.bg {
+            self.bg = bg;
+        }
+        if let Some(fg) = colors.fg {
+            self.fg = fg;
+        }
+        if let Some(bold) = colors.bold {
+            self.bold = bold;
+        }
+        if let Some(guide) = colors.guide {
+            self.guide = guide;
+        }
+        if let Some(cursor_line) = colors.cursor_line {
+            self.cursor_line = cursor_line;
+        }
+        if let Some(selection) = colors.selection {
+            self.selection = selection;
+        }
+        if let Some(search) = colors.search {
+            self.search = search;
+        }
+        if let Some(status) = colors.status {
+            self.status = status;
+        }
+        if let Some(dim) = colors.dim {
+            self.dim = dim;
+        }
+        if let Some(gutter) = colors.gutter {
+            self.gutter = gutter;
+        }
+        if let Some(error) = colors.error {
+            self.error = error;
+        }
+    }
+
+    /// The colors of one of the `BUILTIN_THEMES`.
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::default()),
+            "light" => Some(Self {
+                bg: "#fbf8f3".to_string(),
+                fg: "#3b3835".to_string(),
+                bold: "#a0620a".to_string(),
+                guide: "#d8d2c9".to_string(),
+                cursor_line: "#f1ece4".to_string(),
+                selection: "#b9cbe8".to_string(),
+                search: "#f3d27a".to_string(),
+                status: "#5f5a54".to_string(),
+                dim: "#8c857d".to_string(),
+                gutter: "#4f8a3c".to_string(),
+                error: "#c2412d".to_string(),
+            }),
+            _ => None,
+        }
+    }
 }
 
 impl Default for Colors {
@@ -32,6 +289,14 @@ impl Default for Colors {
             bg: "#33302d".to_string(),
             fg: "#d0d0d0".to_string(),
             bold: "#f5c373".to_string(),
+            guide: "#5a544e".to_string(),
+            cursor_line: "#3d3935".to_string(),
+            selection: "#d0d0d0".to_string(),
+            search: "#f5c373".to_string(),
+            status: "#d0d0d0".to_string(),
+            dim: "#d0d0d0".to_string(),
+            gutter: "#8fbf7f".to_string(),
+            error: "#e06c60".to_string(),
         }
     }
 }
@@ -40,35 +305,185 @@ impl Default for Colors {
 pub struct Config {
     pub colors: Colors,
     pub keymap: Keymap,
+    pub editor: EditorSettings,
 }
 
 impl Config {
     pub fn load() -> Self {
         let mut config = Config::default();
 
-        if let Some(home_dir) = dirs::home_dir() {
-            let config_path = home_dir.join(".dmacs").join("config.toml");
+        let config_override = env_path(CONFIG_ENV);
+        let config_path = config_override
+            .clone()
+            .or_else(|| dirs::home_dir().map(|home| home.join(".dmacs").join("config.toml")));
+        if let Some(config_path) = &config_path {
+            config.editor.snippets = read_snippets(&config_path.with_file_name(SNIPPETS_FILE));
+        }
+        if let Some(config_path) = config_path {
             if config_path.exists() {
                 if let Ok(contents) = fs::read_to_string(&config_path) {
                     match toml::from_str::<PartialConfig>(&contents) {
                         Ok(user_config) => {
-                            if let Some(bg) = user_config.colors.bg {
-                                config.colors.bg = bg;
+                            // The colors of the theme, changed by those of [colors]
+                            if let Some(theme) = &user_config.editor.theme {
+                                config.load_theme(theme);
+                                config.editor.theme = Some(theme.clone());
                             }
-                            if let Some(fg) = user_config.colors.fg {
-                                config.colors.fg = fg;
+                            config.colors.merge(user_config.colors);
+                            config.keymap.bindings.extend(user_config.keymap.bindings);
+                            if let Some(highlight) = user_config.editor.highlight_last_change {
+                                config.editor.highlight_last_change = highlight;
                             }
-                            if let Some(bold) = user_config.colors.bold {
-                                config.colors.bold = bold;
+                            if let Some(interval) = user_config.editor.autosave_interval_secs {
+                                config.editor.autosave_interval_secs = Some(interval);
+                            }
+                            if let Some(inbox_file) = user_config.editor.inbox_file {
+                                config.editor.inbox_file = Some(inbox_file);
+                            }
+                            if let Some(mins) = user_config.editor.pomodoro_work_mins {
+                                config.editor.pomodoro_work_mins = Some(mins);
+                            }
+                            if let Some(mins) = user_config.editor.pomodoro_break_mins {
+                                config.editor.pomodoro_break_mins = Some(mins);
+                            }
+                            if let Some(bell) = user_config.editor.pomodoro_bell {
+                                config.editor.pomodoro_bell = bell;
+                            }
+                            if let Some(agenda_dir) = user_config.editor.agenda_dir {
+                                config.editor.agenda_dir = Some(agenda_dir);
+                            }
+                            if let Some(grep_root) = user_config.editor.grep_root {
+                                config.editor.grep_root = Some(grep_root);
+                            }
+                            if let Some(reopen) = user_config.editor.reopen_last_session {
+                                config.editor.reopen_last_session = reopen;
+                            }
+                            if let Some(keywords) = user_config.editor.todo_keywords {
+                                config.editor.todo_keywords = Some(keywords);
+                            }
+                            if let Some(trim) = user_config.editor.trim_trailing_whitespace {
+                                config.editor.trim_trailing_whitespace = trim;
+                            }
+                            if let Some(ensure) = user_config.editor.ensure_final_newline {
+                                config.editor.ensure_final_newline = ensure;
+                            }
+                            if let Some(expand) = user_config.editor.tabs_to_spaces {
+                                config.editor.tabs_to_spaces = expand;
+                            }
+                            if let Some(rules) = user_config.editor.cleanup {
+                                config.editor.cleanup = Some(rules);
+                            }
+                            if let Some(line_ending) = user_config.editor.line_ending {
+                                config.editor.line_ending = Some(line_ending);
+                            }
+                            if let Some(max_bytes) = user_config.editor.max_line_bytes {
+                                config.editor.max_line_bytes = Some(max_bytes);
+                            }
+                            if let Some(auto_pair) = user_config.editor.auto_pair {
+                                config.editor.auto_pair = auto_pair;
+                            }
+                            if let Some(pairs) = user_config.editor.auto_pairs {
+                                config.editor.auto_pairs = Some(pairs);
+                            }
+                            if let Some(highlight) = user_config.editor.highlight_matching_bracket {
+                                config.editor.highlight_matching_bracket = highlight;
+                            }
+                            if let Some(column) = user_config.editor.ruler_column {
+                                config.editor.ruler_column = Some(column);
+                            }
+                            if let Some(indent_guides) = user_config.editor.indent_guides {
+                                config.editor.indent_guides = indent_guides;
+                            }
+                            if let Some(highlight) = user_config.editor.highlight_cursor_line {
+                                config.editor.highlight_cursor_line = highlight;
+                            }
+                            if let Some(highlight) = user_config.editor.highlight_cursor_column {
+                                config.editor.highlight_cursor_column = highlight;
+                            }
+                            if let Some(outline_pages) = user_config.editor.outline_pages {
+                                config.editor.outline_pages = outline_pages;
+                            }
+                            if let Some(word_chars) = user_config.editor.word_chars {
+                                config.editor.word_chars = Some(word_chars);
+                            }
+                            if let Some(classes) = user_config.editor.word_classes {
+                                config.editor.word_classes = Some(classes);
+                            }
+                            if let Some(secs) = user_config.editor.shell_timeout_secs {
+                                config.editor.shell_timeout_secs = Some(secs);
+                            }
+                            if let Some(filetypes) = user_config.editor.subword_filetypes {
+                                config.editor.subword_filetypes = Some(filetypes);
+                            }
+                            if let Some(clipboard) = user_config.editor.clipboard {
+                                config.editor.clipboard = Some(clipboard);
+                            }
+                            if let Some(hint_bar) = user_config.editor.hint_bar {
+                                config.editor.hint_bar = hint_bar;
+                            }
+                            if let Some(max) = user_config.editor.backup_max_per_file {
+                                config.editor.backup_max_per_file = Some(max);
+                            }
+                            if let Some(days) = user_config.editor.backup_max_age_days {
+                                config.editor.backup_max_age_days = Some(days);
+                            }
+                            if let Some(language) = user_config.editor.spellcheck {
+                                config.editor.spellcheck = Some(language);
+                            }
+                            if let Some(width) = user_config.editor.indent_width {
+                                config.editor.indent_width = Some(width);
+                            }
+                            if let Some(size) = user_config.editor.jump_list_size {
+                                config.editor.jump_list_size = Some(size);
+                            }
+                            if let Some(enabled) = user_config.editor.link_placeholders {
+                                config.editor.link_placeholders = Some(enabled);
+                            }
+                            if let Some(enabled) = user_config.editor.list_continuation {
+                                config.editor.list_continuation = Some(enabled);
+                            }
+                            if let Some(enabled) = user_config.editor.shorten_urls {
+                                config.editor.shorten_urls = enabled;
+                            }
+                            if let Some(templates) = user_config.editor.templates {
+                                config.editor.templates = Some(templates);
+                            }
+                            if let Some(abbrevs) = user_config.editor.abbrevs {
+                                config.editor.abbrevs = Some(abbrevs);
+                            }
+                            if let Some(transforms) = user_config.editor.input_transforms {
+                                config.editor.input_transforms = Some(transforms);
+                            }
+                            if let Some(mins) = user_config.editor.undo_snapshot_mins {
+                                config.editor.undo_snapshot_mins = Some(mins);
+                            }
+                            if let Some(enabled) = user_config.editor.show_whitespace {
+                                config.editor.show_whitespace = enabled;
+                            }
+                            if let Some(format) = user_config.editor.date_format {
+                                config.editor.date_format = Some(format);
+                            }
+                            if let Some(format) = user_config.editor.time_format {
+                                config.editor.time_format = Some(format);
+                            }
+                            if let Some(format) = user_config.editor.datetime_format {
+                                config.editor.datetime_format = Some(format);
+                            }
+                            if let Some(segments) = user_config.editor.status_segments {
+                                config.editor.status_segments = Some(segments);
+                            }
+                            if let Some(enabled) = user_config.editor.git_gutter {
+                                config.editor.git_gutter = enabled;
                             }
-                            config.keymap.bindings.extend(user_config.keymap.bindings);
                         }
                         Err(e) => {
                             log::error!("Failed to parse config.toml: {e}");
                         }
                     }
                 }
-            } else {
+            } else if config_override.is_none()
+                && let Some(home_dir) = dirs::home_dir()
+            {
                 // Backward compatibility: load old keymap.toml if config.toml doesn't exist
                 let keymap_path = home_dir.join(".dmacs").join("keymap.toml");
                 if keymap_path.exists() {
@@ -85,8 +500,101 @@ impl Config {
                 }
             }
         }
+        if let Ok(theme) = std::env::var(THEME_ENV)
+            && !theme.is_empty()
+        {
+            config.load_theme(&theme);
+            config.editor.theme = Some(theme);
+        }
         config
     }
+
+    /// Takes the colors of a built-in theme, or the `[colors]` of a theme
+    /// file: `name` itself if it is a path, or `~/.dmacs/themes/<name>.toml`.
+    fn load_theme(&mut self, name: &str) {
+        match Colors::builtin(name) {
+            Some(colors) => self.colors = colors,
+            None => {
+                if let Some(colors) = read_theme_file(name) {
+                    self.colors.merge(colors);
+                }
+            }
+        }
+    }
+}
+
+fn themes_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".dmacs").join(THEMES_SUBDIR))
+}
+
+/// The snippets of `path`, a table of trigger words and the text they expand
+/// to, or none when there is no such file.
+pub fn read_snippets(path: &Path) -> Option<HashMap<String, String>> {
+    let contents = fs::read_to_string(path).ok()?;
+    match toml::from_str(&contents) {
+        Ok(snippets) => Some(snippets),
+        Err(e) => {
+            log::error!("Failed to parse {}: {e}", path.display());
+            None
+        }
+    }
+}
+
+fn read_theme_file(name: &str) -> Option<PartialColors> {
+    let path = if name.contains(std::path::MAIN_SEPARATOR) || name.ends_with(".toml") {
+        PathBuf::from(name)
+    } else {
+        themes_dir()?.join(format!("{name}.toml"))
+    };
+    match fs::read_to_string(&path) {
+        Ok(contents) => match toml::from_str::<PartialConfig>(&contents) {
+            Ok(theme) => Some(theme.colors),
+            Err(e) => {
+                log::error!("Failed to parse the theme {}: {e}", path.display());
+                None
+            }
+        },
+        Err(e) => {
+            log::error!("Failed to read the theme {}: {e}", path.display());
+            None
+        }
+    }
+}
+
+/// The colors of the theme `name`, built in or a theme file over the default
+/// colors.
+pub fn theme_colors(name: &str) -> Option<Colors> {
+    Colors::builtin(name).or_else(|| {
+        let mut colors = Colors::default();
+        colors.merge(read_theme_file(name)?);
+        Some(colors)
+    })
+}
+
+/// The built-in themes followed by those in `~/.dmacs/themes`, by name.
+pub fn theme_names() -> Vec<String> {
+    let mut names: Vec<String> = BUILTIN_THEMES.iter().map(|name| name.to_string()).collect();
+    let mut files: Vec<String> = themes_dir()
+        .and_then(|dir| fs::read_dir(dir).ok())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            (path.extension()? == "toml").then_some(path.file_stem()?.to_str()?.to_string())
+        })
+        .filter(|name| !names.contains(name))
+        .collect();
+    files.sort();
+    names.extend(files);
+    names
+}
+
+/// The path in the environment variable `name`, unless it is unset or empty.
+fn env_path(name: &str) -> Option<PathBuf> {
+    std::env::var_os(name)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -109,6 +617,9 @@ impl Default for Keymap {
 
         // File Operations
         bindings.insert("alt-s".to_string(), Action::Save);
+        bindings.insert("alt-S".to_string(), Action::SaveAs);
+        bindings.insert("alt-o".to_string(), Action::OpenFile);
+        bindings.insert("alt-O".to_string(), Action::OpenRecent);
         bindings.insert("ctrl-x".to_string(), Action::Quit);
 
         // Cursor Movement
@@ -123,12 +634,30 @@ impl Default for Keymap {
         bindings.insert("ctrl-b".to_string(), Action::MoveWordLeft);
         bindings.insert("alt-up".to_string(), Action::MoveLineUp);
         bindings.insert("alt-down".to_string(), Action::MoveLineDown);
+        bindings.insert("alt-n".to_string(), Action::ToggleScratchBuffer);
         bindings.insert("ctrl-v".to_string(), Action::PageDown);
         bindings.insert("alt-v".to_string(), Action::PageUp);
+        bindings.insert("ctrl-l".to_string(), Action::RecenterView);
+        bindings.insert("ctrl-up".to_string(), Action::ScrollViewUp);
+        bindings.insert("ctrl-down".to_string(), Action::ScrollViewDown);
         bindings.insert("ctrl-n".to_string(), Action::MoveToNextDelimiter);
         bindings.insert("ctrl-p".to_string(), Action::MoveToPreviousDelimiter);
         bindings.insert("alt->".to_string(), Action::GoToEndOfFile);
         bindings.insert("alt-<".to_string(), Action::GoToStartOfFile);
+        bindings.insert("alt-g".to_string(), Action::GoToLine);
+        bindings.insert("alt-P".to_string(), Action::GotoPage(0));
+        bindings.insert("alt-F".to_string(), Action::GrepProject);
+        bindings.insert("alt-Y".to_string(), Action::YankFromHistory);
+        bindings.insert("alt-+".to_string(), Action::ExpandSelection);
+        bindings.insert("alt--".to_string(), Action::ShrinkSelection);
+        bindings.insert("alt-h".to_string(), Action::ShowOutline);
+        bindings.insert("alt-#".to_string(), Action::ListTags);
+        bindings.insert("alt-=".to_string(), Action::ShowStats);
+        bindings.insert("ctrl-o".to_string(), Action::JumpBack);
+        bindings.insert("alt-i".to_string(), Action::JumpForward);
+        bindings.insert("alt-m".to_string(), Action::SetBookmark);
+        bindings.insert("alt-j".to_string(), Action::GotoBookmark);
+        bindings.insert("alt-^".to_string(), Action::FollowFootnote);
 
         // Text Editing
         bindings.insert("backspace".to_string(), Action::DeleteChar);
@@ -137,26 +666,59 @@ impl Default for Keymap {
         bindings.insert("alt-backspace".to_string(), Action::DeleteWord);
         bindings.insert("ctrl-k".to_string(), Action::KillLine);
         bindings.insert("ctrl-y".to_string(), Action::Yank);
+        bindings.insert("alt-p".to_string(), Action::YankNormalized);
         bindings.insert("ctrl-_".to_string(), Action::Undo);
         bindings.insert("alt-_".to_string(), Action::Redo);
         bindings.insert("tab".to_string(), Action::Indent);
         bindings.insert("shift-tab".to_string(), Action::Outdent);
-        bindings.insert("alt-/".to_string(), Action::ToggleComment);
+        bindings.insert("alt-?".to_string(), Action::ToggleComment);
+        bindings.insert("alt-u".to_string(), Action::UpcaseWord);
+        bindings.insert("alt-l".to_string(), Action::DowncaseWord);
+        bindings.insert("alt-c".to_string(), Action::CapitalizeWord);
         bindings.insert("ctrl-t".to_string(), Action::ToggleCheckbox);
+        bindings.insert("alt-k".to_string(), Action::CycleTodoKeyword);
+        bindings.insert("alt-$".to_string(), Action::CorrectSpelling);
+        bindings.insert("alt-/".to_string(), Action::CompleteWord);
+        bindings.insert("alt-;".to_string(), Action::InsertTimestamp);
+        bindings.insert("alt-'".to_string(), Action::ExpandSnippet);
+        bindings.insert("alt-,".to_string(), Action::PromoteHeading);
+        bindings.insert("alt-.".to_string(), Action::DemoteHeading);
+        bindings.insert("alt-{".to_string(), Action::PromoteSubtree);
+        bindings.insert("alt-}".to_string(), Action::DemoteSubtree);
+        bindings.insert("alt-z".to_string(), Action::ToggleFold);
+        bindings.insert("alt-Z".to_string(), Action::FoldAll);
+        bindings.insert("alt-U".to_string(), Action::UnfoldAll);
+        bindings.insert("alt-t".to_string(), Action::StartTaskTimer);
+        bindings.insert("alt-T".to_string(), Action::StopTaskTimer);
+        bindings.insert("alt-d".to_string(), Action::OpenDatePicker);
+        bindings.insert("alt-a".to_string(), Action::OpenAgenda);
         bindings.insert("enter".to_string(), Action::InsertNewline);
 
         // Selection
         bindings.insert("ctrl-space".to_string(), Action::SetMarker);
         bindings.insert("ctrl-w".to_string(), Action::CutSelection);
         bindings.insert("alt-w".to_string(), Action::CopySelection);
+        bindings.insert("alt-W".to_string(), Action::CopyAs);
         bindings.insert("ctrl-g".to_string(), Action::ClearMarker);
+        bindings.insert("alt-r".to_string(), Action::ToggleRectangleMode);
+        bindings.insert("alt-y".to_string(), Action::YankRectangle);
 
         // Search
         bindings.insert("ctrl-s".to_string(), Action::EnterSearchMode);
         bindings.insert("ctrl-f".to_string(), Action::EnterFuzzySearchMode);
 
+        // Macros
+        bindings.insert("alt-(".to_string(), Action::StartMacroRecording);
+        bindings.insert("alt-)".to_string(), Action::StopMacroRecording);
+        bindings.insert("alt-e".to_string(), Action::PlayMacro(1));
+        bindings.insert("ctrl-u".to_string(), Action::UniversalArgument);
+        for digit in 0..=9 {
+            bindings.insert(format!("alt-{digit}"), Action::DigitArgument(digit));
+        }
+
         // Modes
         bindings.insert("esc".to_string(), Action::EnterNormalMode);
+        bindings.insert("alt-x".to_string(), Action::CommandPalette);
 
         Self { bindings }
     }
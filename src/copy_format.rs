@@ -0,0 +1,287 @@
+use crate::editor::heading::heading_level;
+
+/// Target formats for copying Markdown notes into other tools.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyFormat {
+    PlainText,
+    Html,
+    Jira,
+    Slack,
+}
+
+impl CopyFormat {
+    pub const NAMES: &'static str = "text, html, jira, slack";
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "text" | "plain" => Some(CopyFormat::PlainText),
+            "html" => Some(CopyFormat::Html),
+            "jira" => Some(CopyFormat::Jira),
+            "slack" => Some(CopyFormat::Slack),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CopyFormat::PlainText => "plain text",
+            CopyFormat::Html => "HTML",
+            CopyFormat::Jira => "Jira markup",
+            CopyFormat::Slack => "Slack markup",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Block<'a> {
+    Blank,
+    Rule,
+    Heading(usize, &'a str),
+    Checked(usize, &'a str),
+    Unchecked(usize, &'a str),
+    Bullet(usize, &'a str),
+    Paragraph(&'a str),
+}
+
+fn parse_block(line: &str) -> Block<'_> {
+    let trimmed = line.trim_start();
+    let indent = &line[..line.len() - trimmed.len()];
+    let depth = indent
+        .chars()
+        .map(|c| if c == '\t' { 2 } else { 1 })
+        .sum::<usize>()
+        / 2;
+    if trimmed.is_empty() {
+        Block::Blank
+    } else if trimmed == "---" {
+        Block::Rule
+    } else if let Some(level) = heading_level(line) {
+        Block::Heading(level, line[level..].trim())
+    } else if let Some(text) = trimmed.strip_prefix("- [x] ") {
+        Block::Checked(depth, text)
+    } else if let Some(text) = trimmed.strip_prefix("- [ ] ") {
+        Block::Unchecked(depth, text)
+    } else if let Some(text) = trimmed.strip_prefix("- ") {
+        Block::Bullet(depth, text)
+    } else {
+        Block::Paragraph(trimmed)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Inline<'a> {
+    Text(&'a str),
+    Bold(&'a str),
+    Italic(&'a str),
+    Strike(&'a str),
+    Code(&'a str),
+    Link(&'a str, &'a str),
+}
+
+/// Splits a line into spans of Markdown emphasis, code and links. Markers
+/// without a closing counterpart are kept as text.
+fn parse_inline(text: &str) -> Vec<Inline<'_>> {
+    let mut spans = Vec::new();
+    let mut text_start = 0;
+    let mut i = 0;
+    while i < text.len() {
+        let rest = &text[i..];
+        let span = if let Some(inner) = rest.strip_prefix('`') {
+            inner
+                .find('`')
+                .map(|end| (Inline::Code(&inner[..end]), end + 2))
+        } else if let Some(inner) = rest.strip_prefix("**") {
+            inner
+                .find("**")
+                .filter(|&end| end > 0)
+                .map(|end| (Inline::Bold(&inner[..end]), end + 4))
+        } else if let Some(inner) = rest.strip_prefix("~~") {
+            inner
+                .find("~~")
+                .filter(|&end| end > 0)
+                .map(|end| (Inline::Strike(&inner[..end]), end + 4))
+        } else if let Some(inner) = rest.strip_prefix('*') {
+            inner
+                .find('*')
+                .filter(|&end| end > 0 && !inner.starts_with(' '))
+                .map(|end| (Inline::Italic(&inner[..end]), end + 2))
+        } else if let Some(inner) = rest.strip_prefix('[') {
+            inner.find("](").and_then(|label_end| {
+                let url = &inner[label_end + 2..];
+                url.find(')').map(|url_end| {
+                    (
+                        Inline::Link(&inner[..label_end], &url[..url_end]),
+                        label_end + url_end + 4,
+                    )
+                })
+            })
+        } else {
+            None
+        };
+
+        match span {
+            Some((span, len)) => {
+                if text_start < i {
+                    spans.push(Inline::Text(&text[text_start..i]));
+                }
+                spans.push(span);
+                i += len;
+                text_start = i;
+            }
+            None => i += rest.chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    if text_start < text.len() {
+        spans.push(Inline::Text(&text[text_start..]));
+    }
+    spans
+}
+
+fn render_inline(text: &str, format: CopyFormat) -> String {
+    parse_inline(text)
+        .into_iter()
+        .map(|span| match (format, span) {
+            (CopyFormat::Html, Inline::Text(t)) => escape_html(t),
+            (CopyFormat::Html, Inline::Bold(t)) => format!("<strong>{}</strong>", escape_html(t)),
+            (CopyFormat::Html, Inline::Italic(t)) => format!("<em>{}</em>", escape_html(t)),
+            (CopyFormat::Html, Inline::Strike(t)) => format!("<del>{}</del>", escape_html(t)),
+            (CopyFormat::Html, Inline::Code(t)) => format!("<code>{}</code>", escape_html(t)),
+            (CopyFormat::Html, Inline::Link(label, url)) => {
+                format!(
+                    "<a href=\"{}\">{}</a>",
+                    escape_html(url),
+                    escape_html(label)
+                )
+            }
+            (CopyFormat::Jira, Inline::Bold(t)) => format!("*{t}*"),
+            (CopyFormat::Jira, Inline::Italic(t)) => format!("_{t}_"),
+            (CopyFormat::Jira, Inline::Strike(t)) => format!("-{t}-"),
+            (CopyFormat::Jira, Inline::Code(t)) => format!("{{{{{t}}}}}"),
+            (CopyFormat::Jira, Inline::Link(label, url)) => format!("[{label}|{url}]"),
+            (CopyFormat::Slack, Inline::Bold(t)) => format!("*{t}*"),
+            (CopyFormat::Slack, Inline::Italic(t)) => format!("_{t}_"),
+            (CopyFormat::Slack, Inline::Strike(t)) => format!("~{t}~"),
+            (CopyFormat::Slack, Inline::Code(t)) => format!("`{t}`"),
+            (CopyFormat::Slack, Inline::Link(label, url)) => format!("<{url}|{label}>"),
+            (CopyFormat::PlainText, Inline::Link(label, url)) => format!("{label} ({url})"),
+            (
+                _,
+                Inline::Text(t)
+                | Inline::Bold(t)
+                | Inline::Italic(t)
+                | Inline::Strike(t)
+                | Inline::Code(t),
+            ) => t.to_string(),
+        })
+        .collect()
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Converts Markdown `text` to `format`, keeping a trailing newline.
+pub fn convert(text: &str, format: CopyFormat) -> String {
+    let converted = if format == CopyFormat::Html {
+        to_html(text)
+    } else {
+        to_markup(text, format)
+    };
+    if text.ends_with('\n') {
+        converted + "\n"
+    } else {
+        converted
+    }
+}
+
+fn to_markup(text: &str, format: CopyFormat) -> String {
+    let lines: Vec<String> = text
+        .lines()
+        .map(|line| {
+            let inline = |t: &str| render_inline(t, format);
+            match (format, parse_block(line)) {
+                (_, Block::Blank) => String::new(),
+                (CopyFormat::Jira, Block::Rule) => "----".to_string(),
+                (_, Block::Rule) => "---".to_string(),
+                (CopyFormat::Jira, Block::Heading(level, t)) => format!("h{level}. {}", inline(t)),
+                (CopyFormat::Slack, Block::Heading(_, t)) => format!("*{}*", inline(t)),
+                (_, Block::Heading(_, t)) => inline(t),
+                (CopyFormat::Jira, Block::Checked(depth, t)) => {
+                    format!("{} (/) {}", "*".repeat(depth + 1), inline(t))
+                }
+                (CopyFormat::Jira, Block::Unchecked(depth, t)) => {
+                    format!("{} (x) {}", "*".repeat(depth + 1), inline(t))
+                }
+                (CopyFormat::Jira, Block::Bullet(depth, t)) => {
+                    format!("{} {}", "*".repeat(depth + 1), inline(t))
+                }
+                (_, Block::Checked(depth, t)) => format!("{}✓ {}", "  ".repeat(depth), inline(t)),
+                (_, Block::Unchecked(depth, t)) => {
+                    format!("{}✗ {}", "  ".repeat(depth), inline(t))
+                }
+                (_, Block::Bullet(depth, t)) => format!("{}• {}", "  ".repeat(depth), inline(t)),
+                (_, Block::Paragraph(t)) => inline(t),
+            }
+        })
+        .collect();
+    lines.join("\n")
+}
+
+fn to_html(text: &str) -> String {
+    let mut html = Vec::new();
+    // Number of currently open <ul> elements
+    let mut open_lists = 0;
+    for line in text.lines() {
+        let block = parse_block(line);
+        let item = match block {
+            Block::Checked(depth, t) => Some((
+                depth,
+                format!(
+                    "<input type=\"checkbox\" checked disabled> {}",
+                    render_inline(t, CopyFormat::Html)
+                ),
+            )),
+            Block::Unchecked(depth, t) => Some((
+                depth,
+                format!(
+                    "<input type=\"checkbox\" disabled> {}",
+                    render_inline(t, CopyFormat::Html)
+                ),
+            )),
+            Block::Bullet(depth, t) => Some((depth, render_inline(t, CopyFormat::Html))),
+            _ => None,
+        };
+        let target_depth = item.as_ref().map_or(0, |(depth, _)| depth + 1);
+        while open_lists > target_depth {
+            open_lists -= 1;
+            html.push(format!("{}</ul>", "  ".repeat(open_lists)));
+        }
+        while open_lists < target_depth {
+            html.push(format!("{}<ul>", "  ".repeat(open_lists)));
+            open_lists += 1;
+        }
+
+        match (item, block) {
+            (Some((_, content)), _) => {
+                html.push(format!("{}<li>{content}</li>", "  ".repeat(open_lists)))
+            }
+            (None, Block::Heading(level, t)) => html.push(format!(
+                "<h{level}>{}</h{level}>",
+                render_inline(t, CopyFormat::Html)
+            )),
+            (None, Block::Paragraph(t)) => {
+                html.push(format!("<p>{}</p>", render_inline(t, CopyFormat::Html)))
+            }
+            (None, Block::Rule) => html.push("<hr>".to_string()),
+            _ => {}
+        }
+    }
+    while open_lists > 0 {
+        open_lists -= 1;
+        html.push(format!("{}</ul>", "  ".repeat(open_lists)));
+    }
+    html.join("\n")
+}
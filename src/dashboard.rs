@@ -0,0 +1,145 @@
+//! `dmacs --dashboard <dir>`: the open tasks of every Markdown file under a
+//! directory, counted with the same parsing as the agenda.
+
+use crate::agenda::{self, AgendaGroup, AgendaScope};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use unicode_width::UnicodeWidthStr;
+
+const MODIFIED_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// The tasks of one file that has unchecked ones.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileSummary {
+    // Relative to the scanned directory
+    pub path: PathBuf,
+    pub open: usize,
+    pub overdue: usize,
+    pub modified: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize)]
+struct JsonFile {
+    path: String,
+    open: usize,
+    overdue: usize,
+    modified: Option<String>,
+}
+
+#[derive(Serialize)]
+struct JsonDashboard {
+    files: Vec<JsonFile>,
+    open: usize,
+    overdue: usize,
+}
+
+/// Summarizes the files under `dir` with unchecked tasks, in path order.
+pub fn summarize(dir: &Path, today: NaiveDate) -> Vec<FileSummary> {
+    let mut summaries: Vec<FileSummary> = Vec::new();
+    for item in agenda::collect_dir_in_scope(dir, None, today, AgendaScope::All) {
+        let Some(path) = item.path else {
+            continue;
+        };
+        let summary = match summaries.last_mut() {
+            Some(summary) if dir.join(&summary.path) == path => summary,
+            _ => {
+                let modified = fs::metadata(&path)
+                    .and_then(|metadata| metadata.modified())
+                    .ok()
+                    .map(|time| DateTime::<Local>::from(time).naive_local());
+                summaries.push(FileSummary {
+                    path: path.strip_prefix(dir).unwrap_or(&path).to_path_buf(),
+                    open: 0,
+                    overdue: 0,
+                    modified,
+                });
+                summaries.last_mut().unwrap()
+            }
+        };
+        summary.open += 1;
+        if item.group == AgendaGroup::Overdue {
+            summary.overdue += 1;
+        }
+    }
+    summaries
+}
+
+fn totals(summaries: &[FileSummary]) -> (usize, usize) {
+    summaries.iter().fold((0, 0), |(open, overdue), summary| {
+        (open + summary.open, overdue + summary.overdue)
+    })
+}
+
+fn format_modified(modified: Option<NaiveDateTime>) -> Option<String> {
+    modified.map(|time| time.format(MODIFIED_FORMAT).to_string())
+}
+
+/// The summaries as a table with a total row, for the terminal.
+pub fn to_table(summaries: &[FileSummary]) -> String {
+    let (open, overdue) = totals(summaries);
+    let mut rows = vec![[
+        "File".to_string(),
+        "Open".to_string(),
+        "Overdue".to_string(),
+        "Modified".to_string(),
+    ]];
+    for summary in summaries {
+        rows.push([
+            summary.path.display().to_string(),
+            summary.open.to_string(),
+            summary.overdue.to_string(),
+            format_modified(summary.modified).unwrap_or_default(),
+        ]);
+    }
+    rows.push([
+        "Total".to_string(),
+        open.to_string(),
+        overdue.to_string(),
+        String::new(),
+    ]);
+
+    let width = |column: usize| {
+        rows.iter()
+            .map(|row| row[column].width())
+            .max()
+            .unwrap_or(0)
+    };
+    let widths = [width(0), width(1), width(2)];
+    let mut table = String::new();
+    for row in &rows {
+        let padding = " ".repeat(widths[0] - row[0].width());
+        let line = format!(
+            "{}{padding}  {:>w1$}  {:>w2$}  {}",
+            row[0],
+            row[1],
+            row[2],
+            row[3],
+            w1 = widths[1],
+            w2 = widths[2],
+        );
+        table.push_str(line.trim_end());
+        table.push('\n');
+    }
+    table
+}
+
+/// The summaries as JSON, for scripts.
+pub fn to_json(summaries: &[FileSummary]) -> String {
+    let (open, overdue) = totals(summaries);
+    let dashboard = JsonDashboard {
+        files: summaries
+            .iter()
+            .map(|summary| JsonFile {
+                path: summary.path.display().to_string(),
+                open: summary.open,
+                overdue: summary.overdue,
+                modified: format_modified(summary.modified),
+            })
+            .collect(),
+        open,
+        overdue,
+    };
+    serde_json::to_string_pretty(&dashboard).unwrap_or_default()
+}
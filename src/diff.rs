@@ -0,0 +1,142 @@
+/// A line of a diff between two versions of a text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLine<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+// Above this many line pairs the changed middle is shown as replaced
+// wholesale instead of being compared line by line
+const MAX_COMPARED_PAIRS: usize = 4_000_000;
+
+/// A shortest diff turning `old` into `new`, from the longest common
+/// subsequence of their lines. Removed lines come before the lines added in
+/// their place.
+pub fn diff_lines<'a, S: AsRef<str> + 'a>(
+    old: impl IntoIterator<Item = &'a S>,
+    new: impl IntoIterator<Item = &'a S>,
+) -> Vec<DiffLine<'a>> {
+    let old: Vec<&str> = old.into_iter().map(AsRef::as_ref).collect();
+    let new: Vec<&str> = new.into_iter().map(AsRef::as_ref).collect();
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+
+    let mut diff: Vec<DiffLine> = old[..prefix]
+        .iter()
+        .map(|&line| DiffLine::Same(line))
+        .collect();
+    if old_middle.len().saturating_mul(new_middle.len()) > MAX_COMPARED_PAIRS {
+        diff.extend(old_middle.iter().map(|&line| DiffLine::Removed(line)));
+        diff.extend(new_middle.iter().map(|&line| DiffLine::Added(line)));
+    } else {
+        diff.extend(diff_middle(old_middle, new_middle));
+    }
+    diff.extend(
+        old[old.len() - suffix..]
+            .iter()
+            .map(|&line| DiffLine::Same(line)),
+    );
+    diff
+}
+
+fn diff_middle<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<DiffLine<'a>> {
+    // common[i][j]: length of the longest common subsequence of old[i..] and new[j..]
+    let width = new.len() + 1;
+    let mut common = vec![0usize; (old.len() + 1) * width];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i * width + j] = if old[i] == new[j] {
+                common[(i + 1) * width + j + 1] + 1
+            } else {
+                common[(i + 1) * width + j].max(common[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut diff = Vec::with_capacity(old.len() + new.len());
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            diff.push(DiffLine::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if j == new.len()
+            || (i < old.len() && common[(i + 1) * width + j] >= common[i * width + j + 1])
+        {
+            diff.push(DiffLine::Removed(old[i]));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(new[j]));
+            j += 1;
+        }
+    }
+    diff
+}
+
+/// A run of changed lines: `old_len` lines of the old version from
+/// `old_start` replaced by `new_len` lines of the new one from `new_start`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hunk {
+    pub old_start: usize,
+    pub old_len: usize,
+    pub new_start: usize,
+    pub new_len: usize,
+}
+
+/// The runs of removed and added lines of `diff`, in order.
+pub fn hunks(diff: &[DiffLine]) -> Vec<Hunk> {
+    let mut hunks: Vec<Hunk> = Vec::new();
+    let (mut old_y, mut new_y) = (0, 0);
+    let mut in_hunk = false;
+    for line in diff {
+        if let DiffLine::Same(_) = line {
+            old_y += 1;
+            new_y += 1;
+            in_hunk = false;
+            continue;
+        }
+        if !in_hunk {
+            hunks.push(Hunk {
+                old_start: old_y,
+                old_len: 0,
+                new_start: new_y,
+                new_len: 0,
+            });
+            in_hunk = true;
+        }
+        let hunk = hunks.last_mut().expect("a hunk was just started");
+        if let DiffLine::Removed(_) = line {
+            hunk.old_len += 1;
+            old_y += 1;
+        } else {
+            hunk.new_len += 1;
+            new_y += 1;
+        }
+    }
+    hunks
+}
+
+/// Where line `y` of the old version is in the new one. A changed line maps
+/// to the line replacing it, or to where the hunk was when it has no more
+/// lines.
+pub fn map_line(hunks: &[Hunk], y: usize) -> usize {
+    let mut shift = 0isize;
+    for hunk in hunks {
+        if y < hunk.old_start {
+            break;
+        }
+        if y < hunk.old_start + hunk.old_len {
+            return hunk.new_start + (y - hunk.old_start).min(hunk.new_len.saturating_sub(1));
+        }
+        shift = (hunk.new_start + hunk.new_len) as isize - (hunk.old_start + hunk.old_len) as isize;
+    }
+    y.saturating_add_signed(shift)
+}
@@ -1,11 +1,19 @@
-use crate::backup::BackupManager;
+use crate::backup::{BackupManager, RetentionPolicy};
 use crate::error::{DmacsError, Result};
+use crate::file_settings::FileSettings;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 use std::fs;
-use std::io::Write;
+use std::io::{BufWriter, Write};
+use std::ops::Range;
 use std::path::PathBuf;
 use std::time::SystemTime;
 
-#[derive(Clone, Debug)]
+pub mod line_buffer;
+
+pub use line_buffer::LineBuffer;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ActionDiff {
     pub cursor_start_x: usize,
     pub cursor_start_y: usize,
@@ -19,48 +27,246 @@ pub struct ActionDiff {
     pub new: Vec<String>,
 }
 
+// Matches the width tabs are drawn with
+const TAB_WIDTH: usize = 4;
+
+/// Clean-ups applied to the text written by `Document::save`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SaveOptions {
+    pub trim_trailing_whitespace: bool,
+    pub ensure_final_newline: bool,
+    pub tabs_to_spaces: bool,
+}
+
+impl SaveOptions {
+    pub fn is_empty(&self) -> bool {
+        *self == SaveOptions::default()
+    }
+
+    /// Returns `lines` as they are written to disk.
+    pub fn apply(&self, lines: &LineBuffer) -> Vec<String> {
+        let mut cleaned: Vec<String> = lines.iter().map(|line| self.apply_line(line)).collect();
+        if self.ensure_final_newline {
+            // Every line is written with a newline, so dropping empty lines at
+            // the end leaves exactly one
+            while cleaned.len() > 1 && cleaned.last().is_some_and(|line| line.is_empty()) {
+                cleaned.pop();
+            }
+        }
+        cleaned
+    }
+
+    fn apply_line(&self, line: &str) -> String {
+        let line = if self.tabs_to_spaces {
+            expand_tabs(line)
+        } else {
+            line.to_string()
+        };
+        if self.trim_trailing_whitespace {
+            line.trim_end().to_string()
+        } else {
+            line
+        }
+    }
+
+    /// Maps the cursor position `(x, y)` in `lines` to the same text after
+    /// `apply`, so that the cursor stays put across a cleaned-up save.
+    pub fn map_position(&self, lines: &LineBuffer, x: usize, y: usize) -> (usize, usize) {
+        let cleaned = self.apply(lines);
+        let Some(line) = lines.get(y) else {
+            return (x, y);
+        };
+        if y >= cleaned.len() {
+            let last = cleaned.len() - 1;
+            return (cleaned[last].len(), last);
+        }
+        let prefix = &line[..x.min(line.len())];
+        let x = if self.tabs_to_spaces {
+            expand_tabs(prefix).len()
+        } else {
+            prefix.len()
+        };
+        (x.min(cleaned[y].len()), y)
+    }
+}
+
+fn expand_tabs(line: &str) -> String {
+    let mut expanded = String::with_capacity(line.len());
+    let mut column = 0;
+    for c in line.chars() {
+        if c == '\t' {
+            let width = TAB_WIDTH - column % TAB_WIDTH;
+            expanded.push_str(&" ".repeat(width));
+            column += width;
+        } else {
+            expanded.push(c);
+            column += 1;
+        }
+    }
+    expanded
+}
+
+/// Line terminator written by `Document::save`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    CrLf,
+}
+
+impl LineEnding {
+    /// The ending used by most lines of `content`, so files keep theirs.
+    pub fn detect(content: &str) -> Self {
+        let crlf = content.matches("\r\n").count();
+        if crlf > 0 && crlf * 2 >= content.matches('\n').count() {
+            LineEnding::CrLf
+        } else {
+            LineEnding::Lf
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "lf" => Some(LineEnding::Lf),
+            "crlf" => Some(LineEnding::CrLf),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "LF",
+            LineEnding::CrLf => "CRLF",
+        }
+    }
+}
+
+// Changes remembered for `changed_lines_since`
+const CHANGE_LOG_SIZE: usize = 1024;
+
+/// Lines `start..start + removed` were replaced with `start..start + inserted`.
+#[derive(Clone, Copy, Debug)]
+pub struct LineChange {
+    pub start: usize,
+    pub removed: usize,
+    pub inserted: usize,
+}
+
+impl LineChange {
+    /// Maps the line ranges of the lines before this change to those after it,
+    /// adding the lines it inserted.
+    fn apply(&self, ranges: &mut Vec<Range<usize>>) {
+        let removed_end = self.start + self.removed;
+        let shift = |y: usize| y + self.inserted - self.removed;
+        let mut mapped = Vec::with_capacity(ranges.len() + 1);
+        for range in ranges.drain(..) {
+            if range.start < self.start {
+                mapped.push(range.start..range.end.min(self.start));
+            }
+            if range.end > removed_end {
+                mapped.push(shift(range.start.max(removed_end))..shift(range.end));
+            }
+        }
+        mapped.push(self.start..self.start + self.inserted);
+        mapped.sort_by_key(|range| range.start);
+        for range in mapped.into_iter().filter(|range| !range.is_empty()) {
+            match ranges.last_mut() {
+                Some(last) if last.end >= range.start => last.end = last.end.max(range.end),
+                _ => ranges.push(range),
+            }
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct Document {
-    pub lines: Vec<String>,
+    lines: LineBuffer,
     pub filename: Option<String>,
+    pub save_options: SaveOptions,
+    pub backup_retention: RetentionPolicy,
+    pub line_ending: LineEnding,
+    // What the file sets for itself, read when it is opened or saved
+    pub file_settings: FileSettings,
     original_content: Option<String>,
+    revision: u64,
+    // The latest changes, the last one made at `revision`
+    changes: VecDeque<LineChange>,
 }
 
 impl Document {
     pub fn open(filename: &str) -> Result<Self> {
         let content = std::fs::read_to_string(filename).map_err(DmacsError::Io)?;
-        let lines = content.lines().map(|s| s.to_string()).collect();
-        Ok(Self {
+        Ok(Self::from_content(filename, content))
+    }
+
+    /// Builds the document for `filename` from content that was already read.
+    pub fn from_content(filename: &str, content: String) -> Self {
+        let lines: LineBuffer = content.lines().map(|s| s.to_string()).collect();
+        Self {
+            file_settings: FileSettings::parse(&lines),
             lines,
             filename: Some(filename.to_string()),
+            save_options: SaveOptions::default(),
+            backup_retention: RetentionPolicy::default(),
+            line_ending: LineEnding::detect(&content),
             original_content: Some(content),
-        })
+            revision: 0,
+            changes: VecDeque::new(),
+        }
     }
 
     pub fn new_empty() -> Self {
         Self {
-            lines: vec!["".to_string()],
+            lines: LineBuffer::from(vec!["".to_string()]),
             filename: None,
+            save_options: SaveOptions::default(),
+            backup_retention: RetentionPolicy::default(),
+            line_ending: LineEnding::Lf,
+            file_settings: FileSettings::default(),
             original_content: None,
+            revision: 0,
+            changes: VecDeque::new(),
         }
     }
 
     pub fn save(&mut self, base_dir: Option<PathBuf>) -> Result<()> {
         if let Some(filename) = &self.filename {
-            let backup_manager = BackupManager::new_with_base_dir(base_dir)?;
+            let backup_manager =
+                BackupManager::new_with_base_dir(base_dir)?.with_retention(self.backup_retention);
 
             // Backup original content if it exists and the document is dirty
             if self.is_dirty() {
                 if let Some(original_content) = &self.original_content {
                     backup_manager.save_backup(filename, original_content)?;
+                    backup_manager.prune_backups(filename)?;
                 }
             }
 
-            let mut file = std::fs::File::create(filename).map_err(DmacsError::Io)?;
-            for _line in &self.lines {
-                writeln!(file, "{_line}").map_err(DmacsError::Io)?;
+            let cleaned = (!self.save_options.is_empty())
+                .then(|| LineBuffer::from(self.save_options.apply(&self.lines)));
+            let lines = cleaned.as_ref().unwrap_or(&self.lines);
+
+            let file = std::fs::File::create(filename).map_err(DmacsError::Io)?;
+            let mut writer = BufWriter::new(file);
+            let ending = self.line_ending.as_str();
+            for line in lines {
+                write!(writer, "{line}{ending}").map_err(DmacsError::Io)?;
             }
-            self.original_content = Some(self.lines.join("\n") + "\n");
+            writer.flush().map_err(DmacsError::Io)?;
+            self.file_settings = FileSettings::parse(lines);
+            self.original_content = Some(
+                lines
+                    .iter()
+                    .flat_map(|line| [line.as_str(), ending])
+                    .collect(),
+            );
 
             // Clean up old backups
             backup_manager.clean_old_backups()?;
@@ -68,18 +274,52 @@ impl Document {
         Ok(())
     }
 
+    /// Writes the buffer to `filename` and makes it the document's file. A file
+    /// already at that path is backed up before it is overwritten, and the
+    /// backups of the previous file carry over to the new one.
+    pub fn save_as(&mut self, filename: &str, base_dir: Option<PathBuf>) -> Result<()> {
+        let previous_filename = self.filename.replace(filename.to_string());
+        let previous_content = std::mem::replace(
+            &mut self.original_content,
+            fs::read_to_string(filename).ok(),
+        );
+        if let Err(e) = self.save(base_dir.clone()) {
+            self.filename = previous_filename;
+            self.original_content = previous_content;
+            return Err(e);
+        }
+        if let Some(previous_filename) = previous_filename
+            && previous_filename != filename
+        {
+            BackupManager::new_with_base_dir(base_dir)?
+                .copy_backups(&previous_filename, filename)?;
+        }
+        Ok(())
+    }
+
+    /// Content of the file as last read from or written to disk.
+    pub fn original_content(&self) -> Option<&str> {
+        self.original_content.as_deref()
+    }
+
+    /// Records `content` as what is currently on disk, e.g. after a background save.
+    pub fn mark_saved(&mut self, content: String) {
+        self.original_content = Some(content);
+    }
+
+    /// Records `text` as appended to the file on disk, e.g. by a followed log.
+    pub fn mark_appended(&mut self, text: &str) {
+        self.original_content.get_or_insert_default().push_str(text);
+    }
+
     pub fn is_dirty(&self) -> bool {
         if self.filename.is_none() {
             // New file, always dirty until saved
             return true;
         }
-        let original_lines: Vec<String> = self
-            .original_content
-            .as_ref()
-            .map(|s| s.lines().map(|line| line.to_string()).collect())
-            .unwrap_or_default();
-
-        self.lines != original_lines
+        // Compare line by line without allocating, since this runs on every redraw
+        let original = self.original_content.as_deref().unwrap_or_default();
+        !original.lines().eq(self.lines.iter().map(String::as_str))
     }
 
     pub fn last_modified(&self) -> Result<SystemTime> {
@@ -94,6 +334,72 @@ impl Document {
         }
     }
 
+    /// The text of the document, one line per entry without its line ending.
+    pub fn lines(&self) -> &LineBuffer {
+        &self.lines
+    }
+
+    /// Replaces lines `range` with `lines` and records the change.
+    pub fn replace_lines(&mut self, range: Range<usize>, lines: Vec<String>) {
+        let (start, removed, inserted) = (range.start, range.len(), lines.len());
+        self.lines.splice(range, lines);
+        self.record_change(start, removed, inserted);
+    }
+
+    /// Replaces all the lines, as when the buffer is loaded anew.
+    pub fn set_lines(&mut self, lines: Vec<String>) {
+        self.replace_lines(0..self.lines.len(), lines);
+    }
+
+    pub fn set_line(&mut self, y: usize, line: String) {
+        self.replace_lines(y..y + 1, vec![line]);
+    }
+
+    /// Goes up by one with every change to the lines, so that what was worked
+    /// out from them can tell whether it is still current.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Records that lines `start..start + removed` were replaced with
+    /// `start..start + inserted`. Diffs and `replace_lines` record their
+    /// changes themselves.
+    pub fn record_change(&mut self, start: usize, removed: usize, inserted: usize) {
+        self.revision += 1;
+        if self.changes.len() == CHANGE_LOG_SIZE {
+            self.changes.pop_front();
+        }
+        self.changes.push_back(LineChange {
+            start,
+            removed,
+            inserted,
+        });
+    }
+
+    /// The lines changed since `revision`, as sorted ranges of the current
+    /// lines, or `None` when that is more changes ago than are remembered.
+    pub fn changed_lines_since(&self, revision: u64) -> Option<Vec<Range<usize>>> {
+        let mut ranges = Vec::new();
+        for change in self.changes_since(revision)? {
+            change.apply(&mut ranges);
+        }
+        for range in &mut ranges {
+            range.end = range.end.min(self.lines.len());
+        }
+        ranges.retain(|range| !range.is_empty());
+        Some(ranges)
+    }
+
+    /// The changes made since `revision`, oldest first, or `None` when that
+    /// is more changes ago than are remembered.
+    pub fn changes_since(&self, revision: u64) -> Option<impl Iterator<Item = &LineChange>> {
+        let count = usize::try_from(self.revision.checked_sub(revision)?).ok()?;
+        if count > self.changes.len() {
+            return None;
+        }
+        Some(self.changes.range(self.changes.len() - count..))
+    }
+
     pub fn apply_action_diff(
         &mut self,
         action_diff: &ActionDiff,
@@ -113,25 +419,34 @@ impl Document {
         } = *action_diff;
 
         let replacement = if is_undo { old } else { new };
+        let replaced = if is_undo { new } else { old };
+        let lines_before = self.lines.len();
 
         // Delete start..end
         // Do nothing if it is insertion or deletion
         let is_insertion = old.is_empty() && !replacement.is_empty();
         let is_deletion = new.is_empty() && !replacement.is_empty();
+        // Undoing a replacement removes the new text, which may end elsewhere
+        let (end_x, end_y) = if is_undo && !old.is_empty() && !new.is_empty() {
+            let last = new.last().map_or(0, String::len);
+            let end_x = if new.len() == 1 { start_x + last } else { last };
+            (end_x, start_y + new.len() - 1)
+        } else {
+            (end_x, end_y)
+        };
         if !is_insertion && !is_deletion {
             if start_y == end_y {
                 if start_y < self.lines.len() {
                     self.lines[start_y].drain(start_x..end_x);
                 }
             } else {
-                let prefix = self.lines[start_y][..start_x].to_string();
                 let suffix = self.lines[end_y][end_x..].to_string();
-                self.lines[start_y] = format!("{prefix}{suffix}");
+                self.lines[start_y].truncate(start_x);
+                self.lines[start_y].push_str(&suffix);
 
-                for y in (start_y + 1..=end_y).rev() {
-                    if y < self.lines.len() {
-                        self.lines.remove(y);
-                    }
+                let remove_end = (end_y + 1).min(self.lines.len());
+                if start_y + 1 < remove_end {
+                    self.lines.drain(start_y + 1..remove_end);
                 }
             }
         }
@@ -150,26 +465,27 @@ impl Document {
                 } else {
                     String::new()
                 };
-                self.lines[start_y] =
-                    format!("{}{}", &self.lines[start_y][..start_x], replacement[0]);
-
-                for (i, line) in replacement
-                    .iter()
-                    .enumerate()
-                    .skip(1)
-                    .take(replacement.len() - 2)
-                {
-                    self.lines.insert(start_y + i, line.clone());
-                }
+                self.lines[start_y].truncate(start_x);
+                self.lines[start_y].push_str(&replacement[0]);
 
-                let end_line_idx = start_y + replacement.len() - 1;
-                self.lines.insert(
-                    end_line_idx,
-                    format!("{}{}", replacement.last().unwrap(), suffix),
-                );
+                let inserted =
+                    replacement[1..replacement.len() - 1]
+                        .iter()
+                        .cloned()
+                        .chain(std::iter::once(format!(
+                            "{}{}",
+                            replacement.last().unwrap(),
+                            suffix
+                        )));
+                self.lines.splice(start_y + 1..start_y + 1, inserted);
             }
         }
 
+        // The lines the diff spans, with as many more or fewer as it added or removed
+        let removed = replaced.len().max(1);
+        let inserted = (removed + self.lines.len()).saturating_sub(lines_before);
+        self.record_change(start_y, removed, inserted);
+
         // Adjust cursor position
         if is_undo {
             Ok((cursor_start_x, cursor_start_y))
@@ -0,0 +1,320 @@
+use std::fmt;
+use std::iter::{FusedIterator, Rev};
+use std::ops::{Bound, Index, IndexMut, Range, RangeBounds};
+use std::slice;
+
+// Spare capacity kept after lines are removed, so that memory follows the
+// size of the document rather than the largest it has been
+const SPARE_LINES: usize = 1024;
+
+/// The lines of a document, kept as a gap buffer: the lines before the gap in
+/// order and those after it in reverse. Lines are inserted and removed at the
+/// gap, which moves there first, so edits near the last one move few lines
+/// however long the document is. Changing the text within a line never moves
+/// the gap.
+#[derive(Clone, Default)]
+pub struct LineBuffer {
+    before: Vec<String>,
+    // The last line first
+    after: Vec<String>,
+}
+
+impl LineBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.before.len() + self.after.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.before.is_empty() && self.after.is_empty()
+    }
+
+    pub fn get(&self, y: usize) -> Option<&String> {
+        if y < self.before.len() {
+            self.before.get(y)
+        } else {
+            let from_end = self.len().checked_sub(y + 1)?;
+            self.after.get(from_end)
+        }
+    }
+
+    pub fn get_mut(&mut self, y: usize) -> Option<&mut String> {
+        if y < self.before.len() {
+            self.before.get_mut(y)
+        } else {
+            let from_end = self.len().checked_sub(y + 1)?;
+            self.after.get_mut(from_end)
+        }
+    }
+
+    pub fn first(&self) -> Option<&String> {
+        self.get(0)
+    }
+
+    pub fn last(&self) -> Option<&String> {
+        self.after.first().or_else(|| self.before.last())
+    }
+
+    pub fn last_mut(&mut self) -> Option<&mut String> {
+        match self.after.first_mut() {
+            Some(line) => Some(line),
+            None => self.before.last_mut(),
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
+        self.range(..)
+    }
+
+    /// The lines in `range`, which must lie within the buffer.
+    pub fn range(&self, range: impl RangeBounds<usize>) -> Iter<'_> {
+        let Range { start, end } = self.bounds(range);
+        let gap = self.before.len();
+        let before = &self.before[start.min(gap)..end.min(gap)];
+        let after = &self.after[self.len() - end.max(gap)..self.len() - start.max(gap)];
+        Iter {
+            before: before.iter(),
+            after: after.iter().rev(),
+        }
+    }
+
+    pub fn to_vec(&self) -> Vec<String> {
+        self.iter().cloned().collect()
+    }
+
+    pub fn push(&mut self, line: String) {
+        self.move_gap(self.len());
+        self.before.push(line);
+    }
+
+    pub fn insert(&mut self, y: usize, line: String) {
+        self.splice(y..y, [line]);
+    }
+
+    pub fn remove(&mut self, y: usize) -> String {
+        assert!(y < self.len(), "line {y} is out of bounds");
+        self.move_gap(y + 1);
+        let line = self.before.pop().unwrap();
+        self.shrink();
+        line
+    }
+
+    /// Replaces the lines in `range` with `lines`.
+    pub fn splice(
+        &mut self,
+        range: impl RangeBounds<usize>,
+        lines: impl IntoIterator<Item = String>,
+    ) {
+        let Range { start, end } = self.bounds(range);
+        self.move_gap(end);
+        self.before.truncate(start);
+        self.before.extend(lines);
+        self.shrink();
+    }
+
+    /// Removes the lines in `range` and returns them.
+    pub fn drain(&mut self, range: impl RangeBounds<usize>) -> Vec<String> {
+        let Range { start, end } = self.bounds(range);
+        self.move_gap(end);
+        let drained = self.before.split_off(start);
+        self.shrink();
+        drained
+    }
+
+    fn bounds(&self, range: impl RangeBounds<usize>) -> Range<usize> {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end + 1,
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => self.len(),
+        };
+        assert!(
+            start <= end && end <= self.len(),
+            "lines {start}..{end} are out of bounds of {}",
+            self.len()
+        );
+        start..end
+    }
+
+    /// Moves the gap to before line `y`.
+    fn move_gap(&mut self, y: usize) {
+        let gap = self.before.len();
+        if y < gap {
+            self.after.extend(self.before.drain(y..).rev());
+        } else if y > gap {
+            let moved = self.after.len() - (y - gap);
+            self.before.extend(self.after.drain(moved..).rev());
+        }
+    }
+
+    fn shrink(&mut self) {
+        for lines in [&mut self.before, &mut self.after] {
+            if lines.capacity() > lines.len() * 2 + SPARE_LINES {
+                lines.shrink_to(lines.len() + SPARE_LINES);
+            }
+        }
+    }
+}
+
+impl Index<usize> for LineBuffer {
+    type Output = String;
+
+    fn index(&self, y: usize) -> &String {
+        let len = self.len();
+        self.get(y)
+            .unwrap_or_else(|| panic!("line {y} is out of bounds of {len}"))
+    }
+}
+
+impl IndexMut<usize> for LineBuffer {
+    fn index_mut(&mut self, y: usize) -> &mut String {
+        let len = self.len();
+        self.get_mut(y)
+            .unwrap_or_else(|| panic!("line {y} is out of bounds of {len}"))
+    }
+}
+
+impl fmt::Debug for LineBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl From<Vec<String>> for LineBuffer {
+    fn from(lines: Vec<String>) -> Self {
+        Self {
+            before: lines,
+            after: Vec::new(),
+        }
+    }
+}
+
+impl FromIterator<String> for LineBuffer {
+    fn from_iter<I: IntoIterator<Item = String>>(lines: I) -> Self {
+        Self::from(lines.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl<'a> IntoIterator for &'a LineBuffer {
+    type Item = &'a String;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+impl PartialEq for LineBuffer {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl Eq for LineBuffer {}
+
+impl PartialEq<LineBuffer> for &LineBuffer {
+    fn eq(&self, other: &LineBuffer) -> bool {
+        **self == *other
+    }
+}
+
+impl<S> PartialEq<[S]> for LineBuffer
+where
+    String: PartialEq<S>,
+{
+    fn eq(&self, other: &[S]) -> bool {
+        self.len() == other.len() && self.iter().zip(other).all(|(line, other)| *line == *other)
+    }
+}
+
+impl<S> PartialEq<Vec<S>> for LineBuffer
+where
+    String: PartialEq<S>,
+{
+    fn eq(&self, other: &Vec<S>) -> bool {
+        *self == **other
+    }
+}
+
+impl<S, const N: usize> PartialEq<[S; N]> for LineBuffer
+where
+    String: PartialEq<S>,
+{
+    fn eq(&self, other: &[S; N]) -> bool {
+        *self == other[..]
+    }
+}
+
+impl<S> PartialEq<Vec<S>> for &LineBuffer
+where
+    String: PartialEq<S>,
+{
+    fn eq(&self, other: &Vec<S>) -> bool {
+        **self == **other
+    }
+}
+
+impl<S, const N: usize> PartialEq<[S; N]> for &LineBuffer
+where
+    String: PartialEq<S>,
+{
+    fn eq(&self, other: &[S; N]) -> bool {
+        **self == other[..]
+    }
+}
+
+/// The lines of a `LineBuffer`, in order.
+#[derive(Clone)]
+pub struct Iter<'a> {
+    before: slice::Iter<'a, String>,
+    after: Rev<slice::Iter<'a, String>>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = &'a String;
+
+    fn next(&mut self) -> Option<&'a String> {
+        self.before.next().or_else(|| self.after.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.before.len() + self.after.len();
+        (len, Some(len))
+    }
+
+    // Skipping ahead is constant time, as with a slice
+    fn nth(&mut self, n: usize) -> Option<&'a String> {
+        let before = self.before.len();
+        if n < before {
+            return self.before.nth(n);
+        }
+        self.before = [].iter();
+        self.after.nth(n - before)
+    }
+}
+
+impl<'a> DoubleEndedIterator for Iter<'a> {
+    fn next_back(&mut self) -> Option<&'a String> {
+        self.after.next_back().or_else(|| self.before.next_back())
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<&'a String> {
+        let after = self.after.len();
+        if n < after {
+            return self.after.nth_back(n);
+        }
+        self.after = [].iter().rev();
+        self.before.nth_back(n - after)
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+impl FusedIterator for Iter<'_> {}
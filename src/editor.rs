@@ -1,26 +1,94 @@
+use crate::clock::Clock;
+use crate::copy_format::{self, CopyFormat};
 use crate::document::{ActionDiff, Document};
 use crate::editor::search::Search;
+use crate::editor::word::{CharType, find_word_boundary_left};
 use crate::error::Result;
+use crate::inbox;
+use crate::journal::{self, Journal, JournalEntry};
+use crate::paste;
 use crate::persistence::{self, CursorPosition};
 use log::debug;
-
+use std::path::Path;
+
+pub mod abbrev;
+pub mod agenda;
+pub mod annotation;
+pub mod auto_pair;
+pub mod backup_browser;
+pub mod bookmarks;
+pub mod case;
 pub mod checkbox;
+pub mod cleanup;
 pub mod clipboard;
 pub mod command;
+pub mod command_palette;
 pub mod comment;
+pub mod completion;
+pub mod cursor;
+pub mod date_picker;
+pub mod decoration;
+pub mod due;
+pub mod external_change;
+pub mod file_browser;
+pub mod file_format;
+pub mod file_settings;
+pub mod fold;
+pub mod follow;
+pub mod footnote;
+pub mod git_commit;
+pub mod git_gutter;
+pub mod heading;
+pub mod hint_bar;
 pub mod indent;
 pub mod input;
+pub mod input_transform;
+pub mod jump_list;
+pub mod kill_ring;
+pub mod list_subtree;
+pub mod macros;
+pub mod minibuffer;
+pub mod mouse;
+pub mod outline;
+pub mod page;
+pub mod placeholder;
+pub mod pomodoro;
+pub mod recent_files;
+pub mod recovery;
+pub mod rectangle;
+pub mod repeat;
+pub mod save_hooks;
+pub mod scratch_buffer;
 pub mod scroll;
 pub mod search;
 pub mod selection;
+pub mod session;
+pub mod shell;
+pub mod snippet;
+pub mod spelling;
+pub mod stats;
+pub mod status_line;
+pub mod structure;
+pub mod tags;
 pub mod task;
+pub mod theme;
+pub mod time_tracking;
+pub mod timestamp;
+pub mod todo_keyword;
+pub mod todotxt;
 pub mod ui;
 pub mod undo;
+pub mod undo_snapshot;
+pub mod view;
+pub mod word;
+use crate::editor::cursor::Cursor;
 use crate::editor::scroll::Scroll;
 pub mod actions;
+pub mod autosave;
 pub mod fuzzy_search;
-use crate::config::Keymap;
+use crate::config::{Colors, EditorSettings, Keymap};
 use crate::editor::actions::Action;
+use crate::editor::case::CaseChange;
 use crate::editor::task::Task;
 use crate::editor::undo::{LastActionType, UndoRedo};
 
@@ -30,14 +98,28 @@ pub enum EditorMode {
     TaskSelection,
     Search,
     FuzzySearch,
+    ProjectSearch,
+    Recovery,
+    DatePicker,
+    Minibuffer,
+    Agenda,
+    FileBrowser,
+    RecentFiles,
+    Outline,
+    SaveConflict,
+    BackupBrowser,
+    SpellingSuggestions,
+    Completion,
+    KillRing,
+    Bookmarks,
+    CommandPalette,
+    Tags,
 }
 
 pub struct Editor {
     pub should_quit: bool,
     pub document: Document,
-    pub cursor_x: usize, // byte index
-    pub cursor_y: usize,
-    pub desired_cursor_x: usize, // column index
+    pub cursor: Cursor,
     pub status_message: String,
     pub scroll: Scroll,
     pub undo_redo: UndoRedo,
@@ -50,7 +132,95 @@ pub struct Editor {
     pub mode: EditorMode,
     pub task: Task,
     pub fuzzy_search: fuzzy_search::FuzzySearch,
+    pub project_search: fuzzy_search::ProjectSearch,
+    pub kill_ring_browser: kill_ring::KillRingBrowser,
+    pub selection_expansion: structure::SelectionExpansion,
     pub keymap: Keymap,
+    pub monochrome: bool,
+    pub mouse: mouse::Mouse,
+    pub settings: EditorSettings,
+    // Range touched by the most recent edit, as ((start_x, start_y), (end_x, end_y))
+    pub last_change: Option<((usize, usize), (usize, usize))>,
+    pub autosave: autosave::Autosave,
+    pub journal: Journal,
+    // Journal entries from a crashed session, waiting for the user to confirm
+    pub pending_recovery: Vec<JournalEntry>,
+    // The file or scratch buffer that is not currently shown
+    pub alternate_buffer: Option<scratch_buffer::BufferState>,
+    pub macros: macros::Macros,
+    pub repeat: repeat::RepeatCount,
+    // Where the last `RecenterView` scrolled to, to cycle on from there
+    pub recentered: Option<view::Recentered>,
+    pub task_timer: time_tracking::TaskTimer,
+    pub pomodoro: pomodoro::Pomodoro,
+    pub date_picker: Option<date_picker::DatePicker>,
+    pub minibuffer: minibuffer::Minibuffer,
+    pub agenda: agenda::Agenda,
+    pub file_browser: file_browser::FileBrowser,
+    pub recent_files: recent_files::RecentFiles,
+    // Edits and saves are refused, e.g. while following a log file
+    pub read_only: bool,
+    pub follow: Option<follow::Follow>,
+    pub shell_job: Option<shell::ShellJob>,
+    // Overrides `subword_filetypes` for the current buffer
+    pub subword_mode: Option<bool>,
+    // Overrides `input_transforms` for the current buffer
+    pub input_transform: Option<bool>,
+    // Overrides whether `abbrevs` expand in the current buffer
+    pub abbrev_expansion: Option<bool>,
+    pub folds: fold::Folds,
+    pub outline: outline::Outline,
+    pub tag_index: Option<tags::TagIndex>,
+    pub pages: page::PageIndex,
+    pub file_settings: file_settings::AppliedFileSettings,
+    pub tag_list: tags::TagList,
+    // The tag that NextTaggedLine and PreviousTaggedLine go through
+    pub tag_filter: Option<String>,
+    pub external_change: external_change::ExternalChange,
+    pub backup_browser: backup_browser::BackupBrowser,
+    pub spelling: spelling::Spelling,
+    pub completion: completion::Completion,
+    // The tab stops of the snippet being filled in
+    pub snippet_stops: Option<snippet::SnippetStops>,
+    pub jumps: jump_list::JumpList,
+    pub bookmarks: bookmarks::Bookmarks,
+    pub sessions: session::Sessions,
+    pub command_palette: command_palette::CommandPalette,
+    // Decorate each line as it is drawn, in order
+    pub line_transformers: Vec<Box<dyn decoration::LineTransformer>>,
+    // The ruler column while ToggleRuler has it hidden
+    pub hidden_ruler_column: Option<usize>,
+    pub branch_cache: status_line::BranchCache,
+    pub git_gutter: git_gutter::GitGutter,
+    // Colors of a theme switched to, for the terminal to take up
+    pub pending_colors: Option<Colors>,
+}
+
+/// Reads `filename`, or starts an empty document for it if it does not exist
+/// yet, along with the cursor and scroll position saved for it.
+fn open_document(filename: String) -> (Document, Option<(usize, usize, usize, usize)>) {
+    let opened = Document::open(&filename);
+    restore_document(filename, opened)
+}
+
+fn restore_document(
+    filename: String,
+    opened: Result<Document>,
+) -> (Document, Option<(usize, usize, usize, usize)>) {
+    match opened {
+        Ok(doc) => {
+            let restored = doc
+                .last_modified()
+                .ok()
+                .and_then(|lm| persistence::get_cursor_position(&filename, lm));
+            (doc, restored)
+        }
+        Err(_) => {
+            let mut doc = Document::new_empty();
+            doc.filename = Some(filename);
+            (doc, None)
+        }
+    }
 }
 
 impl Editor {
@@ -60,30 +230,53 @@ impl Editor {
         column: Option<usize>,
     ) -> Self {
         let (document, restored_pos) = match filename {
-            Some(fname) => {
-                if let Ok(doc) = Document::open(&fname) {
-                    let last_modified = doc.last_modified().ok();
-                    let restored = if let Some(lm) = last_modified {
-                        persistence::get_cursor_position(&fname, lm)
-                    } else {
-                        None
-                    };
-                    (doc, restored)
-                } else {
-                    let mut doc = Document::new_empty();
-                    doc.filename = Some(fname);
-                    (doc, None)
-                }
-            }
+            Some(fname) => open_document(fname),
             None => (Document::default(), None),
         };
+        Self::with_document(document, restored_pos, line, column)
+    }
+
+    /// Like `new`, for a file whose content was read beforehand, e.g. by a
+    /// `FileLoader`.
+    pub fn from_loaded(
+        filename: String,
+        content: Result<String>,
+        line: Option<usize>,
+        column: Option<usize>,
+    ) -> Self {
+        let opened = content.map(|content| Document::from_content(&filename, content));
+        let (document, restored_pos) = restore_document(filename, opened);
+        Self::with_document(document, restored_pos, line, column)
+    }
+
+    /// Like `from_loaded`, but without going back to where the cursor was left
+    /// in the file last time.
+    pub fn from_loaded_fresh(
+        filename: String,
+        content: Result<String>,
+        line: Option<usize>,
+        column: Option<usize>,
+    ) -> Self {
+        let document = content
+            .map(|content| Document::from_content(&filename, content))
+            .unwrap_or_else(|_| {
+                let mut document = Document::new_empty();
+                document.filename = Some(filename);
+                document
+            });
+        Self::with_document(document, None, line, column)
+    }
 
+    fn with_document(
+        document: Document,
+        restored_pos: Option<(usize, usize, usize, usize)>,
+        line: Option<usize>,
+        column: Option<usize>,
+    ) -> Self {
         let mut editor = Self {
             should_quit: false,
             document,
-            cursor_x: 0,
-            cursor_y: 0,
-            desired_cursor_x: 0,
+            cursor: Cursor::default(),
             status_message: "".to_string(),
             scroll: Scroll::new(),
             undo_redo: UndoRedo::new(),
@@ -95,55 +288,70 @@ impl Editor {
             mode: EditorMode::Normal,
             task: Task::new(),
             fuzzy_search: fuzzy_search::FuzzySearch::new(),
+            project_search: fuzzy_search::ProjectSearch::default(),
+            kill_ring_browser: kill_ring::KillRingBrowser::default(),
+            selection_expansion: structure::SelectionExpansion::default(),
             keymap: Keymap::default(),
+            monochrome: false,
+            mouse: mouse::Mouse::new(),
+            settings: EditorSettings::default(),
+            last_change: None,
+            autosave: autosave::Autosave::new(),
+            journal: Journal::disabled(),
+            pending_recovery: Vec::new(),
+            alternate_buffer: None,
+            macros: macros::Macros::new(),
+            repeat: repeat::RepeatCount::new(),
+            recentered: None,
+            task_timer: time_tracking::TaskTimer::new(),
+            pomodoro: pomodoro::Pomodoro::new(),
+            date_picker: None,
+            minibuffer: minibuffer::Minibuffer::new(),
+            agenda: agenda::Agenda::default(),
+            file_browser: file_browser::FileBrowser::default(),
+            recent_files: recent_files::RecentFiles::default(),
+            read_only: false,
+            follow: None,
+            shell_job: None,
+            subword_mode: None,
+            input_transform: None,
+            abbrev_expansion: None,
+            folds: fold::Folds::default(),
+            outline: outline::Outline::default(),
+            tag_index: None,
+            pages: page::PageIndex::default(),
+            file_settings: file_settings::AppliedFileSettings::default(),
+            tag_list: tags::TagList::default(),
+            tag_filter: None,
+            external_change: external_change::ExternalChange::default(),
+            backup_browser: backup_browser::BackupBrowser::default(),
+            spelling: spelling::Spelling::default(),
+            completion: completion::Completion::default(),
+            snippet_stops: None,
+            jumps: jump_list::JumpList::default(),
+            bookmarks: bookmarks::Bookmarks::default(),
+            sessions: session::Sessions::default(),
+            command_palette: command_palette::CommandPalette::default(),
+            line_transformers: decoration::default_transformers(),
+            hidden_ruler_column: None,
+            branch_cache: status_line::BranchCache::default(),
+            git_gutter: git_gutter::GitGutter::default(),
+            pending_colors: None,
         };
 
         if let Some((x, y, scroll_row, scroll_col)) = restored_pos {
-            editor.cursor_x = x;
-            editor.cursor_y = y;
-            if y < editor.document.lines.len() {
-                editor.desired_cursor_x = editor
+            editor.cursor.x = x;
+            editor.cursor.y = y;
+            if y < editor.document.lines().len() {
+                editor.cursor.desired_x = editor
                     .scroll
-                    .get_display_width_from_bytes(&editor.document.lines[y], x);
+                    .get_display_width_from_bytes(&editor.document.lines()[y], x);
             }
             editor.scroll = Scroll::new_with_offset(scroll_row, scroll_col);
         }
 
         if let Some(line) = line {
-            let y = line.saturating_sub(1); // Convert 1-based to 0-based
-            if y < editor.document.lines.len() {
-                editor.cursor_y = y;
-
-                let col = column.unwrap_or(1).saturating_sub(1); // 0-based char index
-                let line_content = &editor.document.lines[y];
-
-                let mut byte_offset = 0;
-                let mut current_col = 0;
-                for (i, c) in line_content.char_indices() {
-                    if current_col == col {
-                        byte_offset = i;
-                        break;
-                    }
-                    current_col += 1;
-                    byte_offset = i + c.len_utf8();
-                }
-                if current_col < col {
-                    // if col is out of bounds
-                    byte_offset = line_content.len();
-                }
-
-                editor.cursor_x = byte_offset;
-                editor.desired_cursor_x = editor
-                    .scroll
-                    .get_display_width_from_bytes(line_content, byte_offset);
-            } else {
-                // If line is out of bounds, just go to the end of the file.
-                let num_lines = editor.document.lines.len();
-                if num_lines > 0 {
-                    editor.cursor_y = num_lines - 1;
-                    editor.cursor_x = editor.document.lines[num_lines - 1].len();
-                }
-            }
+            editor.go_to_line_column(line, column);
         }
 
         editor
@@ -151,14 +359,31 @@ impl Editor {
 
     pub fn execute_action(&mut self, action: Action) -> Result<()> {
         self.status_message.clear();
+        self.macros.record(&action);
+        if action.is_cursor_movement()
+            || matches!(action, Action::Undo | Action::Redo | Action::UndoToSnapshot)
+        {
+            self.last_change = None;
+        }
+        if self.read_only && action.modifies_buffer() {
+            self.set_message("Buffer is read-only.");
+            return Ok(());
+        }
+        if self.prompt_on_save_conflict(&action) {
+            return Ok(());
+        }
+        let origin = self.cursor_pos();
+        let is_jump = action.is_jump();
         match action {
             // File
             Action::Save => {
-                self.document.save(None)?;
-                self.status_message = "File saved!".to_string();
+                self.finish_pending_autosave();
+                self.save_with_hooks()?;
+                self.reset_journal();
+                self.status_message = self.with_file_format_warnings("File saved!");
             }
             Action::Quit => {
-                if self.no_exit_on_save {
+                if self.no_exit_on_save && !self.read_only {
                     self.save_document()?;
                     self.set_message("File saved. Editor will not exit.");
                 } else {
@@ -176,13 +401,50 @@ impl Editor {
             Action::MoveWordRight => self.move_cursor_word_right()?,
             Action::PageUp => self.scroll_page_up(),
             Action::PageDown => self.scroll_page_down(),
+            Action::RecenterView => self.recenter_view(),
+            Action::ScrollViewUp => self.scroll_view(false),
+            Action::ScrollViewDown => self.scroll_view(true),
             Action::GoToStartOfFile => self.go_to_start_of_file(),
             Action::GoToEndOfFile => self.go_to_end_of_file(),
             Action::MoveToNextDelimiter => self.move_to_next_delimiter(),
             Action::MoveToPreviousDelimiter => self.move_to_previous_delimiter(),
             // Editing
-            Action::InsertChar(c) => self.insert_text(&c.to_string())?,
-            Action::InsertNewline => self.insert_newline()?,
+            Action::InsertChar(c) if self.selection.rectangle => {
+                self.insert_rectangle_text(&c.to_string())?
+            }
+            Action::InsertChar(c) => {
+                let c = self.transform_input(c);
+                self.expand_abbrev(c);
+                self.insert_char(c)?
+            }
+            Action::InsertNewline => {
+                self.expand_abbrev('\n');
+                self.insert_newline()?
+            }
+            Action::Paste(text) => self.paste(&text)?,
+            Action::ReloadFromDisk => self.reload_from_disk(),
+            Action::ToggleHintBar => self.toggle_hint_bar(),
+            Action::ToggleShortenUrls => self.toggle_shorten_urls(),
+            Action::ToggleRuler => self.toggle_ruler(),
+            Action::ToggleWhitespace => self.toggle_whitespace(),
+            Action::CycleTheme => self.cycle_theme(),
+            Action::NextHunk => self.go_to_hunk(true),
+            Action::PrevHunk => self.go_to_hunk(false),
+            Action::RevertHunk => self.revert_hunk(),
+            Action::GitCommitFile => self.git_commit_file(),
+            Action::BrowseBackups => self.browse_backups(),
+            Action::NextMisspelling => self.next_misspelling(),
+            Action::CorrectSpelling => self.correct_spelling(),
+            Action::CompleteWord => self.complete_word()?,
+            Action::InsertFootnote => self.insert_footnote(),
+            Action::InsertTimestamp => self.insert_timestamp()?,
+            Action::ExpandSnippet => self.expand_snippet(),
+            Action::ShowStats => self.show_stats(),
+            Action::JumpBack => self.jump_back(),
+            Action::JumpForward => self.jump_forward(),
+            Action::SetBookmark => self.set_bookmark(),
+            Action::GotoBookmark => self.open_bookmarks(),
+            Action::FollowFootnote => self.follow_footnote(),
             Action::DeleteChar => self.delete_char()?,
             Action::DeleteForwardChar => self.delete_forward_char()?,
             Action::DeleteWord => self.hungry_delete()?,
@@ -191,20 +453,85 @@ impl Editor {
                 self.clipboard.last_action_was_kill = true;
             }
             Action::Yank => self.yank()?,
+            Action::YankNormalized => self.yank_normalized()?,
+            Action::YankFromHistory => self.open_kill_ring(),
             Action::Undo => self.undo(),
             Action::Redo => self.redo(),
+            Action::UndoToSnapshot => self.undo_to_snapshot(),
             Action::Indent => self.indent_line()?,
             Action::Outdent => self.outdent_line()?,
             Action::ToggleComment => self.toggle_comment()?,
+            Action::UpcaseWord => self.change_case(CaseChange::Upper),
+            Action::DowncaseWord => self.change_case(CaseChange::Lower),
+            Action::CapitalizeWord => self.change_case(CaseChange::Capitalize),
             Action::ToggleCheckbox => self.toggle_checkbox()?,
+            Action::CycleTodoKeyword => self.cycle_todo_keyword(),
+            Action::PromoteHeading => self.promote_heading(false),
+            Action::DemoteHeading => self.demote_heading(false),
+            Action::PromoteSubtree => self.promote_heading(true),
+            Action::DemoteSubtree => self.demote_heading(true),
+            Action::MoveSubtreeUp => self.move_subtree_up(),
+            Action::MoveSubtreeDown => self.move_subtree_down(),
+            Action::KillSubtree => self.kill_subtree()?,
+            Action::YankSubtree => self.yank_subtree()?,
+            // Folding
+            Action::ToggleFold => self.toggle_fold(),
+            Action::FoldAll => self.fold_all(),
+            Action::UnfoldAll => self.unfold_all(),
+            // Tasks
+            Action::ArchiveDoneTasks => self.archive_done_tasks(),
+            Action::SortTasksByDue => self.sort_tasks_by_due(),
+            Action::StartTaskTimer => self.start_task_timer(),
+            Action::StopTaskTimer => self.stop_task_timer(),
+            Action::StartPomodoro => self.start_pomodoro(),
+            Action::StopPomodoro => self.stop_pomodoro(),
+            Action::PomodoroStatus => self.pomodoro_status(),
+            Action::OpenDatePicker => self.open_date_picker(),
+            Action::OpenAgenda => self.open_agenda(),
+            Action::OpenTaskList => self.open_task_list(),
+            Action::ShowOutline => self.show_outline(),
+            Action::ListTags => self.list_tags(),
+            Action::NextTaggedLine => self.go_to_tagged_line(true),
+            Action::PreviousTaggedLine => self.go_to_tagged_line(false),
+            Action::ToggleSubwordMode => self.toggle_subword_mode(),
+            Action::ToggleInputTransform => self.toggle_input_transform(),
+            Action::ToggleAbbrevs => self.toggle_abbrev_expansion(),
             // Selection
             Action::SetMarker => self.set_marker_action(),
             Action::ClearMarker => self.clear_marker_action(),
+            Action::ExpandSelection => self.expand_selection(),
+            Action::ShrinkSelection => self.shrink_selection(),
+            Action::CutSelection if self.selection.rectangle => self.cut_rectangle()?,
             Action::CutSelection => self.cut_selection_action()?,
+            Action::CopySelection if self.selection.rectangle => self.copy_rectangle()?,
             Action::CopySelection => self.copy_selection_action()?,
+            Action::CopyAs if self.selection.is_selection_active() => {
+                self.open_minibuffer(minibuffer::PromptKind::CopyAs)
+            }
+            Action::CopyAs => self.status_message = "No selection to copy.".to_string(),
+            Action::ToggleRectangleMode => self.toggle_rectangle_mode(),
+            Action::YankRectangle => self.yank_rectangle()?,
             // Search
             Action::EnterSearchMode => self.enter_search_mode(),
             Action::EnterFuzzySearchMode => self.enter_fuzzy_search_mode(),
+            Action::GrepProject => self.open_minibuffer(minibuffer::PromptKind::GrepProject),
+            Action::GoToLine => self.open_minibuffer(minibuffer::PromptKind::GoToLine),
+            Action::GotoPage(0) => self.open_minibuffer(minibuffer::PromptKind::GotoPage),
+            Action::GotoPage(number) => self.go_to_page(number),
+            Action::OpenFile => self.open_file_browser(),
+            Action::OpenRecent => self.open_recent_files(),
+            Action::ReopenLastClosed => self.reopen_last_closed(),
+            Action::SaveSession => self.save_session(),
+            Action::SaveAs => {
+                let filename = self.document.filename.clone().unwrap_or_default();
+                self.open_minibuffer_with_input(minibuffer::PromptKind::SaveAs, &filename);
+            }
+            // Macros
+            Action::StartMacroRecording => self.start_macro_recording(),
+            Action::StopMacroRecording => self.stop_macro_recording(),
+            Action::PlayMacro(count) => self.play_macro(count)?,
+            Action::DigitArgument(digit) => self.digit_argument(digit),
+            Action::UniversalArgument => self.universal_argument(),
             // Modes
             Action::EnterNormalMode => {
                 if self.mode != EditorMode::Normal {
@@ -214,10 +541,14 @@ impl Editor {
             // Misc
             Action::MoveLineUp => self.move_line_up(),
             Action::MoveLineDown => self.move_line_down(),
+            Action::ToggleScratchBuffer => self.toggle_scratch_buffer(),
+            Action::CommandPalette => self.open_command_palette(),
             _ => { /* NoOp, etc. */ }
         }
-        self.scroll
-            .clamp_cursor_x(&mut self.cursor_x, &self.cursor_y, &self.document);
+        if is_jump {
+            self.record_jump(origin);
+        }
+        self.scroll.clamp_cursor_x(&mut self.cursor, &self.document);
         Ok(())
     }
 
@@ -227,65 +558,110 @@ impl Editor {
 
     pub fn undo(&mut self) {
         self.clipboard.last_action_was_kill = false;
-        match self.undo_redo.undo(
-            &mut self.document,
-            &mut self.cursor_x,
-            &mut self.cursor_y,
-            &mut self.desired_cursor_x,
-            &self.scroll,
-        ) {
-            Ok(_) => self.status_message = "Undo successful.".to_string(),
+        // Line numbers of folds can't be followed through undo history
+        self.folds.clear();
+        match self
+            .undo_redo
+            .undo(&mut self.document, &mut self.cursor, &self.scroll)
+        {
+            Ok(_) => {
+                self.selection.clamp_marker(self.document.lines());
+                self.journal_undo_redo(true);
+                self.update_tag_index_for_undo_redo(true);
+                self.autosave.note_edit();
+                self.status_message = "Undo successful.".to_string();
+            }
             Err(msg) => self.status_message = msg,
         }
     }
 
     pub fn redo(&mut self) {
         self.clipboard.last_action_was_kill = false;
-        match self.undo_redo.redo(
-            &mut self.document,
-            &mut self.cursor_x,
-            &mut self.cursor_y,
-            &mut self.desired_cursor_x,
-            &self.scroll,
-        ) {
-            Ok(_) => self.status_message = "Redo successful.".to_string(),
+        // Line numbers of folds can't be followed through undo history
+        self.folds.clear();
+        match self
+            .undo_redo
+            .redo(&mut self.document, &mut self.cursor, &self.scroll)
+        {
+            Ok(_) => {
+                self.selection.clamp_marker(self.document.lines());
+                self.journal_undo_redo(false);
+                self.update_tag_index_for_undo_redo(false);
+                self.autosave.note_edit();
+                self.status_message = "Redo successful.".to_string();
+            }
             Err(msg) => self.status_message = msg,
         }
     }
 
     pub(super) fn commit(&mut self, action_type: LastActionType, action_diff: &ActionDiff) {
         self.undo_redo.record_action(action_type, action_diff);
+        self.journal.record(action_diff, false);
+        self.autosave.note_edit();
         let (new_x, new_y) = self.document.apply_action_diff(action_diff, false).unwrap();
-        self.cursor_x = new_x;
-        self.cursor_y = new_y;
-        self.desired_cursor_x = self
+        self.update_tag_index(action_diff);
+        if !self.folds.is_empty() {
+            self.folds.adjust(action_diff);
+        }
+        if !self.bookmarks.marks.is_empty() {
+            self.bookmarks.adjust(action_diff);
+        }
+        self.selection.clamp_marker(self.document.lines());
+        if self.settings.highlight_last_change {
+            self.track_last_change(action_diff);
+        }
+        self.cursor.x = new_x;
+        self.cursor.y = new_y;
+        self.cursor.desired_x = self
             .scroll
-            .get_display_width_from_bytes(&self.document.lines[self.cursor_y], self.cursor_x);
+            .get_display_width_from_bytes(&self.document.lines()[self.cursor.y], self.cursor.x);
+    }
+
+    fn track_last_change(&mut self, action_diff: &ActionDiff) {
+        let new = &action_diff.new;
+        if new.is_empty() {
+            self.last_change = None;
+            return;
+        }
+        let start = (action_diff.start_x, action_diff.start_y);
+        let end = if new.len() == 1 {
+            (action_diff.start_x + new[0].len(), action_diff.start_y)
+        } else {
+            let last_line_len = new[new.len() - 1].len();
+            (last_line_len, action_diff.start_y + new.len() - 1)
+        };
+        // Consecutive edits (e.g. typing a word) grow the same range
+        self.last_change = match self.last_change {
+            Some((previous_start, previous_end)) if previous_end == start => {
+                Some((previous_start, end))
+            }
+            _ => Some((start, end)),
+        };
     }
 
     pub fn insert_text(&mut self, text: &str) -> Result<()> {
         // Special case for inserting " " at the end of a line followed by a space
         // Insert "-> "
         if text == " " {
-            let y = self.cursor_y;
-            let x = self.cursor_x;
+            let y = self.cursor.y;
+            let x = self.cursor.x;
             if x > 0
-                && x == self.document.lines[y].len()
-                && !self.document.lines[y][0..x].trim().is_empty()
+                && x == self.document.lines()[y].len()
+                && !self.document.lines()[y][0..x].trim().is_empty()
             {
-                let last_char = self.document.lines[y].chars().last().unwrap();
+                let last_char = self.document.lines()[y].chars().last().unwrap();
                 if last_char == ' ' {
                     self.commit(
                         LastActionType::Insertion,
                         &ActionDiff {
-                            cursor_start_x: self.cursor_x,
-                            cursor_start_y: self.cursor_y,
-                            cursor_end_x: self.cursor_x + 3,
-                            cursor_end_y: self.cursor_y,
-                            start_x: self.cursor_x,
-                            start_y: self.cursor_y,
-                            end_x: self.cursor_x + 3,
-                            end_y: self.cursor_y,
+                            cursor_start_x: self.cursor.x,
+                            cursor_start_y: self.cursor.y,
+                            cursor_end_x: self.cursor.x + 3,
+                            cursor_end_y: self.cursor.y,
+                            start_x: self.cursor.x,
+                            start_y: self.cursor.y,
+                            end_x: self.cursor.x + 3,
+                            end_y: self.cursor.y,
                             new: vec!["-> ".to_string()],
                             old: vec![],
                         },
@@ -298,14 +674,14 @@ impl Editor {
         self.commit(
             LastActionType::Insertion,
             &ActionDiff {
-                cursor_start_x: self.cursor_x,
-                cursor_start_y: self.cursor_y,
-                cursor_end_x: self.cursor_x + text.len(),
-                cursor_end_y: self.cursor_y,
-                start_x: self.cursor_x,
-                start_y: self.cursor_y,
-                end_x: self.cursor_x + text.len(),
-                end_y: self.cursor_y,
+                cursor_start_x: self.cursor.x,
+                cursor_start_y: self.cursor.y,
+                cursor_end_x: self.cursor.x + text.len(),
+                cursor_end_y: self.cursor.y,
+                start_x: self.cursor.x,
+                start_y: self.cursor.y,
+                end_x: self.cursor.x + text.len(),
+                end_y: self.cursor.y,
                 new: vec![text.to_string()],
                 old: vec![],
             },
@@ -316,11 +692,14 @@ impl Editor {
 
     pub fn delete_char(&mut self) -> Result<()> {
         self.clipboard.last_action_was_kill = false;
+        if self.delete_empty_pair() {
+            return Ok(());
+        }
         // Backspace
-        if self.cursor_x > 0 {
-            let line = self.document.lines[self.cursor_y].clone();
+        if self.cursor.x > 0 {
+            let line = self.document.lines()[self.cursor.y].clone();
             // Only apply if cursor is at the end of the line
-            if self.cursor_x == line.len() {
+            if self.cursor.x == line.len() {
                 let trimmed_line = line.trim();
                 let patterns = ["- [x]", "- [ ]", "-"];
                 for pattern in &patterns {
@@ -332,14 +711,14 @@ impl Editor {
                         self.commit(
                             LastActionType::Deletion,
                             &ActionDiff {
-                                cursor_start_x: self.cursor_x,
-                                cursor_start_y: self.cursor_y,
+                                cursor_start_x: self.cursor.x,
+                                cursor_start_y: self.cursor.y,
                                 cursor_end_x: indentation_len,
-                                cursor_end_y: self.cursor_y,
+                                cursor_end_y: self.cursor.y,
                                 start_x,
-                                start_y: self.cursor_y,
+                                start_y: self.cursor.y,
                                 end_x,
-                                end_y: self.cursor_y,
+                                end_y: self.cursor.y,
                                 new: vec![],
                                 old: vec![line[start_x..end_x].to_string()],
                             },
@@ -349,22 +728,22 @@ impl Editor {
                 }
             }
 
-            let line = &self.document.lines[self.cursor_y];
-            let prefix = &line[..self.cursor_x];
+            let line = &self.document.lines()[self.cursor.y];
+            let prefix = &line[..self.cursor.x];
             if prefix.chars().all(|c| c.is_whitespace()) && prefix.ends_with("  ") {
                 // Delete 2 spaces
-                let char_start_byte = self.cursor_x - 2;
+                let char_start_byte = self.cursor.x - 2;
                 self.commit(
                     LastActionType::Deletion,
                     &ActionDiff {
-                        cursor_start_x: self.cursor_x,
-                        cursor_start_y: self.cursor_y,
+                        cursor_start_x: self.cursor.x,
+                        cursor_start_y: self.cursor.y,
                         cursor_end_x: char_start_byte,
-                        cursor_end_y: self.cursor_y,
+                        cursor_end_y: self.cursor.y,
                         start_x: char_start_byte,
-                        start_y: self.cursor_y,
-                        end_x: self.cursor_x,
-                        end_y: self.cursor_y,
+                        start_y: self.cursor.y,
+                        end_x: self.cursor.x,
+                        end_y: self.cursor.y,
                         new: vec![],
                         old: vec!["  ".to_string()],
                     },
@@ -375,7 +754,7 @@ impl Editor {
             let mut char_to_delete = String::new();
             let mut char_start_byte = 0;
 
-            if let Some((idx, ch)) = line[..self.cursor_x].char_indices().next_back() {
+            if let Some((idx, ch)) = line[..self.cursor.x].char_indices().next_back() {
                 char_to_delete = ch.to_string();
                 char_start_byte = idx;
             }
@@ -383,30 +762,30 @@ impl Editor {
             self.commit(
                 LastActionType::Deletion,
                 &ActionDiff {
-                    cursor_start_x: self.cursor_x,
-                    cursor_start_y: self.cursor_y,
+                    cursor_start_x: self.cursor.x,
+                    cursor_start_y: self.cursor.y,
                     cursor_end_x: char_start_byte,
-                    cursor_end_y: self.cursor_y,
+                    cursor_end_y: self.cursor.y,
                     start_x: char_start_byte,
-                    start_y: self.cursor_y,
-                    end_x: self.cursor_x,
-                    end_y: self.cursor_y,
+                    start_y: self.cursor.y,
+                    end_x: self.cursor.x,
+                    end_y: self.cursor.y,
                     new: vec![],
                     old: vec![char_to_delete],
                 },
             );
-        } else if self.cursor_y > 0 {
+        } else if self.cursor.y > 0 {
             self.commit(
                 LastActionType::Deletion,
                 &ActionDiff {
-                    cursor_start_x: self.cursor_x,
-                    cursor_start_y: self.cursor_y,
-                    cursor_end_x: self.document.lines[self.cursor_y - 1].len(),
-                    cursor_end_y: self.cursor_y - 1,
-                    start_x: self.document.lines[self.cursor_y - 1].len(),
-                    start_y: self.cursor_y - 1,
-                    end_x: self.cursor_x,
-                    end_y: self.cursor_y,
+                    cursor_start_x: self.cursor.x,
+                    cursor_start_y: self.cursor.y,
+                    cursor_end_x: self.document.lines()[self.cursor.y - 1].len(),
+                    cursor_end_y: self.cursor.y - 1,
+                    start_x: self.document.lines()[self.cursor.y - 1].len(),
+                    start_y: self.cursor.y - 1,
+                    end_x: self.cursor.x,
+                    end_y: self.cursor.y,
                     new: vec![],
                     old: vec!["".to_string(), "".to_string()],
                 },
@@ -418,11 +797,11 @@ impl Editor {
     pub fn delete_forward_char(&mut self) -> Result<()> {
         self.clipboard.last_action_was_kill = false;
         // Ctrl-D
-        let y = self.cursor_y;
-        let x = self.cursor_x;
-        let line_len = self.document.lines.get(y).map_or(0, |l| l.len());
+        let y = self.cursor.y;
+        let x = self.cursor.x;
+        let line_len = self.document.lines().get(y).map_or(0, |l| l.len());
         if x < line_len {
-            let line = &self.document.lines[y];
+            let line = &self.document.lines()[y];
             let mut char_to_delete = String::new();
 
             if let Some((_, ch)) = line[x..].char_indices().next() {
@@ -431,30 +810,30 @@ impl Editor {
             self.commit(
                 LastActionType::Deletion,
                 &ActionDiff {
-                    cursor_start_x: self.cursor_x,
-                    cursor_start_y: self.cursor_y,
-                    cursor_end_x: self.cursor_x,
-                    cursor_end_y: self.cursor_y,
-                    start_x: self.cursor_x,
-                    start_y: self.cursor_y,
-                    end_x: self.cursor_x + char_to_delete.len(),
-                    end_y: self.cursor_y,
+                    cursor_start_x: self.cursor.x,
+                    cursor_start_y: self.cursor.y,
+                    cursor_end_x: self.cursor.x,
+                    cursor_end_y: self.cursor.y,
+                    start_x: self.cursor.x,
+                    start_y: self.cursor.y,
+                    end_x: self.cursor.x + char_to_delete.len(),
+                    end_y: self.cursor.y,
                     new: vec![],
                     old: vec![char_to_delete],
                 },
             );
-        } else if y < self.document.lines.len() - 1 {
+        } else if y < self.document.lines().len() - 1 {
             self.commit(
                 LastActionType::Deletion,
                 &ActionDiff {
-                    cursor_start_x: self.cursor_x,
-                    cursor_start_y: self.cursor_y,
-                    cursor_end_x: self.cursor_x,
-                    cursor_end_y: self.cursor_y,
-                    start_x: self.cursor_x,
-                    start_y: self.cursor_y,
+                    cursor_start_x: self.cursor.x,
+                    cursor_start_y: self.cursor.y,
+                    cursor_end_x: self.cursor.x,
+                    cursor_end_y: self.cursor.y,
+                    start_x: self.cursor.x,
+                    start_y: self.cursor.y,
                     end_x: 0,
-                    end_y: self.cursor_y + 1,
+                    end_y: self.cursor.y + 1,
                     new: vec![],
                     old: vec!["".to_string(), "".to_string()],
                 },
@@ -464,10 +843,10 @@ impl Editor {
     }
 
     fn get_indentation(&self) -> String {
-        if self.cursor_y >= self.document.lines.len() {
+        if self.cursor.y >= self.document.lines().len() {
             return String::new();
         }
-        self.document.lines[self.cursor_y]
+        self.document.lines()[self.cursor.y]
             .chars()
             .take_while(|&c| c.is_whitespace())
             .collect()
@@ -476,35 +855,37 @@ impl Editor {
     pub fn insert_newline(&mut self) -> Result<()> {
         self.clipboard.last_action_was_kill = false;
 
-        let y = self.cursor_y;
-        let x = self.cursor_x;
-        let current_line = self.document.lines[y].clone();
+        let y = self.cursor.y;
+        let x = self.cursor.x;
+        let current_line = self.document.lines()[y].clone();
+
+        let list_continuation = self.settings.list_continuation();
 
         // Delete empty list item
-        if x == current_line.len() {
+        if list_continuation && x == current_line.len() {
             let indentation_len = current_line.len() - current_line.trim_start().len();
             let content = &current_line[indentation_len..];
 
             let patterns = ["- [x] ", "- [ ] ", "- "];
-            for pattern in &patterns {
-                if content == *pattern {
-                    self.commit(
-                        LastActionType::Newline,
-                        &ActionDiff {
-                            cursor_start_x: self.cursor_x,
-                            cursor_start_y: self.cursor_y,
-                            cursor_end_x: 0,
-                            cursor_end_y: self.cursor_y,
-                            start_x: 0,
-                            start_y: self.cursor_y,
-                            end_x: self.document.lines[self.cursor_y].len(),
-                            end_y: self.cursor_y,
-                            new: vec![],
-                            old: vec![current_line],
-                        },
-                    );
-                    return Ok(());
-                }
+            let empty_ordered_item = indent::ordered_list_number(&current_line)
+                .is_some_and(|(_, _, end)| end + 2 == current_line.len());
+            if patterns.contains(&content) || empty_ordered_item {
+                self.commit(
+                    LastActionType::Newline,
+                    &ActionDiff {
+                        cursor_start_x: self.cursor.x,
+                        cursor_start_y: self.cursor.y,
+                        cursor_end_x: 0,
+                        cursor_end_y: self.cursor.y,
+                        start_x: 0,
+                        start_y: self.cursor.y,
+                        end_x: self.document.lines()[self.cursor.y].len(),
+                        end_y: self.cursor.y,
+                        new: vec![],
+                        old: vec![current_line],
+                    },
+                );
+                return Ok(());
             }
         }
 
@@ -516,18 +897,65 @@ impl Editor {
             self.commit(
                 LastActionType::Other,
                 &ActionDiff {
-                    cursor_start_x: self.cursor_x,
-                    cursor_start_y: self.cursor_y,
-                    cursor_end_x: self.cursor_x,
-                    cursor_end_y: self.cursor_y,
+                    cursor_start_x: self.cursor.x,
+                    cursor_start_y: self.cursor.y,
+                    cursor_end_x: self.cursor.x,
+                    cursor_end_y: self.cursor.y,
+                    start_x: 0,
+                    start_y: self.cursor.y,
+                    end_x: current_line.len(),
+                    end_y: self.cursor.y,
+                    new: vec![],
+                    old: vec![current_line.to_string()],
+                },
+            );
+            return Ok(());
+        }
+
+        let export_todotxt = current_line.trim() == todotxt::EXPORT_COMMAND;
+        if x == current_line.len()
+            && (export_todotxt || current_line.trim() == todotxt::IMPORT_COMMAND)
+        {
+            self.convert_selection_todotxt(export_todotxt);
+            return Ok(());
+        }
+
+        if x == current_line.len() && current_line.trim() == cleanup::CLEANUP_COMMAND {
+            self.run_cleanup_command();
+            return Ok(());
+        }
+
+        if x == current_line.len() && current_line.trim() == task::ARCHIVE_COMMAND {
+            self.run_archive_command();
+            return Ok(());
+        }
+
+        let agenda_command = current_line.trim();
+        if x == current_line.len()
+            && (agenda_command == "/agenda" || agenda_command == "/agenda all")
+        {
+            let all = agenda_command == "/agenda all";
+            // Remove the "/agenda" command line itself
+            self.commit(
+                LastActionType::Other,
+                &ActionDiff {
+                    cursor_start_x: self.cursor.x,
+                    cursor_start_y: self.cursor.y,
+                    cursor_end_x: 0,
+                    cursor_end_y: self.cursor.y,
                     start_x: 0,
-                    start_y: self.cursor_y,
+                    start_y: self.cursor.y,
                     end_x: current_line.len(),
-                    end_y: self.cursor_y,
+                    end_y: self.cursor.y,
                     new: vec![],
                     old: vec![current_line.to_string()],
                 },
             );
+            if all {
+                self.open_task_list();
+            } else {
+                self.open_agenda();
+            }
             return Ok(());
         }
 
@@ -537,19 +965,35 @@ impl Editor {
 
         let mut new_line_prefix = indentation.clone();
 
-        if (trimmed_line.starts_with("- [ ] ") || trimmed_line.starts_with("- [x] "))
-            && self.cursor_x >= new_line_prefix.len() + 6
+        if !list_continuation {
+            // Only the indentation carries over to the next line
+        } else if (trimmed_line.starts_with("- [ ] ") || trimmed_line.starts_with("- [x] "))
+            && self.cursor.x >= new_line_prefix.len() + 6
         {
             new_line_prefix.push_str("- [ ] ");
-        } else if trimmed_line.starts_with("- ") && self.cursor_x >= new_line_prefix.len() + 2 {
+        } else if trimmed_line.starts_with("- ") && self.cursor.x >= new_line_prefix.len() + 2 {
             new_line_prefix.push_str("- ");
+        } else if let Some((number, _, end)) = indent::ordered_list_number(&current_line)
+            && self.cursor.x >= end + 2
+        {
+            // Same delimiter, `.` or `)`
+            new_line_prefix.push_str(&format!("{}{} ", number + 1, &current_line[end..end + 1]));
         }
 
         let indentation_len = new_line_prefix.len();
 
         // Check for command execution
         if x == current_line.len() {
-            match command::execute_command(&current_line) {
+            let inbox_file = self.settings.inbox_file.as_deref().map(inbox::resolve_path);
+            let context = command::CommandContext {
+                current_file: self.document.filename.as_deref().map(Path::new),
+                inbox_file: inbox_file.as_deref(),
+                templates: self.settings.templates.as_ref(),
+                date_format: self.settings.date_format.as_deref(),
+                time_format: self.settings.time_format.as_deref(),
+                datetime_format: self.settings.datetime_format.as_deref(),
+            };
+            match command::execute_command(&current_line, &context) {
                 command::CommandResult::Success {
                     new_line_content,
                     status_message,
@@ -558,14 +1002,14 @@ impl Editor {
                         self.commit(
                             LastActionType::Other,
                             &ActionDiff {
-                                cursor_start_x: self.cursor_x,
-                                cursor_start_y: self.cursor_y,
-                                cursor_end_x: self.cursor_x,
-                                cursor_end_y: self.cursor_y,
+                                cursor_start_x: self.cursor.x,
+                                cursor_start_y: self.cursor.y,
+                                cursor_end_x: self.cursor.x,
+                                cursor_end_y: self.cursor.y,
                                 start_x: 0,
-                                start_y: self.cursor_y,
+                                start_y: self.cursor.y,
                                 end_x: current_line.len(),
-                                end_y: self.cursor_y,
+                                end_y: self.cursor.y,
                                 new: vec![],
                                 old: vec![current_line.to_string()],
                             },
@@ -573,14 +1017,14 @@ impl Editor {
                         self.commit(
                             LastActionType::Ammend,
                             &ActionDiff {
-                                cursor_start_x: self.cursor_x,
-                                cursor_start_y: self.cursor_y,
+                                cursor_start_x: self.cursor.x,
+                                cursor_start_y: self.cursor.y,
                                 cursor_end_x: 0,
-                                cursor_end_y: self.cursor_y + 1,
+                                cursor_end_y: self.cursor.y + 1,
                                 start_x: 0,
-                                start_y: self.cursor_y,
+                                start_y: self.cursor.y,
                                 end_x: 0,
-                                end_y: self.cursor_y + 1,
+                                end_y: self.cursor.y + 1,
                                 new: vec![new_content, "".to_string()],
                                 old: vec![],
                             },
@@ -593,6 +1037,18 @@ impl Editor {
                     self.status_message = message.to_string();
                     return Ok(());
                 }
+                command::CommandResult::Shell { command, replace } => {
+                    self.start_shell_command(&command, replace);
+                    return Ok(());
+                }
+                command::CommandResult::Snippet {
+                    snippet,
+                    status_message,
+                } => {
+                    self.replace_line_with_snippet(y, snippet);
+                    self.status_message = status_message;
+                    return Ok(());
+                }
                 command::CommandResult::NoCommand => {
                     // Do nothing, not a command
                 }
@@ -603,53 +1059,55 @@ impl Editor {
         self.commit(
             LastActionType::Newline,
             &ActionDiff {
-                cursor_start_x: self.cursor_x,
-                cursor_start_y: self.cursor_y,
+                cursor_start_x: self.cursor.x,
+                cursor_start_y: self.cursor.y,
                 cursor_end_x: indentation_len,
-                cursor_end_y: self.cursor_y + 1,
-                start_x: self.cursor_x,
-                start_y: self.cursor_y,
+                cursor_end_y: self.cursor.y + 1,
+                start_x: self.cursor.x,
+                start_y: self.cursor.y,
                 end_x: indentation_len,
-                end_y: self.cursor_y + 1,
+                end_y: self.cursor.y + 1,
                 new: vec!["".to_string(), new_line_prefix],
                 old: vec![],
             },
         );
+        self.renumber_ordered_list(self.cursor.y, None);
 
         Ok(())
     }
 
     pub fn kill_line(&mut self) -> Result<()> {
-        let y = self.cursor_y;
-        let x = self.cursor_x;
-        if y >= self.document.lines.len() {
+        let y = self.cursor.y;
+        let x = self.cursor.x;
+        if y >= self.document.lines().len() {
             return Ok(());
         }
 
         let should_clear_kill_buffer = !self.clipboard.last_action_was_kill;
         if should_clear_kill_buffer {
-            self.clipboard.kill_buffer.clear();
+            self.clipboard.push_kill(String::new());
         }
 
-        let current_line_len = self.document.lines[y].len();
+        let current_line_len = self.document.lines()[y].len();
+        let list_start = self.ordered_list_start(y);
 
         if x < current_line_len {
             // Case 1: Cursor is within the line (not at the very end)
             // Kill from cursor to end of line
-            let current_line = self.document.lines[y].clone();
+            let current_line = self.document.lines()[y].clone();
             let killed_text = current_line[x..].to_string();
             self.clipboard.kill_buffer.push_str(&killed_text);
             self.commit(
                 LastActionType::Deletion,
                 &ActionDiff {
-                    cursor_start_x: self.cursor_x,
-                    cursor_start_y: self.cursor_y,
-                    cursor_end_x: self.cursor_x,
-                    cursor_end_y: self.cursor_y,
-                    start_x: self.cursor_x,
-                    start_y: self.cursor_y,
+                    cursor_start_x: self.cursor.x,
+                    cursor_start_y: self.cursor.y,
+                    cursor_end_x: self.cursor.x,
+                    cursor_end_y: self.cursor.y,
+                    start_x: self.cursor.x,
+                    start_y: self.cursor.y,
                     end_x: current_line_len,
-                    end_y: self.cursor_y,
+                    end_y: self.cursor.y,
                     new: vec![],
                     old: vec![killed_text],
                 },
@@ -657,6 +1115,8 @@ impl Editor {
         } else {
             self.delete_forward_char()?;
             self.clipboard.kill_buffer.push('\x0a');
+            // The next line joined this one, maybe taking an item out of a list
+            self.renumber_ordered_list(y, list_start);
         }
 
         self.set_clipboard(&self.clipboard.kill_buffer.clone());
@@ -674,10 +1134,36 @@ impl Editor {
 
     pub fn yank(&mut self) -> Result<()> {
         if let Some(text) = self.clipboard.get_clipboard_text() {
-            self.clipboard.kill_buffer = text;
+            self.clipboard.take_system_text(text);
+        }
+        self.yank_text(self.clipboard.kill_buffer.clone())
+    }
+
+    /// Pastes like `yank`, but first cleans up text copied from web pages:
+    /// bullets become `-` list items, and smart quotes and non-breaking
+    /// spaces become plain ones.
+    pub fn yank_normalized(&mut self) -> Result<()> {
+        if let Some(text) = self.clipboard.get_clipboard_text() {
+            self.clipboard.take_system_text(text);
+        }
+        let normalized = paste::normalize(&self.clipboard.kill_buffer);
+        self.yank_text(normalized)
+    }
+
+    /// Inserts text pasted into the terminal as one undo step, without the
+    /// auto-indent and list continuation that typing it would trigger.
+    pub fn paste(&mut self, text: &str) -> Result<()> {
+        let text = text.replace("\r\n", "\n").replace('\r', "\n");
+        if text.is_empty() {
+            return Ok(());
         }
+        self.undo_redo.begin_group();
+        let result = self.yank_text(text);
+        self.undo_redo.end_group();
+        result
+    }
 
-        let text_to_yank = self.clipboard.kill_buffer.clone();
+    fn yank_text(&mut self, text_to_yank: String) -> Result<()> {
         if text_to_yank.is_empty() {
             self.status_message = "Kill buffer is empty.".to_string();
             return Ok(());
@@ -692,15 +1178,15 @@ impl Editor {
             self.commit(
                 LastActionType::Insertion,
                 &ActionDiff {
-                    cursor_start_x: self.cursor_x,
-                    cursor_start_y: self.cursor_y,
+                    cursor_start_x: self.cursor.x,
+                    cursor_start_y: self.cursor.y,
                     cursor_end_x: last_yank_line_count,
-                    cursor_end_y: self.cursor_y + line_count - 1,
+                    cursor_end_y: self.cursor.y + line_count - 1,
 
-                    start_x: self.cursor_x,
-                    start_y: self.cursor_y,
+                    start_x: self.cursor.x,
+                    start_y: self.cursor.y,
                     end_x: last_yank_line_count,
-                    end_y: self.cursor_y + line_count - 1,
+                    end_y: self.cursor.y + line_count - 1,
 
                     new: yank_lines,
                     old: vec![],
@@ -710,15 +1196,15 @@ impl Editor {
             self.commit(
                 LastActionType::Insertion,
                 &ActionDiff {
-                    cursor_start_x: self.cursor_x,
-                    cursor_start_y: self.cursor_y,
-                    cursor_end_x: self.cursor_x + last_yank_line_count,
-                    cursor_end_y: self.cursor_y,
+                    cursor_start_x: self.cursor.x,
+                    cursor_start_y: self.cursor.y,
+                    cursor_end_x: self.cursor.x + last_yank_line_count,
+                    cursor_end_y: self.cursor.y,
 
-                    start_x: self.cursor_x,
-                    start_y: self.cursor_y,
-                    end_x: self.cursor_x + last_yank_line_count,
-                    end_y: self.cursor_y,
+                    start_x: self.cursor.x,
+                    start_y: self.cursor.y,
+                    end_x: self.cursor.x + last_yank_line_count,
+                    end_y: self.cursor.y,
 
                     new: vec![text_to_yank.to_string()],
                     old: vec![],
@@ -736,17 +1222,18 @@ impl Editor {
     }
 
     pub fn hungry_delete(&mut self) -> Result<()> {
-        let (x, y) = (self.cursor_x, self.cursor_y);
-        if y >= self.document.lines.len() {
+        let (x, y) = (self.cursor.x, self.cursor.y);
+        if y >= self.document.lines().len() {
             return Ok(());
         }
 
-        let current_line = &mut self.document.lines[y];
+        let classes = self.word_classes();
+        let current_line = &self.document.lines()[y];
 
         if x == 0 {
             self.delete_char()?;
         } else {
-            let start_delete_byte = find_word_boundary_left(current_line, x);
+            let start_delete_byte = find_word_boundary_left(current_line, x, &classes);
 
             // Need to clone the line content before modification for the Diff
             let line_content_before_delete = current_line.clone();
@@ -754,15 +1241,15 @@ impl Editor {
             self.commit(
                 LastActionType::Deletion,
                 &ActionDiff {
-                    cursor_start_x: self.cursor_x,
-                    cursor_start_y: self.cursor_y,
+                    cursor_start_x: self.cursor.x,
+                    cursor_start_y: self.cursor.y,
                     cursor_end_x: start_delete_byte,
-                    cursor_end_y: self.cursor_y,
+                    cursor_end_y: self.cursor.y,
 
                     start_x: start_delete_byte,
-                    start_y: self.cursor_y,
-                    end_x: self.cursor_x,
-                    end_y: self.cursor_y,
+                    start_y: self.cursor.y,
+                    end_x: self.cursor.x,
+                    end_y: self.cursor.y,
 
                     new: vec![],
                     old: vec![deleted_text],
@@ -774,140 +1261,185 @@ impl Editor {
 
     pub fn go_to_start_of_line(&mut self) {
         self.clipboard.last_action_was_kill = false;
-        self.cursor_x = 0;
-        self.desired_cursor_x = 0;
+        self.cursor.x = 0;
+        self.cursor.desired_x = 0;
     }
 
     pub fn go_to_end_of_line(&mut self) {
         self.clipboard.last_action_was_kill = false;
-        let y = self.cursor_y;
-        self.cursor_x = self.document.lines[y].len();
-        self.desired_cursor_x = self
+        let y = self.cursor.y;
+        self.cursor.x = self.document.lines()[y].len();
+        self.cursor.desired_x = self
             .scroll
-            .get_display_width_from_bytes(&self.document.lines[y], self.cursor_x);
+            .get_display_width_from_bytes(&self.document.lines()[y], self.cursor.x);
     }
 
     pub fn move_cursor_word_left(&mut self) -> Result<()> {
         self.clipboard.last_action_was_kill = false;
-        if self.cursor_x == 0 {
-            if self.cursor_y > 0 {
-                self.cursor_y -= 1;
-                self.cursor_x = self.document.lines[self.cursor_y].len();
-                self.desired_cursor_x = self.scroll.get_display_width_from_bytes(
-                    &self.document.lines[self.cursor_y],
-                    self.cursor_x,
+        if self.cursor.x == 0 {
+            if self.cursor.y > 0 {
+                self.cursor.y -= 1;
+                self.cursor.x = self.document.lines()[self.cursor.y].len();
+                self.cursor.desired_x = self.scroll.get_display_width_from_bytes(
+                    &self.document.lines()[self.cursor.y],
+                    self.cursor.x,
                 );
             }
             return Ok(());
         }
 
-        let line = &self.document.lines[self.cursor_y];
-        let mut new_cursor_x = self.cursor_x;
+        let classes = self.word_classes();
+        let line = &self.document.lines()[self.cursor.y];
+        let mut new_cursor_x = self.cursor.x;
 
         // 1. Skip whitespace to the left
         let mut boundary = new_cursor_x;
         for (idx, ch) in line[..new_cursor_x].char_indices().rev() {
-            if get_char_type(ch) != CharType::Whitespace {
+            if classes.char_type(ch) != CharType::Whitespace {
                 break;
             }
             boundary = idx;
         }
         new_cursor_x = boundary;
 
-        // 2. We are at the end of a word. Get its type.
-        if new_cursor_x > 0 {
-            let word_type = get_char_type(line[..new_cursor_x].chars().next_back().unwrap());
-            // 3. Skip all chars of this type
-            for (idx, ch) in line[..new_cursor_x].char_indices().rev() {
-                if get_char_type(ch) != word_type {
-                    break;
-                }
-                boundary = idx;
-            }
-            new_cursor_x = boundary;
-        }
+        // 2. We are at the end of a word. Skip to its start.
+        new_cursor_x = classes.word_start(line, new_cursor_x);
 
-        self.cursor_x = new_cursor_x;
-        self.desired_cursor_x = self
+        self.cursor.x = new_cursor_x;
+        self.cursor.desired_x = self
             .scroll
-            .get_display_width_from_bytes(line, self.cursor_x);
+            .get_display_width_from_bytes(line, self.cursor.x);
         Ok(())
     }
 
     pub fn move_cursor_word_right(&mut self) -> Result<()> {
         self.clipboard.last_action_was_kill = false;
-        let current_line = &self.document.lines[self.cursor_y];
+        let classes = self.word_classes();
+        let current_line = &self.document.lines()[self.cursor.y];
         let line_len = current_line.len();
 
-        if self.cursor_x >= line_len {
-            if self.cursor_y < self.document.lines.len() - 1 {
-                self.cursor_y += 1;
-                self.cursor_x = 0;
-                self.desired_cursor_x = 0;
+        if self.cursor.x >= line_len {
+            if self.cursor.y < self.document.lines().len() - 1 {
+                self.cursor.y += 1;
+                self.cursor.x = 0;
+                self.cursor.desired_x = 0;
             }
             return Ok(());
         }
 
-        let mut new_cursor_x = self.cursor_x;
-        let mut iter = current_line[new_cursor_x..].char_indices().peekable();
+        let mut new_cursor_x = self.cursor.x;
 
         // 1. Skip whitespace
-        while let Some((_, ch)) = iter.peek() {
-            if get_char_type(*ch) == CharType::Whitespace {
-                new_cursor_x += ch.len_utf8();
-                iter.next();
-            } else {
+        for ch in current_line[new_cursor_x..].chars() {
+            if classes.char_type(ch) != CharType::Whitespace {
                 break;
             }
+            new_cursor_x += ch.len_utf8();
         }
 
-        // 2. We are at a word. Get its type.
-        if let Some((_, first_word_char)) = iter.peek() {
-            let word_type = get_char_type(*first_word_char);
-            // 3. Skip all chars of this type
-            while let Some((_, ch)) = iter.peek() {
-                if get_char_type(*ch) == word_type {
-                    new_cursor_x += ch.len_utf8();
-                    iter.next();
-                } else {
-                    break;
-                }
-            }
-        }
+        // 2. We are at a word. Skip to its end.
+        new_cursor_x = classes.word_end(current_line, new_cursor_x);
 
-        self.cursor_x = new_cursor_x;
-        self.desired_cursor_x = self
+        self.cursor.x = new_cursor_x;
+        self.cursor.desired_x = self
             .scroll
-            .get_display_width_from_bytes(current_line, self.cursor_x);
+            .get_display_width_from_bytes(current_line, self.cursor.x);
         Ok(())
     }
 
     pub fn save_document(&mut self) -> Result<()> {
         self.clipboard.last_action_was_kill = false;
-        self.document.save(None)?;
-        self.status_message = "File saved successfully.".to_string();
+        self.finish_pending_autosave();
+        self.save_with_hooks()?;
+        self.reset_journal();
+        self.status_message = self.with_file_format_warnings("File saved successfully.");
         debug!("Document saved.");
         Ok(())
     }
 
-    pub fn quit(&mut self) -> Result<()> {
+    /// Writes the buffer to `path` and continues editing it there.
+    pub fn save_document_as(&mut self, path: &str) {
+        let path = path.trim();
+        if path.is_empty() {
+            self.set_message("No file name given.");
+            return;
+        }
+        let resolved = inbox::resolve_path(path);
+        if resolved.is_dir() {
+            self.set_message(&format!("{path} is a directory."));
+            return;
+        }
+        let new_filename = resolved.to_string_lossy().to_string();
         self.clipboard.last_action_was_kill = false;
-        self.document.save(None)?;
+        self.finish_pending_autosave();
+        let old_filename = self.document.filename.clone();
+        self.apply_save_hooks();
+        if let Err(e) = self.document.save_as(&new_filename, None) {
+            self.set_message(&format!("Could not save {new_filename}: {e}"));
+            return;
+        }
+        self.journal.rename(&new_filename);
+        self.reset_journal();
+        self.clear_external_change();
+        if let Some(old_filename) = old_filename
+            && old_filename != new_filename
+            && let Ok(last_modified) = self.document.last_modified()
+        {
+            persistence::move_cursor_position(&old_filename, &new_filename, last_modified);
+        }
+        self.note_recent_file();
+        self.save_bookmarks();
+        self.set_message(&format!("Saved as {new_filename}"));
+        debug!("Document saved as {new_filename}.");
+    }
+
+    /// Replaces the current file with `filename`, saving it first. The scratch
+    /// buffer stays available through the buffer toggle.
+    pub fn open_file(&mut self, filename: &str) -> Result<()> {
+        self.clipboard.last_action_was_kill = false;
+        self.leave_scratch_buffer();
+        self.finish_pending_autosave();
+        if self.is_scratch_buffer() {
+            self.save_scratch();
+        } else {
+            self.save_with_hooks()?;
+            self.reset_journal();
+            self.save_cursor_position();
+            self.note_closed_file();
+        }
+
+        let (document, restored_pos) = open_document(filename.to_string());
+        let next = self.file_buffer(document, restored_pos);
+        let previous = self.swap_buffer(next);
+        if previous.document.filename.is_none() {
+            self.alternate_buffer = Some(previous);
+        }
+        self.note_recent_file();
+        self.set_message(&format!("Opened {filename}"));
+        self.offer_recovery();
+        self.warn_about_file_format();
+        debug!("Opened {filename}.");
+        Ok(())
+    }
+
+    /// Remembers where the cursor is in the current file for the next time it
+    /// is opened.
+    fn save_cursor_position(&mut self) {
         if let Some(file_path) = &self.document.filename {
             if let Ok(last_modified) = self.document.last_modified() {
                 let cursor_pos = CursorPosition {
                     file_path: file_path.clone(),
                     last_modified,
-                    cursor_x: self.cursor_x,
-                    cursor_y: self.cursor_y,
+                    cursor_x: self.cursor.x,
+                    cursor_y: self.cursor.y,
                     scroll_row_offset: self.scroll.row_offset,
                     scroll_col_offset: self.scroll.col_offset,
                 };
                 debug!(
                     "Saving cursor position for {}: ({}, {}), scroll: ({}, {}), last_modified: {:?}",
                     file_path,
-                    self.cursor_x,
-                    self.cursor_y,
+                    self.cursor.x,
+                    self.cursor.y,
                     self.scroll.row_offset,
                     self.scroll.col_offset,
                     last_modified
@@ -923,21 +1455,32 @@ impl Editor {
         } else {
             debug!("No filename for current document. Not saving cursor position.");
         }
+    }
+
+    pub fn quit(&mut self) -> Result<()> {
+        self.clipboard.last_action_was_kill = false;
+        self.leave_scratch_buffer();
+        self.finish_pending_autosave();
+        self.save_scratch();
+        self.save_with_hooks()?;
+        self.reset_journal();
+        self.save_cursor_position();
+        self.note_closed_file();
         self.should_quit = true;
         debug!("Editor quitting.");
         persistence::cleanup_old_cursor_position_files();
+        journal::cleanup_old_journals();
         Ok(())
     }
 
     pub fn cursor_pos(&self) -> (usize, usize) {
-        (self.cursor_x, self.cursor_y)
+        self.cursor.pos()
     }
 
     pub fn set_cursor_pos(&mut self, x: usize, y: usize) {
-        self.cursor_x = x;
-        self.cursor_y = y;
-        self.scroll
-            .clamp_cursor_x(&mut self.cursor_x, &self.cursor_y, &self.document);
+        self.cursor.x = x;
+        self.cursor.y = y;
+        self.scroll.clamp_cursor_x(&mut self.cursor, &self.document);
     }
 
     pub fn set_message(&mut self, message: &str) {
@@ -945,27 +1488,31 @@ impl Editor {
     }
 
     pub fn move_line_up(&mut self) {
-        if self.cursor_y == 0 {
+        if self.cursor.y == 0 {
             self.status_message = "Cannot move line up further.".to_string();
             return;
         }
-        let swapped_line0 = self.document.lines[self.cursor_y - 1].clone();
-        let swapped_line1 = self.document.lines[self.cursor_y].clone();
-        let current_cursor_x = self.cursor_x;
+        let swapped_line0 = self.document.lines()[self.cursor.y - 1].clone();
+        let swapped_line1 = self.document.lines()[self.cursor.y].clone();
+        let current_cursor_x = self.cursor.x;
+        let list_starts = (
+            self.ordered_list_start(self.cursor.y - 1),
+            self.ordered_list_start(self.cursor.y),
+        );
 
         // Delete 2 lines
         self.commit(
             LastActionType::LineMovement,
             &ActionDiff {
-                cursor_start_x: self.cursor_x,
-                cursor_start_y: self.cursor_y,
+                cursor_start_x: self.cursor.x,
+                cursor_start_y: self.cursor.y,
                 cursor_end_x: 0,
-                cursor_end_y: self.cursor_y - 1,
+                cursor_end_y: self.cursor.y - 1,
 
                 start_x: 0,
-                start_y: self.cursor_y - 1,
-                end_x: self.document.lines[self.cursor_y].len(),
-                end_y: self.cursor_y,
+                start_y: self.cursor.y - 1,
+                end_x: self.document.lines()[self.cursor.y].len(),
+                end_y: self.cursor.y,
 
                 new: vec![],
                 old: vec![swapped_line0.clone(), swapped_line1.clone()],
@@ -975,45 +1522,51 @@ impl Editor {
         self.commit(
             LastActionType::Ammend,
             &ActionDiff {
-                cursor_start_x: self.cursor_x,
-                cursor_start_y: self.cursor_y,
+                cursor_start_x: self.cursor.x,
+                cursor_start_y: self.cursor.y,
                 cursor_end_x: current_cursor_x,
-                cursor_end_y: self.cursor_y,
+                cursor_end_y: self.cursor.y,
 
                 start_x: 0,
-                start_y: self.cursor_y,
+                start_y: self.cursor.y,
                 end_x: swapped_line0.len(),
-                end_y: self.cursor_y + 1,
+                end_y: self.cursor.y + 1,
 
                 new: vec![swapped_line1.clone(), swapped_line0.clone()],
                 old: vec![],
             },
         );
+        self.renumber_ordered_list(self.cursor.y + 1, list_starts.0);
+        self.renumber_ordered_list(self.cursor.y, list_starts.1);
         self.clipboard.last_action_was_kill = false;
     }
 
     pub fn move_line_down(&mut self) {
-        if self.cursor_y == self.document.lines.len() - 1 {
+        if self.cursor.y == self.document.lines().len() - 1 {
             self.status_message = "Cannot move line down further.".to_string();
             return;
         }
 
-        let swapped_line0 = self.document.lines[self.cursor_y].clone();
-        let swapped_line1 = self.document.lines[self.cursor_y + 1].clone();
-        let current_cursor_x = self.cursor_x;
+        let swapped_line0 = self.document.lines()[self.cursor.y].clone();
+        let swapped_line1 = self.document.lines()[self.cursor.y + 1].clone();
+        let current_cursor_x = self.cursor.x;
+        let list_starts = (
+            self.ordered_list_start(self.cursor.y),
+            self.ordered_list_start(self.cursor.y + 1),
+        );
         // Delete 2 lines
         self.commit(
             LastActionType::LineMovement,
             &ActionDiff {
-                cursor_start_x: self.cursor_x,
-                cursor_start_y: self.cursor_y,
+                cursor_start_x: self.cursor.x,
+                cursor_start_y: self.cursor.y,
                 cursor_end_x: 0,
-                cursor_end_y: self.cursor_y,
+                cursor_end_y: self.cursor.y,
 
                 start_x: 0,
-                start_y: self.cursor_y,
-                end_x: self.document.lines[self.cursor_y + 1].len(),
-                end_y: self.cursor_y + 1,
+                start_y: self.cursor.y,
+                end_x: self.document.lines()[self.cursor.y + 1].len(),
+                end_y: self.cursor.y + 1,
 
                 new: vec![],
                 old: vec![swapped_line0.clone(), swapped_line1.clone()],
@@ -1023,98 +1576,160 @@ impl Editor {
         self.commit(
             LastActionType::Ammend,
             &ActionDiff {
-                cursor_start_x: self.cursor_x,
-                cursor_start_y: self.cursor_y,
+                cursor_start_x: self.cursor.x,
+                cursor_start_y: self.cursor.y,
                 cursor_end_x: current_cursor_x,
-                cursor_end_y: self.cursor_y + 1,
+                cursor_end_y: self.cursor.y + 1,
 
                 start_x: 0,
-                start_y: self.cursor_y,
+                start_y: self.cursor.y,
                 end_x: swapped_line0.len(),
-                end_y: self.cursor_y + 1,
+                end_y: self.cursor.y + 1,
 
                 new: vec![swapped_line1.clone(), swapped_line0.clone()],
                 old: vec![],
             },
         );
+        // The cursor is on the moved line, now below the other one
+        self.renumber_ordered_list(self.cursor.y, list_starts.0);
+        self.renumber_ordered_list(self.cursor.y - 1, list_starts.1);
         self.clipboard.last_action_was_kill = false;
     }
 
     pub fn scroll_page_down(&mut self) {
         self.scroll.scroll_page_down(
-            &mut self.cursor_y,
-            &mut self.cursor_x,
+            &mut self.cursor,
             &self.document,
             &mut self.clipboard.last_action_was_kill,
         );
+        self.snap_to_fold_header();
     }
 
     pub fn scroll_page_up(&mut self) {
         self.scroll.scroll_page_up(
-            &mut self.cursor_y,
-            &mut self.cursor_x,
+            &mut self.cursor,
             &self.document,
             &mut self.clipboard.last_action_was_kill,
         );
+        self.snap_to_fold_header();
     }
 
     pub fn go_to_start_of_file(&mut self) {
-        self.scroll.go_to_start_of_file(
-            &mut self.cursor_y,
-            &mut self.cursor_x,
-            &mut self.desired_cursor_x,
-            &mut self.clipboard.last_action_was_kill,
-        );
+        self.scroll
+            .go_to_start_of_file(&mut self.cursor, &mut self.clipboard.last_action_was_kill);
     }
 
     pub fn go_to_end_of_file(&mut self) {
         self.scroll.go_to_end_of_file(
-            &mut self.cursor_y,
-            &mut self.cursor_x,
-            &mut self.desired_cursor_x,
+            &mut self.cursor,
             &self.document,
             &mut self.clipboard.last_action_was_kill,
         );
+        if self.is_line_hidden(self.cursor.y) {
+            self.snap_to_fold_header();
+            self.go_to_end_of_line();
+        }
+    }
+
+    /// Moves the cursor to a 1-based line and character column. A line past the
+    /// end of the document goes to the end of the file.
+    pub fn go_to_line_column(&mut self, line: usize, column: Option<usize>) {
+        let y = line.saturating_sub(1); // Convert 1-based to 0-based
+        if y < self.document.lines().len() {
+            self.cursor.y = y;
+
+            let col = column.unwrap_or(1).saturating_sub(1); // 0-based char index
+            let line_content = &self.document.lines()[y];
+
+            let mut byte_offset = 0;
+            let mut current_col = 0;
+            for (i, c) in line_content.char_indices() {
+                if current_col == col {
+                    byte_offset = i;
+                    break;
+                }
+                current_col += 1;
+                byte_offset = i + c.len_utf8();
+            }
+            if current_col < col {
+                // if col is out of bounds
+                byte_offset = line_content.len();
+            }
+
+            self.cursor.x = byte_offset;
+            self.cursor.desired_x = self
+                .scroll
+                .get_display_width_from_bytes(line_content, byte_offset);
+        } else {
+            // If line is out of bounds, just go to the end of the file.
+            let num_lines = self.document.lines().len();
+            if num_lines > 0 {
+                self.cursor.y = num_lines - 1;
+                self.cursor.x = self.document.lines()[num_lines - 1].len();
+            }
+        }
     }
 
     pub fn move_cursor_up(&mut self) {
+        let previous_y = self.cursor.y;
         self.scroll.move_cursor_up(
-            &mut self.cursor_y,
-            &mut self.cursor_x,
-            &mut self.desired_cursor_x,
+            &mut self.cursor,
             &self.document,
             &mut self.clipboard.last_action_was_kill,
         );
+        if self.skip_folded_lines(previous_y, false) {
+            self.cursor.x = self.column_for_desired_x();
+        }
     }
 
     pub fn move_cursor_down(&mut self) {
+        let previous_y = self.cursor.y;
         self.scroll.move_cursor_down(
-            &mut self.cursor_y,
-            &mut self.cursor_x,
-            &mut self.desired_cursor_x,
+            &mut self.cursor,
             &self.document,
             &mut self.clipboard.last_action_was_kill,
         );
+        if self.skip_folded_lines(previous_y, true) {
+            self.cursor.x = if self.cursor.y == previous_y {
+                self.document.lines()[self.cursor.y].len()
+            } else {
+                self.column_for_desired_x()
+            };
+        }
+    }
+
+    /// The byte column on the cursor line closest to `desired_cursor_x`.
+    fn column_for_desired_x(&self) -> usize {
+        self.scroll
+            .get_byte_pos_from_display_width(
+                &self.document.lines()[self.cursor.y],
+                self.cursor.desired_x,
+            )
+            .0
     }
 
     pub fn move_cursor_left(&mut self) {
+        let previous_y = self.cursor.y;
         self.scroll.move_cursor_left(
-            &mut self.cursor_y,
-            &mut self.cursor_x,
-            &mut self.desired_cursor_x,
+            &mut self.cursor,
             &self.document,
             &mut self.clipboard.last_action_was_kill,
         );
+        if self.skip_folded_lines(previous_y, false) {
+            self.go_to_end_of_line();
+        }
     }
 
     pub fn move_cursor_right(&mut self) {
+        let previous_y = self.cursor.y;
         self.scroll.move_cursor_right(
-            &mut self.cursor_y,
-            &mut self.cursor_x,
-            &mut self.desired_cursor_x,
+            &mut self.cursor,
             &self.document,
             &mut self.clipboard.last_action_was_kill,
         );
+        if self.skip_folded_lines(previous_y, true) && self.cursor.y == previous_y {
+            self.go_to_end_of_line();
+        }
     }
 
     pub fn set_alt_pressed(&mut self, is_alt_pressed: bool) {
@@ -1122,7 +1737,7 @@ impl Editor {
     }
 
     pub fn set_marker_action(&mut self) {
-        self.selection.set_marker(self.cursor_pos());
+        self.selection.set_marker(&self.cursor);
         self.status_message = "Marker set.".to_string();
     }
 
@@ -1132,15 +1747,19 @@ impl Editor {
     }
 
     pub fn cut_selection_action(&mut self) -> Result<()> {
-        let cursor_pos = self.cursor_pos();
+        let list_start = self
+            .selection
+            .get_selection_range(&self.cursor)
+            .and_then(|(start, _)| self.ordered_list_start(start.1));
         let (killed_text, action_diff_option) =
-            self.selection.cut_selection(&self.document, cursor_pos)?;
+            self.selection.cut_selection(&self.document, &self.cursor)?;
 
         if let Some(action_diff) = action_diff_option {
             self.commit(LastActionType::Deletion, &action_diff);
+            self.renumber_ordered_list(self.cursor.y, list_start);
         }
 
-        self.clipboard.kill_buffer = killed_text;
+        self.clipboard.push_kill(killed_text);
         self.set_clipboard(&self.clipboard.kill_buffer.clone());
         self.status_message = "Selection cut to clipboard.".to_string();
         debug!(
@@ -1152,8 +1771,10 @@ impl Editor {
     }
 
     pub fn copy_selection_action(&mut self) -> Result<()> {
-        let cursor_pos = self.cursor_pos();
-        self.clipboard.kill_buffer = self.selection.copy_selection(&self.document, cursor_pos)?;
+        let copied = self
+            .selection
+            .copy_selection(&self.document, &self.cursor)?;
+        self.clipboard.push_kill(copied);
         self.set_clipboard(&self.clipboard.kill_buffer.clone());
         self.status_message = "Selection copied to clipboard.".to_string();
         debug!(
@@ -1163,10 +1784,30 @@ impl Editor {
         Ok(())
     }
 
+    /// Copies the selection converted to the format named `format_name`.
+    pub fn copy_selection_as(&mut self, format_name: &str) {
+        let Some(format) = CopyFormat::parse(format_name) else {
+            self.status_message =
+                format!("Unknown format: {format_name} (use {})", CopyFormat::NAMES);
+            return;
+        };
+        let text = match self.selection.copy_selection(&self.document, &self.cursor) {
+            Ok(text) if !text.is_empty() => text,
+            _ => {
+                self.status_message = "No selection to copy.".to_string();
+                return;
+            }
+        };
+        self.clipboard
+            .push_kill(copy_format::convert(&text, format));
+        self.set_clipboard(&self.clipboard.kill_buffer.clone());
+        self.status_message = format!("Selection copied as {}.", format.name());
+    }
+
     pub fn move_to_next_delimiter(&mut self) {
         self.clipboard.last_action_was_kill = false;
-        let current_line_idx = self.cursor_y;
-        let num_lines = self.document.lines.len();
+        let current_line_idx = self.cursor.y;
+        let num_lines = self.document.lines().len();
 
         if num_lines == 0 {
             return; // Nothing to do in an empty document
@@ -1175,12 +1816,12 @@ impl Editor {
         let mut target_line_y: Option<usize> = None;
 
         // Scenario 1: Current line is a delimiter. Move to the line immediately after it.
-        if current_line_idx < num_lines && self.document.lines[current_line_idx] == "---" {
+        if current_line_idx < num_lines && self.document.lines()[current_line_idx] == "---" {
             target_line_y = Some(current_line_idx + 1);
         } else {
             // Scenario 2: Current line is not a delimiter. Search for the next delimiter *after* the current position.
             for i in (current_line_idx + 1)..num_lines {
-                if self.document.lines[i] == "---" {
+                if self.document.lines()[i] == "---" {
                     target_line_y = Some(i + 1);
                     break;
                 }
@@ -1197,18 +1838,18 @@ impl Editor {
                 return; // Do nothing if moving past the last delimiter and no more exist.
             }
 
-            self.cursor_y = new_cursor_y;
-            self.cursor_x = 0;
-            self.desired_cursor_x = 0;
-            self.scroll.row_offset = self.cursor_y; // Scroll to make cursor at top
+            self.cursor.y = new_cursor_y;
+            self.cursor.x = 0;
+            self.cursor.desired_x = 0;
+            self.scroll.row_offset = self.cursor.y; // Scroll to make cursor at top
         }
         // If target_line_y is None, do nothing, which is the desired behavior.
     }
 
     pub fn move_to_previous_delimiter(&mut self) {
         self.clipboard.last_action_was_kill = false;
-        let current_line_idx = self.cursor_y;
-        let num_lines = self.document.lines.len();
+        let current_line_idx = self.cursor.y;
+        let num_lines = self.document.lines().len();
 
         if num_lines == 0 {
             return; // Nothing to do in an empty document
@@ -1224,7 +1865,7 @@ impl Editor {
                 target_line_y = Some(0);
                 break;
             }
-            if self.document.lines[i - 1] == "---" {
+            if self.document.lines()[i - 1] == "---" {
                 target_line_y = Some(i); // 'i' is the line after the delimiter at 'i-1'
                 break;
             }
@@ -1237,10 +1878,10 @@ impl Editor {
         }
 
         if let Some(new_cursor_y) = target_line_y {
-            self.cursor_y = new_cursor_y;
-            self.cursor_x = 0;
-            self.desired_cursor_x = 0;
-            self.scroll.row_offset = self.cursor_y; // Scroll to make cursor at top
+            self.cursor.y = new_cursor_y;
+            self.cursor.x = 0;
+            self.cursor.desired_x = 0;
+            self.scroll.row_offset = self.cursor.y; // Scroll to make cursor at top
         }
     }
 
@@ -1248,6 +1889,11 @@ impl Editor {
         self.undo_redo.set_undo_debounce_threshold(threshold_ms);
     }
 
+    /// Times edits for undo grouping with `clock` instead of the system's.
+    pub fn set_clock(&mut self, clock: impl Clock + 'static) {
+        self.undo_redo.set_clock(clock);
+    }
+
     pub fn set_no_exit_on_save(&mut self, value: bool) {
         self.no_exit_on_save = value;
     }
@@ -1256,6 +1902,23 @@ impl Editor {
         self.keymap = keymap;
     }
 
+    pub fn set_settings(&mut self, settings: EditorSettings) {
+        self.autosave
+            .set_interval_secs(settings.autosave_interval_secs);
+        self.clipboard
+            .set_backend(clipboard::ClipboardBackend::from_setting(
+                settings.clipboard.as_deref(),
+            ));
+        self.set_spellcheck_language(settings.spellcheck.as_deref());
+        self.settings = settings;
+        self.file_settings = file_settings::AppliedFileSettings::default();
+        self.apply_file_settings();
+    }
+
+    pub fn set_monochrome(&mut self, monochrome: bool) {
+        self.monochrome = monochrome;
+    }
+
     // Method to calculate task UI height
     pub fn task_ui_height(&self) -> usize {
         (self.scroll.screen_rows as f32 * 0.4).round() as usize
@@ -1267,99 +1930,27 @@ impl Editor {
     }
 
     pub fn handle_fuzzy_search_input(&mut self, key: pancurses::Input) {
-        if !self.fuzzy_search.handle_input(
-            key,
-            &mut self.cursor_y,
-            &mut self.cursor_x,
-            &self.document,
-        ) {
+        let origin = self.cursor_pos();
+        if !self
+            .fuzzy_search
+            .handle_input(key, &mut self.cursor, &self.document)
+        {
             self.mode = EditorMode::Normal;
             self.fuzzy_search.reset();
+            self.record_jump(origin);
         }
     }
 }
 
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
-enum CharType {
-    Kanji,
-    Hiragana,
-    Katakana,
-    Alphanumeric,
-    Punctuation,
-    Whitespace,
-    Other,
-}
-
-fn get_char_type(ch: char) -> CharType {
-    if ch.is_whitespace() {
-        return CharType::Whitespace;
-    }
-    if ch == '。' || ch == '、' {
-        return CharType::Punctuation;
-    }
-    // ASCII Alphanumeric
-    if ch.is_ascii_alphanumeric() {
-        return CharType::Alphanumeric;
-    }
-    // Hiragana
-    if ('\u{3040}'..='\u{309F}').contains(&ch) {
-        return CharType::Hiragana;
-    }
-    // Katakana
-    if ('\u{30A0}'..='\u{30FF}').contains(&ch) {
-        return CharType::Katakana;
-    }
-    // CJK Unified Ideographs (Kanji)
-    if ('\u{4E00}'..='\u{9FFF}').contains(&ch) {
-        return CharType::Kanji;
-    }
-    // Full-width digits
-    if ('\u{FF10}'..='\u{FF19}').contains(&ch) {
-        return CharType::Alphanumeric;
-    }
-    // Full-width uppercase
-    if ('\u{FF21}'..='\u{FF3A}').contains(&ch) {
-        return CharType::Alphanumeric;
-    }
-    // Full-width lowercase
-    if ('\u{FF41}'..='\u{FF5A}').contains(&ch) {
-        return CharType::Alphanumeric;
-    }
-    CharType::Other
-}
-
-fn find_word_boundary_left(line: &str, current_x: usize) -> usize {
-    if current_x == 0 {
-        return 0;
+/// Moves `x` along with the text after the indentation when `line` becomes
+/// `new_line`.
+fn shift_cursor_x(line: &str, new_line: &str, x: usize) -> usize {
+    if x < line.len() - line.trim_start().len() {
+        return x;
     }
-
-    let mut boundary = current_x;
-
-    // 1. Find char to the left and its type
-    let (start_idx, start_char) = line[..boundary].char_indices().next_back().unwrap();
-    let current_type = get_char_type(start_char);
-    boundary = start_idx;
-
-    // If it's NOT whitespace, it's a word. Find its beginning.
-    if current_type != CharType::Whitespace {
-        for (idx, ch) in line[..start_idx].char_indices().rev() {
-            if get_char_type(ch) != current_type {
-                break;
-            }
-            boundary = idx;
-        }
-    }
-
-    // Now, `boundary` is at the beginning of the word/whitespace block.
-    // Delete any preceding whitespace.
-    let mut final_boundary = boundary;
-    for (idx, ch) in line[..boundary].char_indices().rev() {
-        if get_char_type(ch) == CharType::Whitespace {
-            final_boundary = idx;
-        } else {
-            break;
-        }
-    }
-
-    final_boundary
+    let shifted = (x + new_line.len()).saturating_sub(line.len());
+    (0..=shifted.min(new_line.len()))
+        .rev()
+        .find(|&x| new_line.is_char_boundary(x))
+        .unwrap_or(0)
 }
@@ -0,0 +1,112 @@
+use crate::document::ActionDiff;
+use crate::editor::{Editor, LastActionType};
+
+/// Whether typing `c` ends the word before the cursor.
+fn is_word_boundary(c: char) -> bool {
+    !c.is_alphanumeric() && c != '_'
+}
+
+/// `text` with its first letter upper-cased.
+fn capitalize_first(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+impl Editor {
+    /// Whether abbreviations expand in this buffer: when `abbrevs` are set
+    /// and the file does not turn them off, unless toggled.
+    pub fn is_abbrev_expansion_on(&self) -> bool {
+        self.abbrev_expansion.unwrap_or(
+            self.settings.abbrevs.is_some() && self.document.file_settings.abbrevs != Some(false),
+        )
+    }
+
+    pub fn toggle_abbrev_expansion(&mut self) {
+        let enabled = !self.is_abbrev_expansion_on();
+        self.abbrev_expansion = Some(enabled);
+        self.set_message(if enabled {
+            "Abbreviation expansion on."
+        } else {
+            "Abbreviation expansion off."
+        });
+    }
+
+    /// The abbreviation that ends at the cursor and what it expands to. A
+    /// capitalized abbreviation gives a capitalized expansion, so that `Teh`
+    /// becomes `The`.
+    fn abbrev_at_cursor(&self) -> Option<(usize, String)> {
+        let abbrevs = self.settings.abbrevs.as_ref()?;
+        let line = &self.document.lines()[self.cursor.y];
+        let before = &line[..self.cursor.x];
+        let run_start = before
+            .char_indices()
+            .rev()
+            .take_while(|&(_, ch)| !ch.is_whitespace())
+            .last()?
+            .0;
+        // The longest abbreviation that starts a word
+        before[run_start..].char_indices().find_map(|(idx, _)| {
+            let start = run_start + idx;
+            if idx > 0 && !before[..start].ends_with(is_word_boundary) {
+                return None;
+            }
+            let word = &before[start..];
+            if let Some(expansion) = abbrevs.get(word) {
+                return Some((start, expansion.clone()));
+            }
+            let mut chars = word.chars();
+            let first = chars.next()?;
+            if first.is_uppercase() {
+                let lowered: String = first.to_lowercase().chain(chars).collect();
+                let expansion = abbrevs.get(&lowered)?;
+                return Some((start, capitalize_first(expansion)));
+            }
+            None
+        })
+    }
+
+    /// Replaces the abbreviation before the cursor with its expansion when
+    /// `c`, about to be typed, ends a word. The expansion is an undo step of
+    /// its own, before that of the typing.
+    pub(super) fn expand_abbrev(&mut self, c: char) {
+        if !is_word_boundary(c) || !self.is_abbrev_expansion_on() {
+            return;
+        }
+        let Some((start, expansion)) = self.abbrev_at_cursor() else {
+            return;
+        };
+        let (x, y) = self.cursor_pos();
+        let abbrev = self.document.lines()[y][start..x].to_string();
+        if abbrev == expansion {
+            return;
+        }
+        let lines: Vec<String> = expansion.split('\n').map(str::to_string).collect();
+        let last = lines.last().map_or(0, String::len);
+        let (end_x, end_y) = if lines.len() == 1 {
+            (start + last, y)
+        } else {
+            (last, y + lines.len() - 1)
+        };
+        self.commit(
+            LastActionType::Other,
+            &ActionDiff {
+                cursor_start_x: x,
+                cursor_start_y: y,
+                cursor_end_x: end_x,
+                cursor_end_y: end_y,
+                start_x: start,
+                start_y: y,
+                end_x: x,
+                end_y: y,
+                new: lines,
+                old: vec![abbrev],
+            },
+        );
+        self.cursor.desired_x = self
+            .scroll
+            .get_display_width_from_bytes(&self.document.lines()[end_y], end_x);
+    }
+}
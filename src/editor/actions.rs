@@ -4,7 +4,18 @@ use serde::{Deserialize, Serialize};
 pub enum Action {
     // -- File operations --
     Save,
+    SaveAs,
+    OpenFile,
+    OpenRecent,
+    ReopenLastClosed,
     Quit,
+    // Replaces the buffer with the file on disk
+    ReloadFromDisk,
+    BrowseBackups,
+    // Records the file, cursor and modes for `dmacs --session <name>`
+    SaveSession,
+    // Stages the file and commits it with a message asked for
+    GitCommitFile,
 
     // -- Cursor movement --
     MoveUp,
@@ -17,38 +28,142 @@ pub enum Action {
     MoveWordRight,
     PageUp,
     PageDown,
+    // Cycles the cursor line between the center, top and bottom of the view
+    RecenterView,
+    // Move the view a line, and the cursor only when it would leave it
+    ScrollViewUp,
+    ScrollViewDown,
     GoToStartOfFile,
     GoToEndOfFile,
+    GoToLine,
+    // The `---` page with this 1-based number; 0 asks for it
+    GotoPage(usize),
     MoveToNextDelimiter,
     MoveToPreviousDelimiter,
+    // Walk the jump list of positions jumped away from
+    JumpBack,
+    JumpForward,
+    // Bookmark the current line, or pick a bookmark of any file to go to
+    SetBookmark,
+    GotoBookmark,
+    // Between a `[^1]` footnote reference and its definition
+    FollowFootnote,
+    ShowOutline,
+    ListTags,
+    NextTaggedLine,
+    PreviousTaggedLine,
+    // Counts words, characters, lines and tasks of the selection or buffer
+    ShowStats,
+    ToggleSubwordMode,
+    // Full-width letters and digits typed in this buffer become ASCII
+    ToggleInputTransform,
+    ToggleAbbrevs,
+    ToggleHintBar,
+    // Draws bare URLs off the cursor line as their domain
+    ToggleShortenUrls,
+    ToggleRuler,
+    // Draws tabs as `→` and trailing spaces as highlighted `·`
+    ToggleWhitespace,
+    // Switches to the next built-in or user theme
+    CycleTheme,
+    // Between the runs of lines changed since the last git commit
+    NextHunk,
+    PrevHunk,
 
     // -- Text editing --
     InsertChar(char),
     InsertNewline,
+    // Text from a bracketed paste, inserted verbatim
+    Paste(String),
     DeleteChar,        // Backspace
     DeleteForwardChar, // Delete key
     DeleteWord,        // Alt-Backspace
     KillLine,
     Yank,
+    YankNormalized,
+    YankFromHistory,
     Undo,
     Redo,
+    UndoToSnapshot,
     Indent,
     Outdent,
     ToggleComment,
+    // The selection, or the word at the cursor
+    UpcaseWord,
+    DowncaseWord,
+    CapitalizeWord,
     ToggleCheckbox,
+    CycleTodoKeyword,
+    PromoteHeading,
+    DemoteHeading,
+    PromoteSubtree,
+    DemoteSubtree,
+    // The list item at the cursor with the items nested under it
+    MoveSubtreeUp,
+    MoveSubtreeDown,
+    KillSubtree,
+    // The killed lines above the cursor line, at the level of the list item there
+    YankSubtree,
+    NextMisspelling,
+    // Offers corrections for the misspelled word at the cursor
+    CorrectSpelling,
+    // From the words of the buffer, nearest first
+    CompleteWord,
+    // A reference to the next numbered footnote, defined at the end of the file
+    InsertFootnote,
+    // The date and time in the `datetime_format` setting
+    InsertTimestamp,
+    // The snippet named by the trigger word before the cursor
+    ExpandSnippet,
+    // Puts back the committed lines of the changes under the cursor
+    RevertHunk,
+
+    // -- Folding --
+    ToggleFold,
+    FoldAll,
+    UnfoldAll,
 
     // -- Selection --
     SetMarker,
     ClearMarker,
+    ExpandSelection,
+    ShrinkSelection,
     CutSelection,
     CopySelection,
+    CopyAs,
+    ToggleRectangleMode,
+    YankRectangle,
 
     // -- Search --
     EnterSearchMode,
     EnterFuzzySearchMode,
+    GrepProject,
 
     // -- Task Management --
     EnterTaskSelectionMode,
+    // Moves the checked tasks below the cursor to the `## Archive` section
+    ArchiveDoneTasks,
+    // Orders the tasks of the section under the cursor by their `@due` date
+    SortTasksByDue,
+    StartTaskTimer,
+    StopTaskTimer,
+    StartPomodoro,
+    StopPomodoro,
+    PomodoroStatus,
+    OpenDatePicker,
+    OpenAgenda,
+    OpenTaskList,
+
+    // -- Macros --
+    StartMacroRecording,
+    StopMacroRecording,
+    PlayMacro(usize), // Repeat count
+
+    // -- Repeat counts --
+    // Runs the next action this many times, e.g. `Alt-5 Ctrl-K`
+    DigitArgument(u8),
+    // Repeats the next action 4 times, or the digits typed after it
+    UniversalArgument,
 
     // -- Editor Modes --
     EnterNormalMode, // e.g., for Esc key
@@ -56,5 +171,224 @@ pub enum Action {
     // -- Miscellaneous --
     MoveLineUp,
     MoveLineDown,
+    ToggleScratchBuffer,
+    // Runs any of the actions below by name
+    CommandPalette,
     NoOp,
 }
+
+/// The actions the command palette offers: all but those that carry typed
+/// text or a count, or do nothing on their own.
+pub const PALETTE_ACTIONS: &[Action] = &[
+    Action::Save,
+    Action::SaveAs,
+    Action::OpenFile,
+    Action::OpenRecent,
+    Action::ReopenLastClosed,
+    Action::Quit,
+    Action::ReloadFromDisk,
+    Action::BrowseBackups,
+    Action::SaveSession,
+    Action::GitCommitFile,
+    Action::MoveUp,
+    Action::MoveDown,
+    Action::MoveLeft,
+    Action::MoveRight,
+    Action::GoToStartOfLine,
+    Action::GoToEndOfLine,
+    Action::MoveWordLeft,
+    Action::MoveWordRight,
+    Action::PageUp,
+    Action::PageDown,
+    Action::RecenterView,
+    Action::ScrollViewUp,
+    Action::ScrollViewDown,
+    Action::GoToStartOfFile,
+    Action::GoToEndOfFile,
+    Action::GoToLine,
+    Action::MoveToNextDelimiter,
+    Action::MoveToPreviousDelimiter,
+    Action::JumpBack,
+    Action::JumpForward,
+    Action::SetBookmark,
+    Action::GotoBookmark,
+    Action::FollowFootnote,
+    Action::ShowOutline,
+    Action::ListTags,
+    Action::NextTaggedLine,
+    Action::PreviousTaggedLine,
+    Action::ShowStats,
+    Action::ToggleSubwordMode,
+    Action::ToggleInputTransform,
+    Action::ToggleAbbrevs,
+    Action::ToggleHintBar,
+    Action::ToggleShortenUrls,
+    Action::ToggleRuler,
+    Action::ToggleWhitespace,
+    Action::CycleTheme,
+    Action::NextHunk,
+    Action::PrevHunk,
+    Action::InsertNewline,
+    Action::DeleteChar,
+    Action::DeleteForwardChar,
+    Action::DeleteWord,
+    Action::KillLine,
+    Action::Yank,
+    Action::YankNormalized,
+    Action::YankFromHistory,
+    Action::Undo,
+    Action::Redo,
+    Action::UndoToSnapshot,
+    Action::Indent,
+    Action::Outdent,
+    Action::ToggleComment,
+    Action::UpcaseWord,
+    Action::DowncaseWord,
+    Action::CapitalizeWord,
+    Action::ToggleCheckbox,
+    Action::CycleTodoKeyword,
+    Action::PromoteHeading,
+    Action::DemoteHeading,
+    Action::PromoteSubtree,
+    Action::DemoteSubtree,
+    Action::MoveSubtreeUp,
+    Action::MoveSubtreeDown,
+    Action::KillSubtree,
+    Action::YankSubtree,
+    Action::NextMisspelling,
+    Action::CorrectSpelling,
+    Action::CompleteWord,
+    Action::InsertFootnote,
+    Action::InsertTimestamp,
+    Action::ExpandSnippet,
+    Action::RevertHunk,
+    Action::ToggleFold,
+    Action::FoldAll,
+    Action::UnfoldAll,
+    Action::SetMarker,
+    Action::ClearMarker,
+    Action::ExpandSelection,
+    Action::ShrinkSelection,
+    Action::CutSelection,
+    Action::CopySelection,
+    Action::CopyAs,
+    Action::ToggleRectangleMode,
+    Action::YankRectangle,
+    Action::EnterSearchMode,
+    Action::EnterFuzzySearchMode,
+    Action::GrepProject,
+    Action::EnterTaskSelectionMode,
+    Action::ArchiveDoneTasks,
+    Action::SortTasksByDue,
+    Action::StartTaskTimer,
+    Action::StopTaskTimer,
+    Action::StartPomodoro,
+    Action::StopPomodoro,
+    Action::PomodoroStatus,
+    Action::OpenDatePicker,
+    Action::OpenAgenda,
+    Action::OpenTaskList,
+    Action::StartMacroRecording,
+    Action::StopMacroRecording,
+    Action::UniversalArgument,
+    Action::MoveLineUp,
+    Action::MoveLineDown,
+    Action::ToggleScratchBuffer,
+];
+
+impl Action {
+    pub fn is_cursor_movement(&self) -> bool {
+        matches!(
+            self,
+            Action::MoveUp
+                | Action::MoveDown
+                | Action::MoveLeft
+                | Action::MoveRight
+                | Action::GoToStartOfLine
+                | Action::GoToEndOfLine
+                | Action::MoveWordLeft
+                | Action::MoveWordRight
+                | Action::PageUp
+                | Action::PageDown
+                | Action::ScrollViewUp
+                | Action::ScrollViewDown
+                | Action::GoToStartOfFile
+                | Action::GoToEndOfFile
+                | Action::MoveToNextDelimiter
+                | Action::MoveToPreviousDelimiter
+        )
+    }
+
+    /// Movements far enough that the jump list remembers where they started.
+    pub fn is_jump(&self) -> bool {
+        matches!(
+            self,
+            Action::GoToStartOfFile
+                | Action::GoToEndOfFile
+                | Action::MoveToNextDelimiter
+                | Action::MoveToPreviousDelimiter
+        )
+    }
+
+    /// Actions that change the buffer or the file, or leave it for another one,
+    /// which a read-only buffer refuses.
+    pub fn modifies_buffer(&self) -> bool {
+        matches!(
+            self,
+            Action::Save
+                | Action::SaveAs
+                | Action::OpenFile
+                | Action::OpenRecent
+                | Action::GrepProject
+                | Action::ReopenLastClosed
+                | Action::ReloadFromDisk
+                | Action::CorrectSpelling
+                | Action::CompleteWord
+                | Action::InsertFootnote
+                | Action::InsertTimestamp
+                | Action::ExpandSnippet
+                | Action::RevertHunk
+                | Action::InsertChar(_)
+                | Action::InsertNewline
+                | Action::Paste(_)
+                | Action::DeleteChar
+                | Action::DeleteForwardChar
+                | Action::DeleteWord
+                | Action::KillLine
+                | Action::Yank
+                | Action::YankNormalized
+                | Action::YankFromHistory
+                | Action::Undo
+                | Action::Redo
+                | Action::UndoToSnapshot
+                | Action::Indent
+                | Action::Outdent
+                | Action::ToggleComment
+                | Action::UpcaseWord
+                | Action::DowncaseWord
+                | Action::CapitalizeWord
+                | Action::ToggleCheckbox
+                | Action::CycleTodoKeyword
+                | Action::PromoteHeading
+                | Action::DemoteHeading
+                | Action::PromoteSubtree
+                | Action::DemoteSubtree
+                | Action::MoveSubtreeUp
+                | Action::MoveSubtreeDown
+                | Action::KillSubtree
+                | Action::YankSubtree
+                | Action::CutSelection
+                | Action::YankRectangle
+                | Action::EnterTaskSelectionMode
+                | Action::ArchiveDoneTasks
+                | Action::SortTasksByDue
+                | Action::StopTaskTimer
+                | Action::OpenDatePicker
+                | Action::OpenAgenda
+                | Action::OpenTaskList
+                | Action::MoveLineUp
+                | Action::MoveLineDown
+                | Action::ToggleScratchBuffer
+        )
+    }
+}
@@ -0,0 +1,253 @@
+use crate::agenda::{self, AgendaItem, AgendaScope};
+use crate::editor::{Editor, EditorMode};
+use crate::inbox;
+use crate::screen::Screen;
+use chrono::Local;
+use pancurses::{A_BOLD, A_REVERSE, Input};
+use unicode_width::UnicodeWidthChar;
+
+const MARGIN: usize = 2;
+
+#[derive(Debug, Default)]
+pub struct Agenda {
+    pub items: Vec<AgendaItem>,
+    pub scope: AgendaScope,
+    pub selected: usize,
+    scroll_offset: usize,
+}
+
+impl Agenda {
+    pub fn selected_item(&self) -> Option<&AgendaItem> {
+        self.items.get(self.selected)
+    }
+}
+
+impl Editor {
+    /// Lists the tasks due soon from the buffer and the configured agenda
+    /// directory.
+    pub fn open_agenda(&mut self) {
+        self.open_agenda_in_scope(AgendaScope::ThisWeek);
+    }
+
+    /// Lists every unchecked task from the buffer and the configured agenda
+    /// directory.
+    pub fn open_task_list(&mut self) {
+        self.open_agenda_in_scope(AgendaScope::All);
+    }
+
+    fn open_agenda_in_scope(&mut self, scope: AgendaScope) {
+        let today = Local::now().date_naive();
+        let current_file = self.document.filename.clone();
+        let mut items = agenda::collect_tasks_in_scope(self.document.lines(), None, today, scope);
+        if let Some(dir) = &self.settings.agenda_dir {
+            let exclude = current_file.as_deref().map(std::path::Path::new);
+            items.extend(agenda::collect_dir_in_scope(
+                &inbox::resolve_path(dir),
+                exclude,
+                today,
+                scope,
+            ));
+        }
+        agenda::sort_items(&mut items);
+
+        if items.is_empty() {
+            self.close_agenda();
+            self.set_message(match scope {
+                AgendaScope::ThisWeek => "No tasks due this week.",
+                AgendaScope::All => "No unchecked tasks.",
+            });
+            return;
+        }
+        self.set_message(&format!(
+            "{} tasks. Enter jumps, x checks off, a switches scope, Esc closes.",
+            items.len()
+        ));
+        self.agenda = Agenda {
+            items,
+            scope,
+            ..Agenda::default()
+        };
+        self.mode = EditorMode::Agenda;
+    }
+
+    pub fn handle_agenda_input(&mut self, key: Input) {
+        let count = self.agenda.items.len();
+        match key {
+            Input::KeyUp | Input::Character('\x10') if count > 0 => {
+                self.agenda.selected = (self.agenda.selected + count - 1) % count;
+            }
+            Input::KeyDown | Input::Character('\x0e') if count > 0 => {
+                self.agenda.selected = (self.agenda.selected + 1) % count;
+            }
+            Input::Character('\n') | Input::Character('\r') => self.jump_to_agenda_item(),
+            Input::Character('x') | Input::Character(' ') => self.check_off_agenda_item(),
+            Input::Character('a') => match self.agenda.scope {
+                AgendaScope::ThisWeek => self.open_task_list(),
+                AgendaScope::All => self.open_agenda(),
+            },
+            // Esc, Ctrl-G or q
+            Input::Character('\x1b') | Input::Character('\x07') | Input::Character('q') => {
+                self.close_agenda();
+            }
+            _ => {}
+        }
+    }
+
+    fn jump_to_agenda_item(&mut self) {
+        let Some(item) = self.agenda.selected_item().cloned() else {
+            return;
+        };
+        self.close_agenda();
+        let origin = self.cursor_pos();
+        if let Some(path) = &item.path {
+            let filename = path.to_string_lossy().to_string();
+            if let Err(e) = self.open_file(&filename) {
+                self.set_message(&format!("Could not open {filename}: {e}"));
+                return;
+            }
+        }
+        self.go_to_line_column(item.line + 1, None);
+        // Another file starts a jump list of its own
+        if item.path.is_none() {
+            self.record_jump(origin);
+        }
+    }
+
+    fn check_off_agenda_item(&mut self) {
+        let Some(item) = self.agenda.selected_item().cloned() else {
+            return;
+        };
+        let result = match &item.path {
+            None => self.check_off_buffer_task(&item),
+            Some(_) => agenda::check_off_in_file(&item).map_err(|e| e.to_string()),
+        };
+        match result {
+            Ok(()) => {
+                self.agenda.items.remove(self.agenda.selected);
+                if self.agenda.items.is_empty() {
+                    self.close_agenda();
+                    self.set_message("All tasks in the agenda are done.");
+                    return;
+                }
+                self.agenda.selected = self.agenda.selected.min(self.agenda.items.len() - 1);
+                self.set_message(&format!("Checked off: {}", item.text));
+            }
+            Err(e) => self.set_message(&e),
+        }
+    }
+
+    fn check_off_buffer_task(&mut self, item: &AgendaItem) -> Result<(), String> {
+        let line = self
+            .document
+            .lines()
+            .get(item.line)
+            .filter(|line| line.trim() == item.text)
+            .ok_or("The task has moved since the agenda was opened.")?;
+        // "- [ ] " -> "- [x] "
+        let mark = line.len() - line.trim_start().len() + 3;
+        self.replace_in_line(item.line, mark, mark + 1, "x");
+        Ok(())
+    }
+
+    fn close_agenda(&mut self) {
+        self.agenda = Agenda::default();
+        self.mode = EditorMode::Normal;
+    }
+
+    /// Draws the agenda as a box over the document, one heading per group.
+    pub fn draw_agenda(&mut self, window: &impl Screen) {
+        let screen_rows = window.get_max_y() as usize;
+        let screen_cols = window.get_max_x() as usize;
+        if screen_rows <= MARGIN * 2 + 2 || screen_cols <= MARGIN * 2 + 2 {
+            return;
+        }
+        let (top, left) = (MARGIN, MARGIN);
+        let (bottom, right) = (screen_rows - MARGIN - 1, screen_cols - MARGIN - 1);
+        let inner_width = right - left - 1;
+
+        // Headings and tasks, with the index of the item on each task row
+        let mut rows: Vec<(String, Option<usize>)> = Vec::new();
+        for (i, item) in self.agenda.items.iter().enumerate() {
+            if i == 0 || self.agenda.items[i - 1].group != item.group {
+                rows.push((item.group.title().to_string(), None));
+            }
+            let location = match &item.path {
+                Some(path) => path
+                    .file_name()
+                    .map(|name| format!("  [{}]", name.to_string_lossy()))
+                    .unwrap_or_default(),
+                None => String::new(),
+            };
+            let due = match item.due {
+                Some(due) => due.format("%m-%d").to_string(),
+                None => "     ".to_string(),
+            };
+            rows.push((format!("  {due}  {}{location}", item.text), Some(i)));
+        }
+
+        let visible_rows = bottom - top - 1;
+        let selected_row = rows
+            .iter()
+            .position(|(_, index)| *index == Some(self.agenda.selected))
+            .unwrap_or(0);
+        if selected_row < self.agenda.scroll_offset {
+            // Keep the group heading in view when moving up to its first task
+            self.agenda.scroll_offset = selected_row.saturating_sub(1);
+        } else if selected_row >= self.agenda.scroll_offset + visible_rows {
+            self.agenda.scroll_offset = selected_row + 1 - visible_rows;
+        }
+
+        let blank = " ".repeat(inner_width);
+        for row in top + 1..bottom {
+            window.mvaddch(row as i32, left as i32, pancurses::ACS_VLINE());
+            window.mvaddstr(row as i32, (left + 1) as i32, &blank);
+            window.mvaddch(row as i32, right as i32, pancurses::ACS_VLINE());
+        }
+        for col in left + 1..right {
+            window.mvaddch(top as i32, col as i32, pancurses::ACS_HLINE());
+            window.mvaddch(bottom as i32, col as i32, pancurses::ACS_HLINE());
+        }
+        window.mvaddch(top as i32, left as i32, pancurses::ACS_ULCORNER());
+        window.mvaddch(top as i32, right as i32, pancurses::ACS_URCORNER());
+        window.mvaddch(bottom as i32, left as i32, pancurses::ACS_LLCORNER());
+        window.mvaddch(bottom as i32, right as i32, pancurses::ACS_LRCORNER());
+        window.attron(A_BOLD);
+        let title = match self.agenda.scope {
+            AgendaScope::ThisWeek => " Agenda ",
+            AgendaScope::All => " All tasks ",
+        };
+        window.mvaddstr(top as i32, (left + 2) as i32, title);
+        window.attroff(A_BOLD);
+
+        for (offset, (text, index)) in rows
+            .iter()
+            .skip(self.agenda.scroll_offset)
+            .take(visible_rows)
+            .enumerate()
+        {
+            let attr = match index {
+                None => A_BOLD,
+                Some(i) if *i == self.agenda.selected => A_REVERSE,
+                Some(_) => 0,
+            };
+            window.attron(attr);
+            window.mvaddstr(
+                (top + 1 + offset) as i32,
+                (left + 1) as i32,
+                truncate_to_width(text, inner_width),
+            );
+            window.attroff(attr);
+        }
+    }
+}
+
+pub(super) fn truncate_to_width(text: &str, max_width: usize) -> &str {
+    let mut width = 0;
+    for (i, ch) in text.char_indices() {
+        width += ch.width().unwrap_or(0);
+        if width > max_width {
+            return &text[..i];
+        }
+    }
+    text
+}
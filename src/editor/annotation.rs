@@ -0,0 +1,99 @@
+use crate::document::ActionDiff;
+use crate::editor::{Editor, LastActionType};
+
+/// Finds the first `@name(value)` annotation in `line`, returning its byte
+/// range and the value inside the parentheses.
+pub fn find_annotation<'a>(line: &'a str, name: &str) -> Option<(usize, usize, &'a str)> {
+    let opening = format!("@{name}(");
+    let start = line.find(&opening)?;
+    let value_start = start + opening.len();
+    let close = line[value_start..].find(')')? + value_start;
+    Some((start, close + 1, &line[value_start..close]))
+}
+
+impl Editor {
+    /// Sets `@name(value)` on line `y`, replacing an existing annotation of the
+    /// same name or appending a new one.
+    pub fn set_line_annotation(&mut self, y: usize, name: &str, value: &str) {
+        let line = &self.document.lines()[y];
+        let annotation = format!("@{name}({value})");
+        match find_annotation(line, name) {
+            Some((start, end, _)) => self.replace_in_line(y, start, end, &annotation),
+            None => {
+                let len = line.len();
+                let separator = if line.is_empty() || line.ends_with(' ') {
+                    ""
+                } else {
+                    " "
+                };
+                self.replace_in_line(y, len, len, &format!("{separator}{annotation}"));
+            }
+        }
+    }
+
+    /// Replaces the bytes `start_x..end_x` of line `y` with `text` as a single
+    /// undo step, keeping the cursor on the text it was on.
+    pub(super) fn replace_in_line(&mut self, y: usize, start_x: usize, end_x: usize, text: &str) {
+        self.replace_in_line_as(LastActionType::Other, y, start_x, end_x, text);
+    }
+
+    /// Like `replace_in_line`, recorded as `action_type` so that an `Ammend`
+    /// joins the previous undo step.
+    pub(super) fn replace_in_line_as(
+        &mut self,
+        action_type: LastActionType,
+        y: usize,
+        start_x: usize,
+        end_x: usize,
+        text: &str,
+    ) {
+        let (cursor_x, cursor_y) = self.cursor_pos();
+        // Keep the cursor on the same character when the edit is before it
+        let end_cursor_x = if cursor_y == y && cursor_x >= end_x {
+            cursor_x - (end_x - start_x) + text.len()
+        } else if cursor_y == y && cursor_x > start_x {
+            start_x
+        } else {
+            cursor_x
+        };
+        let mut action_type = action_type;
+        if start_x < end_x {
+            let old = self.document.lines()[y][start_x..end_x].to_string();
+            self.commit(
+                action_type,
+                &ActionDiff {
+                    cursor_start_x: cursor_x,
+                    cursor_start_y: cursor_y,
+                    cursor_end_x: if cursor_y == y {
+                        cursor_x.min(start_x)
+                    } else {
+                        cursor_x
+                    },
+                    cursor_end_y: cursor_y,
+                    start_x,
+                    start_y: y,
+                    end_x,
+                    end_y: y,
+                    new: vec![],
+                    old: vec![old],
+                },
+            );
+            action_type = LastActionType::Ammend;
+        }
+        self.commit(
+            action_type,
+            &ActionDiff {
+                cursor_start_x: cursor_x,
+                cursor_start_y: cursor_y,
+                cursor_end_x: end_cursor_x,
+                cursor_end_y: cursor_y,
+                start_x,
+                start_y: y,
+                end_x: start_x + text.len(),
+                end_y: y,
+                new: vec![text.to_string()],
+                old: vec![],
+            },
+        );
+    }
+}
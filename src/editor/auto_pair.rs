@@ -0,0 +1,268 @@
+use crate::document::{ActionDiff, LineBuffer};
+use crate::editor::{Editor, LastActionType};
+use crate::error::Result;
+
+pub const DEFAULT_PAIRS: [(char, char); 6] = [
+    ('(', ')'),
+    ('[', ']'),
+    ('{', '}'),
+    ('"', '"'),
+    ('\'', '\''),
+    ('「', '」'),
+];
+
+/// Markdown emphasis markers that wrap the selection when typed.
+pub const EMPHASIS_MARKERS: [char; 4] = ['*', '_', '`', '~'];
+
+// Brackets that the matching highlight pairs up; quotes can't be matched reliably
+const BRACKETS: [(char, char); 4] = [('(', ')'), ('[', ']'), ('{', '}'), ('「', '」')];
+// Don't search further than this for a matching bracket on every redraw
+const MAX_MATCH_LINES: usize = 1000;
+
+/// Finds the bracket matching the one at `(x, y)`, or the one just before it,
+/// returning the positions of both.
+pub fn find_matching_bracket(
+    lines: &LineBuffer,
+    x: usize,
+    y: usize,
+) -> Option<((usize, usize), (usize, usize))> {
+    let line = lines.get(y)?;
+    let at_cursor = line[x.min(line.len())..].chars().next().map(|c| (x, c));
+    let before_cursor = line[..x.min(line.len())].char_indices().next_back();
+    [at_cursor, before_cursor]
+        .into_iter()
+        .flatten()
+        .find_map(|(bx, c)| {
+            BRACKETS.iter().find_map(|&(open, close)| {
+                let found = if c == open {
+                    scan_forward(lines, bx + c.len_utf8(), y, open, close)
+                } else if c == close {
+                    scan_backward(lines, bx, y, open, close)
+                } else {
+                    None
+                };
+                found.map(|found| ((bx, y), found))
+            })
+        })
+}
+
+fn scan_forward(
+    lines: &LineBuffer,
+    x: usize,
+    y: usize,
+    open: char,
+    close: char,
+) -> Option<(usize, usize)> {
+    let mut depth = 0;
+    for (line_y, line) in lines.iter().enumerate().skip(y).take(MAX_MATCH_LINES) {
+        let start = if line_y == y { x } else { 0 };
+        for (cx, c) in line[start..].char_indices() {
+            if c == open {
+                depth += 1;
+            } else if c == close {
+                if depth == 0 {
+                    return Some((start + cx, line_y));
+                }
+                depth -= 1;
+            }
+        }
+    }
+    None
+}
+
+fn scan_backward(
+    lines: &LineBuffer,
+    x: usize,
+    y: usize,
+    open: char,
+    close: char,
+) -> Option<(usize, usize)> {
+    let mut depth = 0;
+    for line_y in (y.saturating_sub(MAX_MATCH_LINES)..=y).rev() {
+        let line = &lines[line_y];
+        let end = if line_y == y { x } else { line.len() };
+        for (cx, c) in line[..end].char_indices().rev() {
+            if c == close {
+                depth += 1;
+            } else if c == open {
+                if depth == 0 {
+                    return Some((cx, line_y));
+                }
+                depth -= 1;
+            }
+        }
+    }
+    None
+}
+
+impl Editor {
+    pub fn auto_pairs(&self) -> Vec<(char, char)> {
+        match &self.settings.auto_pairs {
+            Some(pairs) => pairs
+                .iter()
+                .filter_map(|pair| {
+                    let mut chars = pair.chars();
+                    match (chars.next(), chars.next(), chars.next()) {
+                        (Some(open), Some(close), None) => Some((open, close)),
+                        _ => {
+                            log::warn!("auto_pairs entries are two characters: {pair}");
+                            None
+                        }
+                    }
+                })
+                .collect(),
+            None => DEFAULT_PAIRS.to_vec(),
+        }
+    }
+
+    /// Types `c`, closing an opening bracket or quote, or stepping over the
+    /// closing one that is already there, when auto-pairing is enabled.
+    pub fn insert_char(&mut self, c: char) -> Result<()> {
+        if EMPHASIS_MARKERS.contains(&c) && self.wrap_selection(c) {
+            return Ok(());
+        }
+        if !self.settings.auto_pair {
+            return self.insert_text(&c.to_string());
+        }
+        let pairs = self.auto_pairs();
+        let line = &self.document.lines()[self.cursor.y];
+        let next = line[self.cursor.x..].chars().next();
+        let previous = line[..self.cursor.x].chars().next_back();
+
+        if next == Some(c) && pairs.iter().any(|&(_, close)| close == c) {
+            self.cursor.x += c.len_utf8();
+            self.cursor.desired_x = self
+                .scroll
+                .get_display_width_from_bytes(&self.document.lines()[self.cursor.y], self.cursor.x);
+            return Ok(());
+        }
+
+        let Some(&(_, close)) = pairs.iter().find(|&&(open, _)| open == c) else {
+            return self.insert_text(&c.to_string());
+        };
+        let next_allows_pair = next.is_none_or(|next| {
+            next.is_whitespace() || pairs.iter().any(|&(_, close)| close == next)
+        });
+        // An apostrophe in "don't" is not an opening quote
+        let is_quote = c == close;
+        let previous_allows_pair = !is_quote || previous.is_none_or(|p| !p.is_alphanumeric());
+        if !next_allows_pair || !previous_allows_pair {
+            return self.insert_text(&c.to_string());
+        }
+
+        let pair = format!("{c}{close}");
+        self.commit(
+            LastActionType::Insertion,
+            &ActionDiff {
+                cursor_start_x: self.cursor.x,
+                cursor_start_y: self.cursor.y,
+                cursor_end_x: self.cursor.x + c.len_utf8(),
+                cursor_end_y: self.cursor.y,
+                start_x: self.cursor.x,
+                start_y: self.cursor.y,
+                end_x: self.cursor.x + pair.len(),
+                end_y: self.cursor.y,
+                new: vec![pair],
+                old: vec![],
+            },
+        );
+        Ok(())
+    }
+
+    /// Puts `marker` on both sides of the selection as one undo step. The
+    /// text stays selected, so typing `*` twice makes it `**bold**`. Returns
+    /// whether there was a selection to wrap.
+    fn wrap_selection(&mut self, marker: char) -> bool {
+        let cursor = self.cursor_pos();
+        let Some((start, end)) = self.selection.get_selection_range(&self.cursor) else {
+            return false;
+        };
+        if start == end {
+            return false;
+        }
+        let len = marker.len_utf8();
+        // Both ends of the selection move with the opening marker, and stay
+        // before the closing one
+        let shift = |(x, y): (usize, usize)| {
+            if y == start.1 && x >= start.0 {
+                (x + len, y)
+            } else {
+                (x, y)
+            }
+        };
+        let (cursor_end_x, cursor_end_y) = shift(cursor);
+        let marker_pos = self.selection.marker_pos.map(shift);
+
+        self.undo_redo.begin_group();
+        self.commit(
+            LastActionType::Other,
+            &ActionDiff {
+                cursor_start_x: cursor.0,
+                cursor_start_y: cursor.1,
+                cursor_end_x: cursor.0,
+                cursor_end_y: cursor.1,
+                start_x: end.0,
+                start_y: end.1,
+                end_x: end.0 + len,
+                end_y: end.1,
+                new: vec![marker.to_string()],
+                old: vec![],
+            },
+        );
+        self.commit(
+            LastActionType::Other,
+            &ActionDiff {
+                cursor_start_x: cursor.0,
+                cursor_start_y: cursor.1,
+                cursor_end_x,
+                cursor_end_y,
+                start_x: start.0,
+                start_y: start.1,
+                end_x: start.0 + len,
+                end_y: start.1,
+                new: vec![marker.to_string()],
+                old: vec![],
+            },
+        );
+        self.undo_redo.end_group();
+        self.selection.marker_pos = marker_pos;
+        true
+    }
+
+    /// Deletes both halves of an empty pair when backspacing between them.
+    /// Returns whether it did.
+    pub(super) fn delete_empty_pair(&mut self) -> bool {
+        if !self.settings.auto_pair || self.cursor.x == 0 {
+            return false;
+        }
+        let line = &self.document.lines()[self.cursor.y];
+        let (Some(previous), Some(next)) = (
+            line[..self.cursor.x].chars().next_back(),
+            line[self.cursor.x..].chars().next(),
+        ) else {
+            return false;
+        };
+        if !self.auto_pairs().contains(&(previous, next)) {
+            return false;
+        }
+
+        let start_x = self.cursor.x - previous.len_utf8();
+        let end_x = self.cursor.x + next.len_utf8();
+        self.commit(
+            LastActionType::Deletion,
+            &ActionDiff {
+                cursor_start_x: self.cursor.x,
+                cursor_start_y: self.cursor.y,
+                cursor_end_x: start_x,
+                cursor_end_y: self.cursor.y,
+                start_x,
+                start_y: self.cursor.y,
+                end_x,
+                end_y: self.cursor.y,
+                new: vec![],
+                old: vec![format!("{previous}{next}")],
+            },
+        );
+        true
+    }
+}
@@ -0,0 +1,188 @@
+use crate::document::{Document, SaveOptions};
+use crate::editor::Editor;
+use crate::error::Result;
+use crate::scratch;
+use chrono::{DateTime, Local};
+use log::debug;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long an unnamed buffer must sit untouched before it is written to the
+/// scratch file.
+pub const SCRATCH_IDLE_DELAY: Duration = Duration::from_secs(2);
+
+pub struct Autosave {
+    pub interval: Option<Duration>,
+    pub last_run: Instant,
+    pub last_saved_at: Option<DateTime<Local>>,
+    pending: Option<Receiver<Result<String>>>,
+    pub scratch_path: Option<PathBuf>,
+    pub scratch_dirty: bool,
+    pub last_edit: Instant,
+}
+
+impl Default for Autosave {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Autosave {
+    pub fn new() -> Self {
+        Self {
+            interval: None,
+            last_run: Instant::now(),
+            last_saved_at: None,
+            pending: None,
+            scratch_path: None,
+            scratch_dirty: false,
+            last_edit: Instant::now(),
+        }
+    }
+
+    pub fn set_interval_secs(&mut self, secs: Option<u64>) {
+        self.interval = secs.filter(|&s| s > 0).map(Duration::from_secs);
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    pub fn note_edit(&mut self) {
+        self.scratch_dirty = true;
+        self.last_edit = Instant::now();
+    }
+
+    fn is_due(&self, now: Instant) -> bool {
+        match self.interval {
+            Some(interval) => {
+                self.pending.is_none() && now.duration_since(self.last_run) >= interval
+            }
+            None => false,
+        }
+    }
+
+    /// Writes a snapshot of the document on a background thread. The receiver
+    /// yields the content that was written so the editor can mark it as saved.
+    fn spawn(&mut self, snapshot: Document) {
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let mut snapshot = snapshot;
+            // Save hooks would rewrite the text being typed; they run on explicit saves
+            snapshot.save_options = SaveOptions::default();
+            let result = snapshot
+                .save(None)
+                .map(|_| snapshot.original_content().unwrap_or_default().to_string());
+            let _ = tx.send(result);
+        });
+        self.pending = Some(rx);
+    }
+}
+
+impl Editor {
+    /// Called from the main loop; starts an autosave when the interval has
+    /// elapsed and collects the result of a finished one.
+    pub fn autosave_tick(&mut self) {
+        if let Some(rx) = &self.autosave.pending {
+            match rx.try_recv() {
+                Ok(result) => {
+                    self.autosave.pending = None;
+                    self.apply_autosave_result(result);
+                }
+                Err(TryRecvError::Empty) => {}
+                Err(TryRecvError::Disconnected) => self.autosave.pending = None,
+            }
+        }
+
+        let now = Instant::now();
+        if self.autosave.scratch_dirty
+            && now.duration_since(self.autosave.last_edit) >= SCRATCH_IDLE_DELAY
+        {
+            self.save_scratch();
+        }
+
+        if !self.autosave.is_due(now) {
+            return;
+        }
+        self.autosave.last_run = now;
+        if self.document.filename.is_some() && self.document.is_dirty() {
+            // Never write over changes made by another program
+            self.poll_disk();
+            if self.external_change.is_changed() {
+                return;
+            }
+            debug!("Starting autosave.");
+            self.document.backup_retention = self.settings.backup_retention();
+            self.autosave.spawn(self.document.clone());
+        }
+    }
+
+    /// Blocks until an in-flight autosave has finished, so that an explicit
+    /// save is never overwritten by an older snapshot.
+    pub fn finish_pending_autosave(&mut self) {
+        if let Some(rx) = self.autosave.pending.take()
+            && let Ok(result) = rx.recv()
+        {
+            self.apply_autosave_result(result);
+        }
+    }
+
+    pub fn enable_scratch_autosave(&mut self) {
+        self.enable_scratch_autosave_with_base_dir(None);
+    }
+
+    /// Keeps an unnamed buffer in the scratch file, restoring what was left
+    /// there by the previous session.
+    pub fn enable_scratch_autosave_with_base_dir(&mut self, base_dir: Option<PathBuf>) {
+        let path = match scratch::scratch_path(base_dir) {
+            Ok(path) => path,
+            Err(e) => {
+                self.status_message = format!("Scratch file unavailable: {e}");
+                return;
+            }
+        };
+        if self.document.filename.is_none()
+            && let Some(lines) = scratch::load(&path)
+        {
+            self.document.set_lines(lines);
+            self.set_cursor_pos(0, 0);
+            self.set_message("Restored scratch buffer.");
+        }
+        self.autosave.scratch_path = Some(path);
+    }
+
+    /// Writes an unnamed buffer to the scratch file if it changed since the
+    /// last write.
+    pub fn save_scratch(&mut self) {
+        if !self.autosave.scratch_dirty || self.document.filename.is_some() {
+            return;
+        }
+        let Some(path) = &self.autosave.scratch_path else {
+            return;
+        };
+        match scratch::save(path, self.document.lines()) {
+            Ok(()) => {
+                self.autosave.scratch_dirty = false;
+                self.autosave.last_saved_at = Some(Local::now());
+                debug!("Scratch buffer saved.");
+            }
+            Err(e) => self.status_message = format!("Scratch autosave failed: {e}"),
+        }
+    }
+
+    fn apply_autosave_result(&mut self, result: Result<String>) {
+        match result {
+            Ok(content) => {
+                self.document.mark_saved(content);
+                self.reset_journal();
+                self.autosave.last_saved_at = Some(Local::now());
+                debug!("Autosave finished.");
+            }
+            Err(e) => {
+                self.status_message = format!("Autosave failed: {e}");
+            }
+        }
+    }
+}
@@ -0,0 +1,274 @@
+use crate::backup::{Backup, BackupManager};
+use crate::diff::{DiffLine, diff_lines};
+use crate::editor::agenda::truncate_to_width;
+use crate::editor::{Editor, EditorMode};
+use crate::screen::Screen;
+use pancurses::{A_BOLD, A_DIM, A_REVERSE, Input};
+use std::path::PathBuf;
+
+const MARGIN: usize = 2;
+// Rows of the backup list at most; the rest of the panel previews the selected one
+const MAX_LIST_ROWS: usize = 6;
+// Unchanged lines shown above the first change when a diff is opened
+const DIFF_CONTEXT: usize = 2;
+
+/// The backups of the current file, newest first, with a preview of the
+/// selected one or of its differences from the buffer.
+#[derive(Debug, Default)]
+pub struct BackupBrowser {
+    pub backups: Vec<Backup>,
+    pub selected: usize,
+    pub show_diff: bool,
+    pub preview_offset: usize,
+    list_offset: usize,
+    // Lines of the backup at the index, read when it is first previewed
+    loaded: Option<(usize, Vec<String>)>,
+}
+
+impl BackupBrowser {
+    fn selected_lines(&mut self) -> &[String] {
+        if self.loaded.as_ref().map(|(index, _)| *index) != Some(self.selected) {
+            let lines = self.backups[self.selected]
+                .read()
+                .map(|content| content.lines().map(String::from).collect())
+                .unwrap_or_else(|e| vec![format!("Could not read the backup: {e}")]);
+            self.loaded = Some((self.selected, lines));
+        }
+        self.loaded
+            .as_ref()
+            .map(|(_, lines)| lines.as_slice())
+            .unwrap_or_default()
+    }
+
+    fn select(&mut self, index: usize) {
+        self.selected = index;
+        self.preview_offset = 0;
+    }
+}
+
+impl Editor {
+    pub fn browse_backups(&mut self) {
+        self.browse_backups_with_base_dir(None);
+    }
+
+    /// Opens the backup browser for the current file, with backups kept under
+    /// `base_dir` instead of the home directory.
+    pub fn browse_backups_with_base_dir(&mut self, base_dir: Option<PathBuf>) {
+        let Some(filename) = self.document.filename.clone() else {
+            self.set_message("The buffer has no file to have backups.");
+            return;
+        };
+        let backups = match BackupManager::new_with_base_dir(base_dir)
+            .and_then(|manager| manager.list_backups(&filename))
+        {
+            Ok(backups) => backups,
+            Err(e) => {
+                self.set_message(&format!("Could not list backups: {e}"));
+                return;
+            }
+        };
+        if backups.is_empty() {
+            self.set_message("No backups of this file yet.");
+            return;
+        }
+        self.backup_browser = BackupBrowser {
+            backups,
+            ..BackupBrowser::default()
+        };
+        self.mode = EditorMode::BackupBrowser;
+    }
+
+    /// The lines shown under the list: the selected backup, or with
+    /// `show_diff` what changed from it to the buffer, marked with `-` and `+`.
+    pub fn backup_preview(&mut self) -> Vec<String> {
+        let show_diff = self.backup_browser.show_diff;
+        let backup_lines = self.backup_browser.selected_lines();
+        if !show_diff {
+            return backup_lines.to_vec();
+        }
+        diff_lines(backup_lines, self.document.lines())
+            .into_iter()
+            .map(|line| match line {
+                DiffLine::Same(text) => format!("  {text}"),
+                DiffLine::Removed(text) => format!("- {text}"),
+                DiffLine::Added(text) => format!("+ {text}"),
+            })
+            .collect()
+    }
+
+    pub fn handle_backup_browser_input(&mut self, key: Input) {
+        let browser = &mut self.backup_browser;
+        let count = browser.backups.len();
+        match key {
+            Input::KeyUp | Input::Character('\x10') => {
+                browser.select((browser.selected + count - 1) % count);
+            }
+            Input::KeyDown | Input::Character('\x0e') => {
+                browser.select((browser.selected + 1) % count);
+            }
+            Input::KeyPPage | Input::Character('b') => {
+                browser.preview_offset = browser
+                    .preview_offset
+                    .saturating_sub(self.scroll.screen_rows / 2);
+            }
+            Input::KeyNPage | Input::Character(' ') => {
+                browser.preview_offset += self.scroll.screen_rows / 2;
+            }
+            Input::Character('d') => {
+                browser.show_diff = !browser.show_diff;
+                browser.preview_offset = 0;
+                if browser.show_diff {
+                    // Start at the first change
+                    let first_change = self
+                        .backup_preview()
+                        .iter()
+                        .position(|line| !line.starts_with("  "))
+                        .unwrap_or(0);
+                    self.backup_browser.preview_offset = first_change.saturating_sub(DIFF_CONTEXT);
+                }
+            }
+            Input::Character('\n') | Input::Character('\r') | Input::Character('r') => {
+                self.restore_selected_backup();
+            }
+            // Esc, Ctrl-G or q
+            Input::Character('\x1b') | Input::Character('\x07') | Input::Character('q') => {
+                self.close_backup_browser();
+            }
+            _ => {}
+        }
+    }
+
+    /// Replaces the buffer with the selected backup as one undo step. The
+    /// file itself only changes when the buffer is saved.
+    fn restore_selected_backup(&mut self) {
+        let mut lines = self.backup_browser.selected_lines().to_vec();
+        let created = self.backup_browser.backups[self.backup_browser.selected].created;
+        if lines.is_empty() {
+            lines.push(String::new());
+        }
+        self.close_backup_browser();
+        let cursor_y = self.cursor.y.min(lines.len() - 1);
+        let cursor_x = self.cursor.x.min(lines[cursor_y].len());
+        self.replace_lines(lines, (cursor_x, cursor_y));
+        self.set_cursor_pos(self.cursor.x, self.cursor.y);
+        self.set_message(&format!(
+            "Restored the backup of {}. Save to keep it, undo to go back.",
+            created.format("%Y-%m-%d %H:%M:%S")
+        ));
+    }
+
+    fn close_backup_browser(&mut self) {
+        self.backup_browser = BackupBrowser::default();
+        self.mode = EditorMode::Normal;
+    }
+
+    pub fn draw_backup_browser(&mut self, window: &impl Screen) {
+        let screen_rows = window.get_max_y() as usize;
+        let screen_cols = window.get_max_x() as usize;
+        if screen_rows <= MARGIN * 2 + 4 || screen_cols <= MARGIN * 2 + 2 {
+            return;
+        }
+        let (top, left) = (MARGIN, MARGIN);
+        let (bottom, right) = (screen_rows - MARGIN - 1, screen_cols - MARGIN - 1);
+        let inner_width = right - left - 1;
+        let inner_rows = bottom - top - 1;
+        let list_rows = self
+            .backup_browser
+            .backups
+            .len()
+            .min(MAX_LIST_ROWS)
+            .min(inner_rows / 2);
+        let separator = top + 1 + list_rows;
+        let preview_rows = bottom - separator - 1;
+
+        let blank = " ".repeat(inner_width);
+        for row in top + 1..bottom {
+            window.mvaddch(row as i32, left as i32, pancurses::ACS_VLINE());
+            window.mvaddstr(row as i32, (left + 1) as i32, &blank);
+            window.mvaddch(row as i32, right as i32, pancurses::ACS_VLINE());
+        }
+        for col in left + 1..right {
+            window.mvaddch(top as i32, col as i32, pancurses::ACS_HLINE());
+            window.mvaddch(separator as i32, col as i32, pancurses::ACS_HLINE());
+            window.mvaddch(bottom as i32, col as i32, pancurses::ACS_HLINE());
+        }
+        window.mvaddch(top as i32, left as i32, pancurses::ACS_ULCORNER());
+        window.mvaddch(top as i32, right as i32, pancurses::ACS_URCORNER());
+        window.mvaddch(separator as i32, left as i32, pancurses::ACS_LTEE());
+        window.mvaddch(separator as i32, right as i32, pancurses::ACS_RTEE());
+        window.mvaddch(bottom as i32, left as i32, pancurses::ACS_LLCORNER());
+        window.mvaddch(bottom as i32, right as i32, pancurses::ACS_LRCORNER());
+        window.attron(A_BOLD);
+        window.mvaddstr(
+            top as i32,
+            (left + 2) as i32,
+            truncate_to_width(" Backups ", inner_width.saturating_sub(2)),
+        );
+        window.attroff(A_BOLD);
+        let preview_title = if self.backup_browser.show_diff {
+            " Changes since the backup (d: show backup, RET: restore) "
+        } else {
+            " Backup (d: show changes, RET: restore) "
+        };
+        window.mvaddstr(
+            separator as i32,
+            (left + 2) as i32,
+            truncate_to_width(preview_title, inner_width.saturating_sub(2)),
+        );
+
+        let browser = &mut self.backup_browser;
+        if browser.selected < browser.list_offset {
+            browser.list_offset = browser.selected;
+        } else if browser.selected >= browser.list_offset + list_rows {
+            browser.list_offset = browser.selected + 1 - list_rows;
+        }
+        for (offset, backup) in browser
+            .backups
+            .iter()
+            .skip(browser.list_offset)
+            .take(list_rows)
+            .enumerate()
+        {
+            let attr = if browser.list_offset + offset == browser.selected {
+                A_REVERSE
+            } else {
+                0
+            };
+            window.attron(attr);
+            window.mvaddstr(
+                (top + 1 + offset) as i32,
+                (left + 1) as i32,
+                truncate_to_width(
+                    &backup.created.format(" %Y-%m-%d %H:%M:%S").to_string(),
+                    inner_width,
+                ),
+            );
+            window.attroff(attr);
+        }
+
+        let preview = self.backup_preview();
+        let browser = &mut self.backup_browser;
+        browser.preview_offset = browser
+            .preview_offset
+            .min(preview.len().saturating_sub(preview_rows));
+        for (offset, line) in preview
+            .iter()
+            .skip(browser.preview_offset)
+            .take(preview_rows)
+            .enumerate()
+        {
+            let attr = if browser.show_diff && line.starts_with("  ") {
+                A_DIM
+            } else {
+                0
+            };
+            window.attron(attr);
+            window.mvaddstr(
+                (separator + 1 + offset) as i32,
+                (left + 1) as i32,
+                truncate_to_width(line, inner_width),
+            );
+            window.attroff(attr);
+        }
+    }
+}
@@ -0,0 +1,294 @@
+use crate::document::{ActionDiff, LineBuffer};
+use crate::editor::minibuffer::PromptKind;
+use crate::editor::{Editor, EditorMode};
+use crate::persistence::{self, Bookmark};
+use crate::screen::Screen;
+use pancurses::{A_DIM, A_REVERSE, Input};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Default)]
+pub struct Bookmarks {
+    // Where bookmarks are kept; `None` until enabled at startup
+    pub path: Option<PathBuf>,
+    // The bookmarks of the current buffer, following its edits
+    pub marks: Vec<Bookmark>,
+    // Bookmarks offered by the picker, those of the current buffer first
+    pub entries: Vec<Bookmark>,
+    pub selected_index: usize,
+    pub scroll_offset: usize,
+}
+
+impl Bookmarks {
+    /// Keeps the bookmarks on their lines when an edit adds or removes lines
+    /// above them. A bookmark on a removed line stays with the text it was
+    /// joined to.
+    pub fn adjust(&mut self, diff: &ActionDiff) {
+        let removed = diff.old.len().saturating_sub(1);
+        let added = diff.new.len().saturating_sub(1);
+        let y = diff.start_y;
+        for mark in &mut self.marks {
+            if mark.line > y + removed {
+                mark.line = mark.line + added - removed;
+            } else if mark.line > y {
+                mark.line = y + (mark.line - y).min(added);
+            }
+        }
+    }
+}
+
+/// The line nearest to `line` that reads `text`, or `line` itself when no
+/// line does.
+pub fn find_line(lines: &LineBuffer, line: usize, text: &str) -> usize {
+    let line = line.min(lines.len().saturating_sub(1));
+    if lines.get(line).is_some_and(|found| found == text) {
+        return line;
+    }
+    (1..lines.len())
+        .flat_map(|distance| [line.checked_sub(distance), Some(line + distance)])
+        .flatten()
+        .find(|&y| lines.get(y).is_some_and(|found| found == text))
+        .unwrap_or(line)
+}
+
+impl Editor {
+    pub fn enable_bookmarks(&mut self) {
+        self.enable_bookmarks_with_base_dir(None);
+    }
+
+    /// Starts keeping bookmarks between sessions, taking up those of the
+    /// current file.
+    pub fn enable_bookmarks_with_base_dir(&mut self, base_dir: Option<PathBuf>) {
+        self.bookmarks.path = persistence::bookmarks_path(base_dir);
+        self.load_bookmarks();
+    }
+
+    /// Takes up the bookmarks kept for the current file, on the lines that
+    /// still read as they did.
+    pub(super) fn load_bookmarks(&mut self) {
+        self.bookmarks.marks.clear();
+        let (Some(path), Some(filename)) = (&self.bookmarks.path, &self.document.filename) else {
+            return;
+        };
+        let lines = self.document.lines();
+        self.bookmarks.marks = persistence::load_bookmarks(path)
+            .into_iter()
+            .filter(|bookmark| &bookmark.file_path == filename)
+            .map(|bookmark| Bookmark {
+                line: find_line(lines, bookmark.line, &bookmark.text),
+                ..bookmark
+            })
+            .collect();
+    }
+
+    /// Keeps the bookmarks of the current file, with the text of their lines
+    /// as they are now.
+    pub(super) fn save_bookmarks(&mut self) {
+        let (Some(path), Some(filename)) = (&self.bookmarks.path, &self.document.filename) else {
+            return;
+        };
+        for mark in &mut self.bookmarks.marks {
+            mark.file_path = filename.clone();
+            mark.text = self
+                .document
+                .lines()
+                .get(mark.line)
+                .cloned()
+                .unwrap_or_default();
+        }
+        persistence::save_bookmarks(path, filename, &self.bookmarks.marks);
+    }
+
+    pub fn set_bookmark(&mut self) {
+        self.open_minibuffer(PromptKind::Bookmark);
+    }
+
+    /// Bookmarks the current line as `name`, or with the lowest free number
+    /// when no name is given. A bookmark of the same name moves here.
+    pub(super) fn set_bookmark_named(&mut self, name: &str) {
+        let marks = &mut self.bookmarks.marks;
+        let name = match name.trim() {
+            "" => (1..)
+                .map(|number: usize| number.to_string())
+                .find(|number| marks.iter().all(|mark| &mark.name != number))
+                .unwrap_or_default(),
+            name => name.to_string(),
+        };
+        marks.retain(|mark| mark.name != name);
+        marks.push(Bookmark {
+            file_path: self.document.filename.clone().unwrap_or_default(),
+            name: name.clone(),
+            line: self.cursor.y,
+            text: self.document.lines()[self.cursor.y].clone(),
+        });
+        marks.sort_by_key(|mark| mark.line);
+        self.save_bookmarks();
+        self.set_message(&format!(
+            "Bookmark {name} set at line {}.",
+            self.cursor.y + 1
+        ));
+    }
+
+    /// Offers the bookmarks of the current buffer and of the other files that
+    /// still exist.
+    pub fn open_bookmarks(&mut self) {
+        let current = self.document.filename.clone().unwrap_or_default();
+        let mut entries: Vec<Bookmark> = self
+            .bookmarks
+            .marks
+            .iter()
+            .map(|mark| Bookmark {
+                text: self
+                    .document
+                    .lines()
+                    .get(mark.line)
+                    .cloned()
+                    .unwrap_or_default(),
+                ..mark.clone()
+            })
+            .collect();
+        if let Some(path) = &self.bookmarks.path {
+            let mut others: Vec<Bookmark> = persistence::load_bookmarks(path)
+                .into_iter()
+                .filter(|bookmark| bookmark.file_path != current)
+                .filter(|bookmark| Path::new(&bookmark.file_path).exists())
+                .collect();
+            others.sort_by(|a, b| (&a.file_path, a.line).cmp(&(&b.file_path, b.line)));
+            entries.extend(others);
+        }
+        if entries.is_empty() {
+            self.set_message("No bookmarks.");
+            return;
+        }
+        self.bookmarks.entries = entries;
+        self.bookmarks.selected_index = 0;
+        self.bookmarks.scroll_offset = 0;
+        self.mode = EditorMode::Bookmarks;
+    }
+
+    pub fn handle_bookmarks_input(&mut self, key: Input) {
+        let count = self.bookmarks.entries.len();
+        let selected = &mut self.bookmarks.selected_index;
+        match key {
+            Input::KeyUp | Input::Character('\x10') if count > 0 => {
+                *selected = (*selected + count - 1) % count;
+            }
+            Input::KeyDown | Input::Character('\x0e') if count > 0 => {
+                *selected = (*selected + 1) % count;
+            }
+            Input::Character('\n') | Input::Character('\r') => {
+                let Some(bookmark) = self.bookmarks.entries.get(*selected).cloned() else {
+                    return;
+                };
+                self.close_bookmarks();
+                self.go_to_bookmark(&bookmark);
+            }
+            Input::Character('d') if count > 0 => self.delete_selected_bookmark(),
+            // Esc or Ctrl-G
+            Input::Character('\x1b') | Input::Character('\x07') => self.close_bookmarks(),
+            _ => {}
+        }
+    }
+
+    fn go_to_bookmark(&mut self, bookmark: &Bookmark) {
+        let other_file = Some(&bookmark.file_path) != self.document.filename.as_ref()
+            && !bookmark.file_path.is_empty();
+        if other_file && let Err(e) = self.open_file(&bookmark.file_path) {
+            self.set_message(&format!("Could not open {}: {e}", bookmark.file_path));
+            return;
+        }
+        let lines = self.document.lines();
+        let Some(mark) = self
+            .bookmarks
+            .marks
+            .iter_mut()
+            .find(|mark| mark.name == bookmark.name)
+        else {
+            self.set_message(&format!("Bookmark {} is gone.", bookmark.name));
+            return;
+        };
+        mark.line = find_line(lines, mark.line, &mark.text);
+        let line = mark.line;
+        let origin = self.cursor_pos();
+        self.go_to_line_column(line + 1, None);
+        if !other_file {
+            self.record_jump(origin);
+        }
+        self.set_message(&format!("Bookmark {}", bookmark.name));
+    }
+
+    fn delete_selected_bookmark(&mut self) {
+        let bookmark = self.bookmarks.entries.remove(self.bookmarks.selected_index);
+        if Some(&bookmark.file_path) == self.document.filename.as_ref()
+            || bookmark.file_path.is_empty()
+        {
+            self.bookmarks
+                .marks
+                .retain(|mark| mark.name != bookmark.name);
+            self.save_bookmarks();
+        } else if let Some(path) = &self.bookmarks.path {
+            let remaining: Vec<Bookmark> = persistence::load_bookmarks(path)
+                .into_iter()
+                .filter(|kept| kept.file_path == bookmark.file_path && kept.name != bookmark.name)
+                .collect();
+            persistence::save_bookmarks(path, &bookmark.file_path, &remaining);
+        }
+        if self.bookmarks.entries.is_empty() {
+            self.close_bookmarks();
+        } else {
+            self.bookmarks.selected_index = self
+                .bookmarks
+                .selected_index
+                .min(self.bookmarks.entries.len() - 1);
+        }
+        self.set_message(&format!("Deleted bookmark {}.", bookmark.name));
+    }
+
+    fn close_bookmarks(&mut self) {
+        self.bookmarks.entries.clear();
+        self.mode = EditorMode::Normal;
+    }
+
+    pub fn draw_bookmarks(&mut self, window: &impl Screen) {
+        window.erase();
+        let screen_rows = window.get_max_y() as usize;
+        let list_height = screen_rows.saturating_sub(1);
+        let bookmarks = &mut self.bookmarks;
+
+        if bookmarks.selected_index < bookmarks.scroll_offset {
+            bookmarks.scroll_offset = bookmarks.selected_index;
+        }
+        if bookmarks.selected_index >= bookmarks.scroll_offset + list_height {
+            bookmarks.scroll_offset = bookmarks.selected_index + 1 - list_height;
+        }
+
+        let offset = bookmarks.scroll_offset;
+        for (row, bookmark) in bookmarks
+            .entries
+            .iter()
+            .skip(offset)
+            .take(list_height)
+            .enumerate()
+        {
+            let is_selected = offset + row == bookmarks.selected_index;
+            if is_selected {
+                window.attron(A_REVERSE);
+            }
+            window.mvaddstr(row as i32, 0, &bookmark.name);
+            if is_selected {
+                window.attroff(A_REVERSE);
+            }
+            let file = match bookmark.file_path.as_str() {
+                "" => "(buffer)",
+                file_path => file_path,
+            };
+            window.attron(A_DIM);
+            window.addstr(format!("  {file}:{}", bookmark.line + 1));
+            window.attroff(A_DIM);
+            window.addstr(format!("  {}", bookmark.text.trim()));
+        }
+
+        let prompt = "BOOKMARKS: Enter jumps, d deletes, Esc cancels";
+        window.mvaddstr(screen_rows as i32 - 1, 0, prompt);
+        window.refresh();
+    }
+}
@@ -0,0 +1,146 @@
+use crate::document::{ActionDiff, LineBuffer};
+use crate::editor::{Editor, LastActionType};
+
+/// How `UpcaseWord`, `DowncaseWord` and `CapitalizeWord` change letters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaseChange {
+    Upper,
+    Lower,
+    Capitalize,
+}
+
+impl CaseChange {
+    /// `text` with the case of its letters changed. Characters without case,
+    /// such as kana and kanji, stay as they are, and a letter may map to
+    /// several (`ß` upper-cases to `SS`).
+    pub fn apply(self, text: &str) -> String {
+        match self {
+            CaseChange::Upper => text.to_uppercase(),
+            CaseChange::Lower => text.to_lowercase(),
+            CaseChange::Capitalize => capitalize(text),
+        }
+    }
+}
+
+fn is_word_char(ch: char) -> bool {
+    ch.is_alphanumeric()
+}
+
+// The first letter of every word upper-cased and the rest lower-cased
+fn capitalize(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(is_word_char) {
+        result.push_str(&rest[..start]);
+        let word = &rest[start..];
+        let end = word.find(|ch| !is_word_char(ch)).unwrap_or(word.len());
+        let mut chars = word[..end].chars();
+        if let Some(first) = chars.next() {
+            result.extend(first.to_uppercase());
+            result.push_str(&chars.as_str().to_lowercase());
+        }
+        rest = &word[end..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// The word the cursor at byte `x` of line `y` is on, or else the next one
+/// on that or a later line, as its line and byte range.
+fn word_at(lines: &LineBuffer, x: usize, y: usize) -> Option<(usize, usize, usize)> {
+    for (line_y, line) in lines.iter().enumerate().skip(y) {
+        let from = if line_y == y { x.min(line.len()) } else { 0 };
+        // Back to the start of the word the cursor is on
+        let on_word = line[from..].starts_with(is_word_char);
+        let start = line[..from]
+            .char_indices()
+            .rev()
+            .take_while(|&(_, ch)| on_word && is_word_char(ch))
+            .last()
+            .map_or(from, |(idx, _)| idx);
+        let Some(offset) = line[start..].find(is_word_char) else {
+            continue;
+        };
+        let start = start + offset;
+        let end = line[start..]
+            .find(|ch| !is_word_char(ch))
+            .map_or(line.len(), |len| start + len);
+        return Some((line_y, start, end));
+    }
+    None
+}
+
+impl Editor {
+    /// Changes the case of the selection, or of the word at the cursor and
+    /// moves past it, as one undo step.
+    pub fn change_case(&mut self, change: CaseChange) {
+        self.clipboard.last_action_was_kill = false;
+        let cursor = self.cursor_pos();
+        let (start, end) = if self.selection.is_selection_active() {
+            match self.selection.get_selection_range(&self.cursor) {
+                Some(range) => range,
+                None => return,
+            }
+        } else {
+            match word_at(self.document.lines(), cursor.0, cursor.1) {
+                Some((y, start_x, end_x)) => ((start_x, y), (end_x, y)),
+                None => {
+                    self.set_message("No word to change.");
+                    return;
+                }
+            }
+        };
+
+        let lines = self.document.lines();
+        let old: Vec<String> = (start.1..=end.1)
+            .map(|y| {
+                let from = if y == start.1 { start.0 } else { 0 };
+                let to = if y == end.1 { end.0 } else { lines[y].len() };
+                lines[y][from..to].to_string()
+            })
+            .collect();
+        let new: Vec<String> = old.iter().map(|text| change.apply(text)).collect();
+        let last = new.last().map_or(0, String::len);
+        let new_end = if new.len() == 1 {
+            (start.0 + last, end.1)
+        } else {
+            (last, end.1)
+        };
+
+        let selecting = self.selection.is_selection_active();
+        // The cursor stays on its side of the selection, or moves past the word
+        let cursor_end = if !selecting || cursor == end {
+            new_end
+        } else {
+            cursor
+        };
+        let marker_at_end = selecting && self.selection.marker_pos == Some(end);
+        if old != new {
+            self.undo_redo.begin_group();
+            self.commit(
+                LastActionType::Other,
+                &ActionDiff {
+                    cursor_start_x: cursor.0,
+                    cursor_start_y: cursor.1,
+                    cursor_end_x: cursor_end.0,
+                    cursor_end_y: cursor_end.1,
+                    start_x: start.0,
+                    start_y: start.1,
+                    end_x: end.0,
+                    end_y: end.1,
+                    new,
+                    old,
+                },
+            );
+            self.undo_redo.end_group();
+            if marker_at_end {
+                self.selection.marker_pos = Some(new_end);
+            }
+        } else {
+            self.set_cursor_pos(cursor_end.0, cursor_end.1);
+            self.cursor.desired_x = self
+                .scroll
+                .get_display_width_from_bytes(&self.document.lines()[cursor_end.1], cursor_end.0);
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use crate::document::ActionDiff;
-use crate::editor::{Editor, LastActionType};
+use crate::editor::{Editor, LastActionType, shift_cursor_x};
 use crate::error::Result;
 
 impl Editor {
@@ -8,17 +8,17 @@ impl Editor {
 
         if self.selection.is_selection_active() {
             if let Some(((_start_x, start_y), (_end_x, end_y))) =
-                self.selection.get_selection_range(self.cursor_pos())
+                self.selection.get_selection_range(&self.cursor)
             {
                 let (original_cursor_x, original_cursor_y) = self.cursor_pos();
 
                 let mut states_to_process = Vec::new();
                 for y in start_y..=end_y {
-                    if y >= self.document.lines.len() {
+                    if y >= self.document.lines().len() {
                         continue;
                     }
 
-                    let line = &self.document.lines[y];
+                    let line = &self.document.lines()[y];
                     let is_last_line_and_excluded =
                         y == end_y && original_cursor_y == end_y && original_cursor_x == 0;
 
@@ -44,10 +44,10 @@ impl Editor {
                 let mut new_lines = Vec::new();
                 let mut old_lines = Vec::new();
                 for y in start_y..=end_y {
-                    if y >= self.document.lines.len() {
+                    if y >= self.document.lines().len() {
                         continue;
                     }
-                    let original_line = &self.document.lines[y];
+                    let original_line = &self.document.lines()[y];
                     old_lines.push(original_line.clone());
 
                     let is_last_line_and_excluded =
@@ -59,7 +59,13 @@ impl Editor {
                     }
                 }
 
-                let original_end_line_len = self.document.lines.get(end_y).map_or(0, |l| l.len());
+                let original_end_line_len = self.document.lines().get(end_y).map_or(0, |l| l.len());
+                let cursor_index = original_cursor_y - start_y;
+                let new_cursor_x = shift_cursor_x(
+                    &old_lines[cursor_index],
+                    &new_lines[cursor_index],
+                    original_cursor_x,
+                );
 
                 // Use two-step commit (delete then insert) for undo safety
                 self.commit(
@@ -81,9 +87,9 @@ impl Editor {
                 self.commit(
                     LastActionType::Ammend,
                     &ActionDiff {
-                        cursor_start_x: self.cursor_x,   // Current x after delete
-                        cursor_start_y: self.cursor_y,   // Current y after delete (is start_y)
-                        cursor_end_x: original_cursor_x, // Restore original cursor x
+                        cursor_start_x: self.cursor.x,   // Current x after delete
+                        cursor_start_y: self.cursor.y,   // Current y after delete (is start_y)
+                        cursor_end_x: new_cursor_x,      // Keep the cursor on the same text
                         cursor_end_y: original_cursor_y, // Restore original cursor y
                         start_x: 0,
                         start_y,
@@ -98,12 +104,12 @@ impl Editor {
             }
         } else {
             // Original single-line logic
-            let y = self.cursor_y;
-            if y >= self.document.lines.len() {
+            let y = self.cursor.y;
+            if y >= self.document.lines().len() {
                 return Ok(());
             }
 
-            let original_line = self.document.lines[y].clone();
+            let original_line = self.document.lines()[y].clone();
             let state = get_line_state(&original_line);
             let next_state = state.next();
             let new_line = transform_line(&original_line, next_state);
@@ -117,9 +123,9 @@ impl Editor {
             };
 
             let leading_whitespace_len = original_line.len() - original_line.trim_start().len();
-            let mut new_cursor_x = self.cursor_x;
+            let mut new_cursor_x = self.cursor.x;
             if cursor_x_change > 0 {
-                if self.cursor_x >= leading_whitespace_len {
+                if self.cursor.x >= leading_whitespace_len {
                     new_cursor_x += cursor_x_change as usize;
                 } else {
                     new_cursor_x = leading_whitespace_len + cursor_x_change as usize;
@@ -136,14 +142,14 @@ impl Editor {
             self.commit(
                 LastActionType::ToggleCheckbox,
                 &ActionDiff {
-                    cursor_start_x: self.cursor_x,
-                    cursor_start_y: self.cursor_y,
+                    cursor_start_x: self.cursor.x,
+                    cursor_start_y: self.cursor.y,
                     cursor_end_x: 0, // Cursor position after deletion is irrelevant
-                    cursor_end_y: self.cursor_y,
+                    cursor_end_y: self.cursor.y,
                     start_x: 0,
-                    start_y: self.cursor_y,
+                    start_y: self.cursor.y,
                     end_x: original_line.len(),
-                    end_y: self.cursor_y,
+                    end_y: self.cursor.y,
                     new: vec![],
                     old: vec![original_line],
                 },
@@ -152,13 +158,13 @@ impl Editor {
                 LastActionType::Ammend,
                 &ActionDiff {
                     cursor_start_x: 0, // Cursor position before insertion is irrelevant
-                    cursor_start_y: self.cursor_y,
+                    cursor_start_y: self.cursor.y,
                     cursor_end_x: new_cursor_x,
-                    cursor_end_y: self.cursor_y,
+                    cursor_end_y: self.cursor.y,
                     start_x: 0,
-                    start_y: self.cursor_y,
+                    start_y: self.cursor.y,
                     end_x: new_line.len(),
-                    end_y: self.cursor_y,
+                    end_y: self.cursor.y,
                     new: vec![new_line],
                     old: vec![],
                 },
@@ -0,0 +1,55 @@
+use crate::cleanup::{self, CleanupRule};
+use crate::editor::Editor;
+
+pub const CLEANUP_COMMAND: &str = "/cleanup";
+
+impl Editor {
+    /// Rules from the `cleanup` setting, or all of them when it is not set.
+    pub fn cleanup_rules(&self) -> Vec<CleanupRule> {
+        match &self.settings.cleanup {
+            Some(names) => names
+                .iter()
+                .filter_map(|name| {
+                    let rule = CleanupRule::parse(name);
+                    if rule.is_none() {
+                        log::warn!("Unknown cleanup rule: {name}");
+                    }
+                    rule
+                })
+                .collect(),
+            None => CleanupRule::ALL.to_vec(),
+        }
+    }
+
+    /// Removes the `/cleanup` command line and normalizes the whole document,
+    /// as one undo step. A configured line ending is applied on the next save.
+    pub(super) fn run_cleanup_command(&mut self) {
+        let command_y = self.cursor.y;
+        let mut lines = self.document.lines().clone();
+        lines.remove(command_y);
+        if lines.is_empty() {
+            lines.push(String::new());
+        }
+
+        let result = cleanup::cleanup(&lines, &self.cleanup_rules());
+        // The cursor goes to the line that followed the command
+        let cursor_y = result
+            .line_map
+            .get(command_y)
+            .copied()
+            .unwrap_or(result.lines.len() - 1);
+        self.replace_lines(result.lines, (0, cursor_y));
+
+        let mut message = result.summary.describe();
+        if let Some(ending) = self.configured_line_ending()
+            && ending != self.document.line_ending
+        {
+            self.document.line_ending = ending;
+            message.push_str(&format!(
+                " Line endings will be saved as {}.",
+                ending.name()
+            ));
+        }
+        self.set_message(&message);
+    }
+}
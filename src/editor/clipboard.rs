@@ -1,9 +1,64 @@
+use crate::error::{DmacsError, Result};
 use arboard;
+use std::io::{self, Write};
+
+// Longest sequence that common terminals accept; longer copies are not sent
+const OSC52_MAX_LEN: usize = 100_000;
+// Earlier kills kept besides the current one
+pub const KILL_RING_SIZE: usize = 30;
+
+/// Where copied and cut text goes besides the kill buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ClipboardBackend {
+    /// OSC 52 in an SSH session, the system clipboard otherwise
+    #[default]
+    Auto,
+    System,
+    /// Asks the terminal to set the clipboard of the machine it runs on,
+    /// which also works over SSH. The clipboard cannot be read back.
+    Osc52,
+    /// Only the kill buffer is used
+    Off,
+}
+
+impl ClipboardBackend {
+    pub fn from_setting(setting: Option<&str>) -> Self {
+        match setting {
+            None | Some("auto") => ClipboardBackend::Auto,
+            Some("system") => ClipboardBackend::System,
+            Some("osc52") => ClipboardBackend::Osc52,
+            Some("off") => ClipboardBackend::Off,
+            Some(other) => {
+                log::warn!("Unknown clipboard backend '{other}', using auto.");
+                ClipboardBackend::Auto
+            }
+        }
+    }
+
+    fn resolve(self) -> Self {
+        match self {
+            ClipboardBackend::Auto
+                if std::env::var_os("SSH_TTY").is_some()
+                    || std::env::var_os("SSH_CONNECTION").is_some() =>
+            {
+                ClipboardBackend::Osc52
+            }
+            ClipboardBackend::Auto => ClipboardBackend::System,
+            backend => backend,
+        }
+    }
+}
 
 pub struct Clipboard {
+    // What a yank inserts: the latest kill or copy
     pub kill_buffer: String,
+    // The kills before it, newest first
+    pub kill_ring: Vec<String>,
     pub last_action_was_kill: bool,
+    // Last cut or copied rectangle, one entry per line
+    pub rectangle: Option<Vec<String>>,
     clipboard_enabled: bool,
+    backend: ClipboardBackend,
 }
 
 impl Default for Clipboard {
@@ -16,17 +71,65 @@ impl Clipboard {
     pub fn new() -> Self {
         Self {
             kill_buffer: String::new(),
+            kill_ring: Vec::new(),
             last_action_was_kill: false,
+            rectangle: None,
             clipboard_enabled: true,
+            backend: ClipboardBackend::default(),
         }
     }
 
-    pub fn set_clipboard(&self, text: &str) -> std::result::Result<(), arboard::Error> {
-        if !self.clipboard_enabled {
+    /// Makes `text` the kill buffer, keeping the one it replaces in the kill
+    /// ring. An entry equal to `text` leaves the ring, so each is listed once.
+    pub fn push_kill(&mut self, text: String) {
+        let previous = std::mem::replace(&mut self.kill_buffer, text);
+        if !previous.is_empty() {
+            self.kill_ring.insert(0, previous);
+        }
+        let current = &self.kill_buffer;
+        self.kill_ring.retain(|entry| entry != current);
+        self.kill_ring.truncate(KILL_RING_SIZE);
+    }
+
+    /// Takes `text` from the system clipboard as the latest kill, unless it is
+    /// already.
+    pub fn take_system_text(&mut self, text: String) {
+        if text != self.kill_buffer {
+            self.push_kill(text);
+        }
+    }
+
+    /// The kill buffer and the kills before it, newest first.
+    pub fn kills(&self) -> impl Iterator<Item = &String> {
+        std::iter::once(&self.kill_buffer)
+            .filter(|text| !text.is_empty())
+            .chain(&self.kill_ring)
+    }
+
+    pub fn set_backend(&mut self, backend: ClipboardBackend) {
+        self.backend = backend;
+    }
+
+    pub fn set_clipboard(&self, text: &str) -> Result<()> {
+        if !self.clipboard_enabled || self.backend == ClipboardBackend::Off {
+            return Ok(());
+        }
+        if self.backend.resolve() == ClipboardBackend::Osc52 {
+            let sequence = osc52_sequence(text, std::env::var_os("TMUX").is_some());
+            if sequence.len() > OSC52_MAX_LEN {
+                return Err(DmacsError::Editor(
+                    "text is too long for the terminal clipboard".to_string(),
+                ));
+            }
+            let mut stdout = io::stdout();
+            stdout.write_all(sequence.as_bytes())?;
+            stdout.flush()?;
             return Ok(());
         }
         if let Ok(mut clipboard) = arboard::Clipboard::new() {
-            clipboard.set_text(text.to_string())
+            clipboard
+                .set_text(text.to_string())
+                .map_err(|e| DmacsError::Editor(e.to_string()))
         } else {
             log::debug!("Failed to initialize clipboard.");
             Ok(())
@@ -34,7 +137,12 @@ impl Clipboard {
     }
 
     pub fn get_clipboard_text(&self) -> Option<String> {
-        if !self.clipboard_enabled {
+        if !self.clipboard_enabled
+            || matches!(
+                self.backend.resolve(),
+                ClipboardBackend::Osc52 | ClipboardBackend::Off
+            )
+        {
             return None;
         }
         if let Ok(mut clipboard) = arboard::Clipboard::new() {
@@ -49,3 +157,35 @@ impl Clipboard {
         self.clipboard_enabled = enabled;
     }
 }
+
+/// The escape sequence that puts `text` on the terminal's clipboard. Inside
+/// tmux it is wrapped so that tmux passes it on to the outer terminal.
+pub fn osc52_sequence(text: &str, in_tmux: bool) -> String {
+    let sequence = format!("\x1b]52;c;{}\x07", base64(text.as_bytes()));
+    if in_tmux {
+        format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+    } else {
+        sequence
+    }
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
@@ -1,4 +1,11 @@
+use crate::editor::snippet::{self, Snippet};
+use crate::editor::timestamp::{
+    self, DEFAULT_DATE_FORMAT, DEFAULT_DATETIME_FORMAT, DEFAULT_TIME_FORMAT,
+};
+use crate::inbox;
 use chrono::Local;
+use std::collections::HashMap;
+use std::path::Path;
 use std::process::Command;
 
 pub enum CommandResult {
@@ -7,23 +14,51 @@ pub enum CommandResult {
         status_message: String,
     },
     Error(String),
+    // `/sh <command>`, or `/sh! <command>` to replace the line with the output
+    Shell {
+        command: String,
+        replace: bool,
+    },
+    // Text that replaces the command line, e.g. from `/template <name>`
+    Snippet {
+        snippet: Snippet,
+        status_message: String,
+    },
     NoCommand,
 }
 
-pub fn execute_command(line: &str) -> CommandResult {
+/// What commands may need to know about the editor they run in.
+#[derive(Default)]
+pub struct CommandContext<'a> {
+    pub current_file: Option<&'a Path>,
+    pub inbox_file: Option<&'a Path>,
+    pub templates: Option<&'a HashMap<String, String>>,
+    // strftime formats of `/today`, `/time` and `/now`, the defaults when unset
+    pub date_format: Option<&'a str>,
+    pub time_format: Option<&'a str>,
+    pub datetime_format: Option<&'a str>,
+}
+
+fn timestamp_command(command: &str, format: Option<&str>, default: &str) -> CommandResult {
+    let format = format.unwrap_or(default);
+    match timestamp::format_timestamp(Local::now(), format) {
+        Some(timestamp) => CommandResult::Success {
+            new_line_content: Some(timestamp),
+            status_message: command.to_string(),
+        },
+        None => CommandResult::Error(format!("Invalid format for {command}: {format}")),
+    }
+}
+
+pub fn execute_command(line: &str, context: &CommandContext) -> CommandResult {
     if !line.starts_with('/') {
         return CommandResult::NoCommand;
     }
 
     match line.trim() {
-        "/today" => CommandResult::Success {
-            new_line_content: Some(Local::now().format("%Y-%m-%d").to_string()),
-            status_message: "/today".to_string(),
-        },
-        "/now" => CommandResult::Success {
-            new_line_content: Some(Local::now().format("%Y-%m-%d %H:%M").to_string()),
-            status_message: "/now".to_string(),
-        },
+        "/today" => timestamp_command("/today", context.date_format, DEFAULT_DATE_FORMAT),
+        "/time" => timestamp_command("/time", context.time_format, DEFAULT_TIME_FORMAT),
+        "/now" => timestamp_command("/now", context.datetime_format, DEFAULT_DATETIME_FORMAT),
         _ if line.starts_with("/tweet ") => {
             let message = line.trim_start_matches("/tweet ").trim();
             let tweet_text = format!("{{\"text\":\"{message}\"}}");
@@ -62,6 +97,55 @@ pub fn execute_command(line: &str) -> CommandResult {
                 status_message: "/qiita".to_string(),
             }
         }
+        _ if line.starts_with("/sh ") || line.starts_with("/sh! ") => {
+            let (replace, command) = match line.strip_prefix("/sh! ") {
+                Some(command) => (true, command),
+                None => (false, line.trim_start_matches("/sh ")),
+            };
+            let command = command.trim();
+            if command.is_empty() {
+                return CommandResult::Error("Usage: /sh <command>".to_string());
+            }
+            CommandResult::Shell {
+                command: command.to_string(),
+                replace,
+            }
+        }
+        _ if line.starts_with("/inbox ") => {
+            let text = line.trim_start_matches("/inbox ").trim();
+            if text.is_empty() {
+                return CommandResult::Error("Usage: /inbox <text>".to_string());
+            }
+            let Some(inbox_file) = context.inbox_file else {
+                return CommandResult::Error(
+                    "No inbox file configured. Set inbox_file under [editor].".to_string(),
+                );
+            };
+            if context.current_file == Some(inbox_file) {
+                return CommandResult::Error("The inbox is the file being edited.".to_string());
+            }
+            match inbox::append_line(inbox_file, &format!("- [ ] {text}")) {
+                Ok(()) => CommandResult::Success {
+                    new_line_content: Some(format!("# {line}")),
+                    status_message: format!("/inbox: added to {}", inbox_file.display()),
+                },
+                Err(e) => CommandResult::Error(format!("Failed to append to inbox: {e}")),
+            }
+        }
+        _ if line.starts_with("/template ") => {
+            let name = line.trim_start_matches("/template ").trim();
+            if name.is_empty() {
+                return CommandResult::Error("Usage: /template <name>".to_string());
+            }
+            let Some(template) = context.templates.and_then(|templates| templates.get(name)) else {
+                return CommandResult::Error(format!("No template named {name}."));
+            };
+            let file = context.current_file.and_then(Path::to_str);
+            CommandResult::Snippet {
+                snippet: snippet::expand(template, Local::now(), file),
+                status_message: format!("/template {name}"),
+            }
+        }
         _ => CommandResult::NoCommand,
     }
 }
@@ -0,0 +1,178 @@
+use crate::editor::actions::{Action, PALETTE_ACTIONS};
+use crate::editor::agenda::truncate_to_width;
+use crate::editor::hint_bar::key_label;
+use crate::editor::{Editor, EditorMode};
+use crate::error::Result;
+use crate::screen::Screen;
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use pancurses::{A_BOLD, A_DIM, A_REVERSE, Input};
+use unicode_width::UnicodeWidthStr;
+
+const MARGIN: usize = 2;
+
+#[derive(Debug, Default)]
+pub struct CommandPalette {
+    pub query: String,
+    // The actions matching the query, best match first
+    pub matches: Vec<Action>,
+    pub selected: usize,
+    scroll_offset: usize,
+}
+
+/// The name an action is listed by, as written in the `[keymap]` section.
+pub fn action_name(action: &Action) -> String {
+    format!("{action:?}")
+}
+
+impl CommandPalette {
+    fn update_matches(&mut self) {
+        let matcher = SkimMatcherV2::default();
+        let mut scored: Vec<(i64, &Action)> = PALETTE_ACTIONS
+            .iter()
+            .filter_map(|action| {
+                if self.query.is_empty() {
+                    return Some((0, action));
+                }
+                matcher
+                    .fuzzy_match(&action_name(action), &self.query)
+                    .map(|score| (score, action))
+            })
+            .collect();
+        // Stable, so equal scores keep the order of the list
+        scored.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+        self.matches = scored
+            .into_iter()
+            .map(|(_, action)| action.clone())
+            .collect();
+        self.selected = 0;
+        self.scroll_offset = 0;
+    }
+}
+
+impl Editor {
+    pub fn open_command_palette(&mut self) {
+        self.command_palette = CommandPalette::default();
+        self.command_palette.update_matches();
+        self.mode = EditorMode::CommandPalette;
+    }
+
+    pub fn handle_command_palette_input(&mut self, key: Input) -> Result<()> {
+        let palette = &mut self.command_palette;
+        let count = palette.matches.len();
+        match key {
+            Input::KeyUp | Input::Character('\x10') if count > 0 => {
+                palette.selected = (palette.selected + count - 1) % count;
+            }
+            Input::KeyDown | Input::Character('\x0e') if count > 0 => {
+                palette.selected = (palette.selected + 1) % count;
+            }
+            Input::Character('\n') | Input::Character('\r') => {
+                let Some(action) = palette.matches.get(palette.selected).cloned() else {
+                    return Ok(());
+                };
+                self.close_command_palette();
+                return self.execute_action(action);
+            }
+            // Esc or Ctrl-G
+            Input::Character('\x1b') | Input::Character('\x07') => self.close_command_palette(),
+            Input::KeyBackspace | Input::Character('\x7f') | Input::Character('\x08') => {
+                palette.query.pop();
+                palette.update_matches();
+            }
+            Input::Character(c) if !c.is_control() => {
+                palette.query.push(c);
+                palette.update_matches();
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn close_command_palette(&mut self) {
+        self.command_palette = CommandPalette::default();
+        self.mode = EditorMode::Normal;
+    }
+
+    /// Draws the matching actions in a box over the document, each with the
+    /// key that runs it.
+    pub fn draw_command_palette(&mut self, window: &impl Screen) {
+        let screen_rows = window.get_max_y() as usize;
+        let screen_cols = window.get_max_x() as usize;
+        if screen_rows <= MARGIN * 2 + 2 || screen_cols <= MARGIN * 2 + 2 {
+            return;
+        }
+        let (top, left) = (MARGIN, MARGIN);
+        let (bottom, right) = (screen_rows - MARGIN - 1, screen_cols - MARGIN - 1);
+        let inner_width = right - left - 1;
+
+        let visible_rows = bottom - top - 1;
+        let keys: Vec<Option<String>> = self
+            .command_palette
+            .matches
+            .iter()
+            .map(|action| self.key_for_action(action).map(key_label))
+            .collect();
+        let palette = &mut self.command_palette;
+        if palette.selected < palette.scroll_offset {
+            palette.scroll_offset = palette.selected;
+        } else if palette.selected >= palette.scroll_offset + visible_rows {
+            palette.scroll_offset = palette.selected + 1 - visible_rows;
+        }
+
+        let blank = " ".repeat(inner_width);
+        for row in top + 1..bottom {
+            window.mvaddch(row as i32, left as i32, pancurses::ACS_VLINE());
+            window.mvaddstr(row as i32, (left + 1) as i32, &blank);
+            window.mvaddch(row as i32, right as i32, pancurses::ACS_VLINE());
+        }
+        for col in left + 1..right {
+            window.mvaddch(top as i32, col as i32, pancurses::ACS_HLINE());
+            window.mvaddch(bottom as i32, col as i32, pancurses::ACS_HLINE());
+        }
+        window.mvaddch(top as i32, left as i32, pancurses::ACS_ULCORNER());
+        window.mvaddch(top as i32, right as i32, pancurses::ACS_URCORNER());
+        window.mvaddch(bottom as i32, left as i32, pancurses::ACS_LLCORNER());
+        window.mvaddch(bottom as i32, right as i32, pancurses::ACS_LRCORNER());
+        let title = format!(" Command: {} ", palette.query);
+        window.attron(A_BOLD);
+        window.mvaddstr(
+            top as i32,
+            (left + 2) as i32,
+            truncate_to_width(&title, inner_width.saturating_sub(2)),
+        );
+        window.attroff(A_BOLD);
+
+        for (offset, (action, key)) in palette
+            .matches
+            .iter()
+            .zip(&keys)
+            .skip(palette.scroll_offset)
+            .take(visible_rows)
+            .enumerate()
+        {
+            let row = (top + 1 + offset) as i32;
+            let is_selected = palette.scroll_offset + offset == palette.selected;
+            if is_selected {
+                window.attron(A_REVERSE);
+                window.mvaddstr(row, (left + 1) as i32, &blank);
+            }
+            window.mvaddstr(
+                row,
+                (left + 2) as i32,
+                truncate_to_width(&action_name(action), inner_width.saturating_sub(2)),
+            );
+            if let Some(key) = key {
+                let name_width = action_name(action).width() + 2;
+                if name_width + key.width() + 2 <= inner_width {
+                    window.attron(A_DIM);
+                    window.mvaddstr(row, (right - 1 - key.width()) as i32, key);
+                    window.attroff(A_DIM);
+                }
+            }
+            if is_selected {
+                window.attroff(A_REVERSE);
+            }
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use crate::document::ActionDiff;
-use crate::editor::{Editor, LastActionType};
+use crate::editor::{Editor, LastActionType, shift_cursor_x};
 use crate::error::Result;
 
 const COMMENT_PREFIX: &str = "# ";
@@ -10,16 +10,16 @@ impl Editor {
 
         if self.selection.is_selection_active() {
             if let Some(((_start_x, start_y), (_end_x, end_y))) =
-                self.selection.get_selection_range(self.cursor_pos())
+                self.selection.get_selection_range(&self.cursor)
             {
                 let (original_cursor_x, original_cursor_y) = self.cursor_pos();
 
                 let mut lines_to_process = Vec::new();
                 for y in start_y..=end_y {
-                    if y >= self.document.lines.len() {
+                    if y >= self.document.lines().len() {
                         continue;
                     }
-                    let line = &self.document.lines[y];
+                    let line = &self.document.lines()[y];
                     let is_last_line_and_excluded =
                         y == end_y && original_cursor_y == end_y && original_cursor_x == 0;
 
@@ -40,10 +40,10 @@ impl Editor {
                 let mut new_lines = Vec::new();
                 let mut old_lines = Vec::new();
                 for y in start_y..=end_y {
-                    if y >= self.document.lines.len() {
+                    if y >= self.document.lines().len() {
                         continue;
                     }
-                    let original_line = &self.document.lines[y];
+                    let original_line = &self.document.lines()[y];
                     old_lines.push(original_line.clone());
 
                     let is_last_line_and_excluded =
@@ -57,7 +57,13 @@ impl Editor {
                     }
                 }
 
-                let original_end_line_len = self.document.lines.get(end_y).map_or(0, |l| l.len());
+                let original_end_line_len = self.document.lines().get(end_y).map_or(0, |l| l.len());
+                let cursor_index = original_cursor_y - start_y;
+                let new_cursor_x = shift_cursor_x(
+                    &old_lines[cursor_index],
+                    &new_lines[cursor_index],
+                    original_cursor_x,
+                );
 
                 self.commit(
                     LastActionType::ToggleComment,
@@ -78,9 +84,9 @@ impl Editor {
                 self.commit(
                     LastActionType::Ammend,
                     &ActionDiff {
-                        cursor_start_x: self.cursor_x,
-                        cursor_start_y: self.cursor_y,
-                        cursor_end_x: original_cursor_x,
+                        cursor_start_x: self.cursor.x,
+                        cursor_start_y: self.cursor.y,
+                        cursor_end_x: new_cursor_x,
                         cursor_end_y: original_cursor_y,
                         start_x: 0,
                         start_y,
@@ -95,12 +101,12 @@ impl Editor {
             }
         } else {
             // Single line
-            let y = self.cursor_y;
-            if y >= self.document.lines.len() {
+            let y = self.cursor.y;
+            if y >= self.document.lines().len() {
                 return Ok(());
             }
 
-            let original_line = self.document.lines[y].clone();
+            let original_line = self.document.lines()[y].clone();
             if original_line.is_empty() {
                 return Ok(());
             }
@@ -112,24 +118,19 @@ impl Editor {
                 comment_line(&original_line)
             };
 
-            let cursor_x_change = new_line.len() as isize - original_line.len() as isize;
-            let mut new_cursor_x = self.cursor_x as isize + cursor_x_change;
-            if self.cursor_x < original_line.len() - original_line.trim_start().len() {
-                new_cursor_x = self.cursor_x as isize;
-            }
-            new_cursor_x = new_cursor_x.max(0);
+            let new_cursor_x = shift_cursor_x(&original_line, &new_line, self.cursor.x);
 
             self.commit(
                 LastActionType::ToggleComment,
                 &ActionDiff {
-                    cursor_start_x: self.cursor_x,
-                    cursor_start_y: self.cursor_y,
+                    cursor_start_x: self.cursor.x,
+                    cursor_start_y: self.cursor.y,
                     cursor_end_x: 0,
-                    cursor_end_y: self.cursor_y,
+                    cursor_end_y: self.cursor.y,
                     start_x: 0,
-                    start_y: self.cursor_y,
+                    start_y: self.cursor.y,
                     end_x: original_line.len(),
-                    end_y: self.cursor_y,
+                    end_y: self.cursor.y,
                     new: vec![],
                     old: vec![original_line],
                 },
@@ -138,13 +139,13 @@ impl Editor {
                 LastActionType::Ammend,
                 &ActionDiff {
                     cursor_start_x: 0,
-                    cursor_start_y: self.cursor_y,
-                    cursor_end_x: new_cursor_x as usize,
-                    cursor_end_y: self.cursor_y,
+                    cursor_start_y: self.cursor.y,
+                    cursor_end_x: new_cursor_x,
+                    cursor_end_y: self.cursor.y,
                     start_x: 0,
-                    start_y: self.cursor_y,
+                    start_y: self.cursor.y,
                     end_x: new_line.len(),
-                    end_y: self.cursor_y,
+                    end_y: self.cursor.y,
                     new: vec![new_line.clone()],
                     old: vec![],
                 },
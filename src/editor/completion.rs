@@ -0,0 +1,279 @@
+use crate::document::Document;
+use crate::editor::agenda::truncate_to_width;
+use crate::editor::{Editor, EditorMode};
+use crate::error::Result;
+use crate::screen::Screen;
+use pancurses::{A_REVERSE, Input};
+use unicode_width::UnicodeWidthStr;
+
+const MAX_CANDIDATES: usize = 10;
+// Shorter words are quicker typed than completed
+const MIN_WORD_CHARS: usize = 3;
+
+fn is_word_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_'
+}
+
+/// The words of `line` worth offering as completions, with the byte each
+/// starts at.
+fn words(line: &str) -> Vec<(usize, String)> {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (idx, ch) in line.char_indices().chain([(line.len(), ' ')]) {
+        match (start, is_word_char(ch)) {
+            (None, true) => start = Some(idx),
+            (Some(from), false) => {
+                let word = &line[from..idx];
+                if word.chars().count() >= MIN_WORD_CHARS {
+                    words.push((from, word.to_string()));
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    words
+}
+
+/// The words of every line of the buffer, found when first asked for and
+/// then updated from the lines changed since.
+#[derive(Debug, Default)]
+pub struct WordIndex {
+    lines: Vec<Vec<(usize, String)>>,
+    // The document revision the words were found for
+    revision: Option<u64>,
+}
+
+impl WordIndex {
+    /// Brings the words up to date with `document`, reading only the lines
+    /// changed since the last time when those are still remembered.
+    pub fn update(&mut self, document: &Document) {
+        let revision = document.revision();
+        if self.revision == Some(revision) {
+            return;
+        }
+        let since = self.revision.and_then(|since| {
+            Some((
+                document.changes_since(since)?,
+                document.changed_lines_since(since)?,
+            ))
+        });
+        if let Some((changes, changed)) = since {
+            for change in changes {
+                let end = (change.start + change.removed).min(self.lines.len());
+                let start = change.start.min(end);
+                self.lines.splice(
+                    start..end,
+                    std::iter::repeat_with(Vec::new).take(change.inserted),
+                );
+            }
+            if self.lines.len() == document.lines().len() {
+                for y in changed.into_iter().flatten() {
+                    self.lines[y] = words(&document.lines()[y]);
+                }
+                self.revision = Some(revision);
+                return;
+            }
+        }
+        self.lines = document.lines().iter().map(|line| words(line)).collect();
+        self.revision = Some(revision);
+    }
+
+    /// Forgets the words, for a buffer whose lines were replaced.
+    pub fn clear(&mut self) {
+        *self = WordIndex::default();
+    }
+
+    /// The words of the buffer, nearest to the word at byte `x` of line `y`
+    /// first, and those before it ahead of those after it at the same
+    /// distance. The word at `x` itself is left out.
+    fn nearest_words(&self, x: usize, y: usize) -> impl Iterator<Item = &str> {
+        let line = self.lines.get(y).map_or(&[][..], Vec::as_slice);
+        let before = line.iter().rev().filter(move |(start, _)| *start < x);
+        let after = line.iter().filter(move |(start, _)| *start > x);
+        let around = (1..self.lines.len()).flat_map(move |distance| {
+            let above = y.checked_sub(distance).and_then(|y| self.lines.get(y));
+            let below = self.lines.get(y + distance);
+            above
+                .into_iter()
+                .flat_map(|words| words.iter().rev())
+                .chain(below.into_iter().flatten())
+        });
+        before
+            .chain(after)
+            .chain(around)
+            .map(|(_, word)| word.as_str())
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Completion {
+    pub index: WordIndex,
+    pub popup: Option<CompletionPopup>,
+}
+
+/// Words that complete `prefix`, the word before the cursor.
+#[derive(Debug)]
+pub struct CompletionPopup {
+    pub prefix: String,
+    pub candidates: Vec<String>,
+    pub selected: usize,
+}
+
+impl Editor {
+    /// Completes the word before the cursor from the other words of the
+    /// buffer and of the buffer behind it, nearest first. With more than one
+    /// candidate they are offered in a popup.
+    pub fn complete_word(&mut self) -> Result<()> {
+        self.clipboard.last_action_was_kill = false;
+        let (x, y) = self.cursor_pos();
+        let line = &self.document.lines()[y];
+        let start = line[..x]
+            .char_indices()
+            .rev()
+            .take_while(|&(_, ch)| is_word_char(ch))
+            .last()
+            .map_or(x, |(idx, _)| idx);
+        if start == x {
+            self.set_message("No word before the cursor to complete.");
+            return Ok(());
+        }
+        let prefix = line[start..x].to_string();
+
+        self.completion.index.update(&self.document);
+        let others = self.alternate_buffer.iter().flat_map(|buffer| {
+            buffer
+                .document
+                .lines()
+                .iter()
+                .flat_map(|line| words(line))
+                .map(|(_, word)| word)
+        });
+        let mut candidates: Vec<String> = Vec::new();
+        let nearest = self.completion.index.nearest_words(start, y);
+        for word in nearest.map(str::to_string).chain(others) {
+            if word.len() > prefix.len() && word.starts_with(&prefix) && !candidates.contains(&word)
+            {
+                candidates.push(word);
+                if candidates.len() == MAX_CANDIDATES {
+                    break;
+                }
+            }
+        }
+
+        match candidates.len() {
+            0 => self.set_message(&format!("No completion for {prefix}.")),
+            1 => self.insert_text(&candidates[0][prefix.len()..])?,
+            _ => {
+                self.completion.popup = Some(CompletionPopup {
+                    prefix,
+                    candidates,
+                    selected: 0,
+                });
+                self.mode = EditorMode::Completion;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn handle_completion_input(&mut self, key: Input) -> Result<()> {
+        let Some(popup) = &mut self.completion.popup else {
+            self.mode = EditorMode::Normal;
+            return Ok(());
+        };
+        let count = popup.candidates.len();
+        match key {
+            Input::Character('/') if self.is_alt_pressed => {
+                popup.selected = (popup.selected + 1) % count;
+            }
+            Input::Character('\t') | Input::KeyDown | Input::Character('\x0e') => {
+                popup.selected = (popup.selected + 1) % count;
+            }
+            Input::KeyBTab | Input::KeySTab | Input::KeyUp | Input::Character('\x10') => {
+                popup.selected = (popup.selected + count - 1) % count;
+            }
+            Input::Character('\n') | Input::Character('\r') => {
+                let popup = self.completion.popup.take();
+                self.mode = EditorMode::Normal;
+                if let Some(popup) = popup {
+                    let candidate = &popup.candidates[popup.selected];
+                    self.insert_text(&candidate[popup.prefix.len()..])?;
+                }
+            }
+            // Esc or Ctrl-G
+            Input::Character('\x1b') | Input::Character('\x07') => self.close_completion(),
+            // Any other key closes the popup and does what it always does
+            _ => {
+                self.close_completion();
+                return self.process_input(key, self.is_alt_pressed);
+            }
+        }
+        Ok(())
+    }
+
+    fn close_completion(&mut self) {
+        self.completion.popup = None;
+        self.mode = EditorMode::Normal;
+    }
+
+    /// Draws the candidates in a box under the word being completed, or
+    /// above it when the cursor is near the bottom.
+    pub fn draw_completion(&self, window: &impl Screen, cursor_row: usize, cursor_col: usize) {
+        let Some(popup) = &self.completion.popup else {
+            return;
+        };
+        let screen_rows = window.get_max_y() as usize;
+        let screen_cols = window.get_max_x() as usize;
+        let content_width = popup
+            .candidates
+            .iter()
+            .map(|candidate| candidate.width())
+            .max()
+            .unwrap_or(0);
+        let height = popup.candidates.len() + 2;
+        let width = content_width + 4;
+        if height > screen_rows || width > screen_cols {
+            return;
+        }
+        let top = if cursor_row + 1 + height <= screen_rows {
+            cursor_row + 1
+        } else {
+            cursor_row.saturating_sub(height)
+        };
+        // Line the candidates up with the word they complete
+        let word_col = cursor_col.saturating_sub(popup.prefix.width() + 2);
+        let left = word_col.min(screen_cols - width);
+        let (bottom, right) = (top + height - 1, left + width - 1);
+        let inner_width = right - left - 1;
+
+        let blank = " ".repeat(inner_width);
+        for row in top + 1..bottom {
+            window.mvaddch(row as i32, left as i32, pancurses::ACS_VLINE());
+            window.mvaddstr(row as i32, (left + 1) as i32, &blank);
+            window.mvaddch(row as i32, right as i32, pancurses::ACS_VLINE());
+        }
+        for col in left + 1..right {
+            window.mvaddch(top as i32, col as i32, pancurses::ACS_HLINE());
+            window.mvaddch(bottom as i32, col as i32, pancurses::ACS_HLINE());
+        }
+        window.mvaddch(top as i32, left as i32, pancurses::ACS_ULCORNER());
+        window.mvaddch(top as i32, right as i32, pancurses::ACS_URCORNER());
+        window.mvaddch(bottom as i32, left as i32, pancurses::ACS_LLCORNER());
+        window.mvaddch(bottom as i32, right as i32, pancurses::ACS_LRCORNER());
+
+        for (offset, candidate) in popup.candidates.iter().enumerate() {
+            let attr = if offset == popup.selected {
+                A_REVERSE
+            } else {
+                0
+            };
+            window.attron(attr);
+            window.mvaddstr(
+                (top + 1 + offset) as i32,
+                (left + 1) as i32,
+                truncate_to_width(&format!(" {candidate}"), inner_width),
+            );
+            window.attroff(attr);
+        }
+    }
+}
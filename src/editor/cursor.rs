@@ -0,0 +1,20 @@
+/// Where the cursor is in the document, shared by the code that moves it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Cursor {
+    // Byte index in the line
+    pub x: usize,
+    pub y: usize,
+    // The display column that moving up and down tries to keep
+    pub desired_x: usize,
+}
+
+impl Cursor {
+    pub fn new(x: usize, y: usize) -> Self {
+        Self { x, y, desired_x: 0 }
+    }
+
+    /// The position as `(x, y)`.
+    pub fn pos(&self) -> (usize, usize) {
+        (self.x, self.y)
+    }
+}
@@ -0,0 +1,141 @@
+use crate::editor::annotation::find_annotation;
+use crate::editor::{Editor, EditorMode};
+use crate::screen::Screen;
+use chrono::{Datelike, Days, Local, Months, NaiveDate};
+use pancurses::{A_BOLD, A_REVERSE, A_UNDERLINE, Input};
+
+pub const DUE_DATE_FORMAT: &str = "%Y-%m-%d";
+const CALENDAR_WIDTH: usize = 22;
+const CALENDAR_HEIGHT: usize = 9;
+
+#[derive(Debug)]
+pub struct DatePicker {
+    pub selected: NaiveDate,
+    // Line the picked date is written to
+    pub target_line: usize,
+}
+
+/// Returns the date in a task's `@due(YYYY-MM-DD)` annotation.
+pub fn parse_due(line: &str) -> Option<NaiveDate> {
+    let (_, _, value) = find_annotation(line, "due")?;
+    NaiveDate::parse_from_str(value, DUE_DATE_FORMAT).ok()
+}
+
+impl Editor {
+    pub fn open_date_picker(&mut self) {
+        let line = &self.document.lines()[self.cursor.y];
+        let selected = parse_due(line).unwrap_or_else(|| Local::now().date_naive());
+        self.date_picker = Some(DatePicker {
+            selected,
+            target_line: self.cursor.y,
+        });
+        self.mode = EditorMode::DatePicker;
+        self.set_message("Pick a due date: arrows move, PgUp/PgDn change month, Enter inserts.");
+    }
+
+    pub fn handle_date_picker_input(&mut self, key: Input) {
+        let Some(picker) = self.date_picker.as_mut() else {
+            self.mode = EditorMode::Normal;
+            return;
+        };
+        let selected = picker.selected;
+        let moved = match key {
+            Input::KeyLeft => selected.checked_sub_days(Days::new(1)),
+            Input::KeyRight => selected.checked_add_days(Days::new(1)),
+            Input::KeyUp => selected.checked_sub_days(Days::new(7)),
+            Input::KeyDown => selected.checked_add_days(Days::new(7)),
+            Input::KeyPPage => selected.checked_sub_months(Months::new(1)),
+            Input::KeyNPage => selected.checked_add_months(Months::new(1)),
+            Input::Character('t') => Some(Local::now().date_naive()),
+            Input::Character('\n') | Input::Character('\r') => {
+                self.confirm_date_picker();
+                return;
+            }
+            // Esc or Ctrl-G
+            Input::Character('\x1b') | Input::Character('\x07') => {
+                self.close_date_picker();
+                self.set_message("Cancelled.");
+                return;
+            }
+            _ => None,
+        };
+        if let Some(date) = moved {
+            picker.selected = date;
+        }
+    }
+
+    fn confirm_date_picker(&mut self) {
+        let Some(picker) = self.date_picker.take() else {
+            return;
+        };
+        self.mode = EditorMode::Normal;
+        if picker.target_line >= self.document.lines().len() {
+            return;
+        }
+        let date = picker.selected.format(DUE_DATE_FORMAT).to_string();
+        self.set_line_annotation(picker.target_line, "due", &date);
+        self.set_message(&format!("Due {date}"));
+    }
+
+    fn close_date_picker(&mut self) {
+        self.date_picker = None;
+        self.mode = EditorMode::Normal;
+    }
+
+    /// Draws a month calendar centered on the screen with the selected day
+    /// highlighted.
+    pub fn draw_date_picker(&self, window: &impl Screen) {
+        let Some(picker) = &self.date_picker else {
+            return;
+        };
+        let screen_rows = window.get_max_y() as usize;
+        let screen_cols = window.get_max_x() as usize;
+        let top = screen_rows.saturating_sub(CALENDAR_HEIGHT) / 2;
+        let left = screen_cols.saturating_sub(CALENDAR_WIDTH) / 2;
+        let selected = picker.selected;
+        let today = Local::now().date_naive();
+
+        let (right, bottom) = (left + CALENDAR_WIDTH - 1, top + CALENDAR_HEIGHT - 1);
+        let blank = " ".repeat(CALENDAR_WIDTH - 2);
+        for row in top + 1..bottom {
+            window.mvaddch(row as i32, left as i32, pancurses::ACS_VLINE());
+            window.mvaddstr(row as i32, (left + 1) as i32, &blank);
+            window.mvaddch(row as i32, right as i32, pancurses::ACS_VLINE());
+        }
+        for col in left + 1..right {
+            window.mvaddch(top as i32, col as i32, pancurses::ACS_HLINE());
+            window.mvaddch(bottom as i32, col as i32, pancurses::ACS_HLINE());
+        }
+        window.mvaddch(top as i32, left as i32, pancurses::ACS_ULCORNER());
+        window.mvaddch(top as i32, right as i32, pancurses::ACS_URCORNER());
+        window.mvaddch(bottom as i32, left as i32, pancurses::ACS_LLCORNER());
+        window.mvaddch(bottom as i32, right as i32, pancurses::ACS_LRCORNER());
+
+        let title = selected.format("%B %Y").to_string();
+        let title_col = left + (CALENDAR_WIDTH.saturating_sub(title.len())) / 2;
+        window.attron(A_BOLD);
+        window.mvaddstr(top as i32, title_col as i32, &title);
+        window.attroff(A_BOLD);
+        window.mvaddstr((top + 1) as i32, (left + 1) as i32, "Mo Tu We Th Fr Sa Su");
+
+        let first = selected.with_day(1).unwrap_or(selected);
+        let offset = first.weekday().num_days_from_monday() as usize;
+        let mut day = first;
+        while day.month() == selected.month() {
+            let index = offset + day.day0() as usize;
+            let row = top + 2 + index / 7;
+            let col = left + 1 + (index % 7) * 3;
+            let mut attr = if day == selected { A_REVERSE } else { 0 };
+            if day == today {
+                attr |= A_UNDERLINE;
+            }
+            window.attron(attr);
+            window.mvaddstr(row as i32, col as i32, format!("{:>2}", day.day()));
+            window.attroff(attr);
+            match day.succ_opt() {
+                Some(next) => day = next,
+                None => break,
+            }
+        }
+    }
+}
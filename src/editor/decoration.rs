@@ -0,0 +1,284 @@
+use crate::editor::Editor;
+use crate::editor::due::overdue_annotation;
+use crate::editor::todo_keyword::find_keyword;
+use crate::editor::ui::{
+    DIM_COLOR_PAIR, KEYWORD_COLOR_PAIRS, OVERDUE_COLOR_PAIR, RenderAttributes,
+};
+use chrono::NaiveDate;
+use pancurses::{A_NORMAL, chtype};
+
+/// What the line transformers of one frame share.
+pub struct DecorationContext<'a> {
+    pub attributes: RenderAttributes,
+    pub today: NaiveDate,
+    pub todo_keywords: &'a [String],
+    pub monochrome: bool,
+}
+
+/// A byte range of a line drawn with an attribute, and in color a color pair,
+/// of its own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub attr: chtype,
+    pub color_pair: Option<i16>,
+}
+
+/// A byte range of a line drawn as `label` instead, away from the cursor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Replacement {
+    pub start: usize,
+    pub end: usize,
+    pub label: String,
+    pub attr: chtype,
+}
+
+/// How a line is drawn, built up by the line transformers in turn.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineDecorations {
+    // The attribute and color pair of the text outside the spans
+    pub line_attr: chtype,
+    pub line_color_pair: Option<i16>,
+    // Drawn as a horizontal rule instead of its text
+    pub rule: bool,
+    pub spans: Vec<Span>,
+    pub replacements: Vec<Replacement>,
+}
+
+impl Default for LineDecorations {
+    fn default() -> Self {
+        Self {
+            line_attr: A_NORMAL,
+            line_color_pair: None,
+            rule: false,
+            spans: Vec::new(),
+            replacements: Vec::new(),
+        }
+    }
+}
+
+impl LineDecorations {
+    /// The span over `byte_idx`; of overlapping spans, the one added first.
+    pub fn span_at(&self, byte_idx: usize) -> Option<&Span> {
+        self.spans
+            .iter()
+            .find(|span| (span.start..span.end).contains(&byte_idx))
+    }
+
+    pub fn replacement_at(&self, byte_idx: usize) -> Option<&Replacement> {
+        self.replacements
+            .iter()
+            .find(|replacement| (replacement.start..replacement.end).contains(&byte_idx))
+    }
+}
+
+/// Adds to the decorations of line `y` when it is drawn. Transformers run in
+/// the order they were added, so the spans of earlier ones take precedence.
+pub trait LineTransformer {
+    fn decorate(
+        &self,
+        editor: &Editor,
+        y: usize,
+        line: &str,
+        context: &DecorationContext,
+        decorations: &mut LineDecorations,
+    );
+}
+
+/// Comments, checked and unchecked tasks.
+pub struct LineKindStyle;
+
+impl LineTransformer for LineKindStyle {
+    fn decorate(
+        &self,
+        _editor: &Editor,
+        _y: usize,
+        line: &str,
+        context: &DecorationContext,
+        decorations: &mut LineDecorations,
+    ) {
+        let dim_pair = Some(DIM_COLOR_PAIR).filter(|_| !context.monochrome);
+        if line.trim_start().starts_with('#') {
+            decorations.line_attr = context.attributes.comment;
+            decorations.line_color_pair = dim_pair;
+        } else if Editor::is_checked_checkbox(line) {
+            decorations.line_attr = context.attributes.checked;
+            decorations.line_color_pair = dim_pair;
+        } else if Editor::is_unchecked_checkbox(line) {
+            decorations.line_attr = context.attributes.unchecked;
+            if !context.monochrome {
+                decorations.line_color_pair = Some(3);
+            }
+        }
+    }
+}
+
+/// `---` page separators, drawn across the screen.
+pub struct SeparatorRule;
+
+impl LineTransformer for SeparatorRule {
+    fn decorate(
+        &self,
+        _editor: &Editor,
+        _y: usize,
+        line: &str,
+        _context: &DecorationContext,
+        decorations: &mut LineDecorations,
+    ) {
+        decorations.rule = Editor::is_separator_line(line);
+    }
+}
+
+/// TODO keywords, each in a color of its own.
+pub struct TodoKeywordStyle;
+
+impl LineTransformer for TodoKeywordStyle {
+    fn decorate(
+        &self,
+        _editor: &Editor,
+        _y: usize,
+        line: &str,
+        context: &DecorationContext,
+        decorations: &mut LineDecorations,
+    ) {
+        if let Some((start, end, index)) = find_keyword(line, context.todo_keywords) {
+            decorations.spans.push(Span {
+                start,
+                end,
+                attr: context.attributes.todo_keyword,
+                color_pair: Some(KEYWORD_COLOR_PAIRS[index % KEYWORD_COLOR_PAIRS.len()]),
+            });
+        }
+    }
+}
+
+/// The `@due` date of unchecked tasks that are overdue.
+pub struct OverdueStyle;
+
+impl LineTransformer for OverdueStyle {
+    fn decorate(
+        &self,
+        _editor: &Editor,
+        _y: usize,
+        line: &str,
+        context: &DecorationContext,
+        decorations: &mut LineDecorations,
+    ) {
+        if let Some((start, end)) = overdue_annotation(line, context.today) {
+            decorations.spans.push(Span {
+                start,
+                end,
+                attr: context.attributes.overdue,
+                color_pair: Some(OVERDUE_COLOR_PAIR),
+            });
+        }
+    }
+}
+
+/// Words the spell checker doesn't know.
+pub struct MisspellingStyle;
+
+impl LineTransformer for MisspellingStyle {
+    fn decorate(
+        &self,
+        editor: &Editor,
+        _y: usize,
+        line: &str,
+        context: &DecorationContext,
+        decorations: &mut LineDecorations,
+    ) {
+        for (start, end) in editor.spelling.misspellings(line) {
+            decorations.spans.push(Span {
+                start,
+                end,
+                attr: context.attributes.misspelled,
+                color_pair: None,
+            });
+        }
+    }
+}
+
+/// The byte where the spaces and tabs at the end of `line` start.
+pub fn trailing_whitespace_start(line: &str) -> usize {
+    line.trim_end_matches([' ', '\t']).len()
+}
+
+/// Spaces and tabs at the end of lines, while whitespace is shown.
+pub struct TrailingWhitespace;
+
+impl LineTransformer for TrailingWhitespace {
+    fn decorate(
+        &self,
+        editor: &Editor,
+        _y: usize,
+        line: &str,
+        context: &DecorationContext,
+        decorations: &mut LineDecorations,
+    ) {
+        let start = trailing_whitespace_start(line);
+        if editor.settings.show_whitespace && start < line.len() {
+            decorations.spans.push(Span {
+                start,
+                end: line.len(),
+                attr: context.attributes.trailing_whitespace,
+                color_pair: None,
+            });
+        }
+    }
+}
+
+/// Images, long links and URLs shortened off the cursor line.
+pub struct Placeholders;
+
+impl LineTransformer for Placeholders {
+    fn decorate(
+        &self,
+        editor: &Editor,
+        y: usize,
+        _line: &str,
+        context: &DecorationContext,
+        decorations: &mut LineDecorations,
+    ) {
+        for placeholder in editor.line_placeholders(y) {
+            decorations.replacements.push(Replacement {
+                start: placeholder.start,
+                end: placeholder.end,
+                label: placeholder.label,
+                attr: context.attributes.placeholder,
+            });
+        }
+    }
+}
+
+/// The transformers every editor starts with.
+pub fn default_transformers() -> Vec<Box<dyn LineTransformer>> {
+    vec![
+        Box::new(LineKindStyle),
+        Box::new(SeparatorRule),
+        Box::new(TodoKeywordStyle),
+        Box::new(OverdueStyle),
+        Box::new(MisspellingStyle),
+        Box::new(TrailingWhitespace),
+        Box::new(Placeholders),
+    ]
+}
+
+impl Editor {
+    /// Adds a transformer that runs after the others on every drawn line.
+    pub fn add_line_transformer(&mut self, transformer: Box<dyn LineTransformer>) {
+        self.line_transformers.push(transformer);
+    }
+
+    /// The decorations of line `y` from all the transformers.
+    pub fn decorate_line(&self, y: usize, context: &DecorationContext) -> LineDecorations {
+        let mut decorations = LineDecorations::default();
+        let Some(line) = self.document.lines().get(y) else {
+            return decorations;
+        };
+        for transformer in &self.line_transformers {
+            transformer.decorate(self, y, line, context, &mut decorations);
+        }
+        decorations
+    }
+}
@@ -0,0 +1,128 @@
+use crate::document::LineBuffer;
+use crate::editor::Editor;
+use crate::editor::annotation::find_annotation;
+use crate::editor::date_picker::parse_due;
+use crate::editor::fold::DELIMITER;
+use crate::editor::heading::heading_level;
+use chrono::NaiveDate;
+use std::ops::Range;
+
+fn is_task(line: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with("- [ ] ") || line.starts_with("- [x] ")
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// The byte range of the `@due(...)` annotation of `line` when it is an
+/// unchecked task due before `today`.
+pub fn overdue_annotation(line: &str, today: NaiveDate) -> Option<(usize, usize)> {
+    if !line.trim_start().starts_with("- [ ] ") || parse_due(line)? >= today {
+        return None;
+    }
+    find_annotation(line, "due").map(|(start, end, _)| (start, end))
+}
+
+/// The lines of the section around `y`, between the headings or `---`
+/// delimiters before and after it.
+pub fn section_range(lines: &LineBuffer, y: usize) -> Range<usize> {
+    let is_boundary = |line: &String| line == DELIMITER || heading_level(line).is_some();
+    let start = lines
+        .range(..=y.min(lines.len() - 1))
+        .rposition(is_boundary)
+        .map_or(0, |boundary| boundary + 1);
+    let end = lines
+        .range(start..)
+        .position(is_boundary)
+        .map_or(lines.len(), |offset| start + offset);
+    start..end
+}
+
+/// The lines of `range` with its tasks in order of their `@due` date, those
+/// without one last. A task moves with the lines nested under it, and other
+/// lines stay where they are. Returns the lines and, for each line of `range`,
+/// where it went.
+pub fn sort_tasks_by_due(lines: &LineBuffer, range: Range<usize>) -> (Vec<String>, Vec<usize>) {
+    let section: Vec<&String> = lines.range(range.clone()).collect();
+    let task_indent = section
+        .iter()
+        .filter(|line| is_task(line))
+        .map(|line| indentation(line))
+        .min();
+
+    // Each item is a run of lines: a task with its nested lines, or another line
+    let mut items: Vec<Range<usize>> = Vec::new();
+    let mut y = 0;
+    while y < section.len() {
+        let line = &section[y];
+        let mut end = y + 1;
+        if is_task(line) && Some(indentation(line)) == task_indent {
+            while end < section.len()
+                && !section[end].trim().is_empty()
+                && indentation(section[end]) > indentation(line)
+            {
+                end += 1;
+            }
+        }
+        items.push(y..end);
+        y = end;
+    }
+
+    let is_sorted_task = |item: &Range<usize>| {
+        let line = &section[item.start];
+        is_task(line) && Some(indentation(line)) == task_indent
+    };
+    let mut tasks: Vec<Range<usize>> = items
+        .iter()
+        .filter(|item| is_sorted_task(item))
+        .cloned()
+        .collect();
+    // Stable, so tasks due the same day keep their order
+    tasks.sort_by_key(|task| {
+        let due = parse_due(section[task.start]);
+        (due.is_none(), due)
+    });
+
+    let mut sorted: Vec<String> = lines.range(..range.start).cloned().collect();
+    let mut moved_to = vec![0; section.len()];
+    let mut tasks = tasks.into_iter();
+    for item in items {
+        let item = if is_sorted_task(&item) {
+            tasks.next().unwrap_or(item)
+        } else {
+            item
+        };
+        for y in item {
+            moved_to[y] = sorted.len();
+            sorted.push(section[y].to_string());
+        }
+    }
+    sorted.extend(lines.range(range.end..).cloned());
+    (sorted, moved_to)
+}
+
+impl Editor {
+    /// Orders the tasks of the section under the cursor by due date, as one
+    /// undo step. The cursor stays on the line it was on.
+    pub fn sort_tasks_by_due(&mut self) {
+        let range = section_range(self.document.lines(), self.cursor.y);
+        if !self
+            .document
+            .lines()
+            .range(range.clone())
+            .any(|line| is_task(line))
+        {
+            self.set_message("No tasks in this section.");
+            return;
+        }
+        let (lines, moved_to) = sort_tasks_by_due(self.document.lines(), range.clone());
+        let cursor_y = match self.cursor.y.checked_sub(range.start) {
+            Some(offset) if self.cursor.y < range.end => moved_to[offset],
+            _ => self.cursor.y,
+        };
+        self.replace_lines(lines, (self.cursor.x, cursor_y));
+        self.set_message("Sorted tasks by due date.");
+    }
+}
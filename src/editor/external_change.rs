@@ -0,0 +1,154 @@
+use crate::editor::actions::Action;
+use crate::editor::{Editor, EditorMode};
+use log::debug;
+use pancurses::Input;
+use std::fs;
+use std::time::{Duration, Instant, SystemTime};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+pub const SAVE_CONFLICT_PROMPT: &str =
+    "The file changed on disk since it was opened. (o)verwrite it, (r)eload it, or (c)ancel?";
+
+/// What is known about changes made to the open file by other programs.
+#[derive(Default, Debug)]
+pub struct ExternalChange {
+    last_poll: Option<Instant>,
+    // Modification time of the file when it was last read
+    seen_modified: Option<SystemTime>,
+    // What is on disk, if it differs from what the buffer was loaded or saved as
+    disk_content: Option<String>,
+    // The save or quit waiting for the user to resolve a conflict
+    pending_action: Option<Action>,
+}
+
+impl ExternalChange {
+    pub fn is_changed(&self) -> bool {
+        self.disk_content.is_some()
+    }
+}
+
+impl Editor {
+    /// Called from the main loop; warns once when another program changes the
+    /// file.
+    pub fn external_change_tick(&mut self) {
+        if self
+            .external_change
+            .last_poll
+            .is_some_and(|last_poll| last_poll.elapsed() < POLL_INTERVAL)
+        {
+            return;
+        }
+        self.external_change.last_poll = Some(Instant::now());
+        if self.poll_disk() {
+            self.set_message("The file changed on disk; saving will ask before overwriting it.");
+        }
+    }
+
+    /// Compares the file on disk with what the buffer was loaded or saved as,
+    /// reading it only when its modification time changed. Returns whether a
+    /// change not seen before was found.
+    pub(super) fn poll_disk(&mut self) -> bool {
+        // An autosave in flight is our own change
+        if self.read_only || self.autosave.is_pending() {
+            return false;
+        }
+        let Some(filename) = &self.document.filename else {
+            return false;
+        };
+        let Ok(modified) = fs::metadata(filename).and_then(|metadata| metadata.modified()) else {
+            return false;
+        };
+        if self.external_change.seen_modified == Some(modified) {
+            return false;
+        }
+        let Ok(content) = fs::read_to_string(filename) else {
+            return false;
+        };
+        self.external_change.seen_modified = Some(modified);
+        let original = self.document.original_content().unwrap_or_default();
+        if content.lines().eq(original.lines()) {
+            self.external_change.disk_content = None;
+            return false;
+        }
+        debug!("{filename} changed on disk.");
+        let is_new = self.external_change.disk_content.as_ref() != Some(&content);
+        self.external_change.disk_content = Some(content);
+        is_new
+    }
+
+    /// Asks how to go on if `action` would save over changes made on disk.
+    /// Returns whether the action has to wait for the answer.
+    pub(super) fn prompt_on_save_conflict(&mut self, action: &Action) -> bool {
+        if !matches!(action, Action::Save | Action::Quit) || self.read_only {
+            return false;
+        }
+        self.poll_disk();
+        if !self.external_change.is_changed() {
+            return false;
+        }
+        self.external_change.pending_action = Some(action.clone());
+        self.mode = EditorMode::SaveConflict;
+        self.set_message(SAVE_CONFLICT_PROMPT);
+        true
+    }
+
+    pub fn handle_save_conflict_input(&mut self, key: Input) {
+        match key {
+            Input::Character('o') | Input::Character('O') => {
+                self.mode = EditorMode::Normal;
+                let action = self.external_change.pending_action.take();
+                self.external_change.disk_content = None;
+                if let Some(action) = action
+                    && let Err(e) = self.execute_action(action)
+                {
+                    self.set_message(&format!("Could not save: {e}"));
+                }
+            }
+            Input::Character('r') | Input::Character('R') => {
+                self.mode = EditorMode::Normal;
+                self.external_change.pending_action = None;
+                self.reload_from_disk();
+            }
+            Input::Character('c' | 'C' | '\x07' | '\x1b') => {
+                self.mode = EditorMode::Normal;
+                self.external_change.pending_action = None;
+                self.set_message("Save cancelled.");
+            }
+            _ => self.set_message(SAVE_CONFLICT_PROMPT),
+        }
+    }
+
+    /// Replaces the buffer with the file on disk as one undo step, so the
+    /// discarded text can still be brought back with undo. Only the lines
+    /// that differ are replaced, and the cursor stays on its line.
+    pub fn reload_from_disk(&mut self) {
+        let Some(filename) = self.document.filename.clone() else {
+            self.set_message("The buffer has no file to reload.");
+            return;
+        };
+        self.finish_pending_autosave();
+        let content = match fs::read_to_string(&filename) {
+            Ok(content) => content,
+            Err(e) => {
+                self.set_message(&format!("Could not reload {filename}: {e}"));
+                return;
+            }
+        };
+        let mut lines: Vec<String> = content.lines().map(String::from).collect();
+        if lines.is_empty() {
+            lines.push(String::new());
+        }
+        self.merge_lines(lines);
+        self.document.mark_saved(content);
+        self.clear_external_change();
+        self.reset_journal();
+        self.set_message(&format!("Reloaded {filename}."));
+    }
+
+    /// Forgets about changes on disk once the buffer has been written over
+    /// them or replaced.
+    pub(super) fn clear_external_change(&mut self) {
+        self.external_change = Default::default();
+    }
+}
@@ -0,0 +1,221 @@
+use crate::editor::{Editor, EditorMode};
+use crate::screen::Screen;
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use log::error;
+use once_cell::sync::Lazy;
+use pancurses::{A_BOLD, A_REVERSE, Input};
+use std::fs;
+use std::path::{Path, PathBuf};
+use unicode_width::UnicodeWidthStr;
+
+static MATCHER: Lazy<SkimMatcherV2> = Lazy::new(SkimMatcherV2::default);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DirEntry {
+    pub name: String,
+    pub is_dir: bool,
+}
+
+/// Listing of a directory to pick a file to open from, narrowed down by a
+/// fuzzy query.
+#[derive(Debug, Default)]
+pub struct FileBrowser {
+    pub dir: PathBuf,
+    pub entries: Vec<DirEntry>,
+    pub query: String,
+    // Indices into `entries` that match the query, best first
+    pub matches: Vec<usize>,
+    pub selected_index: usize,
+    pub scroll_offset: usize,
+}
+
+impl FileBrowser {
+    pub fn new(dir: PathBuf) -> Self {
+        let mut browser = Self::default();
+        browser.change_dir(dir);
+        browser
+    }
+
+    pub fn selected_entry(&self) -> Option<&DirEntry> {
+        self.matches
+            .get(self.selected_index)
+            .map(|&index| &self.entries[index])
+    }
+
+    pub fn change_dir(&mut self, dir: PathBuf) {
+        self.entries = read_entries(&dir);
+        self.dir = dir;
+        self.query.clear();
+        self.update_matches();
+    }
+
+    pub fn update_matches(&mut self) {
+        // Hidden files only show up once the query asks for them
+        let show_hidden = self.query.starts_with('.');
+        let visible = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| show_hidden || !entry.name.starts_with('.'));
+        self.matches = if self.query.is_empty() {
+            visible.map(|(i, _)| i).collect()
+        } else {
+            let mut scored: Vec<(i64, usize)> = visible
+                .filter_map(|(i, entry)| {
+                    MATCHER
+                        .fuzzy_match(&entry.name, &self.query)
+                        .map(|score| (score, i))
+                })
+                .collect();
+            scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+            scored.into_iter().map(|(_, i)| i).collect()
+        };
+        self.selected_index = 0;
+        self.scroll_offset = 0;
+    }
+}
+
+/// Directories first, then files, each sorted by name.
+fn read_entries(dir: &Path) -> Vec<DirEntry> {
+    let mut entries: Vec<DirEntry> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .flatten()
+            .filter_map(|entry| {
+                Some(DirEntry {
+                    name: entry.file_name().into_string().ok()?,
+                    is_dir: entry.path().is_dir(),
+                })
+            })
+            .collect(),
+        Err(e) => {
+            error!("Failed to read directory {}: {e}", dir.display());
+            Vec::new()
+        }
+    };
+    entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+    entries
+}
+
+impl Editor {
+    /// Opens the file browser in the directory of the current file.
+    pub fn open_file_browser(&mut self) {
+        let dir = self
+            .document
+            .filename
+            .as_deref()
+            .and_then(|filename| Path::new(filename).parent())
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .map(Path::to_path_buf)
+            .or_else(|| std::env::current_dir().ok())
+            .unwrap_or_else(|| PathBuf::from("."));
+        self.file_browser = FileBrowser::new(dir);
+        self.mode = EditorMode::FileBrowser;
+        self.set_message("Enter opens, Backspace goes up, type to filter or name a new file.");
+    }
+
+    pub fn handle_file_browser_input(&mut self, key: Input) {
+        let browser = &mut self.file_browser;
+        let count = browser.matches.len();
+        match key {
+            Input::KeyUp | Input::Character('\x10') if count > 0 => {
+                browser.selected_index = (browser.selected_index + count - 1) % count;
+            }
+            Input::KeyDown | Input::Character('\x0e') if count > 0 => {
+                browser.selected_index = (browser.selected_index + 1) % count;
+            }
+            Input::Character('\n') | Input::Character('\r') => self.open_selected_entry(),
+            Input::KeyBackspace | Input::Character('\x7f') | Input::Character('\x08') => {
+                if browser.query.pop().is_some() {
+                    browser.update_matches();
+                } else if let Some(parent) = browser.dir.parent() {
+                    let parent = parent.to_path_buf();
+                    browser.change_dir(parent);
+                }
+            }
+            // Esc or Ctrl-G clear the query first, then close
+            Input::Character('\x1b') | Input::Character('\x07') => {
+                if browser.query.is_empty() {
+                    self.close_file_browser();
+                } else {
+                    browser.query.clear();
+                    browser.update_matches();
+                }
+            }
+            Input::Character(c) if !c.is_control() && c != '/' => {
+                browser.query.push(c);
+                browser.update_matches();
+            }
+            _ => {}
+        }
+    }
+
+    fn open_selected_entry(&mut self) {
+        let browser = &mut self.file_browser;
+        let path = match browser.selected_entry().cloned() {
+            Some(DirEntry { name, is_dir: true }) => {
+                let dir = browser.dir.join(name);
+                browser.change_dir(dir);
+                return;
+            }
+            Some(DirEntry { name, .. }) => browser.dir.join(name),
+            // Nothing matches: the query names a new file
+            None if !browser.query.is_empty() => browser.dir.join(&browser.query),
+            None => return,
+        };
+        self.close_file_browser();
+        let filename = path.to_string_lossy().to_string();
+        if let Err(e) = self.open_file(&filename) {
+            self.set_message(&format!("Could not open {filename}: {e}"));
+        }
+    }
+
+    fn close_file_browser(&mut self) {
+        self.file_browser = FileBrowser::default();
+        self.mode = EditorMode::Normal;
+    }
+
+    pub fn draw_file_browser(&mut self, window: &impl Screen) {
+        window.erase();
+        let screen_rows = window.get_max_y() as usize;
+        let list_height = screen_rows.saturating_sub(1);
+        let browser = &mut self.file_browser;
+
+        if browser.selected_index < browser.scroll_offset {
+            browser.scroll_offset = browser.selected_index;
+        }
+        if browser.selected_index >= browser.scroll_offset + list_height {
+            browser.scroll_offset = browser.selected_index + 1 - list_height;
+        }
+
+        let offset = browser.scroll_offset;
+        for (row, &index) in browser
+            .matches
+            .iter()
+            .skip(offset)
+            .take(list_height)
+            .enumerate()
+        {
+            let entry = &browser.entries[index];
+            let is_selected = offset + row == browser.selected_index;
+            let attr = match (is_selected, entry.is_dir) {
+                (true, _) => A_REVERSE,
+                (false, true) => A_BOLD,
+                (false, false) => 0,
+            };
+            let suffix = if entry.is_dir { "/" } else { "" };
+            window.attron(attr);
+            window.mvaddstr(row as i32, 0, format!("{}{suffix}", entry.name));
+            window.attroff(attr);
+        }
+
+        let mut dir = browser.dir.to_string_lossy().to_string();
+        if !dir.ends_with('/') {
+            dir.push('/');
+        }
+        let prompt = format!("OPEN: {dir}{}", browser.query);
+        window.mvaddstr(screen_rows as i32 - 1, 0, &prompt);
+        window.mv(screen_rows as i32 - 1, prompt.width() as i32);
+        window.refresh();
+    }
+}
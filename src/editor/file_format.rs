@@ -0,0 +1,74 @@
+use crate::document::LineEnding;
+use crate::editor::{Editor, EditorMode};
+
+impl Editor {
+    /// Line ending from the `line_ending` setting, if one is configured.
+    pub fn configured_line_ending(&self) -> Option<LineEnding> {
+        let name = self.settings.line_ending.as_deref()?;
+        let ending = LineEnding::parse(name);
+        if ending.is_none() {
+            log::warn!("Unknown line_ending setting: {name}");
+        }
+        ending
+    }
+
+    /// Problems with the file's format that the editor leaves alone until
+    /// the user asks for a fix.
+    pub fn file_format_warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        let original = self.document.original_content().unwrap_or_default();
+        let crlf = original.matches("\r\n").count();
+        if crlf > 0 && crlf < original.matches('\n').count() {
+            warnings.push(format!(
+                "File mixes LF and CRLF line endings; saving writes {}.",
+                self.document.line_ending.name()
+            ));
+        } else if let Some(ending) = self.configured_line_ending()
+            && self.document.filename.is_some()
+            && ending != self.document.line_ending
+        {
+            warnings.push(format!(
+                "File uses {} line endings; /cleanup converts them to {}.",
+                self.document.line_ending.name(),
+                ending.name()
+            ));
+        }
+
+        if let Some(max_bytes) = self.settings.max_line_bytes {
+            let mut long_lines = self
+                .document
+                .lines()
+                .iter()
+                .enumerate()
+                .filter(|(_, line)| line.len() > max_bytes);
+            if let Some((first, _)) = long_lines.next() {
+                let count = long_lines.count() + 1;
+                let others = if count > 1 {
+                    format!(" ({count} lines in total)")
+                } else {
+                    String::new()
+                };
+                warnings.push(format!(
+                    "Line {} is longer than {max_bytes} bytes{others}.",
+                    first + 1
+                ));
+            }
+        }
+        warnings
+    }
+
+    /// Shows the file format warnings, unless another prompt is waiting.
+    pub fn warn_about_file_format(&mut self) {
+        let warnings = self.file_format_warnings();
+        if !warnings.is_empty() && self.mode == EditorMode::Normal {
+            self.set_message(&warnings.join(" "));
+        }
+    }
+
+    /// `message` followed by any file format warnings.
+    pub(super) fn with_file_format_warnings(&self, message: &str) -> String {
+        let mut parts = vec![message.to_string()];
+        parts.extend(self.file_format_warnings());
+        parts.join(" ")
+    }
+}
@@ -0,0 +1,44 @@
+use crate::config::{self, BUILTIN_THEMES, EditorSettings};
+use crate::editor::Editor;
+use crate::file_settings::FileSettings;
+
+/// The file settings in effect and the user's own values of the settings
+/// they replaced.
+#[derive(Debug, Default)]
+pub struct AppliedFileSettings {
+    settings: FileSettings,
+    user: Option<EditorSettings>,
+}
+
+impl Editor {
+    /// Makes the settings the current file gives for itself take effect in
+    /// place of the user's, putting back those an earlier file or an earlier
+    /// version of this one replaced. Settings switched while editing stay
+    /// as they are as long as the file settings do not change.
+    pub fn apply_file_settings(&mut self) {
+        let file = self.document.file_settings.clone();
+        if file == self.file_settings.settings {
+            return;
+        }
+        let theme = self.settings.theme.clone();
+        if let Some(user) = self.file_settings.user.take() {
+            self.file_settings
+                .settings
+                .restore(&mut self.settings, &user);
+        }
+        if !file.is_empty() {
+            self.file_settings.user = Some(self.settings.clone());
+            file.apply(&mut self.settings);
+        }
+        self.file_settings.settings = file;
+
+        if self.settings.theme != theme {
+            // Without a theme of its own the user gets the default colors back
+            let name = self.settings.theme.as_deref().unwrap_or(BUILTIN_THEMES[0]);
+            match config::theme_colors(name) {
+                Some(colors) => self.pending_colors = Some(colors),
+                None => self.set_message(&format!("Could not load the theme {name}.")),
+            }
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use crate::document::{ActionDiff, LineBuffer};
+use crate::editor::Editor;
+use crate::editor::heading::heading_level;
+use std::ops::Range;
+
+pub(super) const DELIMITER: &str = "---";
+
+/// Heading and `---` lines whose sections are collapsed, by line index.
+#[derive(Debug, Default)]
+pub struct Folds {
+    headers: Vec<usize>,
+}
+
+impl Folds {
+    pub fn is_empty(&self) -> bool {
+        self.headers.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.headers.clear();
+    }
+
+    pub fn is_folded(&self, y: usize) -> bool {
+        self.headers.binary_search(&y).is_ok()
+    }
+
+    pub fn headers(&self) -> &[usize] {
+        &self.headers
+    }
+
+    pub(super) fn fold(&mut self, y: usize) {
+        if let Err(index) = self.headers.binary_search(&y) {
+            self.headers.insert(index, y);
+        }
+    }
+
+    fn unfold(&mut self, y: usize) {
+        self.headers.retain(|&header| header != y);
+    }
+
+    /// Keeps the folds on their lines when an edit adds or removes lines above
+    /// them. A fold whose header line was removed is dropped.
+    pub fn adjust(&mut self, diff: &ActionDiff) {
+        let removed = diff.old.len().saturating_sub(1);
+        let added = diff.new.len().saturating_sub(1);
+        let y = diff.start_y;
+        self.headers.retain_mut(|header| {
+            if *header <= y {
+                true
+            } else if *header <= y + removed {
+                false
+            } else {
+                *header = *header + added - removed;
+                true
+            }
+        });
+    }
+}
+
+/// The lines that folding the heading or `---` delimiter at `y` hides: a
+/// heading's section ends at the next heading of the same or a higher level
+/// or at a delimiter, a delimiter's page at the next delimiter.
+pub fn fold_range(lines: &LineBuffer, y: usize) -> Option<Range<usize>> {
+    let line = lines.get(y)?;
+    let rest = lines.iter().enumerate().skip(y + 1);
+    let end = if line == DELIMITER {
+        rest.clone()
+            .find(|(_, line)| *line == DELIMITER)
+            .map_or(lines.len(), |(index, _)| index)
+    } else {
+        let level = heading_level(line)?;
+        rest.clone()
+            .find(|(_, line)| {
+                *line == DELIMITER || heading_level(line).is_some_and(|other| other <= level)
+            })
+            .map_or(lines.len(), |(index, _)| index)
+    };
+    (end > y + 1).then_some(y + 1..end)
+}
+
+impl Editor {
+    /// The ranges of lines hidden by folds, in order. A fold inside another
+    /// folded section adds nothing.
+    pub fn hidden_ranges(&self) -> Vec<Range<usize>> {
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        for &header in &self.folds.headers {
+            if ranges.last().is_some_and(|last| last.contains(&header)) {
+                continue;
+            }
+            if let Some(range) = fold_range(self.document.lines(), header) {
+                ranges.push(range);
+            }
+        }
+        ranges
+    }
+
+    fn hidden_range_at(&self, y: usize) -> Option<Range<usize>> {
+        self.hidden_ranges()
+            .into_iter()
+            .find(|range| range.contains(&y))
+    }
+
+    pub fn is_line_hidden(&self, y: usize) -> bool {
+        self.hidden_range_at(y).is_some()
+    }
+
+    /// The screen row of line `y` counted from the top of the document, with
+    /// folded lines taking no rows.
+    pub fn visible_row(&self, y: usize) -> usize {
+        let hidden: usize = self
+            .hidden_ranges()
+            .iter()
+            .map(|range| range.end.min(y).saturating_sub(range.start))
+            .sum();
+        y - hidden
+    }
+
+    /// The line shown on visible row `row`, the inverse of `visible_row`.
+    pub fn line_at_visible_row(&self, row: usize) -> usize {
+        let mut y = row;
+        for range in self.hidden_ranges() {
+            if range.start > y {
+                break;
+            }
+            y += range.len();
+        }
+        y.min(self.document.lines().len().saturating_sub(1))
+    }
+
+    /// Folds the section under the cursor, or unfolds it if it is folded.
+    /// Inside a section, the closest heading or delimiter above is folded.
+    pub fn toggle_fold(&mut self) {
+        if self.folds.is_folded(self.cursor.y) {
+            self.folds.unfold(self.cursor.y);
+            self.set_message("Unfolded.");
+            return;
+        }
+        let lines = self.document.lines();
+        let header = (0..=self.cursor.y).rev().find(|&y| {
+            fold_range(lines, y)
+                .is_some_and(|range| y == self.cursor.y || range.contains(&self.cursor.y))
+        });
+        let Some(header) = header else {
+            self.set_message("Nothing to fold here.");
+            return;
+        };
+        self.folds.fold(header);
+        if header != self.cursor.y {
+            self.cursor.y = header;
+            self.cursor.x = 0;
+            self.cursor.desired_x = 0;
+        }
+        self.set_message("Folded.");
+    }
+
+    pub fn fold_all(&mut self) {
+        let headers: Vec<usize> = (0..self.document.lines().len())
+            .filter(|&y| fold_range(self.document.lines(), y).is_some())
+            .collect();
+        if headers.is_empty() {
+            self.set_message("Nothing to fold.");
+            return;
+        }
+        for &header in &headers {
+            self.folds.fold(header);
+        }
+        self.snap_to_fold_header();
+        self.set_message(&format!("Folded {} sections.", headers.len()));
+    }
+
+    pub fn unfold_all(&mut self) {
+        self.folds.clear();
+        self.set_message("Unfolded all sections.");
+    }
+
+    /// Unfolds whatever hides the cursor line, e.g. after a search or a jump
+    /// to a line inside a folded section.
+    pub(super) fn reveal_cursor_line(&mut self) {
+        while let Some(range) = self.hidden_range_at(self.cursor.y) {
+            self.folds.unfold(range.start - 1);
+        }
+    }
+
+    /// Moves the cursor from a folded line up to the header it is folded under.
+    pub(super) fn snap_to_fold_header(&mut self) {
+        if let Some(range) = self.hidden_range_at(self.cursor.y) {
+            self.cursor.y = range.start - 1;
+            self.scroll.clamp_cursor_x(&mut self.cursor, &self.document);
+        }
+    }
+
+    /// After a vertical move onto a folded line, carries on past the folded
+    /// lines in the direction of the move. Moving down from the header of a
+    /// fold that reaches the end of the file stays at `previous_y`. Returns
+    /// whether the cursor was moved.
+    pub(super) fn skip_folded_lines(&mut self, previous_y: usize, down: bool) -> bool {
+        let Some(range) = self.hidden_range_at(self.cursor.y) else {
+            return false;
+        };
+        self.cursor.y = if !down {
+            range.start - 1
+        } else if range.end < self.document.lines().len() {
+            range.end
+        } else {
+            previous_y
+        };
+        true
+    }
+}
@@ -0,0 +1,151 @@
+use crate::document::Document;
+use crate::editor::Editor;
+use crate::editor::git_gutter::GitGutter;
+use log::debug;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::time::{Duration, Instant};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Where `tail -f` style following has got to in the file.
+pub struct Follow {
+    // Bytes of the file already in the buffer
+    position: u64,
+    // The last line in the buffer has no line break yet
+    partial_line: bool,
+    last_poll: Instant,
+}
+
+impl Follow {
+    fn new(content: &str) -> Self {
+        Self {
+            position: content.len() as u64,
+            partial_line: !content.is_empty() && !content.ends_with('\n'),
+            last_poll: Instant::now(),
+        }
+    }
+}
+
+impl Editor {
+    /// Makes the buffer read-only and starts appending whatever is written to
+    /// the end of the file, keeping the view at the bottom.
+    pub fn enable_follow(&mut self) {
+        self.read_only = true;
+        let content = self
+            .document
+            .original_content()
+            .unwrap_or_default()
+            .to_string();
+        self.follow = Some(Follow::new(&content));
+        self.go_to_end_of_file();
+        self.go_to_start_of_line();
+    }
+
+    pub fn follow_tick(&mut self) {
+        if let Some(follow) = &self.follow
+            && follow.last_poll.elapsed() >= POLL_INTERVAL
+        {
+            self.poll_followed_file();
+        }
+    }
+
+    /// Reads the lines appended to the file since the last poll. Only complete
+    /// lines are taken, so a line that is still being written shows up whole.
+    pub fn poll_followed_file(&mut self) {
+        let Some(filename) = self.document.filename.clone() else {
+            return;
+        };
+        let Some(follow) = &mut self.follow else {
+            return;
+        };
+        follow.last_poll = Instant::now();
+        let Ok(len) = std::fs::metadata(&filename).map(|metadata| metadata.len()) else {
+            return;
+        };
+        if len < follow.position {
+            self.reload_followed_file(&filename);
+            return;
+        }
+        if len == follow.position {
+            return;
+        }
+
+        let mut appended = Vec::new();
+        let read = File::open(&filename).and_then(|mut file| {
+            file.seek(SeekFrom::Start(follow.position))?;
+            file.take(len - follow.position).read_to_end(&mut appended)
+        });
+        if let Err(e) = read {
+            debug!("Could not read appended lines of {filename}: {e}");
+            return;
+        }
+        let Some(last_break) = appended.iter().rposition(|&b| b == b'\n') else {
+            return;
+        };
+        appended.truncate(last_break + 1);
+        follow.position += appended.len() as u64;
+        let text = String::from_utf8_lossy(&appended).into_owned();
+        let mut new_lines: Vec<String> = text.lines().map(str::to_string).collect();
+        let partial_line = std::mem::replace(&mut follow.partial_line, false);
+
+        let at_end = self.cursor.y + 1 >= self.document.lines().len();
+        let lines = self.document.lines();
+        let mut start = lines.len();
+        // A partial last line is completed by the first appended one
+        if partial_line && let (Some(last), Some(first)) = (lines.last(), new_lines.first_mut()) {
+            first.insert_str(0, last);
+            start -= 1;
+        }
+        let added = new_lines.len() - partial_line as usize;
+        // An empty file is shown as one empty line, which the first line replaces
+        if self
+            .document
+            .original_content()
+            .unwrap_or_default()
+            .is_empty()
+        {
+            start = 0;
+        }
+        if start == 0 && new_lines.is_empty() {
+            new_lines.push(String::new());
+        }
+        self.document
+            .replace_lines(start..self.document.lines().len(), new_lines);
+        // Appended text is not an edit
+        self.document.mark_appended(&text);
+
+        if at_end {
+            self.go_to_end_of_file();
+            self.go_to_start_of_line();
+        } else if added > 0 {
+            self.set_message(&format!(
+                "{added} new line{} below. Go to the end of the file to follow again.",
+                if added == 1 { "" } else { "s" }
+            ));
+        }
+    }
+
+    /// Starts over when the file was truncated or replaced, e.g. by log rotation.
+    fn reload_followed_file(&mut self, filename: &str) {
+        match Document::open(filename) {
+            Ok(document) => {
+                let content = document.original_content().unwrap_or_default().to_string();
+                self.document = document;
+                // Its revisions start over, so the hunks are found afresh
+                self.git_gutter = GitGutter::default();
+                self.folds.clear();
+                self.tag_index = None;
+                self.pages.clear();
+                self.completion.index.clear();
+                self.snippet_stops = None;
+                self.apply_file_settings();
+                self.follow = Some(Follow::new(&content));
+                self.go_to_end_of_file();
+                self.go_to_start_of_line();
+                self.set_message(&format!("{filename} was truncated; reloaded."));
+            }
+            Err(e) => debug!("Could not reload {filename}: {e}"),
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use crate::document::{ActionDiff, LineBuffer};
+use crate::editor::{Editor, LastActionType};
+
+/// The `[^label]` references of `line` as byte ranges with their label. The
+/// label of a definition, `[^label]: text`, is not a reference.
+pub fn references(line: &str) -> Vec<(usize, usize, &str)> {
+    let mut found = Vec::new();
+    let mut from = 0;
+    while let Some(offset) = line[from..].find("[^") {
+        let start = from + offset;
+        let Some(close) = line[start + 2..].find(']').map(|close| start + 2 + close) else {
+            break;
+        };
+        let label = &line[start + 2..close];
+        from = close + 1;
+        let is_definition = start == 0 && line[close + 1..].starts_with(':');
+        if !label.is_empty() && !label.contains(char::is_whitespace) && !is_definition {
+            found.push((start, close + 1, label));
+        }
+    }
+    found
+}
+
+/// The label and the text of `line` when it defines a footnote.
+pub fn definition(line: &str) -> Option<(&str, &str)> {
+    let rest = line.strip_prefix("[^")?;
+    let close = rest.find("]:")?;
+    let label = &rest[..close];
+    if label.is_empty() || label.contains(char::is_whitespace) {
+        return None;
+    }
+    Some((label, rest[close + 2..].trim()))
+}
+
+/// The number after the highest numbered footnote of `lines`.
+pub fn next_number(lines: &LineBuffer) -> usize {
+    lines
+        .iter()
+        .flat_map(|line| {
+            let defined = definition(line).map(|(label, _)| label);
+            references(line)
+                .into_iter()
+                .map(|(_, _, label)| label)
+                .chain(defined)
+                .collect::<Vec<_>>()
+        })
+        .filter_map(|label| label.parse::<usize>().ok())
+        .max()
+        .unwrap_or(0)
+        + 1
+}
+
+impl Editor {
+    /// The label of the footnote reference the cursor is on.
+    fn footnote_reference_at_cursor(&self) -> Option<&str> {
+        let x = self.cursor.x;
+        references(&self.document.lines()[self.cursor.y])
+            .into_iter()
+            .find(|&(start, end, _)| (start..end).contains(&x))
+            .map(|(_, _, label)| label)
+    }
+
+    fn footnote_definition_line(&self, label: &str) -> Option<usize> {
+        self.document
+            .lines()
+            .iter()
+            .position(|line| definition(line).is_some_and(|(defined, _)| defined == label))
+    }
+
+    /// `[^label]: text` when the cursor is on a reference to a defined
+    /// footnote, for the status bar.
+    pub fn footnote_at_cursor(&self) -> Option<String> {
+        let label = self.footnote_reference_at_cursor()?;
+        let line = &self.document.lines()[self.footnote_definition_line(label)?];
+        let (_, text) = definition(line)?;
+        Some(format!("[^{label}]: {text}"))
+    }
+
+    /// Goes from a footnote reference to its definition, or from a definition
+    /// to the first reference to it.
+    pub fn follow_footnote(&mut self) {
+        let origin = self.cursor_pos();
+        let target = if let Some(label) = self.footnote_reference_at_cursor() {
+            let label = label.to_string();
+            match self.footnote_definition_line(&label) {
+                Some(y) => (label.len() + "[^]: ".len(), y),
+                None => {
+                    self.set_message(&format!("Footnote [^{label}] is not defined."));
+                    return;
+                }
+            }
+        } else if let Some((label, _)) = definition(&self.document.lines()[self.cursor.y]) {
+            let label = label.to_string();
+            let reference = self
+                .document
+                .lines()
+                .iter()
+                .enumerate()
+                .find_map(|(y, line)| {
+                    references(line)
+                        .into_iter()
+                        .find(|&(_, _, found)| found == label)
+                        .map(|(start, _, _)| (start, y))
+                });
+            match reference {
+                Some(position) => position,
+                None => {
+                    self.set_message(&format!("Footnote [^{label}] is not referenced."));
+                    return;
+                }
+            }
+        } else {
+            self.set_message("No footnote at the cursor.");
+            return;
+        };
+        let (x, y) = target;
+        let x = x.min(self.document.lines()[y].len());
+        self.set_cursor_pos(x, y);
+        self.cursor.desired_x = self
+            .scroll
+            .get_display_width_from_bytes(&self.document.lines()[y], x);
+        self.snap_to_fold_header();
+        self.record_jump(origin);
+    }
+
+    /// Inserts a reference to the next numbered footnote at the cursor and
+    /// its definition at the end of the file, where the cursor goes to write
+    /// it. Both are one undo step.
+    pub fn insert_footnote(&mut self) {
+        let number = next_number(self.document.lines());
+        let reference = format!("[^{number}]");
+        let (x, y) = self.cursor_pos();
+        self.replace_in_line(y, x, x, &reference);
+        self.set_cursor_pos(x + reference.len(), y);
+        let origin = self.cursor_pos();
+
+        let last_y = self.document.lines().len() - 1;
+        let last_line = &self.document.lines()[last_y];
+        let definition_line = format!("[^{number}]: ");
+        // Definitions are kept together, after a blank line
+        let new = if last_line.is_empty() {
+            vec![definition_line]
+        } else if definition(last_line).is_some() {
+            vec![String::new(), definition_line]
+        } else {
+            vec![String::new(), String::new(), definition_line]
+        };
+        let start_x = last_line.len();
+        let end_y = last_y + new.len() - 1;
+        let end_x = match new.as_slice() {
+            [only] => start_x + only.len(),
+            _ => new.last().map_or(0, String::len),
+        };
+        self.commit(
+            LastActionType::Ammend,
+            &ActionDiff {
+                cursor_start_x: origin.0,
+                cursor_start_y: origin.1,
+                cursor_end_x: end_x,
+                cursor_end_y: end_y,
+                start_x,
+                start_y: last_y,
+                end_x,
+                end_y,
+                new,
+                old: vec![],
+            },
+        );
+        self.record_jump(origin);
+    }
+}
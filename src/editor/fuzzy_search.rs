@@ -2,7 +2,21 @@ use fuzzy_matcher::FuzzyMatcher;
 use fuzzy_matcher::skim::SkimMatcherV2;
 use once_cell::sync::Lazy;
 
+use pancurses::{A_REVERSE, Input};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use unicode_width::UnicodeWidthStr;
+
 use crate::document::Document;
+use crate::editor::agenda::truncate_to_width;
+use crate::editor::cursor::Cursor;
+use crate::editor::{Editor, EditorMode};
+use crate::inbox;
+use crate::screen::Screen;
 
 static MATCHER: Lazy<SkimMatcherV2> = Lazy::new(SkimMatcherV2::default);
 
@@ -29,8 +43,7 @@ impl FuzzySearch {
     pub fn handle_input(
         &mut self,
         key: pancurses::Input,
-        cursor_y: &mut usize,
-        cursor_x: &mut usize,
+        cursor: &mut Cursor,
         document: &Document,
     ) -> bool {
         match key {
@@ -40,8 +53,8 @@ impl FuzzySearch {
             }
             pancurses::Input::Character('\n') => {
                 if let Some((_, line_number)) = self.matches.get(self.selected_index) {
-                    *cursor_y = *line_number;
-                    *cursor_x = 0;
+                    cursor.y = *line_number;
+                    cursor.x = 0;
                 }
                 self.reset();
                 return false; // Exit fuzzy search
@@ -83,14 +96,14 @@ impl FuzzySearch {
     pub fn update_matches(&mut self, document: &Document) {
         if self.query.is_empty() {
             self.matches = document
-                .lines
+                .lines()
                 .iter()
                 .enumerate()
                 .map(|(i, line)| (line.clone(), i))
                 .collect();
         } else {
             self.matches = document
-                .lines
+                .lines()
                 .iter()
                 .enumerate()
                 .filter_map(|(i, line)| {
@@ -103,3 +116,394 @@ impl FuzzySearch {
         self.selected_index = 0;
     }
 }
+
+// Enough to pick from; more only makes the list longer to draw
+const MAX_PROJECT_MATCHES: usize = 1000;
+// Larger files are seldom text worth searching and slow the scan down
+const MAX_PROJECT_FILE_BYTES: u64 = 1024 * 1024;
+
+/// A line of a file under the project root that matches the query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectMatch {
+    pub path: PathBuf,
+    pub line_number: usize,
+    // Character index of the first matched character
+    pub column: usize,
+    pub line: String,
+    score: i64,
+}
+
+/// A pattern of a `.gitignore`, for the paths under `base`, the directory the
+/// file is in relative to the root.
+#[derive(Debug)]
+struct IgnoreRule {
+    base: String,
+    pattern: Vec<char>,
+    negated: bool,
+    dir_only: bool,
+    // Matched against the path under `base` rather than the file name
+    anchored: bool,
+}
+
+impl IgnoreRule {
+    fn parse(base: &str, line: &str) -> Option<Self> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line.strip_prefix('\\').unwrap_or(line)),
+        };
+        let (dir_only, line) = match line.strip_suffix('/') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let anchored = line.contains('/');
+        let pattern = line.strip_prefix('/').unwrap_or(line);
+        (!pattern.is_empty()).then(|| IgnoreRule {
+            base: base.to_string(),
+            pattern: pattern.chars().collect(),
+            negated,
+            dir_only,
+            anchored,
+        })
+    }
+
+    fn matches(&self, path: &str, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        let below_base = if self.base.is_empty() {
+            Some(path)
+        } else {
+            path.strip_prefix(&self.base)
+                .and_then(|rest| rest.strip_prefix('/'))
+        };
+        let Some(below_base) = below_base else {
+            return false;
+        };
+        let subject = if self.anchored {
+            below_base
+        } else {
+            below_base.rsplit('/').next().unwrap_or(below_base)
+        };
+        let subject: Vec<char> = subject.chars().collect();
+        glob_match(&self.pattern, &subject)
+    }
+}
+
+/// Whether `text` matches the `.gitignore` glob `pattern`: `*` and `?` stand
+/// for any characters but `/`, and `**` for any directories.
+fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some(('*', rest)) if rest.first() == Some(&'*') => match rest[1..].split_first() {
+            // `**/` may also stand for no directory at all
+            Some(('/', after)) => (0..=text.len())
+                .filter(|&i| i == 0 || text[i - 1] == '/')
+                .any(|i| glob_match(after, &text[i..])),
+            _ => (0..=text.len()).any(|i| glob_match(&rest[1..], &text[i..])),
+        },
+        Some(('*', rest)) => (0..=text.len())
+            .take_while(|&i| i == 0 || text[i - 1] != '/')
+            .any(|i| glob_match(rest, &text[i..])),
+        Some(('?', rest)) => {
+            text.first().is_some_and(|&c| c != '/') && glob_match(rest, &text[1..])
+        }
+        Some((c, rest)) => text.first() == Some(c) && glob_match(rest, &text[1..]),
+    }
+}
+
+fn is_ignored(rules: &[IgnoreRule], path: &str, is_dir: bool) -> bool {
+    // The last rule that matches decides, so `!` can take a path back
+    rules
+        .iter()
+        .rev()
+        .find(|rule| rule.matches(path, is_dir))
+        .is_some_and(|rule| !rule.negated)
+}
+
+/// The files under `root`, in path order, leaving out `.git` and what the
+/// `.gitignore` files along the way ignore.
+pub fn project_files(root: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    collect_project_files(root, "", &mut Vec::new(), &mut files);
+    files
+}
+
+fn collect_project_files(
+    dir: &Path,
+    relative: &str,
+    rules: &mut Vec<IgnoreRule>,
+    files: &mut Vec<PathBuf>,
+) {
+    let inherited = rules.len();
+    if let Ok(content) = fs::read_to_string(dir.join(".gitignore")) {
+        rules.extend(
+            content
+                .lines()
+                .filter_map(|line| IgnoreRule::parse(relative, line)),
+        );
+    }
+    let mut entries: Vec<fs::DirEntry> = match fs::read_dir(dir) {
+        Ok(entries) => entries.flatten().collect(),
+        Err(_) => Vec::new(),
+    };
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        // Symbolic links to directories are not followed, to not loop
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let is_dir = file_type.is_dir();
+        let path = if relative.is_empty() {
+            name.clone()
+        } else {
+            format!("{relative}/{name}")
+        };
+        if name == ".git" || is_ignored(rules, &path, is_dir) {
+            continue;
+        }
+        if is_dir {
+            collect_project_files(&entry.path(), &path, rules, files);
+        } else {
+            files.push(entry.path());
+        }
+    }
+    rules.truncate(inherited);
+}
+
+/// The lines of the project files under `root` that fuzzy match `query`,
+/// best first. Binary and very large files are skipped, and the scan stops
+/// early once `cancel` is set.
+pub fn grep_project(root: &Path, query: &str, cancel: &AtomicBool) -> Vec<ProjectMatch> {
+    let mut matches = Vec::new();
+    for path in project_files(root) {
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
+        if fs::metadata(&path).is_ok_and(|meta| meta.len() > MAX_PROJECT_FILE_BYTES) {
+            continue;
+        }
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        if content.contains('\0') {
+            continue;
+        }
+        for (line_number, line) in content.lines().enumerate() {
+            if let Some((score, indices)) = MATCHER.fuzzy_indices(line, query) {
+                matches.push(ProjectMatch {
+                    path: path.clone(),
+                    line_number,
+                    column: indices.first().copied().unwrap_or(0),
+                    line: line.to_string(),
+                    score,
+                });
+            }
+        }
+    }
+    // Stable, so equally good lines stay in file order
+    matches.sort_by_key(|found| std::cmp::Reverse(found.score));
+    matches.truncate(MAX_PROJECT_MATCHES);
+    matches
+}
+
+/// A fuzzy search through the lines of every file of the project, run on a
+/// worker thread and shown in an overlay like `FuzzySearch`.
+#[derive(Debug, Default)]
+pub struct ProjectSearch {
+    pub root: PathBuf,
+    pub query: String,
+    pub matches: Vec<ProjectMatch>,
+    pub selected_index: usize,
+    pub scroll_offset: usize,
+    // The scan while it runs
+    receiver: Option<Receiver<Vec<ProjectMatch>>>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl ProjectSearch {
+    pub fn is_scanning(&self) -> bool {
+        self.receiver.is_some()
+    }
+
+    /// Stops the scan, if any, and forgets the matches.
+    fn reset(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+        *self = ProjectSearch::default();
+    }
+}
+
+impl Editor {
+    /// Where `GrepProject` searches: `grep_root` when set, otherwise the git
+    /// repository of the file, or the directory it is in.
+    pub fn project_root(&self) -> PathBuf {
+        if let Some(root) = &self.settings.grep_root {
+            return inbox::resolve_path(root);
+        }
+        let dir = self
+            .document
+            .filename
+            .as_deref()
+            .and_then(|filename| Path::new(filename).parent())
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .map(Path::to_path_buf)
+            .or_else(|| std::env::current_dir().ok())
+            .unwrap_or_else(|| PathBuf::from("."));
+        let dir = dir.canonicalize().unwrap_or(dir);
+        dir.ancestors()
+            .find(|ancestor| ancestor.join(".git").exists())
+            .map(Path::to_path_buf)
+            .unwrap_or(dir)
+    }
+
+    /// Starts searching the project for `query` and shows the overlay, which
+    /// fills in when the scan is done.
+    pub fn grep_project(&mut self, query: &str) {
+        if query.trim().is_empty() {
+            self.set_message("Nothing to search for.");
+            return;
+        }
+        self.project_search.reset();
+        let root = self.project_root();
+        let (tx, rx) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (scan_root, scan_query, scan_cancel) =
+            (root.clone(), query.to_string(), Arc::clone(&cancel));
+        thread::spawn(move || {
+            let _ = tx.send(grep_project(&scan_root, &scan_query, &scan_cancel));
+        });
+        self.project_search = ProjectSearch {
+            root,
+            query: query.to_string(),
+            receiver: Some(rx),
+            cancel,
+            ..ProjectSearch::default()
+        };
+        self.mode = EditorMode::ProjectSearch;
+    }
+
+    /// Takes the matches once the scan is done.
+    pub fn project_search_tick(&mut self) {
+        let Some(receiver) = &self.project_search.receiver else {
+            return;
+        };
+        match receiver.try_recv() {
+            Ok(matches) => self.project_search.matches = matches,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => {}
+        }
+        self.project_search.receiver = None;
+    }
+
+    pub fn handle_project_search_input(&mut self, key: Input) {
+        let search = &mut self.project_search;
+        let count = search.matches.len();
+        match key {
+            Input::KeyUp | Input::Character('\x10') if count > 0 => {
+                search.selected_index = (search.selected_index + count - 1) % count;
+            }
+            Input::KeyDown | Input::Character('\x0e') if count > 0 => {
+                search.selected_index = (search.selected_index + 1) % count;
+            }
+            Input::Character('\n') | Input::Character('\r') => {
+                let Some(found) = search.matches.get(search.selected_index).cloned() else {
+                    return;
+                };
+                self.close_project_search();
+                self.open_project_match(&found);
+            }
+            // Esc or Ctrl-G
+            Input::Character('\x1b') | Input::Character('\x07') => self.close_project_search(),
+            _ => {}
+        }
+    }
+
+    fn close_project_search(&mut self) {
+        self.project_search.reset();
+        self.mode = EditorMode::Normal;
+    }
+
+    /// Opens the file of `found`, unless it is the one being edited, and puts
+    /// the cursor on the match.
+    fn open_project_match(&mut self, found: &ProjectMatch) {
+        let origin = self.cursor_pos();
+        let current = self
+            .document
+            .filename
+            .as_deref()
+            .and_then(|filename| Path::new(filename).canonicalize().ok());
+        let is_current = current.is_some() && found.path.canonicalize().ok() == current;
+        if !is_current {
+            let filename = found.path.to_string_lossy().into_owned();
+            if let Err(e) = self.open_file(&filename) {
+                self.set_message(&format!("Could not open {filename}: {e}"));
+                return;
+            }
+        }
+        self.go_to_line_column(found.line_number + 1, Some(found.column + 1));
+        if is_current {
+            self.record_jump(origin);
+        }
+    }
+
+    pub fn draw_project_search(&mut self, window: &impl Screen) {
+        window.erase();
+        let screen_rows = window.get_max_y() as usize;
+        let screen_cols = window.get_max_x() as usize;
+        let list_height = screen_rows.saturating_sub(1);
+        let search = &mut self.project_search;
+
+        if search.selected_index < search.scroll_offset {
+            search.scroll_offset = search.selected_index;
+        }
+        if search.selected_index >= search.scroll_offset + list_height {
+            search.scroll_offset = search.selected_index + 1 - list_height;
+        }
+
+        let offset = search.scroll_offset;
+        for (row, found) in search
+            .matches
+            .iter()
+            .skip(offset)
+            .take(list_height)
+            .enumerate()
+        {
+            let path = found.path.strip_prefix(&search.root).unwrap_or(&found.path);
+            let text = format!(
+                "{}:{}: {}",
+                path.display(),
+                found.line_number + 1,
+                found.line.trim()
+            );
+            let is_selected = offset + row == search.selected_index;
+            if is_selected {
+                window.attron(A_REVERSE);
+            }
+            window.mvaddstr(row as i32, 0, truncate_to_width(&text, screen_cols));
+            if is_selected {
+                window.attroff(A_REVERSE);
+            }
+        }
+
+        let status = if search.is_scanning() {
+            "searching...".to_string()
+        } else if search.matches.is_empty() {
+            "no match".to_string()
+        } else {
+            format!("{} of {}", search.selected_index + 1, search.matches.len())
+        };
+        let prompt = format!(
+            "GREP {}: {} ({status})",
+            search.root.display(),
+            search.query
+        );
+        let prompt = truncate_to_width(&prompt, screen_cols);
+        window.mvaddstr(screen_rows as i32 - 1, 0, prompt);
+        window.mv(screen_rows as i32 - 1, prompt.width() as i32);
+        window.refresh();
+    }
+}
@@ -0,0 +1,55 @@
+use crate::editor::Editor;
+use crate::editor::minibuffer::PromptKind;
+use crate::editor::timestamp::{DEFAULT_DATETIME_FORMAT, format_timestamp};
+use crate::git;
+use chrono::Local;
+use std::path::Path;
+
+impl Editor {
+    /// Asks for the message to commit the current file with, starting from
+    /// one with the file name and the time.
+    pub fn git_commit_file(&mut self) {
+        let Some(filename) = self.document.filename.clone() else {
+            self.set_message("No file to commit.");
+            return;
+        };
+        if git::git_dir(Path::new(&filename)).is_none() {
+            self.set_message("Not in a git repository.");
+            return;
+        }
+        if self.document.is_dirty() {
+            self.set_message("Save the file before committing it.");
+            return;
+        }
+        let name = Path::new(&filename)
+            .file_name()
+            .map_or(filename.clone(), |name| name.to_string_lossy().into_owned());
+        let format = self
+            .settings
+            .datetime_format
+            .as_deref()
+            .unwrap_or(DEFAULT_DATETIME_FORMAT);
+        let time = format_timestamp(Local::now(), format).unwrap_or_default();
+        let message = format!("Update {name} {time}");
+        self.open_minibuffer_with_input(PromptKind::GitCommit, message.trim_end());
+    }
+
+    /// Stages and commits the current file with `message`.
+    pub fn git_commit_file_with_message(&mut self, message: &str) {
+        let message = message.trim();
+        if message.is_empty() {
+            self.set_message("Not committed: the message is empty.");
+            return;
+        }
+        let Some(filename) = self.document.filename.clone() else {
+            return;
+        };
+        match git::commit_file(Path::new(&filename), message) {
+            Ok(summary) => {
+                self.reread_git_gutter();
+                self.set_message(&format!("Committed: {summary}"));
+            }
+            Err(e) => self.set_message(&format!("Commit failed: {e}")),
+        }
+    }
+}
@@ -0,0 +1,347 @@
+use crate::diff::{self, Hunk};
+use crate::document::{Document, LineBuffer, LineChange};
+use crate::editor::Editor;
+use crate::editor::ui::{ERROR_COLOR_PAIR, GUTTER_COLOR_PAIR};
+use crate::git;
+use crate::screen::Screen;
+use pancurses::A_BOLD;
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// How long the committed content of the file is trusted before `git show`
+// runs again, so that a commit made elsewhere shows up
+const BASE_REFRESH: Duration = Duration::from_secs(5);
+
+/// How a line differs from the last commit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HunkMark {
+    Added,
+    Changed,
+    // Lines were removed right above this one
+    Deleted,
+}
+
+impl HunkMark {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            HunkMark::Added => "+",
+            HunkMark::Changed => "~",
+            HunkMark::Deleted => "_",
+        }
+    }
+
+    pub fn color_pair(&self) -> i16 {
+        match self {
+            HunkMark::Added | HunkMark::Changed => GUTTER_COLOR_PAIR,
+            HunkMark::Deleted => ERROR_COLOR_PAIR,
+        }
+    }
+}
+
+/// The lines of the file at HEAD and how the buffer differs from them.
+#[derive(Debug, Default)]
+pub struct GitGutter {
+    // None when the file is not committed in a git repository
+    base: Option<Vec<String>>,
+    base_for: Option<String>,
+    read_at: Option<Instant>,
+    // `git show` running on a background thread
+    pending: Option<Receiver<Option<String>>>,
+    hunks: Vec<Hunk>,
+    // The document revision the hunks were found for
+    hunks_revision: Option<u64>,
+}
+
+impl GitGutter {
+    pub fn hunks(&self) -> &[Hunk] {
+        &self.hunks
+    }
+
+    pub fn is_tracked(&self) -> bool {
+        self.base.is_some()
+    }
+
+    /// Whether `git show` is still running for the file.
+    pub fn is_reading(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    fn set_base(&mut self, content: Option<String>) {
+        let base = content.map(|content| {
+            // An empty file is one empty line in the buffer
+            let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+            if lines.is_empty() {
+                lines.push(String::new());
+            }
+            lines
+        });
+        if base != self.base {
+            self.hunks_revision = None;
+        }
+        self.base = base;
+        self.read_at = Some(Instant::now());
+        self.pending = None;
+    }
+
+    /// Starts reading the file at HEAD without holding up the input loop.
+    fn read_base_in_background(&mut self, filename: Option<String>) {
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let content = filename.and_then(|filename| git::head_content(Path::new(&filename)));
+            let _ = tx.send(content);
+        });
+        self.pending = Some(rx);
+    }
+
+    fn poll_base(&mut self) {
+        let Some(rx) = &self.pending else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(content) => self.set_base(content),
+            Err(TryRecvError::Empty) => {}
+            Err(TryRecvError::Disconnected) => self.pending = None,
+        }
+    }
+
+    /// Brings the hunks up to date with the document. Only the lines around
+    /// those changed since the hunks were found are compared again, unless
+    /// that is more changes ago than the document remembers.
+    fn update_hunks(&mut self, document: &Document) {
+        let revision = document.revision();
+        if self.hunks_revision == Some(revision) {
+            return;
+        }
+        let lines = document.lines();
+        let changes = self
+            .hunks_revision
+            .and_then(|since| document.changes_since(since));
+        self.hunks = match (&self.base, changes) {
+            (None, _) => Vec::new(),
+            (Some(base), Some(changes)) => {
+                let mut hunks: Vec<(Hunk, bool)> =
+                    self.hunks.iter().map(|&hunk| (hunk, false)).collect();
+                for change in changes {
+                    apply_change(&mut hunks, change);
+                }
+                hunks
+                    .into_iter()
+                    .flat_map(|(hunk, changed)| {
+                        if changed {
+                            rediff(base, lines, hunk)
+                        } else {
+                            vec![hunk]
+                        }
+                    })
+                    .collect()
+            }
+            (Some(base), None) => diff::hunks(&diff::diff_lines(base, lines)),
+        };
+        self.hunks_revision = Some(revision);
+    }
+}
+
+/// How far lines after `hunk` have moved from where they were at HEAD.
+fn shift_after(hunk: &Hunk) -> isize {
+    (hunk.new_start + hunk.new_len) as isize - (hunk.old_start + hunk.old_len) as isize
+}
+
+/// Updates `hunks` for a change to the buffer. The hunks the change overlaps
+/// or touches become a single one, marked to be compared again.
+fn apply_change(hunks: &mut Vec<(Hunk, bool)>, change: &LineChange) {
+    let end = change.start + change.removed;
+    let first = hunks.partition_point(|(hunk, _)| hunk.new_start + hunk.new_len < change.start);
+    let last = hunks.partition_point(|(hunk, _)| hunk.new_start <= end);
+    let shift = first.checked_sub(1).map_or(0, |i| shift_after(&hunks[i].0));
+    let (mut new_start, mut old_start) = (change.start, change.start.saturating_add_signed(-shift));
+    let (mut new_end, mut old_end) = (end, end.saturating_add_signed(-shift));
+    if first < last {
+        let (first_hunk, last_hunk) = (hunks[first].0, hunks[last - 1].0);
+        if first_hunk.new_start < new_start {
+            (new_start, old_start) = (first_hunk.new_start, first_hunk.old_start);
+        }
+        old_end = end.saturating_add_signed(-shift_after(&last_hunk));
+        if last_hunk.new_start + last_hunk.new_len >= new_end {
+            new_end = last_hunk.new_start + last_hunk.new_len;
+            old_end = last_hunk.old_start + last_hunk.old_len;
+        }
+    }
+    let changed = Hunk {
+        old_start,
+        old_len: old_end - old_start,
+        new_start,
+        new_len: new_end - new_start + change.inserted - change.removed,
+    };
+    hunks.splice(first..last, [(changed, true)]);
+    for (hunk, _) in &mut hunks[first + 1..] {
+        hunk.new_start = hunk.new_start + change.inserted - change.removed;
+    }
+}
+
+/// The hunks within `hunk`, found by comparing its lines again.
+fn rediff(base: &[String], lines: &LineBuffer, hunk: Hunk) -> Vec<Hunk> {
+    let old = &base[hunk.old_start..hunk.old_start + hunk.old_len];
+    let new = lines.range(hunk.new_start..hunk.new_start + hunk.new_len);
+    diff::hunks(&diff::diff_lines(old, new))
+        .into_iter()
+        .map(|found| Hunk {
+            old_start: hunk.old_start + found.old_start,
+            old_len: found.old_len,
+            new_start: hunk.new_start + found.new_start,
+            new_len: found.new_len,
+        })
+        .collect()
+}
+
+/// The line of the buffer that marks `hunk`; removed lines are marked on the
+/// line that follows them, or the last line at the end of the file.
+fn hunk_line(hunk: &Hunk, line_count: usize) -> usize {
+    hunk.new_start.min(line_count.saturating_sub(1))
+}
+
+impl Editor {
+    /// Called from the main loop while the gutter is shown.
+    pub fn git_gutter_tick(&mut self) {
+        if self.settings.git_gutter {
+            self.update_git_gutter();
+        }
+    }
+
+    /// Diffs the buffer against the file at HEAD as last read. `git show`
+    /// only ever runs in the background, when the file changed or the last
+    /// run is stale, and its result is picked up on a later call.
+    pub fn update_git_gutter(&mut self) {
+        let filename = self.document.filename.clone();
+        let gutter = &mut self.git_gutter;
+        if gutter.base_for != filename {
+            // Another file's marks must not show while this one's is read
+            *gutter = GitGutter {
+                base_for: filename.clone(),
+                ..GitGutter::default()
+            };
+        }
+        gutter.poll_base();
+        if gutter.pending.is_none()
+            && gutter
+                .read_at
+                .is_none_or(|read_at| read_at.elapsed() >= BASE_REFRESH)
+        {
+            gutter.read_base_in_background(filename);
+        }
+        gutter.update_hunks(&self.document);
+    }
+
+    /// Reads the file at HEAD again without waiting for the last read to go
+    /// stale, as when a commit was just made.
+    pub fn reread_git_gutter(&mut self) {
+        let filename = self.document.filename.clone();
+        self.git_gutter.read_base_in_background(filename);
+    }
+
+    /// Columns taken by the gutter left of the text.
+    pub fn gutter_width(&self) -> usize {
+        usize::from(self.settings.git_gutter && self.git_gutter.is_tracked())
+    }
+
+    /// How line `y` differs from the last commit, if it does.
+    pub fn hunk_mark(&self, y: usize) -> Option<HunkMark> {
+        let line_count = self.document.lines().len();
+        self.git_gutter.hunks().iter().find_map(|hunk| {
+            if hunk.new_len == 0 {
+                (hunk_line(hunk, line_count) == y).then_some(HunkMark::Deleted)
+            } else if (hunk.new_start..hunk.new_start + hunk.new_len).contains(&y) {
+                Some(if hunk.old_len == 0 {
+                    HunkMark::Added
+                } else {
+                    HunkMark::Changed
+                })
+            } else {
+                None
+            }
+        })
+    }
+
+    pub(super) fn draw_hunk_mark(&self, window: &impl Screen, row: usize, mark: HunkMark) {
+        if !self.monochrome {
+            window.color_set(mark.color_pair());
+        }
+        window.attron(A_BOLD);
+        window.mvaddstr(row as i32, 0, mark.symbol());
+        window.attroff(A_BOLD);
+        if !self.monochrome {
+            window.color_set(1);
+        }
+    }
+
+    /// Moves to the first line of the next or previous changed hunk,
+    /// wrapping around the file.
+    pub fn go_to_hunk(&mut self, forward: bool) {
+        self.update_git_gutter();
+        if !self.git_gutter.is_tracked() {
+            self.set_message(if self.git_gutter.is_reading() {
+                "Still reading the committed file."
+            } else {
+                "Not a committed file in a git repository."
+            });
+            return;
+        }
+        let line_count = self.document.lines().len();
+        let lines: Vec<usize> = self
+            .git_gutter
+            .hunks()
+            .iter()
+            .map(|hunk| hunk_line(hunk, line_count))
+            .collect();
+        let y = self.cursor.y;
+        let target = if forward {
+            lines.iter().find(|&&line| line > y).or(lines.first())
+        } else {
+            lines.iter().rev().find(|&&line| line < y).or(lines.last())
+        };
+        let Some(&target) = target else {
+            self.set_message("No changes since the last commit.");
+            return;
+        };
+        let origin = self.cursor_pos();
+        self.set_cursor_pos(0, target);
+        self.cursor.desired_x = 0;
+        self.snap_to_fold_header();
+        self.record_jump(origin);
+        let position = lines.iter().position(|&line| line == target).unwrap_or(0);
+        self.set_message(&format!("Hunk {}/{}", position + 1, lines.len()));
+    }
+
+    /// Puts back the committed lines of the hunk under the cursor, as one
+    /// undo step.
+    pub fn revert_hunk(&mut self) {
+        self.update_git_gutter();
+        let line_count = self.document.lines().len();
+        let y = self.cursor.y;
+        let Some(hunk) = self.git_gutter.hunks().iter().copied().find(|hunk| {
+            (hunk.new_start..hunk.new_start + hunk.new_len).contains(&y)
+                || (hunk.new_len == 0 && hunk_line(hunk, line_count) == y)
+        }) else {
+            self.set_message("No change to revert here.");
+            return;
+        };
+        let Some(base) = &self.git_gutter.base else {
+            return;
+        };
+        let committed = base[hunk.old_start..hunk.old_start + hunk.old_len].to_vec();
+        let last_line = (line_count + hunk.old_len - hunk.new_len).saturating_sub(1);
+
+        self.undo_redo.begin_group();
+        self.replace_line_range(
+            hunk.new_start,
+            hunk.new_start + hunk.new_len,
+            committed,
+            (0, hunk.new_start.min(last_line)),
+        );
+        self.undo_redo.end_group();
+        self.cursor.desired_x = 0;
+        self.update_git_gutter();
+        self.set_message("Hunk reverted.");
+    }
+}
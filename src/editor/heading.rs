@@ -0,0 +1,98 @@
+use crate::document::LineBuffer;
+use crate::editor::Editor;
+use crate::editor::structure::enclosing_list_item;
+
+const MAX_HEADING_LEVEL: usize = 6;
+
+/// The level of a Markdown heading line (`## Title` is 2), or `None` for other lines.
+pub fn heading_level(line: &str) -> Option<usize> {
+    let level = line.len() - line.trim_start_matches('#').len();
+    let rest = &line[level..];
+    ((1..=MAX_HEADING_LEVEL).contains(&level) && (rest.is_empty() || rest.starts_with(' ')))
+        .then_some(level)
+}
+
+/// Indices of the heading at `y` and every heading nested below it, up to the
+/// next heading of the same or a higher level.
+pub fn section_headings(lines: &LineBuffer, y: usize) -> Vec<usize> {
+    let Some(level) = lines.get(y).and_then(|line| heading_level(line)) else {
+        return Vec::new();
+    };
+    let mut headings = vec![y];
+    for (index, line) in lines.iter().enumerate().skip(y + 1) {
+        match heading_level(line) {
+            Some(sub_level) if sub_level <= level => break,
+            Some(_) => headings.push(index),
+            None => {}
+        }
+    }
+    headings
+}
+
+impl Editor {
+    /// Raises the heading under the cursor one level by removing a `#`;
+    /// with `subtree`, its subheadings are raised along with it, or in a list
+    /// the item the cursor is in is outdented with its nested lines.
+    pub fn promote_heading(&mut self, subtree: bool) {
+        self.shift_heading_levels(subtree, false);
+    }
+
+    /// Lowers the heading under the cursor one level by adding a `#`;
+    /// with `subtree`, its subheadings are lowered along with it, or in a list
+    /// the item the cursor is in is indented with its nested lines.
+    pub fn demote_heading(&mut self, subtree: bool) {
+        self.shift_heading_levels(subtree, true);
+    }
+
+    fn shift_heading_levels(&mut self, subtree: bool, demote: bool) {
+        let y = self.cursor.y;
+        let headings = if subtree {
+            section_headings(self.document.lines(), y)
+        } else {
+            self.document
+                .lines()
+                .get(y)
+                .and_then(|line| heading_level(line))
+                .map(|_| vec![y])
+                .unwrap_or_default()
+        };
+        if headings.is_empty() {
+            // Outside headings the subtree is that of the list item
+            if subtree && enclosing_list_item(self.document.lines(), y).is_some() {
+                self.shift_list_subtree(demote);
+            } else {
+                self.status_message = "Not on a heading.".to_string();
+            }
+            return;
+        }
+
+        let levels: Vec<usize> = headings
+            .iter()
+            .filter_map(|&index| heading_level(&self.document.lines()[index]))
+            .collect();
+        if demote && levels.contains(&MAX_HEADING_LEVEL) {
+            self.status_message = "Headings go down to level 6.".to_string();
+            return;
+        }
+        if !demote && levels[0] == 1 {
+            self.status_message = "Already a top-level heading.".to_string();
+            return;
+        }
+
+        self.undo_redo.begin_group();
+        for &index in &headings {
+            if demote {
+                self.replace_in_line(index, 0, 0, "#");
+            } else {
+                self.replace_in_line(index, 0, 1, "");
+            }
+        }
+        self.undo_redo.end_group();
+
+        let verb = if demote { "Demoted" } else { "Promoted" };
+        self.status_message = match headings.len() {
+            1 => format!("{verb} heading."),
+            count => format!("{verb} {count} headings."),
+        };
+    }
+}
@@ -0,0 +1,137 @@
+use crate::editor::actions::Action;
+use crate::editor::{Editor, EditorMode};
+use crate::screen::Screen;
+use pancurses::A_DIM;
+use unicode_width::UnicodeWidthStr;
+
+const HINT_SEPARATOR: &str = " · ";
+
+// Bound through the keymap, so the keys shown follow the user's bindings
+const NORMAL_HINTS: [(Action, &str); 7] = [
+    (Action::Save, "save"),
+    (Action::Quit, "exit"),
+    (Action::Undo, "undo"),
+    (Action::EnterSearchMode, "search"),
+    (Action::EnterFuzzySearchMode, "find line"),
+    (Action::ShowOutline, "outline"),
+    (Action::SetMarker, "mark"),
+];
+const SELECTION_HINTS: [(Action, &str); 4] = [
+    (Action::CutSelection, "cut"),
+    (Action::CopySelection, "copy"),
+    (Action::ToggleRectangleMode, "rectangle"),
+    (Action::ClearMarker, "cancel"),
+];
+
+/// `ctrl-s` as `C-s` and `alt-v` as `M-v`.
+pub(super) fn key_label(key: &str) -> String {
+    if let Some(rest) = key.strip_prefix("ctrl-") {
+        format!("C-{}", rest.replace("space", "SPC"))
+    } else if let Some(rest) = key.strip_prefix("alt-") {
+        format!("M-{rest}")
+    } else {
+        key.to_uppercase()
+    }
+}
+
+impl Editor {
+    /// Keys worth knowing about in the current mode, e.g. `"C-s search"`.
+    /// Modes with their own fixed keys list those; otherwise the keymap is
+    /// asked which key runs each action.
+    pub fn hints(&self) -> Vec<String> {
+        let fixed: &[&str] = if self.search.mode {
+            &[
+                "C-s next",
+                "C-r previous",
+                "M-c case",
+                "M-w word",
+                "RET done",
+            ]
+        } else {
+            match self.mode {
+                EditorMode::TaskSelection => &["↑↓ select", "SPACE move", "# comment", "ESC exit"],
+                EditorMode::Agenda => &["↑↓ select", "RET jump", "x check off", "ESC close"],
+                EditorMode::Outline | EditorMode::Tags => {
+                    &["↑↓ select", "type to filter", "RET jump", "ESC close"]
+                }
+                EditorMode::CommandPalette => {
+                    &["↑↓ select", "type to filter", "RET run", "ESC close"]
+                }
+                EditorMode::DatePicker => &[
+                    "←→ day",
+                    "↑↓ week",
+                    "PgUp/PgDn month",
+                    "t today",
+                    "RET insert",
+                    "ESC cancel",
+                ],
+                EditorMode::Minibuffer => &["RET confirm", "C-g cancel"],
+                EditorMode::SpellingSuggestions => {
+                    &["↑↓ select", "1-9 pick", "RET apply", "ESC cancel"]
+                }
+                EditorMode::Completion => &["TAB/↑↓ select", "RET complete", "ESC cancel"],
+                EditorMode::BackupBrowser => &[
+                    "↑↓ select",
+                    "d changes",
+                    "SPACE/b scroll",
+                    "RET restore",
+                    "ESC close",
+                ],
+                // The prompt in the status bar lists the answers
+                EditorMode::Recovery | EditorMode::SaveConflict => &[],
+                _ => {
+                    let hints: &[(Action, &str)] = if self.selection.marker_pos.is_some() {
+                        &SELECTION_HINTS
+                    } else {
+                        &NORMAL_HINTS
+                    };
+                    return hints
+                        .iter()
+                        .filter_map(|(action, label)| {
+                            let key = self.key_for_action(action)?;
+                            Some(format!("{} {label}", key_label(key)))
+                        })
+                        .collect();
+                }
+            }
+        };
+        fixed.iter().map(|hint| hint.to_string()).collect()
+    }
+
+    /// The shortest key bound to `action`.
+    pub(super) fn key_for_action(&self, action: &Action) -> Option<&str> {
+        self.keymap
+            .bindings
+            .iter()
+            .filter(|(_, bound)| *bound == action)
+            .map(|(key, _)| key.as_str())
+            .min_by_key(|key| (key.len(), *key))
+    }
+
+    pub fn toggle_hint_bar(&mut self) {
+        self.settings.hint_bar = !self.settings.hint_bar;
+    }
+
+    /// Writes as many hints as fit over the line below the status bar.
+    pub(super) fn draw_hint_bar(&self, window: &impl Screen, row: usize, screen_cols: usize) {
+        let mut text = String::new();
+        for hint in self.hints() {
+            let next = if text.is_empty() {
+                format!(" {hint}")
+            } else {
+                format!("{HINT_SEPARATOR}{hint}")
+            };
+            if text.width() + next.width() + 2 > screen_cols {
+                break;
+            }
+            text.push_str(&next);
+        }
+        if text.is_empty() {
+            return;
+        }
+        text.push(' ');
+        window.attron(A_DIM);
+        window.mvaddstr(row as i32, 1, &text);
+        window.attroff(A_DIM);
+    }
+}
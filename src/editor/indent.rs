@@ -2,6 +2,51 @@ use crate::document::ActionDiff;
 use crate::editor::{Editor, LastActionType};
 use crate::error::Result;
 
+/// The number of an ordered list item such as `  2. text` or `2) text`, with
+/// the byte range of its digits.
+pub fn ordered_list_number(line: &str) -> Option<(usize, usize, usize)> {
+    let start = line.len() - line.trim_start_matches(' ').len();
+    let end = start
+        + line[start..]
+            .bytes()
+            .take_while(|b| b.is_ascii_digit())
+            .count();
+    let rest = &line[end..];
+    if end == start || !(rest.starts_with(". ") || rest.starts_with(") ")) {
+        return None;
+    }
+    let number = line[start..end].parse().ok()?;
+    Some((number, start, end))
+}
+
+/// Whether `line` is a bullet, checkbox or numbered list item indented with
+/// spaces.
+pub(super) fn is_list_item(line: &str) -> bool {
+    let content = line.trim_start_matches(' ');
+    content.starts_with("- ")
+        || content.starts_with("* ")
+        || content.starts_with("+ ")
+        || ordered_list_number(line).is_some()
+}
+
+pub(super) fn leading_spaces(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+/// Nesting levels are `width` spaces apart; an item indented in between
+/// moves to the nearest level in the direction it is moved.
+pub(super) fn next_level(indent: usize, width: usize) -> usize {
+    (indent / width + 1) * width
+}
+
+pub(super) fn previous_level(indent: usize, width: usize) -> usize {
+    if indent.is_multiple_of(width) {
+        indent.saturating_sub(width)
+    } else {
+        indent / width * width
+    }
+}
+
 impl Editor {
     fn handle_selection_indent_outdent<F>(&mut self, operation: F) -> Result<()>
     where
@@ -13,10 +58,7 @@ impl Editor {
         let original_cursor_pos = self.cursor_pos();
         let original_marker_pos = self.selection.marker_pos.unwrap();
 
-        let (start, end) = self
-            .selection
-            .get_selection_range(original_cursor_pos)
-            .unwrap();
+        let (start, end) = self.selection.get_selection_range(&self.cursor).unwrap();
         let start_y = start.1;
         let mut end_y = end.1;
 
@@ -33,8 +75,8 @@ impl Editor {
         let mut line_deltas = std::collections::HashMap::new();
 
         for y in start_y..=end_y {
-            if y < self.document.lines.len() {
-                let line = &self.document.lines[y];
+            if y < self.document.lines().len() {
+                let line = &self.document.lines()[y];
                 original_lines.push(line.clone());
                 if line.is_empty() {
                     new_lines.push(line.clone());
@@ -51,12 +93,12 @@ impl Editor {
             return Ok(());
         }
 
-        let original_end_x = self.document.lines.get(end_y).map_or(0, |l| l.len());
+        let original_end_x = self.document.lines().get(end_y).map_or(0, |l| l.len());
 
         // 1. Delete the original lines
         let delete_diff = ActionDiff {
-            cursor_start_x: self.cursor_x,
-            cursor_start_y: self.cursor_y,
+            cursor_start_x: self.cursor.x,
+            cursor_start_y: self.cursor.y,
             cursor_end_x: 0,
             cursor_end_y: start_y,
             start_x: 0,
@@ -71,14 +113,14 @@ impl Editor {
         // 2. Insert the new lines
         let new_last_line_len = new_lines.last().map_or(0, |l| l.len());
         let insert_diff = ActionDiff {
-            cursor_start_x: self.cursor_x,
-            cursor_start_y: self.cursor_y,
-            cursor_end_x: self.cursor_x, // Keep cursor at start of modified region
-            cursor_end_y: self.cursor_y,
-            start_x: self.cursor_x,
-            start_y: self.cursor_y,
+            cursor_start_x: self.cursor.x,
+            cursor_start_y: self.cursor.y,
+            cursor_end_x: self.cursor.x, // Keep cursor at start of modified region
+            cursor_end_y: self.cursor.y,
+            start_x: self.cursor.x,
+            start_y: self.cursor.y,
             end_x: new_last_line_len,
-            end_y: self.cursor_y + new_lines.len() - 1,
+            end_y: self.cursor.y + new_lines.len() - 1,
             new: new_lines,
             old: vec![],
         };
@@ -96,97 +138,218 @@ impl Editor {
         }
 
         // 4. Update state
-        self.cursor_x = new_cursor_pos.0;
-        self.cursor_y = new_cursor_pos.1;
+        self.cursor.x = new_cursor_pos.0;
+        self.cursor.y = new_cursor_pos.1;
         self.selection.marker_pos = Some(new_marker_pos);
 
-        self.desired_cursor_x = self
+        self.cursor.desired_x = self
             .scroll
-            .get_display_width_from_bytes(&self.document.lines[self.cursor_y], self.cursor_x);
+            .get_display_width_from_bytes(&self.document.lines()[self.cursor.y], self.cursor.x);
 
         Ok(())
     }
 
     pub fn indent_line(&mut self) -> Result<()> {
+        let width = self.settings.indent_width();
         if self.selection.is_selection_active() {
-            self.handle_selection_indent_outdent(|line| format!("  {line}"))
+            let indentation = " ".repeat(width);
+            self.handle_selection_indent_outdent(|line| format!("{indentation}{line}"))
         } else {
-            let y = self.cursor_y;
-            if y >= self.document.lines.len() {
+            let y = self.cursor.y;
+            if y >= self.document.lines().len() {
                 return Ok(());
             }
-            self.commit(
-                LastActionType::Other,
-                &ActionDiff {
-                    cursor_start_x: self.cursor_x,
-                    cursor_start_y: self.cursor_y,
-                    cursor_end_x: self.cursor_x + 2,
-                    cursor_end_y: self.cursor_y,
-                    start_x: 0,
-                    start_y: y,
-                    end_x: 0,
-                    end_y: y,
-                    new: vec!["  ".to_string()],
-                    old: vec![],
-                },
-            );
+            let line = &self.document.lines()[y];
+            let indent = leading_spaces(line);
+            if is_list_item(line) {
+                self.reindent_list_item(y, next_level(indent, width));
+            } else {
+                self.set_indentation(y, indent + width);
+            }
             self.clipboard.last_action_was_kill = false;
             Ok(())
         }
     }
 
     pub fn outdent_line(&mut self) -> Result<()> {
+        let width = self.settings.indent_width();
         if self.selection.is_selection_active() {
             self.handle_selection_indent_outdent(|line| {
-                if let Some(stripped) = line.strip_prefix("  ") {
-                    stripped.to_string()
-                } else if let Some(stripped) = line.strip_prefix(' ') {
-                    stripped.to_string()
-                } else {
-                    line.to_string()
-                }
+                line[leading_spaces(line).min(width)..].to_string()
             })
         } else {
-            let y = self.cursor_y;
-            if y >= self.document.lines.len() {
+            let y = self.cursor.y;
+            if y >= self.document.lines().len() {
                 return Ok(());
             }
-            let line = &self.document.lines[y];
-            if line.starts_with("  ") {
-                self.commit(
-                    LastActionType::Other,
-                    &ActionDiff {
-                        cursor_start_x: self.cursor_x,
-                        cursor_start_y: self.cursor_y,
-                        cursor_end_x: self.cursor_x.saturating_sub(2),
-                        cursor_end_y: self.cursor_y,
-                        start_x: 0,
-                        start_y: y,
-                        end_x: 2,
-                        end_y: y,
-                        new: vec![],
-                        old: vec!["  ".to_string()],
-                    },
-                );
-            } else if line.starts_with(' ') {
-                self.commit(
-                    LastActionType::Other,
-                    &ActionDiff {
-                        cursor_start_x: self.cursor_x,
-                        cursor_start_y: self.cursor_y,
-                        cursor_end_x: self.cursor_x.saturating_sub(1),
-                        cursor_end_y: self.cursor_y,
-                        start_x: 0,
-                        start_y: y,
-                        end_x: 1,
-                        end_y: y,
-                        new: vec![],
-                        old: vec![" ".to_string()],
-                    },
-                );
+            let line = &self.document.lines()[y];
+            let indent = leading_spaces(line);
+            if is_list_item(line) {
+                self.reindent_list_item(y, previous_level(indent, width));
+            } else {
+                self.set_indentation(y, indent.saturating_sub(width));
             }
             self.clipboard.last_action_was_kill = false;
             Ok(())
         }
     }
+
+    /// Adds or removes spaces at the start of line `y` until it is indented
+    /// by `indent` spaces.
+    fn set_indentation(&mut self, y: usize, indent: usize) {
+        let current = leading_spaces(&self.document.lines()[y]);
+        if y != self.cursor.y && indent != current {
+            let spaces = " ".repeat(indent);
+            self.replace_in_line(y, 0, current, &spaces);
+        } else if indent > current {
+            let added = indent - current;
+            self.commit(
+                LastActionType::Other,
+                &ActionDiff {
+                    cursor_start_x: self.cursor.x,
+                    cursor_start_y: self.cursor.y,
+                    cursor_end_x: self.cursor.x + added,
+                    cursor_end_y: self.cursor.y,
+                    start_x: 0,
+                    start_y: y,
+                    end_x: added,
+                    end_y: y,
+                    new: vec![" ".repeat(added)],
+                    old: vec![],
+                },
+            );
+        } else if indent < current {
+            let removed = current - indent;
+            self.commit(
+                LastActionType::Other,
+                &ActionDiff {
+                    cursor_start_x: self.cursor.x,
+                    cursor_start_y: self.cursor.y,
+                    cursor_end_x: self.cursor.x.saturating_sub(removed),
+                    cursor_end_y: self.cursor.y,
+                    start_x: 0,
+                    start_y: y,
+                    end_x: removed,
+                    end_y: y,
+                    new: vec![],
+                    old: vec![" ".repeat(removed)],
+                },
+            );
+        }
+    }
+
+    /// Moves the list item on line `y` to another nesting level. A numbered
+    /// item is renumbered in its new list, and the items it leaves behind
+    /// close the gap.
+    fn reindent_list_item(&mut self, y: usize, indent: usize) {
+        self.reindent_list_subtree(y, y, indent);
+    }
+
+    /// Like `reindent_list_item`, taking the lines after it up to `end` along
+    /// so that they stay as deep under it as they were.
+    pub(super) fn reindent_list_subtree(&mut self, y: usize, end: usize, indent: usize) {
+        let old_indent = leading_spaces(&self.document.lines()[y]);
+        let old_start = self.ordered_list_start(y);
+        self.set_indentation(y, indent);
+        for below in y + 1..=end {
+            let line = &self.document.lines()[below];
+            let current = leading_spaces(line);
+            let shifted = (current + indent).saturating_sub(old_indent);
+            if !line.trim().is_empty() && shifted != current {
+                let spaces = " ".repeat(shifted);
+                self.replace_in_line_as(LastActionType::Ammend, below, 0, current, &spaces);
+            }
+        }
+        if old_start.is_none() {
+            return;
+        }
+        if let Some(sibling) = self.next_sibling(end, old_indent) {
+            self.renumber_ordered_list(sibling, old_start);
+        }
+        // An item starting a list of its own is item 1
+        let starts_list = self.ordered_list_run(y).first() == Some(&y);
+        self.renumber_ordered_list(y, starts_list.then_some(1));
+    }
+
+    /// The first line after `y` indented by `indent` spaces, if it comes
+    /// before a blank or less indented line.
+    fn next_sibling(&self, y: usize, indent: usize) -> Option<usize> {
+        for (below, line) in self.document.lines().iter().enumerate().skip(y + 1) {
+            if line.trim().is_empty() || leading_spaces(line) < indent {
+                return None;
+            }
+            if leading_spaces(line) == indent {
+                return Some(below);
+            }
+        }
+        None
+    }
+
+    /// Lines of the numbered items in the same list as line `y`, at the same
+    /// level. Items nested deeper are passed over; a blank line, a less
+    /// indented line or anything else at the same level ends the list.
+    fn ordered_list_run(&self, y: usize) -> Vec<usize> {
+        let lines = self.document.lines();
+        let Some((_, indent, _)) = lines.get(y).and_then(|line| ordered_list_number(line)) else {
+            return Vec::new();
+        };
+        // Some(true) for an item of the list, Some(false) for a line nested in one
+        let in_list = |line: &str| {
+            if line.trim().is_empty() || leading_spaces(line) < indent {
+                None
+            } else if leading_spaces(line) > indent {
+                Some(false)
+            } else {
+                ordered_list_number(line).map(|_| true)
+            }
+        };
+        let mut first = y;
+        for above in (0..y).rev() {
+            match in_list(&lines[above]) {
+                Some(true) => first = above,
+                Some(false) => {}
+                None => break,
+            }
+        }
+        let mut run = Vec::new();
+        for (below, line) in lines.iter().enumerate().skip(first) {
+            match in_list(line) {
+                Some(true) => run.push(below),
+                Some(false) => {}
+                None => break,
+            }
+        }
+        run
+    }
+
+    /// The number the list containing line `y` starts at, if line `y` is a
+    /// numbered item.
+    pub(super) fn ordered_list_start(&self, y: usize) -> Option<usize> {
+        let first = *self.ordered_list_run(y).first()?;
+        ordered_list_number(&self.document.lines()[first]).map(|(number, _, _)| number)
+    }
+
+    /// Numbers the list containing line `y` consecutively from `start`, or
+    /// from its first item, as part of the previous undo step.
+    pub(super) fn renumber_ordered_list(&mut self, y: usize, start: Option<usize>) {
+        let run = self.ordered_list_run(y);
+        let Some(mut expected) = start.or_else(|| self.ordered_list_start(y)) else {
+            return;
+        };
+        for item in run {
+            if let Some((number, start_x, end_x)) =
+                ordered_list_number(&self.document.lines()[item])
+                && number != expected
+            {
+                self.replace_in_line_as(
+                    LastActionType::Ammend,
+                    item,
+                    start_x,
+                    end_x,
+                    &expected.to_string(),
+                );
+            }
+            expected += 1;
+        }
+    }
 }
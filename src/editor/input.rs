@@ -2,6 +2,7 @@ use crate::editor::Editor;
 use crate::editor::EditorMode;
 use crate::editor::actions::Action;
 use crate::error::Result;
+use crate::keyboard::KeyChord;
 use log::debug;
 use pancurses::Input;
 
@@ -66,10 +67,56 @@ fn key_to_string(key: Input, is_alt_pressed: bool) -> String {
 }
 
 impl Editor {
+    /// Handles a bracketed paste. Prompts and pickers get the text as if it
+    /// was typed.
+    pub fn process_paste(&mut self, text: String) -> Result<()> {
+        if self.search.mode || self.mode != EditorMode::Normal {
+            for c in text.chars() {
+                self.process_input(Input::Character(c), false)?;
+            }
+            return Ok(());
+        }
+        self.execute_action(Action::Paste(text))
+    }
+
+    /// Handles a key reported with modifiers that the legacy encoding lacks.
+    /// In the buffer it runs what the keymap binds to its name, e.g.
+    /// `"ctrl-enter"`; elsewhere, or when nothing is bound, it acts as the
+    /// closest plain key.
+    pub fn process_chord(&mut self, chord: KeyChord) -> Result<()> {
+        debug!("Processing chord: {}", chord.name());
+        if self.mode == EditorMode::Normal
+            && !self.search.mode
+            && let Some(action) = self.keymap.bindings.get(&chord.name()).cloned()
+        {
+            self.set_alt_pressed(chord.alt);
+            self.pomodoro.alert = None;
+            let snapshot = self.snippet_snapshot();
+            let result = self.execute_repeated(action);
+            self.follow_snippet_edit(snapshot);
+            return result;
+        }
+        let (key, alt) = chord.fallback();
+        self.process_input(key, alt)
+    }
+
     pub fn process_input(&mut self, key: Input, is_alt_pressed: bool) -> Result<()> {
         debug!("Processing input: {key:?}, Alt pressed: {is_alt_pressed}");
         self.set_alt_pressed(is_alt_pressed);
+        self.pomodoro.alert = None;
+
+        // Tab moves between the fields of a snippet, and typing in one is
+        // copied to those that mirror it
+        if self.handle_snippet_key(key, is_alt_pressed) {
+            return Ok(());
+        }
+        let snapshot = self.snippet_snapshot();
+        let result = self.process_key(key, is_alt_pressed);
+        self.follow_snippet_edit(snapshot);
+        result
+    }
 
+    fn process_key(&mut self, key: Input, is_alt_pressed: bool) -> Result<()> {
         // Handle mode-specific inputs first
         if self.search.mode {
             self.handle_search_input(key);
@@ -83,18 +130,87 @@ impl Editor {
             self.handle_fuzzy_search_input(key);
             return Ok(());
         }
+        if self.mode == EditorMode::ProjectSearch {
+            self.handle_project_search_input(key);
+            return Ok(());
+        }
+        if self.mode == EditorMode::KillRing {
+            return self.handle_kill_ring_input(key);
+        }
+        if self.mode == EditorMode::Recovery {
+            self.handle_recovery_input(key);
+            return Ok(());
+        }
+        if self.mode == EditorMode::DatePicker {
+            self.handle_date_picker_input(key);
+            return Ok(());
+        }
+        if self.mode == EditorMode::Minibuffer {
+            self.handle_minibuffer_input(key);
+            return Ok(());
+        }
+        if self.mode == EditorMode::Agenda {
+            self.handle_agenda_input(key);
+            return Ok(());
+        }
+        if self.mode == EditorMode::FileBrowser {
+            self.handle_file_browser_input(key);
+            return Ok(());
+        }
+        if self.mode == EditorMode::RecentFiles {
+            self.handle_recent_files_input(key);
+            return Ok(());
+        }
+        if self.mode == EditorMode::Bookmarks {
+            self.handle_bookmarks_input(key);
+            return Ok(());
+        }
+        if self.mode == EditorMode::CommandPalette {
+            return self.handle_command_palette_input(key);
+        }
+        if self.mode == EditorMode::Outline {
+            self.handle_outline_input(key);
+            return Ok(());
+        }
+        if self.mode == EditorMode::Tags {
+            self.handle_tags_input(key);
+            return Ok(());
+        }
+        if self.mode == EditorMode::SaveConflict {
+            self.handle_save_conflict_input(key);
+            return Ok(());
+        }
+        if self.mode == EditorMode::BackupBrowser {
+            self.handle_backup_browser_input(key);
+            return Ok(());
+        }
+        if self.mode == EditorMode::SpellingSuggestions {
+            self.handle_spelling_suggestions_input(key);
+            return Ok(());
+        }
+        if self.mode == EditorMode::Completion {
+            return self.handle_completion_input(key);
+        }
 
         // Normal mode input handling using keymap
         let key_string = key_to_string(key, is_alt_pressed);
         debug!("Key string: '{key_string}'");
 
+        // Digits typed after `Ctrl-U` or `Alt-<digit>` extend the count
+        if self.repeat.is_pending()
+            && !is_alt_pressed
+            && let Input::Character(c @ '0'..='9') = key
+        {
+            return self.execute_action(Action::DigitArgument(c as u8 - b'0'));
+        }
+
         if let Some(action) = self.keymap.bindings.get(&key_string).cloned() {
-            self.execute_action(action)?;
+            self.execute_repeated(action)?;
         } else if let Input::Character(c) = key {
             // If no specific action is bound, and it's a character, insert it.
             // We exclude control characters from being inserted directly.
             if !c.is_control() {
-                self.execute_action(Action::InsertChar(c))?;
+                self.execute_repeated(Action::InsertChar(c))?;
             }
         }
         // If no binding and not a character, do nothing.
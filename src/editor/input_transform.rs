@@ -0,0 +1,85 @@
+use crate::editor::Editor;
+
+/// Conversions applied to characters as they are typed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputTransform {
+    /// Full-width letters and digits (`Ａ`, `１`) become ASCII.
+    Alphanumerics,
+    /// Full-width comma and period (`，`, `．`) become `、` and `。`.
+    Punctuation,
+}
+
+impl InputTransform {
+    pub const ALL: [InputTransform; 2] =
+        [InputTransform::Alphanumerics, InputTransform::Punctuation];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "alphanumerics" => Some(InputTransform::Alphanumerics),
+            "punctuation" => Some(InputTransform::Punctuation),
+            _ => None,
+        }
+    }
+
+    pub fn apply(self, c: char) -> char {
+        match self {
+            InputTransform::Alphanumerics if c.is_alphanumeric() && ('！'..='～').contains(&c) => {
+                // The full-width forms mirror ASCII at a fixed offset
+                char::from_u32(c as u32 - 0xFEE0).unwrap_or(c)
+            }
+            InputTransform::Punctuation => match c {
+                '，' => '、',
+                '．' => '。',
+                _ => c,
+            },
+            _ => c,
+        }
+    }
+}
+
+impl Editor {
+    /// Transforms from the `input_transforms` setting, or all of them when it
+    /// is not set.
+    pub fn input_transforms(&self) -> Vec<InputTransform> {
+        match &self.settings.input_transforms {
+            Some(names) => names
+                .iter()
+                .filter_map(|name| {
+                    let transform = InputTransform::parse(name);
+                    if transform.is_none() {
+                        log::warn!("Unknown input transform: {name}");
+                    }
+                    transform
+                })
+                .collect(),
+            None => InputTransform::ALL.to_vec(),
+        }
+    }
+
+    /// Whether typed characters are transformed in this buffer: when
+    /// `input_transforms` is set, unless toggled.
+    pub fn is_input_transform_on(&self) -> bool {
+        self.input_transform
+            .unwrap_or(self.settings.input_transforms.is_some())
+    }
+
+    pub fn toggle_input_transform(&mut self) {
+        let enabled = !self.is_input_transform_on();
+        self.input_transform = Some(enabled);
+        self.set_message(if enabled {
+            "Input transform on."
+        } else {
+            "Input transform off."
+        });
+    }
+
+    /// `c` as it is inserted when typed.
+    pub(super) fn transform_input(&self, c: char) -> char {
+        if !self.is_input_transform_on() {
+            return c;
+        }
+        self.input_transforms()
+            .into_iter()
+            .fold(c, |c, transform| transform.apply(c))
+    }
+}
@@ -0,0 +1,96 @@
+use crate::editor::Editor;
+
+/// Places the cursor jumped away from (a search, the start or end of the
+/// file, a heading of the outline, ...), walked back and forward like the
+/// history of a web browser.
+#[derive(Debug, Default)]
+pub struct JumpList {
+    positions: Vec<(usize, usize)>,
+    // Where the walk is in `positions`; their count when not walking
+    index: usize,
+}
+
+impl JumpList {
+    /// Remembers `origin`, forgetting the positions ahead of a walk back and
+    /// the oldest ones beyond `max`.
+    pub fn record(&mut self, origin: (usize, usize), max: usize) {
+        self.positions.truncate(self.index);
+        if self.positions.last() != Some(&origin) {
+            self.positions.push(origin);
+        }
+        let excess = self.positions.len().saturating_sub(max);
+        self.positions.drain(..excess);
+        self.index = self.positions.len();
+    }
+
+    /// The position before `current`. Starting a walk remembers `current`,
+    /// so that `forward` can come back to it.
+    pub fn back(&mut self, current: (usize, usize)) -> Option<(usize, usize)> {
+        if self.index == self.positions.len() && self.positions.last() != Some(&current) {
+            self.positions.push(current);
+        }
+        let position = self.positions[..self.index]
+            .iter()
+            .rposition(|&position| position != current)?;
+        self.index = position;
+        Some(self.positions[position])
+    }
+
+    pub fn forward(&mut self) -> Option<(usize, usize)> {
+        if self.index + 1 >= self.positions.len() {
+            return None;
+        }
+        self.index += 1;
+        Some(self.positions[self.index])
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+impl Editor {
+    /// Remembers `origin` when the cursor has jumped away from it.
+    pub(super) fn record_jump(&mut self, origin: (usize, usize)) {
+        if origin != self.cursor_pos() {
+            self.jumps.record(origin, self.settings.jump_list_size());
+        }
+    }
+
+    pub fn jump_back(&mut self) {
+        match self.jumps.back(self.cursor_pos()) {
+            Some(position) => self.go_to_jump(position),
+            None => self.set_message("No earlier position in the jump list."),
+        }
+    }
+
+    pub fn jump_forward(&mut self) {
+        match self.jumps.forward() {
+            Some(position) => self.go_to_jump(position),
+            None => self.set_message("No later position in the jump list."),
+        }
+    }
+
+    /// Goes to a remembered position, or as near as the edits since allow.
+    fn go_to_jump(&mut self, (x, y): (usize, usize)) {
+        let y = y.min(self.document.lines().len().saturating_sub(1));
+        let line = &self.document.lines()[y];
+        let mut x = x.min(line.len());
+        while !line.is_char_boundary(x) {
+            x -= 1;
+        }
+        self.set_cursor_pos(x, y);
+        self.cursor.desired_x = self
+            .scroll
+            .get_display_width_from_bytes(&self.document.lines()[y], x);
+        self.snap_to_fold_header();
+    }
+}
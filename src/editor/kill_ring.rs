@@ -0,0 +1,118 @@
+use crate::editor::agenda::truncate_to_width;
+use crate::editor::{Editor, EditorMode};
+use crate::error::Result;
+use crate::screen::Screen;
+use pancurses::{A_DIM, A_REVERSE, Input};
+use unicode_width::UnicodeWidthStr;
+
+/// The overlay listing the kill buffer and the kills before it.
+#[derive(Debug, Default)]
+pub struct KillRingBrowser {
+    // Snapshot of the kills when the overlay was opened, newest first
+    pub entries: Vec<String>,
+    pub selected_index: usize,
+    pub scroll_offset: usize,
+}
+
+/// The first line of `text` with the others counted after it, and the number
+/// of lines left out.
+fn preview(text: &str) -> (&str, usize) {
+    let mut lines = text.split('\n');
+    let first = lines.next().unwrap_or_default();
+    (first, lines.count())
+}
+
+impl Editor {
+    pub fn open_kill_ring(&mut self) {
+        let entries: Vec<String> = self.clipboard.kills().cloned().collect();
+        if entries.is_empty() {
+            self.set_message("Kill ring is empty.");
+            return;
+        }
+        self.kill_ring_browser = KillRingBrowser {
+            entries,
+            ..KillRingBrowser::default()
+        };
+        self.mode = EditorMode::KillRing;
+    }
+
+    pub fn handle_kill_ring_input(&mut self, key: Input) -> Result<()> {
+        let browser = &mut self.kill_ring_browser;
+        let count = browser.entries.len();
+        match key {
+            Input::KeyUp | Input::Character('\x10') if count > 0 => {
+                browser.selected_index = (browser.selected_index + count - 1) % count;
+            }
+            Input::KeyDown | Input::Character('\x0e') if count > 0 => {
+                browser.selected_index = (browser.selected_index + 1) % count;
+            }
+            Input::Character('\n') | Input::Character('\r') => {
+                let Some(text) = browser.entries.get(browser.selected_index).cloned() else {
+                    return Ok(());
+                };
+                self.close_kill_ring();
+                // The chosen kill becomes the latest, so that `Ctrl-Y` yanks it again
+                self.clipboard.take_system_text(text.clone());
+                self.set_clipboard(&text);
+                self.clipboard.last_action_was_kill = false;
+                return self.yank_text(text);
+            }
+            // Esc or Ctrl-G
+            Input::Character('\x1b') | Input::Character('\x07') => self.close_kill_ring(),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn close_kill_ring(&mut self) {
+        self.kill_ring_browser = KillRingBrowser::default();
+        self.mode = EditorMode::Normal;
+    }
+
+    pub fn draw_kill_ring(&mut self, window: &impl Screen) {
+        window.erase();
+        let screen_rows = window.get_max_y() as usize;
+        let screen_cols = window.get_max_x() as usize;
+        let list_height = screen_rows.saturating_sub(1);
+        let browser = &mut self.kill_ring_browser;
+
+        if browser.selected_index < browser.scroll_offset {
+            browser.scroll_offset = browser.selected_index;
+        }
+        if browser.selected_index >= browser.scroll_offset + list_height {
+            browser.scroll_offset = browser.selected_index + 1 - list_height;
+        }
+
+        let offset = browser.scroll_offset;
+        for (row, text) in browser
+            .entries
+            .iter()
+            .skip(offset)
+            .take(list_height)
+            .enumerate()
+        {
+            let (first, more) = preview(text);
+            let more = match more {
+                0 => String::new(),
+                1 => "  +1 line".to_string(),
+                n => format!("  +{n} lines"),
+            };
+            let first = truncate_to_width(first, screen_cols.saturating_sub(more.width()));
+            let is_selected = offset + row == browser.selected_index;
+            if is_selected {
+                window.attron(A_REVERSE);
+            }
+            window.mvaddstr(row as i32, 0, first);
+            if is_selected {
+                window.attroff(A_REVERSE);
+            }
+            window.attron(A_DIM);
+            window.addstr(&more);
+            window.attroff(A_DIM);
+        }
+
+        let prompt = "KILL RING: Enter yanks, Esc cancels";
+        window.mvaddstr(screen_rows as i32 - 1, 0, prompt);
+        window.refresh();
+    }
+}
@@ -0,0 +1,280 @@
+use crate::document::{ActionDiff, LineBuffer};
+use crate::editor::indent::{is_list_item, leading_spaces, next_level, previous_level};
+use crate::editor::structure::{enclosing_list_item, subtree_end};
+use crate::editor::{Editor, LastActionType};
+use crate::error::Result;
+
+/// The list item above the one on line `y` at the same level, passing over
+/// the lines nested under it and blank lines.
+pub fn previous_sibling(lines: &LineBuffer, y: usize) -> Option<usize> {
+    let indent = leading_spaces(&lines[y]);
+    (0..y).rev().find_map(|above| {
+        let line = &lines[above];
+        if line.trim().is_empty() || leading_spaces(line) > indent {
+            return None;
+        }
+        Some((leading_spaces(line) == indent && is_list_item(line)).then_some(above))
+    })?
+}
+
+/// The list item after the subtree of the one on line `y`, at the same level.
+pub fn next_sibling(lines: &LineBuffer, y: usize) -> Option<usize> {
+    let indent = leading_spaces(&lines[y]);
+    let below =
+        (subtree_end(lines, y) + 1..lines.len()).find(|&below| !lines[below].trim().is_empty())?;
+    let line = &lines[below];
+    (leading_spaces(line) == indent && is_list_item(line)).then_some(below)
+}
+
+/// `lines` moved from the level of their first line to `indent` spaces, each
+/// staying as deep under the first as it was.
+fn reindent_lines(lines: &mut [String], indent: usize) {
+    let Some(first) = lines.first().map(|line| leading_spaces(line)) else {
+        return;
+    };
+    for line in lines.iter_mut().filter(|line| !line.trim().is_empty()) {
+        let current = leading_spaces(line);
+        let shifted = (current + indent).saturating_sub(first);
+        *line = format!("{}{}", " ".repeat(shifted), &line[current..]);
+    }
+}
+
+impl Editor {
+    /// The list item the cursor is in and the last line of its subtree.
+    fn list_subtree_at_cursor(&mut self) -> Option<(usize, usize)> {
+        let lines = self.document.lines();
+        let Some(start) = enclosing_list_item(lines, self.cursor.y) else {
+            self.set_message("Not in a list item.");
+            return None;
+        };
+        Some((start, subtree_end(lines, start)))
+    }
+
+    /// Swaps the list item the cursor is in, with the lines nested under it,
+    /// and the item above it at the same level.
+    pub fn move_subtree_up(&mut self) {
+        self.clipboard.last_action_was_kill = false;
+        let Some((start, end)) = self.list_subtree_at_cursor() else {
+            return;
+        };
+        let Some(above) = previous_sibling(self.document.lines(), start) else {
+            self.set_message("No list item above at this level.");
+            return;
+        };
+        let above_end = subtree_end(self.document.lines(), above);
+        self.swap_subtrees(above, above_end, start, end);
+    }
+
+    /// Swaps the list item the cursor is in, with the lines nested under it,
+    /// and the item below it at the same level.
+    pub fn move_subtree_down(&mut self) {
+        self.clipboard.last_action_was_kill = false;
+        let Some((start, end)) = self.list_subtree_at_cursor() else {
+            return;
+        };
+        let Some(below) = next_sibling(self.document.lines(), start) else {
+            self.set_message("No list item below at this level.");
+            return;
+        };
+        let below_end = subtree_end(self.document.lines(), below);
+        self.swap_subtrees(start, end, below, below_end);
+    }
+
+    /// Swaps lines `first..=first_end` and `second..=second_end` as one undo
+    /// step, leaving the blank lines between them in place. The cursor moves
+    /// along with its line.
+    fn swap_subtrees(&mut self, first: usize, first_end: usize, second: usize, second_end: usize) {
+        let list_starts = (
+            self.ordered_list_start(first),
+            self.ordered_list_start(second),
+        );
+        let lines = self.document.lines();
+        let new: Vec<String> = lines
+            .range(second..=second_end)
+            .chain(lines.range(first_end + 1..second))
+            .chain(lines.range(first..=first_end))
+            .cloned()
+            .collect();
+        let old: Vec<String> = lines.range(first..=second_end).cloned().collect();
+        let old_end_x = lines[second_end].len();
+
+        let (x, y) = self.cursor_pos();
+        let cursor_y = if y >= second {
+            y - (second - first)
+        } else if y <= first_end {
+            y + (second_end - first_end)
+        } else {
+            y
+        };
+        self.commit(
+            LastActionType::LineMovement,
+            &ActionDiff {
+                cursor_start_x: x,
+                cursor_start_y: y,
+                cursor_end_x: 0,
+                cursor_end_y: first,
+                start_x: 0,
+                start_y: first,
+                end_x: old_end_x,
+                end_y: second_end,
+                new: vec![],
+                old,
+            },
+        );
+        self.commit(
+            LastActionType::Ammend,
+            &ActionDiff {
+                cursor_start_x: 0,
+                cursor_start_y: first,
+                cursor_end_x: x,
+                cursor_end_y: cursor_y,
+                start_x: 0,
+                start_y: first,
+                end_x: new.last().map_or(0, String::len),
+                end_y: second_end,
+                new,
+                old: vec![],
+            },
+        );
+        // A blank line between the items makes them lists of their own
+        self.renumber_ordered_list(first, list_starts.0);
+        self.renumber_ordered_list(first + second_end - first_end, list_starts.1);
+    }
+
+    /// Moves the list item the cursor is in, with the lines nested under it,
+    /// one level in or out.
+    pub(super) fn shift_list_subtree(&mut self, demote: bool) {
+        self.clipboard.last_action_was_kill = false;
+        let Some((start, end)) = self.list_subtree_at_cursor() else {
+            return;
+        };
+        let indent = leading_spaces(&self.document.lines()[start]);
+        let width = self.settings.indent_width();
+        if !demote && indent == 0 {
+            self.set_message("Already a top-level list item.");
+            return;
+        }
+        let new_indent = if demote {
+            next_level(indent, width)
+        } else {
+            previous_level(indent, width)
+        };
+        self.undo_redo.begin_group();
+        self.reindent_list_subtree(start, end, new_indent);
+        self.undo_redo.end_group();
+
+        let verb = if demote { "Demoted" } else { "Promoted" };
+        self.status_message = match end - start {
+            0 => format!("{verb} list item."),
+            1 => format!("{verb} list item and the line under it."),
+            nested => format!("{verb} list item and {nested} lines under it."),
+        };
+    }
+
+    /// Kills the list item the cursor is in together with the lines nested
+    /// under it, as whole lines.
+    pub fn kill_subtree(&mut self) -> Result<()> {
+        let Some((start, end)) = self.list_subtree_at_cursor() else {
+            return Ok(());
+        };
+        let indent = leading_spaces(&self.document.lines()[start]);
+        let list_start = self.ordered_list_start(start);
+        let lines = self.document.lines();
+        let killed: Vec<String> = lines.range(start..=end).cloned().collect();
+        let text = format!("{}\n", killed.join("\n"));
+
+        // Take the line break after the subtree, or the one before it at the
+        // end of the buffer
+        let ((start_x, start_y), (end_x, end_y), old) = if end + 1 < lines.len() {
+            let old = [killed, vec![String::new()]].concat();
+            ((0, start), (0, end + 1), old)
+        } else if start > 0 {
+            let old = [vec![String::new()], killed].concat();
+            (
+                (lines[start - 1].len(), start - 1),
+                (lines[end].len(), end),
+                old,
+            )
+        } else {
+            ((0, start), (lines[end].len(), end), killed)
+        };
+        self.commit(
+            LastActionType::Deletion,
+            &ActionDiff {
+                cursor_start_x: self.cursor.x,
+                cursor_start_y: self.cursor.y,
+                cursor_end_x: start_x,
+                cursor_end_y: start_y,
+                start_x,
+                start_y,
+                end_x,
+                end_y,
+                new: vec![],
+                old,
+            },
+        );
+        // The next item of the same list takes the place of the killed one
+        if self
+            .document
+            .lines()
+            .get(start)
+            .is_some_and(|line| leading_spaces(line) == indent)
+        {
+            self.renumber_ordered_list(start, list_start);
+        }
+
+        self.clipboard.push_kill(text.clone());
+        self.set_clipboard(&text);
+        self.clipboard.last_action_was_kill = false;
+        self.status_message = match end - start {
+            0 => "Killed list item.".to_string(),
+            1 => "Killed list item and the line under it.".to_string(),
+            nested => format!("Killed list item and {nested} lines under it."),
+        };
+        Ok(())
+    }
+
+    /// Puts the lines of the kill buffer back above the cursor line. On a
+    /// list item they are moved to its level, so that a killed subtree lands
+    /// as the item's sibling.
+    pub fn yank_subtree(&mut self) -> Result<()> {
+        self.clipboard.last_action_was_kill = false;
+        if let Some(text) = self.clipboard.get_clipboard_text() {
+            self.clipboard.take_system_text(text);
+        }
+        let kill_buffer = &self.clipboard.kill_buffer;
+        let text = kill_buffer.strip_suffix('\n').unwrap_or(kill_buffer);
+        if text.is_empty() {
+            self.set_message("Kill buffer is empty.");
+            return Ok(());
+        }
+        let mut yanked: Vec<String> = text.split('\n').map(str::to_string).collect();
+
+        let y = self.cursor.y;
+        let current = &self.document.lines()[y];
+        if is_list_item(current) {
+            reindent_lines(&mut yanked, leading_spaces(current));
+        }
+        let list_start = self.ordered_list_start(y);
+        let count = yanked.len();
+        let cursor_x = leading_spaces(&yanked[0]);
+        yanked.push(String::new());
+        self.commit(
+            LastActionType::Insertion,
+            &ActionDiff {
+                cursor_start_x: self.cursor.x,
+                cursor_start_y: y,
+                cursor_end_x: cursor_x,
+                cursor_end_y: y,
+                start_x: 0,
+                start_y: y,
+                end_x: 0,
+                end_y: y + count,
+                new: yanked,
+                old: vec![],
+            },
+        );
+        self.renumber_ordered_list(y, list_start);
+        Ok(())
+    }
+}
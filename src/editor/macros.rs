@@ -0,0 +1,93 @@
+use crate::editor::Editor;
+use crate::editor::actions::Action;
+use crate::error::Result;
+
+#[derive(Default, Debug)]
+pub struct Macros {
+    pub recording: Option<Vec<Action>>,
+    pub last_macro: Vec<Action>,
+}
+
+impl Macros {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// Adds an executed action to the macro being recorded. The macro
+    /// commands themselves are never recorded, so playing a macro while
+    /// recording captures what it did instead.
+    pub fn record(&mut self, action: &Action) {
+        if let Some(recording) = self.recording.as_mut()
+            && !matches!(
+                action,
+                Action::StartMacroRecording
+                    | Action::StopMacroRecording
+                    | Action::PlayMacro(_)
+                    // Repeated actions are recorded once per repetition
+                    | Action::DigitArgument(_)
+                    | Action::UniversalArgument
+                    // The action run from the palette is recorded instead
+                    | Action::CommandPalette
+            )
+        {
+            recording.push(action.clone());
+        }
+    }
+}
+
+impl Editor {
+    pub fn start_macro_recording(&mut self) {
+        if self.macros.is_recording() {
+            self.set_message("Already recording a macro.");
+            return;
+        }
+        self.macros.recording = Some(Vec::new());
+        self.set_message("Recording macro...");
+    }
+
+    pub fn stop_macro_recording(&mut self) {
+        match self.macros.recording.take() {
+            Some(actions) if !actions.is_empty() => {
+                let message = format!("Macro recorded ({} actions).", actions.len());
+                self.macros.last_macro = actions;
+                self.set_message(&message);
+            }
+            Some(_) => self.set_message("Empty macro discarded."),
+            None => self.set_message("Not recording a macro."),
+        }
+    }
+
+    /// Replays the last recorded macro `count` times as a single undo step.
+    pub fn play_macro(&mut self, count: usize) -> Result<()> {
+        if self.macros.last_macro.is_empty() {
+            self.set_message("No macro recorded.");
+            return Ok(());
+        }
+        let actions = self.macros.last_macro.clone();
+        let count = count.max(1);
+
+        self.undo_redo.begin_group();
+        let mut result = Ok(());
+        'playback: for _ in 0..count {
+            for action in &actions {
+                result = self.execute_action(action.clone());
+                if result.is_err() {
+                    break 'playback;
+                }
+            }
+        }
+        self.undo_redo.end_group();
+
+        result?;
+        if count == 1 {
+            self.set_message("Macro played.");
+        } else {
+            self.set_message(&format!("Macro played {count} times."));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,254 @@
+use crate::editor::{Editor, EditorMode};
+use crate::inbox;
+use pancurses::Input;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+const MAX_HISTORY: usize = 50;
+
+/// What the minibuffer input is for. Each kind keeps its own history.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PromptKind {
+    GoToLine,
+    GotoPage,
+    SaveAs,
+    CopyAs,
+    Bookmark,
+    SaveSession,
+    GitCommit,
+    GrepProject,
+}
+
+impl PromptKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PromptKind::GoToLine => "Goto line: ",
+            PromptKind::GotoPage => "Goto page: ",
+            PromptKind::SaveAs => "Save as: ",
+            PromptKind::CopyAs => "Copy as (text, html, jira, slack): ",
+            PromptKind::Bookmark => "Bookmark name (empty for a number): ",
+            PromptKind::SaveSession => "Save session as: ",
+            PromptKind::GitCommit => "Commit message: ",
+            PromptKind::GrepProject => "Grep project: ",
+        }
+    }
+
+    fn completes_paths(&self) -> bool {
+        matches!(self, PromptKind::SaveAs)
+    }
+}
+
+pub enum MinibufferResult {
+    Continue,
+    Submit(String),
+    Cancel,
+}
+
+/// Single-line prompt shown in the status bar for asking the user for input.
+#[derive(Default, Debug)]
+pub struct Minibuffer {
+    pub prompt: Option<PromptKind>,
+    pub input: String,
+    pub cursor: usize, // byte index into `input`
+    history: HashMap<PromptKind, Vec<String>>,
+    history_index: Option<usize>,
+}
+
+impl Minibuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn open(&mut self, kind: PromptKind, input: &str) {
+        self.prompt = Some(kind);
+        self.input = input.to_string();
+        self.cursor = self.input.len();
+        self.history_index = None;
+    }
+
+    pub fn close(&mut self) {
+        self.prompt = None;
+        self.input.clear();
+        self.cursor = 0;
+        self.history_index = None;
+    }
+
+    pub fn history(&self, kind: PromptKind) -> &[String] {
+        self.history.get(&kind).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn handle_key(&mut self, key: Input) -> MinibufferResult {
+        let Some(kind) = self.prompt else {
+            return MinibufferResult::Cancel;
+        };
+        match key {
+            Input::Character('\n') | Input::Character('\r') => {
+                let input = std::mem::take(&mut self.input);
+                self.push_history(kind, &input);
+                self.close();
+                return MinibufferResult::Submit(input);
+            }
+            // Esc or Ctrl-G
+            Input::Character('\x1b') | Input::Character('\x07') => {
+                self.close();
+                return MinibufferResult::Cancel;
+            }
+            Input::Character('\x7f') | Input::Character('\x08') | Input::KeyBackspace => {
+                if let Some(c) = self.input[..self.cursor].chars().next_back() {
+                    self.cursor -= c.len_utf8();
+                    self.input.remove(self.cursor);
+                }
+            }
+            Input::Character('\x04') | Input::KeyDC if self.cursor < self.input.len() => {
+                self.input.remove(self.cursor);
+            }
+            Input::Character('\x01') | Input::KeyHome => self.cursor = 0,
+            Input::Character('\x05') | Input::KeyEnd => self.cursor = self.input.len(),
+            Input::Character('\x0b') => self.input.truncate(self.cursor),
+            Input::Character('\x02') | Input::KeyLeft => {
+                if let Some(c) = self.input[..self.cursor].chars().next_back() {
+                    self.cursor -= c.len_utf8();
+                }
+            }
+            Input::Character('\x06') | Input::KeyRight => {
+                if let Some(c) = self.input[self.cursor..].chars().next() {
+                    self.cursor += c.len_utf8();
+                }
+            }
+            Input::Character('\t') if kind.completes_paths() => self.complete_directory(),
+            Input::Character('\x10') | Input::KeyUp => self.recall_history(kind, true),
+            Input::Character('\x0e') | Input::KeyDown => self.recall_history(kind, false),
+            Input::Character(c) if !c.is_control() => {
+                self.input.insert(self.cursor, c);
+                self.cursor += c.len_utf8();
+            }
+            _ => {}
+        }
+        MinibufferResult::Continue
+    }
+
+    /// Completes the last path component to the directories it matches, as far
+    /// as their names agree.
+    fn complete_directory(&mut self) {
+        let split = self.input.rfind('/').map_or(0, |i| i + 1);
+        let (dir_part, prefix) = self.input.split_at(split);
+        let dir = if dir_part.is_empty() {
+            PathBuf::from(".")
+        } else {
+            inbox::resolve_path(dir_part)
+        };
+        let Ok(entries) = fs::read_dir(&dir) else {
+            return;
+        };
+        let matches: Vec<String> = entries
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .filter(|name| name.starts_with(prefix))
+            // Hidden directories only when asked for
+            .filter(|name| !name.starts_with('.') || prefix.starts_with('.'))
+            .collect();
+        let completion = match matches.as_slice() {
+            [] => return,
+            [only] => format!("{only}/"),
+            [first, rest @ ..] => rest.iter().fold(first.clone(), |common, name| {
+                common
+                    .chars()
+                    .zip(name.chars())
+                    .take_while(|(a, b)| a == b)
+                    .map(|(a, _)| a)
+                    .collect()
+            }),
+        };
+        self.input = format!("{dir_part}{completion}");
+        self.cursor = self.input.len();
+    }
+
+    fn push_history(&mut self, kind: PromptKind, input: &str) {
+        if input.is_empty() {
+            return;
+        }
+        let history = self.history.entry(kind).or_default();
+        history.retain(|entry| entry != input);
+        history.push(input.to_string());
+        if history.len() > MAX_HISTORY {
+            history.remove(0);
+        }
+    }
+
+    /// Steps through earlier inputs of the same kind, most recent first.
+    fn recall_history(&mut self, kind: PromptKind, older: bool) {
+        let history = self.history(kind);
+        if history.is_empty() {
+            return;
+        }
+        let index = match (self.history_index, older) {
+            (None, true) => Some(history.len() - 1),
+            (None, false) => None,
+            (Some(i), true) => Some(i.saturating_sub(1)),
+            (Some(i), false) if i + 1 < history.len() => Some(i + 1),
+            (Some(_), false) => None,
+        };
+        self.input = index.map_or_else(String::new, |i| history[i].clone());
+        self.cursor = self.input.len();
+        self.history_index = index;
+    }
+}
+
+impl Editor {
+    pub fn open_minibuffer(&mut self, kind: PromptKind) {
+        self.open_minibuffer_with_input(kind, "");
+    }
+
+    /// Opens a prompt with `input` already filled in for editing.
+    pub fn open_minibuffer_with_input(&mut self, kind: PromptKind, input: &str) {
+        self.minibuffer.open(kind, input);
+        self.mode = EditorMode::Minibuffer;
+    }
+
+    pub fn handle_minibuffer_input(&mut self, key: Input) {
+        let Some(kind) = self.minibuffer.prompt else {
+            self.mode = EditorMode::Normal;
+            return;
+        };
+        match self.minibuffer.handle_key(key) {
+            MinibufferResult::Continue => {}
+            MinibufferResult::Cancel => {
+                self.mode = EditorMode::Normal;
+                self.set_message("Cancelled.");
+            }
+            MinibufferResult::Submit(input) => {
+                self.mode = EditorMode::Normal;
+                self.submit_minibuffer(kind, &input);
+            }
+        }
+    }
+
+    fn submit_minibuffer(&mut self, kind: PromptKind, input: &str) {
+        match kind {
+            PromptKind::GoToLine => self.go_to_line_input(input),
+            PromptKind::GotoPage => self.go_to_page_input(input),
+            PromptKind::SaveAs => self.save_document_as(input),
+            PromptKind::CopyAs => self.copy_selection_as(input),
+            PromptKind::Bookmark => self.set_bookmark_named(input),
+            PromptKind::SaveSession => self.save_session_as(input),
+            PromptKind::GitCommit => self.git_commit_file_with_message(input),
+            PromptKind::GrepProject => self.grep_project(input),
+        }
+    }
+
+    /// Jumps to `line[:column]`, both 1-based.
+    fn go_to_line_input(&mut self, input: &str) {
+        let mut parts = input.trim().splitn(2, ':');
+        let line = parts.next().and_then(|s| s.trim().parse::<usize>().ok());
+        let column = parts.next().map(|s| s.trim().parse::<usize>().ok());
+        let origin = self.cursor_pos();
+        match (line, column) {
+            (Some(line), None) => self.go_to_line_column(line, None),
+            (Some(line), Some(Some(column))) => self.go_to_line_column(line, Some(column)),
+            _ => self.set_message(&format!("Invalid line number: {input}")),
+        }
+        self.record_jump(origin);
+    }
+}
@@ -0,0 +1,158 @@
+use crate::editor::cursor::Cursor;
+use crate::editor::placeholder::byte_at_column;
+use crate::editor::ui::STATUS_BAR_HEIGHT;
+use crate::editor::{Editor, EditorMode};
+use serde::{Deserialize, Serialize};
+
+const WHEEL_SCROLL_LINES: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MouseEvent {
+    Press { row: usize, col: usize },
+    Drag { row: usize, col: usize },
+    Release { row: usize, col: usize },
+    ScrollUp,
+    ScrollDown,
+}
+
+#[derive(Default, Debug)]
+pub struct Mouse {
+    pub drag_origin: Option<(usize, usize)>,
+}
+
+impl Mouse {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Editor {
+    pub fn handle_mouse_event(&mut self, event: MouseEvent) {
+        if self.mode != EditorMode::Normal || self.search.mode {
+            return;
+        }
+        self.clipboard.last_action_was_kill = false;
+
+        match event {
+            MouseEvent::Press { row, col } => {
+                if let Some((x, y)) = self.screen_to_document_pos(row, col) {
+                    self.last_change = None;
+                    self.selection.clear_marker();
+                    self.move_cursor_to(x, y);
+                    self.mouse.drag_origin = Some((x, y));
+                }
+            }
+            MouseEvent::Drag { row, col } => {
+                if let Some(origin) = self.mouse.drag_origin
+                    && let Some((x, y)) = self.screen_to_document_pos(row, col)
+                {
+                    if !self.selection.is_selection_active() && (x, y) != origin {
+                        self.selection.set_marker(&Cursor::new(origin.0, origin.1));
+                    }
+                    self.move_cursor_to(x, y);
+                }
+            }
+            MouseEvent::Release { row, col } => {
+                if self.mouse.drag_origin.is_some() {
+                    if let Some((x, y)) = self.screen_to_document_pos(row, col) {
+                        self.move_cursor_to(x, y);
+                    }
+                    if self.selection.marker_pos == Some(self.cursor_pos()) {
+                        self.selection.clear_marker();
+                    }
+                }
+                self.mouse.drag_origin = None;
+            }
+            MouseEvent::ScrollUp => {
+                let amount = WHEEL_SCROLL_LINES.min(self.scroll.row_offset);
+                self.scroll.row_offset -= amount;
+                self.cursor.y = self.cursor.y.saturating_sub(amount);
+                self.restore_desired_cursor_x();
+            }
+            MouseEvent::ScrollDown => {
+                let last_line = self.document.lines().len().saturating_sub(1);
+                let amount =
+                    WHEEL_SCROLL_LINES.min(last_line.saturating_sub(self.scroll.row_offset));
+                self.scroll.row_offset += amount;
+                self.cursor.y = (self.cursor.y + amount).min(last_line);
+                self.restore_desired_cursor_x();
+            }
+        }
+    }
+
+    /// Maps a screen cell to a `(byte_x, line_y)` document position, mirroring
+    /// how `draw` lays out the line prefix, the horizontal-scroll ellipsis and
+    /// wide characters.
+    pub fn screen_to_document_pos(&self, row: usize, col: usize) -> Option<(usize, usize)> {
+        if row < STATUS_BAR_HEIGHT || row >= self.scroll.screen_rows {
+            return None;
+        }
+        if self.document.lines().is_empty() {
+            return None;
+        }
+        let y = self.line_at_visible_row(
+            self.visible_row(self.scroll.row_offset) + row - STATUS_BAR_HEIGHT,
+        );
+        let line = &self.document.lines()[y];
+        // The git gutter is left of the text
+        let col = col.saturating_sub(self.gutter_width());
+
+        if Self::is_separator_line(line) {
+            return Some((0, y));
+        }
+        let placeholders = self.line_placeholders(y);
+        if !placeholders.is_empty() {
+            return Some((byte_at_column(line, &placeholders, col), y));
+        }
+
+        let (prefix_byte_len, prefix_display_width) = self.get_prefix_info(line);
+        let col_offset = if y == self.cursor.y {
+            self.scroll.col_offset
+        } else {
+            0
+        };
+
+        if col < prefix_display_width || col_offset == 0 {
+            let (x, _) = self.scroll.get_byte_pos_from_display_width(line, col);
+            return Some((x, y));
+        }
+
+        let content = &line[prefix_byte_len..];
+        let (_, display_pos) = self
+            .scroll
+            .get_byte_pos_from_display_width(content, col_offset);
+        let wide_char_scroll_adjust = display_pos < col_offset;
+        let (ellipsis_width, effective_col_offset) = if wide_char_scroll_adjust {
+            (2, col_offset + 1)
+        } else {
+            (1, col_offset)
+        };
+
+        let content_col = col
+            .saturating_sub(prefix_display_width + ellipsis_width)
+            .saturating_add(effective_col_offset);
+        let (x, _) = self
+            .scroll
+            .get_byte_pos_from_display_width(content, content_col);
+        Some((prefix_byte_len + x, y))
+    }
+
+    fn move_cursor_to(&mut self, x: usize, y: usize) {
+        self.cursor.y = y;
+        self.cursor.x = x;
+        self.scroll.clamp_cursor_x(&mut self.cursor, &self.document);
+        self.cursor.desired_x = self
+            .scroll
+            .get_display_width_from_bytes(&self.document.lines()[self.cursor.y], self.cursor.x);
+    }
+
+    fn restore_desired_cursor_x(&mut self) {
+        self.cursor.x = self
+            .scroll
+            .get_byte_pos_from_display_width(
+                &self.document.lines()[self.cursor.y],
+                self.cursor.desired_x,
+            )
+            .0;
+    }
+}
@@ -0,0 +1,220 @@
+use crate::document::LineBuffer;
+use crate::editor::agenda::truncate_to_width;
+use crate::editor::heading::heading_level;
+use crate::editor::{Editor, EditorMode};
+use crate::screen::Screen;
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use pancurses::{A_BOLD, A_DIM, A_REVERSE, Input};
+
+const MARGIN: usize = 2;
+
+/// A heading, or with `outline_pages` a `---` page, listed in the outline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutlineEntry {
+    pub line: usize,
+    // Heading level, 0 for a page
+    pub level: usize,
+    pub title: String,
+}
+
+impl OutlineEntry {
+    /// The entry as listed, indented by its nesting.
+    pub fn label(&self) -> String {
+        if self.level == 0 {
+            format!("--- {}", self.title)
+        } else {
+            format!("{}{}", "  ".repeat(self.level - 1), self.title)
+        }
+    }
+}
+
+/// The headings in `lines`, and the pages after each `---` delimiter titled
+/// by their first non-empty line when `include_pages` is set.
+pub fn outline_entries(lines: &LineBuffer, include_pages: bool) -> Vec<OutlineEntry> {
+    let mut entries = Vec::new();
+    for (y, line) in lines.iter().enumerate() {
+        if let Some(level) = heading_level(line) {
+            entries.push(OutlineEntry {
+                line: y,
+                level,
+                title: line[level..].trim().to_string(),
+            });
+        } else if include_pages && line == "---" {
+            let title = lines
+                .range(y + 1..)
+                .take_while(|line| *line != "---")
+                .map(|line| line.trim_start_matches('#').trim())
+                .find(|line| !line.is_empty())
+                .unwrap_or("(empty page)");
+            entries.push(OutlineEntry {
+                line: y,
+                level: 0,
+                title: title.to_string(),
+            });
+        }
+    }
+    entries
+}
+
+#[derive(Debug, Default)]
+pub struct Outline {
+    pub entries: Vec<OutlineEntry>,
+    pub query: String,
+    // Indices into `entries` that match the query, in document order
+    pub matches: Vec<usize>,
+    pub selected: usize,
+    scroll_offset: usize,
+}
+
+impl Outline {
+    fn update_matches(&mut self) {
+        let matcher = SkimMatcherV2::default();
+        self.matches = (0..self.entries.len())
+            .filter(|&i| {
+                self.query.is_empty()
+                    || matcher
+                        .fuzzy_match(&self.entries[i].title, &self.query)
+                        .is_some()
+            })
+            .collect();
+        self.selected = 0;
+        self.scroll_offset = 0;
+    }
+
+    pub fn selected_entry(&self) -> Option<&OutlineEntry> {
+        self.matches
+            .get(self.selected)
+            .map(|&index| &self.entries[index])
+    }
+}
+
+impl Editor {
+    /// Lists the headings of the document, starting at the section the cursor
+    /// is in.
+    pub fn show_outline(&mut self) {
+        let entries = outline_entries(self.document.lines(), self.settings.outline_pages);
+        if entries.is_empty() {
+            self.set_message("No headings.");
+            return;
+        }
+        let current = entries
+            .iter()
+            .rposition(|entry| entry.line <= self.cursor.y)
+            .unwrap_or(0);
+        self.outline = Outline {
+            entries,
+            ..Outline::default()
+        };
+        self.outline.update_matches();
+        self.outline.selected = current;
+        self.mode = EditorMode::Outline;
+    }
+
+    pub fn handle_outline_input(&mut self, key: Input) {
+        let count = self.outline.matches.len();
+        match key {
+            Input::KeyUp | Input::Character('\x10') if count > 0 => {
+                self.outline.selected = (self.outline.selected + count - 1) % count;
+            }
+            Input::KeyDown | Input::Character('\x0e') if count > 0 => {
+                self.outline.selected = (self.outline.selected + 1) % count;
+            }
+            Input::Character('\n') | Input::Character('\r') => {
+                let Some(line) = self.outline.selected_entry().map(|entry| entry.line) else {
+                    return;
+                };
+                self.close_outline();
+                let origin = self.cursor_pos();
+                self.cursor.y = line;
+                self.cursor.x = 0;
+                self.cursor.desired_x = 0;
+                self.record_jump(origin);
+                // Show the section from its heading down
+                self.scroll.row_offset = line;
+            }
+            // Esc or Ctrl-G
+            Input::Character('\x1b') | Input::Character('\x07') => self.close_outline(),
+            Input::KeyBackspace | Input::Character('\x7f') | Input::Character('\x08') => {
+                self.outline.query.pop();
+                self.outline.update_matches();
+            }
+            Input::Character(c) if !c.is_control() => {
+                self.outline.query.push(c);
+                self.outline.update_matches();
+            }
+            _ => {}
+        }
+    }
+
+    fn close_outline(&mut self) {
+        self.outline = Outline::default();
+        self.mode = EditorMode::Normal;
+    }
+
+    pub fn draw_outline(&mut self, window: &impl Screen) {
+        let screen_rows = window.get_max_y() as usize;
+        let screen_cols = window.get_max_x() as usize;
+        if screen_rows <= MARGIN * 2 + 2 || screen_cols <= MARGIN * 2 + 2 {
+            return;
+        }
+        let (top, left) = (MARGIN, MARGIN);
+        let (bottom, right) = (screen_rows - MARGIN - 1, screen_cols - MARGIN - 1);
+        let inner_width = right - left - 1;
+
+        let visible_rows = bottom - top - 1;
+        let outline = &mut self.outline;
+        if outline.selected < outline.scroll_offset {
+            outline.scroll_offset = outline.selected;
+        } else if outline.selected >= outline.scroll_offset + visible_rows {
+            outline.scroll_offset = outline.selected + 1 - visible_rows;
+        }
+
+        let blank = " ".repeat(inner_width);
+        for row in top + 1..bottom {
+            window.mvaddch(row as i32, left as i32, pancurses::ACS_VLINE());
+            window.mvaddstr(row as i32, (left + 1) as i32, &blank);
+            window.mvaddch(row as i32, right as i32, pancurses::ACS_VLINE());
+        }
+        for col in left + 1..right {
+            window.mvaddch(top as i32, col as i32, pancurses::ACS_HLINE());
+            window.mvaddch(bottom as i32, col as i32, pancurses::ACS_HLINE());
+        }
+        window.mvaddch(top as i32, left as i32, pancurses::ACS_ULCORNER());
+        window.mvaddch(top as i32, right as i32, pancurses::ACS_URCORNER());
+        window.mvaddch(bottom as i32, left as i32, pancurses::ACS_LLCORNER());
+        window.mvaddch(bottom as i32, right as i32, pancurses::ACS_LRCORNER());
+        let title = format!(" Outline: {} ", outline.query);
+        window.attron(A_BOLD);
+        window.mvaddstr(
+            top as i32,
+            (left + 2) as i32,
+            truncate_to_width(&title, inner_width.saturating_sub(2)),
+        );
+        window.attroff(A_BOLD);
+
+        for (offset, &index) in outline
+            .matches
+            .iter()
+            .skip(outline.scroll_offset)
+            .take(visible_rows)
+            .enumerate()
+        {
+            let entry = &outline.entries[index];
+            let attr = if outline.scroll_offset + offset == outline.selected {
+                A_REVERSE
+            } else if entry.level == 0 {
+                A_DIM
+            } else {
+                0
+            };
+            window.attron(attr);
+            window.mvaddstr(
+                (top + 1 + offset) as i32,
+                (left + 1) as i32,
+                truncate_to_width(&entry.label(), inner_width),
+            );
+            window.attroff(attr);
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use crate::document::Document;
+use crate::editor::Editor;
+use crate::editor::fold::DELIMITER;
+
+/// The `---` delimiter lines that split the buffer into pages, found when
+/// first asked for and then updated from the lines changed since.
+#[derive(Debug, Default)]
+pub struct PageIndex {
+    delimiters: Vec<usize>,
+    // The document revision the delimiters were found for
+    revision: Option<u64>,
+}
+
+impl PageIndex {
+    /// Brings the delimiters up to date with `document`, looking only at the
+    /// lines changed since the last time when those are still remembered.
+    pub fn update(&mut self, document: &Document) {
+        let revision = document.revision();
+        if self.revision == Some(revision) {
+            return;
+        }
+        let since = self.revision.and_then(|since| {
+            Some((
+                document.changes_since(since)?,
+                document.changed_lines_since(since)?,
+            ))
+        });
+        match since {
+            Some((changes, changed)) => {
+                // Follow the delimiters that were kept through each change
+                for change in changes {
+                    let removed_end = change.start + change.removed;
+                    self.delimiters
+                        .retain(|&y| y < change.start || y >= removed_end);
+                    for y in &mut self.delimiters {
+                        if *y >= removed_end {
+                            *y = *y + change.inserted - change.removed;
+                        }
+                    }
+                }
+                self.delimiters.retain(|&y| {
+                    y < document.lines().len() && !changed.iter().any(|range| range.contains(&y))
+                });
+                for range in changed {
+                    self.delimiters
+                        .extend(range.filter(|&y| document.lines()[y] == DELIMITER));
+                }
+                self.delimiters.sort_unstable();
+            }
+            None => {
+                self.delimiters = document
+                    .lines()
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, line)| *line == DELIMITER)
+                    .map(|(y, _)| y)
+                    .collect();
+            }
+        }
+        self.revision = Some(revision);
+    }
+
+    /// Forgets the delimiters, for a buffer whose lines were replaced.
+    pub fn clear(&mut self) {
+        *self = PageIndex::default();
+    }
+
+    /// The first line of every page. A delimiter on the last line starts
+    /// no page of its own.
+    pub fn page_starts(&self, line_count: usize) -> Vec<usize> {
+        std::iter::once(0)
+            .chain(self.delimiters.iter().map(|y| y + 1))
+            .filter(|&y| y == 0 || y < line_count)
+            .collect()
+    }
+
+    /// The 1-based page that line `y` is on; a delimiter ends its page.
+    pub fn page_of(&self, y: usize) -> usize {
+        self.delimiters.partition_point(|&delimiter| delimiter < y) + 1
+    }
+}
+
+impl Editor {
+    /// The page the cursor is on and how many pages there are.
+    pub fn current_page(&mut self) -> (usize, usize) {
+        self.pages.update(&self.document);
+        let count = self.pages.page_starts(self.document.lines().len()).len();
+        (self.pages.page_of(self.cursor.y).min(count), count)
+    }
+
+    /// Moves to the first line of the 1-based page `number` and scrolls it
+    /// to the top.
+    pub fn go_to_page(&mut self, number: usize) {
+        self.pages.update(&self.document);
+        let starts = self.pages.page_starts(self.document.lines().len());
+        let Some(&y) = number.checked_sub(1).and_then(|index| starts.get(index)) else {
+            self.set_message(&format!("No page {number} (1-{}).", starts.len()));
+            return;
+        };
+        let origin = self.cursor_pos();
+        self.clipboard.last_action_was_kill = false;
+        self.set_cursor_pos(0, y);
+        self.cursor.desired_x = 0;
+        self.snap_to_fold_header();
+        self.scroll.row_offset = self.cursor.y;
+        self.record_jump(origin);
+        self.set_message(&format!("Page {number}/{}", starts.len()));
+    }
+
+    pub(super) fn go_to_page_input(&mut self, input: &str) {
+        match input.trim().parse::<usize>() {
+            Ok(number) => self.go_to_page(number),
+            Err(_) => self.set_message(&format!("Invalid page number: {input}")),
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use crate::editor::Editor;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+const TAB_STOP: usize = 4;
+// Links whose target is longer than this many characters get a placeholder
+const LONG_TARGET: usize = 40;
+
+/// A Markdown image, a link with a long target or a bare URL at `start..end`
+/// of a line, drawn as `label` unless the cursor is on the line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placeholder {
+    pub start: usize,
+    pub end: usize,
+    pub label: String,
+}
+
+/// The images of `line` as `[img: alt]`, and its links with a long target,
+/// such as a data URL, as `[link: text]`.
+pub fn placeholders(line: &str) -> Vec<Placeholder> {
+    let mut found = Vec::new();
+    let mut from = 0;
+    while let Some(offset) = line[from..].find('[') {
+        let open = from + offset;
+        let Some((close, end)) = link_at(line, open) else {
+            from = open + 1;
+            continue;
+        };
+        let text = line[open + 1..close].trim();
+        let target = &line[close + 2..end - 1];
+        if line[..open].ends_with('!') {
+            let label = if text.is_empty() {
+                "[img]".to_string()
+            } else {
+                format!("[img: {text}]")
+            };
+            found.push(Placeholder {
+                start: open - 1,
+                end,
+                label,
+            });
+        } else if target.chars().count() > LONG_TARGET {
+            found.push(Placeholder {
+                start: open,
+                end,
+                label: format!("[link: {text}]"),
+            });
+        }
+        from = end;
+    }
+    found
+}
+
+/// The bare URLs of `line` with more than a domain, as `domain…`. URLs in
+/// `links` are left to them.
+pub fn url_placeholders(line: &str, links: &[Placeholder]) -> Vec<Placeholder> {
+    let mut found = Vec::new();
+    let mut from = 0;
+    while let Some(offset) = line[from..].find("http") {
+        let start = from + offset;
+        let rest = &line[start..];
+        let Some(scheme) = ["https://", "http://"]
+            .into_iter()
+            .find(|scheme| rest.starts_with(scheme))
+        else {
+            from = start + "http".len();
+            continue;
+        };
+        let url_len = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '(' | ')' | '[' | ']'))
+            .unwrap_or(rest.len());
+        // Punctuation closing a sentence isn't part of the URL
+        let url = rest[..url_len].trim_end_matches(['.', ',', ';', ':', '!', '?', '"', '\'']);
+        let end = start + url.len();
+        from = start + url_len;
+
+        let address = &url[scheme.len()..];
+        let domain_len = address.find(['/', '?', '#']).unwrap_or(address.len());
+        let (domain, path) = address.split_at(domain_len);
+        let inside_link = links
+            .iter()
+            .any(|link| start < link.end && link.start < end);
+        if domain.is_empty() || path.len() <= 1 || inside_link || line[..start].ends_with("](") {
+            continue;
+        }
+        found.push(Placeholder {
+            start,
+            end,
+            label: format!("{domain}…"),
+        });
+    }
+    found
+}
+
+/// The `]` and the end of the `[text](target)` starting at `open`.
+fn link_at(line: &str, open: usize) -> Option<(usize, usize)> {
+    let close = open + line[open..].find(']')?;
+    if line[open + 1..close].contains('[') || !line[close + 1..].starts_with('(') {
+        return None;
+    }
+    let end = close + 2 + line[close + 2..].find(')')? + 1;
+    Some((close, end))
+}
+
+/// The byte of `line` drawn at display column `column` when `placeholders`
+/// are shown in place of their links. A column within a placeholder gives the
+/// start of its link.
+pub fn byte_at_column(line: &str, placeholders: &[Placeholder], column: usize) -> usize {
+    let mut x = 0;
+    let mut byte = 0;
+    while let Some(ch) = line[byte..].chars().next() {
+        let (width, next) = match placeholders.iter().find(|p| p.start == byte) {
+            Some(placeholder) => (placeholder.label.width(), placeholder.end),
+            None if ch == '\t' => (TAB_STOP - x % TAB_STOP, byte + 1),
+            None => (ch.width().unwrap_or(0), byte + ch.len_utf8()),
+        };
+        if x + width > column {
+            return byte;
+        }
+        x += width;
+        byte = next;
+    }
+    line.len()
+}
+
+impl Editor {
+    /// The placeholders line `y` is drawn with. The cursor line shows its
+    /// links in full, so that they can be edited.
+    pub fn line_placeholders(&self, y: usize) -> Vec<Placeholder> {
+        let Some(line) = self.document.lines().get(y).filter(|_| y != self.cursor.y) else {
+            return Vec::new();
+        };
+        let mut found = if self.settings.link_placeholders() {
+            placeholders(line)
+        } else {
+            Vec::new()
+        };
+        if self.settings.shorten_urls {
+            let urls = url_placeholders(line, &found);
+            found.extend(urls);
+            found.sort_by_key(|placeholder| placeholder.start);
+        }
+        found
+    }
+
+    pub fn toggle_shorten_urls(&mut self) {
+        self.settings.shorten_urls = !self.settings.shorten_urls;
+        self.set_message(if self.settings.shorten_urls {
+            "URL shortening on."
+        } else {
+            "URL shortening off."
+        });
+    }
+}
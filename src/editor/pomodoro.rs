@@ -0,0 +1,119 @@
+use crate::editor::Editor;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_WORK_MINS: u64 = 25;
+pub const DEFAULT_BREAK_MINS: u64 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PomodoroPhase {
+    Work,
+    Break,
+}
+
+#[derive(Default, Debug)]
+pub struct Pomodoro {
+    pub phase: Option<PomodoroPhase>,
+    pub ends_at: Option<Instant>,
+    pub completed: usize,
+    // Shown highlighted in the status bar until the next key press
+    pub alert: Option<String>,
+}
+
+impl Pomodoro {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.phase.is_some()
+    }
+
+    pub fn remaining(&self) -> Option<Duration> {
+        self.ends_at
+            .map(|ends_at| ends_at.saturating_duration_since(Instant::now()))
+    }
+
+    /// Countdown for the status bar, e.g. `work 24:59`.
+    pub fn status(&self) -> Option<String> {
+        let phase = match self.phase? {
+            PomodoroPhase::Work => "work",
+            PomodoroPhase::Break => "break",
+        };
+        let secs = self.remaining()?.as_secs();
+        Some(format!("{phase} {:02}:{:02}", secs / 60, secs % 60))
+    }
+
+    fn begin(&mut self, phase: PomodoroPhase, minutes: u64) {
+        self.phase = Some(phase);
+        self.ends_at = Some(Instant::now() + Duration::from_secs(minutes * 60));
+    }
+
+    fn stop(&mut self) {
+        self.phase = None;
+        self.ends_at = None;
+    }
+}
+
+impl Editor {
+    pub fn start_pomodoro(&mut self) {
+        let minutes = self
+            .settings
+            .pomodoro_work_mins
+            .unwrap_or(DEFAULT_WORK_MINS);
+        self.pomodoro.begin(PomodoroPhase::Work, minutes);
+        self.pomodoro.alert = None;
+        self.set_message(&format!("Pomodoro started: {minutes} minutes of work."));
+    }
+
+    pub fn stop_pomodoro(&mut self) {
+        if !self.pomodoro.is_running() {
+            self.set_message("No pomodoro running.");
+            return;
+        }
+        self.pomodoro.stop();
+        self.set_message("Pomodoro stopped.");
+    }
+
+    pub fn pomodoro_status(&mut self) {
+        let message = match self.pomodoro.status() {
+            Some(status) => format!(
+                "Pomodoro: {status} left, {} completed this session.",
+                self.pomodoro.completed
+            ),
+            None => format!(
+                "No pomodoro running, {} completed this session.",
+                self.pomodoro.completed
+            ),
+        };
+        self.set_message(&message);
+    }
+
+    /// Called from the main loop; moves on to the next period when the current
+    /// one is over. Returns true when the terminal bell should ring.
+    pub fn pomodoro_tick(&mut self) -> bool {
+        let (Some(phase), Some(ends_at)) = (self.pomodoro.phase, self.pomodoro.ends_at) else {
+            return false;
+        };
+        if Instant::now() < ends_at {
+            return false;
+        }
+
+        match phase {
+            PomodoroPhase::Work => {
+                self.pomodoro.completed += 1;
+                let minutes = self
+                    .settings
+                    .pomodoro_break_mins
+                    .unwrap_or(DEFAULT_BREAK_MINS);
+                self.pomodoro.begin(PomodoroPhase::Break, minutes);
+                self.pomodoro.alert =
+                    Some(format!("Pomodoro done! Take a {minutes} minute break."));
+            }
+            PomodoroPhase::Break => {
+                self.pomodoro.stop();
+                self.pomodoro.alert = Some("Break is over. Back to work!".to_string());
+            }
+        }
+        self.settings.pomodoro_bell
+    }
+}
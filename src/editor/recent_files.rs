@@ -0,0 +1,159 @@
+use crate::editor::{Editor, EditorMode};
+use crate::persistence::{self, RecentFile};
+use crate::screen::Screen;
+use chrono::{DateTime, Local};
+use pancurses::{A_DIM, A_REVERSE, Input};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Default)]
+pub struct RecentFiles {
+    // Where the list is kept; `None` until enabled at startup
+    pub path: Option<PathBuf>,
+    // Where the files closed in this and earlier sessions are kept
+    pub closed_path: Option<PathBuf>,
+    // Files offered by the picker
+    pub entries: Vec<RecentFile>,
+    pub selected_index: usize,
+    pub scroll_offset: usize,
+}
+
+impl Editor {
+    pub fn enable_recent_files(&mut self) {
+        self.enable_recent_files_with_base_dir(None);
+    }
+
+    /// Starts remembering the files opened in this session, beginning with
+    /// the current one.
+    pub fn enable_recent_files_with_base_dir(&mut self, base_dir: Option<PathBuf>) {
+        self.recent_files.path = persistence::recent_files_path(base_dir.clone());
+        self.recent_files.closed_path = persistence::closed_files_path(base_dir);
+        self.note_recent_file();
+    }
+
+    /// Remembers the current file as closed, before switching away from it or
+    /// quitting.
+    pub(super) fn note_closed_file(&mut self) {
+        if let Some(path) = &self.recent_files.closed_path
+            && let Some(filename) = &self.document.filename
+        {
+            persistence::record_closed_file(path, filename);
+        }
+    }
+
+    /// Opens the file closed most recently, at the cursor position it was
+    /// left at.
+    pub fn reopen_last_closed(&mut self) {
+        let Some(path) = &self.recent_files.closed_path else {
+            self.set_message("Closed files are not available.");
+            return;
+        };
+        let current = self.document.filename.as_deref();
+        let Some(filename) = persistence::take_last_closed_file(path, current) else {
+            self.set_message("No closed files to reopen.");
+            return;
+        };
+        if let Err(e) = self.open_file(&filename) {
+            self.set_message(&format!("Could not open {filename}: {e}"));
+        }
+    }
+
+    /// Puts the current file at the top of the recent files list.
+    pub(super) fn note_recent_file(&mut self) {
+        if let Some(path) = &self.recent_files.path
+            && let Some(filename) = &self.document.filename
+        {
+            persistence::record_recent_file(path, filename);
+        }
+    }
+
+    pub fn open_recent_files(&mut self) {
+        let Some(path) = &self.recent_files.path else {
+            self.set_message("Recent files are not available.");
+            return;
+        };
+        let current = self.document.filename.as_deref();
+        let entries: Vec<RecentFile> = persistence::load_recent_files(path)
+            .into_iter()
+            .filter(|file| Some(file.file_path.as_str()) != current)
+            .filter(|file| Path::new(&file.file_path).exists())
+            .collect();
+        if entries.is_empty() {
+            self.set_message("No recent files.");
+            return;
+        }
+        self.recent_files.entries = entries;
+        self.recent_files.selected_index = 0;
+        self.recent_files.scroll_offset = 0;
+        self.mode = EditorMode::RecentFiles;
+    }
+
+    pub fn handle_recent_files_input(&mut self, key: Input) {
+        let count = self.recent_files.entries.len();
+        let selected = &mut self.recent_files.selected_index;
+        match key {
+            Input::KeyUp | Input::Character('\x10') if count > 0 => {
+                *selected = (*selected + count - 1) % count;
+            }
+            Input::KeyDown | Input::Character('\x0e') if count > 0 => {
+                *selected = (*selected + 1) % count;
+            }
+            Input::Character('\n') | Input::Character('\r') => {
+                let Some(file) = self.recent_files.entries.get(*selected).cloned() else {
+                    return;
+                };
+                self.close_recent_files();
+                if let Err(e) = self.open_file(&file.file_path) {
+                    self.set_message(&format!("Could not open {}: {e}", file.file_path));
+                }
+            }
+            // Esc or Ctrl-G
+            Input::Character('\x1b') | Input::Character('\x07') => self.close_recent_files(),
+            _ => {}
+        }
+    }
+
+    fn close_recent_files(&mut self) {
+        self.recent_files.entries.clear();
+        self.mode = EditorMode::Normal;
+    }
+
+    pub fn draw_recent_files(&mut self, window: &impl Screen) {
+        window.erase();
+        let screen_rows = window.get_max_y() as usize;
+        let list_height = screen_rows.saturating_sub(1);
+        let recent = &mut self.recent_files;
+
+        if recent.selected_index < recent.scroll_offset {
+            recent.scroll_offset = recent.selected_index;
+        }
+        if recent.selected_index >= recent.scroll_offset + list_height {
+            recent.scroll_offset = recent.selected_index + 1 - list_height;
+        }
+
+        let offset = recent.scroll_offset;
+        for (row, file) in recent
+            .entries
+            .iter()
+            .skip(offset)
+            .take(list_height)
+            .enumerate()
+        {
+            let opened_at: DateTime<Local> = file.opened_at.into();
+            let is_selected = offset + row == recent.selected_index;
+            if is_selected {
+                window.attron(A_REVERSE);
+            }
+            window.mvaddstr(row as i32, 0, &file.file_path);
+            if is_selected {
+                window.attroff(A_REVERSE);
+            }
+            window.attron(A_DIM);
+            window.addstr(format!("  {}", opened_at.format("%Y-%m-%d %H:%M")));
+            window.attroff(A_DIM);
+        }
+
+        let prompt = "RECENT FILES: Enter opens, Esc cancels";
+        window.mvaddstr(screen_rows as i32 - 1, 0, prompt);
+        window.refresh();
+    }
+}
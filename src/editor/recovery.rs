@@ -0,0 +1,164 @@
+use crate::document::ActionDiff;
+use crate::editor::{Editor, EditorMode};
+use crate::journal::{Journal, JournalEntry};
+use log::debug;
+use pancurses::Input;
+use std::path::PathBuf;
+
+pub const RECOVERY_PROMPT: &str =
+    "Unsaved changes from a previous session were found. Recover them? (y/n)";
+
+impl Editor {
+    pub fn enable_journal(&mut self) {
+        self.enable_journal_with_base_dir(None);
+    }
+
+    /// Starts journaling edits to the current file. If a journal from a crashed
+    /// session matches the file on disk, the user is asked whether to replay it.
+    pub fn enable_journal_with_base_dir(&mut self, base_dir: Option<PathBuf>) {
+        let Some(filename) = self.document.filename.clone() else {
+            self.journal = Journal::unnamed_with_base_dir(base_dir);
+            return;
+        };
+        let base_content = self.document.original_content().unwrap_or_default();
+        self.journal = Journal::new_with_base_dir(base_dir, &filename, base_content);
+        self.offer_recovery();
+    }
+
+    /// Asks whether to replay the current journal if a crashed session left
+    /// one behind.
+    pub(super) fn offer_recovery(&mut self) {
+        if let Some(entries) = self.journal.recoverable_entries() {
+            debug!("Found {} journal entries.", entries.len());
+            self.pending_recovery = entries;
+            self.mode = EditorMode::Recovery;
+            self.set_message(RECOVERY_PROMPT);
+        }
+    }
+
+    pub fn handle_recovery_input(&mut self, key: Input) {
+        match key {
+            Input::Character('y') | Input::Character('Y') => self.recover_from_journal(),
+            Input::Character('n') | Input::Character('N') => {
+                self.pending_recovery.clear();
+                self.journal.discard();
+                self.mode = EditorMode::Normal;
+                self.set_message("Discarded unsaved changes.");
+            }
+            _ => self.set_message(RECOVERY_PROMPT),
+        }
+    }
+
+    fn recover_from_journal(&mut self) {
+        let entries = std::mem::take(&mut self.pending_recovery);
+        let total = entries.len();
+        let mut applied = 0;
+        for JournalEntry { diff, is_undo } in &entries {
+            match self.document.apply_action_diff(diff, *is_undo) {
+                Ok((x, y)) => {
+                    self.cursor.x = x;
+                    self.cursor.y = y;
+                    applied += 1;
+                }
+                Err(e) => {
+                    debug!("Stopping journal replay: {e}");
+                    break;
+                }
+            }
+        }
+        self.tag_index = None;
+        self.pages.clear();
+        self.completion.index.clear();
+        self.snippet_stops = None;
+        self.cursor.y = self
+            .cursor
+            .y
+            .min(self.document.lines().len().saturating_sub(1));
+        self.scroll.clamp_cursor_x(&mut self.cursor, &self.document);
+        if let Some(line) = self.document.lines().get(self.cursor.y) {
+            self.cursor.desired_x = self
+                .scroll
+                .get_display_width_from_bytes(line, self.cursor.x);
+        }
+
+        // Keep appending so a second crash before saving loses nothing
+        self.journal.resume();
+        self.mode = EditorMode::Normal;
+        if applied == total {
+            self.set_message(&format!("Recovered {total} changes."));
+        } else {
+            self.set_message(&format!("Recovered {applied} of {total} changes."));
+        }
+    }
+
+    pub(super) fn journal_undo_redo(&mut self, is_undo: bool) {
+        let group = if is_undo {
+            self.undo_redo.redo_stack.last()
+        } else {
+            self.undo_redo.undo_stack.last()
+        };
+        let Some(group) = group else {
+            return;
+        };
+        let diffs: Vec<ActionDiff> = if is_undo {
+            group.iter().rev().cloned().collect()
+        } else {
+            group.clone()
+        };
+        for diff in &diffs {
+            self.journal.record(diff, is_undo);
+        }
+    }
+
+    /// Starts a fresh journal once the document has been written to disk. Edits
+    /// made while a background save was running are carried over as a single
+    /// entry on top of the saved content.
+    pub(super) fn reset_journal(&mut self) {
+        let saved_content = self
+            .document
+            .original_content()
+            .unwrap_or_default()
+            .to_string();
+        self.journal.reset(&saved_content);
+        if !self.document.is_dirty() {
+            return;
+        }
+        // Only the lines between those the two have in common at either end
+        let old: Vec<&str> = saved_content.lines().collect();
+        let lines = self.document.lines();
+        let common = old.len().min(lines.len());
+        if common == 0 {
+            return;
+        }
+        let prefix = old
+            .iter()
+            .zip(lines)
+            .take(common - 1)
+            .take_while(|(old, new)| **old == new.as_str())
+            .count();
+        let suffix = old
+            .iter()
+            .rev()
+            .zip(lines.iter().rev())
+            .take(common - prefix - 1)
+            .take_while(|(old, new)| **old == new.as_str())
+            .count();
+        let old_end = old.len() - suffix;
+        let diff = ActionDiff {
+            cursor_start_x: 0,
+            cursor_start_y: 0,
+            cursor_end_x: self.cursor.x,
+            cursor_end_y: self.cursor.y,
+            start_x: 0,
+            start_y: prefix,
+            end_x: old[old_end - 1].len(),
+            end_y: old_end - 1,
+            old: old[prefix..old_end]
+                .iter()
+                .map(|line| line.to_string())
+                .collect(),
+            new: lines.range(prefix..lines.len() - suffix).cloned().collect(),
+        };
+        self.journal.record(&diff, false);
+    }
+}
@@ -0,0 +1,236 @@
+use crate::document::ActionDiff;
+use crate::editor::cursor::Cursor;
+use crate::editor::{Editor, LastActionType};
+use crate::error::Result;
+
+/// A block of lines `top..=bottom` and display columns `left..right`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rectangle {
+    pub top: usize,
+    pub bottom: usize,
+    pub left: usize,
+    pub right: usize,
+}
+
+impl Rectangle {
+    pub fn contains(&self, line_index: usize, display_x: usize) -> bool {
+        line_index >= self.top
+            && line_index <= self.bottom
+            && display_x >= self.left
+            && display_x < self.right
+    }
+}
+
+impl Editor {
+    /// Starts a rectangle selection at the cursor, or switches an active
+    /// selection between the regular and the rectangle shape.
+    pub fn toggle_rectangle_mode(&mut self) {
+        if !self.selection.is_selection_active() {
+            self.selection.set_marker(&self.cursor);
+            self.selection.rectangle = true;
+            self.set_message("Rectangle mark set.");
+        } else {
+            self.selection.rectangle = !self.selection.rectangle;
+            if self.selection.rectangle {
+                self.set_message("Rectangle selection.");
+            } else {
+                self.set_message("Regular selection.");
+            }
+        }
+    }
+
+    pub fn rectangle_bounds(&self) -> Option<Rectangle> {
+        if !self.selection.rectangle {
+            return None;
+        }
+        let (marker_x, marker_y) = self.selection.marker_pos?;
+        let marker_col = self.display_col(marker_x, marker_y);
+        let cursor_col = self.display_col(self.cursor.x, self.cursor.y);
+        Some(Rectangle {
+            top: marker_y.min(self.cursor.y),
+            bottom: marker_y.max(self.cursor.y),
+            left: marker_col.min(cursor_col),
+            right: marker_col.max(cursor_col),
+        })
+    }
+
+    fn display_col(&self, x: usize, y: usize) -> usize {
+        self.document
+            .lines()
+            .get(y)
+            .map_or(0, |line| self.scroll.get_display_width_from_bytes(line, x))
+    }
+
+    /// Byte range of the display columns `left..right` within line `y`.
+    fn column_byte_range(&self, y: usize, left: usize, right: usize) -> (usize, usize) {
+        let line = &self.document.lines()[y];
+        let (start, _) = self.scroll.get_byte_pos_from_display_width(line, left);
+        let (end, _) = self.scroll.get_byte_pos_from_display_width(line, right);
+        (start, end)
+    }
+
+    fn rectangle_text(&self, rect: &Rectangle) -> Vec<String> {
+        (rect.top..=rect.bottom)
+            .map(|y| {
+                let (start, end) = self.column_byte_range(y, rect.left, rect.right);
+                self.document.lines()[y][start..end].to_string()
+            })
+            .collect()
+    }
+
+    fn store_rectangle(&mut self, lines: Vec<String>) {
+        self.clipboard.push_kill(lines.join("\n"));
+        self.set_clipboard(&self.clipboard.kill_buffer.clone());
+        self.clipboard.rectangle = Some(lines);
+    }
+
+    pub fn copy_rectangle(&mut self) -> Result<()> {
+        let Some(rect) = self.rectangle_bounds() else {
+            return Ok(());
+        };
+        let lines = self.rectangle_text(&rect);
+        self.store_rectangle(lines);
+        self.selection.clear_marker();
+        self.set_message("Rectangle copied.");
+        Ok(())
+    }
+
+    pub fn cut_rectangle(&mut self) -> Result<()> {
+        let Some(rect) = self.rectangle_bounds() else {
+            return Ok(());
+        };
+        let lines = self.rectangle_text(&rect);
+        let original_cursor = self.cursor_pos();
+        let (final_x, _) = self.column_byte_range(rect.top, rect.left, rect.left);
+
+        let mut action_type = LastActionType::Deletion;
+        for y in rect.top..=rect.bottom {
+            let (start, end) = self.column_byte_range(y, rect.left, rect.right);
+            if start == end {
+                continue;
+            }
+            let old = self.document.lines()[y][start..end].to_string();
+            self.commit(
+                action_type,
+                &ActionDiff {
+                    cursor_start_x: original_cursor.0,
+                    cursor_start_y: original_cursor.1,
+                    cursor_end_x: final_x,
+                    cursor_end_y: rect.top,
+                    start_x: start,
+                    start_y: y,
+                    end_x: end,
+                    end_y: y,
+                    new: vec![],
+                    old: vec![old],
+                },
+            );
+            action_type = LastActionType::Ammend;
+        }
+        self.set_cursor_pos(final_x, rect.top);
+
+        self.store_rectangle(lines);
+        self.selection.clear_marker();
+        self.set_message("Rectangle cut.");
+        Ok(())
+    }
+
+    /// Inserts the last cut or copied rectangle with its top-left corner at
+    /// the cursor, padding short lines with spaces.
+    pub fn yank_rectangle(&mut self) -> Result<()> {
+        let Some(lines) = self.clipboard.rectangle.clone() else {
+            self.set_message("No rectangle to yank.");
+            return Ok(());
+        };
+        let col = self.display_col(self.cursor.x, self.cursor.y);
+        let top = self.cursor.y;
+        self.insert_at_column(top, top + lines.len() - 1, col, |i| lines[i].clone());
+        self.set_message("Rectangle yanked.");
+        Ok(())
+    }
+
+    /// Typing while a rectangle is selected inserts the text at the
+    /// rectangle's left edge on every line it spans.
+    pub fn insert_rectangle_text(&mut self, text: &str) -> Result<()> {
+        let Some(rect) = self.rectangle_bounds() else {
+            return Ok(());
+        };
+        let (marker_x, marker_y) = self.selection.marker_pos.unwrap_or_default();
+        let marker_col = self.display_col(marker_x, marker_y);
+        let cursor_col = self.display_col(self.cursor.x, self.cursor.y);
+        let (cursor_x, cursor_y) = self.cursor_pos();
+
+        self.insert_at_column(rect.top, rect.bottom, rect.left, |_| text.to_string());
+
+        // Both ends sit at or right of the insertion column, so they move with the text
+        let shift = |col: usize, x: usize| if col >= rect.left { x + text.len() } else { x };
+        self.selection
+            .set_marker(&Cursor::new(shift(marker_col, marker_x), marker_y));
+        self.set_cursor_pos(shift(cursor_col, cursor_x), cursor_y);
+        self.cursor.desired_x = self.display_col(self.cursor.x, self.cursor.y);
+        Ok(())
+    }
+
+    fn insert_at_column(
+        &mut self,
+        top: usize,
+        bottom: usize,
+        col: usize,
+        text_for_line: impl Fn(usize) -> String,
+    ) {
+        let original_cursor = self.cursor_pos();
+        let mut action_type = LastActionType::Insertion;
+
+        // Lines past the end of the document are created empty first
+        while self.document.lines().len() <= bottom {
+            let last_y = self.document.lines().len() - 1;
+            let last_len = self.document.lines()[last_y].len();
+            self.commit(
+                action_type,
+                &ActionDiff {
+                    cursor_start_x: original_cursor.0,
+                    cursor_start_y: original_cursor.1,
+                    cursor_end_x: original_cursor.0,
+                    cursor_end_y: original_cursor.1,
+                    start_x: last_len,
+                    start_y: last_y,
+                    end_x: 0,
+                    end_y: last_y + 1,
+                    new: vec![String::new(), String::new()],
+                    old: vec![],
+                },
+            );
+            action_type = LastActionType::Ammend;
+        }
+
+        let mut end = original_cursor;
+        for y in top..=bottom {
+            let line = &self.document.lines()[y];
+            let line_width = self.scroll.get_display_width_from_bytes(line, line.len());
+            let (x, _) = self.scroll.get_byte_pos_from_display_width(line, col);
+            let padding = " ".repeat(col.saturating_sub(line_width));
+            let text = format!("{padding}{}", text_for_line(y - top));
+            if text.is_empty() {
+                continue;
+            }
+            end = (x + text.len(), y);
+            self.commit(
+                action_type,
+                &ActionDiff {
+                    cursor_start_x: original_cursor.0,
+                    cursor_start_y: original_cursor.1,
+                    cursor_end_x: end.0,
+                    cursor_end_y: end.1,
+                    start_x: x,
+                    start_y: y,
+                    end_x: x + text.len(),
+                    end_y: y,
+                    new: vec![text],
+                    old: vec![],
+                },
+            );
+            action_type = LastActionType::Ammend;
+        }
+        self.set_cursor_pos(end.0, end.1);
+    }
+}
@@ -0,0 +1,106 @@
+use crate::editor::actions::Action;
+use crate::editor::{Editor, EditorMode};
+use crate::error::Result;
+
+// What each `Ctrl-U` multiplies the count by, so `Ctrl-U Ctrl-U` repeats 16 times
+const UNIVERSAL_ARGUMENT_FACTOR: usize = 4;
+// Keeps a mistyped count from running an action for minutes
+const MAX_REPEAT_COUNT: usize = 9999;
+
+/// The count typed with `Alt-<digit>` or `Ctrl-U` for the next action.
+#[derive(Debug, Default)]
+pub struct RepeatCount {
+    count: Option<usize>,
+    // Whether the count was typed in digits, which further digits extend
+    // rather than replace
+    digits: bool,
+}
+
+impl RepeatCount {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.count.is_some()
+    }
+
+    pub fn push_digit(&mut self, digit: u8) {
+        let digit = usize::from(digit);
+        let count = match self.count {
+            Some(count) if self.digits => count * 10 + digit,
+            _ => digit,
+        };
+        self.count = Some(count.min(MAX_REPEAT_COUNT));
+        self.digits = true;
+    }
+
+    pub fn multiply(&mut self) {
+        let count = self.count.unwrap_or(1) * UNIVERSAL_ARGUMENT_FACTOR;
+        self.count = Some(count.min(MAX_REPEAT_COUNT));
+        self.digits = false;
+    }
+
+    pub fn take(&mut self) -> Option<usize> {
+        self.digits = false;
+        self.count.take()
+    }
+}
+
+impl Editor {
+    pub fn digit_argument(&mut self, digit: u8) {
+        self.repeat.push_digit(digit);
+        self.show_repeat_count();
+    }
+
+    pub fn universal_argument(&mut self) {
+        self.repeat.multiply();
+        self.show_repeat_count();
+    }
+
+    fn show_repeat_count(&mut self) {
+        if let Some(count) = self.repeat.count {
+            self.set_message(&format!("Repeat {count}×"));
+        }
+    }
+
+    /// Runs an action bound to a key, as many times as the count typed
+    /// before it says. The repetitions are undone as a single step, and stop
+    /// early when the action opens a prompt or fails.
+    pub(super) fn execute_repeated(&mut self, action: Action) -> Result<()> {
+        if matches!(action, Action::DigitArgument(_) | Action::UniversalArgument) {
+            return self.execute_action(action);
+        }
+        let Some(count) = self.repeat.take() else {
+            return self.execute_action(action);
+        };
+        match action {
+            // Ctrl-G drops the count
+            Action::ClearMarker => {
+                self.set_message("Repeat count cancelled.");
+                return Ok(());
+            }
+            // The count is the page to go to
+            Action::GotoPage(0) => return self.execute_action(Action::GotoPage(count)),
+            Action::PlayMacro(times) => {
+                return self.execute_action(Action::PlayMacro(times.max(1) * count));
+            }
+            _ => {}
+        }
+
+        self.undo_redo.begin_group();
+        let mut result = Ok(());
+        for _ in 0..count {
+            result = self.execute_action(action.clone());
+            if result.is_err()
+                || self.mode != EditorMode::Normal
+                || self.search.mode
+                || self.should_quit
+            {
+                break;
+            }
+        }
+        self.undo_redo.end_group();
+        result
+    }
+}
@@ -0,0 +1,178 @@
+use crate::diff;
+use crate::document::ActionDiff;
+use crate::editor::{Editor, LastActionType};
+use crate::error::Result;
+
+impl Editor {
+    /// Saves the document with the configured save hooks. The buffer is
+    /// cleaned up first, as one undo step, so that it matches the file and
+    /// the cursor position remembered for the file stays on the same text.
+    pub(super) fn save_with_hooks(&mut self) -> Result<()> {
+        if self.read_only {
+            return Ok(());
+        }
+        self.apply_save_hooks();
+        self.document.save(None)?;
+        self.apply_file_settings();
+        self.clear_external_change();
+        self.save_bookmarks();
+        Ok(())
+    }
+
+    pub(super) fn apply_save_hooks(&mut self) {
+        let options = self.settings.save_options();
+        self.document.save_options = options;
+        self.document.backup_retention = self.settings.backup_retention();
+        if options.is_empty() || self.document.filename.is_none() {
+            return;
+        }
+        let cleaned = options.apply(self.document.lines());
+        let cursor = options.map_position(self.document.lines(), self.cursor.x, self.cursor.y);
+        self.replace_lines(cleaned, cursor);
+    }
+
+    /// Replaces the buffer with `new_lines` as a single undo step, touching only
+    /// the lines that differ, and moves the cursor to `cursor`.
+    pub(super) fn replace_lines(&mut self, new_lines: Vec<String>, cursor: (usize, usize)) {
+        let old_lines = self.document.lines();
+        let prefix = old_lines
+            .iter()
+            .zip(&new_lines)
+            .take_while(|(old, new)| old == new)
+            .count();
+        if prefix == old_lines.len() && prefix == new_lines.len() {
+            return;
+        }
+        let suffix = old_lines
+            .range(prefix..)
+            .rev()
+            .zip(new_lines[prefix..].iter().rev())
+            .take_while(|(old, new)| old == new)
+            .count();
+        let old_end = old_lines.len() - suffix; // exclusive
+        let new_end = new_lines.len() - suffix;
+
+        self.undo_redo.begin_group();
+        self.replace_line_range(prefix, old_end, new_lines[prefix..new_end].to_vec(), cursor);
+        self.undo_redo.end_group();
+        self.cursor.desired_x = self
+            .scroll
+            .get_display_width_from_bytes(&self.document.lines()[self.cursor.y], self.cursor.x);
+    }
+
+    /// Turns the buffer into `new_lines` as a single undo step, changing only
+    /// the runs of lines that differ between them. The cursor and the mark
+    /// stay on the lines they were on.
+    pub(super) fn merge_lines(&mut self, new_lines: Vec<String>) {
+        let hunks = diff::hunks(&diff::diff_lines(self.document.lines(), &new_lines));
+        if hunks.is_empty() {
+            return;
+        }
+        let (cursor_x, cursor_y) = self.cursor_pos();
+        let cursor_y = diff::map_line(&hunks, cursor_y);
+        let marker = self
+            .selection
+            .marker_pos
+            .map(|(x, y)| (x, diff::map_line(&hunks, y)));
+
+        self.undo_redo.begin_group();
+        // From the bottom up, so that the lines of the hunks above stay put
+        for hunk in hunks.iter().rev() {
+            let replacement = new_lines[hunk.new_start..hunk.new_start + hunk.new_len].to_vec();
+            self.replace_line_range(
+                hunk.old_start,
+                hunk.old_start + hunk.old_len,
+                replacement,
+                // A line that is still there once the hunk is replaced
+                (
+                    0,
+                    hunk.old_start - usize::from(hunk.new_len == 0 && hunk.old_start > 0),
+                ),
+            );
+        }
+        self.undo_redo.end_group();
+
+        self.selection.marker_pos = marker;
+        self.selection.clamp_marker(self.document.lines());
+        self.set_cursor_pos(cursor_x, cursor_y.min(self.document.lines().len() - 1));
+        self.cursor.desired_x = self
+            .scroll
+            .get_display_width_from_bytes(&self.document.lines()[self.cursor.y], self.cursor.x);
+    }
+
+    /// Replaces lines `start..end` with `replacement` by a removal and an
+    /// insertion, and moves the cursor to `cursor`.
+    pub(super) fn replace_line_range(
+        &mut self,
+        mut start: usize,
+        mut end: usize,
+        mut replacement: Vec<String>,
+        cursor: (usize, usize),
+    ) {
+        let old_lines = self.document.lines();
+        if start == end {
+            // Only lines are added: replace a neighbouring line along with them
+            if start > 0 {
+                start -= 1;
+                replacement.insert(0, old_lines[start].clone());
+            } else {
+                replacement.push(old_lines[0].clone());
+                end += 1;
+            }
+        }
+        let (cursor_x, cursor_y) = self.cursor_pos();
+
+        let removed: Vec<String> = old_lines.range(start..end).cloned().collect();
+        // Without replacement lines, a line break next to them goes too
+        let (start_x, start_y, end_x, end_y, old) = if !replacement.is_empty() {
+            let end_x = old_lines[end - 1].len();
+            (0, start, end_x, end - 1, removed)
+        } else if start > 0 {
+            let mut old = vec![String::new()];
+            old.extend(removed);
+            let end_x = old_lines[end - 1].len();
+            (old_lines[start - 1].len(), start - 1, end_x, end - 1, old)
+        } else {
+            let mut old = removed;
+            old.push(String::new());
+            (0, 0, 0, end, old)
+        };
+
+        self.commit(
+            LastActionType::Other,
+            &ActionDiff {
+                cursor_start_x: cursor_x,
+                cursor_start_y: cursor_y,
+                cursor_end_x: start_x,
+                cursor_end_y: start_y,
+                start_x,
+                start_y,
+                end_x,
+                end_y,
+                new: vec![],
+                old,
+            },
+        );
+
+        if !replacement.is_empty() {
+            let end_y = start + replacement.len() - 1;
+            self.commit(
+                LastActionType::Ammend,
+                &ActionDiff {
+                    cursor_start_x: 0,
+                    cursor_start_y: start,
+                    cursor_end_x: cursor.0,
+                    cursor_end_y: cursor.1,
+                    start_x: 0,
+                    start_y: start,
+                    end_x: replacement.last().map_or(0, String::len),
+                    end_y,
+                    new: replacement,
+                    old: vec![],
+                },
+            );
+        } else {
+            self.set_cursor_pos(cursor.0, cursor.1);
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use crate::document::Document;
+use crate::editor::Editor;
+use crate::editor::cursor::Cursor;
+use crate::editor::undo::UndoRedo;
+use crate::journal::Journal;
+use crate::scratch;
+use std::mem;
+
+/// Everything that belongs to the buffer that is not currently shown, so that
+/// switching back restores it exactly as it was left.
+pub struct BufferState {
+    pub document: Document,
+    pub cursor: Cursor,
+    pub scroll_offset: (usize, usize),
+    pub undo_redo: UndoRedo,
+    pub journal: Journal,
+}
+
+impl Editor {
+    pub fn is_scratch_buffer(&self) -> bool {
+        self.document.filename.is_none()
+    }
+
+    /// Switches between the current file and the scratch buffer.
+    pub fn toggle_scratch_buffer(&mut self) {
+        if self.alternate_buffer.is_none() {
+            if self.is_scratch_buffer() {
+                self.set_message("Already in the scratch buffer.");
+                return;
+            }
+            self.alternate_buffer = Some(self.new_scratch_buffer());
+        }
+
+        self.finish_pending_autosave();
+        if self.is_scratch_buffer() {
+            self.save_scratch();
+        }
+        let Some(next) = self.alternate_buffer.take() else {
+            return;
+        };
+        let previous = self.swap_buffer(next);
+        self.alternate_buffer = Some(previous);
+
+        match self.document.filename.clone() {
+            Some(filename) => self.set_message(&format!("Back to {filename}")),
+            None => self.set_message("Scratch buffer"),
+        }
+    }
+
+    /// Makes sure the file, not the scratch buffer, is current, e.g. before
+    /// quitting saves it.
+    pub fn leave_scratch_buffer(&mut self) {
+        if self.is_scratch_buffer()
+            && self
+                .alternate_buffer
+                .as_ref()
+                .is_some_and(|buffer| buffer.document.filename.is_some())
+        {
+            self.toggle_scratch_buffer();
+        }
+    }
+
+    fn new_scratch_buffer(&self) -> BufferState {
+        let mut document = Document::new_empty();
+        if let Some(path) = &self.autosave.scratch_path
+            && let Some(lines) = scratch::load(path)
+        {
+            document.set_lines(lines);
+        }
+        let y = document.lines().len() - 1;
+        let mut cursor = Cursor::new(document.lines()[y].len(), y);
+        cursor.desired_x = self
+            .scroll
+            .get_display_width_from_bytes(&document.lines()[y], cursor.x);
+        BufferState {
+            document,
+            cursor,
+            scroll_offset: (0, 0),
+            undo_redo: UndoRedo::new(),
+            journal: Journal::disabled(),
+        }
+    }
+
+    /// A buffer for a file that was just read, with the cursor where it was
+    /// left last time.
+    pub(super) fn file_buffer(
+        &self,
+        document: Document,
+        restored_pos: Option<(usize, usize, usize, usize)>,
+    ) -> BufferState {
+        let (x, y, scroll_row, scroll_col) = restored_pos.unwrap_or_default();
+        let y = y.min(document.lines().len().saturating_sub(1));
+        let line = document.lines().get(y).map_or("", String::as_str);
+        let mut cursor = Cursor::new(x.min(line.len()), y);
+        cursor.desired_x = self.scroll.get_display_width_from_bytes(line, cursor.x);
+        let filename = document.filename.clone().unwrap_or_default();
+        let journal = self
+            .journal
+            .for_file(&filename, document.original_content().unwrap_or_default());
+        BufferState {
+            document,
+            cursor,
+            scroll_offset: (scroll_row, scroll_col),
+            undo_redo: UndoRedo::new(),
+            journal,
+        }
+    }
+
+    pub(super) fn swap_buffer(&mut self, mut next: BufferState) -> BufferState {
+        next.undo_redo.inherit_timing(&self.undo_redo);
+        self.selection.clear_marker();
+        self.last_change = None;
+        self.folds.clear();
+        self.jumps.clear();
+        self.subword_mode = None;
+        self.input_transform = None;
+        self.abbrev_expansion = None;
+        self.tag_index = None;
+        self.pages.clear();
+        self.completion.index.clear();
+        self.snippet_stops = None;
+        self.tag_filter = None;
+        self.clear_external_change();
+        self.autosave.scratch_dirty = false;
+        let previous = BufferState {
+            document: mem::replace(&mut self.document, next.document),
+            cursor: self.cursor,
+            scroll_offset: (self.scroll.row_offset, self.scroll.col_offset),
+            undo_redo: mem::replace(&mut self.undo_redo, next.undo_redo),
+            journal: mem::replace(&mut self.journal, next.journal),
+        };
+        self.cursor = next.cursor;
+        (self.scroll.row_offset, self.scroll.col_offset) = next.scroll_offset;
+        self.load_bookmarks();
+        self.apply_file_settings();
+        previous
+    }
+}
@@ -1,9 +1,16 @@
 use crate::document::Document;
+use crate::editor::cursor::Cursor;
 use crate::editor::ui::STATUS_BAR_HEIGHT;
 use unicode_width::UnicodeWidthChar;
 
 const TAB_STOP: usize = 4;
 
+/// Rows kept between the cursor line and the top or bottom edge of the
+/// text, so that some context stays visible around it.
+pub fn vertical_margin(text_rows: usize) -> usize {
+    text_rows / 4
+}
+
 pub struct Scroll {
     pub row_offset: usize,
     pub col_offset: usize,
@@ -81,23 +88,24 @@ impl Scroll {
         (byte_pos, current_display_x)
     }
 
-    // Helper for clamping cursor_x, now part of Scroll
-    pub fn clamp_cursor_x(&self, cursor_x: &mut usize, cursor_y: &usize, document: &Document) {
-        if *cursor_y >= document.lines.len() {
-            *cursor_x = 0;
+    // Helper for clamping the cursor's x, now part of Scroll
+    pub fn clamp_cursor_x(&self, cursor: &mut Cursor, document: &Document) {
+        if cursor.y >= document.lines().len() {
+            cursor.x = 0;
             return;
         }
-        let line_len = document.lines[*cursor_y].len();
-        if *cursor_x > line_len {
-            *cursor_x = line_len;
+        let line = &document.lines()[cursor.y];
+        cursor.x = cursor.x.min(line.len());
+        // Don't land inside a multi-byte character
+        while !line.is_char_boundary(cursor.x) {
+            cursor.x -= 1;
         }
     }
 
     // Methods that modify Editor's cursor and document
     pub fn scroll_page_down(
         &mut self,
-        cursor_y: &mut usize,
-        cursor_x: &mut usize,
+        cursor: &mut Cursor,
         document: &Document,
         last_action_was_kill: &mut bool,
     ) {
@@ -105,22 +113,24 @@ impl Scroll {
         let page_height = self.screen_rows.saturating_sub(STATUS_BAR_HEIGHT).max(1);
         let old_row_offset = self.row_offset;
         self.row_offset = self.row_offset.saturating_add(page_height);
-        self.row_offset = self.row_offset.min(document.lines.len().saturating_sub(1));
+        self.row_offset = self
+            .row_offset
+            .min(document.lines().len().saturating_sub(1));
 
-        if old_row_offset == self.row_offset && *cursor_y < document.lines.len().saturating_sub(1) {
-            *cursor_y = document.lines.len().saturating_sub(1);
+        if old_row_offset == self.row_offset && cursor.y < document.lines().len().saturating_sub(1)
+        {
+            cursor.y = document.lines().len().saturating_sub(1);
         } else {
-            let scroll_amount = self.row_offset - old_row_offset;
-            *cursor_y = cursor_y.saturating_add(scroll_amount);
-            *cursor_y = (*cursor_y).min(document.lines.len().saturating_sub(1));
+            let scroll_amount = self.row_offset.saturating_sub(old_row_offset);
+            cursor.y = cursor.y.saturating_add(scroll_amount);
+            cursor.y = (cursor.y).min(document.lines().len().saturating_sub(1));
         }
-        self.clamp_cursor_x(cursor_x, cursor_y, document);
+        self.clamp_cursor_x(cursor, document);
     }
 
     pub fn scroll_page_up(
         &mut self,
-        cursor_y: &mut usize,
-        cursor_x: &mut usize,
+        cursor: &mut Cursor,
         document: &Document,
         last_action_was_kill: &mut bool,
     ) {
@@ -129,137 +139,120 @@ impl Scroll {
         let old_row_offset = self.row_offset;
         self.row_offset = self.row_offset.saturating_sub(page_height);
 
-        if old_row_offset == self.row_offset && *cursor_y > 0 {
-            *cursor_y = 0;
+        if old_row_offset == self.row_offset && cursor.y > 0 {
+            cursor.y = 0;
         } else {
             let scroll_amount = old_row_offset - self.row_offset;
-            *cursor_y = cursor_y.saturating_sub(scroll_amount);
+            cursor.y = cursor.y.saturating_sub(scroll_amount);
         }
-        self.clamp_cursor_x(cursor_x, cursor_y, document);
+        self.clamp_cursor_x(cursor, document);
     }
 
-    pub fn go_to_start_of_file(
-        &mut self,
-        cursor_y: &mut usize,
-        cursor_x: &mut usize,
-        desired_cursor_x: &mut usize,
-        last_action_was_kill: &mut bool,
-    ) {
+    pub fn go_to_start_of_file(&mut self, cursor: &mut Cursor, last_action_was_kill: &mut bool) {
         *last_action_was_kill = false;
-        *cursor_y = 0;
-        *cursor_x = 0;
-        *desired_cursor_x = 0;
+        cursor.y = 0;
+        cursor.x = 0;
+        cursor.desired_x = 0;
         self.row_offset = 0;
         self.col_offset = 0;
     }
 
     pub fn go_to_end_of_file(
         &mut self,
-        cursor_y: &mut usize,
-        cursor_x: &mut usize,
-        desired_cursor_x: &mut usize,
+        cursor: &mut Cursor,
         document: &Document,
         last_action_was_kill: &mut bool,
     ) {
         *last_action_was_kill = false;
-        *cursor_y = document.lines.len().saturating_sub(1);
-        *cursor_x = document.lines[*cursor_y].len();
-        *desired_cursor_x =
-            self.get_display_width_from_bytes(&document.lines[*cursor_y], *cursor_x);
+        cursor.y = document.lines().len().saturating_sub(1);
+        cursor.x = document.lines()[cursor.y].len();
+        cursor.desired_x = self.get_display_width_from_bytes(&document.lines()[cursor.y], cursor.x);
         let screen_height = self.screen_rows.saturating_sub(1);
-        if *cursor_y >= self.row_offset + screen_height {
-            self.row_offset = cursor_y.saturating_sub(screen_height) + 1;
+        if cursor.y >= self.row_offset + screen_height {
+            self.row_offset = cursor.y.saturating_sub(screen_height) + 1;
         }
-        self.clamp_cursor_x(cursor_x, cursor_y, document);
+        self.clamp_cursor_x(cursor, document);
     }
 
     pub fn move_cursor_up(
         &mut self,
-        cursor_y: &mut usize,
-        cursor_x: &mut usize,
-        desired_cursor_x: &mut usize,
+        cursor: &mut Cursor,
         document: &Document,
         last_action_was_kill: &mut bool,
     ) {
         *last_action_was_kill = false;
-        if *cursor_y > 0 {
-            *cursor_y -= 1;
-            *cursor_x = self
-                .get_byte_pos_from_display_width(&document.lines[*cursor_y], *desired_cursor_x)
+        if cursor.y > 0 {
+            cursor.y -= 1;
+            cursor.x = self
+                .get_byte_pos_from_display_width(&document.lines()[cursor.y], cursor.desired_x)
                 .0;
         } else {
-            *cursor_x = 0;
-            *desired_cursor_x = 0;
+            cursor.x = 0;
+            cursor.desired_x = 0;
         }
     }
 
     pub fn move_cursor_down(
         &mut self,
-        cursor_y: &mut usize,
-        cursor_x: &mut usize,
-        desired_cursor_x: &mut usize,
+        cursor: &mut Cursor,
         document: &Document,
         last_action_was_kill: &mut bool,
     ) {
         *last_action_was_kill = false;
-        if *cursor_y < document.lines.len().saturating_sub(1) {
-            *cursor_y += 1;
-            *cursor_x = self
-                .get_byte_pos_from_display_width(&document.lines[*cursor_y], *desired_cursor_x)
+        if cursor.y < document.lines().len().saturating_sub(1) {
+            cursor.y += 1;
+            cursor.x = self
+                .get_byte_pos_from_display_width(&document.lines()[cursor.y], cursor.desired_x)
                 .0;
         } else {
-            *cursor_x = document.lines[*cursor_y].len();
-            *desired_cursor_x =
-                self.get_display_width_from_bytes(&document.lines[*cursor_y], *cursor_x);
+            cursor.x = document.lines()[cursor.y].len();
+            cursor.desired_x =
+                self.get_display_width_from_bytes(&document.lines()[cursor.y], cursor.x);
         }
     }
 
     pub fn move_cursor_left(
         &mut self,
-        cursor_y: &mut usize,
-        cursor_x: &mut usize,
-        desired_cursor_x: &mut usize,
+        cursor: &mut Cursor,
         document: &Document,
         last_action_was_kill: &mut bool,
     ) {
         *last_action_was_kill = false;
-        let line = &document.lines[*cursor_y];
-        if *cursor_x > 0 {
-            let mut new_pos = *cursor_x - 1;
+        let line = &document.lines()[cursor.y];
+        if cursor.x > 0 {
+            let mut new_pos = cursor.x - 1;
             while !line.is_char_boundary(new_pos) {
                 new_pos -= 1;
             }
-            *cursor_x = new_pos;
-            *desired_cursor_x = self.get_display_width_from_bytes(line, *cursor_x);
-        } else if *cursor_y > 0 {
-            *cursor_y -= 1;
-            *cursor_x = document.lines[*cursor_y].len();
-            *desired_cursor_x =
-                self.get_display_width_from_bytes(&document.lines[*cursor_y], *cursor_x);
+            cursor.x = new_pos;
+            cursor.desired_x = self.get_display_width_from_bytes(line, cursor.x);
+        } else if cursor.y > 0 {
+            cursor.y -= 1;
+            cursor.x = document.lines()[cursor.y].len();
+            cursor.desired_x =
+                self.get_display_width_from_bytes(&document.lines()[cursor.y], cursor.x);
         }
     }
 
     pub fn move_cursor_right(
         &mut self,
-        cursor_y: &mut usize,
-        cursor_x: &mut usize,
-        desired_cursor_x: &mut usize,
+        cursor: &mut Cursor,
         document: &Document,
         last_action_was_kill: &mut bool,
     ) {
         *last_action_was_kill = false;
-        let line = &document.lines[*cursor_y];
-        if *cursor_x < line.len() {
-            let mut new_pos = *cursor_x + 1;
+        let line = &document.lines()[cursor.y];
+        if cursor.x < line.len() {
+            let mut new_pos = cursor.x + 1;
             while !line.is_char_boundary(new_pos) {
                 new_pos += 1;
             }
-            *cursor_x = new_pos;
-            *desired_cursor_x = self.get_display_width_from_bytes(line, *cursor_x);
-        } else if *cursor_y < document.lines.len().saturating_sub(1) {
-            *cursor_y += 1;
-            *cursor_x = 0;
-            *desired_cursor_x = 0;
+            cursor.x = new_pos;
+            cursor.desired_x = self.get_display_width_from_bytes(line, cursor.x);
+        } else if cursor.y < document.lines().len().saturating_sub(1) {
+            cursor.y += 1;
+            cursor.x = 0;
+            cursor.desired_x = 0;
         }
     }
 }
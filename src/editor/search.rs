@@ -1,10 +1,107 @@
 use crate::editor::Editor;
 
+/// Whether letters must match in case.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaseMode {
+    /// Case-insensitive unless the query has an upper-case letter.
+    Smart,
+    Sensitive,
+    Insensitive,
+}
+
+impl CaseMode {
+    pub fn next(self) -> Self {
+        match self {
+            CaseMode::Smart => CaseMode::Sensitive,
+            CaseMode::Sensitive => CaseMode::Insensitive,
+            CaseMode::Insensitive => CaseMode::Smart,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CaseMode::Smart => "smart",
+            CaseMode::Sensitive => "exact",
+            CaseMode::Insensitive => "any",
+        }
+    }
+
+    pub fn ignores_case(self, query: &str) -> bool {
+        match self {
+            CaseMode::Smart => !query.chars().any(char::is_uppercase),
+            CaseMode::Sensitive => false,
+            CaseMode::Insensitive => true,
+        }
+    }
+}
+
+fn is_word_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_'
+}
+
+/// Where `query` occurs in `line`, as the start and length in bytes of each
+/// match. Whole words have no letter, digit or `_` right before or after them.
+pub fn find_matches(
+    line: &str,
+    query: &str,
+    ignore_case: bool,
+    whole_word: bool,
+) -> Vec<(usize, usize)> {
+    let mut found = Vec::new();
+    let mut from = 0;
+    while from <= line.len() {
+        let Some((start, len)) = find_from(line, from, query, ignore_case) else {
+            break;
+        };
+        let end = start + len;
+        let is_word =
+            !line[..start].ends_with(is_word_char) && !line[end..].starts_with(is_word_char);
+        if !whole_word || is_word {
+            found.push((start, len));
+            from = end.max(start + 1);
+        } else {
+            from = start + line[start..].chars().next().map_or(1, char::len_utf8);
+        }
+        while !line.is_char_boundary(from) {
+            from += 1;
+        }
+    }
+    found
+}
+
+/// The first match of `query` in `line` at or after byte `from`.
+fn find_from(line: &str, from: usize, query: &str, ignore_case: bool) -> Option<(usize, usize)> {
+    if !ignore_case {
+        return line[from..]
+            .find(query)
+            .map(|offset| (from + offset, query.len()));
+    }
+    line[from..].char_indices().find_map(|(offset, _)| {
+        let start = from + offset;
+        let mut chars = line[start..].char_indices();
+        let mut len = 0;
+        for q in query.chars() {
+            let (idx, c) = chars.next()?;
+            if c != q && !c.to_lowercase().eq(q.to_lowercase()) {
+                return None;
+            }
+            len = idx + c.len_utf8();
+        }
+        Some((start, len))
+    })
+}
+
 pub struct Search {
     pub mode: bool,
     pub query: String,
-    pub results: Vec<(usize, usize)>,
+    // Line, start byte and length of each match
+    pub results: Vec<(usize, usize, usize)>,
     pub current_match_index: Option<usize>,
+    // Where the search started, for the jump list
+    pub origin: Option<(usize, usize)>,
+    // Kept from one search to the next
+    pub case_mode: CaseMode,
+    pub whole_word: bool,
 }
 
 impl Default for Search {
@@ -20,6 +117,9 @@ impl Search {
             query: String::new(),
             results: Vec::new(),
             current_match_index: None,
+            origin: None,
+            case_mode: CaseMode::Smart,
+            whole_word: false,
         }
     }
 }
@@ -30,13 +130,23 @@ impl Editor {
         self.search.query.clear();
         self.search.results.clear();
         self.search.current_match_index = None;
+        self.search.origin = Some(self.cursor_pos());
 
-        self.status_message = "Search: ".to_string();
+        self.update_search_prompt();
     }
 
     pub fn handle_search_input(&mut self, key: pancurses::Input) {
         if let pancurses::Input::Character(c) = key {
             match c {
+                // Alt-C cycles the case mode and Alt-W whole words
+                'c' if self.is_alt_pressed => {
+                    self.search.case_mode = self.search.case_mode.next();
+                    self.search();
+                }
+                'w' if self.is_alt_pressed => {
+                    self.search.whole_word = !self.search.whole_word;
+                    self.search();
+                }
                 '\x1b' | '\x0a' | '\x0d' | '\x07' => {
                     // Escape or Enter or Ctrl+G to exit search mode
                     self.search.mode = false;
@@ -44,6 +154,9 @@ impl Editor {
                     self.search.results.clear();
                     self.search.current_match_index = None;
                     self.status_message.clear();
+                    if let Some(origin) = self.search.origin.take() {
+                        self.record_jump(origin);
+                    }
                 }
                 '\x13' => {
                     // Ctrl + S for next match
@@ -69,20 +182,29 @@ impl Editor {
             }
         }
         if self.search.mode {
-            self.status_message = format!(
-                "Search: {}{}",
-                self.search.query,
-                if self.search.query.is_empty() {
-                    ""
-                } else if self.search.results.is_empty() {
-                    " (No match)"
-                } else {
-                    ""
-                }
-            );
+            self.update_search_prompt();
         }
     }
 
+    /// Shows the query, and the options with the keys that switch them.
+    fn update_search_prompt(&mut self) {
+        self.status_message = format!(
+            "Search: {}{}  [M-c {}, M-w {}]",
+            self.search.query,
+            if self.search.query.is_empty() || !self.search.results.is_empty() {
+                ""
+            } else {
+                " (No match)"
+            },
+            self.search.case_mode.label(),
+            if self.search.whole_word {
+                "words"
+            } else {
+                "parts"
+            }
+        );
+    }
+
     pub fn search(&mut self) {
         self.search.results.clear();
         self.search.current_match_index = None;
@@ -91,17 +213,19 @@ impl Editor {
             return;
         }
 
-        for (row_idx, line) in self.document.lines.iter().enumerate() {
-            for (col_idx, _) in line.match_indices(&self.search.query) {
-                self.search.results.push((row_idx, col_idx));
+        let query = &self.search.query;
+        let ignore_case = self.search.case_mode.ignores_case(query);
+        for (row_idx, line) in self.document.lines().iter().enumerate() {
+            for (col_idx, len) in find_matches(line, query, ignore_case, self.search.whole_word) {
+                self.search.results.push((row_idx, col_idx, len));
             }
         }
 
         if !self.search.results.is_empty() {
             // Try to find a match from the current cursor position onwards
-            let current_pos = (self.cursor_y, self.cursor_x);
+            let current_pos = (self.cursor.y, self.cursor.x);
             let mut found_current_or_next = false;
-            for (i, &(row, col)) in self.search.results.iter().enumerate() {
+            for (i, &(row, col, _)) in self.search.results.iter().enumerate() {
                 if row > current_pos.0 || (row == current_pos.0 && col >= current_pos.1) {
                     self.search.current_match_index = Some(i);
                     self.move_to_match();
@@ -119,12 +243,12 @@ impl Editor {
 
     pub fn move_to_match(&mut self) {
         if let Some(index) = self.search.current_match_index {
-            if let Some(&(row, col)) = self.search.results.get(index) {
-                self.cursor_y = row;
-                self.cursor_x = col;
-                self.desired_cursor_x = self.scroll.get_display_width_from_bytes(
-                    &self.document.lines[self.cursor_y],
-                    self.cursor_x,
+            if let Some(&(row, col, _)) = self.search.results.get(index) {
+                self.cursor.y = row;
+                self.cursor.x = col;
+                self.cursor.desired_x = self.scroll.get_display_width_from_bytes(
+                    &self.document.lines()[self.cursor.y],
+                    self.cursor.x,
                 );
             }
         }
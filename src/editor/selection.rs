@@ -1,8 +1,11 @@
-use crate::document::{ActionDiff, Document};
+use crate::document::{ActionDiff, Document, LineBuffer};
+use crate::editor::cursor::Cursor;
 use crate::error::Result;
 
 pub struct Selection {
     pub marker_pos: Option<(usize, usize)>,
+    // Select a block of columns instead of a stream of text
+    pub rectangle: bool,
 }
 
 impl Default for Selection {
@@ -13,25 +16,39 @@ impl Default for Selection {
 
 impl Selection {
     pub fn new() -> Self {
-        Self { marker_pos: None }
+        Self {
+            marker_pos: None,
+            rectangle: false,
+        }
     }
 
-    pub fn set_marker(&mut self, cursor_pos: (usize, usize)) {
-        self.marker_pos = Some(cursor_pos);
+    pub fn set_marker(&mut self, cursor: &Cursor) {
+        self.marker_pos = Some(cursor.pos());
     }
 
     pub fn clear_marker(&mut self) {
         self.marker_pos = None;
+        self.rectangle = false;
+    }
+
+    /// Keeps the marker inside `lines` after an edit removed text under it.
+    pub fn clamp_marker(&mut self, lines: &LineBuffer) {
+        if let Some((x, y)) = &mut self.marker_pos {
+            *y = (*y).min(lines.len().saturating_sub(1));
+            let line = lines.get(*y).map_or("", String::as_str);
+            *x = (*x).min(line.len());
+            while !line.is_char_boundary(*x) {
+                *x -= 1;
+            }
+        }
     }
 
     pub fn is_selection_active(&self) -> bool {
         self.marker_pos.is_some()
     }
 
-    pub fn get_selection_range(
-        &self,
-        cursor_pos: (usize, usize),
-    ) -> Option<((usize, usize), (usize, usize))> {
+    pub fn get_selection_range(&self, cursor: &Cursor) -> Option<((usize, usize), (usize, usize))> {
+        let cursor_pos = cursor.pos();
         if let Some(marker) = self.marker_pos {
             if marker.1 < cursor_pos.1 || (marker.1 == cursor_pos.1 && marker.0 < cursor_pos.0) {
                 Some((marker, cursor_pos))
@@ -46,22 +63,22 @@ impl Selection {
     pub fn cut_selection(
         &mut self,
         document: &Document,
-        cursor_pos: (usize, usize),
+        cursor: &Cursor,
     ) -> Result<(String, Option<crate::document::ActionDiff>)> {
-        if let Some(((start_x, start_y), (end_x, end_y))) = self.get_selection_range(cursor_pos) {
+        if let Some(((start_x, start_y), (end_x, end_y))) = self.get_selection_range(cursor) {
             let mut killed_text = String::new();
             let mut deleted_content_lines: Vec<String> = Vec::new();
 
             if start_y == end_y {
                 // Single line selection
-                let line = &document.lines[start_y];
+                let line = &document.lines()[start_y];
                 let removed = line[start_x..end_x].to_string();
                 killed_text.push_str(&removed);
                 deleted_content_lines.push(removed);
             } else {
                 // Multi-line selection
                 // Part of the start line
-                let start_line = &document.lines[start_y];
+                let start_line = &document.lines()[start_y];
                 let removed_start = start_line[start_x..].to_string();
                 killed_text.push_str(&removed_start);
                 killed_text.push('\n');
@@ -69,13 +86,13 @@ impl Selection {
 
                 // Full lines in between
                 for i in (start_y + 1)..end_y {
-                    killed_text.push_str(&document.lines[i]);
+                    killed_text.push_str(&document.lines()[i]);
                     killed_text.push('\n');
-                    deleted_content_lines.push(document.lines[i].clone());
+                    deleted_content_lines.push(document.lines()[i].clone());
                 }
 
                 // Part of the end line
-                let end_line = &document.lines[end_y];
+                let end_line = &document.lines()[end_y];
                 killed_text.push_str(&end_line[..end_x]);
                 deleted_content_lines.push(end_line[..end_x].to_string());
             }
@@ -101,31 +118,27 @@ impl Selection {
         }
     }
 
-    pub fn copy_selection(
-        &mut self,
-        document: &Document,
-        cursor_pos: (usize, usize),
-    ) -> Result<String> {
-        if let Some(((start_x, start_y), (end_x, end_y))) = self.get_selection_range(cursor_pos) {
+    pub fn copy_selection(&mut self, document: &Document, cursor: &Cursor) -> Result<String> {
+        if let Some(((start_x, start_y), (end_x, end_y))) = self.get_selection_range(cursor) {
             let mut copied_text = String::new();
 
             if start_y == end_y {
                 // Single line selection
-                copied_text.push_str(&document.lines[start_y][start_x..end_x]);
+                copied_text.push_str(&document.lines()[start_y][start_x..end_x]);
             } else {
                 // Multi-line selection
                 // Part of the start line
-                copied_text.push_str(&document.lines[start_y][start_x..]);
+                copied_text.push_str(&document.lines()[start_y][start_x..]);
                 copied_text.push('\n');
 
                 // Full lines in between
                 for i in (start_y + 1)..end_y {
-                    copied_text.push_str(&document.lines[i]);
+                    copied_text.push_str(&document.lines()[i]);
                     copied_text.push('\n');
                 }
 
                 // Part of the end line
-                copied_text.push_str(&document.lines[end_y][..end_x]);
+                copied_text.push_str(&document.lines()[end_y][..end_x]);
             }
             self.clear_marker();
             Ok(copied_text)
@@ -0,0 +1,115 @@
+use crate::editor::Editor;
+use crate::editor::fold::fold_range;
+use crate::editor::minibuffer::PromptKind;
+use crate::persistence::{self, Session};
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+#[derive(Debug, Default)]
+pub struct Sessions {
+    // The session restored or saved last, offered when saving again
+    pub name: Option<String>,
+    // Where `.dmacs/sessions` is instead of the state directory, e.g. in tests
+    pub base_dir: Option<PathBuf>,
+}
+
+impl Editor {
+    pub fn save_session(&mut self) {
+        let name = self.sessions.name.clone().unwrap_or_default();
+        self.open_minibuffer_with_input(PromptKind::SaveSession, &name);
+    }
+
+    /// Saves the editor as it is now as the session `name`.
+    pub fn save_session_as(&mut self, name: &str) {
+        let name = name.trim();
+        if !persistence::is_valid_session_name(name) {
+            self.set_message(&format!("Invalid session name: {name}"));
+            return;
+        }
+        let Some(path) = persistence::session_path(self.sessions.base_dir.clone(), name) else {
+            self.set_message("Sessions are not available.");
+            return;
+        };
+        match persistence::save_session(&path, &self.current_session()) {
+            Ok(()) => {
+                self.sessions.name = Some(name.to_string());
+                self.set_message(&format!("Session {name} saved."));
+            }
+            Err(e) => self.set_message(&format!("Could not save session {name}: {e}")),
+        }
+    }
+
+    /// The file, the view of it and the modes in effect. When the scratch
+    /// buffer is shown, the file is the one it was opened over.
+    pub fn current_session(&self) -> Session {
+        let file_buffer = self
+            .alternate_buffer
+            .as_ref()
+            .filter(|buffer| self.is_scratch_buffer() && buffer.document.filename.is_some());
+        let (file_path, cursor, scroll, folds) = match file_buffer {
+            Some(buffer) => (
+                buffer.document.filename.clone(),
+                (buffer.cursor.x, buffer.cursor.y),
+                buffer.scroll_offset,
+                // Switching buffers unfolds the file
+                Vec::new(),
+            ),
+            None => (
+                self.document.filename.clone(),
+                self.cursor_pos(),
+                (self.scroll.row_offset, self.scroll.col_offset),
+                self.folds.headers().to_vec(),
+            ),
+        };
+        Session {
+            file_path,
+            cursor_x: cursor.0,
+            cursor_y: cursor.1,
+            scroll_row_offset: scroll.0,
+            scroll_col_offset: scroll.1,
+            scratch: file_buffer.is_some(),
+            folds,
+            subword_mode: self.subword_mode,
+            hint_bar: self.settings.hint_bar,
+            saved_at: SystemTime::now(),
+        }
+    }
+
+    /// Continues the session `name`, bringing back the view of its file when
+    /// `session` was saved for the file that is open.
+    pub fn restore_session(&mut self, name: &str, session: Option<&Session>) {
+        self.sessions.name = Some(name.to_string());
+        let Some(session) = session else {
+            self.set_message(&format!("New session {name}"));
+            return;
+        };
+        self.settings.hint_bar = session.hint_bar;
+        if session.file_path != self.document.filename {
+            return;
+        }
+        let lines = self.document.lines();
+        let y = session.cursor_y.min(lines.len().saturating_sub(1));
+        let mut x = session.cursor_x.min(lines[y].len());
+        while !lines[y].is_char_boundary(x) {
+            x -= 1;
+        }
+        self.set_cursor_pos(x, y);
+        self.cursor.desired_x = self
+            .scroll
+            .get_display_width_from_bytes(&self.document.lines()[y], x);
+        (self.scroll.row_offset, self.scroll.col_offset) =
+            (session.scroll_row_offset, session.scroll_col_offset);
+        for &header in &session.folds {
+            if fold_range(self.document.lines(), header).is_some() {
+                self.folds.fold(header);
+            }
+        }
+        self.snap_to_fold_header();
+        if session.scratch && !self.is_scratch_buffer() {
+            self.toggle_scratch_buffer();
+        }
+        // Set for the buffer shown, which switching buffers resets
+        self.subword_mode = session.subword_mode;
+        self.set_message(&format!("Session {name}"));
+    }
+}
@@ -0,0 +1,242 @@
+use crate::document::ActionDiff;
+use crate::editor::{Editor, LastActionType};
+use std::io::Read;
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_SHELL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What a `/sh` command printed and how it ended.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ShellOutput {
+    pub stdout: String,
+    pub stderr: String,
+    // None when the command was killed or did not start
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+}
+
+/// Runs `command` with `sh -c` in `dir`, killing it after `timeout`.
+pub fn run_shell_command(command: &str, dir: Option<&Path>, timeout: Duration) -> ShellOutput {
+    let mut process = Command::new("sh");
+    process
+        .arg("-c")
+        .arg(command)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(dir) = dir {
+        process.current_dir(dir);
+    }
+    // Its own process group, so that a timeout also stops what it started
+    #[cfg(unix)]
+    process.process_group(0);
+    let mut child = match process.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            return ShellOutput {
+                stderr: format!("Failed to run sh: {e}"),
+                ..ShellOutput::default()
+            };
+        }
+    };
+
+    // Drain both pipes while waiting so a chatty command cannot block on a full pipe
+    let stdout = read_in_background(child.stdout.take());
+    let stderr = read_in_background(child.stderr.take());
+    let started = Instant::now();
+    let mut timed_out = false;
+    let exit_code = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status.code(),
+            Ok(None) if started.elapsed() >= timeout => {
+                #[cfg(unix)]
+                unsafe {
+                    libc::kill(-(child.id() as i32), libc::SIGKILL);
+                }
+                let _ = child.kill();
+                let _ = child.wait();
+                timed_out = true;
+                break None;
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(10)),
+            Err(_) => break None,
+        }
+    };
+    ShellOutput {
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+        exit_code,
+        timed_out,
+    }
+}
+
+fn read_in_background(pipe: Option<impl Read + Send + 'static>) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut bytes = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut bytes);
+        }
+        String::from_utf8_lossy(&bytes).into_owned()
+    })
+}
+
+/// A `/sh` command running in the background.
+pub struct ShellJob {
+    // The command line as typed, to find it again if lines above it changed
+    line: String,
+    y: usize,
+    replace: bool,
+    timeout: Duration,
+    receiver: Receiver<ShellOutput>,
+}
+
+impl Editor {
+    pub fn is_shell_command_running(&self) -> bool {
+        self.shell_job.is_some()
+    }
+
+    /// Starts `command` for the command line at the cursor. Its output is put
+    /// below the line, or in place of it when `replace` is set, once it ends.
+    pub fn start_shell_command(&mut self, command: &str, replace: bool) {
+        if self.shell_job.is_some() {
+            self.set_message("A shell command is already running.");
+            return;
+        }
+        let timeout = self
+            .settings
+            .shell_timeout_secs
+            .map_or(DEFAULT_SHELL_TIMEOUT, Duration::from_secs);
+        let dir: Option<PathBuf> = self
+            .document
+            .filename
+            .as_deref()
+            .and_then(|filename| Path::new(filename).parent())
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .map(Path::to_path_buf);
+        let (tx, rx) = mpsc::channel();
+        let command_to_run = command.to_string();
+        thread::spawn(move || {
+            let _ = tx.send(run_shell_command(&command_to_run, dir.as_deref(), timeout));
+        });
+        self.shell_job = Some(ShellJob {
+            line: self.document.lines()[self.cursor.y].clone(),
+            y: self.cursor.y,
+            replace,
+            timeout,
+            receiver: rx,
+        });
+        self.set_message(&format!("Running {command}..."));
+    }
+
+    pub fn shell_tick(&mut self) {
+        let Some(job) = &self.shell_job else {
+            return;
+        };
+        let output = match job.receiver.try_recv() {
+            Ok(output) => output,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => ShellOutput {
+                stderr: "The shell command was lost.".to_string(),
+                ..ShellOutput::default()
+            },
+        };
+        let job = self.shell_job.take().unwrap();
+        self.finish_shell_command(job, output);
+    }
+
+    fn finish_shell_command(&mut self, job: ShellJob, output: ShellOutput) {
+        let stderr = output.stderr.lines().find(|line| !line.trim().is_empty());
+        let failure = if output.timed_out {
+            Some(format!("timed out after {}s", job.timeout.as_secs()))
+        } else {
+            match output.exit_code {
+                Some(0) => None,
+                Some(code) => Some(format!("exit {code}")),
+                None => Some("failed".to_string()),
+            }
+        };
+        let lines: Vec<String> = output.stdout.lines().map(str::to_string).collect();
+        if lines.is_empty() || failure.is_some() {
+            let mut message = match failure {
+                Some(failure) => format!("/sh {failure}"),
+                None => "/sh: no output".to_string(),
+            };
+            if let Some(stderr) = stderr {
+                message.push_str(": ");
+                message.push_str(stderr.trim());
+            }
+            self.set_message(&message);
+            return;
+        }
+
+        let Some(y) = self.find_shell_command_line(&job) else {
+            self.set_message("/sh: the command line was edited; output discarded.");
+            return;
+        };
+        let count = lines.len();
+        let diff = if job.replace {
+            ActionDiff {
+                cursor_start_x: self.cursor.x,
+                cursor_start_y: self.cursor.y,
+                cursor_end_x: 0,
+                cursor_end_y: y,
+                start_x: 0,
+                start_y: y,
+                end_x: job.line.len(),
+                end_y: y,
+                new: lines,
+                old: vec![job.line.clone()],
+            }
+        } else {
+            let end = self.document.lines()[y].len();
+            let mut new = vec![String::new()];
+            new.extend(lines);
+            ActionDiff {
+                cursor_start_x: self.cursor.x,
+                cursor_start_y: self.cursor.y,
+                cursor_end_x: self.cursor.x,
+                cursor_end_y: self.cursor.y,
+                start_x: end,
+                start_y: y,
+                end_x: new.last().unwrap().len(),
+                end_y: y + count,
+                new,
+                old: vec![],
+            }
+        };
+        let (cursor_x, cursor_y) = (self.cursor.x, self.cursor.y);
+        self.undo_redo.begin_group();
+        self.commit(LastActionType::Other, &diff);
+        self.undo_redo.end_group();
+        // Keep typing where the cursor was, unless that text was replaced
+        if !job.replace || cursor_y != y {
+            let cursor_y = if cursor_y > y {
+                cursor_y + count - usize::from(job.replace)
+            } else {
+                cursor_y
+            };
+            self.set_cursor_pos(cursor_x, cursor_y);
+        }
+        let mut message = format!("/sh: {count} line(s) of output");
+        if let Some(stderr) = stderr {
+            message.push_str(&format!(" ({})", stderr.trim()));
+        }
+        self.set_message(&message);
+    }
+
+    fn find_shell_command_line(&self, job: &ShellJob) -> Option<usize> {
+        if self.document.lines().get(job.y) == Some(&job.line) {
+            return Some(job.y);
+        }
+        self.document
+            .lines()
+            .iter()
+            .position(|line| *line == job.line)
+    }
+}
@@ -0,0 +1,470 @@
+use crate::document::ActionDiff;
+use crate::editor::{Editor, EditorMode, LastActionType};
+use chrono::{DateTime, Local};
+use pancurses::Input;
+
+pub const CURSOR_PLACEHOLDER: &str = "{cursor}";
+// The tab stop the cursor leaves the snippet at
+const EXIT_STOP: usize = 0;
+
+/// Text to insert, with where the cursor goes in it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snippet {
+    pub lines: Vec<String>,
+    // Relative to the start of the snippet: column (in bytes) and line
+    pub cursor: (usize, usize),
+}
+
+/// Expands the placeholders of `template`: `{date}` and `{time}` as of `now`,
+/// `{file}` as the name of `file` without its extension, and `{cursor}` as the
+/// place the cursor goes, at the end of the snippet when there is none.
+/// Other text in braces is kept as it is.
+pub fn expand(template: &str, now: DateTime<Local>, file: Option<&str>) -> Snippet {
+    let file = file
+        .and_then(|file| std::path::Path::new(file).file_stem())
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let text = template
+        .strip_suffix('\n')
+        .unwrap_or(template)
+        .replace("{date}", &now.format("%Y-%m-%d").to_string())
+        .replace("{time}", &now.format("%H:%M").to_string())
+        .replace("{file}", &file);
+
+    let mut cursor = None;
+    let lines = text
+        .split('\n')
+        .enumerate()
+        .map(|(y, line)| {
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            match line.find(CURSOR_PLACEHOLDER) {
+                Some(x) if cursor.is_none() => {
+                    cursor = Some((x, y));
+                    line.replacen(CURSOR_PLACEHOLDER, "", 1)
+                }
+                _ => line.to_string(),
+            }
+        })
+        .collect::<Vec<_>>();
+    let cursor = cursor.unwrap_or_else(|| (lines.last().map_or(0, String::len), lines.len() - 1));
+    Snippet { lines, cursor }
+}
+
+/// A tab stop of an expanded snippet, with its text at `start..end` of
+/// line `y`. Fields with the same number mirror each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Field {
+    pub number: usize,
+    pub y: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// The number, the default text and the length of the tab stop at the start
+/// of `text`, the part of a template after a `$`: `1` or `{1:default}`.
+fn parse_stop(text: &str) -> Option<(usize, &str, usize)> {
+    let (inner, braced) = match text.strip_prefix('{') {
+        Some(inner) => (inner, true),
+        None => (text, false),
+    };
+    let digits = inner
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(inner.len());
+    let number = inner[..digits].parse().ok()?;
+    if !braced {
+        return Some((number, "", digits));
+    }
+    let close = digits + inner[digits..].find('}')?;
+    let default = match &inner[digits..close] {
+        "" => "",
+        rest => rest.strip_prefix(':')?,
+    };
+    Some((number, default, close + 2))
+}
+
+/// Takes the `$1`, `${2:default}` and `$0` tab stops out of `snippet` and
+/// returns them in the order they appear. Without a `$0` the snippet is
+/// left where its cursor goes.
+pub fn tab_stops(snippet: &mut Snippet) -> Vec<Field> {
+    let mut fields = Vec::new();
+    let (cursor_x, cursor_y) = snippet.cursor;
+    for (y, line) in snippet.lines.iter_mut().enumerate() {
+        let mut text = String::with_capacity(line.len());
+        let mut rest = line.as_str();
+        let mut removed = 0;
+        while let Some(dollar) = rest.find('$') {
+            text.push_str(&rest[..dollar]);
+            let at = line.len() - rest.len() + dollar;
+            let after = &rest[dollar + 1..];
+            let Some((number, default, len)) = parse_stop(after) else {
+                text.push('$');
+                rest = after;
+                continue;
+            };
+            if y == cursor_y && cursor_x > at {
+                removed += len + 1 - default.len();
+            }
+            fields.push(Field {
+                number,
+                y,
+                start: text.len(),
+                end: text.len() + default.len(),
+            });
+            text.push_str(default);
+            rest = &after[len..];
+        }
+        text.push_str(rest);
+        if y == cursor_y {
+            snippet.cursor.0 -= removed;
+        }
+        *line = text;
+    }
+    if !fields.iter().any(|field| field.number == EXIT_STOP) {
+        let (x, y) = snippet.cursor;
+        fields.push(Field {
+            number: EXIT_STOP,
+            y,
+            start: x,
+            end: x,
+        });
+    }
+    fields
+}
+
+/// The tab stops of the snippet being filled in, visited by number with
+/// `$0` last. The first field of a number is the one typed in.
+#[derive(Debug)]
+pub struct SnippetStops {
+    pub fields: Vec<Field>,
+    order: Vec<usize>,
+    current: usize,
+}
+
+impl SnippetStops {
+    fn new(fields: Vec<Field>) -> Self {
+        let mut order: Vec<usize> = fields.iter().map(|field| field.number).collect();
+        order.sort_unstable_by_key(|&number| (number == EXIT_STOP, number));
+        order.dedup();
+        Self {
+            fields,
+            order,
+            current: 0,
+        }
+    }
+
+    /// The field typed in at the current stop.
+    pub fn primary(&self) -> Option<Field> {
+        let number = self.order[self.current];
+        self.fields
+            .iter()
+            .copied()
+            .find(|field| field.number == number)
+    }
+
+    /// Moves the fields on line `y` from byte `from` on by `delta` bytes.
+    fn shift(&mut self, y: usize, from: usize, delta: isize, except: usize) {
+        for (index, field) in self.fields.iter_mut().enumerate() {
+            if index != except && field.y == y && field.start >= from {
+                field.start = field.start.saturating_add_signed(delta);
+                field.end = field.end.saturating_add_signed(delta);
+            }
+        }
+    }
+
+    fn lines(&self) -> (usize, usize) {
+        let ys = self.fields.iter().map(|field| field.y);
+        (ys.clone().min().unwrap_or(0), ys.max().unwrap_or(0))
+    }
+}
+
+/// The document as it was before an input, to find what the input changed
+/// in the field being typed in.
+pub struct FieldSnapshot {
+    revision: u64,
+    line_count: usize,
+    line: String,
+}
+
+/// The bytes at the start and at the end that `old` and `new` share.
+fn common_ends(old: &str, new: &str) -> (usize, usize) {
+    let prefix: usize = old
+        .chars()
+        .zip(new.chars())
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a.len_utf8())
+        .sum();
+    let suffix: usize = old
+        .chars()
+        .rev()
+        .zip(new.chars().rev())
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a.len_utf8())
+        .sum();
+    (prefix, suffix)
+}
+
+impl Editor {
+    /// Replaces the trigger word before the cursor with the snippet it names
+    /// in the snippets file, as one undo step, and moves to its first tab
+    /// stop. The longest trigger ending at the cursor wins.
+    pub fn expand_snippet(&mut self) {
+        self.clipboard.last_action_was_kill = false;
+        let (x, y) = self.cursor_pos();
+        let line = &self.document.lines()[y];
+        let word_start = line[..x]
+            .char_indices()
+            .rev()
+            .take_while(|&(_, ch)| !ch.is_whitespace())
+            .last()
+            .map_or(x, |(idx, _)| idx);
+        if word_start == x {
+            self.set_message("No snippet trigger before the cursor.");
+            return;
+        }
+        let snippets = self.settings.snippets.as_ref();
+        let Some((start, template)) = line[word_start..x].char_indices().find_map(|(idx, _)| {
+            Some((word_start + idx, snippets?.get(&line[word_start + idx..x])?))
+        }) else {
+            self.set_message(&format!("No snippet for {}.", &line[word_start..x]));
+            return;
+        };
+        let trigger = line[start..x].to_string();
+        let indent: String = line.chars().take_while(|c| c.is_whitespace()).collect();
+        let mut snippet = expand(template, Local::now(), self.document.filename.as_deref());
+        let mut fields = tab_stops(&mut snippet);
+        // Following lines keep the indentation of the trigger line
+        for line in snippet.lines.iter_mut().skip(1) {
+            line.insert_str(0, &indent);
+        }
+        for field in &mut fields {
+            let offset = if field.y == 0 { start } else { indent.len() };
+            field.start += offset;
+            field.end += offset;
+            field.y += y;
+        }
+
+        let stops = SnippetStops::new(fields);
+        let Some(first) = stops.primary() else {
+            return;
+        };
+        let end_y = y + snippet.lines.len() - 1;
+        let last_len = snippet.lines.last().map_or(0, String::len);
+        let end_x = if end_y == y {
+            start + last_len
+        } else {
+            last_len
+        };
+        self.undo_redo.begin_group();
+        self.commit(
+            LastActionType::Other,
+            &ActionDiff {
+                cursor_start_x: x,
+                cursor_start_y: y,
+                cursor_end_x: start,
+                cursor_end_y: y,
+                start_x: start,
+                start_y: y,
+                end_x: x,
+                end_y: y,
+                new: vec![],
+                old: vec![trigger.clone()],
+            },
+        );
+        self.commit(
+            LastActionType::Ammend,
+            &ActionDiff {
+                cursor_start_x: start,
+                cursor_start_y: y,
+                cursor_end_x: first.end,
+                cursor_end_y: first.y,
+                start_x: start,
+                start_y: y,
+                end_x,
+                end_y,
+                new: snippet.lines,
+                old: vec![],
+            },
+        );
+        self.undo_redo.end_group();
+        self.cursor.desired_x = self
+            .scroll
+            .get_display_width_from_bytes(&self.document.lines()[first.y], first.end);
+        if stops.order.len() > 1 {
+            self.snippet_stops = Some(stops);
+            self.set_message(&format!("Snippet {trigger}: Tab for the next field."));
+        } else {
+            self.snippet_stops = None;
+        }
+    }
+
+    /// Moves to the next or previous tab stop of the snippet. Reaching `$0`
+    /// leaves the snippet.
+    pub(super) fn go_to_snippet_stop(&mut self, forward: bool) {
+        let Some(stops) = &mut self.snippet_stops else {
+            return;
+        };
+        stops.current = if forward {
+            (stops.current + 1).min(stops.order.len() - 1)
+        } else {
+            stops.current.saturating_sub(1)
+        };
+        let Some(field) = stops.primary() else {
+            return;
+        };
+        if stops.current == stops.order.len() - 1 {
+            self.snippet_stops = None;
+        }
+        self.selection.clear_marker();
+        self.set_cursor_pos(field.end, field.y);
+        self.cursor.desired_x = self
+            .scroll
+            .get_display_width_from_bytes(&self.document.lines()[field.y], field.end);
+    }
+
+    /// Handles Tab and Shift-Tab while a snippet is being filled in.
+    pub(super) fn handle_snippet_key(&mut self, key: Input, is_alt_pressed: bool) -> bool {
+        if self.snippet_stops.is_none()
+            || is_alt_pressed
+            || self.mode != EditorMode::Normal
+            || self.search.mode
+        {
+            return false;
+        }
+        match key {
+            Input::Character('\t') => self.go_to_snippet_stop(true),
+            Input::KeyBTab | Input::KeySTab => self.go_to_snippet_stop(false),
+            _ => return false,
+        }
+        true
+    }
+
+    pub(super) fn snippet_snapshot(&self) -> Option<FieldSnapshot> {
+        let field = self.snippet_stops.as_ref()?.primary()?;
+        Some(FieldSnapshot {
+            revision: self.document.revision(),
+            line_count: self.document.lines().len(),
+            line: self.document.lines().get(field.y)?.clone(),
+        })
+    }
+
+    /// Follows an edit in the field being typed in and copies its text to
+    /// the fields that mirror it. An edit anywhere else, or leaving the lines
+    /// of the snippet, ends it.
+    pub(super) fn follow_snippet_edit(&mut self, before: Option<FieldSnapshot>) {
+        let Some(before) = before else {
+            return;
+        };
+        if self.document.revision() == before.revision {
+            if let Some(stops) = &self.snippet_stops {
+                let (top, bottom) = stops.lines();
+                if !(top..=bottom).contains(&self.cursor.y) {
+                    self.snippet_stops = None;
+                }
+            }
+            return;
+        }
+        let Some(stops) = &mut self.snippet_stops else {
+            return;
+        };
+        let Some(field) = stops.primary() else {
+            return;
+        };
+        let index = stops.fields.iter().position(|f| *f == field).unwrap_or(0);
+        let old = &before.line;
+        let new = &self.document.lines()[field.y];
+        // Where the line changed, as far inside the field as it can be
+        let (prefix, suffix) = common_ends(old, new);
+        let prefix = prefix.min(field.end);
+        let suffix = suffix.min(old.len().min(new.len()) - prefix);
+        let (old_end, new_end) = (old.len() - suffix, new.len() - suffix);
+        if self.document.lines().len() != before.line_count
+            || prefix < field.start
+            || old_end > field.end
+        {
+            self.snippet_stops = None;
+            return;
+        }
+        let delta = new_end as isize - old_end as isize;
+        stops.shift(field.y, field.end, delta, index);
+        stops.fields[index].end = field.end.saturating_add_signed(delta);
+
+        let field = stops.fields[index];
+        let text = new[field.start..field.end].to_string();
+        let mirrors: Vec<usize> = (0..stops.fields.len())
+            .filter(|&i| i != index && stops.fields[i].number == field.number)
+            .collect();
+        for mirror in mirrors {
+            let Some(stops) = &mut self.snippet_stops else {
+                return;
+            };
+            let Field { y, start, end, .. } = stops.fields[mirror];
+            let old_text = self.document.lines()[y][start..end].to_string();
+            if old_text == text {
+                continue;
+            }
+            let delta = text.len() as isize - old_text.len() as isize;
+            stops.shift(y, end, delta, mirror);
+            stops.fields[mirror].end = start + text.len();
+            let (mut cursor_x, cursor_y) = self.cursor_pos();
+            if cursor_y == y && cursor_x >= end {
+                cursor_x = cursor_x.saturating_add_signed(delta);
+            }
+            self.commit(
+                LastActionType::Ammend,
+                &ActionDiff {
+                    cursor_start_x: self.cursor.x,
+                    cursor_start_y: self.cursor.y,
+                    cursor_end_x: cursor_x,
+                    cursor_end_y: cursor_y,
+                    start_x: start,
+                    start_y: y,
+                    end_x: end,
+                    end_y: y,
+                    new: vec![text.clone()],
+                    old: vec![old_text],
+                },
+            );
+        }
+    }
+
+    /// Replaces line `y` with `snippet` as one undo step and puts the cursor
+    /// where the snippet says.
+    pub(super) fn replace_line_with_snippet(&mut self, y: usize, snippet: Snippet) {
+        let old_line = self.document.lines()[y].clone();
+        let (cursor_x, cursor_y) = self.cursor_pos();
+        let end_y = y + snippet.lines.len() - 1;
+        let end_x = snippet.lines.last().map_or(0, String::len);
+        self.undo_redo.begin_group();
+        self.commit(
+            LastActionType::Other,
+            &ActionDiff {
+                cursor_start_x: cursor_x,
+                cursor_start_y: cursor_y,
+                cursor_end_x: 0,
+                cursor_end_y: y,
+                start_x: 0,
+                start_y: y,
+                end_x: old_line.len(),
+                end_y: y,
+                new: vec![],
+                old: vec![old_line],
+            },
+        );
+        self.commit(
+            LastActionType::Ammend,
+            &ActionDiff {
+                cursor_start_x: 0,
+                cursor_start_y: y,
+                cursor_end_x: snippet.cursor.0,
+                cursor_end_y: y + snippet.cursor.1,
+                start_x: 0,
+                start_y: y,
+                end_x,
+                end_y,
+                new: snippet.lines,
+                old: vec![],
+            },
+        );
+        self.undo_redo.end_group();
+    }
+}
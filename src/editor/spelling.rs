@@ -0,0 +1,247 @@
+use crate::editor::agenda::truncate_to_width;
+use crate::editor::{Editor, EditorMode};
+use crate::screen::Screen;
+use crate::spellcheck::SpellChecker;
+use pancurses::{A_BOLD, A_REVERSE, Input};
+use unicode_width::UnicodeWidthStr;
+
+const MAX_SUGGESTIONS: usize = 9;
+
+#[derive(Debug, Default)]
+pub struct Spelling {
+    // Loaded when the `spellcheck` setting names a language
+    pub checker: Option<SpellChecker>,
+    language: Option<String>,
+    pub picker: Option<SuggestionPicker>,
+}
+
+/// Corrections offered for the misspelled word at `start..end` of line `y`.
+#[derive(Debug)]
+pub struct SuggestionPicker {
+    pub y: usize,
+    pub start: usize,
+    pub end: usize,
+    pub suggestions: Vec<String>,
+    // Past the suggestions, the last entry adds the word to the dictionary
+    pub selected: usize,
+}
+
+impl Spelling {
+    pub fn misspellings(&self, line: &str) -> Vec<(usize, usize)> {
+        match &self.checker {
+            Some(checker) => checker.misspellings(line),
+            None => Vec::new(),
+        }
+    }
+}
+
+impl Editor {
+    /// Loads the dictionary for `language`, or turns spell checking off.
+    pub(super) fn set_spellcheck_language(&mut self, language: Option<&str>) {
+        if self.spelling.language.as_deref() == language {
+            return;
+        }
+        self.spelling.language = language.map(String::from);
+        self.spelling.checker = None;
+        if let Some(language) = language {
+            match SpellChecker::load(language, None) {
+                Ok(checker) => self.spelling.checker = Some(checker),
+                Err(e) => self.set_message(&format!("Spell checking is off: {e}")),
+            }
+        }
+    }
+
+    pub fn set_spell_checker(&mut self, checker: Option<SpellChecker>) {
+        self.spelling.checker = checker;
+    }
+
+    /// Moves to the start of the next misspelled word, wrapping around at the
+    /// end of the buffer.
+    pub fn next_misspelling(&mut self) {
+        if self.spelling.checker.is_none() {
+            self.set_message("Spell checking is off; set spellcheck in the config.");
+            return;
+        }
+        let line_count = self.document.lines().len();
+        for offset in 0..=line_count {
+            let y = (self.cursor.y + offset) % line_count;
+            let found = self
+                .spelling
+                .misspellings(&self.document.lines()[y])
+                .into_iter()
+                .find(|&(start, _)| offset > 0 || start > self.cursor.x);
+            if let Some((start, _)) = found {
+                self.set_cursor_pos(start, y);
+                return;
+            }
+        }
+        self.set_message("No misspellings.");
+    }
+
+    /// Offers corrections for the misspelled word at the cursor.
+    pub fn correct_spelling(&mut self) {
+        let Some(checker) = &self.spelling.checker else {
+            self.set_message("Spell checking is off; set spellcheck in the config.");
+            return;
+        };
+        let y = self.cursor.y;
+        let line = &self.document.lines()[y];
+        let Some((start, end)) = checker
+            .misspellings(line)
+            .into_iter()
+            .find(|&(start, end)| start <= self.cursor.x && self.cursor.x <= end)
+        else {
+            self.set_message("No misspelled word at the cursor.");
+            return;
+        };
+        let suggestions = checker.suggestions(&line[start..end], MAX_SUGGESTIONS);
+        self.spelling.picker = Some(SuggestionPicker {
+            y,
+            start,
+            end,
+            suggestions,
+            selected: 0,
+        });
+        self.mode = EditorMode::SpellingSuggestions;
+    }
+
+    pub fn handle_spelling_suggestions_input(&mut self, key: Input) {
+        let Some(picker) = &mut self.spelling.picker else {
+            self.mode = EditorMode::Normal;
+            return;
+        };
+        let count = picker.suggestions.len() + 1;
+        match key {
+            Input::KeyUp | Input::Character('\x10') => {
+                picker.selected = (picker.selected + count - 1) % count;
+            }
+            Input::KeyDown | Input::Character('\x0e') => {
+                picker.selected = (picker.selected + 1) % count;
+            }
+            Input::Character(c @ '1'..='9') => {
+                let index = c as usize - '1' as usize;
+                if index < picker.suggestions.len() {
+                    picker.selected = index;
+                    self.apply_spelling_choice();
+                }
+            }
+            Input::Character('\n') | Input::Character('\r') => self.apply_spelling_choice(),
+            // Esc or Ctrl-G
+            Input::Character('\x1b') | Input::Character('\x07') => self.close_spelling_picker(),
+            _ => {}
+        }
+    }
+
+    fn apply_spelling_choice(&mut self) {
+        let Some(picker) = self.spelling.picker.take() else {
+            return;
+        };
+        self.mode = EditorMode::Normal;
+        let word = self.document.lines()[picker.y][picker.start..picker.end].to_string();
+        match picker.suggestions.get(picker.selected) {
+            Some(suggestion) => {
+                self.replace_in_line(picker.y, picker.start, picker.end, suggestion);
+                self.set_cursor_pos(picker.start + suggestion.len(), picker.y);
+            }
+            None => {
+                if let Some(checker) = &mut self.spelling.checker
+                    && let Err(e) = checker.add_word(&word)
+                {
+                    self.set_message(&format!("Could not save {word} to the dictionary: {e}"));
+                    return;
+                }
+                self.set_message(&format!("Added {word} to the dictionary."));
+            }
+        }
+    }
+
+    fn close_spelling_picker(&mut self) {
+        self.spelling.picker = None;
+        self.mode = EditorMode::Normal;
+    }
+
+    /// Draws the suggestions in a box under the cursor, or above it when the
+    /// cursor is near the bottom.
+    pub fn draw_spelling_suggestions(
+        &self,
+        window: &impl Screen,
+        cursor_row: usize,
+        cursor_col: usize,
+    ) {
+        let Some(picker) = &self.spelling.picker else {
+            return;
+        };
+        let word = &self.document.lines()[picker.y][picker.start..picker.end];
+        let mut labels: Vec<String> = picker
+            .suggestions
+            .iter()
+            .enumerate()
+            .map(|(index, suggestion)| format!("{} {suggestion}", index + 1))
+            .collect();
+        labels.push(format!("  Add \"{word}\" to the dictionary"));
+        let title = if picker.suggestions.is_empty() {
+            format!(" No suggestions for {word} ")
+        } else {
+            format!(" Correct {word} ")
+        };
+
+        let screen_rows = window.get_max_y() as usize;
+        let screen_cols = window.get_max_x() as usize;
+        let content_width = labels
+            .iter()
+            .map(|label| label.width())
+            .chain([title.width()])
+            .max()
+            .unwrap_or(0);
+        let height = labels.len() + 2;
+        let width = content_width + 4;
+        if height > screen_rows || width > screen_cols {
+            return;
+        }
+        let top = if cursor_row + 1 + height <= screen_rows {
+            cursor_row + 1
+        } else {
+            cursor_row.saturating_sub(height)
+        };
+        let left = cursor_col.min(screen_cols - width);
+        let (bottom, right) = (top + height - 1, left + width - 1);
+        let inner_width = right - left - 1;
+
+        let blank = " ".repeat(inner_width);
+        for row in top + 1..bottom {
+            window.mvaddch(row as i32, left as i32, pancurses::ACS_VLINE());
+            window.mvaddstr(row as i32, (left + 1) as i32, &blank);
+            window.mvaddch(row as i32, right as i32, pancurses::ACS_VLINE());
+        }
+        for col in left + 1..right {
+            window.mvaddch(top as i32, col as i32, pancurses::ACS_HLINE());
+            window.mvaddch(bottom as i32, col as i32, pancurses::ACS_HLINE());
+        }
+        window.mvaddch(top as i32, left as i32, pancurses::ACS_ULCORNER());
+        window.mvaddch(top as i32, right as i32, pancurses::ACS_URCORNER());
+        window.mvaddch(bottom as i32, left as i32, pancurses::ACS_LLCORNER());
+        window.mvaddch(bottom as i32, right as i32, pancurses::ACS_LRCORNER());
+        window.attron(A_BOLD);
+        window.mvaddstr(
+            top as i32,
+            (left + 1) as i32,
+            truncate_to_width(&title, inner_width),
+        );
+        window.attroff(A_BOLD);
+
+        for (offset, label) in labels.iter().enumerate() {
+            let attr = if offset == picker.selected {
+                A_REVERSE
+            } else {
+                0
+            };
+            window.attron(attr);
+            window.mvaddstr(
+                (top + 1 + offset) as i32,
+                (left + 1) as i32,
+                truncate_to_width(&format!(" {label}"), inner_width),
+            );
+            window.attroff(attr);
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use crate::editor::Editor;
+use unicode_segmentation::UnicodeSegmentation;
+
+// Average silent reading speed used for the reading time estimate
+const WORDS_PER_MINUTE: usize = 200;
+
+/// Counts for a buffer or a selection.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DocumentStats {
+    pub words: usize,
+    // User-perceived characters, so "é" or an emoji with a modifier count once
+    pub characters: usize,
+    pub lines: usize,
+    pub checked_tasks: usize,
+    pub unchecked_tasks: usize,
+}
+
+impl DocumentStats {
+    pub fn of_lines<S: AsRef<str>>(lines: impl IntoIterator<Item = S>) -> Self {
+        let mut stats = DocumentStats::default();
+        for line in lines {
+            let line = line.as_ref();
+            stats.lines += 1;
+            stats.characters += line.graphemes(true).count();
+            let mut text = line;
+            if Editor::is_checked_checkbox(line) {
+                stats.checked_tasks += 1;
+                // The x of a checked box is not a word
+                text = line.trim_start().trim_start_matches("- [x]");
+            } else if Editor::is_unchecked_checkbox(line) {
+                stats.unchecked_tasks += 1;
+            }
+            stats.words += text.unicode_words().count();
+        }
+        stats
+    }
+
+    /// Whole minutes to read the words, at least one if there are any.
+    pub fn reading_minutes(&self) -> usize {
+        self.words.div_ceil(WORDS_PER_MINUTE)
+    }
+
+    /// One line for the status bar, e.g. "120 words, 640 characters, 12 lines,
+    /// 3/5 tasks done, 1 min read".
+    pub fn summary(&self) -> String {
+        let mut parts = vec![
+            plural(self.words, "word"),
+            plural(self.characters, "character"),
+            plural(self.lines, "line"),
+        ];
+        let tasks = self.checked_tasks + self.unchecked_tasks;
+        if tasks > 0 {
+            parts.push(format!("{}/{tasks} tasks done", self.checked_tasks));
+        }
+        if self.words > 0 {
+            parts.push(format!("{} min read", self.reading_minutes()));
+        }
+        parts.join(", ")
+    }
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("1 {noun}")
+    } else {
+        format!("{count} {noun}s")
+    }
+}
+
+impl Editor {
+    /// Counts the selected text, or the whole buffer without a selection.
+    pub fn document_stats(&self) -> (DocumentStats, bool) {
+        let Some(((start_x, start_y), (end_x, end_y))) =
+            self.selection.get_selection_range(&self.cursor)
+        else {
+            return (DocumentStats::of_lines(self.document.lines()), false);
+        };
+        let lines: Vec<&str> = (start_y..=end_y)
+            .map(|y| {
+                let line = self.document.lines()[y].as_str();
+                let end = if y == end_y { end_x } else { line.len() };
+                let start = if y == start_y { start_x } else { 0 };
+                &line[start..end.max(start)]
+            })
+            .collect();
+        let mut stats = DocumentStats::of_lines(&lines);
+        // A selection ending at the start of a line does not take it in
+        if end_y > start_y && end_x == 0 {
+            stats.lines -= 1;
+        }
+        // Tasks are recognized by whole lines
+        let whole_lines = self.document.lines().range(start_y..=end_y);
+        let task_stats = DocumentStats::of_lines(whole_lines);
+        stats.checked_tasks = task_stats.checked_tasks;
+        stats.unchecked_tasks = task_stats.unchecked_tasks;
+        (stats, true)
+    }
+
+    pub fn show_stats(&mut self) {
+        let (stats, is_selection) = self.document_stats();
+        let scope = if is_selection { "Selection" } else { "Buffer" };
+        self.set_message(&format!("{scope}: {}", stats.summary()));
+    }
+}
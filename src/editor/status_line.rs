@@ -0,0 +1,256 @@
+use crate::editor::Editor;
+use crate::editor::ui::STATUS_COLOR_PAIR;
+use crate::git;
+use crate::screen::Screen;
+use pancurses::{A_BOLD, A_DIM, A_NORMAL, chtype};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use unicode_width::UnicodeWidthStr;
+
+const SEPARATOR: &str = " - ";
+// How long the git branch of the file is trusted before `.git/HEAD` is read again
+const BRANCH_REFRESH: Duration = Duration::from_secs(5);
+
+/// A part of the status line, in the order of the `status_segments` setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusSegment {
+    /// The file name, `*` when modified and whether it changed on disk.
+    File,
+    /// How many lines the buffer has.
+    Lines,
+    /// The line and column of the cursor.
+    Position,
+    /// How far through the file the cursor is.
+    Percent,
+    /// The `---` page the cursor is on, once there is more than one.
+    Page,
+    /// Line ending and encoding.
+    Format,
+    /// The current search match out of all of them.
+    Matches,
+    /// The git branch of the file.
+    Branch,
+    Undo,
+    Macro,
+    Timer,
+    Pomodoro,
+    Autosave,
+}
+
+impl StatusSegment {
+    pub const DEFAULT: [StatusSegment; 12] = [
+        StatusSegment::File,
+        StatusSegment::Lines,
+        StatusSegment::Position,
+        StatusSegment::Percent,
+        StatusSegment::Page,
+        StatusSegment::Matches,
+        StatusSegment::Branch,
+        StatusSegment::Undo,
+        StatusSegment::Macro,
+        StatusSegment::Timer,
+        StatusSegment::Pomodoro,
+        StatusSegment::Autosave,
+    ];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "file" => Some(StatusSegment::File),
+            "lines" => Some(StatusSegment::Lines),
+            "position" => Some(StatusSegment::Position),
+            "percent" => Some(StatusSegment::Percent),
+            "page" => Some(StatusSegment::Page),
+            "format" => Some(StatusSegment::Format),
+            "matches" => Some(StatusSegment::Matches),
+            "branch" => Some(StatusSegment::Branch),
+            "undo" => Some(StatusSegment::Undo),
+            "macro" => Some(StatusSegment::Macro),
+            "timer" => Some(StatusSegment::Timer),
+            "pomodoro" => Some(StatusSegment::Pomodoro),
+            "autosave" => Some(StatusSegment::Autosave),
+            _ => None,
+        }
+    }
+}
+
+/// The text of a segment and how it is drawn.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusPart {
+    pub text: String,
+    pub attr: chtype,
+    pub color_pair: Option<i16>,
+}
+
+impl StatusPart {
+    fn new(text: String, attr: chtype) -> Self {
+        Self {
+            text,
+            attr,
+            color_pair: Some(STATUS_COLOR_PAIR),
+        }
+    }
+}
+
+/// The git branch last read for a file.
+#[derive(Debug, Default)]
+pub struct BranchCache {
+    filename: Option<String>,
+    branch: Option<String>,
+    read_at: Option<Instant>,
+}
+
+impl Editor {
+    /// Segments from the `status_segments` setting, or the default ones when
+    /// it is not set.
+    pub fn status_segments(&self) -> Vec<StatusSegment> {
+        match &self.settings.status_segments {
+            Some(names) => names
+                .iter()
+                .filter_map(|name| {
+                    let segment = StatusSegment::parse(name);
+                    if segment.is_none() {
+                        log::warn!("Unknown status segment: {name}");
+                    }
+                    segment
+                })
+                .collect(),
+            None => StatusSegment::DEFAULT.to_vec(),
+        }
+    }
+
+    /// The git branch of the file, read again every few seconds.
+    pub fn git_branch(&mut self) -> Option<String> {
+        let cache = &mut self.branch_cache;
+        let stale = cache.filename != self.document.filename
+            || cache
+                .read_at
+                .is_none_or(|read_at| read_at.elapsed() >= BRANCH_REFRESH);
+        if stale {
+            cache.filename = self.document.filename.clone();
+            cache.branch = self
+                .document
+                .filename
+                .as_deref()
+                .and_then(|filename| git::current_branch(Path::new(filename)));
+            cache.read_at = Some(Instant::now());
+        }
+        cache.branch.clone()
+    }
+
+    /// What `segment` shows now, if anything.
+    pub fn status_part(&mut self, segment: StatusSegment) -> Option<StatusPart> {
+        match segment {
+            StatusSegment::File => {
+                let filename = self.document.filename.as_deref().unwrap_or("[No Name]");
+                let modified = if self.document.is_dirty() { "*" } else { "" };
+                let on_disk = if self.external_change.is_changed() {
+                    " (changed on disk)"
+                } else {
+                    ""
+                };
+                Some(StatusPart {
+                    text: format!("{filename}{modified}{on_disk}"),
+                    attr: A_BOLD,
+                    color_pair: Some(3),
+                })
+            }
+            StatusSegment::Lines => Some(StatusPart::new(
+                format!("{} lines", self.document.lines().len()),
+                A_NORMAL,
+            )),
+            StatusSegment::Position => {
+                let column = self.scroll.get_display_width_from_bytes(
+                    &self.document.lines()[self.cursor.y],
+                    self.cursor.x,
+                );
+                Some(StatusPart::new(
+                    format!("{}:{}", self.cursor.y + 1, column + 1),
+                    A_NORMAL,
+                ))
+            }
+            StatusSegment::Percent => {
+                let percent = (self.cursor.y + 1) * 100 / self.document.lines().len().max(1);
+                Some(StatusPart::new(format!("{percent}%"), A_DIM))
+            }
+            StatusSegment::Page => {
+                let (page, count) = self.current_page();
+                (count > 1).then(|| StatusPart::new(format!("page {page}/{count}"), A_DIM))
+            }
+            StatusSegment::Format => Some(StatusPart::new(
+                format!("{} UTF-8", self.document.line_ending.name()),
+                A_DIM,
+            )),
+            StatusSegment::Matches => {
+                let total = self.search.results.len();
+                if !self.search.mode || total == 0 {
+                    return None;
+                }
+                let text = match self.search.current_match_index {
+                    Some(index) => format!("match {}/{total}", index + 1),
+                    None => format!("{total} matches"),
+                };
+                Some(StatusPart::new(text, A_NORMAL))
+            }
+            StatusSegment::Branch => self
+                .git_branch()
+                .map(|branch| StatusPart::new(format!("⎇ {branch}"), A_DIM)),
+            StatusSegment::Undo => self
+                .undo_redo
+                .depth_indicator()
+                .map(|depth| StatusPart::new(depth, A_DIM)),
+            StatusSegment::Macro => self
+                .macros
+                .is_recording()
+                .then(|| StatusPart::new("recording macro".to_string(), A_BOLD)),
+            StatusSegment::Timer => self.task_timer.running.as_ref().map(|running| {
+                StatusPart::new(
+                    format!(
+                        "timer {}",
+                        crate::task_timer::format_spent(running.elapsed())
+                    ),
+                    A_BOLD,
+                )
+            }),
+            StatusSegment::Pomodoro => self
+                .pomodoro
+                .status()
+                .map(|status| StatusPart::new(status, A_BOLD)),
+            StatusSegment::Autosave => self.autosave.last_saved_at.map(|saved_at| {
+                StatusPart::new(format!("autosaved {}", saved_at.format("%H:%M:%S")), A_DIM)
+            }),
+        }
+    }
+
+    /// Draws the segments on the first row from the left, each after the
+    /// previous one, and returns the column where they end.
+    pub(super) fn draw_status_segments(&mut self, window: &impl Screen) -> usize {
+        let mut column = 0;
+        for segment in self.status_segments() {
+            let Some(part) = self.status_part(segment) else {
+                continue;
+            };
+            if column > 0 {
+                if !self.monochrome {
+                    window.color_set(STATUS_COLOR_PAIR);
+                }
+                window.mvaddstr(0, column as i32, SEPARATOR);
+                if !self.monochrome {
+                    window.color_set(1);
+                }
+                column += SEPARATOR.width();
+            }
+            let color_pair = part.color_pair.filter(|_| !self.monochrome);
+            if let Some(pair) = color_pair {
+                window.color_set(pair);
+            }
+            window.attron(part.attr);
+            window.mvaddstr(0, column as i32, &part.text);
+            window.attroff(part.attr);
+            if color_pair.is_some() {
+                window.color_set(1);
+            }
+            column += part.text.width();
+        }
+        column
+    }
+}
@@ -0,0 +1,331 @@
+use crate::document::LineBuffer;
+use crate::editor::Editor;
+use crate::editor::fold::DELIMITER;
+use crate::editor::indent::{is_list_item, leading_spaces, ordered_list_number};
+use crate::editor::word::{CharType, WordClasses};
+
+/// A position in the buffer as `(x, y)`.
+pub type Position = (usize, usize);
+
+/// A stretch of the buffer from one position to another.
+pub type Span = (Position, Position);
+
+/// What `ExpandSelection` grows the selection to, smallest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Word,
+    Sentence,
+    ListItem,
+    Page,
+    Buffer,
+}
+
+impl Unit {
+    pub fn name(self) -> &'static str {
+        match self {
+            Unit::Word => "word",
+            Unit::Sentence => "sentence",
+            Unit::ListItem => "list item",
+            Unit::Page => "page",
+            Unit::Buffer => "buffer",
+        }
+    }
+}
+
+/// Where the text of `line` starts after its indentation, list marker,
+/// checkbox or heading hashes.
+pub fn content_start(line: &str) -> usize {
+    let mut start = leading_spaces(line);
+    if let Some((_, _, end)) = ordered_list_number(line) {
+        start = end + 2;
+    } else if is_list_item(line) {
+        start += 2;
+    } else {
+        let hashes = line[start..].bytes().take_while(|&b| b == b'#').count();
+        if hashes > 0 && line[start + hashes..].starts_with(' ') {
+            start += hashes + 1;
+        }
+    }
+    for checkbox in ["[ ] ", "[x] ", "[X] "] {
+        if line[start..].starts_with(checkbox) {
+            start += checkbox.len();
+        }
+    }
+    start
+}
+
+/// The word at byte `x` of `line`, or the one that ends there.
+pub fn word_span(line: &str, x: usize, classes: &WordClasses) -> Option<(usize, usize)> {
+    let at = line[x..].chars().next();
+    let before = line[..x].chars().next_back();
+    let is_word =
+        |ch: Option<char>| ch.is_some_and(|ch| classes.char_type(ch) != CharType::Whitespace);
+    if is_word(at) {
+        let same_word = before
+            .zip(at)
+            .is_some_and(|(before, at)| classes.char_type(before) == classes.char_type(at));
+        let start = if same_word {
+            classes.word_start(line, x)
+        } else {
+            x
+        };
+        Some((start, classes.word_end(line, x)))
+    } else if is_word(before) {
+        Some((classes.word_start(line, x), x))
+    } else {
+        None
+    }
+}
+
+/// The sentence of `line` that byte `x` is in, or the one that follows when
+/// `x` is between two. A sentence ends with `.`, `!` or `?` before a space.
+pub fn sentence_span(line: &str, x: usize) -> Option<(usize, usize)> {
+    let content = content_start(line).min(line.len());
+    let mut sentences = Vec::new();
+    let mut start = None;
+    let mut chars = line[content..].char_indices().peekable();
+    while let Some((offset, ch)) = chars.next() {
+        let idx = content + offset;
+        if start.is_none() && !ch.is_whitespace() {
+            start = Some(idx);
+        }
+        let ends = matches!(ch, '.' | '!' | '?')
+            && chars.peek().is_none_or(|&(_, next)| next.is_whitespace());
+        if let (true, Some(from)) = (ends, start) {
+            sentences.push((from, idx + ch.len_utf8()));
+            start = None;
+        }
+    }
+    if let Some(from) = start {
+        sentences.push((from, line.trim_end().len()));
+    }
+    sentences
+        .iter()
+        .find(|&&(_, end)| x < end)
+        .or(sentences.last())
+        .copied()
+}
+
+/// The list item that line `y` belongs to: the line itself when it is an
+/// item, otherwise the nearest item above whose subtree reaches down to it.
+pub fn enclosing_list_item(lines: &LineBuffer, y: usize) -> Option<usize> {
+    if is_list_item(lines.get(y)?) {
+        return Some(y);
+    }
+    let mut item = (0..y).rev().find(|&above| is_list_item(&lines[above]));
+    while let Some(start) = item {
+        if subtree_end(lines, start) >= y {
+            return Some(start);
+        }
+        item = parent_list_item(lines, start);
+    }
+    None
+}
+
+/// The last line of the subtree of the list item on line `y`: the lines
+/// after it that are indented deeper, with the blank lines between them.
+pub fn subtree_end(lines: &LineBuffer, y: usize) -> usize {
+    let indent = leading_spaces(&lines[y]);
+    let mut end = y;
+    for (below, line) in lines.iter().enumerate().skip(y + 1) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if leading_spaces(line) <= indent {
+            break;
+        }
+        end = below;
+    }
+    end
+}
+
+/// The item above line `y` that the list item on it is nested under.
+pub fn parent_list_item(lines: &LineBuffer, y: usize) -> Option<usize> {
+    let indent = leading_spaces(&lines[y]);
+    (0..y).rev().find_map(|above| {
+        let line = &lines[above];
+        if line.trim().is_empty() || leading_spaces(line) >= indent {
+            return None;
+        }
+        Some(is_list_item(line).then_some(above))
+    })?
+}
+
+/// Lines `start` to `end` whole, ending at the start of the line after them
+/// so that copying them keeps the line break.
+fn whole_lines(lines: &LineBuffer, start: usize, end: usize) -> Span {
+    if end + 1 < lines.len() {
+        ((0, start), (0, end + 1))
+    } else {
+        ((0, start), (lines[end].len(), end))
+    }
+}
+
+/// The units around `(x, y)`, smallest first, each holding the one before.
+pub fn units_around(
+    lines: &LineBuffer,
+    x: usize,
+    y: usize,
+    classes: &WordClasses,
+) -> Vec<(Unit, Span)> {
+    let mut units = Vec::new();
+    let line = &lines[y];
+    if let Some((start, end)) = word_span(line, x, classes) {
+        units.push((Unit::Word, ((start, y), (end, y))));
+    }
+    if let Some((start, end)) = sentence_span(line, x) {
+        units.push((Unit::Sentence, ((start, y), (end, y))));
+    }
+    let mut item = enclosing_list_item(lines, y);
+    while let Some(start) = item {
+        units.push((
+            Unit::ListItem,
+            whole_lines(lines, start, subtree_end(lines, start)),
+        ));
+        item = parent_list_item(lines, start);
+    }
+    let page_start = lines
+        .range(..y)
+        .rposition(|line| line == DELIMITER)
+        .map_or(0, |delimiter| delimiter + 1);
+    let page_end = lines
+        .range(y..)
+        .position(|line| line == DELIMITER)
+        .map_or(lines.len(), |offset| y + offset);
+    if (page_start > 0 || page_end < lines.len()) && page_end > page_start {
+        units.push((Unit::Page, whole_lines(lines, page_start, page_end - 1)));
+    }
+    let last = lines.len() - 1;
+    units.push((Unit::Buffer, ((0, 0), (lines[last].len(), last))));
+    units
+}
+
+// Positions in buffer order
+fn order((x, y): Position) -> (usize, usize) {
+    (y, x)
+}
+
+fn contains(outer: Span, inner: Span) -> bool {
+    order(outer.0) <= order(inner.0) && order(inner.1) <= order(outer.1)
+}
+
+/// The selections that `ExpandSelection` grew from, for `ShrinkSelection` to
+/// go back to, while the selection is still the one it made.
+#[derive(Debug, Default)]
+pub struct SelectionExpansion {
+    // Marker and cursor before each expansion
+    history: Vec<(Option<Position>, Position)>,
+    // Marker and cursor after the last expansion, and the document revision
+    last: Option<(Span, u64)>,
+}
+
+impl Editor {
+    fn current_span(&self) -> Span {
+        let cursor = self.cursor_pos();
+        self.selection
+            .get_selection_range(&self.cursor)
+            .unwrap_or((cursor, cursor))
+    }
+
+    /// Whether the selection is still the one the last expansion made.
+    fn is_expansion_current(&self) -> bool {
+        self.selection_expansion
+            .last
+            .is_some_and(|((marker, cursor), revision)| {
+                self.selection.marker_pos == Some(marker)
+                    && self.cursor_pos() == cursor
+                    && self.document.revision() == revision
+            })
+    }
+
+    /// Where the units are looked for: where the cursor was before the first
+    /// expansion, so that each one holds the last. A selection of whole lines
+    /// is looked into from the end of its last line.
+    fn expansion_anchor(&self, (start, end): Span) -> Position {
+        if let Some(&(_, cursor)) = self.selection_expansion.history.first() {
+            return cursor;
+        }
+        let cursor = self.cursor_pos();
+        if cursor == end && end.0 == 0 && end.1 > start.1 {
+            let y = end.1 - 1;
+            return (self.document.lines()[y].len(), y);
+        }
+        cursor
+    }
+
+    fn select_span(&mut self, (start, end): Span) {
+        self.selection.rectangle = false;
+        self.selection.marker_pos = Some(start);
+        self.set_cursor_pos(end.0, end.1);
+        self.cursor.desired_x = self
+            .scroll
+            .get_display_width_from_bytes(&self.document.lines()[end.1], end.0);
+        self.selection_expansion.last = Some(((start, end), self.document.revision()));
+    }
+
+    /// Grows the selection to the next larger unit around the cursor: word,
+    /// sentence, list item with its children, the item it is nested under,
+    /// page and buffer.
+    pub fn expand_selection(&mut self) {
+        self.clipboard.last_action_was_kill = false;
+        if !self.is_expansion_current() {
+            self.selection_expansion = SelectionExpansion::default();
+        }
+        let current = self.current_span();
+        let (x, y) = self.expansion_anchor(current);
+        let units = units_around(self.document.lines(), x, y, &self.word_classes());
+        let Some((unit, span)) = units
+            .into_iter()
+            .find(|&(_, span)| span != current && contains(span, current))
+        else {
+            self.set_message("The whole buffer is selected.");
+            return;
+        };
+        let before = (self.selection.marker_pos, self.cursor_pos());
+        self.selection_expansion.history.push(before);
+        self.select_span(span);
+        self.set_message(&format!("Selected {}.", unit.name()));
+    }
+
+    /// Takes the selection back to what it was before the last expansion, or
+    /// without one to the largest unit inside it.
+    pub fn shrink_selection(&mut self) {
+        self.clipboard.last_action_was_kill = false;
+        if !self.is_expansion_current() {
+            self.selection_expansion = SelectionExpansion::default();
+        }
+        if let Some((marker, cursor)) = self.selection_expansion.history.pop() {
+            match marker {
+                Some(marker) => self.select_span((marker, cursor)),
+                None => {
+                    self.selection.clear_marker();
+                    self.set_cursor_pos(cursor.0, cursor.1);
+                    self.selection_expansion = SelectionExpansion::default();
+                }
+            }
+            self.set_message("Selection shrunk.");
+            return;
+        }
+        if !self.selection.is_selection_active() {
+            self.set_message("No selection to shrink.");
+            return;
+        }
+        let current = self.current_span();
+        let (x, y) = self.expansion_anchor(current);
+        let units = units_around(self.document.lines(), x, y, &self.word_classes());
+        match units
+            .into_iter()
+            .rev()
+            .find(|&(_, span)| span != current && contains(current, span))
+        {
+            Some((unit, span)) => {
+                self.select_span(span);
+                self.set_message(&format!("Selected {}.", unit.name()));
+            }
+            None => {
+                self.selection.clear_marker();
+                self.set_message("Selection cleared.");
+            }
+        }
+    }
+}
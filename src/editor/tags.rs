@@ -0,0 +1,350 @@
+use crate::document::{ActionDiff, LineBuffer};
+use crate::editor::agenda::truncate_to_width;
+use crate::editor::{Editor, EditorMode};
+use crate::screen::Screen;
+use pancurses::{A_BOLD, A_REVERSE, Input};
+use std::collections::BTreeMap;
+
+const MARGIN: usize = 2;
+
+fn is_tag_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '/')
+}
+
+/// The `#tags` of `line` as byte ranges with the tag without its `#`. A tag
+/// starts a line or follows a space or an opening bracket, and is not all
+/// digits, so headings, URL fragments and `#1` are not tags.
+pub fn tags_in_line(line: &str) -> Vec<(usize, usize, &str)> {
+    let mut tags = Vec::new();
+    let mut previous = None;
+    for (start, c) in line.char_indices() {
+        let starts_tag =
+            c == '#' && previous.is_none_or(|p: char| p.is_whitespace() || matches!(p, '(' | '['));
+        previous = Some(c);
+        if !starts_tag {
+            continue;
+        }
+        let name_start = start + 1;
+        let name_end = line[name_start..]
+            .find(|c| !is_tag_char(c))
+            .map_or(line.len(), |offset| name_start + offset);
+        let name = line[name_start..name_end].trim_end_matches(['-', '/']);
+        if name.chars().any(|c| !c.is_ascii_digit()) {
+            tags.push((start, name_start + name.len(), name));
+        }
+    }
+    tags
+}
+
+/// The tags of each line of a document and how many lines each tag is on,
+/// kept up to date edit by edit.
+#[derive(Debug, Default, Clone)]
+pub struct TagIndex {
+    lines: Vec<Vec<String>>,
+    counts: BTreeMap<String, usize>,
+}
+
+impl TagIndex {
+    pub fn build(lines: &LineBuffer) -> Self {
+        let mut index = TagIndex::default();
+        index.splice(lines, 0, 0, lines.len());
+        index
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Tags with the number of lines they are on, most used first.
+    pub fn counts(&self) -> Vec<(String, usize)> {
+        let mut counts: Vec<(String, usize)> = self
+            .counts
+            .iter()
+            .map(|(tag, &count)| (tag.clone(), count))
+            .collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        counts
+    }
+
+    /// The lines `tag` is on, in order.
+    pub fn lines_with(&self, tag: &str) -> Vec<usize> {
+        (0..self.lines.len())
+            .filter(|&y| self.lines[y].iter().any(|found| found == tag))
+            .collect()
+    }
+
+    /// Follows `diff` as just applied to `lines`, forwards or, for undo,
+    /// backwards. Only the lines it touched are read again.
+    pub fn apply_diff(&mut self, diff: &ActionDiff, is_undo: bool, lines: &LineBuffer) {
+        let (removed, inserted) = if is_undo {
+            (&diff.new, &diff.old)
+        } else {
+            (&diff.old, &diff.new)
+        };
+        self.splice(
+            lines,
+            diff.start_y,
+            removed.len().max(1),
+            inserted.len().max(1),
+        );
+        if self.lines.len() != lines.len() {
+            // A change the line counts above don't describe, e.g. a line appended at the end
+            *self = TagIndex::build(lines);
+        }
+    }
+
+    /// Replaces the entries of `removed` lines from `start` with those of
+    /// `inserted` lines of `lines`.
+    fn splice(&mut self, lines: &LineBuffer, start: usize, removed: usize, inserted: usize) {
+        let start = start.min(self.lines.len());
+        let removed = removed.min(self.lines.len() - start);
+        let inserted = inserted.min(lines.len().saturating_sub(start));
+        let new: Vec<Vec<String>> = lines
+            .range(start..start + inserted)
+            .map(|line| {
+                let mut tags: Vec<String> = tags_in_line(line)
+                    .into_iter()
+                    .map(|(_, _, tag)| tag.to_string())
+                    .collect();
+                tags.sort();
+                tags.dedup();
+                tags
+            })
+            .collect();
+        for tag in new.iter().flatten() {
+            *self.counts.entry(tag.clone()).or_default() += 1;
+        }
+        for tag in self
+            .lines
+            .splice(start..start + removed, new)
+            .flatten()
+            .collect::<Vec<_>>()
+        {
+            if let Some(count) = self.counts.get_mut(&tag) {
+                *count -= 1;
+                if *count == 0 {
+                    self.counts.remove(&tag);
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct TagList {
+    pub entries: Vec<(String, usize)>,
+    pub query: String,
+    // Indices into `entries` of the tags containing the query
+    pub matches: Vec<usize>,
+    pub selected: usize,
+    scroll_offset: usize,
+}
+
+impl TagList {
+    fn update_matches(&mut self) {
+        let query = self.query.to_lowercase();
+        self.matches = (0..self.entries.len())
+            .filter(|&i| self.entries[i].0.to_lowercase().contains(&query))
+            .collect();
+        self.selected = 0;
+        self.scroll_offset = 0;
+    }
+
+    pub fn selected_tag(&self) -> Option<&str> {
+        self.matches
+            .get(self.selected)
+            .map(|&index| self.entries[index].0.as_str())
+    }
+}
+
+impl Editor {
+    /// The tag index of the buffer, built the first time it is needed.
+    pub fn tag_index(&mut self) -> &TagIndex {
+        let lines = self.document.lines();
+        let index = self.tag_index.get_or_insert_with(|| TagIndex::build(lines));
+        // The lines were replaced without an edit, e.g. by following a file
+        if index.line_count() != lines.len() {
+            *index = TagIndex::build(lines);
+        }
+        index
+    }
+
+    /// Keeps the tag index, once built, in step with an edit.
+    pub(super) fn update_tag_index(&mut self, diff: &ActionDiff) {
+        if let Some(index) = &mut self.tag_index {
+            index.apply_diff(diff, false, self.document.lines());
+        }
+    }
+
+    /// Keeps the tag index, once built, in step with an undo or redo, whose
+    /// diffs are now on top of the other stack.
+    pub(super) fn update_tag_index_for_undo_redo(&mut self, is_undo: bool) {
+        let Some(index) = &mut self.tag_index else {
+            return;
+        };
+        let group = if is_undo {
+            self.undo_redo.redo_stack.last()
+        } else {
+            self.undo_redo.undo_stack.last()
+        };
+        match group.map(Vec::as_slice) {
+            Some([diff]) => index.apply_diff(diff, is_undo, self.document.lines()),
+            // Only the lines after the last of several diffs are at hand
+            _ => *index = TagIndex::build(self.document.lines()),
+        }
+    }
+
+    /// Lists the tags of the buffer with their counts.
+    pub fn list_tags(&mut self) {
+        let entries = self.tag_index().counts();
+        if entries.is_empty() {
+            self.set_message("No #tags in this buffer.");
+            return;
+        }
+        self.tag_list = TagList {
+            entries,
+            ..TagList::default()
+        };
+        self.tag_list.update_matches();
+        self.mode = EditorMode::Tags;
+    }
+
+    pub fn handle_tags_input(&mut self, key: Input) {
+        let count = self.tag_list.matches.len();
+        match key {
+            Input::KeyUp | Input::Character('\x10') if count > 0 => {
+                self.tag_list.selected = (self.tag_list.selected + count - 1) % count;
+            }
+            Input::KeyDown | Input::Character('\x0e') if count > 0 => {
+                self.tag_list.selected = (self.tag_list.selected + 1) % count;
+            }
+            Input::Character('\n') | Input::Character('\r') => {
+                let Some(tag) = self.tag_list.selected_tag().map(str::to_string) else {
+                    return;
+                };
+                self.close_tag_list();
+                self.tag_filter = Some(tag);
+                self.go_to_tagged_line(true);
+            }
+            // Esc or Ctrl-G
+            Input::Character('\x1b') | Input::Character('\x07') => self.close_tag_list(),
+            Input::KeyBackspace | Input::Character('\x7f') | Input::Character('\x08') => {
+                self.tag_list.query.pop();
+                self.tag_list.update_matches();
+            }
+            Input::Character(c) if !c.is_control() => {
+                self.tag_list.query.push(c);
+                self.tag_list.update_matches();
+            }
+            _ => {}
+        }
+    }
+
+    fn close_tag_list(&mut self) {
+        self.tag_list = TagList::default();
+        self.mode = EditorMode::Normal;
+    }
+
+    /// Moves to the next (or previous) line with the tag picked in the tag
+    /// list, wrapping around the buffer.
+    pub fn go_to_tagged_line(&mut self, forward: bool) {
+        let Some(tag) = self.tag_filter.clone() else {
+            self.set_message("No tag to follow. Pick one with ListTags.");
+            return;
+        };
+        let lines = self.tag_index().lines_with(&tag);
+        let y = self.cursor.y;
+        let target = if forward {
+            lines.iter().find(|&&line| line > y).or(lines.first())
+        } else {
+            lines.iter().rev().find(|&&line| line < y).or(lines.last())
+        };
+        let Some(&target) = target else {
+            self.set_message(&format!("No lines tagged #{tag}."));
+            return;
+        };
+        let origin = self.cursor_pos();
+        let x = tags_in_line(&self.document.lines()[target])
+            .into_iter()
+            .find(|&(_, _, found)| found == tag)
+            .map_or(0, |(start, _, _)| start);
+        self.set_cursor_pos(x, target);
+        self.cursor.desired_x = self
+            .scroll
+            .get_display_width_from_bytes(&self.document.lines()[target], x);
+        self.snap_to_fold_header();
+        self.record_jump(origin);
+        let position = lines.iter().position(|&line| line == target).unwrap_or(0);
+        self.set_message(&format!("#{tag} {}/{}", position + 1, lines.len()));
+    }
+
+    pub fn draw_tags(&mut self, window: &impl Screen) {
+        let screen_rows = window.get_max_y() as usize;
+        let screen_cols = window.get_max_x() as usize;
+        if screen_rows <= MARGIN * 2 + 2 || screen_cols <= MARGIN * 2 + 2 {
+            return;
+        }
+        let (top, left) = (MARGIN, MARGIN);
+        let (bottom, right) = (screen_rows - MARGIN - 1, screen_cols - MARGIN - 1);
+        let inner_width = right - left - 1;
+
+        let visible_rows = bottom - top - 1;
+        let list = &mut self.tag_list;
+        if list.selected < list.scroll_offset {
+            list.scroll_offset = list.selected;
+        } else if list.selected >= list.scroll_offset + visible_rows {
+            list.scroll_offset = list.selected + 1 - visible_rows;
+        }
+
+        let blank = " ".repeat(inner_width);
+        for row in top + 1..bottom {
+            window.mvaddch(row as i32, left as i32, pancurses::ACS_VLINE());
+            window.mvaddstr(row as i32, (left + 1) as i32, &blank);
+            window.mvaddch(row as i32, right as i32, pancurses::ACS_VLINE());
+        }
+        for col in left + 1..right {
+            window.mvaddch(top as i32, col as i32, pancurses::ACS_HLINE());
+            window.mvaddch(bottom as i32, col as i32, pancurses::ACS_HLINE());
+        }
+        window.mvaddch(top as i32, left as i32, pancurses::ACS_ULCORNER());
+        window.mvaddch(top as i32, right as i32, pancurses::ACS_URCORNER());
+        window.mvaddch(bottom as i32, left as i32, pancurses::ACS_LLCORNER());
+        window.mvaddch(bottom as i32, right as i32, pancurses::ACS_LRCORNER());
+        let title = format!(" Tags: {} ", list.query);
+        window.attron(A_BOLD);
+        window.mvaddstr(
+            top as i32,
+            (left + 2) as i32,
+            truncate_to_width(&title, inner_width.saturating_sub(2)),
+        );
+        window.attroff(A_BOLD);
+
+        let count_width = list
+            .entries
+            .iter()
+            .map(|(_, count)| count.to_string().len())
+            .max()
+            .unwrap_or(1);
+        for (offset, &index) in list
+            .matches
+            .iter()
+            .skip(list.scroll_offset)
+            .take(visible_rows)
+            .enumerate()
+        {
+            let (tag, count) = &list.entries[index];
+            let attr = if list.scroll_offset + offset == list.selected {
+                A_REVERSE
+            } else {
+                0
+            };
+            window.attron(attr);
+            window.mvaddstr(
+                (top + 1 + offset) as i32,
+                (left + 1) as i32,
+                truncate_to_width(&format!(" {count:>count_width$}  #{tag}"), inner_width),
+            );
+            window.attroff(attr);
+        }
+    }
+}
@@ -1,8 +1,10 @@
 // src/editor/task.rs
 
-use crate::document::ActionDiff;
+use crate::document::{ActionDiff, LineBuffer};
+use crate::editor::date_picker::parse_due;
 use crate::editor::fuzzy_search::FuzzySearch;
 use crate::editor::{Editor, EditorMode, LastActionType};
+use chrono::Local;
 use fuzzy_matcher::FuzzyMatcher;
 use fuzzy_matcher::skim::SkimMatcherV2;
 use once_cell::sync::Lazy;
@@ -10,6 +12,9 @@ use pancurses::Input;
 
 static MATCHER: Lazy<SkimMatcherV2> = Lazy::new(SkimMatcherV2::default);
 
+pub const ARCHIVE_COMMAND: &str = "/archive";
+const ARCHIVE_HEADING: &str = "## Archive";
+
 #[derive(Debug)]
 pub struct Task {
     pub tasks: Vec<(usize, String)>, // Store (original_line_index, content)
@@ -37,7 +42,96 @@ impl Task {
     }
 }
 
+/// `lines` with the checked tasks from line `from` on moved to the end of the
+/// `## Archive` section, which is added at the end of the file when missing.
+/// Each gets an `@archived(stamp)` annotation. Returns the number moved.
+pub fn archive_done_tasks(lines: &LineBuffer, from: usize, stamp: &str) -> (Vec<String>, usize) {
+    let heading = lines
+        .iter()
+        .position(|line| line.trim_end() == ARCHIVE_HEADING);
+    // The section runs up to the next heading of its level or above
+    let section = heading.map(|heading| {
+        let end = lines
+            .range(heading + 1..)
+            .position(|line| line.starts_with("# ") || line.starts_with("## "))
+            .map_or(lines.len(), |offset| heading + 1 + offset);
+        heading..end
+    });
+    let is_archived = |y: usize| section.as_ref().is_some_and(|section| section.contains(&y));
+
+    let mut kept = Vec::new();
+    let mut archived = Vec::new();
+    // Where the archived tasks go in `kept`
+    let mut insert_at = None;
+    for (y, line) in lines.iter().enumerate() {
+        if section.as_ref().is_some_and(|section| section.end == y) {
+            insert_at = Some(kept.len());
+        }
+        if y >= from && !is_archived(y) && line.trim_start().starts_with("- [x] ") {
+            let task = line.trim();
+            archived.push(format!("{task} @archived({stamp})"));
+        } else {
+            kept.push(line.clone());
+        }
+    }
+    if archived.is_empty() {
+        return (kept, 0);
+    }
+    let count = archived.len();
+
+    if heading.is_some() {
+        let mut at = insert_at.unwrap_or(kept.len());
+        // Before the blank lines that separate the section from the next
+        while at > 0 && kept[at - 1].trim().is_empty() {
+            at -= 1;
+        }
+        kept.splice(at..at, archived);
+    } else {
+        while kept.last().is_some_and(|line| line.trim().is_empty()) {
+            kept.pop();
+        }
+        if !kept.is_empty() {
+            kept.push(String::new());
+        }
+        kept.push(ARCHIVE_HEADING.to_string());
+        kept.extend(archived);
+    }
+    (kept, count)
+}
+
 impl Editor {
+    /// Moves the checked tasks below the cursor to the `## Archive` section,
+    /// as one undo step.
+    pub fn archive_done_tasks(&mut self) {
+        let lines = self.document.lines().clone();
+        self.archive_done_tasks_from(lines, self.cursor.y + 1, self.cursor_pos());
+    }
+
+    /// Removes the `/archive` command line and archives the checked tasks
+    /// that followed it, as one undo step.
+    pub(super) fn run_archive_command(&mut self) {
+        let command_y = self.cursor.y;
+        let mut lines = self.document.lines().clone();
+        lines.remove(command_y);
+        if lines.is_empty() {
+            lines.push(String::new());
+        }
+        let cursor_y = command_y.min(lines.len() - 1);
+        self.archive_done_tasks_from(lines, command_y, (0, cursor_y));
+    }
+
+    fn archive_done_tasks_from(&mut self, lines: LineBuffer, from: usize, cursor: (usize, usize)) {
+        let stamp = Local::now().format("%Y-%m-%d %H:%M").to_string();
+        let (lines, count) = archive_done_tasks(&lines, from, &stamp);
+        // Only lines after the cursor move, so it stays where it is
+        self.replace_lines(lines, cursor);
+        match count {
+            0 => self.set_message("No completed tasks below the cursor."),
+            1 => self.set_message("Archived 1 task."),
+            count => self.set_message(&format!("Archived {count} tasks.")),
+        }
+    }
+
     pub fn find_unchecked_tasks(&mut self) {
         self.task.tasks.clear();
         self.task.all_tasks.clear();
@@ -46,12 +140,18 @@ impl Editor {
         self.task.fuzzy_search.reset();
 
         let mut found_tasks = Vec::new();
-        for (i, line) in self.document.lines.iter().enumerate() {
-            if i > self.cursor_y && line.trim_start().starts_with("- [ ] ") {
+        for (i, line) in self.document.lines().iter().enumerate() {
+            if i > self.cursor.y && line.trim_start().starts_with("- [ ] ") {
                 found_tasks.push((i, line.clone())); // Store (index, content)
             }
         }
 
+        // Soonest due first; stable, so undated tasks keep their order at the end
+        found_tasks.sort_by_key(|(_, line)| {
+            let due = parse_due(line);
+            (due.is_none(), due)
+        });
+
         if !found_tasks.is_empty() {
             self.task.all_tasks = found_tasks.clone();
             self.task.tasks = found_tasks;
@@ -138,19 +238,19 @@ impl Editor {
                     if let Some((original_line_idx, task_content)) =
                         self.task.tasks.get(selected_idx).cloned()
                     {
-                        let current_cursor_y = self.cursor_y;
-                        let current_cursor_x = self.cursor_x;
+                        let current_cursor_y = self.cursor.y;
+                        let current_cursor_x = self.cursor.x;
 
                         // Remove the task from its original position
-                        self.cursor_x = 0;
-                        self.cursor_y = original_line_idx;
+                        self.cursor.x = 0;
+                        self.cursor.y = original_line_idx;
                         {
                             // kill line
-                            let y = self.cursor_y;
+                            let y = self.cursor.y;
                             let x = 0;
-                            let task_line_len = self.document.lines[y].len();
+                            let task_line_len = self.document.lines()[y].len();
 
-                            let current_line = self.document.lines[y].clone();
+                            let current_line = self.document.lines()[y].clone();
                             let killed_text = current_line[x..].to_string();
                             self.clipboard.kill_buffer.push_str(&killed_text);
                             self.commit(
@@ -158,12 +258,12 @@ impl Editor {
                                 &ActionDiff {
                                     cursor_start_x: current_cursor_x,
                                     cursor_start_y: current_cursor_y,
-                                    cursor_end_x: self.cursor_x,
-                                    cursor_end_y: self.cursor_y,
-                                    start_x: self.cursor_x,
-                                    start_y: self.cursor_y,
+                                    cursor_end_x: self.cursor.x,
+                                    cursor_end_y: self.cursor.y,
+                                    start_x: self.cursor.x,
+                                    start_y: self.cursor.y,
                                     end_x: task_line_len,
-                                    end_y: self.cursor_y,
+                                    end_y: self.cursor.y,
                                     new: vec![],
                                     old: vec![killed_text],
                                 },
@@ -174,33 +274,33 @@ impl Editor {
                         self.commit(
                             LastActionType::Ammend,
                             &ActionDiff {
-                                cursor_start_x: self.cursor_x,
-                                cursor_start_y: self.cursor_y,
-                                cursor_end_x: self.document.lines[self.cursor_y - 1].len(),
-                                cursor_end_y: self.cursor_y - 1,
-                                start_x: self.document.lines[self.cursor_y - 1].len(),
-                                start_y: self.cursor_y - 1,
-                                end_x: self.cursor_x,
-                                end_y: self.cursor_y,
+                                cursor_start_x: self.cursor.x,
+                                cursor_start_y: self.cursor.y,
+                                cursor_end_x: self.document.lines()[self.cursor.y - 1].len(),
+                                cursor_end_y: self.cursor.y - 1,
+                                start_x: self.document.lines()[self.cursor.y - 1].len(),
+                                start_y: self.cursor.y - 1,
+                                end_x: self.cursor.x,
+                                end_y: self.cursor.y,
                                 new: vec![],
                                 old: vec!["".to_string(), "".to_string()],
                             },
                         );
 
                         // Insert the task at the current cursor position
-                        self.cursor_y = current_cursor_y;
-                        self.cursor_x = current_cursor_x;
+                        self.cursor.y = current_cursor_y;
+                        self.cursor.x = current_cursor_x;
                         self.commit(
                             LastActionType::Ammend,
                             &ActionDiff {
                                 cursor_start_x: 0,
-                                cursor_start_y: self.cursor_y,
+                                cursor_start_y: self.cursor.y,
                                 cursor_end_x: 0,
-                                cursor_end_y: self.cursor_y + 1,
+                                cursor_end_y: self.cursor.y + 1,
                                 start_x: 0,
-                                start_y: self.cursor_y,
+                                start_y: self.cursor.y,
                                 end_x: 0,
-                                end_y: self.cursor_y + 1,
+                                end_y: self.cursor.y + 1,
                                 new: vec![task_content, "".to_string()],
                                 old: vec![],
                             },
@@ -243,10 +343,10 @@ impl Editor {
                         self.commit(
                             LastActionType::ToggleComment,
                             &ActionDiff {
-                                cursor_start_x: self.cursor_x,
-                                cursor_start_y: self.cursor_y,
-                                cursor_end_x: self.cursor_x,
-                                cursor_end_y: self.cursor_y,
+                                cursor_start_x: self.cursor.x,
+                                cursor_start_y: self.cursor.y,
+                                cursor_end_x: self.cursor.x,
+                                cursor_end_y: self.cursor.y,
                                 start_x: 0,
                                 start_y: original_line_idx,
                                 end_x: "# ".len(),
@@ -0,0 +1,32 @@
+use crate::config::{self, BUILTIN_THEMES, Colors};
+use crate::editor::Editor;
+
+impl Editor {
+    /// Switches to the theme after the current one, the built-in themes
+    /// first and then those in `~/.dmacs/themes`.
+    pub fn cycle_theme(&mut self) {
+        let names = config::theme_names();
+        let current = self.settings.theme.as_deref().unwrap_or(BUILTIN_THEMES[0]);
+        let next = names
+            .iter()
+            .position(|name| name == current)
+            .map_or(0, |index| (index + 1) % names.len());
+        let name = names[next].clone();
+        let Some(colors) = config::theme_colors(&name) else {
+            self.set_message(&format!("Could not load the theme {name}."));
+            return;
+        };
+        self.pending_colors = Some(colors);
+        if self.monochrome {
+            self.set_message(&format!("Theme {name} (this terminal shows no colors)."));
+        } else {
+            self.set_message(&format!("Theme {name}."));
+        }
+        self.settings.theme = Some(name);
+    }
+
+    /// The colors to switch the terminal to, once.
+    pub fn take_pending_colors(&mut self) -> Option<Colors> {
+        self.pending_colors.take()
+    }
+}
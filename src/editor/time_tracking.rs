@@ -0,0 +1,133 @@
+use crate::editor::Editor;
+use crate::task_timer::{self, RunningTimer};
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+#[derive(Default, Debug)]
+pub struct TaskTimer {
+    pub running: Option<RunningTimer>,
+    pub state_path: Option<PathBuf>,
+}
+
+impl TaskTimer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn persist(&self) {
+        if let Some(path) = &self.state_path {
+            task_timer::store(path, self.running.as_ref());
+        }
+    }
+}
+
+fn is_task_line(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    trimmed.starts_with("- [ ] ") || trimmed.starts_with("- [x] ")
+}
+
+impl Editor {
+    pub fn enable_task_timer_persistence(&mut self) {
+        self.enable_task_timer_persistence_with_base_dir(None);
+    }
+
+    /// Picks up a timer left running by a previous session and keeps future
+    /// changes on disk.
+    pub fn enable_task_timer_persistence_with_base_dir(&mut self, base_dir: Option<PathBuf>) {
+        let path = task_timer::timer_path(base_dir);
+        self.task_timer.running = path.as_ref().and_then(task_timer::load);
+        self.task_timer.state_path = path;
+    }
+
+    pub fn start_task_timer(&mut self) {
+        let line = self.document.lines()[self.cursor.y].clone();
+        if !is_task_line(&line) {
+            self.set_message("Not a task.");
+            return;
+        }
+        if let Some(running) = &self.task_timer.running {
+            if running.file_path != self.document.filename {
+                let file = running.file_path.as_deref().unwrap_or("[No Name]");
+                self.set_message(&format!("A timer is already running in {file}."));
+                return;
+            }
+            if running.task == line {
+                self.set_message("Timer already running on this task.");
+                return;
+            }
+            // Switching tasks books the time spent on the previous one
+            self.stop_task_timer();
+            if self.task_timer.running.is_some() {
+                return;
+            }
+        }
+
+        self.task_timer.running = Some(RunningTimer {
+            file_path: self.document.filename.clone(),
+            task: line,
+            line: self.cursor.y,
+            started_at: SystemTime::now(),
+        });
+        self.task_timer.persist();
+        self.set_message("Timer started.");
+    }
+
+    /// Stops the running timer and adds the elapsed time to the task's
+    /// `@spent(...)` annotation.
+    pub fn stop_task_timer(&mut self) {
+        let Some(running) = self.task_timer.running.clone() else {
+            self.set_message("No timer running.");
+            return;
+        };
+        if running.file_path != self.document.filename {
+            let file = running.file_path.as_deref().unwrap_or("[No Name]");
+            self.set_message(&format!("The timer is running in {file}."));
+            return;
+        }
+        let Some(y) = self.find_timed_task(&running) else {
+            self.set_message("Could not find the timed task.");
+            return;
+        };
+
+        let elapsed = running.elapsed();
+        let line = self.document.lines()[y].clone();
+        let (total, spent_str) = match task_timer::find_spent(&line) {
+            Some((start, end, previous)) => {
+                let total = previous + elapsed;
+                let annotation = format!("@spent({})", task_timer::format_spent(total));
+                self.replace_in_line(y, start, end, &annotation);
+                (total, task_timer::format_spent(elapsed))
+            }
+            None => {
+                let annotation = format!(" @spent({})", task_timer::format_spent(elapsed));
+                self.replace_in_line(y, line.len(), line.len(), &annotation);
+                (elapsed, task_timer::format_spent(elapsed))
+            }
+        };
+
+        self.task_timer.running = None;
+        self.task_timer.persist();
+        self.set_message(&format!(
+            "Timer stopped: {spent_str} (total {}).",
+            task_timer::format_spent(total)
+        ));
+    }
+
+    /// The timed task is looked up by its text first, since lines may have
+    /// moved since the timer was started.
+    fn find_timed_task(&self, running: &RunningTimer) -> Option<usize> {
+        let lines = self.document.lines();
+        let by_text = lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| **line == running.task)
+            .min_by_key(|(y, _)| y.abs_diff(running.line))
+            .map(|(y, _)| y);
+        by_text.or_else(|| {
+            lines
+                .get(running.line)
+                .filter(|line| is_task_line(line))
+                .map(|_| running.line)
+        })
+    }
+}
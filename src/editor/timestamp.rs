@@ -0,0 +1,34 @@
+use crate::editor::Editor;
+use chrono::{DateTime, Local};
+use std::fmt::Write;
+
+pub const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+pub const DEFAULT_TIME_FORMAT: &str = "%H:%M";
+pub const DEFAULT_DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// `now` in the strftime `format`, or `None` when the format has a specifier
+/// chrono doesn't know.
+pub fn format_timestamp(now: DateTime<Local>, format: &str) -> Option<String> {
+    let mut text = String::new();
+    write!(text, "{}", now.format(format)).ok()?;
+    Some(text)
+}
+
+impl Editor {
+    /// Inserts the current date and time at the cursor, in the
+    /// `datetime_format` setting.
+    pub fn insert_timestamp(&mut self) -> crate::error::Result<()> {
+        let format = self
+            .settings
+            .datetime_format
+            .as_deref()
+            .unwrap_or(DEFAULT_DATETIME_FORMAT);
+        match format_timestamp(Local::now(), format) {
+            Some(timestamp) => self.insert_text(&timestamp),
+            None => {
+                self.set_message(&format!("Invalid datetime_format: {format}"));
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use crate::editor::Editor;
+
+pub const DEFAULT_TODO_KEYWORDS: [&str; 4] = ["TODO", "DOING", "DONE", "WAIT"];
+
+/// Byte offset where a TODO keyword goes on a heading (`## `) or list (`- `)
+/// line, or `None` for other lines.
+pub fn keyword_position(line: &str) -> Option<usize> {
+    let indent = line.len() - line.trim_start().len();
+    let rest = &line[indent..];
+    let hashes = rest.len() - rest.trim_start_matches('#').len();
+    let marker = if hashes > 0 {
+        hashes
+    } else if rest.starts_with('-') {
+        1
+    } else {
+        return None;
+    };
+    rest[marker..]
+        .starts_with(' ')
+        .then_some(indent + marker + 1)
+}
+
+/// Finds the TODO keyword of `line`, returning its byte range and its index
+/// in `keywords`.
+pub fn find_keyword<S: AsRef<str>>(line: &str, keywords: &[S]) -> Option<(usize, usize, usize)> {
+    let start = keyword_position(line)?;
+    let rest = &line[start..];
+    keywords.iter().enumerate().find_map(|(index, keyword)| {
+        let keyword = keyword.as_ref();
+        let after = rest.strip_prefix(keyword)?;
+        (!keyword.is_empty() && (after.is_empty() || after.starts_with(' '))).then_some((
+            start,
+            start + keyword.len(),
+            index,
+        ))
+    })
+}
+
+impl Editor {
+    pub fn todo_keywords(&self) -> Vec<String> {
+        match &self.settings.todo_keywords {
+            Some(keywords) => keywords.clone(),
+            None => DEFAULT_TODO_KEYWORDS.map(String::from).to_vec(),
+        }
+    }
+
+    /// Advances the TODO keyword of the current line to the next configured
+    /// one; after the last keyword it is removed again.
+    pub fn cycle_todo_keyword(&mut self) {
+        let y = self.cursor.y;
+        let Some(line) = self.document.lines().get(y) else {
+            return;
+        };
+        let keywords = self.todo_keywords();
+        let Some(position) = keyword_position(line) else {
+            self.status_message = "TODO keywords go on heading or list lines.".to_string();
+            return;
+        };
+        if keywords.is_empty() {
+            return;
+        }
+
+        // Each press is its own undo step, like toggling a checkbox
+        self.undo_redo.begin_group();
+        match find_keyword(line, &keywords) {
+            Some((start, end, index)) if index + 1 < keywords.len() => {
+                self.replace_in_line(y, start, end, &keywords[index + 1]);
+            }
+            Some((start, end, _)) => {
+                // Drop the separating space along with the last keyword
+                let end = if line[end..].starts_with(' ') {
+                    end + 1
+                } else {
+                    end
+                };
+                self.replace_in_line(y, start, end, "");
+            }
+            None => {
+                let separator = if line.len() > position { " " } else { "" };
+                self.replace_in_line(
+                    y,
+                    position,
+                    position,
+                    &format!("{}{separator}", keywords[0]),
+                );
+            }
+        }
+        self.undo_redo.end_group();
+    }
+}
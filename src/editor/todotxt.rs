@@ -0,0 +1,73 @@
+use crate::document::ActionDiff;
+use crate::editor::{Editor, LastActionType};
+use crate::todotxt;
+
+pub const EXPORT_COMMAND: &str = "/export-todotxt";
+pub const IMPORT_COMMAND: &str = "/import-todotxt";
+
+impl Editor {
+    /// Runs a todo.txt command typed as the last line of a selection: the
+    /// selected lines above it are converted and the command line is removed.
+    pub(super) fn convert_selection_todotxt(&mut self, export: bool) {
+        let (cursor_x, command_y) = self.cursor_pos();
+        let Some((_, start_y)) = self.selection.marker_pos.filter(|&(_, y)| y < command_y) else {
+            self.set_message("Select the lines to convert, ending with the command line.");
+            return;
+        };
+
+        let converted: Vec<String> = self
+            .document
+            .lines()
+            .range(start_y..command_y)
+            .map(|line| {
+                let line_converted = if export {
+                    todotxt::to_todotxt(line)
+                } else {
+                    todotxt::from_todotxt(line)
+                };
+                line_converted.unwrap_or_else(|| line.clone())
+            })
+            .collect();
+        let old: Vec<String> = self
+            .document
+            .lines()
+            .range(start_y..=command_y)
+            .cloned()
+            .collect();
+        let count = converted.len();
+
+        self.selection.clear_marker();
+        self.commit(
+            LastActionType::Other,
+            &ActionDiff {
+                cursor_start_x: cursor_x,
+                cursor_start_y: command_y,
+                cursor_end_x: 0,
+                cursor_end_y: start_y,
+                start_x: 0,
+                start_y,
+                end_x: self.document.lines()[command_y].len(),
+                end_y: command_y,
+                new: vec![],
+                old,
+            },
+        );
+        self.commit(
+            LastActionType::Ammend,
+            &ActionDiff {
+                cursor_start_x: 0,
+                cursor_start_y: start_y,
+                cursor_end_x: 0,
+                cursor_end_y: start_y,
+                start_x: 0,
+                start_y,
+                end_x: converted.last().map_or(0, |line| line.len()),
+                end_y: start_y + count - 1,
+                new: converted,
+                old: vec![],
+            },
+        );
+        let format = if export { "todo.txt" } else { "checkbox tasks" };
+        self.set_message(&format!("Converted {count} lines to {format}."));
+    }
+}
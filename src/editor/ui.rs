@@ -1,13 +1,155 @@
 use crate::editor::Editor;
-use pancurses::{A_BOLD, A_DIM, A_REVERSE, Window};
+use crate::editor::agenda::truncate_to_width;
+use crate::editor::auto_pair::find_matching_bracket;
+use crate::editor::date_picker::parse_due;
+use crate::editor::decoration::{DecorationContext, trailing_whitespace_start};
+use crate::editor::scroll;
+use crate::screen::Screen;
+use chrono::Local;
+use pancurses::{
+    A_ATTRIBUTES, A_BOLD, A_COLOR, A_DIM, A_ITALIC, A_NORMAL, A_REVERSE, A_UNDERLINE, COLOR_PAIR,
+    chtype,
+};
 use std::cmp::min;
 use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 const TAB_STOP: usize = 4;
 pub const STATUS_BAR_HEIGHT: usize = 2;
+/// Color pairs that TODO keywords cycle through, initialized by the terminal.
+pub const KEYWORD_COLOR_PAIRS: [i16; 4] = [4, 5, 6, 7];
+/// Color pair for indentation guides and the column ruler.
+pub const GUIDE_COLOR_PAIR: i16 = 8;
+/// Color pairs of the theme colors, initialized by the terminal. Selection
+/// and search matches are drawn reversed.
+pub const SELECTION_COLOR_PAIR: i16 = 9;
+pub const SEARCH_COLOR_PAIR: i16 = 10;
+pub const STATUS_COLOR_PAIR: i16 = 11;
+pub const DIM_COLOR_PAIR: i16 = 12;
+pub const GUTTER_COLOR_PAIR: i16 = 13;
+pub const ERROR_COLOR_PAIR: i16 = 14;
+/// Color pair for the due date of overdue tasks.
+pub const OVERDUE_COLOR_PAIR: i16 = ERROR_COLOR_PAIR;
+/// Column of the ruler that `ToggleRuler` shows when `ruler_column` is not set.
+pub const DEFAULT_RULER_COLUMN: usize = 80;
+// Pairs 1, 3..=8 and 11..=14 have a variant this much higher with the cursor
+// line background
+const CURSOR_LINE_PAIR_OFFSET: i16 = 16;
+// Spaces per nesting level, as inserted by Indent
+const INDENT_WIDTH: usize = 2;
+
+/// Attributes used for each kind of highlighted text.
+///
+/// In color mode some distinctions are carried by color pairs alone, so the
+/// monochrome set gives every kind its own attribute combination instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RenderAttributes {
+    pub comment: chtype,
+    pub checked: chtype,
+    pub unchecked: chtype,
+    pub selection: chtype,
+    pub search_match: chtype,
+    pub last_change: chtype,
+    pub todo_keyword: chtype,
+    pub matching_bracket: chtype,
+    pub guide: chtype,
+    pub misspelled: chtype,
+    pub placeholder: chtype,
+    pub overdue: chtype,
+    pub whitespace: chtype,
+    pub trailing_whitespace: chtype,
+}
+
+impl RenderAttributes {
+    pub fn new(monochrome: bool) -> Self {
+        if monochrome {
+            Self {
+                comment: A_UNDERLINE,
+                checked: A_DIM | A_ITALIC,
+                unchecked: A_BOLD,
+                selection: A_REVERSE,
+                search_match: A_REVERSE | A_UNDERLINE,
+                last_change: A_BOLD | A_UNDERLINE,
+                todo_keyword: A_BOLD | A_REVERSE,
+                matching_bracket: A_BOLD | A_UNDERLINE,
+                guide: A_DIM,
+                misspelled: A_ITALIC | A_UNDERLINE,
+                placeholder: A_DIM | A_UNDERLINE,
+                overdue: A_BOLD | A_ITALIC,
+                whitespace: A_DIM,
+                trailing_whitespace: A_REVERSE | A_ITALIC,
+            }
+        } else {
+            Self {
+                comment: A_DIM,
+                checked: A_DIM,
+                unchecked: A_BOLD,
+                selection: A_REVERSE,
+                search_match: A_REVERSE,
+                last_change: A_UNDERLINE,
+                todo_keyword: A_BOLD,
+                matching_bracket: A_BOLD | A_UNDERLINE,
+                guide: A_DIM,
+                misspelled: A_UNDERLINE,
+                placeholder: A_DIM,
+                overdue: A_BOLD,
+                whitespace: A_DIM,
+                trailing_whitespace: A_DIM | A_REVERSE,
+            }
+        }
+    }
+}
+
+/// The variant of color pair `pair` drawn on the cursor line and column.
+/// Text in the default pair gets the regular foreground.
+pub fn cursor_line_pair(pair: i16) -> i16 {
+    match pair {
+        0 | 1 => 1 + CURSOR_LINE_PAIR_OFFSET,
+        3..=GUIDE_COLOR_PAIR | STATUS_COLOR_PAIR..=ERROR_COLOR_PAIR => {
+            pair + CURSOR_LINE_PAIR_OFFSET
+        }
+        _ => pair,
+    }
+}
+
+/// Display columns in the leading whitespace of `line` where indentation
+/// guides are drawn, one for each nesting level.
+pub fn indent_guide_columns(line: &str) -> Vec<usize> {
+    let mut width = 0;
+    for ch in line.chars() {
+        match ch {
+            ' ' => width += 1,
+            '\t' => width += TAB_STOP - width % TAB_STOP,
+            _ => break,
+        }
+    }
+    (0..width).step_by(INDENT_WIDTH).collect()
+}
+
+/// Whether `(byte_idx, line_index)` falls inside a half-open `(start, end)` range.
+fn range_contains(
+    range: Option<((usize, usize), (usize, usize))>,
+    line_index: usize,
+    byte_idx: usize,
+) -> bool {
+    let Some(((start_x, start_y), (end_x, end_y))) = range else {
+        return false;
+    };
+    if line_index < start_y || line_index > end_y {
+        return false;
+    }
+    if line_index == start_y && line_index == end_y {
+        byte_idx >= start_x && byte_idx < end_x
+    } else if line_index == start_y {
+        byte_idx >= start_x
+    } else if line_index == end_y {
+        byte_idx < end_x
+    } else {
+        true
+    }
+}
 
 impl Editor {
-    fn draw_fuzzy_search(&mut self, window: &Window) {
+    fn draw_fuzzy_search(&mut self, window: &impl Screen) {
         let screen_rows = window.get_max_y() as usize;
 
         window.erase();
@@ -107,7 +249,84 @@ impl Editor {
         (prefix_byte_len, prefix_display_width)
     }
 
-    pub fn draw(&mut self, window: &Window) {
+    /// Draws one cell of an indentation guide or the ruler, then goes back to
+    /// the colors of the line.
+    fn draw_guide(
+        &self,
+        window: &impl Screen,
+        row: usize,
+        column: usize,
+        attributes: &RenderAttributes,
+        line_pair: i16,
+    ) {
+        window.attron(attributes.guide);
+        if !self.monochrome {
+            window.color_set(GUIDE_COLOR_PAIR);
+        }
+        window.mvaddch(row as i32, column as i32, pancurses::ACS_VLINE());
+        window.attroff(attributes.guide);
+        if !self.monochrome {
+            window.color_set(line_pair);
+        }
+    }
+
+    /// Shows or hides the column ruler, at `ruler_column` or column 80.
+    pub fn toggle_ruler(&mut self) {
+        match self.settings.ruler_column.filter(|&column| column > 0) {
+            Some(column) => {
+                self.settings.ruler_column = None;
+                self.hidden_ruler_column = Some(column);
+                self.set_message("Ruler off.");
+            }
+            None => {
+                let column = self.hidden_ruler_column.unwrap_or(DEFAULT_RULER_COLUMN);
+                self.settings.ruler_column = Some(column);
+                self.set_message(&format!("Ruler at column {column}."));
+            }
+        }
+    }
+
+    pub fn toggle_whitespace(&mut self) {
+        self.settings.show_whitespace = !self.settings.show_whitespace;
+        self.set_message(if self.settings.show_whitespace {
+            "Whitespace shown."
+        } else {
+            "Whitespace hidden."
+        });
+    }
+
+    /// Draws the `… N lines` placeholder of a folded section at `column` and
+    /// returns its width.
+    fn draw_fold_marker(
+        &self,
+        window: &impl Screen,
+        row: usize,
+        column: usize,
+        count: usize,
+        screen_cols: usize,
+    ) -> usize {
+        let marker = format!(" … {count} line{} ", if count == 1 { "" } else { "s" });
+        if column + marker.width() > screen_cols {
+            return 0;
+        }
+        window.attron(A_DIM | A_REVERSE);
+        window.mvaddstr(row as i32, column as i32, &marker);
+        window.attroff(A_DIM | A_REVERSE);
+        marker.width()
+    }
+
+    /// Gives the drawn cells in `columns` of `row` the cursor line background,
+    /// keeping their text, attributes and foreground color.
+    fn tint_cells(window: &impl Screen, row: usize, columns: std::ops::Range<usize>) {
+        for x in columns {
+            let cell = window.mvinch(row as i32, x as i32);
+            let pair = ((cell & A_COLOR) / COLOR_PAIR(1)) as i16;
+            let attributes = cell & A_ATTRIBUTES & !A_COLOR;
+            window.mvchgat(row as i32, x as i32, 1, attributes, cursor_line_pair(pair));
+        }
+    }
+
+    pub fn draw(&mut self, window: &impl Screen) {
         let screen_rows = window.get_max_y() as usize;
         let screen_cols = window.get_max_x() as usize;
 
@@ -115,12 +334,48 @@ impl Editor {
             self.draw_fuzzy_search(window);
             return;
         }
+        if self.mode == crate::editor::EditorMode::ProjectSearch {
+            self.draw_project_search(window);
+            return;
+        }
+        if self.mode == crate::editor::EditorMode::KillRing {
+            self.draw_kill_ring(window);
+            return;
+        }
+        if self.mode == crate::editor::EditorMode::FileBrowser {
+            self.draw_file_browser(window);
+            return;
+        }
+        if self.mode == crate::editor::EditorMode::RecentFiles {
+            self.draw_recent_files(window);
+            return;
+        }
+        if self.mode == crate::editor::EditorMode::Bookmarks {
+            self.draw_bookmarks(window);
+            return;
+        }
 
         self.scroll();
 
         window.erase();
 
-        let selection_range = self.selection.get_selection_range(self.cursor_pos());
+        let selection_range = self.selection.get_selection_range(&self.cursor);
+        let rectangle = self.rectangle_bounds();
+        let attributes = RenderAttributes::new(self.monochrome);
+        let todo_keywords = self.todo_keywords();
+        let today = Local::now().date_naive();
+        let matching_brackets = if self.settings.highlight_matching_bracket {
+            find_matching_bracket(self.document.lines(), self.cursor.x, self.cursor.y)
+        } else {
+            None
+        };
+
+        let gutter_width = self.gutter_width();
+        let ruler_column = self
+            .settings
+            .ruler_column
+            .filter(|&column| column > 0)
+            .map(|column| column + gutter_width);
 
         let document_start_row = STATUS_BAR_HEIGHT; // Default for normal mode
         let mut document_end_row = screen_rows;
@@ -128,6 +383,12 @@ impl Editor {
         if self.mode == crate::editor::EditorMode::TaskSelection {
             let task_ui_height = self.task_ui_height();
             let start_task_row = screen_rows.saturating_sub(task_ui_height);
+            // Due dates go in a column of their own when any task has one
+            let show_due = self
+                .task
+                .tasks
+                .iter()
+                .any(|(_, task_content)| parse_due(task_content).is_some());
 
             for (i, (_original_idx, task_content)) in self.task.tasks.iter().enumerate() {
                 let display_row = start_task_row + i - self.task.task_display_offset;
@@ -141,7 +402,21 @@ impl Editor {
                 if Some(i) == self.task.selected_task_index {
                     window.attron(A_REVERSE);
                 }
-                window.mvaddstr(display_row as i32, 0, task_content);
+                if show_due {
+                    let due = parse_due(task_content);
+                    let due_attr = if due.is_some_and(|due| due < today) {
+                        attributes.overdue
+                    } else {
+                        A_DIM
+                    };
+                    let due_label = due.map_or(String::new(), |due| due.to_string());
+                    window.attron(due_attr);
+                    window.mvaddstr(display_row as i32, 0, format!("{due_label:<10}  "));
+                    window.attroff(due_attr);
+                    window.addstr(task_content);
+                } else {
+                    window.mvaddstr(display_row as i32, 0, task_content);
+                }
                 if Some(i) == self.task.selected_task_index {
                     window.attroff(A_REVERSE);
                 }
@@ -156,37 +431,61 @@ impl Editor {
             document_end_row = start_task_row.saturating_sub(1);
         }
 
-        // Draw text
-        for (index, line) in self.document.lines.iter().enumerate() {
-            if index < self.scroll.row_offset {
+        // Draw text, leaving out folded lines
+        let context = DecorationContext {
+            attributes,
+            today,
+            todo_keywords: &todo_keywords,
+            monochrome: self.monochrome,
+        };
+        let hidden_ranges = self.hidden_ranges();
+        let mut next_row = document_start_row;
+        for (index, line) in self.document.lines().iter().enumerate() {
+            if index < self.scroll.row_offset
+                || hidden_ranges.iter().any(|range| range.contains(&index))
+            {
                 continue;
             }
-            let row = index - self.scroll.row_offset;
-            if row >= document_end_row.saturating_sub(document_start_row) {
+            if next_row >= document_end_row {
                 break;
             }
-            let row = row + document_start_row;
+            let row = next_row;
+            next_row += 1;
+            let folded_lines = hidden_ranges
+                .iter()
+                .find(|range| range.start == index + 1)
+                .map(|range| range.len());
+            if gutter_width > 0
+                && let Some(mark) = self.hunk_mark(index)
+            {
+                self.draw_hunk_mark(window, row, mark);
+            }
 
-            let is_comment = line.trim_start().starts_with('#');
-            let is_unchecked = Self::is_unchecked_checkbox(line);
-            let is_checked = Self::is_checked_checkbox(line);
+            let decorations = self.decorate_line(index, &context);
+            let trailing_start = if self.settings.show_whitespace {
+                trailing_whitespace_start(line)
+            } else {
+                line.len()
+            };
+            let line_attr = decorations.line_attr;
+            let line_pair = decorations.line_color_pair.unwrap_or(1);
+            let guide_columns = if self.settings.indent_guides {
+                indent_guide_columns(line)
+            } else {
+                Vec::new()
+            };
 
-            if is_comment || is_checked {
-                window.attron(A_DIM);
-            }
-            if is_unchecked {
-                window.color_set(3);
-                window.attron(A_BOLD);
+            window.attron(line_attr);
+            if let Some(pair) = decorations.line_color_pair {
+                window.color_set(pair);
             }
 
-            if Self::is_separator_line(line) {
-                if is_comment {
-                    window.attroff(A_DIM);
-                }
+            if decorations.rule {
+                window.attroff(line_attr);
 
                 let replacement_char_chtype = pancurses::ACS_HLINE();
-                for i in 0..screen_cols {
-                    if i < 3 {
+                for i in gutter_width..screen_cols {
+                    if i < gutter_width + 3 {
                         window.mvaddch(row as i32, i as i32, replacement_char_chtype);
                     } else {
                         window.attron(A_DIM);
@@ -194,18 +493,21 @@ impl Editor {
                         window.attroff(A_DIM);
                     }
                 }
+                if let Some(count) = folded_lines {
+                    self.draw_fold_marker(window, row, gutter_width + 4, count, screen_cols);
+                }
                 continue;
             }
 
             let (prefix_byte_len, _) = self.get_prefix_info(line);
-            let content_col_offset = if index == self.cursor_y {
+            let content_col_offset = if index == self.cursor.y {
                 self.scroll.col_offset
             } else {
                 0
             };
 
             let mut current_display_x = 0;
-            let mut screen_x = 0;
+            let mut screen_x = gutter_width;
 
             let (mut content_start_byte_in_content, display_pos) = if content_col_offset > 0 {
                 self.scroll
@@ -233,6 +535,22 @@ impl Editor {
                     break;
                 }
 
+                if let Some(replacement) = decorations.replacement_at(byte_idx) {
+                    if byte_idx == replacement.start {
+                        let width = replacement.label.width();
+                        if screen_x + width > screen_cols {
+                            break;
+                        }
+                        window.attron(replacement.attr);
+                        window.mvaddstr(row as i32, screen_x as i32, &replacement.label);
+                        window.attroff(replacement.attr);
+                        window.attron(line_attr);
+                        screen_x += width;
+                        current_display_x += width;
+                    }
+                    continue;
+                }
+
                 let is_in_prefix = byte_idx < prefix_byte_len;
                 let mut should_draw = false;
 
@@ -269,43 +587,71 @@ impl Editor {
                     }
 
                     let is_highlighted = self.search.mode
-                        && self.search.results.iter().any(|&(r, c)| {
-                            r == index && byte_idx >= c && byte_idx < c + self.search.query.len()
+                        && self.search.results.iter().any(|&(r, c, len)| {
+                            r == index && byte_idx >= c && byte_idx < c + len
                         });
-                    let is_selected =
-                        if let Some(((sel_start_x, sel_start_y), (sel_end_x, sel_end_y))) =
-                            selection_range
-                        {
-                            if index >= sel_start_y && index <= sel_end_y {
-                                if index == sel_start_y && index == sel_end_y {
-                                    byte_idx >= sel_start_x && byte_idx < sel_end_x
-                                } else if index == sel_start_y {
-                                    byte_idx >= sel_start_x
-                                } else if index == sel_end_y {
-                                    byte_idx < sel_end_x
-                                } else {
-                                    true
-                                }
-                            } else {
-                                false
-                            }
-                        } else {
-                            false
-                        };
-
-                    if is_highlighted || is_selected {
-                        window.attron(A_REVERSE);
+                    let is_selected = match rectangle {
+                        Some(rect) => rect.contains(index, current_display_x),
+                        None => range_contains(selection_range, index, byte_idx),
+                    };
+                    let is_last_change = range_contains(self.last_change, index, byte_idx);
+                    let is_matching_bracket = matching_brackets
+                        .is_some_and(|(a, b)| a == (byte_idx, index) || b == (byte_idx, index));
+                    let span = decorations.span_at(byte_idx);
+                    let cell_pair = if is_highlighted {
+                        Some(SEARCH_COLOR_PAIR)
+                    } else if is_selected {
+                        Some(SELECTION_COLOR_PAIR)
+                    } else {
+                        span.and_then(|span| span.color_pair)
+                    }
+                    .filter(|_| !self.monochrome);
+
+                    let highlight_attr = if is_highlighted {
+                        attributes.search_match
+                    } else if is_selected {
+                        attributes.selection
+                    } else if is_last_change {
+                        attributes.last_change
+                    } else if is_matching_bracket {
+                        attributes.matching_bracket
+                    } else if let Some(span) = span {
+                        span.attr
+                    } else if ch == '\t' && self.settings.show_whitespace {
+                        attributes.whitespace
+                    } else {
+                        A_NORMAL
+                    };
+                    window.attron(highlight_attr);
+                    if let Some(pair) = cell_pair {
+                        window.color_set(pair);
                     }
 
-                    let display_string = if ch == '\t' {
+                    let display_string = if ch == '\t' && self.settings.show_whitespace {
+                        format!("→{}", " ".repeat(char_width - 1))
+                    } else if ch == '\t' {
                         " ".repeat(char_width)
+                    } else if ch == ' ' && byte_idx >= trailing_start {
+                        "·".to_string()
                     } else {
                         ch.to_string()
                     };
-                    window.mvaddstr(row as i32, screen_x as i32, &display_string);
+                    if is_in_prefix
+                        && highlight_attr == A_NORMAL
+                        && guide_columns.contains(&current_display_x)
+                    {
+                        self.draw_guide(window, row, screen_x, &attributes, line_pair);
+                        window.mvaddstr(row as i32, screen_x as i32 + 1, &display_string[1..]);
+                    } else {
+                        window.mvaddstr(row as i32, screen_x as i32, &display_string);
+                    }
 
-                    if is_highlighted || is_selected {
-                        window.attroff(A_REVERSE);
+                    if highlight_attr != A_NORMAL {
+                        window.attroff(highlight_attr);
+                        window.attron(line_attr);
+                    }
+                    if cell_pair.is_some() {
+                        window.color_set(line_pair);
                     }
 
                     screen_x += char_width;
@@ -319,23 +665,27 @@ impl Editor {
                 current_display_x += char_width_for_display;
             }
 
-            if is_comment || is_checked {
-                window.attroff(A_DIM);
-            }
-            if is_unchecked {
-                window.attroff(A_BOLD);
+            window.attroff(line_attr);
+            if decorations.line_color_pair.is_some() {
                 window.color_set(1);
             }
+            if let Some(count) = folded_lines {
+                screen_x += self.draw_fold_marker(window, row, screen_x, count, screen_cols);
+            }
+            if let Some(column) = ruler_column
+                && screen_x <= column
+                && column < screen_cols
+            {
+                self.draw_guide(window, row, column, &attributes, 1);
+            }
+        }
+        if let Some(column) = ruler_column
+            && column < screen_cols
+        {
+            for row in next_row..document_end_row {
+                self.draw_guide(window, row, column, &attributes, 1);
+            }
         }
-
-        let filename_display = self.document.filename.as_deref().unwrap_or("[No Name]");
-        let modified_indicator = if self.document.is_dirty() { "*" } else { "" };
-        let filename_and_modified = format!("{filename_display}{modified_indicator}");
-        window.color_set(3);
-        window.attron(A_BOLD);
-        window.mvaddstr(0, 0, &filename_and_modified);
-        window.attroff(A_BOLD);
-        window.color_set(1);
 
         window.attron(A_DIM);
         for i in 0..screen_cols {
@@ -346,38 +696,43 @@ impl Editor {
             );
         }
         window.attroff(A_DIM);
-
-        let mut current_col = 0;
-        for ch in filename_and_modified.chars() {
-            current_col += ch.width().unwrap_or(0);
+        if self.settings.hint_bar {
+            self.draw_hint_bar(window, STATUS_BAR_HEIGHT - 1, screen_cols);
         }
 
-        let line_count_str = format!(" - {} lines", self.document.lines.len());
-        window.mvaddstr(0, current_col as i32, &line_count_str);
-        for ch in line_count_str.chars() {
-            current_col += ch.width().unwrap_or(0);
-        }
+        let current_col = self.draw_status_segments(window);
 
-        if !self.status_message.is_empty() {
+        if let Some(alert) = &self.pomodoro.alert {
+            let alert_start_col = screen_cols.saturating_sub(alert.width());
+            window.attron(A_REVERSE | A_BOLD);
+            window.mvaddstr(0, alert_start_col as i32, alert);
+            window.attroff(A_REVERSE | A_BOLD);
+        } else if !self.status_message.is_empty() {
             let mut message_display_width = 0;
             for ch in self.status_message.chars() {
                 message_display_width += ch.width().unwrap_or(0);
             }
             let message_start_col = screen_cols.saturating_sub(message_display_width);
             window.mvaddstr(0, message_start_col as i32, &self.status_message);
+        } else if let Some(footnote) = self.footnote_at_cursor() {
+            let width = screen_cols.saturating_sub(current_col + 1);
+            let footnote = truncate_to_width(&footnote, width);
+            window.attron(A_DIM);
+            window.mvaddstr(0, (screen_cols - footnote.width()) as i32, footnote);
+            window.attroff(A_DIM);
         }
 
         let (prefix_byte_len, prefix_display_width) =
-            self.get_prefix_info(&self.document.lines[self.cursor_y]);
+            self.get_prefix_info(&self.document.lines()[self.cursor.y]);
         let display_cursor_x = self
             .scroll
-            .get_display_width_from_bytes(&self.document.lines[self.cursor_y], self.cursor_x);
+            .get_display_width_from_bytes(&self.document.lines()[self.cursor.y], self.cursor.x);
 
-        let final_cursor_x = if self.cursor_x < prefix_byte_len {
+        let final_cursor_x = if self.cursor.x < prefix_byte_len {
             display_cursor_x
         } else {
             let content_display_cursor_x = display_cursor_x.saturating_sub(prefix_display_width);
-            let content_line = &self.document.lines[self.cursor_y][prefix_byte_len..];
+            let content_line = &self.document.lines()[self.cursor.y][prefix_byte_len..];
             let (_, display_pos) = self
                 .scroll
                 .get_byte_pos_from_display_width(content_line, self.scroll.col_offset);
@@ -400,45 +755,108 @@ impl Editor {
 
             prefix_display_width + ellipsis_width + cursor_pos_in_scrolled_content
         };
+        let final_cursor_x = gutter_width + final_cursor_x;
+
+        let cursor_screen_row = self
+            .visible_row(self.cursor.y)
+            .saturating_sub(self.visible_row(self.scroll.row_offset))
+            + document_start_row;
+        if !self.monochrome {
+            if self.settings.highlight_cursor_line {
+                Self::tint_cells(window, cursor_screen_row, 0..screen_cols);
+            }
+            if self.settings.highlight_cursor_column && final_cursor_x < screen_cols {
+                for row in document_start_row..document_end_row {
+                    if row != cursor_screen_row {
+                        Self::tint_cells(window, row, final_cursor_x..final_cursor_x + 1);
+                    }
+                }
+            }
+        }
 
-        window.mv(
-            (self.cursor_y - self.scroll.row_offset + document_start_row) as i32,
-            final_cursor_x as i32,
-        );
+        if self.mode == crate::editor::EditorMode::DatePicker {
+            self.draw_date_picker(window);
+        }
+        if self.mode == crate::editor::EditorMode::Agenda {
+            self.draw_agenda(window);
+        }
+        if self.mode == crate::editor::EditorMode::Outline {
+            self.draw_outline(window);
+        }
+        if self.mode == crate::editor::EditorMode::Tags {
+            self.draw_tags(window);
+        }
+        if self.mode == crate::editor::EditorMode::CommandPalette {
+            self.draw_command_palette(window);
+        }
+        if self.mode == crate::editor::EditorMode::BackupBrowser {
+            self.draw_backup_browser(window);
+        }
+        if self.mode == crate::editor::EditorMode::SpellingSuggestions {
+            self.draw_spelling_suggestions(window, cursor_screen_row, final_cursor_x);
+        }
+        if self.mode == crate::editor::EditorMode::Completion {
+            self.draw_completion(window, cursor_screen_row, final_cursor_x);
+        }
+
+        if let Some(kind) = self.minibuffer.prompt {
+            self.draw_minibuffer(window, kind.label(), screen_cols);
+        } else {
+            window.mv(cursor_screen_row as i32, final_cursor_x as i32);
+        }
         window.refresh();
     }
 
-    pub fn scroll(&mut self) {
-        let mut visible_content_height = self.scroll.screen_rows.saturating_sub(STATUS_BAR_HEIGHT);
+    /// Draws the prompt over the status line and leaves the cursor in the input.
+    fn draw_minibuffer(&self, window: &impl Screen, label: &str, screen_cols: usize) {
+        window.mv(0, 0);
+        window.clrtoeol();
+        window.attron(A_BOLD);
+        window.mvaddstr(0, 0, label);
+        window.attroff(A_BOLD);
+        window.mvaddstr(0, label.width() as i32, &self.minibuffer.input);
+        let cursor_col = label.width() + self.minibuffer.input[..self.minibuffer.cursor].width();
+        window.mv(0, min(cursor_col, screen_cols.saturating_sub(1)) as i32);
+    }
 
+    /// Rows the buffer is shown in, above the status bar and the task list.
+    pub fn text_rows(&self) -> usize {
+        let rows = self.scroll.screen_rows.saturating_sub(STATUS_BAR_HEIGHT);
         if self.mode == crate::editor::EditorMode::TaskSelection {
-            let task_ui_height = self.task_ui_height();
-            visible_content_height = self
-                .scroll
-                .screen_rows
-                .saturating_sub(STATUS_BAR_HEIGHT)
-                .saturating_sub(task_ui_height);
+            rows.saturating_sub(self.task_ui_height())
+        } else {
+            rows
         }
+    }
 
-        // Vertical scroll
-        let scroll_margin = visible_content_height / 4;
-        if self.cursor_y < self.scroll.row_offset + scroll_margin {
-            self.scroll.row_offset = self.cursor_y.saturating_sub(scroll_margin);
-        } else if self.cursor_y >= self.scroll.row_offset + visible_content_height - scroll_margin {
-            self.scroll.row_offset = self.cursor_y.saturating_sub(visible_content_height - scroll_margin);
+    pub fn scroll(&mut self) {
+        let visible_content_height = self.text_rows();
+
+        // Vertical scroll, counted in visible rows so that folded lines take no space
+        self.reveal_cursor_line();
+        let scroll_margin = scroll::vertical_margin(visible_content_height);
+        let cursor_row = self.visible_row(self.cursor.y);
+        let offset_row = self.visible_row(self.scroll.row_offset);
+        if cursor_row < offset_row + scroll_margin {
+            self.scroll.row_offset =
+                self.line_at_visible_row(cursor_row.saturating_sub(scroll_margin));
+        } else if cursor_row >= offset_row + visible_content_height - scroll_margin {
+            self.scroll.row_offset = self.line_at_visible_row(
+                cursor_row.saturating_sub(visible_content_height - scroll_margin),
+            );
         }
 
         // Horizontal scroll
         let scroll_margin = 10;
-        let screen_width = self.scroll.screen_cols;
-        let current_line = &self.document.lines[self.cursor_y];
+        let screen_width = self.scroll.screen_cols.saturating_sub(self.gutter_width());
+        let current_line = &self.document.lines()[self.cursor.y];
 
         let (prefix_byte_len, prefix_display_width) = self.get_prefix_info(current_line);
         let display_cursor_x = self
             .scroll
-            .get_display_width_from_bytes(current_line, self.cursor_x);
+            .get_display_width_from_bytes(current_line, self.cursor.x);
 
-        if self.cursor_x < prefix_byte_len {
+        if self.cursor.x < prefix_byte_len {
             self.scroll.col_offset = 0;
         } else {
             let content_display_cursor_x = display_cursor_x.saturating_sub(prefix_display_width);
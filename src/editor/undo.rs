@@ -1,6 +1,10 @@
+use crate::clock::{Clock, SystemClock};
 use crate::document::{ActionDiff, Document};
+use crate::editor::cursor::Cursor;
 use crate::editor::scroll::Scroll;
+use chrono::{DateTime, Local};
 use log::debug;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 #[derive(PartialEq, Debug, Clone, Copy)]
@@ -16,12 +20,32 @@ pub enum LastActionType {
     Other,
 }
 
+/// How long editing must pause before a due snapshot is taken.
+pub const SNAPSHOT_IDLE_DELAY: Duration = Duration::from_secs(2);
+
+/// A restore point in the undo history: the document as it was with `depth`
+/// undo steps on the stack.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    pub depth: usize,
+    pub taken_at: DateTime<Local>,
+}
+
 pub struct UndoRedo {
     pub undo_stack: Vec<Vec<ActionDiff>>,
     pub redo_stack: Vec<Vec<ActionDiff>>,
     last_action_time: Option<Instant>,
     last_action_type: LastActionType,
     undo_debounce_threshold: Duration,
+    clock: Arc<dyn Clock>,
+    // Ordered by depth
+    pub snapshots: Vec<Snapshot>,
+    // When the first edit since the last snapshot was made
+    edited_since: Option<Instant>,
+    // Above zero while a compound operation such as macro playback is
+    // running; groups begun inside it join the outermost one
+    group_depth: usize,
+    group_started: bool,
 }
 
 impl Default for UndoRedo {
@@ -38,6 +62,11 @@ impl UndoRedo {
             last_action_time: None,
             last_action_type: LastActionType::None,
             undo_debounce_threshold: Duration::from_millis(500),
+            clock: Arc::new(SystemClock),
+            snapshots: Vec::new(),
+            edited_since: None,
+            group_depth: 0,
+            group_started: false,
         }
     }
 
@@ -45,6 +74,48 @@ impl UndoRedo {
         self.undo_debounce_threshold = Duration::from_millis(threshold_ms);
     }
 
+    /// Replaces the clock edits are timed with to decide whether they join
+    /// the previous undo step.
+    pub fn set_clock(&mut self, clock: impl Clock + 'static) {
+        self.clock = Arc::new(clock);
+    }
+
+    /// Times edits with the same clock and threshold as `other`, for a buffer
+    /// taking the place of the one `other` belongs to.
+    pub fn inherit_timing(&mut self, other: &UndoRedo) {
+        self.clock = Arc::clone(&other.clock);
+        self.undo_debounce_threshold = other.undo_debounce_threshold;
+    }
+
+    /// Makes every action recorded until `end_group` part of one undo step.
+    pub fn begin_group(&mut self) {
+        if self.group_depth == 0 {
+            self.group_started = false;
+        }
+        self.group_depth += 1;
+    }
+
+    pub fn end_group(&mut self) {
+        self.group_depth = self.group_depth.saturating_sub(1);
+        if self.group_depth == 0 {
+            // Keep later typing from joining the group
+            self.last_action_type = LastActionType::Other;
+        }
+    }
+
+    /// Undo steps and redo steps available, as `u:14 r:2`, once anything has
+    /// been edited.
+    pub fn depth_indicator(&self) -> Option<String> {
+        if self.undo_stack.is_empty() && self.redo_stack.is_empty() {
+            return None;
+        }
+        Some(format!(
+            "u:{} r:{}",
+            self.undo_stack.len(),
+            self.redo_stack.len()
+        ))
+    }
+
     pub fn record_action(&mut self, action_type: LastActionType, action_diff: &ActionDiff) {
         self.save_state_for_undo(action_type);
         if let Some(last_transaction) = self.undo_stack.last_mut() {
@@ -53,13 +124,16 @@ impl UndoRedo {
     }
 
     fn save_state_for_undo(&mut self, current_action_type: LastActionType) {
-        let now = Instant::now();
+        let now = self.clock.now();
         debug!(
             "save_state_for_undo: current_action_type={:?}, last_action_type={:?}, undo_debounce_threshold={:?}",
             current_action_type, self.last_action_type, self.undo_debounce_threshold
         );
 
-        let should_start_new_group = if self.last_action_time.is_none() {
+        let should_start_new_group = if self.group_depth > 0 {
+            debug!("save_state_for_undo: Grouped action");
+            !std::mem::replace(&mut self.group_started, true)
+        } else if self.last_action_time.is_none() {
             debug!("save_state_for_undo: First action ever");
             true
         } else if current_action_type == LastActionType::Ammend {
@@ -69,7 +143,8 @@ impl UndoRedo {
             debug!("save_state_for_undo: ToggleCheckbox always starts a new group");
             true
         } else {
-            let time_since_last_action = now.duration_since(self.last_action_time.unwrap());
+            let time_since_last_action =
+                now.saturating_duration_since(self.last_action_time.unwrap());
             debug!("save_state_for_undo: time_since_last_action={time_since_last_action:?}");
             self.last_action_type != current_action_type
                 || time_since_last_action >= self.undo_debounce_threshold
@@ -77,39 +152,85 @@ impl UndoRedo {
 
         if should_start_new_group {
             debug!("save_state_for_undo: Pushing new undo group");
+            // Snapshots of states only reachable by redo are gone with it
+            let depth = self.undo_stack.len();
+            self.snapshots.retain(|snapshot| snapshot.depth <= depth);
             self.undo_stack.push(Vec::new());
             self.redo_stack.clear();
         }
         self.last_action_time = Some(now);
+        self.edited_since.get_or_insert(now);
         if current_action_type != LastActionType::Ammend {
             self.last_action_type = current_action_type;
         }
     }
 
+    /// Takes a snapshot once `interval` has passed since the first edit after
+    /// the last one and editing has paused. Returns whether it took one.
+    pub fn snapshot_tick(&mut self, interval: Duration) -> bool {
+        let (Some(edited_since), Some(last_action_time)) =
+            (self.edited_since, self.last_action_time)
+        else {
+            return false;
+        };
+        let now = self.clock.now();
+        if self.group_depth > 0
+            || now.saturating_duration_since(last_action_time) < SNAPSHOT_IDLE_DELAY
+            || now.saturating_duration_since(edited_since) < interval
+        {
+            return false;
+        }
+        self.take_snapshot();
+        true
+    }
+
+    /// Marks the current state as a snapshot.
+    pub fn take_snapshot(&mut self) {
+        let depth = self.undo_stack.len();
+        self.snapshots.retain(|snapshot| snapshot.depth != depth);
+        let at = self
+            .snapshots
+            .partition_point(|snapshot| snapshot.depth < depth);
+        self.snapshots.insert(
+            at,
+            Snapshot {
+                depth,
+                taken_at: Local::now(),
+            },
+        );
+        self.edited_since = None;
+        // Later typing starts an undo step of its own
+        self.last_action_type = LastActionType::Other;
+    }
+
+    /// The latest snapshot before the current state.
+    pub fn previous_snapshot(&self) -> Option<&Snapshot> {
+        let depth = self.undo_stack.len();
+        self.snapshots
+            .iter()
+            .rev()
+            .find(|snapshot| snapshot.depth < depth)
+    }
+
     pub fn undo(
         &mut self,
         document: &mut Document,
-        cursor_x: &mut usize,
-        cursor_y: &mut usize,
-        desired_cursor_x: &mut usize,
+        cursor: &mut Cursor,
         scroll: &Scroll,
     ) -> Result<(), String> {
         debug!(
-            "Undo called. Current undo_stack length: {}. Current document: {:?}",
-            self.undo_stack.len(),
-            document.lines
+            "Undo called. Current undo_stack length: {}.",
+            self.undo_stack.len()
         );
         if let Some(mut actions_to_undo) = self.undo_stack.pop() {
             let mut actions_for_redo = Vec::new();
-            let mut current_cursor_x = *cursor_x;
-            let mut current_cursor_y = *cursor_y;
+            let mut current = cursor.pos();
 
             actions_to_undo.reverse();
             for action_diff in actions_to_undo.iter() {
                 match document.apply_action_diff(action_diff, true) {
-                    Ok((new_x, new_y)) => {
-                        current_cursor_x = new_x;
-                        current_cursor_y = new_y;
+                    Ok(position) => {
+                        current = position;
                         actions_for_redo.push(action_diff.clone());
                     }
                     Err(e) => {
@@ -121,12 +242,14 @@ impl UndoRedo {
             }
             actions_for_redo.reverse();
             self.redo_stack.push(actions_for_redo);
+            // The next edit must not join a group that is no longer on top
+            self.last_action_type = LastActionType::None;
+            self.group_started = false;
 
-            *cursor_x = current_cursor_x;
-            *cursor_y = current_cursor_y;
-            *desired_cursor_x =
-                scroll.get_display_width_from_bytes(&document.lines[*cursor_y], *cursor_x);
-            debug!("Document after undo: {:?}", document.lines);
+            (cursor.x, cursor.y) = current;
+            cursor.desired_x =
+                scroll.get_display_width_from_bytes(&document.lines()[cursor.y], cursor.x);
+            debug!("Document after undo: {} lines", document.lines().len());
             Ok(())
         } else {
             debug!("Undo stack is empty. Nothing to undo.");
@@ -137,26 +260,21 @@ impl UndoRedo {
     pub fn redo(
         &mut self,
         document: &mut Document,
-        cursor_x: &mut usize,
-        cursor_y: &mut usize,
-        desired_cursor_x: &mut usize,
+        cursor: &mut Cursor,
         scroll: &Scroll,
     ) -> Result<(), String> {
         debug!(
-            "Redo called. Current redo_stack length: {}. Current document: {:?}",
-            self.redo_stack.len(),
-            document.lines
+            "Redo called. Current redo_stack length: {}.",
+            self.redo_stack.len()
         );
         if let Some(actions_to_redo) = self.redo_stack.pop() {
             let mut actions_for_undo = Vec::new();
-            let mut current_cursor_x = *cursor_x;
-            let mut current_cursor_y = *cursor_y;
+            let mut current = cursor.pos();
 
             for action_diff in actions_to_redo.iter() {
                 match document.apply_action_diff(action_diff, false) {
-                    Ok((new_x, new_y)) => {
-                        current_cursor_x = new_x;
-                        current_cursor_y = new_y;
+                    Ok(position) => {
+                        current = position;
                         actions_for_undo.push(action_diff.clone());
                     }
                     Err(e) => {
@@ -167,12 +285,14 @@ impl UndoRedo {
                 }
             }
             self.undo_stack.push(actions_for_undo);
+            // Start a new group with the next edit, which clears the rest of the redo stack
+            self.last_action_type = LastActionType::None;
+            self.group_started = false;
 
-            *cursor_x = current_cursor_x;
-            *cursor_y = current_cursor_y;
-            *desired_cursor_x =
-                scroll.get_display_width_from_bytes(&document.lines[*cursor_y], *cursor_x);
-            debug!("Document after redo: {:?}", document.lines);
+            (cursor.x, cursor.y) = current;
+            cursor.desired_x =
+                scroll.get_display_width_from_bytes(&document.lines()[cursor.y], cursor.x);
+            debug!("Document after redo: {} lines", document.lines().len());
             Ok(())
         } else {
             debug!("Redo stack is empty. Nothing to redo.");
@@ -0,0 +1,30 @@
+use crate::editor::Editor;
+
+impl Editor {
+    /// Called from the main loop; marks a snapshot in the undo history at a
+    /// pause after every few minutes of editing.
+    pub fn undo_snapshot_tick(&mut self) {
+        if let Some(interval) = self.settings.undo_snapshot_interval() {
+            self.undo_redo.snapshot_tick(interval);
+        }
+    }
+
+    /// Undoes everything since the latest snapshot, or since the one before
+    /// it when nothing has changed since. Each step can still be redone.
+    pub fn undo_to_snapshot(&mut self) {
+        let Some(snapshot) = self.undo_redo.previous_snapshot().cloned() else {
+            self.set_message("No earlier snapshot.");
+            return;
+        };
+        let steps = self.undo_redo.undo_stack.len() - snapshot.depth;
+        for _ in 0..steps {
+            self.undo();
+        }
+        if self.undo_redo.undo_stack.len() == snapshot.depth {
+            self.set_message(&format!(
+                "Back to the snapshot of {} ({steps} undo steps).",
+                snapshot.taken_at.format("%H:%M")
+            ));
+        }
+    }
+}
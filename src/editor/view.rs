@@ -0,0 +1,103 @@
+use crate::editor::Editor;
+use crate::editor::scroll::vertical_margin;
+
+/// Where `RecenterView` puts the cursor line, in the order repeated presses
+/// cycle through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecenterPosition {
+    Center,
+    Top,
+    Bottom,
+}
+
+impl RecenterPosition {
+    fn next(self) -> Self {
+        match self {
+            RecenterPosition::Center => RecenterPosition::Top,
+            RecenterPosition::Top => RecenterPosition::Bottom,
+            RecenterPosition::Bottom => RecenterPosition::Center,
+        }
+    }
+}
+
+/// The view `RecenterView` last left, so that pressing it again without
+/// moving continues the cycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Recentered {
+    pub cursor_y: usize,
+    pub row_offset: usize,
+    pub position: RecenterPosition,
+}
+
+impl Editor {
+    /// Scrolls the cursor line to the center of the view, or to the top and
+    /// then the bottom when repeated. Top and bottom leave the scroll margin
+    /// free, as drawing would scroll back otherwise.
+    pub fn recenter_view(&mut self) {
+        let rows = self.text_rows();
+        let margin = vertical_margin(rows);
+        let position = match self.recentered {
+            Some(last)
+                if last.cursor_y == self.cursor.y && last.row_offset == self.scroll.row_offset =>
+            {
+                last.position.next()
+            }
+            _ => RecenterPosition::Center,
+        };
+        let rows_above = match position {
+            RecenterPosition::Center => rows / 2,
+            RecenterPosition::Top => margin,
+            RecenterPosition::Bottom => rows.saturating_sub(margin + 1),
+        };
+        let cursor_row = self.visible_row(self.cursor.y);
+        self.scroll.row_offset = self.line_at_visible_row(cursor_row.saturating_sub(rows_above));
+        self.recentered = Some(Recentered {
+            cursor_y: self.cursor.y,
+            row_offset: self.scroll.row_offset,
+            position,
+        });
+    }
+
+    /// Moves the view one line towards the end of the file when `down` is
+    /// set, or towards the start, taking the cursor along only as far as it
+    /// has to go to stay clear of the scroll margin.
+    pub fn scroll_view(&mut self, down: bool) {
+        self.clipboard.last_action_was_kill = false;
+        let rows = self.text_rows();
+        let margin = vertical_margin(rows);
+        let last_row = self.visible_row(self.document.lines().len().saturating_sub(1));
+        let offset_row = self.visible_row(self.scroll.row_offset);
+        let offset_row = if down {
+            // The last line stays below the top margin
+            if offset_row + 1 + margin > last_row {
+                return;
+            }
+            offset_row + 1
+        } else {
+            let Some(row) = offset_row.checked_sub(1) else {
+                return;
+            };
+            row
+        };
+        self.scroll.row_offset = self.line_at_visible_row(offset_row);
+
+        let top = if offset_row == 0 {
+            0
+        } else {
+            offset_row + margin
+        };
+        let bottom = (offset_row + rows).saturating_sub(margin + 1).max(top);
+        let cursor_row = self.visible_row(self.cursor.y);
+        let target = cursor_row.clamp(top, bottom).min(last_row);
+        if target != cursor_row {
+            self.cursor.y = self.line_at_visible_row(target);
+            self.cursor.x = self
+                .scroll
+                .get_byte_pos_from_display_width(
+                    &self.document.lines()[self.cursor.y],
+                    self.cursor.desired_x,
+                )
+                .0;
+        }
+    }
+}
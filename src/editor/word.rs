@@ -0,0 +1,219 @@
+use crate::config::EditorSettings;
+use crate::editor::Editor;
+use std::path::Path;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum CharType {
+    Kanji,
+    Hiragana,
+    Katakana,
+    Alphanumeric,
+    Punctuation,
+    Whitespace,
+    // One of the `word_classes` from the config, by index
+    Custom(usize),
+    Other,
+}
+
+/// Decides which characters belong to the same word for word movement and
+/// deletion. `word_chars` join letters and digits, and each of `classes` is a
+/// set of characters that form words of their own. With `subwords`, words
+/// also end at camelCase humps and underscores.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct WordClasses<'a> {
+    word_chars: &'a str,
+    classes: &'a [String],
+    subwords: bool,
+}
+
+impl<'a> WordClasses<'a> {
+    pub fn new(word_chars: &'a str, classes: &'a [String]) -> Self {
+        Self {
+            word_chars,
+            classes,
+            subwords: false,
+        }
+    }
+
+    pub fn with_subwords(self, subwords: bool) -> Self {
+        Self { subwords, ..self }
+    }
+
+    pub fn from_settings(settings: &'a EditorSettings) -> Self {
+        Self::new(
+            settings.word_chars.as_deref().unwrap_or_default(),
+            settings.word_classes.as_deref().unwrap_or_default(),
+        )
+    }
+
+    pub fn char_type(&self, ch: char) -> CharType {
+        if ch.is_whitespace() {
+            return CharType::Whitespace;
+        }
+        if self.word_chars.contains(ch) {
+            return CharType::Alphanumeric;
+        }
+        if let Some(index) = self.classes.iter().position(|class| class.contains(ch)) {
+            return CharType::Custom(index);
+        }
+        get_char_type(ch)
+    }
+
+    /// Whether a word ends between `before` and `after`, where `next` is the
+    /// character following `after`.
+    fn splits(&self, before: char, after: char, next: Option<char>) -> bool {
+        let word_type = self.char_type(before);
+        if word_type != self.char_type(after) {
+            return true;
+        }
+        self.subwords
+            && word_type == CharType::Alphanumeric
+            && is_subword_boundary(before, after, next)
+    }
+
+    /// Start of the word that ends at byte `end` of `line`.
+    pub fn word_start(&self, line: &str, end: usize) -> usize {
+        let mut start = end;
+        let (mut after, mut next) = (None, None);
+        for (idx, ch) in line[..end].char_indices().rev() {
+            if let Some(after) = after
+                && self.splits(ch, after, next)
+            {
+                break;
+            }
+            start = idx;
+            next = after;
+            after = Some(ch);
+        }
+        start
+    }
+
+    /// End of the word that starts at byte `start` of `line`.
+    pub fn word_end(&self, line: &str, start: usize) -> usize {
+        let mut end = start;
+        let mut before = None;
+        let mut chars = line[start..].chars().peekable();
+        while let Some(ch) = chars.next() {
+            if let Some(before) = before
+                && self.splits(before, ch, chars.peek().copied())
+            {
+                break;
+            }
+            end += ch.len_utf8();
+            before = Some(ch);
+        }
+        end
+    }
+}
+
+// `fooBar`, `HTTPServer` and `foo_bar` split before `B`, `S` and around `_`
+fn is_subword_boundary(before: char, after: char, next: Option<char>) -> bool {
+    if (before == '_') != (after == '_') {
+        return true;
+    }
+    let hump = (before.is_lowercase() || before.is_ascii_digit()) && after.is_uppercase();
+    let acronym_end =
+        before.is_uppercase() && after.is_uppercase() && next.is_some_and(char::is_lowercase);
+    hump || acronym_end
+}
+
+fn get_char_type(ch: char) -> CharType {
+    if ch == '。' || ch == '、' {
+        return CharType::Punctuation;
+    }
+    // ASCII Alphanumeric
+    if ch.is_ascii_alphanumeric() {
+        return CharType::Alphanumeric;
+    }
+    // Hiragana
+    if ('\u{3040}'..='\u{309F}').contains(&ch) {
+        return CharType::Hiragana;
+    }
+    // Katakana
+    if ('\u{30A0}'..='\u{30FF}').contains(&ch) {
+        return CharType::Katakana;
+    }
+    // CJK Unified Ideographs (Kanji)
+    if ('\u{4E00}'..='\u{9FFF}').contains(&ch) {
+        return CharType::Kanji;
+    }
+    // Full-width digits
+    if ('\u{FF10}'..='\u{FF19}').contains(&ch) {
+        return CharType::Alphanumeric;
+    }
+    // Full-width uppercase
+    if ('\u{FF21}'..='\u{FF3A}').contains(&ch) {
+        return CharType::Alphanumeric;
+    }
+    // Full-width lowercase
+    if ('\u{FF41}'..='\u{FF5A}').contains(&ch) {
+        return CharType::Alphanumeric;
+    }
+    CharType::Other
+}
+
+pub fn find_word_boundary_left(line: &str, current_x: usize, classes: &WordClasses) -> usize {
+    if current_x == 0 {
+        return 0;
+    }
+
+    let mut boundary = current_x;
+
+    // 1. Find char to the left and its type
+    let (start_idx, start_char) = line[..boundary].char_indices().next_back().unwrap();
+    boundary = start_idx;
+
+    // If it's NOT whitespace, it's a word. Find its beginning.
+    if classes.char_type(start_char) != CharType::Whitespace {
+        boundary = classes.word_start(line, current_x);
+    }
+
+    // Now, `boundary` is at the beginning of the word/whitespace block.
+    // Delete any preceding whitespace.
+    let mut final_boundary = boundary;
+    for (idx, ch) in line[..boundary].char_indices().rev() {
+        if classes.char_type(ch) == CharType::Whitespace {
+            final_boundary = idx;
+        } else {
+            break;
+        }
+    }
+
+    final_boundary
+}
+
+impl Editor {
+    pub fn word_classes(&self) -> WordClasses<'_> {
+        WordClasses::from_settings(&self.settings).with_subwords(self.is_subword_mode())
+    }
+
+    /// Whether word movement stops inside camelCase and snake_case words, as
+    /// set for the file's extension in `subword_filetypes` or toggled.
+    pub fn is_subword_mode(&self) -> bool {
+        self.subword_mode.unwrap_or_else(|| {
+            let extension = self
+                .document
+                .filename
+                .as_deref()
+                .and_then(|filename| Path::new(filename).extension())
+                .and_then(|extension| extension.to_str());
+            extension.is_some_and(|extension| {
+                self.settings
+                    .subword_filetypes
+                    .iter()
+                    .flatten()
+                    .any(|filetype| filetype.trim_start_matches('.') == extension)
+            })
+        })
+    }
+
+    pub fn toggle_subword_mode(&mut self) {
+        let enabled = !self.is_subword_mode();
+        self.subword_mode = Some(enabled);
+        self.set_message(if enabled {
+            "Subword movement on."
+        } else {
+            "Subword movement off."
+        });
+    }
+}
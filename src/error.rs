@@ -12,6 +12,10 @@ pub enum DmacsError {
     Document(String),
     #[error("Backup not found for {0}")]
     BackupNotFound(String),
+    #[error("No file named {0} in the trash")]
+    NotInTrash(String),
+    #[error("git: {0}")]
+    Git(String),
     #[error("Unknown error")]
     Unknown,
 }
@@ -0,0 +1,149 @@
+use crate::config::EditorSettings;
+use crate::document::line_buffer::{self, LineBuffer};
+
+// A `dmacs:` modeline is looked for in this many lines from the top
+const MODELINE_LINES: usize = 5;
+// A front matter block longer than this is taken for something else
+const FRONT_MATTER_MAX_LINES: usize = 50;
+const FRONT_MATTER_MARKER: &str = "---";
+const MODELINE_MARKER: &str = "dmacs:";
+
+/// Settings a file gives for itself, in a YAML front matter block at the
+/// top or a `dmacs:` modeline in its first lines:
+///
+/// ```text
+/// # dmacs: indent_width=4 list_continuation=off theme=light
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileSettings {
+    pub indent_width: Option<usize>,
+    pub ruler_column: Option<usize>,
+    pub list_continuation: Option<bool>,
+    pub theme: Option<String>,
+    pub abbrevs: Option<bool>,
+}
+
+impl FileSettings {
+    pub fn parse(lines: &LineBuffer) -> Self {
+        let mut settings = FileSettings::default();
+        if let Some(front_matter) = front_matter(lines) {
+            for line in front_matter {
+                // Nested under a `dmacs:` key or not
+                if let Some((key, value)) = line.trim().split_once(':')
+                    && !value.trim().is_empty()
+                {
+                    settings.set(key.trim(), value.trim(), false);
+                }
+            }
+        }
+        for line in lines.iter().take(MODELINE_LINES) {
+            let Some(start) = line.find(MODELINE_MARKER) else {
+                continue;
+            };
+            let modeline = line[start + MODELINE_MARKER.len()..]
+                .trim_end()
+                .trim_end_matches("-->")
+                .trim_end_matches("*/");
+            let pairs = modeline
+                .split(|c: char| c.is_whitespace() || c == ';' || c == ',')
+                .filter_map(|pair| pair.split_once('='));
+            for (key, value) in pairs {
+                settings.set(key, value, true);
+            }
+        }
+        settings
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == FileSettings::default()
+    }
+
+    // Front matter usually holds other keys too, which only a modeline warns about
+    fn set(&mut self, key: &str, value: &str, warn: bool) {
+        let value = value.trim_matches(|c| c == '"' || c == '\'');
+        let valid = match key {
+            "indent_width" | "tab_width" => {
+                self.indent_width = value.parse().ok().filter(|&width| width > 0);
+                self.indent_width.is_some()
+            }
+            "ruler_column" | "fill_column" => {
+                self.ruler_column = value.parse().ok();
+                self.ruler_column.is_some()
+            }
+            "list_continuation" => {
+                self.list_continuation = parse_bool(value);
+                self.list_continuation.is_some()
+            }
+            "abbrevs" => {
+                self.abbrevs = parse_bool(value);
+                self.abbrevs.is_some()
+            }
+            "theme" => {
+                self.theme = Some(value.to_string()).filter(|theme| !theme.is_empty());
+                self.theme.is_some()
+            }
+            _ => {
+                if warn {
+                    log::warn!("Unknown file setting: {key}");
+                }
+                true
+            }
+        };
+        if !valid {
+            log::warn!("Invalid value for the file setting {key}: {value}");
+        }
+    }
+
+    /// Puts the settings the file gives in place of those in `settings`.
+    pub fn apply(&self, settings: &mut EditorSettings) {
+        if self.indent_width.is_some() {
+            settings.indent_width = self.indent_width;
+        }
+        if self.ruler_column.is_some() {
+            settings.ruler_column = self.ruler_column;
+        }
+        if self.list_continuation.is_some() {
+            settings.list_continuation = self.list_continuation;
+        }
+        if self.theme.is_some() {
+            settings.theme = self.theme.clone();
+        }
+    }
+
+    /// Puts back the values of `user` for the settings `apply` replaced.
+    pub fn restore(&self, settings: &mut EditorSettings, user: &EditorSettings) {
+        if self.indent_width.is_some() {
+            settings.indent_width = user.indent_width;
+        }
+        if self.ruler_column.is_some() {
+            settings.ruler_column = user.ruler_column;
+        }
+        if self.list_continuation.is_some() {
+            settings.list_continuation = user.list_continuation;
+        }
+        if self.theme.is_some() {
+            settings.theme = user.theme.clone();
+        }
+    }
+}
+
+/// The lines between a `---` on the first line and the next `---` or `...`.
+fn front_matter(lines: &LineBuffer) -> Option<line_buffer::Iter<'_>> {
+    if lines.first()? != FRONT_MATTER_MARKER {
+        return None;
+    }
+    let end = lines
+        .iter()
+        .take(FRONT_MATTER_MAX_LINES)
+        .skip(1)
+        .position(|line| line == FRONT_MATTER_MARKER || line == "...")?;
+    Some(lines.range(1..end + 1))
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "true" | "yes" | "on" => Some(true),
+        "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
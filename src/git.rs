@@ -0,0 +1,102 @@
+//! What dmacs knows about the git repository a file is in. Most of it is read
+//! from the `.git` directory; only the committed content of a file takes
+//! running git.
+
+use crate::error::{DmacsError, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// The `.git` directory of the repository that `path` is in. A `.git` file,
+/// as in worktrees and submodules, points to it with `gitdir: <path>`.
+pub fn git_dir(path: &Path) -> Option<PathBuf> {
+    let start = if path.is_dir() { path } else { path.parent()? };
+    let start = start.canonicalize().ok()?;
+    for dir in start.ancestors() {
+        let dot_git = dir.join(".git");
+        if dot_git.is_dir() {
+            return Some(dot_git);
+        }
+        if let Ok(content) = fs::read_to_string(&dot_git)
+            && let Some(target) = content.trim().strip_prefix("gitdir:")
+        {
+            return Some(dir.join(target.trim()));
+        }
+    }
+    None
+}
+
+/// The branch checked out in the repository of `path`, or the first seven
+/// digits of the commit when HEAD is detached.
+pub fn current_branch(path: &Path) -> Option<String> {
+    let head = fs::read_to_string(git_dir(path)?.join("HEAD")).ok()?;
+    let head = head.trim();
+    match head.strip_prefix("ref: ") {
+        Some(reference) => Some(
+            reference
+                .strip_prefix("refs/heads/")
+                .unwrap_or(reference)
+                .to_string(),
+        ),
+        None => Some(head.chars().take(7).collect()).filter(|hash: &String| !hash.is_empty()),
+    }
+}
+
+/// `git` run in the directory of `path`, and the name of the file there.
+fn git_for_file(path: &Path) -> Option<(Command, &str)> {
+    let name = path.file_name()?.to_str()?;
+    let mut command = Command::new("git");
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        command.arg("-C").arg(dir);
+    }
+    command.stdin(Stdio::null());
+    Some((command, name))
+}
+
+/// The content of `path` as of the last commit, from `git show HEAD:<file>`,
+/// or `None` when the file is not in a repository or not committed yet.
+pub fn head_content(path: &Path) -> Option<String> {
+    git_dir(path)?;
+    let (mut command, name) = git_for_file(path)?;
+    let output = command
+        .arg("show")
+        .arg(format!("HEAD:./{name}"))
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()
+}
+
+/// Runs `git <args> -- <file>` for `path` and returns what it printed.
+fn run_on_file(path: &Path, args: &[&str]) -> Result<String> {
+    let (mut command, name) = git_for_file(path)
+        .ok_or_else(|| DmacsError::Git(format!("Not a file: {}", path.display())))?;
+    let output = command.args(args).arg("--").arg(name).output()?;
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    if output.status.success() {
+        return Ok(stdout);
+    }
+    // git says what went wrong on stderr, or on the last line of its
+    // status when there is nothing to commit
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let reason = stderr
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .or_else(|| stdout.lines().rfind(|line| !line.trim().is_empty()))
+        .unwrap_or("failed");
+    Err(DmacsError::Git(reason.trim().to_string()))
+}
+
+/// Stages `path` and commits it, and only it, with `message`. Returns the
+/// summary git prints, such as `[main 1a2b3c4] Update notes.md`.
+pub fn commit_file(path: &Path, message: &str) -> Result<String> {
+    if git_dir(path).is_none() {
+        return Err(DmacsError::Git("Not in a git repository".to_string()));
+    }
+    run_on_file(path, &["add"])?;
+    let output = run_on_file(path, &["commit", "-m", message])?;
+    Ok(output.lines().next().unwrap_or_default().to_string())
+}
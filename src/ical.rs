@@ -0,0 +1,109 @@
+use crate::editor::annotation::find_annotation;
+use crate::editor::date_picker::parse_due;
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+
+const UNCHECKED: &str = "- [ ] ";
+const CHECKED: &str = "- [x] ";
+// Content lines longer than this many bytes are folded (RFC 5545)
+const MAX_LINE_BYTES: usize = 75;
+
+/// Builds an iCalendar file with a VTODO for every task in `content` that has
+/// a `@due(YYYY-MM-DD)` date. `now` is used as the timestamp of the entries.
+pub fn export(content: &str, now: DateTime<Utc>) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//DevMassive//dmacs//EN".to_string(),
+    ];
+    for line in content.lines() {
+        lines.extend(vtodo(line, now).unwrap_or_default());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold(line) + "\r\n").collect()
+}
+
+fn vtodo(line: &str, now: DateTime<Utc>) -> Option<Vec<String>> {
+    let trimmed = line.trim_start();
+    let (done, rest) = match (
+        trimmed.strip_prefix(UNCHECKED),
+        trimmed.strip_prefix(CHECKED),
+    ) {
+        (Some(rest), _) => (false, rest),
+        (_, Some(rest)) => (true, rest),
+        _ => return None,
+    };
+    let due = parse_due(rest)?;
+    let (priority, summary) = split_priority(&without_due(rest));
+
+    let mut entry = vec![
+        "BEGIN:VTODO".to_string(),
+        // Based on the text only, so checking a task off updates the same entry
+        format!("UID:{}@dmacs", uid(&summary, due.to_string().as_str())),
+        format!("DTSTAMP:{}", now.format("%Y%m%dT%H%M%SZ")),
+        format!("SUMMARY:{}", escape(&summary)),
+        format!("DUE;VALUE=DATE:{}", due.format("%Y%m%d")),
+        format!("STATUS:{}", if done { "COMPLETED" } else { "NEEDS-ACTION" }),
+    ];
+    if let Some(priority) = priority {
+        entry.push(format!("PRIORITY:{priority}"));
+    }
+    entry.push("END:VTODO".to_string());
+    Some(entry)
+}
+
+fn without_due(text: &str) -> String {
+    match find_annotation(text, "due") {
+        Some((start, end, _)) => format!("{}{}", &text[..start], &text[end..])
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" "),
+        None => text.trim().to_string(),
+    }
+}
+
+/// Maps a todo.txt style `(A)`..`(I)` priority to iCalendar's 1..9.
+fn split_priority(text: &str) -> (Option<u8>, String) {
+    let bytes = text.as_bytes();
+    if bytes.len() >= 4
+        && bytes[0] == b'('
+        && bytes[1].is_ascii_uppercase()
+        && bytes[2] == b')'
+        && bytes[3] == b' '
+    {
+        let priority = (bytes[1] - b'A' + 1).min(9);
+        (Some(priority), text[4..].to_string())
+    } else {
+        (None, text.to_string())
+    }
+}
+
+fn uid(summary: &str, due: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(summary.as_bytes());
+    hasher.update(due.as_bytes());
+    format!("{:x}", hasher.finalize())[..16].to_string()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+}
+
+/// Splits a long content line into continuation lines starting with a space,
+/// without breaking UTF-8 characters.
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut line_bytes = 0;
+    for c in line.chars() {
+        if line_bytes + c.len_utf8() > MAX_LINE_BYTES {
+            folded.push_str("\r\n ");
+            line_bytes = 1;
+        }
+        folded.push(c);
+        line_bytes += c.len_utf8();
+    }
+    folded
+}
@@ -0,0 +1,50 @@
+use crate::error::{DmacsError, Result};
+use chrono::Local;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Expands a leading `~/` so config values can point into the home directory.
+pub fn resolve_path(path: &str) -> PathBuf {
+    if let Some(rest) = path.strip_prefix("~/")
+        && let Some(home_dir) = dirs::home_dir()
+    {
+        return home_dir.join(rest);
+    }
+    PathBuf::from(path)
+}
+
+/// Appends `line` to the end of `path`, creating the file if needed and making
+/// sure it starts on a line of its own.
+pub fn append_line(path: &Path, line: &str) -> Result<()> {
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent).map_err(DmacsError::Io)?;
+    }
+    let needs_newline = fs::read(path)
+        .map(|content| !content.is_empty() && !content.ends_with(b"\n"))
+        .unwrap_or(false);
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(DmacsError::Io)?;
+    if needs_newline {
+        writeln!(file).map_err(DmacsError::Io)?;
+    }
+    writeln!(file, "{line}").map_err(DmacsError::Io)
+}
+
+/// Appends `text` to the inbox as a timestamped list item and returns the line
+/// that was written.
+pub fn capture(path: &Path, text: &str) -> Result<String> {
+    let line = format!(
+        "- {} {}",
+        Local::now().format("%Y-%m-%d %H:%M"),
+        text.trim()
+    );
+    append_line(path, &line)?;
+    Ok(line)
+}
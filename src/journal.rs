@@ -0,0 +1,239 @@
+use crate::document::ActionDiff;
+use crate::error::{DmacsError, Result};
+use crate::persistence;
+use log::{debug, error};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+const JOURNAL_SUBDIR: &str = "journal";
+const CLEANUP_THRESHOLD_DAYS: u64 = 7;
+
+/// First line of a journal file. Entries are only valid on top of the file
+/// content they were recorded against.
+#[derive(Serialize, Deserialize, Debug)]
+struct JournalHeader {
+    file_path: String,
+    base_hash: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct JournalEntry {
+    pub diff: ActionDiff,
+    pub is_undo: bool,
+}
+
+/// Append-only log of the edits applied to a file since it was last saved,
+/// used to recover unsaved changes after a crash.
+pub struct Journal {
+    // Where journals are kept, so that files opened later get one too
+    dir: Option<PathBuf>,
+    path: Option<PathBuf>,
+    file_path: String,
+    base_hash: String,
+    writer: Option<File>,
+}
+
+impl Journal {
+    /// A journal that records nothing, used for buffers without a file.
+    pub fn disabled() -> Self {
+        Self {
+            dir: None,
+            path: None,
+            file_path: String::new(),
+            base_hash: String::new(),
+            writer: None,
+        }
+    }
+
+    pub fn new(file_path: &str, base_content: &str) -> Self {
+        Self::new_with_base_dir(None, file_path, base_content)
+    }
+
+    pub fn new_with_base_dir(
+        base_dir: Option<PathBuf>,
+        file_path: &str,
+        base_content: &str,
+    ) -> Self {
+        let dir = match journal_dir(base_dir) {
+            Ok(dir) => Some(dir),
+            Err(e) => {
+                error!("Failed to get journal directory: {e}");
+                None
+            }
+        };
+        Self::in_dir(dir, file_path, base_content)
+    }
+
+    /// A journal that records nothing until the buffer gets a file name.
+    pub fn unnamed_with_base_dir(base_dir: Option<PathBuf>) -> Self {
+        let mut journal = Self::new_with_base_dir(base_dir, "", "");
+        journal.path = None;
+        journal
+    }
+
+    /// Starts a journal for another file next to this one's.
+    pub fn for_file(&self, file_path: &str, base_content: &str) -> Self {
+        Self::in_dir(self.dir.clone(), file_path, base_content)
+    }
+
+    fn in_dir(dir: Option<PathBuf>, file_path: &str, base_content: &str) -> Self {
+        Self {
+            path: dir.as_ref().map(|dir| journal_file(dir, file_path)),
+            dir,
+            file_path: file_path.to_string(),
+            base_hash: hash_str(base_content),
+            writer: None,
+        }
+    }
+
+    /// Returns the entries of a journal left behind by a previous session, if
+    /// it was recorded against the current file content.
+    pub fn recoverable_entries(&self) -> Option<Vec<JournalEntry>> {
+        let path = self.path.as_ref()?;
+        let file = File::open(path).ok()?;
+        let mut lines = BufReader::new(file).lines();
+
+        let header: JournalHeader = serde_json::from_str(&lines.next()?.ok()?).ok()?;
+        if header.file_path != self.file_path || header.base_hash != self.base_hash {
+            debug!("Journal for {} is stale, ignoring it.", self.file_path);
+            return None;
+        }
+
+        // A torn last line from a crash just ends the recoverable history
+        let entries: Vec<JournalEntry> = lines
+            .map_while(|line| serde_json::from_str(&line.ok()?).ok())
+            .collect();
+        if entries.is_empty() {
+            None
+        } else {
+            Some(entries)
+        }
+    }
+
+    /// Continues appending to the existing journal file after a recovery.
+    pub fn resume(&mut self) {
+        if let Some(path) = &self.path {
+            match OpenOptions::new().append(true).open(path) {
+                Ok(file) => self.writer = Some(file),
+                Err(e) => error!("Failed to reopen journal {}: {e}", path.display()),
+            }
+        }
+    }
+
+    pub fn record(&mut self, diff: &ActionDiff, is_undo: bool) {
+        if let Err(e) = self.try_record(diff, is_undo) {
+            error!("Failed to write journal entry: {e}");
+        }
+    }
+
+    fn try_record(&mut self, diff: &ActionDiff, is_undo: bool) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if self.writer.is_none() {
+            let mut file = File::create(path).map_err(DmacsError::Io)?;
+            let header = JournalHeader {
+                file_path: self.file_path.clone(),
+                base_hash: self.base_hash.clone(),
+            };
+            writeln!(file, "{}", to_json(&header)?).map_err(DmacsError::Io)?;
+            self.writer = Some(file);
+        }
+        let entry = JournalEntry {
+            diff: diff.clone(),
+            is_undo,
+        };
+        if let Some(writer) = self.writer.as_mut() {
+            writeln!(writer, "{}", to_json(&entry)?).map_err(DmacsError::Io)?;
+        }
+        Ok(())
+    }
+
+    /// Drops the journal once its edits are safely on disk; later edits start
+    /// a new journal against `saved_content`.
+    pub fn reset(&mut self, saved_content: &str) {
+        self.writer = None;
+        self.base_hash = hash_str(saved_content);
+        self.remove_file();
+    }
+
+    /// Points the journal at a file's new path, dropping what was recorded for
+    /// the old one.
+    pub fn rename(&mut self, file_path: &str) {
+        self.discard();
+        self.path = self.dir.as_ref().map(|dir| journal_file(dir, file_path));
+        self.file_path = file_path.to_string();
+    }
+
+    pub fn discard(&mut self) {
+        self.writer = None;
+        self.remove_file();
+    }
+
+    fn remove_file(&self) {
+        if let Some(path) = &self.path
+            && path.exists()
+            && let Err(e) = fs::remove_file(path)
+        {
+            error!("Failed to remove journal {}: {e}", path.display());
+        }
+    }
+}
+
+fn journal_dir(base_dir: Option<PathBuf>) -> std::io::Result<PathBuf> {
+    let state_dir = match base_dir {
+        Some(dir) => dir.join(".dmacs"),
+        None => persistence::state_dir().ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::NotFound, "Home directory not found")
+        })?,
+    };
+    let dir = state_dir.join(JOURNAL_SUBDIR);
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+fn journal_file(dir: &Path, file_path: &str) -> PathBuf {
+    dir.join(format!("{}.journal", hash_str(file_path)))
+}
+
+fn hash_str(s: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(s.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String> {
+    serde_json::to_string(value).map_err(|e| DmacsError::Document(e.to_string()))
+}
+
+/// Removes journals that were abandoned long ago, e.g. for files that no
+/// longer exist.
+pub fn cleanup_old_journals() {
+    let dir = match journal_dir(None) {
+        Ok(dir) => dir,
+        Err(e) => {
+            error!("Failed to get journal directory for cleanup: {e}");
+            return;
+        }
+    };
+    let threshold = SystemTime::now() - Duration::from_secs(CLEANUP_THRESHOLD_DAYS * 24 * 60 * 60);
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let is_old = fs::metadata(&path)
+            .and_then(|m| m.modified())
+            .is_ok_and(|modified| modified < threshold);
+        if is_old {
+            match fs::remove_file(&path) {
+                Ok(_) => debug!("Deleted old journal: {}", path.display()),
+                Err(e) => error!("Failed to delete old journal {}: {e}", path.display()),
+            }
+        }
+    }
+}
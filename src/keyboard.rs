@@ -0,0 +1,658 @@
+//! Keys read from the terminal. Escape sequences go through a state machine
+//! that turns them into keys with modifiers, including the ones the legacy
+//! encoding cannot tell apart, such as Ctrl+Shift+K or Ctrl+Enter, as
+//! reported by the kitty keyboard protocol and by xterm's modifyOtherKeys.
+
+use log::warn;
+use pancurses::Input;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+// Asks for ambiguous keys as `CSI code;mods u` (kitty, "disambiguate escape
+// codes") or else `CSI 27;mods;code ~` (xterm modifyOtherKeys level 2).
+// Terminals that support neither ignore both requests.
+pub const ENABLE_KEYBOARD_PROTOCOL: &str = "\x1b[>1u\x1b[>4;2m";
+pub const DISABLE_KEYBOARD_PROTOCOL: &str = "\x1b[<u\x1b[>4m";
+
+// Bits of the modifier parameter, which is sent plus one
+const SHIFT: u32 = 1;
+const ALT: u32 = 2;
+const CTRL: u32 = 4;
+// Super, hyper and meta: left to the window manager
+const OTHER_MODIFIERS: u32 = 8 | 16 | 32;
+
+// Start of the private use area where kitty puts keys without a character
+const KITTY_FUNCTIONAL_KEYS: u32 = 0xE000;
+
+// Longest parameter string of a CSI sequence that is read before giving up
+const CSI_MAX_PARAMS_LEN: usize = 32;
+
+// Timeouts of getch() in a row after which the rest of a character split
+// across reads is given up
+const UTF8_MAX_IDLE_READS: usize = 10;
+
+// The parameter of ESC [200~, which starts a bracketed paste
+const PASTE_START: &str = "200";
+
+const FUNCTION_KEYS: [Input; 12] = [
+    Input::KeyF1,
+    Input::KeyF2,
+    Input::KeyF3,
+    Input::KeyF4,
+    Input::KeyF5,
+    Input::KeyF6,
+    Input::KeyF7,
+    Input::KeyF8,
+    Input::KeyF9,
+    Input::KeyF10,
+    Input::KeyF11,
+    Input::KeyF12,
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChordKey {
+    // Letters are kept lowercase; shift is a modifier of its own
+    Char(char),
+    Enter,
+    Tab,
+    Backspace,
+    Escape,
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Insert,
+    Delete,
+    // F1 to F12
+    F(u8),
+}
+
+impl ChordKey {
+    /// The key of `CSI <modifiers> <final>` or `ESC O <final>`.
+    fn from_final_byte(final_byte: char) -> Option<Self> {
+        Some(match final_byte {
+            'A' => ChordKey::Up,
+            'B' => ChordKey::Down,
+            'C' => ChordKey::Right,
+            'D' => ChordKey::Left,
+            'H' => ChordKey::Home,
+            'F' => ChordKey::End,
+            'P' => ChordKey::F(1),
+            'Q' => ChordKey::F(2),
+            'R' => ChordKey::F(3),
+            'S' => ChordKey::F(4),
+            _ => return None,
+        })
+    }
+
+    /// The key of `CSI <number>;<modifiers> ~`.
+    fn from_tilde_number(number: u32) -> Option<Self> {
+        Some(match number {
+            1 | 7 => ChordKey::Home,
+            2 => ChordKey::Insert,
+            3 => ChordKey::Delete,
+            4 | 8 => ChordKey::End,
+            5 => ChordKey::PageUp,
+            6 => ChordKey::PageDown,
+            11..=15 => ChordKey::F((number - 10) as u8),
+            17..=21 => ChordKey::F((number - 11) as u8),
+            23 | 24 => ChordKey::F((number - 12) as u8),
+            _ => return None,
+        })
+    }
+
+    /// The key of a terminfo name of curses, e.g. `UP` in `kUP5`.
+    fn from_terminfo_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "UP" => ChordKey::Up,
+            "DN" => ChordKey::Down,
+            "LFT" => ChordKey::Left,
+            "RIT" => ChordKey::Right,
+            "HOM" => ChordKey::Home,
+            "END" => ChordKey::End,
+            "PRV" => ChordKey::PageUp,
+            "NXT" => ChordKey::PageDown,
+            "IC" => ChordKey::Insert,
+            "DC" => ChordKey::Delete,
+            _ => return None,
+        })
+    }
+}
+
+/// A key and the modifiers held with it.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyChord {
+    pub key: ChordKey,
+    #[serde(default)]
+    pub ctrl: bool,
+    #[serde(default)]
+    pub alt: bool,
+    #[serde(default)]
+    pub shift: bool,
+}
+
+impl KeyChord {
+    pub fn new(key: ChordKey) -> Self {
+        Self {
+            key,
+            ctrl: false,
+            alt: false,
+            shift: false,
+        }
+    }
+
+    fn with_modifiers(key: ChordKey, modifiers: u32) -> Option<Self> {
+        let bits = modifiers.saturating_sub(1);
+        if bits & OTHER_MODIFIERS != 0 {
+            return None;
+        }
+        Some(Self {
+            key,
+            ctrl: bits & CTRL != 0,
+            alt: bits & ALT != 0,
+            shift: bits & SHIFT != 0,
+        })
+    }
+
+    /// Decodes the parameters and final byte of a CSI sequence: a key with
+    /// modifiers as in `1;5A` or `5;2~`, kitty's `code[:alternates];mods u`
+    /// or modifyOtherKeys' `27;mods;code ~`. Returns `None` for any other
+    /// sequence and for keys held with super, hyper or meta.
+    pub fn from_csi(params: &str, final_byte: char) -> Option<Self> {
+        let fields: Vec<&str> = params.split(';').collect();
+        let number = |field: &str| -> Option<u32> {
+            // Kitty may follow the code with the shifted and base layout keys
+            field.split(':').next()?.parse().ok()
+        };
+        // Anything after the modifiers is kitty's event types and text
+        let modifiers = |rest: &[&str]| rest.first().map_or(Some(1), |field| number(field));
+        match (final_byte, fields.as_slice()) {
+            ('u', [code, rest @ ..]) => Self::from_code(number(code)?, modifiers(rest)?),
+            ('~', ["27", modifiers, code]) => Self::from_code(number(code)?, number(modifiers)?),
+            ('~', [key, rest @ ..]) => {
+                Self::with_modifiers(ChordKey::from_tilde_number(number(key)?)?, modifiers(rest)?)
+            }
+            ('Z', [""]) => Some(Self {
+                shift: true,
+                ..Self::new(ChordKey::Tab)
+            }),
+            (_, [""]) => Some(Self::new(ChordKey::from_final_byte(final_byte)?)),
+            (_, ["1", rest @ ..]) => {
+                Self::with_modifiers(ChordKey::from_final_byte(final_byte)?, modifiers(rest)?)
+            }
+            _ => None,
+        }
+    }
+
+    fn from_code(code: u32, modifiers: u32) -> Option<Self> {
+        let key = match code {
+            13 => ChordKey::Enter,
+            9 => ChordKey::Tab,
+            8 | 127 => ChordKey::Backspace,
+            27 => ChordKey::Escape,
+            KITTY_FUNCTIONAL_KEYS.. => return None,
+            _ => {
+                let c = char::from_u32(code).filter(|c| !c.is_control())?;
+                ChordKey::Char(c.to_ascii_lowercase())
+            }
+        };
+        let chord = Self::with_modifiers(key, modifiers)?;
+        Some(Self {
+            // modifyOtherKeys sends the shifted letter as well
+            shift: chord.shift || matches!(code, 65..=90),
+            ..chord
+        })
+    }
+
+    /// The chord of a curses key that carries Shift, or of an extended key
+    /// named by terminfo such as `kUP5` for Ctrl+Up.
+    pub fn from_curses(input: Input, name: Option<&str>) -> Option<Self> {
+        let shifted = |key| {
+            Some(Self {
+                shift: true,
+                ..Self::new(key)
+            })
+        };
+        match input {
+            Input::KeySR => shifted(ChordKey::Up),
+            Input::KeySF => shifted(ChordKey::Down),
+            Input::KeySLeft => shifted(ChordKey::Left),
+            Input::KeySRight => shifted(ChordKey::Right),
+            Input::KeySHome => shifted(ChordKey::Home),
+            Input::KeySEnd => shifted(ChordKey::End),
+            Input::KeySPrevious => shifted(ChordKey::PageUp),
+            Input::KeySNext => shifted(ChordKey::PageDown),
+            Input::KeySIC => shifted(ChordKey::Insert),
+            Input::KeySDC => shifted(ChordKey::Delete),
+            // Shifted function keys, as xterm's terminfo describes them
+            Input::KeyF13 => shifted(ChordKey::F(1)),
+            Input::KeyF14 => shifted(ChordKey::F(2)),
+            Input::KeyF15 => shifted(ChordKey::F(3)),
+            Input::Unknown(_) => Self::from_terminfo_name(name?),
+            _ => None,
+        }
+    }
+
+    fn from_terminfo_name(name: &str) -> Option<Self> {
+        if let Some(number) = name
+            .strip_prefix("KEY_F(")
+            .and_then(|rest| rest.strip_suffix(')'))
+        {
+            // F13 and up are F1 to F12 with Shift, then with Ctrl
+            let number: u8 = number.parse().ok()?;
+            let (key, modifiers) = match number {
+                13..=24 => (number - 12, 1 + SHIFT),
+                25..=36 => (number - 24, 1 + CTRL),
+                _ => return None,
+            };
+            return Self::with_modifiers(ChordKey::F(key), modifiers);
+        }
+        let name = name.strip_prefix('k')?;
+        let split = name.find(|c: char| c.is_ascii_digit())?;
+        let (key, modifiers) = name.split_at(split);
+        Self::with_modifiers(ChordKey::from_terminfo_name(key)?, modifiers.parse().ok()?)
+    }
+
+    fn is_letter(&self) -> bool {
+        matches!(self.key, ChordKey::Char(c) if c.is_ascii_lowercase())
+    }
+
+    /// The name of the chord in the keymap, e.g. `"ctrl-shift-k"` or
+    /// `"ctrl-enter"`. Without Ctrl, a shifted letter is named by its
+    /// uppercase form as in `"alt-S"`.
+    pub fn name(&self) -> String {
+        let mut name = String::new();
+        if self.ctrl {
+            name.push_str("ctrl-");
+        }
+        if self.alt {
+            name.push_str("alt-");
+        }
+        let shift_in_letter = self.shift && !self.ctrl && self.is_letter();
+        if self.shift && !shift_in_letter {
+            name.push_str("shift-");
+        }
+        match self.key {
+            ChordKey::Char(' ') => name.push_str("space"),
+            ChordKey::Char(c) if shift_in_letter => name.push(c.to_ascii_uppercase()),
+            ChordKey::Char(c) => name.push(c),
+            ChordKey::Enter => name.push_str("enter"),
+            ChordKey::Tab => name.push_str("tab"),
+            ChordKey::Backspace => name.push_str("backspace"),
+            ChordKey::Escape => name.push_str("esc"),
+            ChordKey::Up => name.push_str("up"),
+            ChordKey::Down => name.push_str("down"),
+            ChordKey::Left => name.push_str("left"),
+            ChordKey::Right => name.push_str("right"),
+            ChordKey::Home => name.push_str("home"),
+            ChordKey::End => name.push_str("end"),
+            ChordKey::PageUp => name.push_str("pageup"),
+            ChordKey::PageDown => name.push_str("pagedown"),
+            ChordKey::Insert => name.push_str("insert"),
+            ChordKey::Delete => name.push_str("delete"),
+            ChordKey::F(number) => name.push_str(&format!("f{number}")),
+        }
+        name
+    }
+
+    /// The same key as the legacy encoding sends it, for chords that it can
+    /// express. The flag is whether Alt is held, as in `Event::Key`.
+    pub fn legacy(&self) -> Option<(Input, bool)> {
+        let Self {
+            key,
+            ctrl,
+            alt,
+            shift,
+        } = *self;
+        let input = match key {
+            ChordKey::Char(c) if ctrl => {
+                // Control characters cannot carry Alt
+                if alt {
+                    return None;
+                }
+                Input::Character(control_character(c, shift)?)
+            }
+            ChordKey::Char(c) if shift && self.is_letter() => {
+                Input::Character(c.to_ascii_uppercase())
+            }
+            ChordKey::Char(_) if shift => return None,
+            ChordKey::Char(c) => Input::Character(c),
+            _ if ctrl => return None,
+            ChordKey::Tab if shift && !alt => Input::KeyBTab,
+            _ if shift => return None,
+            ChordKey::Backspace if alt => Input::KeyBackspace,
+            ChordKey::Enter | ChordKey::Tab | ChordKey::Escape if alt => return None,
+            ChordKey::Enter => Input::Character('\r'),
+            ChordKey::Tab => Input::Character('\t'),
+            ChordKey::Backspace => Input::Character('\x7f'),
+            ChordKey::Escape => Input::Character('\x1b'),
+            ChordKey::Up => Input::KeyUp,
+            ChordKey::Down => Input::KeyDown,
+            ChordKey::Left => Input::KeyLeft,
+            ChordKey::Right => Input::KeyRight,
+            ChordKey::Home => Input::KeyHome,
+            ChordKey::End => Input::KeyEnd,
+            ChordKey::PageUp => Input::KeyPPage,
+            ChordKey::PageDown => Input::KeyNPage,
+            ChordKey::Insert => Input::KeyIC,
+            ChordKey::Delete => Input::KeyDC,
+            ChordKey::F(number) => *FUNCTION_KEYS.get(usize::from(number).checked_sub(1)?)?,
+        };
+        Some((input, alt))
+    }
+
+    /// The closest key the legacy encoding can express, dropping Shift, then
+    /// Ctrl, then Alt, for chords that nothing is bound to.
+    pub fn fallback(&self) -> (Input, bool) {
+        let unshifted = Self {
+            shift: false,
+            ..*self
+        };
+        let without_ctrl = Self {
+            ctrl: false,
+            ..unshifted
+        };
+        [*self, unshifted, without_ctrl]
+            .iter()
+            .find_map(KeyChord::legacy)
+            .or_else(|| KeyChord::new(self.key).legacy())
+            .unwrap_or((Input::Character('\x1b'), false))
+    }
+}
+
+/// The character the legacy encoding sends for Ctrl and `c`.
+fn control_character(c: char, shift: bool) -> Option<char> {
+    let control = match (c, shift) {
+        ('a'..='z', false) => c as u8 - b'a' + 1,
+        (' ' | '@' | '2', false) => 0,
+        ('[', false) => 0x1b,
+        ('\\', false) => 0x1c,
+        (']', false) => 0x1d,
+        ('^' | '6', false) => 0x1e,
+        ('_' | '/', false) | ('-', true) => 0x1f,
+        _ => return None,
+    };
+    Some(control as char)
+}
+
+/// Puts together characters that arrive as UTF-8 bytes, which an input
+/// method may send in several writes.
+#[derive(Debug, Default)]
+pub struct Utf8Assembler {
+    bytes: Vec<u8>,
+}
+
+impl Utf8Assembler {
+    /// Adds `byte` and returns the character it completes. Bytes that do not
+    /// fit in a sequence are dropped.
+    pub fn push(&mut self, byte: u8) -> Option<char> {
+        match byte {
+            0x00..=0x7f => {
+                self.drop_incomplete();
+                return Some(char::from(byte));
+            }
+            0x80..=0xbf if !self.bytes.is_empty() => self.bytes.push(byte),
+            0xc2..=0xf4 => {
+                self.drop_incomplete();
+                self.bytes.push(byte);
+                return None;
+            }
+            _ => {
+                warn!("Dropped a stray byte of input: {byte:#04x}");
+                return None;
+            }
+        }
+        let len = match self.bytes[0] {
+            0xc2..=0xdf => 2,
+            0xe0..=0xef => 3,
+            _ => 4,
+        };
+        if self.bytes.len() < len {
+            return None;
+        }
+        let decoded = std::str::from_utf8(&self.bytes)
+            .ok()
+            .and_then(|text| text.chars().next());
+        if decoded.is_none() {
+            warn!("Dropped invalid UTF-8 input: {:02x?}", self.bytes);
+        }
+        self.bytes.clear();
+        decoded
+    }
+
+    /// Whether part of a character has arrived.
+    pub fn is_pending(&self) -> bool {
+        !self.bytes.is_empty()
+    }
+
+    pub fn drop_incomplete(&mut self) {
+        if self.is_pending() {
+            warn!("Dropped incomplete UTF-8 input: {:02x?}", self.bytes);
+            self.bytes.clear();
+        }
+    }
+}
+
+/// A key read from the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyInput {
+    /// A key as curses reports it, and whether Alt was held
+    Plain(Input, bool),
+    /// A key with modifiers, decoded from an escape sequence
+    Chord(KeyChord),
+    /// The start of a bracketed paste, whose text follows
+    PasteStart,
+}
+
+#[derive(Debug, Default)]
+enum ParserState {
+    #[default]
+    Ground,
+    // After ESC. `alt` when that ESC came after another one, which is how
+    // some terminals send Alt with a key that is itself a sequence.
+    Escape {
+        alt: bool,
+    },
+    Csi {
+        params: String,
+        alt: bool,
+    },
+    // ESC O, which starts arrows and F1 to F4 in application mode
+    Ss3 {
+        alt: bool,
+    },
+}
+
+/// Turns the input of curses into keys. Curses decodes the sequences its
+/// terminfo entry knows; the others arrive byte by byte and are decoded
+/// here. A sequence cut short is given back as the keys that were typed once
+/// `timeout` is called.
+pub struct KeyParser {
+    state: ParserState,
+    ready: VecDeque<KeyInput>,
+    utf8: Utf8Assembler,
+    utf8_idle_reads: usize,
+    // The terminfo name of a key code of curses, for extended keys
+    key_name: fn(i32) -> Option<String>,
+}
+
+impl KeyParser {
+    pub fn new(key_name: fn(i32) -> Option<String>) -> Self {
+        Self {
+            state: ParserState::Ground,
+            ready: VecDeque::new(),
+            utf8: Utf8Assembler::default(),
+            utf8_idle_reads: 0,
+            key_name,
+        }
+    }
+
+    /// Whether the parser is in the middle of a sequence or a character.
+    pub fn is_pending(&self) -> bool {
+        !matches!(self.state, ParserState::Ground) || self.utf8.is_pending()
+    }
+
+    pub fn next_key(&mut self) -> Option<KeyInput> {
+        self.ready.pop_front()
+    }
+
+    /// Feeds a byte of input, which is decoded as UTF-8.
+    pub fn feed_byte(&mut self, byte: u8) {
+        self.utf8_idle_reads = 0;
+        if let Some(c) = self.utf8.push(byte) {
+            self.feed(Input::Character(c));
+        }
+    }
+
+    pub fn feed(&mut self, input: Input) {
+        let state = std::mem::take(&mut self.state);
+        match (state, input) {
+            (ParserState::Ground, Input::Character('\x1b')) => {
+                self.state = ParserState::Escape { alt: false };
+            }
+            (ParserState::Ground, input) => self.push_key(input, false),
+            (ParserState::Escape { alt }, Input::Character('\x1b')) => {
+                // A third ESC: the first one was pressed on its own
+                if alt {
+                    self.push_escape();
+                }
+                self.state = ParserState::Escape { alt: true };
+            }
+            (ParserState::Escape { alt }, Input::Character('[')) => {
+                self.state = ParserState::Csi {
+                    params: String::new(),
+                    alt,
+                };
+            }
+            (ParserState::Escape { alt }, Input::Character('O')) => {
+                self.state = ParserState::Ss3 { alt };
+            }
+            (ParserState::Escape { alt }, Input::KeyResize | Input::KeyMouse) => {
+                self.flush(ParserState::Escape { alt });
+                self.push_key(input, false);
+            }
+            (ParserState::Escape { alt }, input) => {
+                if alt {
+                    self.push_escape();
+                }
+                self.push_key(input, true);
+            }
+            (ParserState::Csi { mut params, alt }, Input::Character(c @ '\x20'..='\x3f'))
+                if params.len() < CSI_MAX_PARAMS_LEN =>
+            {
+                params.push(c);
+                self.state = ParserState::Csi { params, alt };
+            }
+            (ParserState::Csi { params, alt }, Input::Character(c @ '\x40'..='\x7e')) => {
+                self.finish_csi(&params, c, alt);
+            }
+            (ParserState::Ss3 { alt }, Input::Character(c)) => {
+                let key = match c {
+                    // Enter of the keypad
+                    'M' => Some(ChordKey::Enter),
+                    _ => ChordKey::from_final_byte(c),
+                };
+                match key {
+                    Some(key) => self.push_chord(KeyChord::new(key), alt),
+                    None => {
+                        self.flush(ParserState::Ss3 { alt });
+                        self.feed(input);
+                    }
+                }
+            }
+            (state, input) => {
+                self.flush(state);
+                self.feed(input);
+            }
+        }
+    }
+
+    /// Gives back what was typed of a sequence that did not complete, e.g. a
+    /// bare ESC. The rest of a character is waited for a few more times.
+    pub fn timeout(&mut self) {
+        if self.utf8.is_pending() {
+            self.utf8_idle_reads += 1;
+            if self.utf8_idle_reads < UTF8_MAX_IDLE_READS {
+                return;
+            }
+            self.utf8.drop_incomplete();
+        }
+        let state = std::mem::take(&mut self.state);
+        self.flush(state);
+    }
+
+    fn flush(&mut self, state: ParserState) {
+        match state {
+            ParserState::Ground => {}
+            ParserState::Escape { alt } => {
+                self.push_escape();
+                if alt {
+                    self.push_escape();
+                }
+            }
+            ParserState::Csi { params, alt } => {
+                if alt {
+                    self.push_escape();
+                }
+                self.push_key(Input::Character('['), true);
+                for c in params.chars() {
+                    self.push_key(Input::Character(c), false);
+                }
+            }
+            ParserState::Ss3 { alt } => {
+                if alt {
+                    self.push_escape();
+                }
+                self.push_key(Input::Character('O'), true);
+            }
+        }
+    }
+
+    fn finish_csi(&mut self, params: &str, final_byte: char, alt: bool) {
+        if final_byte == '~' && params == PASTE_START {
+            self.ready.push_back(KeyInput::PasteStart);
+            return;
+        }
+        // In application cursor mode, which curses turns on, arrows without
+        // modifiers come as ESC O. The CSI form is how some terminals, such
+        // as macOS Terminal, send them with Alt.
+        let bare_arrow = params.is_empty() && matches!(final_byte, 'A'..='D');
+        // Other sequences, such as focus reports, are dropped
+        if let Some(chord) = KeyChord::from_csi(params, final_byte) {
+            self.push_chord(chord, alt || bare_arrow);
+        }
+    }
+
+    fn push_escape(&mut self) {
+        self.ready
+            .push_back(KeyInput::Plain(Input::Character('\x1b'), false));
+    }
+
+    fn push_chord(&mut self, chord: KeyChord, alt: bool) {
+        self.ready.push_back(KeyInput::Chord(KeyChord {
+            alt: chord.alt || alt,
+            ..chord
+        }));
+    }
+
+    fn push_key(&mut self, input: Input, alt: bool) {
+        let name = match input {
+            Input::Unknown(code) => (self.key_name)(code),
+            _ => None,
+        };
+        if let Some(chord) = KeyChord::from_curses(input, name.as_deref()) {
+            self.push_chord(chord, alt);
+            return;
+        }
+        let input = match input {
+            Input::Character('\x7f') if alt => Input::KeyBackspace,
+            _ => input,
+        };
+        self.ready.push_back(KeyInput::Plain(input, alt));
+    }
+}
@@ -1,13 +1,38 @@
+pub mod agenda;
 pub mod backup;
+pub mod cleanup;
+pub mod clock;
 pub mod config;
+pub mod copy_format;
+pub mod dashboard;
+pub mod diff;
 pub mod document;
 pub mod editor;
 pub mod error;
+pub mod file_settings;
+pub mod git;
+pub mod ical;
+pub mod inbox;
+pub mod journal;
+pub mod keyboard;
+pub mod loader;
+pub mod paste;
 pub mod persistence;
+pub mod replay;
+pub mod scratch;
+pub mod screen;
+pub mod spellcheck;
+pub mod task_timer;
 pub mod terminal;
+pub mod todotxt;
+pub mod trash;
 
 pub enum Event {
     Key(pancurses::Input, bool), // Input, is_alt_pressed
+    // A key with modifiers that `Key` cannot express
+    Chord(keyboard::KeyChord),
+    Mouse(editor::mouse::MouseEvent),
+    Paste(String),
     Resize,
     Quit,
     ClearMessage,
@@ -15,32 +40,162 @@ pub enum Event {
 
 use editor::Editor;
 use error::Result;
+use loader::FileLoader;
+use replay::{InputLog, InputReplay};
+use std::time::{Duration, Instant};
 use terminal::Terminal;
 
+// How long to sleep at most while waiting for the next replayed event, so
+// that timers keep ticking
+const REPLAY_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+// Files that load faster than this open without showing any progress
+const LOAD_PROGRESS_DELAY: Duration = Duration::from_millis(200);
+
+/// Reads `filename` in the background, showing progress on the status line
+/// once it takes a while. Returns `None` if the user cancels with Ctrl-G or
+/// Ctrl-C.
+fn load_with_progress(terminal: &Terminal, filename: &str) -> Result<Option<Result<String>>> {
+    let loader = FileLoader::start(filename);
+    let started = Instant::now();
+    while !loader.is_finished() {
+        if started.elapsed() >= LOAD_PROGRESS_DELAY {
+            let (loaded, total) = loader.progress();
+            let window = terminal.window();
+            let message = format!(
+                "{} (Ctrl-G to cancel)",
+                loader::describe_progress(filename, loaded, total)
+            );
+            window.erase();
+            window.mvaddstr(terminal.size().0.saturating_sub(1) as i32, 0, &message);
+            window.refresh();
+        }
+        // Waits for up to the terminal's input timeout
+        match terminal.next_event()? {
+            Some(Event::Key(pancurses::Input::Character('\x07'), _)) | Some(Event::Quit) => {
+                terminal::CTRL_C_COUNT.store(0, std::sync::atomic::Ordering::SeqCst);
+                // Not joined: the thread may still be stuck in a slow read
+                loader.cancel();
+                return Ok(None);
+            }
+            _ => {}
+        }
+    }
+    Ok(loader.finish())
+}
+
+fn next_replayed_event(replay: &mut InputReplay) -> Option<Event> {
+    if let Some(wait) = replay.time_to_next() {
+        std::thread::sleep(wait.min(REPLAY_POLL_INTERVAL));
+    }
+    replay.next_event()
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn run_editor(
     terminal: &Terminal,
     filename: Option<String>,
     line: Option<usize>,
     column: Option<usize>,
     no_exit_on_save: bool,
+    follow: bool,
     keymap: config::Keymap,
+    settings: config::EditorSettings,
+    input_log: Option<InputLog>,
+    safe_mode: bool,
+    session: Option<(String, Option<persistence::Session>)>,
 ) -> Result<()> {
     let (screen_rows, screen_cols) = terminal.size();
-    let mut editor = Editor::new(filename, line, column);
+    let mut editor = match filename {
+        Some(filename) => match load_with_progress(terminal, &filename)? {
+            Some(content) if safe_mode => {
+                Editor::from_loaded_fresh(filename, content, line, column)
+            }
+            Some(content) => Editor::from_loaded(filename, content, line, column),
+            None => return Ok(()),
+        },
+        None => Editor::new(None, line, column),
+    };
     editor.set_keymap(keymap);
+    editor.set_settings(settings);
     editor.set_no_exit_on_save(no_exit_on_save);
+    editor.set_monochrome(terminal.is_monochrome());
+    if safe_mode {
+        // Nothing from earlier sessions is brought back
+        editor.set_message("Safe mode: default settings, no restored state, no system clipboard.");
+    } else {
+        editor.enable_journal();
+        editor.enable_scratch_autosave();
+        editor.enable_task_timer_persistence();
+        editor.enable_recent_files();
+        editor.enable_bookmarks();
+    }
+    editor.warn_about_file_format();
+    if let Some((name, saved)) = session {
+        editor.restore_session(&name, saved.as_ref());
+    }
+    if follow {
+        editor.enable_follow();
+    }
     editor.update_screen_size(screen_rows, screen_cols);
 
+    let (mut recorder, mut replay) = match input_log {
+        Some(InputLog::Record(recorder)) => (Some(recorder), None),
+        Some(InputLog::Replay(replay)) => (None, Some(replay)),
+        None => (None, None),
+    };
+    if let Some(recorder) = &mut recorder {
+        recorder.start(terminal.size());
+    }
+    if let Some(replay) = &mut replay {
+        replay.start();
+        editor.set_clock(replay.clock());
+    }
+
     loop {
-        editor.update_screen_size(terminal.size().0, terminal.size().1);
+        editor.autosave_tick();
+        editor.undo_snapshot_tick();
+        editor.follow_tick();
+        editor.external_change_tick();
+        editor.git_gutter_tick();
+        editor.shell_tick();
+        editor.project_search_tick();
+        if editor.pomodoro_tick() {
+            pancurses::beep();
+        }
+        let (rows, cols) = replay
+            .as_ref()
+            .and_then(InputReplay::screen_size)
+            .unwrap_or_else(|| terminal.size());
+        editor.update_screen_size(rows, cols);
+        if let Some(colors) = editor.take_pending_colors() {
+            terminal.apply_colors(&colors)?;
+        }
         editor.draw(terminal.window());
 
-        if let Some(event) = terminal.next_event()? {
+        let event = match &mut replay {
+            Some(replay) => next_replayed_event(replay),
+            None => terminal.next_event()?,
+        };
+        if let (Some(recorder), Some(event)) = (&mut recorder, &event) {
+            recorder.record(event, terminal.size());
+        }
+        if let Some(event) = event {
             match event {
                 Event::Key(key, is_alt_pressed) => {
                     editor.process_input(key, is_alt_pressed)?;
                     terminal::CTRL_C_COUNT.store(0, std::sync::atomic::Ordering::SeqCst);
                 }
+                Event::Chord(chord) => {
+                    editor.process_chord(chord)?;
+                    terminal::CTRL_C_COUNT.store(0, std::sync::atomic::Ordering::SeqCst);
+                }
+                Event::Mouse(mouse_event) => {
+                    editor.handle_mouse_event(mouse_event);
+                }
+                Event::Paste(text) => {
+                    editor.process_paste(text)?;
+                }
                 Event::Resize => {
                     // Handled by update_screen_size at the beginning of the loop
                 }
@@ -66,10 +221,19 @@ pub fn run_editor(
             }
         }
 
+        if let Some(finished) = replay.take_if(|replay| replay.is_finished()) {
+            editor.set_message(&format!(
+                "Replay finished after {} events.",
+                finished.replayed()
+            ));
+        }
+
         if editor.should_quit {
             break;
         }
     }
+    // Also covers quitting with a double Ctrl+C
+    editor.save_scratch();
 
     Ok(())
 }
@@ -0,0 +1,123 @@
+use crate::error::{DmacsError, Result};
+use std::fs::File;
+use std::io::{self, Read};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::thread::{self, JoinHandle};
+
+// Small enough that progress moves and cancelling is quick on slow mounts
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Reads `reader` to the end in chunks, adding the bytes read so far to
+/// `progress`. Returns `None` as soon as `cancelled` is set.
+pub fn read_with_progress(
+    mut reader: impl Read,
+    progress: &AtomicU64,
+    cancelled: &AtomicBool,
+) -> Option<io::Result<Vec<u8>>> {
+    let mut content = Vec::new();
+    let mut chunk = vec![0; CHUNK_SIZE];
+    loop {
+        if cancelled.load(Ordering::SeqCst) {
+            return None;
+        }
+        match reader.read(&mut chunk) {
+            Ok(0) => return Some(Ok(content)),
+            Ok(n) => {
+                content.extend_from_slice(&chunk[..n]);
+                progress.fetch_add(n as u64, Ordering::SeqCst);
+            }
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Some(Err(e)),
+        }
+    }
+}
+
+/// Reads a file on a background thread so that the UI can show progress and
+/// let the user cancel while a slow filesystem is being read.
+pub struct FileLoader {
+    loaded: Arc<AtomicU64>,
+    total: Arc<AtomicU64>,
+    cancelled: Arc<AtomicBool>,
+    handle: JoinHandle<Option<Result<String>>>,
+}
+
+impl FileLoader {
+    pub fn start(filename: &str) -> Self {
+        let loaded = Arc::new(AtomicU64::new(0));
+        let total = Arc::new(AtomicU64::new(0));
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let filename = filename.to_string();
+        let handle = {
+            let (loaded, total, cancelled) = (loaded.clone(), total.clone(), cancelled.clone());
+            thread::spawn(move || {
+                let file = match File::open(&filename) {
+                    Ok(file) => file,
+                    Err(e) => return Some(Err(DmacsError::Io(e))),
+                };
+                if let Ok(metadata) = file.metadata() {
+                    total.store(metadata.len(), Ordering::SeqCst);
+                }
+                let bytes = match read_with_progress(file, &loaded, &cancelled)? {
+                    Ok(bytes) => bytes,
+                    Err(e) => return Some(Err(DmacsError::Io(e))),
+                };
+                Some(
+                    String::from_utf8(bytes)
+                        .map_err(|e| DmacsError::Io(io::Error::new(io::ErrorKind::InvalidData, e))),
+                )
+            })
+        };
+        Self {
+            loaded,
+            total,
+            cancelled,
+            handle,
+        }
+    }
+
+    /// Bytes read so far and the size of the file (0 while it is unknown).
+    pub fn progress(&self) -> (u64, u64) {
+        (
+            self.loaded.load(Ordering::SeqCst),
+            self.total.load(Ordering::SeqCst),
+        )
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Waits for the thread and returns the content, or `None` if loading was
+    /// cancelled.
+    pub fn finish(self) -> Option<Result<String>> {
+        self.handle.join().unwrap_or_else(|_| {
+            Some(Err(DmacsError::Document(
+                "Loading thread panicked".to_string(),
+            )))
+        })
+    }
+}
+
+/// Describes the progress of a load for the status line, e.g.
+/// `Loading notes.md... 1.5 MB / 12.0 MB (12%)`.
+pub fn describe_progress(filename: &str, loaded: u64, total: u64) -> String {
+    const MB: f64 = 1024.0 * 1024.0;
+    let name = std::path::Path::new(filename)
+        .file_name()
+        .map_or(filename.into(), |name| name.to_string_lossy());
+    if total == 0 {
+        format!("Loading {name}... {:.1} MB", loaded as f64 / MB)
+    } else {
+        format!(
+            "Loading {name}... {:.1} MB / {:.1} MB ({}%)",
+            loaded as f64 / MB,
+            total as f64 / MB,
+            (loaded * 100 / total).min(100)
+        )
+    }
+}
@@ -1,8 +1,15 @@
 use dmacs::backup::BackupManager;
 use dmacs::config::Config as DmacsConfig;
+use dmacs::dashboard;
 use dmacs::error::Result;
+use dmacs::ical;
+use dmacs::inbox;
+use dmacs::persistence;
+use dmacs::replay::{InputLog, InputRecorder, InputReplay};
 use dmacs::run_editor;
 use dmacs::terminal::Terminal;
+use dmacs::todotxt;
+use dmacs::trash::Trash;
 use simplelog::{Config, LevelFilter, WriteLogger};
 use std::env;
 use std::fs::File;
@@ -28,7 +35,20 @@ fn main() -> Result<()> {
     let mut column: Option<usize> = None;
     let mut debug_mode = false;
     let mut no_exit_on_save = false;
+    let mut no_color = false;
+    let mut follow = false;
+    let mut reopen_last_closed = false;
+    let mut safe_mode = false;
     let mut restore_path: Option<String> = None;
+    let mut trash_list = false;
+    let mut trash_restore_name: Option<String> = None;
+    let mut capture_text: Option<String> = None;
+    let mut todotxt_conversion: Option<(bool, String)> = None; // (export, path)
+    let mut ics_export_path: Option<String> = None;
+    let mut dashboard_dir: Option<String> = None;
+    let mut json = false;
+    let mut input_log_path: Option<(bool, String)> = None; // (record, path)
+    let mut session_name: Option<String> = None;
 
     // Simple argument parsing
     let mut i = 1;
@@ -66,6 +86,10 @@ fn main() -> Result<()> {
             match arg.as_str() {
                 "--debug" => debug_mode = true,
                 "--no-exit-on-save" => no_exit_on_save = true,
+                "--no-color" => no_color = true,
+                "--follow" => follow = true,
+                "--last" => reopen_last_closed = true,
+                "--safe" => safe_mode = true,
                 "--restore" => {
                     if i + 1 < args.len() {
                         restore_path = Some(args[i + 1].clone());
@@ -75,6 +99,71 @@ fn main() -> Result<()> {
                         return Ok(());
                     }
                 }
+                "--trash-list" => trash_list = true,
+                "--trash-restore" => {
+                    if i + 1 < args.len() {
+                        trash_restore_name = Some(args[i + 1].clone());
+                        i += 1; // Skip next argument
+                    } else {
+                        eprintln!("Error: --trash-restore requires a name from --trash-list.");
+                        return Ok(());
+                    }
+                }
+                "--capture" => {
+                    if i + 1 < args.len() {
+                        capture_text = Some(args[i + 1].clone());
+                        i += 1; // Skip next argument
+                    } else {
+                        eprintln!("Error: --capture requires the text to capture.");
+                        return Ok(());
+                    }
+                }
+                "--export-ics" => {
+                    if i + 1 < args.len() {
+                        ics_export_path = Some(args[i + 1].clone());
+                        i += 1; // Skip next argument
+                    } else {
+                        eprintln!("Error: --export-ics requires a file path.");
+                        return Ok(());
+                    }
+                }
+                "--dashboard" => {
+                    if i + 1 < args.len() {
+                        dashboard_dir = Some(args[i + 1].clone());
+                        i += 1; // Skip next argument
+                    } else {
+                        eprintln!("Error: --dashboard requires a directory.");
+                        return Ok(());
+                    }
+                }
+                "--json" => json = true,
+                "--export-todotxt" | "--import-todotxt" => {
+                    if i + 1 < args.len() {
+                        todotxt_conversion = Some((arg == "--export-todotxt", args[i + 1].clone()));
+                        i += 1; // Skip next argument
+                    } else {
+                        eprintln!("Error: {arg} requires a file path.");
+                        return Ok(());
+                    }
+                }
+                "--session" => {
+                    if i + 1 < args.len() {
+                        session_name = Some(args[i + 1].clone());
+                        i += 1; // Skip next argument
+                    } else {
+                        eprintln!("Error: --session requires a session name.");
+                        return Ok(());
+                    }
+                }
+                "--record-input" | "--replay-input" => {
+                    if i + 1 < args.len() {
+                        input_log_path = Some((arg == "--record-input", args[i + 1].clone()));
+                        i += 1; // Skip next argument
+                    } else {
+                        eprintln!("Error: {arg} requires a file path.");
+                        return Ok(());
+                    }
+                }
                 _ => {}
             }
         }
@@ -99,6 +188,130 @@ fn main() -> Result<()> {
         return Ok(());
     }
 
+    if trash_list {
+        let trashed = Trash::new()?.list()?;
+        if trashed.is_empty() {
+            println!("The trash is empty.");
+        }
+        for file in trashed {
+            println!(
+                "{}  {}  {}",
+                file.name,
+                file.deleted_at.format("%Y-%m-%d %H:%M"),
+                file.original_path.display()
+            );
+        }
+        return Ok(());
+    }
+
+    if let Some(name) = trash_restore_name {
+        match Trash::new()?.restore(&name) {
+            Ok(path) => println!("Restored {}", path.display()),
+            Err(e) => eprintln!("Failed to restore {name}: {e}"),
+        }
+        return Ok(());
+    }
+
+    if let Some(dir) = dashboard_dir {
+        if !std::path::Path::new(&dir).is_dir() {
+            eprintln!("Error: {dir} is not a directory.");
+            return Ok(());
+        }
+        let summaries = dashboard::summarize(dir.as_ref(), chrono::Local::now().date_naive());
+        if json {
+            println!("{}", dashboard::to_json(&summaries));
+        } else {
+            print!("{}", dashboard::to_table(&summaries));
+        }
+        return Ok(());
+    }
+
+    if let Some(path) = ics_export_path {
+        match std::fs::read_to_string(&path) {
+            Ok(content) => print!("{}", ical::export(&content, chrono::Utc::now())),
+            Err(e) => eprintln!("Failed to read {path}: {e}"),
+        }
+        return Ok(());
+    }
+
+    if let Some((export, path)) = todotxt_conversion {
+        match std::fs::read_to_string(&path) {
+            Ok(content) if export => print!("{}", todotxt::export(&content)),
+            Ok(content) => print!("{}", todotxt::import(&content)),
+            Err(e) => eprintln!("Failed to read {path}: {e}"),
+        }
+        return Ok(());
+    }
+
+    let dmacs_config = if safe_mode {
+        // Settings that only touch the terminal and the file being edited
+        let mut config = DmacsConfig::default();
+        config.editor.clipboard = Some("off".to_string());
+        config
+    } else {
+        DmacsConfig::load()
+    };
+
+    if let Some(text) = capture_text {
+        match &dmacs_config.editor.inbox_file {
+            Some(inbox_file) => {
+                let path = inbox::resolve_path(inbox_file);
+                match inbox::capture(&path, &text) {
+                    Ok(line) => println!("Captured to {}: {line}", path.display()),
+                    Err(e) => eprintln!("Failed to capture to {}: {e}", path.display()),
+                }
+            }
+            None => eprintln!("Error: set inbox_file under [editor] in ~/.dmacs/config.toml."),
+        }
+        return Ok(());
+    }
+
+    if filename.is_none() && reopen_last_closed {
+        filename = persistence::closed_files_path(None)
+            .and_then(|path| persistence::take_last_closed_file(&path, None));
+        if filename.is_none() {
+            eprintln!("No closed files to reopen.");
+            return Ok(());
+        }
+    }
+
+    let session = match session_name {
+        Some(name) if !persistence::is_valid_session_name(&name) => {
+            eprintln!("Error: invalid session name: {name}");
+            return Ok(());
+        }
+        Some(name) => {
+            let saved = persistence::session_path(None, &name)
+                .filter(|path| path.exists())
+                .map(|path| persistence::load_session(&path));
+            match saved {
+                Some(Err(e)) => {
+                    eprintln!("Failed to read session {name}: {e}");
+                    return Ok(());
+                }
+                Some(Ok(saved)) => {
+                    if filename.is_none() {
+                        filename = saved
+                            .file_path
+                            .clone()
+                            .filter(|file_path| std::path::Path::new(file_path).exists());
+                    }
+                    Some((name, Some(saved)))
+                }
+                None => Some((name, None)),
+            }
+        }
+        None => None,
+    };
+
+    if filename.is_none() && dmacs_config.editor.reopen_last_session {
+        filename = persistence::recent_files_path(None)
+            .map(|path| persistence::load_recent_files(&path))
+            .and_then(|files| files.into_iter().next())
+            .map(|file| file.file_path)
+            .filter(|file_path| std::path::Path::new(file_path).exists());
+    }
+
     let absolute_filename = if let Some(fname) = filename {
         match std::fs::canonicalize(&fname) {
             Ok(path) => {
@@ -119,16 +332,37 @@ fn main() -> Result<()> {
         None
     };
 
-    let dmacs_config = DmacsConfig::load();
+    let input_log = match input_log_path {
+        Some((true, path)) => match InputRecorder::create(std::path::Path::new(&path)) {
+            Ok(recorder) => Some(InputLog::Record(recorder)),
+            Err(e) => {
+                eprintln!("Failed to create {path}: {e}");
+                return Ok(());
+            }
+        },
+        Some((false, path)) => match InputReplay::load(std::path::Path::new(&path)) {
+            Ok(replay) => Some(InputLog::Replay(replay)),
+            Err(e) => {
+                eprintln!("Failed to read {path}: {e}");
+                return Ok(());
+            }
+        },
+        None => None,
+    };
 
-    let terminal = Terminal::new(&dmacs_config.colors)?;
+    let terminal = Terminal::new(&dmacs_config.colors, no_color)?;
     run_editor(
         &terminal,
         absolute_filename,
         line,
         column,
         no_exit_on_save,
+        follow,
         dmacs_config.keymap,
+        dmacs_config.editor,
+        input_log,
+        safe_mode,
+        session,
     )?;
 
     Ok(())
@@ -0,0 +1,78 @@
+/// Bullet characters used by web pages and word processors, with the nesting
+/// level they usually stand for when copied without indentation.
+const BULLETS: [(char, usize); 10] = [
+    ('•', 0),
+    ('●', 0),
+    ('·', 0),
+    ('∙', 0),
+    ('‣', 0),
+    ('◦', 1),
+    ('○', 1),
+    ('▪', 2),
+    ('■', 2),
+    ('▫', 2),
+];
+const UNCHECKED_BOXES: [char; 1] = ['☐'];
+const CHECKED_BOXES: [char; 4] = ['☑', '☒', '✓', '✔'];
+
+/// Cleans up text copied from browsers so that it reads as plain Markdown.
+pub fn normalize(text: &str) -> String {
+    let text = text.replace("\r\n", "\n").replace('\r', "\n");
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.split('\n') {
+        let line = normalize_line(&normalize_chars(line));
+        // Pages copy with blank lines between every paragraph; keep one
+        let is_repeated_blank = line.is_empty() && lines.last().is_some_and(|last| last.is_empty());
+        if !is_repeated_blank {
+            lines.push(line);
+        }
+    }
+    lines.join("\n")
+}
+
+fn normalize_chars(line: &str) -> String {
+    line.chars()
+        .filter_map(|c| match c {
+            '\u{a0}' | '\u{2007}' | '\u{202f}' => Some(' '),
+            '\u{200b}' | '\u{feff}' => None,
+            '‘' | '’' | '‚' | '′' => Some('\''),
+            '“' | '”' | '„' | '″' => Some('"'),
+            c => Some(c),
+        })
+        .collect()
+}
+
+fn normalize_line(line: &str) -> String {
+    let trimmed = line.trim_start();
+    let indent: String = line[..line.len() - trimmed.len()].replace('\t', "  ");
+    let mut chars = trimmed.chars();
+    let Some(first) = chars.next() else {
+        return String::new();
+    };
+    let rest = chars.as_str().trim_start();
+
+    let marker = if UNCHECKED_BOXES.contains(&first) {
+        Some(("- [ ] ", 0))
+    } else if CHECKED_BOXES.contains(&first) {
+        Some(("- [x] ", 0))
+    } else if let Some(&(_, level)) = BULLETS.iter().find(|(bullet, _)| *bullet == first) {
+        Some(("- ", level))
+    } else if matches!(first, '*' | '+') && trimmed[1..].starts_with(' ') {
+        Some(("- ", 0))
+    } else {
+        None
+    };
+
+    let line = match marker {
+        Some((marker, level)) => {
+            let indent = if indent.is_empty() {
+                "  ".repeat(level)
+            } else {
+                indent
+            };
+            format!("{indent}{marker}{rest}")
+        }
+        None => format!("{indent}{trimmed}"),
+    };
+    line.trim_end().to_string()
+}
@@ -3,12 +3,18 @@ use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::fs;
 use std::io::{self, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime};
 
 const DMACS_CONFIG_DIR: &str = ".dmacs";
+pub const STATE_DIR_ENV: &str = "DMACS_STATE_DIR";
 const CURSOR_POSITIONS_SUBDIR: &str = "cursor_positions";
 const CLEANUP_THRESHOLD_DAYS: u64 = 3;
+const RECENT_FILES_NAME: &str = "recent_files.json";
+const MAX_RECENT_FILES: usize = 30;
+const CLOSED_FILES_NAME: &str = "closed_files.json";
+const BOOKMARKS_NAME: &str = "bookmarks.json";
+const SESSIONS_SUBDIR: &str = "sessions";
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct CursorPosition {
@@ -20,10 +26,61 @@ pub struct CursorPosition {
     pub scroll_col_offset: usize,
 }
 
+/// A file opened in an earlier session, most recent first in the list.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RecentFile {
+    pub file_path: String,
+    pub opened_at: SystemTime,
+}
+
+/// A file that was switched away from or quit, most recently closed first in
+/// the list.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ClosedFile {
+    pub file_path: String,
+    pub closed_at: SystemTime,
+}
+
+/// A line marked in a file. Its text finds the line again when lines were
+/// added or removed above it while the bookmark could not follow.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Bookmark {
+    pub file_path: String,
+    pub name: String,
+    pub line: usize,
+    pub text: String,
+}
+
+/// The editor as it was left, saved under a name and restored by
+/// `dmacs --session <name>`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Session {
+    pub file_path: Option<String>,
+    pub cursor_x: usize,
+    pub cursor_y: usize,
+    pub scroll_row_offset: usize,
+    pub scroll_col_offset: usize,
+    // Whether the scratch buffer was shown over the file
+    pub scratch: bool,
+    // Header lines of the folded sections of the file
+    pub folds: Vec<usize>,
+    pub subword_mode: Option<bool>,
+    pub hint_bar: bool,
+    pub saved_at: SystemTime,
+}
+
+/// Where backups, journals, cursor positions and the other files dmacs keeps
+/// between sessions go: `$DMACS_STATE_DIR`, or `~/.dmacs`.
+pub fn state_dir() -> Option<PathBuf> {
+    match std::env::var_os(STATE_DIR_ENV) {
+        Some(dir) if !dir.is_empty() => Some(PathBuf::from(dir)),
+        _ => dirs::home_dir().map(|home| home.join(DMACS_CONFIG_DIR)),
+    }
+}
+
 fn get_config_dir() -> Result<PathBuf, io::Error> {
-    let home_dir = dirs::home_dir()
+    let config_dir = state_dir()
         .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Home directory not found"))?;
-    let config_dir = home_dir.join(DMACS_CONFIG_DIR);
     if !config_dir.exists() {
         fs::create_dir_all(&config_dir)?;
     }
@@ -142,6 +199,31 @@ pub fn get_cursor_position(
     None
 }
 
+/// Moves the saved cursor position of `old_path` to `new_path`, which now
+/// holds the same content.
+pub fn move_cursor_position(old_path: &str, new_path: &str, last_modified: SystemTime) {
+    let Some(pos) = load_cursor_position(old_path) else {
+        return;
+    };
+    let moved = CursorPosition {
+        file_path: new_path.to_string(),
+        last_modified,
+        ..pos
+    };
+    if let Err(e) = save_cursor_position(moved) {
+        error!("Failed to move cursor position to {new_path}: {e}");
+        return;
+    }
+    match get_cursor_pos_file_path(old_path) {
+        Ok(path) => {
+            if let Err(e) = fs::remove_file(&path) {
+                error!("Failed to remove {}: {e}", path.display());
+            }
+        }
+        Err(e) => error!("Failed to get cursor position file path for {old_path}: {e}"),
+    }
+}
+
 pub fn cleanup_old_cursor_position_files() {
     debug!("Starting cleanup of old cursor position files.");
     let cursor_pos_dir = match get_cursor_pos_dir() {
@@ -205,3 +287,163 @@ pub fn cleanup_old_cursor_position_files() {
     }
     debug!("Finished cleanup of old cursor position files.");
 }
+
+pub fn recent_files_path(base_dir: Option<PathBuf>) -> Option<PathBuf> {
+    config_file_path(base_dir, RECENT_FILES_NAME)
+}
+
+pub fn closed_files_path(base_dir: Option<PathBuf>) -> Option<PathBuf> {
+    config_file_path(base_dir, CLOSED_FILES_NAME)
+}
+
+pub fn bookmarks_path(base_dir: Option<PathBuf>) -> Option<PathBuf> {
+    config_file_path(base_dir, BOOKMARKS_NAME)
+}
+
+fn config_file_path(base_dir: Option<PathBuf>, name: &str) -> Option<PathBuf> {
+    let config_dir = match base_dir {
+        Some(dir) => dir.join(DMACS_CONFIG_DIR),
+        None => match get_config_dir() {
+            Ok(dir) => dir,
+            Err(e) => {
+                error!("Failed to get config directory: {e}");
+                return None;
+            }
+        },
+    };
+    if let Err(e) = fs::create_dir_all(&config_dir) {
+        error!("Failed to create {}: {e}", config_dir.display());
+        return None;
+    }
+    Some(config_dir.join(name))
+}
+
+pub fn load_recent_files(path: &Path) -> Vec<RecentFile> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    serde_json::from_str(&content).unwrap_or_else(|e| {
+        error!("Failed to parse {}: {e}", path.display());
+        Vec::new()
+    })
+}
+
+/// Moves `file_path` to the top of the recent files list.
+pub fn record_recent_file(path: &Path, file_path: &str) {
+    let mut files = load_recent_files(path);
+    files.retain(|file| file.file_path != file_path);
+    files.insert(
+        0,
+        RecentFile {
+            file_path: file_path.to_string(),
+            opened_at: SystemTime::now(),
+        },
+    );
+    files.truncate(MAX_RECENT_FILES);
+    let result = serde_json::to_string_pretty(&files)
+        .map_err(io::Error::other)
+        .and_then(|json| fs::write(path, json));
+    if let Err(e) = result {
+        error!("Failed to update {}: {e}", path.display());
+    }
+}
+
+pub fn load_closed_files(path: &Path) -> Vec<ClosedFile> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    serde_json::from_str(&content).unwrap_or_else(|e| {
+        error!("Failed to parse {}: {e}", path.display());
+        Vec::new()
+    })
+}
+
+/// Moves `file_path` to the top of the closed files list.
+pub fn record_closed_file(path: &Path, file_path: &str) {
+    let mut files = load_closed_files(path);
+    files.retain(|file| file.file_path != file_path);
+    files.insert(
+        0,
+        ClosedFile {
+            file_path: file_path.to_string(),
+            closed_at: SystemTime::now(),
+        },
+    );
+    files.truncate(MAX_RECENT_FILES);
+    write_closed_files(path, &files);
+}
+
+/// Takes the most recently closed file that still exists, other than
+/// `current`, off the closed files list.
+pub fn take_last_closed_file(path: &Path, current: Option<&str>) -> Option<String> {
+    let mut files = load_closed_files(path);
+    let index = files.iter().position(|file| {
+        Some(file.file_path.as_str()) != current && Path::new(&file.file_path).exists()
+    })?;
+    let file = files.remove(index);
+    write_closed_files(path, &files);
+    Some(file.file_path)
+}
+
+fn write_closed_files(path: &Path, files: &[ClosedFile]) {
+    let result = serde_json::to_string_pretty(files)
+        .map_err(io::Error::other)
+        .and_then(|json| fs::write(path, json));
+    if let Err(e) = result {
+        error!("Failed to update {}: {e}", path.display());
+    }
+}
+
+pub fn load_bookmarks(path: &Path) -> Vec<Bookmark> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    serde_json::from_str(&content).unwrap_or_else(|e| {
+        error!("Failed to parse {}: {e}", path.display());
+        Vec::new()
+    })
+}
+
+/// Replaces the bookmarks kept for `file_path` with `bookmarks`.
+pub fn save_bookmarks(path: &Path, file_path: &str, bookmarks: &[Bookmark]) {
+    let kept = load_bookmarks(path);
+    let mut all: Vec<Bookmark> = kept
+        .iter()
+        .filter(|bookmark| bookmark.file_path != file_path)
+        .cloned()
+        .collect();
+    all.extend_from_slice(bookmarks);
+    if all.len() == kept.len() && all.iter().all(|bookmark| kept.contains(bookmark)) {
+        return;
+    }
+    let result = serde_json::to_string_pretty(&all)
+        .map_err(io::Error::other)
+        .and_then(|json| fs::write(path, json));
+    if let Err(e) = result {
+        error!("Failed to update {}: {e}", path.display());
+    }
+}
+
+/// Session names become file names, so they can't name a directory.
+pub fn is_valid_session_name(name: &str) -> bool {
+    !name.is_empty() && !name.starts_with('.') && !name.contains(['/', '\\'])
+}
+
+pub fn session_path(base_dir: Option<PathBuf>, name: &str) -> Option<PathBuf> {
+    let sessions_dir = config_file_path(base_dir, SESSIONS_SUBDIR)?;
+    if let Err(e) = fs::create_dir_all(&sessions_dir) {
+        error!("Failed to create {}: {e}", sessions_dir.display());
+        return None;
+    }
+    Some(sessions_dir.join(format!("{name}.json")))
+}
+
+pub fn load_session(path: &Path) -> Result<Session, io::Error> {
+    let content = fs::read_to_string(path)?;
+    serde_json::from_str(&content).map_err(io::Error::other)
+}
+
+pub fn save_session(path: &Path, session: &Session) -> Result<(), io::Error> {
+    let content = serde_json::to_string_pretty(session)?;
+    fs::write(path, content)
+}
@@ -0,0 +1,254 @@
+use crate::Event;
+use crate::clock::ManualClock;
+use crate::editor::mouse::MouseEvent;
+use crate::error::{DmacsError, Result};
+use crate::keyboard::KeyChord;
+use log::{error, warn};
+use pancurses::Input;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+// Keys other than characters that can appear in a recording, stored by name
+const SPECIAL_KEYS: &[Input] = &[
+    Input::KeyUp,
+    Input::KeyDown,
+    Input::KeyLeft,
+    Input::KeyRight,
+    Input::KeyHome,
+    Input::KeyEnd,
+    Input::KeyPPage,
+    Input::KeyNPage,
+    Input::KeyBackspace,
+    Input::KeyDC,
+    Input::KeyIC,
+    Input::KeyEnter,
+    Input::KeyBTab,
+    Input::KeySTab,
+    Input::KeySLeft,
+    Input::KeySRight,
+    Input::KeySHome,
+    Input::KeySEnd,
+    Input::KeyF1,
+    Input::KeyF2,
+    Input::KeyF3,
+    Input::KeyF4,
+    Input::KeyF5,
+    Input::KeyF6,
+    Input::KeyF7,
+    Input::KeyF8,
+    Input::KeyF9,
+    Input::KeyF10,
+    Input::KeyF11,
+    Input::KeyF12,
+];
+
+/// An event as processed by the editor. Keys are the character itself or the
+/// curses name of the key, e.g. `"KeyUp"`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum InputEvent {
+    Key { key: String, alt: bool },
+    Chord { chord: KeyChord },
+    Mouse { event: MouseEvent },
+    Paste { text: String },
+    Resize { rows: usize, cols: usize },
+}
+
+impl InputEvent {
+    pub fn key(key: Input, alt: bool) -> Self {
+        let key = match key {
+            Input::Character(c) => c.to_string(),
+            other => format!("{other:?}"),
+        };
+        InputEvent::Key { key, alt }
+    }
+
+    /// The event to feed the editor, or `None` for keys this build does not
+    /// know. A resize only changes the recorded screen size.
+    fn to_event(&self) -> Option<Event> {
+        match self {
+            InputEvent::Key { key, alt } => parse_key(key).map(|input| Event::Key(input, *alt)),
+            InputEvent::Chord { chord } => Some(Event::Chord(*chord)),
+            InputEvent::Mouse { event } => Some(Event::Mouse(*event)),
+            InputEvent::Paste { text } => Some(Event::Paste(text.clone())),
+            InputEvent::Resize { .. } => Some(Event::Resize),
+        }
+    }
+}
+
+fn parse_key(key: &str) -> Option<Input> {
+    let mut chars = key.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(Input::Character(c));
+    }
+    SPECIAL_KEYS
+        .iter()
+        .find(|input| format!("{input:?}") == key)
+        .copied()
+}
+
+/// One line of a recording: an event and when it happened, in milliseconds
+/// since the recording started.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RecordedEvent {
+    pub ms: u64,
+    #[serde(flatten)]
+    pub event: InputEvent,
+}
+
+/// Writes the events of a session to a file, one JSON object per line, so
+/// that the session can be replayed with `--replay-input`.
+pub struct InputRecorder {
+    file: Option<File>,
+    started: Instant,
+}
+
+impl InputRecorder {
+    pub fn create(path: &Path) -> Result<Self> {
+        Ok(Self {
+            file: Some(File::create(path)?),
+            started: Instant::now(),
+        })
+    }
+
+    /// Starts the clock of the recording with the initial screen size.
+    pub fn start(&mut self, screen_size: (usize, usize)) {
+        self.started = Instant::now();
+        self.record(&Event::Resize, screen_size);
+    }
+
+    /// Records `event`. Resizes are stored with the new `screen_size`, and
+    /// events that do not reach the editor are left out.
+    pub fn record(&mut self, event: &Event, screen_size: (usize, usize)) {
+        let event = match event {
+            Event::Key(key, alt) => InputEvent::key(*key, *alt),
+            Event::Chord(chord) => InputEvent::Chord { chord: *chord },
+            Event::Mouse(mouse_event) => InputEvent::Mouse {
+                event: *mouse_event,
+            },
+            Event::Paste(text) => InputEvent::Paste { text: text.clone() },
+            Event::Resize => InputEvent::Resize {
+                rows: screen_size.0,
+                cols: screen_size.1,
+            },
+            Event::Quit | Event::ClearMessage => return,
+        };
+        self.write(RecordedEvent {
+            ms: self.started.elapsed().as_millis() as u64,
+            event,
+        });
+    }
+
+    fn write(&mut self, recorded: RecordedEvent) {
+        let Some(file) = &mut self.file else {
+            return;
+        };
+        let written = serde_json::to_string(&recorded)
+            .map_err(|e| DmacsError::Editor(e.to_string()))
+            .and_then(|line| Ok(writeln!(file, "{line}")?));
+        if let Err(e) = written {
+            // Keep editing; the recording just ends here
+            error!("Failed to record input: {e}");
+            self.file = None;
+        }
+    }
+}
+
+/// Feeds the events of a recording back at the pace they were recorded.
+pub struct InputReplay {
+    events: VecDeque<RecordedEvent>,
+    started: Instant,
+    screen_size: Option<(usize, usize)>,
+    replayed: usize,
+    // Stands at the recorded time of the last replayed event
+    clock: ManualClock,
+}
+
+impl InputReplay {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)?;
+        Self::parse(&content)
+    }
+
+    pub fn parse(content: &str) -> Result<Self> {
+        let events = content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                serde_json::from_str(line)
+                    .map_err(|e| DmacsError::Editor(format!("line {}: {e}", index + 1)))
+            })
+            .collect::<Result<VecDeque<RecordedEvent>>>()?;
+        Ok(Self {
+            events,
+            started: Instant::now(),
+            screen_size: None,
+            replayed: 0,
+            clock: ManualClock::new(),
+        })
+    }
+
+    /// Starts the clock the recorded timings are measured against.
+    pub fn start(&mut self) {
+        self.started = Instant::now();
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Number of events fed to the editor so far.
+    pub fn replayed(&self) -> usize {
+        self.replayed
+    }
+
+    /// The screen size of the recording as of the last replayed resize, which
+    /// takes the place of the terminal's size during the replay.
+    pub fn screen_size(&self) -> Option<(usize, usize)> {
+        self.screen_size
+    }
+
+    /// A clock that reads the recorded time of the last replayed event, so
+    /// that undo steps are grouped as they were while recording however
+    /// late the events arrive.
+    pub fn clock(&self) -> ManualClock {
+        self.clock.clone()
+    }
+
+    /// Time until the next event is due, zero if it already is.
+    pub fn time_to_next(&self) -> Option<Duration> {
+        let next = self.events.front()?;
+        Some(Duration::from_millis(next.ms).saturating_sub(self.started.elapsed()))
+    }
+
+    /// The next event if its time has come.
+    pub fn next_event(&mut self) -> Option<Event> {
+        while self.time_to_next()? == Duration::ZERO {
+            let recorded = self.events.pop_front()?;
+            self.clock.set_elapsed(Duration::from_millis(recorded.ms));
+            if let InputEvent::Resize { rows, cols } = recorded.event {
+                self.screen_size = Some((rows, cols));
+            }
+            match recorded.event.to_event() {
+                Some(event) => {
+                    self.replayed += 1;
+                    return Some(event);
+                }
+                None => warn!("Skipping unknown key in replay: {:?}", recorded.event),
+            }
+        }
+        None
+    }
+}
+
+/// Whether `run_editor` writes the events it processes to a file or reads
+/// them from one instead of the terminal.
+pub enum InputLog {
+    Record(InputRecorder),
+    Replay(InputReplay),
+}
@@ -0,0 +1,39 @@
+use crate::document::LineBuffer;
+use crate::error::{DmacsError, Result};
+use crate::persistence;
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+const SCRATCH_FILE_NAME: &str = "scratch.md";
+
+/// Well-known location where the contents of an unnamed buffer are kept
+/// between sessions.
+pub fn scratch_path(base_dir: Option<PathBuf>) -> Result<PathBuf> {
+    let dir = match base_dir {
+        Some(dir) => dir.join(".dmacs"),
+        None => persistence::state_dir().ok_or_else(|| {
+            DmacsError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "Home directory not found",
+            ))
+        })?,
+    };
+    fs::create_dir_all(&dir).map_err(DmacsError::Io)?;
+    Ok(dir.join(SCRATCH_FILE_NAME))
+}
+
+pub fn load(path: &PathBuf) -> Option<Vec<String>> {
+    let content = fs::read_to_string(path).ok()?;
+    let lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
+    if lines.is_empty() { None } else { Some(lines) }
+}
+
+pub fn save(path: &PathBuf, lines: &LineBuffer) -> Result<()> {
+    let file = fs::File::create(path).map_err(DmacsError::Io)?;
+    let mut writer = BufWriter::new(file);
+    for line in lines {
+        writeln!(writer, "{line}").map_err(DmacsError::Io)?;
+    }
+    writer.flush().map_err(DmacsError::Io)
+}
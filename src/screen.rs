@@ -0,0 +1,81 @@
+use pancurses::{ToChtype, Window, chtype};
+
+#[cfg(feature = "virtual-terminal")]
+pub mod virtual_screen;
+
+/// The part of a curses window that the editor draws with, so that drawing
+/// can also be captured off-screen.
+pub trait Screen {
+    fn get_max_y(&self) -> i32;
+    fn get_max_x(&self) -> i32;
+    fn erase(&self) -> i32;
+    fn refresh(&self) -> i32;
+    fn mv(&self, y: i32, x: i32) -> i32;
+    fn addstr<T: AsRef<str>>(&self, string: T) -> i32;
+    fn mvaddstr<T: AsRef<str>>(&self, y: i32, x: i32, string: T) -> i32;
+    fn mvaddch<T: ToChtype>(&self, y: i32, x: i32, ch: T) -> i32;
+    fn clrtoeol(&self) -> i32;
+    fn attron<T: Into<chtype>>(&self, attributes: T) -> i32;
+    fn attroff<T: Into<chtype>>(&self, attributes: T) -> i32;
+    fn color_set(&self, color_pair: i16) -> i32;
+    fn mvinch(&self, y: i32, x: i32) -> chtype;
+    fn mvchgat(&self, y: i32, x: i32, n: i32, attributes: chtype, color_pair: i16) -> i32;
+}
+
+impl Screen for Window {
+    fn get_max_y(&self) -> i32 {
+        Window::get_max_y(self)
+    }
+
+    fn get_max_x(&self) -> i32 {
+        Window::get_max_x(self)
+    }
+
+    fn erase(&self) -> i32 {
+        Window::erase(self)
+    }
+
+    fn refresh(&self) -> i32 {
+        Window::refresh(self)
+    }
+
+    fn mv(&self, y: i32, x: i32) -> i32 {
+        Window::mv(self, y, x)
+    }
+
+    fn addstr<T: AsRef<str>>(&self, string: T) -> i32 {
+        Window::addstr(self, string)
+    }
+
+    fn mvaddstr<T: AsRef<str>>(&self, y: i32, x: i32, string: T) -> i32 {
+        Window::mvaddstr(self, y, x, string)
+    }
+
+    fn mvaddch<T: ToChtype>(&self, y: i32, x: i32, ch: T) -> i32 {
+        Window::mvaddch(self, y, x, ch)
+    }
+
+    fn clrtoeol(&self) -> i32 {
+        Window::clrtoeol(self)
+    }
+
+    fn attron<T: Into<chtype>>(&self, attributes: T) -> i32 {
+        Window::attron(self, attributes)
+    }
+
+    fn attroff<T: Into<chtype>>(&self, attributes: T) -> i32 {
+        Window::attroff(self, attributes)
+    }
+
+    fn color_set(&self, color_pair: i16) -> i32 {
+        Window::color_set(self, color_pair)
+    }
+
+    fn mvinch(&self, y: i32, x: i32) -> chtype {
+        Window::mvinch(self, y, x)
+    }
+
+    fn mvchgat(&self, y: i32, x: i32, n: i32, attributes: chtype, color_pair: i16) -> i32 {
+        Window::mvchgat(self, y, x, n, attributes, color_pair)
+    }
+}
@@ -0,0 +1,330 @@
+use crate::screen::Screen;
+use pancurses::{
+    A_ATTRIBUTES, A_BLINK, A_BOLD, A_CHARTEXT, A_COLOR, A_DIM, A_ITALIC, A_REVERSE, A_STANDOUT,
+    A_UNDERLINE, COLOR_PAIR, ToChtype, chtype,
+};
+use std::cell::{Cell as StdCell, RefCell};
+use unicode_width::UnicodeWidthChar;
+
+/// One character cell of a `VirtualScreen`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cell {
+    // '\0' for the second column of a wide character
+    pub ch: char,
+    // Attributes and color pair, as in a curses `chtype`
+    pub attributes: chtype,
+}
+
+// Attributes named in a styled snapshot
+const ATTRIBUTE_NAMES: [(chtype, &str); 7] = [
+    (A_BOLD, "bold"),
+    (A_DIM, "dim"),
+    (A_ITALIC, "italic"),
+    (A_UNDERLINE, "underline"),
+    (A_REVERSE, "reverse"),
+    (A_STANDOUT, "standout"),
+    (A_BLINK, "blink"),
+];
+
+impl Cell {
+    const BLANK: Cell = Cell {
+        ch: ' ',
+        attributes: 0,
+    };
+
+    // Plain text, drawn with no attributes in the default pair 0 or 1
+    fn is_plain(&self) -> bool {
+        self.attributes & A_ATTRIBUTES & !A_COLOR == 0 && self.color_pair() <= 1
+    }
+
+    fn describe_style(&self) -> String {
+        let mut parts = vec![format!("pair {}", self.color_pair())];
+        parts.extend(
+            ATTRIBUTE_NAMES
+                .iter()
+                .filter(|&&(attribute, _)| self.has(attribute))
+                .map(|&(_, name)| name.to_string()),
+        );
+        parts.join(" ")
+    }
+
+    pub fn color_pair(&self) -> i16 {
+        ((self.attributes & A_COLOR) / COLOR_PAIR(1)) as i16
+    }
+
+    pub fn has(&self, attribute: chtype) -> bool {
+        self.attributes & attribute == attribute
+    }
+}
+
+/// An off-screen grid that records what drawing would show on a terminal,
+/// for checking the rendering in tests. Text running past the right edge
+/// wraps like it does in curses.
+pub struct VirtualScreen {
+    rows: usize,
+    cols: usize,
+    cells: RefCell<Vec<Vec<Cell>>>,
+    cursor: StdCell<(usize, usize)>,
+    attributes: StdCell<chtype>,
+}
+
+impl VirtualScreen {
+    pub fn new(rows: usize, cols: usize) -> Self {
+        Self {
+            rows,
+            cols,
+            cells: RefCell::new(vec![vec![Cell::BLANK; cols]; rows]),
+            cursor: StdCell::new((0, 0)),
+            attributes: StdCell::new(0),
+        }
+    }
+
+    /// The cursor as `(row, column)`.
+    pub fn cursor(&self) -> (usize, usize) {
+        self.cursor.get()
+    }
+
+    pub fn cell(&self, row: usize, col: usize) -> Cell {
+        self.cells.borrow()[row][col]
+    }
+
+    /// The text of `row`, with trailing blanks removed.
+    pub fn row_text(&self, row: usize) -> String {
+        let text: String = self.cells.borrow()[row]
+            .iter()
+            .filter(|cell| cell.ch != '\0')
+            .map(|cell| cell.ch)
+            .collect();
+        text.trim_end().to_string()
+    }
+
+    /// Every row of the screen, one per line, for comparing against an
+    /// expected snapshot.
+    pub fn text(&self) -> String {
+        (0..self.rows)
+            .map(|row| self.row_text(row))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Like `text`, with the style of the cells under each row that has any
+    /// besides plain text: one letter per column, named in a legend at the
+    /// end. Suited to golden files, where a change in highlighting shows up
+    /// in the diff.
+    pub fn styled_text(&self) -> String {
+        let mut styles: Vec<chtype> = Vec::new();
+        let mut lines = Vec::new();
+        for row in 0..self.rows {
+            lines.push(self.row_text(row));
+            let marks: String = (0..self.cols)
+                .map(|col| {
+                    let cell = self.cell(row, col);
+                    if cell.is_plain() {
+                        return ' ';
+                    }
+                    let index = styles
+                        .iter()
+                        .position(|&style| style == cell.attributes)
+                        .unwrap_or_else(|| {
+                            styles.push(cell.attributes);
+                            styles.len() - 1
+                        });
+                    style_letter(index)
+                })
+                .collect();
+            if !marks.trim_end().is_empty() {
+                lines.push(marks.trim_end().to_string());
+            }
+        }
+        lines.push("--".to_string());
+        for (index, &attributes) in styles.iter().enumerate() {
+            let cell = Cell {
+                ch: ' ',
+                attributes,
+            };
+            lines.push(format!(
+                "{}: {}",
+                style_letter(index),
+                cell.describe_style()
+            ));
+        }
+        lines.join("\n")
+    }
+
+    /// The columns of `row` drawn with all of `attribute`.
+    pub fn columns_with(&self, row: usize, attribute: chtype) -> Vec<usize> {
+        (0..self.cols)
+            .filter(|&col| self.cell(row, col).has(attribute))
+            .collect()
+    }
+
+    // Writes `ch` at the cursor and advances it, wrapping at the right edge
+    fn put(&self, ch: char, attributes: chtype) -> bool {
+        let (mut row, mut col) = self.cursor.get();
+        if row >= self.rows {
+            return false;
+        }
+        let width = ch.width().unwrap_or(0);
+        if width == 0 {
+            return true;
+        }
+        if col + width > self.cols {
+            // A wide character that does not fit goes to the next row
+            let mut cells = self.cells.borrow_mut();
+            for cell in &mut cells[row][col..] {
+                *cell = Cell::BLANK;
+            }
+            row += 1;
+            col = 0;
+            if row >= self.rows {
+                self.cursor.set((row, col));
+                return false;
+            }
+        }
+        {
+            let mut cells = self.cells.borrow_mut();
+            cells[row][col] = Cell { ch, attributes };
+            if width == 2 {
+                cells[row][col + 1] = Cell {
+                    ch: '\0',
+                    attributes,
+                };
+            }
+        }
+        col += width;
+        if col >= self.cols {
+            row += 1;
+            col = 0;
+        }
+        self.cursor.set((row, col));
+        true
+    }
+
+    fn move_to(&self, y: i32, x: i32) -> bool {
+        if y < 0 || x < 0 || y as usize >= self.rows || x as usize >= self.cols {
+            return false;
+        }
+        self.cursor.set((y as usize, x as usize));
+        true
+    }
+}
+
+// Letters of the styles in a styled snapshot: a to z, then A to Z
+fn style_letter(index: usize) -> char {
+    (b'a'..=b'z')
+        .chain(b'A'..=b'Z')
+        .nth(index)
+        .map_or('?', char::from)
+}
+
+fn status(ok: bool) -> i32 {
+    if ok { 0 } else { -1 }
+}
+
+impl Screen for VirtualScreen {
+    fn get_max_y(&self) -> i32 {
+        self.rows as i32
+    }
+
+    fn get_max_x(&self) -> i32 {
+        self.cols as i32
+    }
+
+    fn erase(&self) -> i32 {
+        for row in self.cells.borrow_mut().iter_mut() {
+            row.fill(Cell::BLANK);
+        }
+        self.cursor.set((0, 0));
+        0
+    }
+
+    fn refresh(&self) -> i32 {
+        0
+    }
+
+    fn mv(&self, y: i32, x: i32) -> i32 {
+        status(self.move_to(y, x))
+    }
+
+    fn addstr<T: AsRef<str>>(&self, string: T) -> i32 {
+        let attributes = self.attributes.get();
+        status(string.as_ref().chars().all(|ch| self.put(ch, attributes)))
+    }
+
+    fn mvaddstr<T: AsRef<str>>(&self, y: i32, x: i32, string: T) -> i32 {
+        if !self.move_to(y, x) {
+            return -1;
+        }
+        self.addstr(string)
+    }
+
+    fn mvaddch<T: ToChtype>(&self, y: i32, x: i32, ch: T) -> i32 {
+        if !self.move_to(y, x) {
+            return -1;
+        }
+        let ch = ch.to_chtype();
+        // Line drawing characters are only known once curses is initialized
+        let text = char::from_u32(ch & A_CHARTEXT).filter(|&c| c != '\0');
+        status(self.put(
+            text.unwrap_or(' '),
+            self.attributes.get() | (ch & A_ATTRIBUTES),
+        ))
+    }
+
+    fn clrtoeol(&self) -> i32 {
+        let (row, col) = self.cursor.get();
+        if row < self.rows {
+            self.cells.borrow_mut()[row][col..].fill(Cell::BLANK);
+        }
+        0
+    }
+
+    fn attron<T: Into<chtype>>(&self, attributes: T) -> i32 {
+        self.attributes
+            .set(self.attributes.get() | attributes.into());
+        0
+    }
+
+    fn attroff<T: Into<chtype>>(&self, attributes: T) -> i32 {
+        self.attributes
+            .set(self.attributes.get() & !attributes.into());
+        0
+    }
+
+    fn color_set(&self, color_pair: i16) -> i32 {
+        let attributes = self.attributes.get() & !A_COLOR;
+        self.attributes
+            .set(attributes | COLOR_PAIR(color_pair as chtype));
+        0
+    }
+
+    fn mvinch(&self, y: i32, x: i32) -> chtype {
+        if !self.move_to(y, x) {
+            return 0;
+        }
+        let cell = self.cell(y as usize, x as usize);
+        let text = if cell.ch.is_ascii() {
+            cell.ch as chtype
+        } else {
+            0
+        };
+        cell.attributes | text
+    }
+
+    fn mvchgat(&self, y: i32, x: i32, n: i32, attributes: chtype, color_pair: i16) -> i32 {
+        if !self.move_to(y, x) {
+            return -1;
+        }
+        let (row, col) = (y as usize, x as usize);
+        let end = if n < 0 {
+            self.cols
+        } else {
+            (col + n as usize).min(self.cols)
+        };
+        let attributes = (attributes & !A_COLOR) | COLOR_PAIR(color_pair as chtype);
+        for cell in &mut self.cells.borrow_mut()[row][col..end] {
+            cell.attributes = attributes;
+        }
+        0
+    }
+}
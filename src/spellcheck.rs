@@ -0,0 +1,296 @@
+use crate::error::{DmacsError, Result};
+use log::debug;
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const DICTIONARIES_SUBDIR: &str = "dictionaries";
+const USER_DICTIONARY_NAME: &str = "user.txt";
+// Where installed hunspell dictionaries are looked for, after the config dir
+const SYSTEM_DICTIONARY_DIRS: [&str; 3] = [
+    "/usr/share/hunspell",
+    "/usr/share/myspell",
+    "/usr/share/myspell/dicts",
+];
+// Plain word list used for English when no hunspell dictionary is installed
+const SYSTEM_WORD_LIST: &str = "/usr/share/dict/words";
+// Endings stripped from English words not found as they are, with what the
+// stem may have lost ("making" -> "make")
+const ENGLISH_SUFFIXES: [(&str, &str); 12] = [
+    ("'s", ""),
+    ("ies", "y"),
+    ("ied", "y"),
+    ("es", ""),
+    ("s", ""),
+    ("ed", ""),
+    ("ed", "e"),
+    ("ing", ""),
+    ("ing", "e"),
+    ("er", ""),
+    ("est", ""),
+    ("ly", ""),
+];
+const MAX_EDIT_DISTANCE: usize = 2;
+
+/// Words of one language plus the user's own, checked case-insensitively
+/// unless the dictionary spells a word with capitals.
+#[derive(Debug, Default)]
+pub struct SpellChecker {
+    words: HashSet<String>,
+    user_words: HashSet<String>,
+    user_dictionary: Option<PathBuf>,
+    english: bool,
+}
+
+impl SpellChecker {
+    /// Loads the dictionary for `language` (e.g. `en_US`) from
+    /// `~/.dmacs/dictionaries` or the installed hunspell dictionaries, with
+    /// the user dictionary `~/.dmacs/dictionaries/user.txt`.
+    pub fn load(language: &str, base_dir: Option<PathBuf>) -> Result<Self> {
+        let base = match base_dir {
+            Some(dir) => dir,
+            None => dirs::home_dir().ok_or(DmacsError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "Home directory not found",
+            )))?,
+        };
+        let dictionaries_dir = base.join(".dmacs").join(DICTIONARIES_SUBDIR);
+        let english = language.starts_with("en");
+        let mut candidates = vec![
+            dictionaries_dir.join(format!("{language}.dic")),
+            dictionaries_dir.join(format!("{language}.txt")),
+        ];
+        candidates.extend(
+            SYSTEM_DICTIONARY_DIRS
+                .iter()
+                .map(|dir| Path::new(dir).join(format!("{language}.dic"))),
+        );
+        if english {
+            candidates.push(PathBuf::from(SYSTEM_WORD_LIST));
+        }
+        let Some(path) = candidates.iter().find(|path| path.is_file()) else {
+            return Err(DmacsError::Editor(format!(
+                "No dictionary for {language}; put one at {}",
+                dictionaries_dir.join(format!("{language}.dic")).display()
+            )));
+        };
+        debug!("Loading the {language} dictionary from {}", path.display());
+        let content = fs::read_to_string(path).map_err(DmacsError::Io)?;
+        let user_dictionary = dictionaries_dir.join(USER_DICTIONARY_NAME);
+        let user_words = fs::read_to_string(&user_dictionary)
+            .map(|content| parse_word_list(&content).collect())
+            .unwrap_or_default();
+        Ok(Self {
+            words: parse_word_list(&content).collect(),
+            user_words,
+            user_dictionary: Some(user_dictionary),
+            english,
+        })
+    }
+
+    /// A checker knowing only `words`, with English word endings.
+    pub fn from_words<'a>(words: impl IntoIterator<Item = &'a str>) -> Self {
+        Self {
+            words: words.into_iter().map(String::from).collect(),
+            english: true,
+            ..Self::default()
+        }
+    }
+
+    /// Keeps words added with `add_word` in `path`.
+    pub fn with_user_dictionary(self, path: PathBuf) -> Self {
+        Self {
+            user_dictionary: Some(path),
+            ..self
+        }
+    }
+
+    pub fn is_correct(&self, word: &str) -> bool {
+        let word = word.replace('’', "'");
+        if self.knows(&word) {
+            return true;
+        }
+        self.english
+            && ENGLISH_SUFFIXES.iter().any(|(suffix, restored)| {
+                word.strip_suffix(suffix).is_some_and(|stem| {
+                    stem.chars().count() > 1 && self.knows(&format!("{stem}{restored}"))
+                })
+            })
+    }
+
+    fn knows(&self, word: &str) -> bool {
+        let lower = word.to_lowercase();
+        [word, lower.as_str()]
+            .iter()
+            .any(|word| self.words.contains(*word) || self.user_words.contains(*word))
+    }
+
+    /// Byte ranges of the words in `line` that are not in the dictionary.
+    pub fn misspellings(&self, line: &str) -> Vec<(usize, usize)> {
+        checked_words(line)
+            .into_iter()
+            .filter(|&(start, end)| !self.is_correct(&line[start..end]))
+            .collect()
+    }
+
+    /// Dictionary words close to `word`, closest first, capitalized like it.
+    pub fn suggestions(&self, word: &str, max: usize) -> Vec<String> {
+        let lower = word.to_lowercase();
+        let length = lower.chars().count();
+        let mut candidates: Vec<(usize, bool, &String)> = self
+            .words
+            .iter()
+            .chain(&self.user_words)
+            .filter(|candidate| candidate.chars().count().abs_diff(length) <= MAX_EDIT_DISTANCE)
+            .filter_map(|candidate| {
+                let distance = edit_distance(&lower, &candidate.to_lowercase());
+                let other_start = candidate.chars().next() != lower.chars().next();
+                (distance <= MAX_EDIT_DISTANCE).then_some((distance, other_start, candidate))
+            })
+            .collect();
+        candidates.sort();
+        let capitalized = word.chars().next().is_some_and(char::is_uppercase);
+        let mut suggestions: Vec<String> = Vec::new();
+        for (_, _, candidate) in candidates {
+            let suggestion = if capitalized {
+                capitalize(candidate)
+            } else {
+                candidate.clone()
+            };
+            if !suggestions.contains(&suggestion) {
+                suggestions.push(suggestion);
+            }
+            if suggestions.len() == max {
+                break;
+            }
+        }
+        suggestions
+    }
+
+    /// Accepts `word` from now on, writing it to the user dictionary.
+    pub fn add_word(&mut self, word: &str) -> Result<()> {
+        self.user_words.insert(word.to_string());
+        let Some(path) = &self.user_dictionary else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(DmacsError::Io)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(DmacsError::Io)?;
+        writeln!(file, "{word}").map_err(DmacsError::Io)
+    }
+}
+
+/// Words of a plain word list or a hunspell `.dic` file, whose first line is
+/// the word count and whose words may be followed by `/` and affix flags.
+fn parse_word_list(content: &str) -> impl Iterator<Item = String> + '_ {
+    content
+        .lines()
+        .map(|line| line.split('/').next().unwrap_or_default().trim())
+        .filter(|word| !word.is_empty() && !word.chars().all(|c| c.is_ascii_digit()))
+        .map(String::from)
+}
+
+/// Byte ranges of the words in `line` worth checking: runs of Latin letters,
+/// leaving out code spans, links, paths, `@annotations`, `#tags`, acronyms
+/// and words mixed with digits.
+pub fn checked_words(line: &str) -> Vec<(usize, usize)> {
+    let mut words = Vec::new();
+    let mut in_code = false;
+    let mut chunk_start = 0;
+    for (index, ch) in line.char_indices().chain([(line.len(), ' ')]) {
+        if !ch.is_whitespace() {
+            continue;
+        }
+        let chunk = &line[chunk_start..index];
+        let skipped = in_code
+            || chunk.contains('`')
+            || chunk.contains("://")
+            || chunk.contains(['@', '/', '\\', '_', '='])
+            || (chunk.starts_with('#') && chunk.len() > 1 && !chunk.starts_with("##"));
+        if chunk.matches('`').count() % 2 == 1 {
+            in_code = !in_code;
+        }
+        if !skipped {
+            words.extend(
+                words_in_chunk(chunk)
+                    .into_iter()
+                    .map(|(start, end)| (chunk_start + start, chunk_start + end)),
+            );
+        }
+        chunk_start = index + ch.len_utf8();
+    }
+    words
+}
+
+fn words_in_chunk(chunk: &str) -> Vec<(usize, usize)> {
+    let chars: Vec<(usize, char)> = chunk.char_indices().collect();
+    let mut words = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if !chars[i].1.is_alphabetic() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        // Apostrophes join letters, as in "don't"
+        while i < chars.len()
+            && (chars[i].1.is_alphabetic()
+                || (matches!(chars[i].1, '\'' | '’')
+                    && chars.get(i + 1).is_some_and(|(_, c)| c.is_alphabetic())))
+        {
+            i += 1;
+        }
+        let word: Vec<char> = chars[start..i].iter().map(|&(_, c)| c).collect();
+        let next_to_digit = (start > 0 && chars[start - 1].1.is_ascii_digit())
+            || chars.get(i).is_some_and(|(_, c)| c.is_ascii_digit());
+        let is_latin = word.iter().all(|&c| (c as u32) < 0x250 || c == '’');
+        let has_inner_capital = word.iter().skip(1).any(|c| c.is_uppercase());
+        if word.len() > 1 && is_latin && !next_to_digit && !has_inner_capital {
+            let end = chars.get(i).map_or(chunk.len(), |&(index, _)| index);
+            words.push((chars[start].0, end));
+        }
+    }
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Edits (insertions, deletions, substitutions and swaps of neighbouring
+/// letters) turning `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let width = b.len() + 1;
+    let mut d = vec![0usize; (a.len() + 1) * width];
+    for i in 0..=a.len() {
+        d[i * width] = i;
+    }
+    for (j, cell) in d.iter_mut().enumerate().take(width) {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut best = (d[(i - 1) * width + j] + 1)
+                .min(d[i * width + j - 1] + 1)
+                .min(d[(i - 1) * width + j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(d[(i - 2) * width + j - 2] + 1);
+            }
+            d[i * width + j] = best;
+        }
+    }
+    d[a.len() * width + b.len()]
+}
@@ -0,0 +1,103 @@
+use crate::editor::annotation::find_annotation;
+use crate::persistence;
+use log::error;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+const TIMER_FILE_NAME: &str = "task_timer.json";
+
+/// A timer started on a checkbox task, kept on disk so it keeps running when
+/// the editor is restarted.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RunningTimer {
+    pub file_path: Option<String>,
+    pub task: String,
+    pub line: usize,
+    pub started_at: SystemTime,
+}
+
+impl RunningTimer {
+    pub fn elapsed(&self) -> Duration {
+        SystemTime::now()
+            .duration_since(self.started_at)
+            .unwrap_or_default()
+    }
+}
+
+pub fn timer_path(base_dir: Option<PathBuf>) -> Option<PathBuf> {
+    let dir = match base_dir {
+        Some(dir) => dir.join(".dmacs"),
+        None => persistence::state_dir()?,
+    };
+    if let Err(e) = fs::create_dir_all(&dir) {
+        error!("Failed to create {}: {e}", dir.display());
+        return None;
+    }
+    Some(dir.join(TIMER_FILE_NAME))
+}
+
+pub fn load(path: &PathBuf) -> Option<RunningTimer> {
+    let content = fs::read_to_string(path).ok()?;
+    match serde_json::from_str(&content) {
+        Ok(timer) => Some(timer),
+        Err(e) => {
+            error!("Failed to parse {}: {e}", path.display());
+            None
+        }
+    }
+}
+
+pub fn store(path: &PathBuf, timer: Option<&RunningTimer>) {
+    let result = match timer {
+        Some(timer) => serde_json::to_string(timer)
+            .map_err(std::io::Error::other)
+            .and_then(|json| fs::write(path, json)),
+        None if path.exists() => fs::remove_file(path),
+        None => Ok(()),
+    };
+    if let Err(e) = result {
+        error!("Failed to update {}: {e}", path.display());
+    }
+}
+
+/// Formats a duration the way `@spent(...)` annotations are written, e.g.
+/// `1h23m` or `5m`.
+pub fn format_spent(duration: Duration) -> String {
+    let minutes = duration.as_secs() / 60;
+    let (hours, minutes) = (minutes / 60, minutes % 60);
+    if hours > 0 {
+        format!("{hours}h{minutes}m")
+    } else {
+        format!("{minutes}m")
+    }
+}
+
+/// Parses the inside of a `@spent(...)` annotation.
+pub fn parse_spent(value: &str) -> Option<Duration> {
+    let mut total_minutes = 0;
+    let mut number = String::new();
+    for c in value.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'h' => total_minutes += number.parse::<u64>().ok()? * 60,
+            'm' => total_minutes += number.parse::<u64>().ok()?,
+            _ => return None,
+        }
+        if !c.is_ascii_digit() {
+            number.clear();
+        }
+    }
+    if !number.is_empty() {
+        return None;
+    }
+    Some(Duration::from_secs(total_minutes * 60))
+}
+
+/// Returns the byte range of the `@spent(...)` annotation in `line` and the
+/// time it records.
+pub fn find_spent(line: &str) -> Option<(usize, usize, Duration)> {
+    let (start, end, value) = find_annotation(line, "spent")?;
+    Some((start, end, parse_spent(value)?))
+}
@@ -1,13 +1,25 @@
 use pancurses::{
-    COLOR_BLACK, COLOR_WHITE, COLOR_YELLOW, Input, Window, can_change_color, curs_set, endwin,
-    init_color, init_pair, initscr, noecho, start_color, use_default_colors,
+    ALL_MOUSE_EVENTS, BUTTON1_PRESSED, BUTTON1_RELEASED, BUTTON4_PRESSED, BUTTON5_PRESSED,
+    COLOR_BLACK, COLOR_CYAN, COLOR_GREEN, COLOR_MAGENTA, COLOR_RED, COLOR_WHITE, COLOR_YELLOW,
+    Input, REPORT_MOUSE_POSITION, Window, can_change_color, curs_set, endwin, getmouse, init_color,
+    init_pair, initscr, keyname, mouseinterval, mousemask, noecho, start_color, use_default_colors,
 };
-use std::io::{self, stdin};
+use std::cell::RefCell;
+use std::io::{self, Write, stdin};
 #[cfg(unix)]
 use std::os::unix::io::AsRawFd;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::mpsc::{self, Receiver};
 
+use crate::editor::mouse::MouseEvent;
+use crate::editor::ui::{
+    DIM_COLOR_PAIR, ERROR_COLOR_PAIR, GUIDE_COLOR_PAIR, GUTTER_COLOR_PAIR, KEYWORD_COLOR_PAIRS,
+    SEARCH_COLOR_PAIR, SELECTION_COLOR_PAIR, STATUS_COLOR_PAIR, cursor_line_pair,
+};
+use crate::keyboard::{
+    DISABLE_KEYBOARD_PROTOCOL, ENABLE_KEYBOARD_PROTOCOL, KeyChord, KeyInput, KeyParser,
+    Utf8Assembler,
+};
 use crate::{config::Colors, Event};
 
 use crate::error::{DmacsError, Result};
@@ -15,36 +27,114 @@ use crate::error::{DmacsError, Result};
 // Import necessary types and functions from the libc crate
 #[cfg(all(unix, target_os = "macos"))]
 use libc::{
-    _POSIX_VDISABLE, TCSANOW, VDSUSP, VLNEXT, VREPRINT, VSTATUS, VSTOP, tcgetattr, tcsetattr,
-    termios,
+    _POSIX_VDISABLE, TCSANOW, VDISCARD, VDSUSP, VLNEXT, VREPRINT, VSTATUS, VSTOP, tcgetattr,
+    tcsetattr, termios,
 };
 #[cfg(all(unix, not(target_os = "macos")))]
 use libc::{
-    _POSIX_VDISABLE, TCSANOW, VLNEXT, VREPRINT, VSTOP, VSUSP, tcgetattr, tcsetattr, termios,
+    _POSIX_VDISABLE, TCSANOW, VDISCARD, VLNEXT, VREPRINT, VSTOP, VSUSP, tcgetattr, tcsetattr,
+    termios,
 };
 
-// Function to convert hex color string to RGB values on a 0-1000 scale
-fn hex_to_rgb_1000(hex: &str) -> Result<(i16, i16, i16)> {
+// Enables reporting of mouse motion while a button is held, so drags arrive as events.
+const ENABLE_BUTTON_MOTION_TRACKING: &str = "\x1b[?1002h";
+const DISABLE_BUTTON_MOTION_TRACKING: &str = "\x1b[?1002l";
+
+// Makes the terminal wrap pasted text in ESC [200~ and ESC [201~, so that it
+// arrives as one event instead of being typed key by key.
+const ENABLE_BRACKETED_PASTE: &str = "\x1b[?2004h";
+const DISABLE_BRACKETED_PASTE: &str = "\x1b[?2004l";
+const PASTE_END: &str = "\x1b[201~";
+// Timeouts of getch() in a row after which a paste missing its end marker is
+// given up
+const PASTE_MAX_IDLE_READS: usize = 20;
+
+fn hex_to_rgb(hex: &str) -> Result<(u8, u8, u8)> {
     let hex = hex.trim_start_matches('#');
-    if hex.len() != 6 {
+    if hex.len() != 6 || !hex.is_ascii() {
         return Err(DmacsError::Terminal("Invalid hex color format".to_string()));
     }
-    let r = i32::from_str_radix(&hex[0..2], 16)
-        .map_err(|_| DmacsError::Terminal("Invalid hex color format".to_string()))?;
-    let g = i32::from_str_radix(&hex[2..4], 16)
-        .map_err(|_| DmacsError::Terminal("Invalid hex color format".to_string()))?;
-    let b = i32::from_str_radix(&hex[4..6], 16)
-        .map_err(|_| DmacsError::Terminal("Invalid hex color format".to_string()))?;
-
-    Ok((
-        (r * 1000 / 255) as i16,
-        (g * 1000 / 255) as i16,
-        (b * 1000 / 255) as i16,
-    ))
+    let channel = |range: std::ops::Range<usize>| {
+        u8::from_str_radix(&hex[range], 16)
+            .map_err(|_| DmacsError::Terminal("Invalid hex color format".to_string()))
+    };
+    Ok((channel(0..2)?, channel(2..4)?, channel(4..6)?))
+}
+
+// Function to convert hex color string to RGB values on a 0-1000 scale
+fn hex_to_rgb_1000(hex: &str) -> Result<(i16, i16, i16)> {
+    let (r, g, b) = hex_to_rgb(hex)?;
+    let scale = |channel: u8| (i32::from(channel) * 1000 / 255) as i16;
+    Ok((scale(r), scale(g), scale(b)))
+}
+
+// Levels of each channel in the 6x6x6 color cube of the 256-color palette
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+const CUBE_START: i16 = 16;
+const GRAY_START: i16 = 232;
+const GRAY_STEPS: u8 = 24;
+
+/// The color of the 256-color palette closest to a `#rrggbb` color, for
+/// terminals where the palette cannot be redefined. Only the color cube and
+/// the gray ramp are considered, since the first 16 colors vary with the
+/// terminal's theme.
+pub fn nearest_palette_color(hex: &str) -> Result<i16> {
+    let (r, g, b) = hex_to_rgb(hex)?;
+    let distance = |(r2, g2, b2): (u8, u8, u8)| {
+        [(r, r2), (g, g2), (b, b2)]
+            .iter()
+            .map(|&(a, b)| (i32::from(a) - i32::from(b)).pow(2))
+            .sum::<i32>()
+    };
+    let cube = (0..216).map(|index| {
+        let level = |digit: usize| CUBE_LEVELS[digit % 6];
+        let rgb = (level(index / 36), level(index / 6), level(index));
+        (CUBE_START + index as i16, distance(rgb))
+    });
+    let grays = (0..GRAY_STEPS).map(|step| {
+        let value = 8 + step * 10;
+        (
+            GRAY_START + i16::from(step),
+            distance((value, value, value)),
+        )
+    });
+    Ok(cube
+        .chain(grays)
+        .min_by_key(|&(_, distance)| distance)
+        .map(|(color, _)| color)
+        .unwrap_or(CUBE_START))
 }
 
 pub static CTRL_C_COUNT: AtomicUsize = AtomicUsize::new(0);
 
+// The plain functions of curses on the standard screen, which is the window
+// of the terminal
+#[cfg(unix)]
+unsafe extern "C" {
+    fn getch() -> libc::c_int;
+    fn ungetch(ch: libc::c_int) -> libc::c_int;
+}
+
+/// What curses reads: a byte of a character or a key it decoded itself.
+enum RawInput {
+    Byte(u8),
+    Key(Input),
+}
+
+/// The event for a key reported with its modifiers: a plain key when the
+/// legacy encoding has it too, so that everything else keeps working.
+fn chord_event(chord: KeyChord) -> Event {
+    match chord.legacy() {
+        // Reported as a key instead of raising SIGINT
+        Some((Input::Character('\x03'), _)) => {
+            CTRL_C_COUNT.fetch_add(1, Ordering::SeqCst);
+            Event::Quit
+        }
+        Some((key, alt)) => Event::Key(key, alt),
+        None => Event::Chord(chord),
+    }
+}
+
 pub struct Terminal {
     window: Window,
     #[cfg(unix)]
@@ -53,10 +143,150 @@ pub struct Terminal {
     original_termios: (),
     event_rx: Receiver<Event>,
     event_tx: mpsc::Sender<Event>,
+    monochrome: bool,
+    keys: RefCell<KeyParser>,
+}
+
+const KEYWORD_COLORS: [i16; 4] = [COLOR_RED, COLOR_CYAN, COLOR_GREEN, COLOR_MAGENTA];
+
+/// Color pairs for TODO keywords, used in turn for each configured keyword.
+fn init_keyword_pairs(bg: i16) {
+    for (pair, color) in KEYWORD_COLOR_PAIRS.iter().zip(KEYWORD_COLORS) {
+        init_pair(*pair, color, bg);
+    }
+}
+
+/// The terminal color used for each of the theme colors.
+struct Palette {
+    bg: i16,
+    fg: i16,
+    bold: i16,
+    guide: i16,
+    cursor_line: i16,
+    selection: i16,
+    search: i16,
+    status: i16,
+    dim: i16,
+    gutter: i16,
+    error: i16,
+}
+
+impl Palette {
+    /// Redefines colors 11 to 21 of the terminal as those of the theme.
+    fn redefined(colors: &Colors) -> Result<Self> {
+        let palette = Self {
+            cursor_line: 11,
+            guide: 12,
+            bg: 13,
+            fg: 14,
+            bold: 15,
+            selection: 16,
+            search: 17,
+            status: 18,
+            dim: 19,
+            gutter: 20,
+            error: 21,
+        };
+        for (color, hex) in [
+            (palette.cursor_line, &colors.cursor_line),
+            (palette.guide, &colors.guide),
+            (palette.bg, &colors.bg),
+            (palette.fg, &colors.fg),
+            (palette.bold, &colors.bold),
+            (palette.selection, &colors.selection),
+            (palette.search, &colors.search),
+            (palette.status, &colors.status),
+            (palette.dim, &colors.dim),
+            (palette.gutter, &colors.gutter),
+            (palette.error, &colors.error),
+        ] {
+            let (r, g, b) = hex_to_rgb_1000(hex)?;
+            init_color(color, r, g, b);
+        }
+        Ok(palette)
+    }
+
+    /// The palette is fixed, so use the nearest colors it has.
+    fn nearest(colors: &Colors) -> Result<Self> {
+        Ok(Self {
+            bg: nearest_palette_color(&colors.bg)?,
+            fg: nearest_palette_color(&colors.fg)?,
+            bold: nearest_palette_color(&colors.bold)?,
+            guide: nearest_palette_color(&colors.guide)?,
+            cursor_line: nearest_palette_color(&colors.cursor_line)?,
+            selection: nearest_palette_color(&colors.selection)?,
+            search: nearest_palette_color(&colors.search)?,
+            status: nearest_palette_color(&colors.status)?,
+            dim: nearest_palette_color(&colors.dim)?,
+            gutter: nearest_palette_color(&colors.gutter)?,
+            error: nearest_palette_color(&colors.error)?,
+        })
+    }
+
+    /// The eight basic colors on the terminal's own background.
+    fn basic() -> Self {
+        use_default_colors();
+        Self {
+            bg: -1,
+            fg: COLOR_WHITE,
+            bold: COLOR_YELLOW,
+            guide: COLOR_WHITE,
+            cursor_line: COLOR_BLACK,
+            selection: COLOR_WHITE,
+            search: COLOR_YELLOW,
+            status: COLOR_WHITE,
+            dim: COLOR_WHITE,
+            gutter: COLOR_GREEN,
+            error: COLOR_RED,
+        }
+    }
+
+    fn init_pairs(&self) {
+        init_pair(1, self.fg, self.bg); // Background
+        // For highlighting
+        init_pair(2, if self.bg < 0 { COLOR_BLACK } else { self.bg }, self.fg);
+        init_pair(3, self.bold, self.bg); // Bold
+        init_keyword_pairs(self.bg);
+        init_pair(GUIDE_COLOR_PAIR, self.guide, self.bg);
+        // Drawn reversed, so the text is in the background color
+        init_pair(SELECTION_COLOR_PAIR, self.selection, self.bg);
+        init_pair(SEARCH_COLOR_PAIR, self.search, self.bg);
+        init_pair(STATUS_COLOR_PAIR, self.status, self.bg);
+        init_pair(DIM_COLOR_PAIR, self.dim, self.bg);
+        init_pair(GUTTER_COLOR_PAIR, self.gutter, self.bg);
+        init_pair(ERROR_COLOR_PAIR, self.error, self.bg);
+
+        // Variants of the text pairs with the cursor line background
+        let bg = self.cursor_line;
+        init_pair(cursor_line_pair(1), self.fg, bg);
+        init_pair(cursor_line_pair(3), self.bold, bg);
+        for (pair, color) in KEYWORD_COLOR_PAIRS.iter().zip(KEYWORD_COLORS) {
+            init_pair(cursor_line_pair(*pair), color, bg);
+        }
+        init_pair(cursor_line_pair(GUIDE_COLOR_PAIR), self.guide, bg);
+        init_pair(cursor_line_pair(STATUS_COLOR_PAIR), self.status, bg);
+        init_pair(cursor_line_pair(DIM_COLOR_PAIR), self.dim, bg);
+        init_pair(cursor_line_pair(GUTTER_COLOR_PAIR), self.gutter, bg);
+        init_pair(cursor_line_pair(ERROR_COLOR_PAIR), self.error, bg);
+    }
+}
+
+/// Sets up the color pairs for `colors` in whatever way the terminal allows.
+fn init_colors(window: &Window, colors: &Colors) -> Result<()> {
+    let palette = if can_change_color() {
+        Palette::redefined(colors)?
+    } else if pancurses::COLORS() >= 256 {
+        Palette::nearest(colors)?
+    } else {
+        Palette::basic()
+    };
+    palette.init_pairs();
+    window.bkgd(pancurses::COLOR_PAIR(1));
+    Ok(())
 }
 
 impl Terminal {
-    pub fn new(colors: &Colors) -> Result<Self> {
+    pub fn new(colors: &Colors, no_color: bool) -> Result<Self> {
         let window = initscr();
         window.keypad(true);
         noecho();
@@ -64,6 +294,15 @@ impl Terminal {
         window.nodelay(true); // Make getch() non-blocking
         window.timeout(50); // Set a timeout for getch() to reduce CPU usage
 
+        // Report presses and releases separately so that drags can be tracked
+        mousemask(
+            ALL_MOUSE_EVENTS | REPORT_MOUSE_POSITION,
+            std::ptr::null_mut(),
+        );
+        mouseinterval(0);
+        print!("{ENABLE_BUTTON_MOTION_TRACKING}{ENABLE_BRACKETED_PASTE}{ENABLE_KEYBOARD_PROTOCOL}");
+        let _ = io::stdout().flush();
+
         #[cfg(unix)]
         let original_termios = {
             // termios settings change starts here
@@ -96,6 +335,9 @@ impl Terminal {
             // Disable reprint character (Ctrl+R)
             termios_settings.c_cc[VREPRINT] = _POSIX_VDISABLE;
 
+            // Disable discard character (Ctrl+O)
+            termios_settings.c_cc[VDISCARD] = _POSIX_VDISABLE;
+
             // Disable status character (Ctrl+T)
             #[cfg(target_os = "macos")]
             {
@@ -109,29 +351,10 @@ impl Terminal {
         #[cfg(not(unix))]
         let original_termios = ();
 
-        if pancurses::has_colors() {
+        let monochrome = no_color || !pancurses::has_colors();
+        if !monochrome {
             start_color();
-            if can_change_color() {
-                let (r, g, b) = hex_to_rgb_1000(&colors.bg)?;
-                init_color(13, r, g, b);
-
-                let (r, g, b) = hex_to_rgb_1000(&colors.fg)?;
-                init_color(14, r, g, b);
-
-                let (r, g, b) = hex_to_rgb_1000(&colors.bold)?;
-                init_color(15, r, g, b);
-
-                init_pair(1, 14, 13); // Background
-                init_pair(2, 13, 14); // For highlighting
-                init_pair(3, 15, 13); // Bold
-                window.bkgd(pancurses::COLOR_PAIR(1));
-            } else {
-                use_default_colors();
-                init_pair(1, COLOR_WHITE, -1);
-                init_pair(2, COLOR_BLACK, COLOR_WHITE); // For highlighting
-                init_pair(3, COLOR_YELLOW, -1);
-                window.bkgd(pancurses::COLOR_PAIR(1));
-            }
+            init_colors(&window, colors)?;
         }
         let (tx, rx) = mpsc::channel();
         let tx_clone_for_handler = tx.clone();
@@ -151,9 +374,23 @@ impl Terminal {
             original_termios,
             event_rx: rx,
             event_tx: tx,
+            monochrome,
+            keys: RefCell::new(KeyParser::new(keyname)),
         })
     }
 
+    pub fn is_monochrome(&self) -> bool {
+        self.monochrome
+    }
+
+    /// Switches to `colors`, as when the theme changes.
+    pub fn apply_colors(&self, colors: &Colors) -> Result<()> {
+        if self.monochrome {
+            return Ok(());
+        }
+        init_colors(&self.window, colors)
+    }
+
     pub fn window(&self) -> &Window {
         &self.window
     }
@@ -181,71 +418,106 @@ impl Terminal {
             }
         }
 
-        // If no channel event, check for key input
-        if let Some(key) = self.window.getch() {
-            let mut is_alt_pressed = false;
-            let processed_key = match key {
-                Input::Character('\x1b') => {
-                    // This is an escape character. Could be a standalone ESC, or part of an Alt/Meta sequence, or an arrow key.
-                    let next_key = self.window.getch();
-
-                    match next_key {
-                        Some(Input::Character('[')) => {
-                            // This could be an arrow key sequence (e.g., ESC [ A for Up Arrow)
-                            let third_key = self.window.getch();
-                            match third_key {
-                                Some(Input::Character('A')) => {
-                                    is_alt_pressed = true;
-                                    Input::KeyUp
-                                }
-                                Some(Input::Character('B')) => {
-                                    is_alt_pressed = true;
-                                    Input::KeyDown
-                                }
-                                _ => Input::Character('\x1b'), // Fallback if not an arrow key sequence
-                            }
-                        }
-                        Some(Input::KeyLeft) => {
-                            is_alt_pressed = true;
-                            Input::KeyLeft
-                        }
-                        Some(Input::KeyRight) => {
-                            is_alt_pressed = true;
-                            Input::KeyRight
-                        }
-                        Some(Input::KeyUp) => {
-                            is_alt_pressed = true;
-                            Input::KeyUp
-                        }
-                        Some(Input::KeyDown) => {
-                            is_alt_pressed = true;
-                            Input::KeyDown
-                        }
-                        Some(Input::Character('\x7f') | Input::KeyBackspace) => {
-                            is_alt_pressed = true;
-                            Input::KeyBackspace
-                        }
-                        Some(Input::Character(c)) => {
-                            // If the next character is a printable character, it's likely an Alt/Meta sequence.
-                            is_alt_pressed = true;
-                            Input::Character(c)
-                        }
-                        _ => Input::Character('\x1b'), // Just an escape key
+        // If no channel event, read keys until one is complete. getch() waits
+        // for its timeout, after which a sequence cut short is given up.
+        let mut keys = self.keys.borrow_mut();
+        loop {
+            if let Some(key) = keys.next_key() {
+                return Ok(match key {
+                    KeyInput::Plain(Input::KeyResize, _) => Some(Event::Resize),
+                    KeyInput::Plain(Input::KeyMouse, _) => {
+                        self.next_mouse_event().map(Event::Mouse)
                     }
+                    KeyInput::Plain(key, is_alt_pressed) => Some(Event::Key(key, is_alt_pressed)),
+                    KeyInput::Chord(chord) => Some(chord_event(chord)),
+                    KeyInput::PasteStart => Some(Event::Paste(self.read_paste())),
+                });
+            }
+            match self.read_raw() {
+                Some(RawInput::Byte(byte)) => keys.feed_byte(byte),
+                Some(RawInput::Key(input)) => keys.feed(input),
+                None if keys.is_pending() => keys.timeout(),
+                None => return Ok(None),
+            }
+        }
+    }
+}
+
+impl Terminal {
+    /// Reads a byte of a character, or a key that curses decoded. Bytes are
+    /// read one at a time because pancurses' own decoding drops the rest of a
+    /// character that arrives late, as it can from an input method.
+    #[cfg(unix)]
+    fn read_raw(&self) -> Option<RawInput> {
+        let code = unsafe { getch() };
+        if code < 0 {
+            return None;
+        }
+        if let Ok(byte) = u8::try_from(code) {
+            return Some(RawInput::Byte(byte));
+        }
+        // Handed back for pancurses to name the key
+        unsafe { ungetch(code) };
+        self.window.getch().map(RawInput::Key)
+    }
+
+    #[cfg(not(unix))]
+    fn read_raw(&self) -> Option<RawInput> {
+        self.window.getch().map(RawInput::Key)
+    }
+
+    /// Reads pasted text up to the end marker.
+    fn read_paste(&self) -> String {
+        let mut text = String::new();
+        let mut idle_reads = 0;
+        let mut utf8 = Utf8Assembler::default();
+        while !text.ends_with(PASTE_END) && idle_reads < PASTE_MAX_IDLE_READS {
+            match self.read_raw() {
+                Some(RawInput::Byte(byte)) => text.extend(utf8.push(byte)),
+                Some(RawInput::Key(Input::Character(c))) => text.push(c),
+                Some(RawInput::Key(Input::KeyEnter)) => text.push('\n'),
+                Some(_) => {}
+                None => {
+                    idle_reads += 1;
+                    continue;
                 }
-                Input::KeyResize => {
-                    return Ok(Some(Event::Resize));
-                }
-                _ => key,
-            };
-            return Ok(Some(Event::Key(processed_key, is_alt_pressed)));
+            }
+            idle_reads = 0;
+        }
+        if let Some(stripped) = text.strip_suffix(PASTE_END) {
+            text.truncate(stripped.len());
+        }
+        text
+    }
+
+    fn next_mouse_event(&self) -> Option<MouseEvent> {
+        let event = getmouse().ok()?;
+        let row = event.y.max(0) as usize;
+        let col = event.x.max(0) as usize;
+        let bstate = event.bstate;
+
+        if bstate & BUTTON4_PRESSED != 0 {
+            Some(MouseEvent::ScrollUp)
+        } else if bstate & BUTTON5_PRESSED != 0 {
+            Some(MouseEvent::ScrollDown)
+        } else if bstate & BUTTON1_PRESSED != 0 {
+            Some(MouseEvent::Press { row, col })
+        } else if bstate & BUTTON1_RELEASED != 0 {
+            Some(MouseEvent::Release { row, col })
+        } else if bstate & REPORT_MOUSE_POSITION != 0 {
+            Some(MouseEvent::Drag { row, col })
+        } else {
+            None
         }
-        Ok(None)
     }
 }
 
 impl Drop for Terminal {
     fn drop(&mut self) {
+        print!(
+            "{DISABLE_KEYBOARD_PROTOCOL}{DISABLE_BUTTON_MOTION_TRACKING}{DISABLE_BRACKETED_PASTE}"
+        );
+        let _ = io::stdout().flush();
         #[cfg(unix)]
         {
             let stdin_fd = stdin().as_raw_fd();
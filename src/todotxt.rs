@@ -0,0 +1,125 @@
+const UNCHECKED: &str = "- [ ] ";
+const CHECKED: &str = "- [x] ";
+
+/// Converts a checkbox task to a todo.txt line. Other lines give `None`.
+/// Priorities, contexts and projects are written the same way in both
+/// formats; annotations such as `@due(2025-06-12)` become `due:2025-06-12`.
+pub fn to_todotxt(line: &str) -> Option<String> {
+    let trimmed = line.trim_start();
+    let (done, rest) = match (
+        trimmed.strip_prefix(UNCHECKED),
+        trimmed.strip_prefix(CHECKED),
+    ) {
+        (Some(rest), _) => (false, rest),
+        (_, Some(rest)) => (true, rest),
+        _ => return None,
+    };
+    let (priority, rest) = split_priority(rest);
+
+    let mut completed_on = None;
+    let mut words = Vec::new();
+    for word in rest.split(' ') {
+        match annotation(word) {
+            Some(("done", date)) if done => completed_on = Some(date),
+            Some((name, value)) => words.push(format!("{name}:{value}")),
+            None => words.push(word.to_string()),
+        }
+    }
+    let text = words.join(" ");
+
+    if done {
+        // todo.txt drops the priority of completed tasks, so keep it as a tag
+        let date = completed_on.map(|d| format!("{d} ")).unwrap_or_default();
+        let priority = priority.map(|p| format!(" pri:{p}")).unwrap_or_default();
+        Some(format!("x {date}{text}{priority}"))
+    } else {
+        let priority = priority.map(|p| format!("({p}) ")).unwrap_or_default();
+        Some(format!("{priority}{text}"))
+    }
+}
+
+/// Converts a todo.txt line to a checkbox task. Blank lines give `None`.
+pub fn from_todotxt(line: &str) -> Option<String> {
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
+    }
+    let (done, rest) = match line.strip_prefix("x ") {
+        Some(rest) => (true, rest),
+        None => (false, line),
+    };
+    let (completed_on, rest) = match rest.split_once(' ') {
+        Some((date, rest)) if done && is_date(date) => (Some(date), rest),
+        _ => (None, rest),
+    };
+    let (mut priority, rest) = split_priority(rest);
+
+    let mut words = Vec::new();
+    for word in rest.split(' ') {
+        match tag(word) {
+            Some(("pri", value)) if value.len() == 1 => priority = value.chars().next(),
+            Some((key, value)) => words.push(format!("@{key}({value})")),
+            None => words.push(word.to_string()),
+        }
+    }
+    if let Some(date) = completed_on {
+        words.push(format!("@done({date})"));
+    }
+
+    let checkbox = if done { CHECKED } else { UNCHECKED };
+    let priority = priority.map(|p| format!("({p}) ")).unwrap_or_default();
+    Some(format!("{checkbox}{priority}{}", words.join(" ")))
+}
+
+/// Converts the task lines of a Markdown file to todo.txt, leaving out
+/// everything else.
+pub fn export(content: &str) -> String {
+    content
+        .lines()
+        .filter_map(to_todotxt)
+        .map(|line| line + "\n")
+        .collect()
+}
+
+/// Converts a todo.txt file to a checkbox list.
+pub fn import(content: &str) -> String {
+    content
+        .lines()
+        .filter_map(from_todotxt)
+        .map(|line| line + "\n")
+        .collect()
+}
+
+fn split_priority(text: &str) -> (Option<char>, &str) {
+    let bytes = text.as_bytes();
+    if bytes.len() >= 4
+        && bytes[0] == b'('
+        && bytes[1].is_ascii_uppercase()
+        && bytes[2] == b')'
+        && bytes[3] == b' '
+    {
+        (Some(bytes[1] as char), &text[4..])
+    } else {
+        (None, text)
+    }
+}
+
+/// Splits a whole-word `@name(value)` annotation.
+fn annotation(word: &str) -> Option<(&str, &str)> {
+    let (name, value) = word.strip_prefix('@')?.strip_suffix(')')?.split_once('(')?;
+    (is_key(name) && !value.is_empty()).then_some((name, value))
+}
+
+/// Splits a todo.txt `key:value` tag, leaving URLs alone.
+fn tag(word: &str) -> Option<(&str, &str)> {
+    let (key, value) = word.split_once(':')?;
+    (is_key(key) && !value.is_empty() && !value.starts_with("//")).then_some((key, value))
+}
+
+fn is_key(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_lowercase() || c == '_')
+}
+
+fn is_date(s: &str) -> bool {
+    chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").is_ok()
+}
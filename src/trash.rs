@@ -0,0 +1,167 @@
+//! Files that dmacs removes are moved to `~/.dmacs/trash` instead of being
+//! unlinked, so that they can be brought back with `--trash-restore`. Any
+//! feature that deletes or replaces a file on the user's behalf goes through
+//! `Trash::move_to_trash`.
+
+use crate::error::{DmacsError, Result};
+use crate::persistence;
+use chrono::{Local, NaiveDateTime, SubsecRound};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const INFO_EXTENSION: &str = "trashinfo";
+const DELETED_AT_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+/// A file in the trash.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrashedFile {
+    // Name of the file in the trash, which `restore` takes
+    pub name: String,
+    pub original_path: PathBuf,
+    pub deleted_at: NaiveDateTime,
+}
+
+/// What is kept next to a trashed file, as JSON.
+#[derive(Serialize, Deserialize)]
+struct TrashInfo {
+    original_path: PathBuf,
+    deleted_at: String,
+}
+
+pub struct Trash {
+    trash_dir: PathBuf,
+}
+
+impl Trash {
+    pub fn new() -> Result<Self> {
+        Self::new_with_base_dir(None)
+    }
+
+    pub fn new_with_base_dir(base_dir: Option<PathBuf>) -> Result<Self> {
+        let state_dir = if let Some(dir) = base_dir {
+            dir.join(".dmacs")
+        } else {
+            persistence::state_dir().ok_or(DmacsError::Io(io::Error::new(
+                io::ErrorKind::NotFound,
+                "Home directory not found",
+            )))?
+        };
+        let trash_dir = state_dir.join("trash");
+        fs::create_dir_all(&trash_dir).map_err(DmacsError::Io)?;
+        Ok(Self { trash_dir })
+    }
+
+    /// Moves `path` to the trash, remembering where it came from.
+    pub fn move_to_trash(&self, path: &Path) -> Result<TrashedFile> {
+        let original_path = path.canonicalize().map_err(DmacsError::Io)?;
+        let deleted_at = Local::now().naive_local().trunc_subsecs(0);
+        let file_name = original_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let stamp = deleted_at.format("%Y%m%d%H%M%S");
+        let mut name = format!("{stamp}-{file_name}");
+        let mut copy = 1;
+        while self.trash_dir.join(&name).exists() || self.info_path(&name).exists() {
+            copy += 1;
+            name = format!("{stamp}-{copy}-{file_name}");
+        }
+
+        let trashed = TrashedFile {
+            name,
+            original_path,
+            deleted_at,
+        };
+        let info = serde_json::to_string(&TrashInfo {
+            original_path: trashed.original_path.clone(),
+            deleted_at: deleted_at.format(DELETED_AT_FORMAT).to_string(),
+        })
+        .map_err(|e| DmacsError::Editor(e.to_string()))?;
+        fs::write(self.info_path(&trashed.name), info).map_err(DmacsError::Io)?;
+        if let Err(e) = move_file(&trashed.original_path, &self.trash_dir.join(&trashed.name)) {
+            let _ = fs::remove_file(self.info_path(&trashed.name));
+            return Err(DmacsError::Io(e));
+        }
+        debug!(
+            "Moved {} to the trash as {}",
+            trashed.original_path.display(),
+            trashed.name
+        );
+        Ok(trashed)
+    }
+
+    /// The files in the trash, most recently deleted first.
+    pub fn list(&self) -> Result<Vec<TrashedFile>> {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(&self.trash_dir).map_err(DmacsError::Io)? {
+            let path = entry.map_err(DmacsError::Io)?.path();
+            if path.extension().is_none_or(|ext| ext != INFO_EXTENSION) {
+                continue;
+            }
+            let trashed = read_info(&path);
+            match trashed {
+                Some(trashed) if self.trash_dir.join(&trashed.name).exists() => files.push(trashed),
+                _ => debug!("Skipping unreadable trash entry {}", path.display()),
+            }
+        }
+        files.sort_by(|a, b| {
+            b.deleted_at
+                .cmp(&a.deleted_at)
+                .then_with(|| b.name.cmp(&a.name))
+        });
+        Ok(files)
+    }
+
+    /// Moves the file called `name` in the trash back to where it was
+    /// deleted from, unless another file has taken its place.
+    pub fn restore(&self, name: &str) -> Result<PathBuf> {
+        let trashed = self
+            .list()?
+            .into_iter()
+            .find(|trashed| trashed.name == name)
+            .ok_or_else(|| DmacsError::NotInTrash(name.to_string()))?;
+        if trashed.original_path.exists() {
+            return Err(DmacsError::Io(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} already exists", trashed.original_path.display()),
+            )));
+        }
+        if let Some(parent) = trashed.original_path.parent() {
+            fs::create_dir_all(parent).map_err(DmacsError::Io)?;
+        }
+        move_file(&self.trash_dir.join(name), &trashed.original_path).map_err(DmacsError::Io)?;
+        fs::remove_file(self.info_path(name)).map_err(DmacsError::Io)?;
+        debug!(
+            "Restored {} from the trash",
+            trashed.original_path.display()
+        );
+        Ok(trashed.original_path)
+    }
+
+    fn info_path(&self, name: &str) -> PathBuf {
+        self.trash_dir.join(format!("{name}.{INFO_EXTENSION}"))
+    }
+}
+
+/// The entry described by the info file at `path`.
+fn read_info(path: &Path) -> Option<TrashedFile> {
+    let info: TrashInfo = serde_json::from_str(&fs::read_to_string(path).ok()?).ok()?;
+    Some(TrashedFile {
+        name: path.file_stem()?.to_string_lossy().into_owned(),
+        original_path: info.original_path,
+        deleted_at: NaiveDateTime::parse_from_str(&info.deleted_at, DELETED_AT_FORMAT).ok()?,
+    })
+}
+
+/// Renames `from` to `to`, copying it when they are on different file
+/// systems.
+fn move_file(from: &Path, to: &Path) -> io::Result<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    fs::copy(from, to)?;
+    fs::remove_file(from)
+}
@@ -0,0 +1,118 @@
+use chrono::NaiveDate;
+use dmacs::agenda::{self, AgendaGroup, AgendaScope};
+use dmacs::document::LineBuffer;
+use std::fs;
+use tempfile::TempDir;
+
+fn date(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+fn lines(lines: &[&str]) -> LineBuffer {
+    lines.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn test_group_for_date() {
+    // A Wednesday
+    let today = date("2025-06-11");
+    assert_eq!(
+        AgendaGroup::for_date(date("2025-06-10"), today),
+        Some(AgendaGroup::Overdue)
+    );
+    assert_eq!(
+        AgendaGroup::for_date(today, today),
+        Some(AgendaGroup::Today)
+    );
+    assert_eq!(
+        AgendaGroup::for_date(date("2025-06-15"), today),
+        Some(AgendaGroup::ThisWeek)
+    );
+    assert_eq!(AgendaGroup::for_date(date("2025-06-16"), today), None);
+}
+
+#[test]
+fn test_collect_all_tasks_groups_later_and_undated() {
+    let today = date("2025-06-11");
+    let mut items = agenda::collect_tasks_in_scope(
+        &lines(&[
+            "- [ ] no date",
+            "- [ ] next month @due(2025-07-01)",
+            "- [x] done",
+            "- [ ] today @due(2025-06-11)",
+        ]),
+        None,
+        today,
+        AgendaScope::All,
+    );
+    agenda::sort_items(&mut items);
+    let found: Vec<(usize, AgendaGroup)> = items.iter().map(|i| (i.line, i.group)).collect();
+    assert_eq!(
+        found,
+        vec![
+            (3, AgendaGroup::Today),
+            (1, AgendaGroup::Later),
+            (0, AgendaGroup::NoDate)
+        ]
+    );
+    assert_eq!(items[2].due, None);
+}
+
+#[test]
+fn test_collect_tasks_skips_checked_and_undated() {
+    let today = date("2025-06-11");
+    let items = agenda::collect_tasks(
+        &lines(&[
+            "- [ ] later @due(2025-06-13)",
+            "- [x] done @due(2025-06-01)",
+            "- [ ] no date",
+            "  - [ ] nested @due(2025-06-01)",
+            "- [ ] next month @due(2025-07-01)",
+        ]),
+        None,
+        today,
+    );
+    let found: Vec<(usize, &str)> = items.iter().map(|i| (i.line, i.text.as_str())).collect();
+    assert_eq!(
+        found,
+        vec![
+            (0, "- [ ] later @due(2025-06-13)"),
+            (3, "- [ ] nested @due(2025-06-01)")
+        ]
+    );
+
+    let mut items = items;
+    agenda::sort_items(&mut items);
+    assert_eq!(items[0].group, AgendaGroup::Overdue);
+    assert_eq!(items[1].group, AgendaGroup::ThisWeek);
+}
+
+#[test]
+fn test_collect_dir_and_check_off() {
+    let dir = TempDir::new().unwrap();
+    let today = date("2025-06-11");
+    fs::create_dir(dir.path().join("daily")).unwrap();
+    let page = dir.path().join("daily").join("2025-06-10.md");
+    fs::write(&page, "# notes\n- [ ] call bank @due(2025-06-11)\n").unwrap();
+    let open_file = dir.path().join("open.md");
+    fs::write(&open_file, "- [ ] already open @due(2025-06-11)\n").unwrap();
+    fs::write(
+        dir.path().join("other.txt"),
+        "- [ ] ignored @due(2025-06-11)\n",
+    )
+    .unwrap();
+
+    let items = agenda::collect_dir(dir.path(), Some(&open_file), today);
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].path.as_deref(), Some(page.as_path()));
+    assert_eq!(items[0].line, 1);
+    assert_eq!(items[0].group, AgendaGroup::Today);
+
+    agenda::check_off_in_file(&items[0]).unwrap();
+    assert_eq!(
+        fs::read_to_string(&page).unwrap(),
+        "# notes\n- [x] call bank @due(2025-06-11)\n"
+    );
+    // The line no longer holds the listed task
+    assert!(agenda::check_off_in_file(&items[0]).is_err());
+}
@@ -1,5 +1,5 @@
 use chrono::{Duration, Local};
-use dmacs::backup::BackupManager;
+use dmacs::backup::{BackupManager, RetentionPolicy};
 use sha2::{Digest, Sha256};
 use std::fs;
 use std::path::PathBuf;
@@ -193,3 +193,136 @@ fn test_restore_backup_not_found() {
 
     teardown_test_env(&temp_dir);
 }
+
+#[test]
+fn test_copy_backups() {
+    let temp_dir = setup_test_env();
+    let backup_manager = BackupManager::new_with_base_dir(Some(temp_dir.clone())).unwrap();
+
+    let old_file = temp_dir.join("old.txt");
+    let new_file = temp_dir.join("new.txt");
+    fs::write(&old_file, "current").unwrap();
+    fs::write(&new_file, "current").unwrap();
+    let old_str = old_file.to_str().unwrap();
+    let new_str = new_file.to_str().unwrap();
+    backup_manager.save_backup(old_str, "previous").unwrap();
+
+    backup_manager.copy_backups(old_str, new_str).unwrap();
+    backup_manager.restore_backup(new_str).unwrap();
+    assert_eq!(fs::read_to_string(&new_file).unwrap(), "previous");
+    // The old file keeps its own backups
+    backup_manager.restore_backup(old_str).unwrap();
+    assert_eq!(fs::read_to_string(&old_file).unwrap(), "previous");
+
+    teardown_test_env(&temp_dir);
+}
+
+// Saves a backup of `content` and moves it back to `timestamp`
+fn save_backup_at(backup_manager: &BackupManager, filename: &str, content: &str, timestamp: &str) {
+    backup_manager.save_backup(filename, content).unwrap();
+    let latest = backup_manager.list_backups(filename).unwrap().remove(0);
+    let name = latest.path.file_name().unwrap().to_str().unwrap();
+    let prefix = &name[..name.len() - "20250101000000.bak".len()];
+    fs::rename(
+        &latest.path,
+        latest
+            .path
+            .with_file_name(format!("{prefix}{timestamp}.bak")),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_list_backups_newest_first() {
+    let temp_dir = setup_test_env();
+    let backup_manager = BackupManager::new_with_base_dir(Some(temp_dir.clone())).unwrap();
+    let file = temp_dir.join("notes.md");
+    fs::write(&file, "current").unwrap();
+    let filename = file.to_str().unwrap();
+    save_backup_at(&backup_manager, filename, "first", "20250101090000");
+    save_backup_at(&backup_manager, filename, "third", "20250103090000");
+    save_backup_at(&backup_manager, filename, "second", "20250102090000");
+
+    let backups = backup_manager.list_backups(filename).unwrap();
+    let contents: Vec<String> = backups.iter().map(|b| b.read().unwrap()).collect();
+    assert_eq!(contents, vec!["third", "second", "first"]);
+    assert_eq!(
+        backups[0].created.format("%Y-%m-%d %H:%M").to_string(),
+        "2025-01-03 09:00"
+    );
+
+    teardown_test_env(&temp_dir);
+}
+
+#[test]
+fn test_prune_backups_keeps_newest() {
+    let temp_dir = setup_test_env();
+    let backup_manager = BackupManager::new_with_base_dir(Some(temp_dir.clone()))
+        .unwrap()
+        .with_retention(RetentionPolicy {
+            max_per_file: Some(2),
+            ..RetentionPolicy::default()
+        });
+    let file = temp_dir.join("notes.md");
+    let other = temp_dir.join("other.md");
+    fs::write(&file, "current").unwrap();
+    fs::write(&other, "current").unwrap();
+    let filename = file.to_str().unwrap();
+    save_backup_at(&backup_manager, filename, "first", "20250101090000");
+    save_backup_at(&backup_manager, filename, "second", "20250102090000");
+    save_backup_at(&backup_manager, filename, "third", "20250103090000");
+    save_backup_at(
+        &backup_manager,
+        other.to_str().unwrap(),
+        "other",
+        "20250101090000",
+    );
+
+    backup_manager.prune_backups(filename).unwrap();
+    let contents: Vec<String> = backup_manager
+        .list_backups(filename)
+        .unwrap()
+        .iter()
+        .map(|b| b.read().unwrap())
+        .collect();
+    assert_eq!(contents, vec!["third", "second"]);
+    // Other files keep theirs
+    assert_eq!(
+        backup_manager
+            .list_backups(other.to_str().unwrap())
+            .unwrap()
+            .len(),
+        1
+    );
+
+    teardown_test_env(&temp_dir);
+}
+
+#[test]
+fn test_clean_old_backups_uses_max_age() {
+    let temp_dir = setup_test_env();
+    let backup_manager = BackupManager::new_with_base_dir(Some(temp_dir.clone()))
+        .unwrap()
+        .with_retention(RetentionPolicy {
+            max_per_file: None,
+            max_age_days: 30,
+        });
+    let file = temp_dir.join("notes.md");
+    fs::write(&file, "current").unwrap();
+    let filename = file.to_str().unwrap();
+    let ten_days_ago = (Local::now() - Duration::days(10))
+        .format("%Y%m%d%H%M%S")
+        .to_string();
+    let sixty_days_ago = (Local::now() - Duration::days(60))
+        .format("%Y%m%d%H%M%S")
+        .to_string();
+    save_backup_at(&backup_manager, filename, "recent", &ten_days_ago);
+    save_backup_at(&backup_manager, filename, "old", &sixty_days_ago);
+
+    backup_manager.clean_old_backups().unwrap();
+    let backups = backup_manager.list_backups(filename).unwrap();
+    assert_eq!(backups.len(), 1);
+    assert_eq!(backups[0].read().unwrap(), "recent");
+
+    teardown_test_env(&temp_dir);
+}
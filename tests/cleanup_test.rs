@@ -0,0 +1,59 @@
+use dmacs::cleanup::{CleanupRule, cleanup};
+use dmacs::document::LineBuffer;
+
+fn lines(text: &[&str]) -> LineBuffer {
+    text.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn test_cleanup_all_rules() {
+    let input = lines(&[
+        "# Notes  ",
+        "* one",
+        "  + two",
+        "",
+        "",
+        "",
+        "text",
+        "",
+        "",
+        "end",
+        "",
+        " ",
+    ]);
+    let result = cleanup(&input, &CleanupRule::ALL);
+    assert_eq!(
+        result.lines,
+        ["# Notes", "- one", "  - two", "", "text", "", "", "end"]
+    );
+    assert_eq!(
+        result.summary.describe(),
+        "Cleanup: trimmed 2 lines, normalized 2 bullets, removed 4 blank lines."
+    );
+    assert_eq!(result.line_map[6], 4);
+    assert_eq!(result.line_map[5], 3);
+    assert_eq!(result.line_map[11], 7);
+}
+
+#[test]
+fn test_cleanup_selected_rules() {
+    let input = lines(&["* item  ", "", "", ""]);
+    let result = cleanup(&input, &[CleanupRule::Bullets]);
+    assert_eq!(result.lines, ["- item  ", "", "", ""]);
+    assert_eq!(result.summary.describe(), "Cleanup: normalized 1 bullet.");
+}
+
+#[test]
+fn test_cleanup_nothing_to_change() {
+    let input = lines(&["- done", "", "", "**bold**"]);
+    let result = cleanup(&input, &CleanupRule::ALL);
+    assert_eq!(result.lines, input.to_vec());
+    assert!(result.summary.is_empty());
+    assert_eq!(result.summary.describe(), "Cleanup: nothing to change.");
+}
+
+#[test]
+fn test_parse_rule_names() {
+    assert_eq!(CleanupRule::parse("bullets"), Some(CleanupRule::Bullets));
+    assert_eq!(CleanupRule::parse("tabs"), None);
+}
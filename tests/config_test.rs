@@ -0,0 +1,146 @@
+use dmacs::config::{self, CONFIG_ENV, Colors, Config, THEME_ENV};
+use dmacs::persistence::{self, STATE_DIR_ENV};
+use dmacs::scratch;
+use serial_test::serial;
+use std::fs;
+use tempfile::tempdir;
+
+/// Runs `test` with the environment variables set, restoring them after.
+fn with_env(vars: &[(&str, &str)], test: impl FnOnce()) {
+    let saved: Vec<_> = vars
+        .iter()
+        .map(|(name, _)| (*name, std::env::var_os(name)))
+        .collect();
+    for (name, value) in vars {
+        unsafe {
+            std::env::set_var(name, value);
+        }
+    }
+    test();
+    for (name, value) in saved {
+        match value {
+            Some(value) => unsafe { std::env::set_var(name, value) },
+            None => unsafe { std::env::remove_var(name) },
+        }
+    }
+}
+
+#[test]
+#[serial]
+fn test_config_env_replaces_config_file() {
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("demo.toml");
+    fs::write(
+        &config_path,
+        "[colors]\nbg = \"#ffffff\"\n\n[editor]\nindent_width = 4\n",
+    )
+    .unwrap();
+    with_env(&[(CONFIG_ENV, config_path.to_str().unwrap())], || {
+        let config = Config::load();
+        assert_eq!(config.colors.bg, "#ffffff");
+        assert_eq!(config.editor.indent_width(), 4);
+    });
+}
+
+#[test]
+#[serial]
+fn test_theme_env_overrides_config_colors() {
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("config.toml");
+    fs::write(
+        &config_path,
+        "[colors]\nbg = \"#ffffff\"\nfg = \"#101010\"\n",
+    )
+    .unwrap();
+    let theme_path = dir.path().join("dark.toml");
+    fs::write(&theme_path, "[colors]\nbg = \"#000000\"\n").unwrap();
+    with_env(
+        &[
+            (CONFIG_ENV, config_path.to_str().unwrap()),
+            (THEME_ENV, theme_path.to_str().unwrap()),
+        ],
+        || {
+            let config = Config::load();
+            assert_eq!(config.colors.bg, "#000000");
+            assert_eq!(config.colors.fg, "#101010");
+        },
+    );
+}
+
+#[test]
+#[serial]
+fn test_state_dir_env_moves_state_files() {
+    let dir = tempdir().unwrap();
+    let state_dir = dir.path().join("state");
+    with_env(&[(STATE_DIR_ENV, state_dir.to_str().unwrap())], || {
+        assert_eq!(persistence::state_dir(), Some(state_dir.clone()));
+        assert_eq!(
+            scratch::scratch_path(None).unwrap(),
+            state_dir.join("scratch.md")
+        );
+        assert_eq!(
+            persistence::recent_files_path(None),
+            Some(state_dir.join("recent_files.json"))
+        );
+    });
+}
+
+#[test]
+#[serial]
+fn test_builtin_theme_with_colors_over_it() {
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("config.toml");
+    fs::write(
+        &config_path,
+        "[colors]\nselection = \"#123456\"\n\n[editor]\ntheme = \"light\"\n",
+    )
+    .unwrap();
+    with_env(&[(CONFIG_ENV, config_path.to_str().unwrap())], || {
+        let config = Config::load();
+        let light = Colors::builtin("light").unwrap();
+        assert_eq!(config.editor.theme.as_deref(), Some("light"));
+        assert_eq!(config.colors.bg, light.bg);
+        assert_eq!(config.colors.error, light.error);
+        assert_eq!(config.colors.selection, "#123456");
+    });
+}
+
+#[test]
+#[serial]
+fn test_theme_files_follow_builtin_themes() {
+    let home = tempdir().unwrap();
+    let themes = home.path().join(".dmacs/themes");
+    fs::create_dir_all(&themes).unwrap();
+    fs::write(
+        themes.join("solarized.toml"),
+        "[colors]\nbg = \"#002b36\"\n",
+    )
+    .unwrap();
+    fs::write(themes.join("notes.txt"), "not a theme").unwrap();
+    with_env(&[("HOME", home.path().to_str().unwrap())], || {
+        assert_eq!(config::theme_names(), vec!["dark", "light", "solarized"]);
+        let solarized = config::theme_colors("solarized").unwrap();
+        assert_eq!(solarized.bg, "#002b36");
+        assert_eq!(solarized.fg, Colors::default().fg);
+        assert_eq!(config::theme_colors("dark"), Some(Colors::default()));
+        assert_eq!(config::theme_colors("missing"), None);
+    });
+}
+
+#[test]
+#[serial]
+fn test_snippets_are_read_next_to_the_config_file() {
+    let dir = tempdir().unwrap();
+    let config_path = dir.path().join("config.toml");
+    fs::write(&config_path, "[editor]\nindent_width = 4\n").unwrap();
+    fs::write(
+        dir.path().join("snippets.toml"),
+        "link = \"[$1]($2)$0\"\npost = \"\"\"\n# $1\n$0\n\"\"\"\n",
+    )
+    .unwrap();
+    with_env(&[(CONFIG_ENV, config_path.to_str().unwrap())], || {
+        let snippets = Config::load().editor.snippets.unwrap();
+        assert_eq!(snippets["link"], "[$1]($2)$0");
+        assert_eq!(snippets["post"], "# $1\n$0\n");
+    });
+}
@@ -0,0 +1,79 @@
+use dmacs::copy_format::{CopyFormat, convert};
+
+const NOTES: &str = "## Plan
+- [x] book **flights**
+- [ ] pack
+  - charger
+See [docs](https://example.com) for `make`.";
+
+#[test]
+fn test_plain_text() {
+    assert_eq!(
+        convert(NOTES, CopyFormat::PlainText),
+        "Plan
+✓ book flights
+✗ pack
+  • charger
+See docs (https://example.com) for make."
+    );
+}
+
+#[test]
+fn test_html() {
+    assert_eq!(
+        convert(NOTES, CopyFormat::Html),
+        "<h2>Plan</h2>
+<ul>
+  <li><input type=\"checkbox\" checked disabled> book <strong>flights</strong></li>
+  <li><input type=\"checkbox\" disabled> pack</li>
+  <ul>
+    <li>charger</li>
+  </ul>
+</ul>
+<p>See <a href=\"https://example.com\">docs</a> for <code>make</code>.</p>"
+    );
+    assert_eq!(
+        convert("a < b & *c*", CopyFormat::Html),
+        "<p>a &lt; b &amp; <em>c</em></p>"
+    );
+}
+
+#[test]
+fn test_jira() {
+    assert_eq!(
+        convert(NOTES, CopyFormat::Jira),
+        "h2. Plan
+* (/) book *flights*
+* (x) pack
+** charger
+See [docs|https://example.com] for {{make}}."
+    );
+}
+
+#[test]
+fn test_slack() {
+    assert_eq!(
+        convert(NOTES, CopyFormat::Slack),
+        "*Plan*
+✓ book *flights*
+✗ pack
+  • charger
+See <https://example.com|docs> for `make`."
+    );
+    assert_eq!(convert("~~old~~ *new*", CopyFormat::Slack), "~old~ _new_");
+}
+
+#[test]
+fn test_unclosed_markers_stay_text() {
+    assert_eq!(
+        convert("2 * 3 = 6 and [not a link", CopyFormat::PlainText),
+        "2 * 3 = 6 and [not a link"
+    );
+}
+
+#[test]
+fn test_parse_format_names() {
+    assert_eq!(CopyFormat::parse(" HTML "), Some(CopyFormat::Html));
+    assert_eq!(CopyFormat::parse("plain"), Some(CopyFormat::PlainText));
+    assert_eq!(CopyFormat::parse("rtf"), None);
+}
@@ -0,0 +1,89 @@
+use chrono::NaiveDate;
+use dmacs::dashboard;
+use std::fs;
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+fn today() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 6, 11).unwrap()
+}
+
+fn notes_dir() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    fs::create_dir(dir.path().join("work")).unwrap();
+    fs::write(
+        dir.path().join("inbox.md"),
+        "- [ ] call @due(2025-06-01)\n- [x] done @due(2025-06-01)\n- [ ] read\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("work").join("project.md"),
+        "# Project\n  - [ ] ship @due(2025-06-20)\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join("empty.md"), "- [x] all done\n").unwrap();
+    fs::write(dir.path().join("todo.txt"), "- [ ] not markdown\n").unwrap();
+    dir
+}
+
+#[test]
+fn test_summarize_counts_open_and_overdue_tasks_per_file() {
+    let dir = notes_dir();
+    let summaries = dashboard::summarize(dir.path(), today());
+
+    let counts: Vec<(PathBuf, usize, usize)> = summaries
+        .iter()
+        .map(|summary| (summary.path.clone(), summary.open, summary.overdue))
+        .collect();
+    assert_eq!(
+        counts,
+        vec![
+            (PathBuf::from("inbox.md"), 2, 1),
+            (PathBuf::from("work/project.md"), 1, 0),
+        ]
+    );
+    assert!(summaries.iter().all(|summary| summary.modified.is_some()));
+}
+
+#[test]
+fn test_table_has_a_total_row() {
+    let dir = notes_dir();
+    let mut summaries = dashboard::summarize(dir.path(), today());
+    for summary in &mut summaries {
+        summary.modified = None;
+    }
+
+    assert_eq!(
+        dashboard::to_table(&summaries),
+        "File             Open  Overdue  Modified\n\
+         inbox.md            2        1\n\
+         work/project.md     1        0\n\
+         Total               3        1\n"
+    );
+}
+
+#[test]
+fn test_json_lists_files_and_totals() {
+    let dir = notes_dir();
+    let summaries = dashboard::summarize(dir.path(), today());
+    let json: serde_json::Value = serde_json::from_str(&dashboard::to_json(&summaries)).unwrap();
+
+    assert_eq!(json["open"], 3);
+    assert_eq!(json["overdue"], 1);
+    assert_eq!(json["files"][0]["path"], "inbox.md");
+    assert_eq!(json["files"][0]["open"], 2);
+    assert_eq!(json["files"][1]["path"], "work/project.md");
+    assert!(json["files"][1]["modified"].is_string());
+}
+
+#[test]
+fn test_empty_directory() {
+    let dir = TempDir::new().unwrap();
+    let summaries = dashboard::summarize(dir.path(), today());
+
+    assert!(summaries.is_empty());
+    assert_eq!(
+        dashboard::to_table(&summaries),
+        "File   Open  Overdue  Modified\nTotal     0        0\n"
+    );
+}
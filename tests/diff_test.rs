@@ -0,0 +1,38 @@
+use dmacs::diff::{Hunk, diff_lines, hunks, map_line};
+
+#[test]
+fn test_hunks_of_separate_changes() {
+    let old = ["a", "b", "c", "d", "e"];
+    let new = ["new", "a", "b", "D", "e"];
+    let hunks = hunks(&diff_lines(&old, &new));
+    assert_eq!(
+        hunks,
+        vec![
+            Hunk {
+                old_start: 0,
+                old_len: 0,
+                new_start: 0,
+                new_len: 1
+            },
+            Hunk {
+                old_start: 2,
+                old_len: 2,
+                new_start: 3,
+                new_len: 1
+            },
+        ]
+    );
+
+    // Unchanged lines shift, changed ones go to what replaced them
+    assert_eq!(map_line(&hunks, 0), 1);
+    assert_eq!(map_line(&hunks, 2), 3);
+    assert_eq!(map_line(&hunks, 3), 3);
+    assert_eq!(map_line(&hunks, 4), 4);
+}
+
+#[test]
+fn test_no_hunks_for_same_lines() {
+    let lines = ["a", "b"];
+    assert!(hunks(&diff_lines(&lines, &lines)).is_empty());
+    assert_eq!(map_line(&[], 1), 1);
+}
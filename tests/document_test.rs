@@ -1,4 +1,4 @@
-use dmacs::document::Document;
+use dmacs::document::{ActionDiff, Document, LineBuffer, LineEnding, SaveOptions};
 use std::fs;
 use std::path::PathBuf;
 
@@ -22,9 +22,9 @@ fn test_open_document() {
     fs::write(filename, "hello\nworld").unwrap();
 
     let doc = Document::open(filename).unwrap();
-    assert_eq!(doc.lines.len(), 2);
-    assert_eq!(doc.lines[0], "hello");
-    assert_eq!(doc.lines[1], "world");
+    assert_eq!(doc.lines().len(), 2);
+    assert_eq!(doc.lines()[0], "hello");
+    assert_eq!(doc.lines()[1], "world");
 
     fs::remove_file(filename).unwrap();
 }
@@ -35,7 +35,7 @@ fn test_document_save() {
     let filename = temp_dir.join("test_save.txt");
     let mut doc = Document::new_empty();
     doc.filename = Some(filename.to_str().unwrap().to_string());
-    doc.lines = vec!["line1".to_string(), "line2".to_string()];
+    doc.set_lines(vec!["line1".to_string(), "line2".to_string()]);
     doc.save(Some(temp_dir.clone())).unwrap();
 
     let content = fs::read_to_string(&filename).unwrap();
@@ -66,7 +66,7 @@ fn test_is_dirty_after_modification() {
     fs::write(filename, content).unwrap();
 
     let mut doc = Document::open(filename).unwrap();
-    doc.lines = vec!["line1".to_string()];
+    doc.set_lines(vec!["line1".to_string()]);
     assert!(
         doc.is_dirty(),
         "Document should be dirty after modification."
@@ -83,7 +83,7 @@ fn test_is_dirty_after_save() {
     fs::write(&filename, content).unwrap();
 
     let mut doc = Document::open(filename.to_str().unwrap()).unwrap();
-    doc.lines = vec!["line1".to_string()];
+    doc.set_lines(vec!["line1".to_string()]);
     assert!(doc.is_dirty(), "Document should be dirty before saving.");
     doc.save(Some(temp_dir.clone())).unwrap();
     assert!(
@@ -114,3 +114,212 @@ fn test_is_dirty_after_opening_file_no_trailing_newline() {
 
     fs::remove_file(filename).unwrap();
 }
+
+#[test]
+fn test_apply_action_diff_multiline_replace_and_undo() {
+    let mut doc = Document::new_empty();
+    doc.set_lines(vec![
+        "alpha".to_string(),
+        "beta".to_string(),
+        "gamma".to_string(),
+        "delta".to_string(),
+    ]);
+    // Replace "pha\nbeta\ngam" with "X\nY\nZ\nW"
+    let diff = ActionDiff {
+        cursor_start_x: 2,
+        cursor_start_y: 0,
+        cursor_end_x: 1,
+        cursor_end_y: 3,
+        start_x: 2,
+        start_y: 0,
+        end_x: 3,
+        end_y: 2,
+        old: vec!["pha".to_string(), "beta".to_string(), "gam".to_string()],
+        new: vec![
+            "X".to_string(),
+            "Y".to_string(),
+            "Z".to_string(),
+            "W".to_string(),
+        ],
+    };
+    let undo_diff = ActionDiff {
+        start_x: 2,
+        start_y: 0,
+        end_x: 1,
+        end_y: 3,
+        ..diff.clone()
+    };
+
+    assert_eq!(doc.apply_action_diff(&diff, false).unwrap(), (1, 3));
+    assert_eq!(doc.lines(), vec!["alX", "Y", "Z", "Wma", "delta"]);
+
+    assert_eq!(doc.apply_action_diff(&undo_diff, true).unwrap(), (2, 0));
+    assert_eq!(doc.lines(), vec!["alpha", "beta", "gamma", "delta"]);
+}
+
+#[test]
+fn test_save_and_reopen_large_document() {
+    let temp_dir = setup_test_env();
+    let filename = temp_dir.join("test_large.txt");
+    let mut doc = Document::new_empty();
+    doc.filename = Some(filename.to_str().unwrap().to_string());
+    doc.set_lines((0..100_000).map(|i| format!("line {i}")).collect());
+    doc.save(Some(temp_dir.clone())).unwrap();
+    assert!(!doc.is_dirty());
+
+    let reopened = Document::open(filename.to_str().unwrap()).unwrap();
+    assert_eq!(reopened.lines().len(), 100_000);
+    assert_eq!(reopened.lines()[99_999], "line 99999");
+    assert!(!reopened.is_dirty());
+
+    teardown_test_env(&temp_dir);
+}
+
+#[test]
+fn test_line_buffer_edits_on_either_side_of_the_gap() {
+    let mut expected: Vec<String> = (0..10).map(|i| i.to_string()).collect();
+    let mut lines = LineBuffer::from(expected.clone());
+
+    lines.insert(7, "a".to_string());
+    expected.insert(7, "a".to_string());
+    assert_eq!(lines.remove(2), expected.remove(2));
+    lines.splice(4..6, ["b".to_string()]);
+    expected.splice(4..6, ["b".to_string()]);
+    assert_eq!(lines.drain(7..), expected.drain(7..).collect::<Vec<_>>());
+    lines.push("c".to_string());
+    expected.push("c".to_string());
+    lines[0].push('!');
+    expected[0].push('!');
+    assert_eq!(lines, expected);
+
+    assert_eq!(lines.len(), expected.len());
+    assert_eq!(lines.last(), expected.last());
+    assert_eq!(lines.get(expected.len()), None);
+    assert!(lines.iter().rev().eq(expected.iter().rev()));
+}
+
+#[test]
+fn test_line_buffer_range() {
+    let mut lines: LineBuffer = (0..6).map(|i| i.to_string()).collect();
+    // Leaves the gap after line 3
+    lines.insert(3, "x".to_string());
+    assert_eq!(lines.range(2..5).collect::<Vec<_>>(), ["2", "x", "3"]);
+    assert_eq!(lines.range(..=1).rev().collect::<Vec<_>>(), ["1", "0"]);
+    assert_eq!(lines.range(4..).nth(1), Some(&"4".to_string()));
+    assert_eq!(lines.range(1..6).rposition(|line| line == "x"), Some(2));
+    assert_eq!(lines.range(3..3).count(), 0);
+}
+
+#[test]
+fn test_apply_action_diff_far_from_the_last_edit() {
+    let mut doc = Document::new_empty();
+    doc.set_lines((0..1000).map(|i| format!("line {i}")).collect());
+    let break_line = |y: usize| ActionDiff {
+        cursor_start_x: 2,
+        cursor_start_y: y,
+        cursor_end_x: 0,
+        cursor_end_y: y + 1,
+        start_x: 2,
+        start_y: y,
+        end_x: 0,
+        end_y: y + 1,
+        old: vec![],
+        new: vec![String::new(), String::new()],
+    };
+    doc.apply_action_diff(&break_line(900), false).unwrap();
+    doc.apply_action_diff(&break_line(10), false).unwrap();
+    assert_eq!(doc.lines().len(), 1002);
+    assert_eq!(doc.lines()[10], "li");
+    assert_eq!(doc.lines()[11], "ne 10");
+    assert_eq!(doc.lines()[901], "li");
+    assert_eq!(doc.lines()[902], "ne 900");
+
+    doc.apply_action_diff(&break_line(901), true).unwrap();
+    doc.apply_action_diff(&break_line(10), true).unwrap();
+    let original: Vec<String> = (0..1000).map(|i| format!("line {i}")).collect();
+    assert_eq!(doc.lines(), original);
+}
+
+fn save_options() -> SaveOptions {
+    SaveOptions {
+        trim_trailing_whitespace: true,
+        ensure_final_newline: true,
+        tabs_to_spaces: true,
+    }
+}
+
+#[test]
+fn test_save_options_apply() {
+    let lines: LineBuffer = ["a  ", "\tb\tc", "  ", "", ""]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    assert_eq!(save_options().apply(&lines), vec!["a", "    b   c"]);
+
+    let only_newline = SaveOptions {
+        ensure_final_newline: true,
+        ..SaveOptions::default()
+    };
+    assert_eq!(only_newline.apply(&lines), vec!["a  ", "\tb\tc", "  "]);
+    assert_eq!(
+        only_newline.apply(&LineBuffer::from(vec![String::new()])),
+        vec![""]
+    );
+}
+
+#[test]
+fn test_save_options_map_position() {
+    let lines: LineBuffer = ["a  ", "\tb\tc", "", ""]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    let options = save_options();
+    assert_eq!(options.map_position(&lines, 3, 0), (1, 0));
+    assert_eq!(options.map_position(&lines, 3, 1), (8, 1));
+    assert_eq!(options.map_position(&lines, 0, 3), (9, 1));
+}
+
+#[test]
+fn test_document_save_applies_save_options() {
+    let temp_dir = setup_test_env();
+    let filename = temp_dir.join("hooks.txt");
+    fs::write(&filename, "").unwrap();
+
+    let mut doc = Document::open(filename.to_str().unwrap()).unwrap();
+    doc.set_lines(vec!["x \t".to_string(), String::new(), String::new()]);
+    doc.save_options = save_options();
+    doc.save(Some(temp_dir.clone())).unwrap();
+    assert_eq!(fs::read_to_string(&filename).unwrap(), "x\n");
+
+    teardown_test_env(&temp_dir);
+}
+
+#[test]
+fn test_crlf_line_endings_are_kept_on_save() {
+    let temp_dir = setup_test_env();
+    let filename = temp_dir.join("windows.txt");
+    fs::write(&filename, "one\r\ntwo\r\n").unwrap();
+
+    let mut doc = Document::open(filename.to_str().unwrap()).unwrap();
+    assert_eq!(doc.line_ending, LineEnding::CrLf);
+    assert_eq!(doc.lines(), vec!["one", "two"]);
+    assert!(!doc.is_dirty());
+
+    doc.replace_lines(2..2, vec!["three".to_string()]);
+    doc.save(Some(temp_dir.clone())).unwrap();
+    assert_eq!(
+        fs::read_to_string(&filename).unwrap(),
+        "one\r\ntwo\r\nthree\r\n"
+    );
+    assert!(!doc.is_dirty());
+
+    teardown_test_env(&temp_dir);
+}
+
+#[test]
+fn test_detect_line_ending() {
+    assert_eq!(LineEnding::detect("a\nb\n"), LineEnding::Lf);
+    assert_eq!(LineEnding::detect("a\r\nb"), LineEnding::CrLf);
+    assert_eq!(LineEnding::detect("a\r\nb\nc\nd\n"), LineEnding::Lf);
+    assert_eq!(LineEnding::parse("CRLF"), Some(LineEnding::CrLf));
+}
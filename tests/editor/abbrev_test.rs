@@ -0,0 +1,96 @@
+use dmacs::editor::Editor;
+use dmacs::editor::actions::Action;
+use dmacs::file_settings::FileSettings;
+use pancurses::Input;
+use std::collections::HashMap;
+
+fn create_editor_with_content(content: &str) -> Editor {
+    let mut editor = Editor::new(None, None, None);
+    editor
+        .document
+        .set_lines(content.lines().map(|s| s.to_string()).collect());
+    if editor.document.lines().is_empty() {
+        editor.document.set_lines(vec![String::new()]);
+    }
+    editor.settings.abbrevs = Some(HashMap::from([
+        ("teh".to_string(), "the".to_string()),
+        ("btw".to_string(), "by the way".to_string()),
+        ("sig".to_string(), "Regards,\nDev".to_string()),
+    ]));
+    editor
+}
+
+fn type_text(editor: &mut Editor, text: &str) {
+    for c in text.chars() {
+        editor.process_input(Input::Character(c), false).unwrap();
+    }
+}
+
+#[test]
+fn test_abbrev_expands_after_a_word_boundary() {
+    let mut editor = create_editor_with_content("");
+    type_text(&mut editor, "teh cat, btw.");
+    assert_eq!(editor.document.lines()[0], "the cat, by the way.");
+    assert_eq!(editor.cursor_pos(), (20, 0));
+}
+
+#[test]
+fn test_abbrev_needs_a_whole_word() {
+    let mut editor = create_editor_with_content("");
+    type_text(&mut editor, "tehran xteh teh");
+    assert_eq!(editor.document.lines()[0], "tehran xteh teh");
+    type_text(&mut editor, "\n");
+    assert_eq!(editor.document.lines(), vec!["tehran xteh the", ""]);
+}
+
+#[test]
+fn test_capitalized_abbrev_and_punctuation_before_it() {
+    let mut editor = create_editor_with_content("");
+    type_text(&mut editor, "Teh end (btw)");
+    assert_eq!(editor.document.lines()[0], "The end (by the way)");
+}
+
+#[test]
+fn test_multiline_expansion() {
+    let mut editor = create_editor_with_content("");
+    type_text(&mut editor, "sig ");
+    assert_eq!(editor.document.lines(), vec!["Regards,", "Dev "]);
+    assert_eq!(editor.cursor_pos(), (4, 1));
+}
+
+#[test]
+fn test_expansion_is_undone_on_its_own() {
+    let mut editor = create_editor_with_content("");
+    type_text(&mut editor, "teh ");
+    assert_eq!(editor.document.lines()[0], "the ");
+    editor.undo();
+    assert_eq!(editor.document.lines()[0], "the");
+    editor.undo();
+    assert_eq!(editor.document.lines()[0], "teh");
+    assert_eq!(editor.cursor_pos(), (3, 0));
+    editor.redo();
+    assert_eq!(editor.document.lines()[0], "the");
+}
+
+#[test]
+fn test_toggle_abbrevs_for_the_buffer() {
+    let mut editor = create_editor_with_content("");
+    editor.execute_action(Action::ToggleAbbrevs).unwrap();
+    assert_eq!(editor.status_message, "Abbreviation expansion off.");
+    type_text(&mut editor, "teh ");
+    assert_eq!(editor.document.lines()[0], "teh ");
+
+    editor.execute_action(Action::ToggleAbbrevs).unwrap();
+    type_text(&mut editor, "teh ");
+    assert_eq!(editor.document.lines()[0], "teh the ");
+}
+
+#[test]
+fn test_file_turns_abbrevs_off() {
+    let mut editor = create_editor_with_content("<!-- dmacs: abbrevs=off -->");
+    editor.document.file_settings = FileSettings::parse(editor.document.lines());
+    assert!(!editor.is_abbrev_expansion_on());
+    editor.set_cursor_pos(0, 0);
+    type_text(&mut editor, "teh ");
+    assert!(editor.document.lines()[0].starts_with("teh "));
+}
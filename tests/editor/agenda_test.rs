@@ -0,0 +1,141 @@
+use crate::editor_with_lines;
+use chrono::{Days, Local};
+use dmacs::editor::EditorMode;
+use dmacs::editor::actions::Action;
+use pancurses::Input;
+use std::fs;
+use tempfile::TempDir;
+
+fn due_in(days: i64) -> String {
+    let today = Local::now().date_naive();
+    let date = if days < 0 {
+        today - Days::new(days.unsigned_abs())
+    } else {
+        today + Days::new(days as u64)
+    };
+    date.format("%Y-%m-%d").to_string()
+}
+
+#[test]
+fn test_agenda_lists_overdue_before_today() {
+    let today = format!("- [ ] today @due({})", due_in(0));
+    let overdue = format!("- [ ] overdue @due({})", due_in(-3));
+    let mut editor = editor_with_lines(&[&today, "- [ ] no date", &overdue]);
+    editor.execute_action(Action::OpenAgenda).unwrap();
+    assert_eq!(editor.mode, EditorMode::Agenda);
+
+    let listed: Vec<usize> = editor.agenda.items.iter().map(|i| i.line).collect();
+    assert_eq!(listed, vec![2, 0]);
+}
+
+#[test]
+fn test_task_list_includes_undated_and_switches_scope() {
+    let next_month = format!("- [ ] next month @due({})", due_in(40));
+    let today = format!("- [ ] today @due({})", due_in(0));
+    let mut editor = editor_with_lines(&["- [ ] no date", &next_month, &today]);
+    editor.execute_action(Action::OpenTaskList).unwrap();
+    assert_eq!(editor.mode, EditorMode::Agenda);
+    let listed: Vec<usize> = editor.agenda.items.iter().map(|i| i.line).collect();
+    assert_eq!(listed, vec![2, 1, 0]);
+
+    editor.process_input(Input::Character('a'), false).unwrap();
+    let listed: Vec<usize> = editor.agenda.items.iter().map(|i| i.line).collect();
+    assert_eq!(listed, vec![2]);
+
+    editor.process_input(Input::Character('a'), false).unwrap();
+    editor.process_input(Input::KeyDown, false).unwrap();
+    editor.process_input(Input::KeyDown, false).unwrap();
+    editor.process_input(Input::Character('x'), false).unwrap();
+    assert_eq!(editor.document.lines()[0], "- [x] no date");
+}
+
+#[test]
+fn test_agenda_all_command_without_tasks() {
+    let mut editor = editor_with_lines(&["- [x] done", "/agenda all"]);
+    editor.set_cursor_pos(11, 1);
+    editor.process_input(Input::Character('\n'), false).unwrap();
+    assert_eq!(editor.document.lines(), vec!["- [x] done", ""]);
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert_eq!(editor.status_message, "No unchecked tasks.");
+}
+
+#[test]
+fn test_agenda_jump_to_task() {
+    let today = format!("- [ ] today @due({})", due_in(0));
+    let mut editor = editor_with_lines(&["notes", &today]);
+    editor.execute_action(Action::OpenAgenda).unwrap();
+    editor.process_input(Input::Character('\n'), false).unwrap();
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert_eq!(editor.cursor_pos(), (0, 1));
+}
+
+#[test]
+fn test_agenda_check_off_task_is_undoable() {
+    let first = format!("  - [ ] first @due({})", due_in(0));
+    let second = format!("- [ ] second @due({})", due_in(0));
+    let mut editor = editor_with_lines(&[&first, &second]);
+    editor.execute_action(Action::OpenAgenda).unwrap();
+    editor.process_input(Input::Character('x'), false).unwrap();
+    assert!(editor.document.lines()[0].starts_with("  - [x] first"));
+    assert_eq!(editor.agenda.items.len(), 1);
+    assert_eq!(editor.mode, EditorMode::Agenda);
+
+    editor.process_input(Input::Character('x'), false).unwrap();
+    assert!(editor.document.lines()[1].starts_with("- [x] second"));
+    assert_eq!(editor.mode, EditorMode::Normal);
+
+    editor.undo();
+    editor.undo();
+    assert_eq!(editor.document.lines(), [first, second]);
+}
+
+#[test]
+fn test_agenda_includes_notes_directory() {
+    let dir = TempDir::new().unwrap();
+    let page = dir.path().join("2025-01-01.md");
+    fs::write(&page, format!("- [ ] from notes @due({})\n", due_in(-1))).unwrap();
+
+    let mut editor = editor_with_lines(&[&format!("- [ ] here @due({})", due_in(0))]);
+    editor.settings.agenda_dir = Some(dir.path().to_string_lossy().to_string());
+    editor.execute_action(Action::OpenAgenda).unwrap();
+    assert_eq!(editor.agenda.items.len(), 2);
+    assert_eq!(editor.agenda.items[0].path.as_deref(), Some(page.as_path()));
+
+    editor.process_input(Input::Character('x'), false).unwrap();
+    assert!(
+        fs::read_to_string(&page)
+            .unwrap()
+            .starts_with("- [x] from notes")
+    );
+}
+
+#[test]
+fn test_agenda_command_and_empty_agenda() {
+    let mut editor = editor_with_lines(&["- [ ] no date", "/agenda"]);
+    editor.set_cursor_pos(7, 1);
+    editor.process_input(Input::Character('\n'), false).unwrap();
+    assert_eq!(editor.document.lines(), vec!["- [ ] no date", ""]);
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert_eq!(editor.status_message, "No tasks due this week.");
+}
+
+#[test]
+fn test_agenda_jump_opens_other_file() {
+    let dir = TempDir::new().unwrap();
+    let page = dir.path().join("2025-01-01.md");
+    fs::write(
+        &page,
+        format!("# day\n- [ ] from notes @due({})\n", due_in(0)),
+    )
+    .unwrap();
+
+    let mut editor = editor_with_lines(&["scratch"]);
+    editor.settings.agenda_dir = Some(dir.path().to_string_lossy().to_string());
+    editor.execute_action(Action::OpenAgenda).unwrap();
+    editor.process_input(Input::Character('\n'), false).unwrap();
+    assert_eq!(
+        editor.document.filename.as_deref(),
+        Some(page.to_string_lossy().as_ref())
+    );
+    assert_eq!(editor.cursor_pos(), (0, 1));
+}
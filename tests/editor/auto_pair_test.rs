@@ -0,0 +1,157 @@
+use dmacs::document::LineBuffer;
+use dmacs::editor::Editor;
+use dmacs::editor::actions::Action;
+use dmacs::editor::auto_pair::find_matching_bracket;
+
+fn editor_with_auto_pair(line: &str) -> Editor {
+    let mut editor = Editor::new(None, None, None);
+    editor.settings.auto_pair = true;
+    editor.document.set_lines(vec![line.to_string()]);
+    editor
+}
+
+fn type_text(editor: &mut Editor, text: &str) {
+    for c in text.chars() {
+        editor.execute_action(Action::InsertChar(c)).unwrap();
+    }
+}
+
+#[test]
+fn test_auto_pair_disabled_by_default() {
+    let mut editor = Editor::new(None, None, None);
+    type_text(&mut editor, "(");
+    assert_eq!(editor.document.lines()[0], "(");
+}
+
+#[test]
+fn test_auto_pair_closes_and_skips_over_closer() {
+    let mut editor = editor_with_auto_pair("");
+    type_text(&mut editor, "f(");
+    assert_eq!(editor.document.lines()[0], "f()");
+    assert_eq!(editor.cursor_pos(), (2, 0));
+
+    type_text(&mut editor, "x)");
+    assert_eq!(editor.document.lines()[0], "f(x)");
+    assert_eq!(editor.cursor_pos(), (4, 0));
+}
+
+#[test]
+fn test_auto_pair_japanese_brackets() {
+    let mut editor = editor_with_auto_pair("");
+    type_text(&mut editor, "「はい」");
+    assert_eq!(editor.document.lines()[0], "「はい」");
+    assert_eq!(editor.cursor_pos(), ("「はい」".len(), 0));
+}
+
+#[test]
+fn test_auto_pair_not_before_word() {
+    let mut editor = editor_with_auto_pair("word");
+    type_text(&mut editor, "(");
+    assert_eq!(editor.document.lines()[0], "(word");
+}
+
+#[test]
+fn test_apostrophe_is_not_paired() {
+    let mut editor = editor_with_auto_pair("");
+    type_text(&mut editor, "don't");
+    assert_eq!(editor.document.lines()[0], "don't");
+
+    type_text(&mut editor, " '");
+    assert_eq!(editor.document.lines()[0], "don't ''");
+}
+
+#[test]
+fn test_backspace_deletes_empty_pair() {
+    let mut editor = editor_with_auto_pair("");
+    type_text(&mut editor, "[");
+    editor.execute_action(Action::DeleteChar).unwrap();
+    assert_eq!(editor.document.lines()[0], "");
+    assert_eq!(editor.cursor_pos(), (0, 0));
+}
+
+#[test]
+fn test_configured_pairs() {
+    let mut editor = editor_with_auto_pair("");
+    editor.settings.auto_pairs = Some(vec!["<>".to_string()]);
+    type_text(&mut editor, "<(");
+    assert_eq!(editor.document.lines()[0], "<(>");
+}
+
+#[test]
+fn test_undo_removes_pair() {
+    let mut editor = editor_with_auto_pair("");
+    type_text(&mut editor, "{");
+    editor.undo();
+    assert_eq!(editor.document.lines()[0], "");
+}
+
+#[test]
+fn test_emphasis_marker_wraps_selection() {
+    let mut editor = Editor::new(None, None, None);
+    editor.document.set_lines(vec!["make it bold".to_string()]);
+    editor.set_cursor_pos(8, 0);
+    editor.set_marker_action();
+    editor.set_cursor_pos(12, 0);
+
+    type_text(&mut editor, "**");
+    assert_eq!(editor.document.lines()[0], "make it **bold**");
+    assert_eq!(editor.selection.marker_pos, Some((10, 0)));
+    assert_eq!(editor.cursor_pos(), (14, 0));
+
+    editor.undo();
+    assert_eq!(editor.document.lines()[0], "make it *bold*");
+    editor.undo();
+    assert_eq!(editor.document.lines()[0], "make it bold");
+}
+
+#[test]
+fn test_emphasis_marker_wraps_backward_multiline_selection() {
+    let mut editor = Editor::new(None, None, None);
+    editor
+        .document
+        .set_lines(vec!["one two".to_string(), "three four".to_string()]);
+    editor.set_cursor_pos(5, 1);
+    editor.set_marker_action();
+    editor.set_cursor_pos(4, 0);
+
+    type_text(&mut editor, "~");
+    assert_eq!(editor.document.lines(), vec!["one ~two", "three~ four"]);
+    assert_eq!(editor.cursor_pos(), (5, 0));
+    assert_eq!(editor.selection.marker_pos, Some((5, 1)));
+}
+
+#[test]
+fn test_emphasis_marker_without_selection_is_typed() {
+    let mut editor = Editor::new(None, None, None);
+    type_text(&mut editor, "a_b`");
+    assert_eq!(editor.document.lines()[0], "a_b`");
+
+    // An empty selection is not wrapped either
+    editor.set_marker_action();
+    type_text(&mut editor, "*");
+    assert_eq!(editor.document.lines()[0], "a_b`*");
+}
+
+#[test]
+fn test_find_matching_bracket() {
+    let lines: LineBuffer = ["fn f(a, (b)) {", "    x[0]", "}"]
+        .into_iter()
+        .map(String::from)
+        .collect();
+    assert_eq!(find_matching_bracket(&lines, 4, 0), Some(((4, 0), (11, 0))));
+    // The bracket just before the cursor counts too
+    assert_eq!(
+        find_matching_bracket(&lines, 12, 0),
+        Some(((11, 0), (4, 0)))
+    );
+    assert_eq!(
+        find_matching_bracket(&lines, 13, 0),
+        Some(((13, 0), (0, 2)))
+    );
+    assert_eq!(find_matching_bracket(&lines, 0, 2), Some(((0, 2), (13, 0))));
+    assert_eq!(find_matching_bracket(&lines, 1, 0), None);
+    assert_eq!(
+        find_matching_bracket(&LineBuffer::from(vec!["(".to_string()]), 0, 0),
+        None
+    );
+}
@@ -0,0 +1,71 @@
+use dmacs::config::EditorSettings;
+use dmacs::editor::Editor;
+use pancurses::Input;
+use std::fs;
+use std::time::{Duration, Instant};
+use tempfile::NamedTempFile;
+
+fn editor_with_autosave(path: &str, interval_secs: Option<u64>) -> Editor {
+    let mut editor = Editor::new(Some(path.to_string()), None, None);
+    editor.set_settings(EditorSettings {
+        autosave_interval_secs: interval_secs,
+        ..EditorSettings::default()
+    });
+    editor
+}
+
+#[test]
+fn test_autosave_writes_dirty_document_after_interval() {
+    let file = NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap().to_string();
+    fs::write(&path, "hello").unwrap();
+
+    let mut editor = editor_with_autosave(&path, Some(1));
+    editor.process_input(Input::Character('X'), false).unwrap();
+    assert!(editor.document.is_dirty());
+
+    // Not due yet
+    editor.autosave_tick();
+    assert!(!editor.autosave.is_pending());
+
+    editor.autosave.last_run = Instant::now() - Duration::from_secs(2);
+    editor.autosave_tick();
+    assert!(editor.autosave.is_pending());
+    editor.finish_pending_autosave();
+
+    assert_eq!(fs::read_to_string(&path).unwrap(), "Xhello\n");
+    assert!(!editor.document.is_dirty());
+    assert!(editor.autosave.last_saved_at.is_some());
+}
+
+#[test]
+fn test_autosave_keeps_later_edits_dirty() {
+    let file = NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap().to_string();
+    fs::write(&path, "hello").unwrap();
+
+    let mut editor = editor_with_autosave(&path, Some(1));
+    editor.process_input(Input::Character('X'), false).unwrap();
+    editor.autosave.last_run = Instant::now() - Duration::from_secs(2);
+    editor.autosave_tick();
+    // Edit while the snapshot is being written
+    editor.process_input(Input::Character('Y'), false).unwrap();
+    editor.finish_pending_autosave();
+
+    assert_eq!(fs::read_to_string(&path).unwrap(), "Xhello\n");
+    assert!(editor.document.is_dirty());
+}
+
+#[test]
+fn test_autosave_disabled_by_default() {
+    let file = NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap().to_string();
+    fs::write(&path, "hello").unwrap();
+
+    let mut editor = editor_with_autosave(&path, None);
+    editor.process_input(Input::Character('X'), false).unwrap();
+    editor.autosave.last_run = Instant::now() - Duration::from_secs(3600);
+    editor.autosave_tick();
+    assert!(!editor.autosave.is_pending());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "hello");
+}
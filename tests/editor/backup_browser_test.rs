@@ -0,0 +1,87 @@
+use dmacs::backup::BackupManager;
+use dmacs::editor::{Editor, EditorMode};
+use pancurses::Input;
+use std::fs;
+use tempfile::TempDir;
+
+// An editor on notes.md holding `content`, with backups of `versions`
+// (oldest first) kept in the same temporary directory
+fn editor_with_backups(dir: &TempDir, content: &str, versions: &[&str]) -> Editor {
+    let path = dir.path().join("notes.md");
+    fs::write(&path, content).unwrap();
+    let filename = path.to_string_lossy().to_string();
+    let backup_manager = BackupManager::new_with_base_dir(Some(dir.path().to_path_buf())).unwrap();
+    for (day, version) in versions.iter().enumerate() {
+        backup_manager.save_backup(&filename, version).unwrap();
+        let latest = backup_manager.list_backups(&filename).unwrap().remove(0);
+        let name = latest.path.file_name().unwrap().to_str().unwrap();
+        let prefix = &name[..name.len() - "20250101000000.bak".len()];
+        let renamed = format!("{prefix}202501{:02}090000.bak", day + 1);
+        fs::rename(&latest.path, latest.path.with_file_name(renamed)).unwrap();
+    }
+    let mut editor = Editor::new(Some(filename), None, None);
+    editor.browse_backups_with_base_dir(Some(dir.path().to_path_buf()));
+    editor
+}
+
+#[test]
+fn test_browser_lists_backups_newest_first() {
+    let dir = TempDir::new().unwrap();
+    let editor = editor_with_backups(&dir, "three\n", &["one\n", "two\n"]);
+    assert_eq!(editor.mode, EditorMode::BackupBrowser);
+    let created: Vec<String> = editor
+        .backup_browser
+        .backups
+        .iter()
+        .map(|backup| backup.created.format("%Y-%m-%d").to_string())
+        .collect();
+    assert_eq!(created, vec!["2025-01-02", "2025-01-01"]);
+}
+
+#[test]
+fn test_no_backups_leaves_browser_closed() {
+    let dir = TempDir::new().unwrap();
+    let editor = editor_with_backups(&dir, "one\n", &[]);
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert!(editor.status_message.contains("No backups"));
+}
+
+#[test]
+fn test_preview_shows_backup_or_changes() {
+    let dir = TempDir::new().unwrap();
+    let mut editor = editor_with_backups(&dir, "a\nc\nd\n", &["a\nb\nc\n"]);
+    assert_eq!(editor.backup_preview(), vec!["a", "b", "c"]);
+
+    editor.process_input(Input::Character('d'), false).unwrap();
+    assert_eq!(editor.backup_preview(), vec!["  a", "- b", "  c", "+ d"]);
+}
+
+#[test]
+fn test_restore_replaces_buffer_as_one_undo_step() {
+    let dir = TempDir::new().unwrap();
+    let mut editor = editor_with_backups(&dir, "three\n", &["one\n", "two\n"]);
+    editor.process_input(Input::KeyDown, false).unwrap();
+    editor.process_input(Input::Character('\n'), false).unwrap();
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert_eq!(editor.document.lines(), vec!["one"]);
+    assert!(editor.document.is_dirty());
+    // The file only changes on save
+    assert_eq!(
+        fs::read_to_string(dir.path().join("notes.md")).unwrap(),
+        "three\n"
+    );
+
+    editor.undo();
+    assert_eq!(editor.document.lines(), vec!["three"]);
+}
+
+#[test]
+fn test_escape_closes_browser() {
+    let dir = TempDir::new().unwrap();
+    let mut editor = editor_with_backups(&dir, "two\n", &["one\n"]);
+    editor
+        .process_input(Input::Character('\x1b'), false)
+        .unwrap();
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert_eq!(editor.document.lines(), vec!["two"]);
+}
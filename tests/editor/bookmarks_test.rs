@@ -0,0 +1,126 @@
+use dmacs::document::LineBuffer;
+use dmacs::editor::actions::Action;
+use dmacs::editor::bookmarks::find_line;
+use dmacs::editor::{Editor, EditorMode};
+use dmacs::persistence;
+use pancurses::Input;
+use std::fs;
+use tempfile::TempDir;
+
+fn path_string(dir: &TempDir, name: &str) -> String {
+    dir.path().join(name).to_string_lossy().to_string()
+}
+
+fn open_with_bookmarks(dir: &TempDir, filename: &str) -> Editor {
+    let mut editor = Editor::new(Some(filename.to_string()), None, None);
+    editor.enable_bookmarks_with_base_dir(Some(dir.path().to_path_buf()));
+    editor
+}
+
+fn set_bookmark(editor: &mut Editor, name: &str) {
+    editor.execute_action(Action::SetBookmark).unwrap();
+    assert_eq!(editor.mode, EditorMode::Minibuffer);
+    for c in name.chars() {
+        editor.process_input(Input::Character(c), false).unwrap();
+    }
+    editor.process_input(Input::Character('\n'), false).unwrap();
+}
+
+#[test]
+fn test_find_line() {
+    let lines: LineBuffer = ["a", "b", "c", "b"].iter().map(|s| s.to_string()).collect();
+    assert_eq!(find_line(&lines, 2, "c"), 2);
+    assert_eq!(find_line(&lines, 2, "b"), 1);
+    assert_eq!(find_line(&lines, 9, "b"), 3);
+    assert_eq!(find_line(&lines, 2, "missing"), 2);
+}
+
+#[test]
+fn test_set_and_go_to_bookmark() {
+    let dir = TempDir::new().unwrap();
+    let notes = path_string(&dir, "notes.md");
+    fs::write(&notes, "one\ntwo\nthree\n").unwrap();
+    let mut editor = open_with_bookmarks(&dir, &notes);
+
+    editor.set_cursor_pos(0, 1);
+    set_bookmark(&mut editor, "");
+    assert_eq!(editor.status_message, "Bookmark 1 set at line 2.");
+    editor.set_cursor_pos(0, 2);
+    set_bookmark(&mut editor, "end");
+
+    // A line inserted above moves both bookmarks down
+    editor.set_cursor_pos(0, 0);
+    editor.insert_newline().unwrap();
+    editor.execute_action(Action::GotoBookmark).unwrap();
+    assert_eq!(editor.mode, EditorMode::Bookmarks);
+    let names: Vec<&str> = editor
+        .bookmarks
+        .entries
+        .iter()
+        .map(|bookmark| bookmark.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["1", "end"]);
+    editor.process_input(Input::KeyDown, false).unwrap();
+    editor.process_input(Input::Character('\n'), false).unwrap();
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert_eq!(editor.cursor_pos(), (0, 3));
+
+    // The jump is in the jump list
+    editor.execute_action(Action::JumpBack).unwrap();
+    assert_eq!(editor.cursor_pos(), (0, 1));
+}
+
+#[test]
+fn test_bookmarks_survive_restart() {
+    let dir = TempDir::new().unwrap();
+    let notes = path_string(&dir, "notes.md");
+    fs::write(&notes, "one\ntwo\nthree\n").unwrap();
+    let mut editor = open_with_bookmarks(&dir, &notes);
+    editor.set_cursor_pos(0, 2);
+    set_bookmark(&mut editor, "three");
+
+    // Lines added above while dmacs was not looking
+    fs::write(&notes, "zero\nhalf\none\ntwo\nthree\n").unwrap();
+    let editor = open_with_bookmarks(&dir, &notes);
+    assert_eq!(editor.bookmarks.marks.len(), 1);
+    assert_eq!(editor.bookmarks.marks[0].line, 4);
+}
+
+#[test]
+fn test_go_to_bookmark_in_other_file() {
+    let dir = TempDir::new().unwrap();
+    let first = path_string(&dir, "first.md");
+    let second = path_string(&dir, "second.md");
+    fs::write(&first, "first\n").unwrap();
+    fs::write(&second, "a\nb\nc\n").unwrap();
+
+    let mut editor = open_with_bookmarks(&dir, &second);
+    editor.set_cursor_pos(0, 2);
+    set_bookmark(&mut editor, "c");
+    editor.open_file(&first).unwrap();
+    assert!(editor.bookmarks.marks.is_empty());
+
+    editor.execute_action(Action::GotoBookmark).unwrap();
+    editor.process_input(Input::Character('\n'), false).unwrap();
+    assert_eq!(editor.document.filename.as_deref(), Some(second.as_str()));
+    assert_eq!(editor.cursor_pos(), (0, 2));
+}
+
+#[test]
+fn test_delete_bookmark() {
+    let dir = TempDir::new().unwrap();
+    let notes = path_string(&dir, "notes.md");
+    fs::write(&notes, "one\ntwo\n").unwrap();
+    let mut editor = open_with_bookmarks(&dir, &notes);
+    set_bookmark(&mut editor, "top");
+
+    editor.execute_action(Action::GotoBookmark).unwrap();
+    editor.process_input(Input::Character('d'), false).unwrap();
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert!(editor.bookmarks.marks.is_empty());
+    let path = persistence::bookmarks_path(Some(dir.path().to_path_buf())).unwrap();
+    assert!(persistence::load_bookmarks(&path).is_empty());
+
+    editor.execute_action(Action::GotoBookmark).unwrap();
+    assert_eq!(editor.status_message, "No bookmarks.");
+}
@@ -0,0 +1,131 @@
+use dmacs::editor::Editor;
+use dmacs::editor::case::CaseChange;
+use pancurses::Input;
+
+fn create_editor_with_content(content: &str) -> Editor {
+    let mut editor = Editor::new(None, None, None);
+    editor
+        .document
+        .set_lines(content.lines().map(|s| s.to_string()).collect());
+    if editor.document.lines().is_empty() {
+        editor.document.set_lines(vec![String::new()]);
+    }
+    editor
+}
+
+fn press_alt(editor: &mut Editor, key: char) {
+    editor.process_input(Input::Character(key), true).unwrap();
+}
+
+#[test]
+fn test_case_change_maps_unicode_letters() {
+    assert_eq!(CaseChange::Upper.apply("café"), "CAFÉ");
+    assert_eq!(CaseChange::Upper.apply("straße"), "STRASSE");
+    assert_eq!(CaseChange::Lower.apply("ÉCOLE Ÿ"), "école ÿ");
+    assert_eq!(
+        CaseChange::Capitalize.apply("élan vital, ÖSTERREICH"),
+        "Élan Vital, Österreich"
+    );
+}
+
+#[test]
+fn test_case_change_leaves_japanese_intact() {
+    assert_eq!(CaseChange::Upper.apply("日本語とカナ"), "日本語とカナ");
+    assert_eq!(CaseChange::Lower.apply("東京ＡＢＣ"), "東京ａｂｃ");
+    assert_eq!(
+        CaseChange::Capitalize.apply("ｄｍａｃｓ です"),
+        "Ｄｍａｃｓ です"
+    );
+}
+
+#[test]
+fn test_upcase_word_at_cursor_moves_past_it() {
+    let mut editor = create_editor_with_content("hello world");
+    editor.set_cursor_pos(2, 0);
+    press_alt(&mut editor, 'u');
+    assert_eq!(editor.document.lines()[0], "HELLO world");
+    assert_eq!(editor.cursor_pos(), (5, 0));
+
+    press_alt(&mut editor, 'u');
+    assert_eq!(editor.document.lines()[0], "HELLO WORLD");
+    assert_eq!(editor.cursor_pos(), (11, 0));
+}
+
+#[test]
+fn test_downcase_and_capitalize_word() {
+    let mut editor = create_editor_with_content("ÉTÉ À PARIS");
+    press_alt(&mut editor, 'l');
+    assert_eq!(editor.document.lines()[0], "été À PARIS");
+    press_alt(&mut editor, 'c');
+    press_alt(&mut editor, 'c');
+    assert_eq!(editor.document.lines()[0], "été À Paris");
+    assert_eq!(editor.cursor_pos(), (editor.document.lines()[0].len(), 0));
+}
+
+#[test]
+fn test_case_change_continues_on_the_next_line() {
+    let mut editor = create_editor_with_content("end.\nnext line");
+    editor.set_cursor_pos(1, 0);
+    press_alt(&mut editor, 'u');
+    press_alt(&mut editor, 'u');
+    assert_eq!(editor.document.lines(), vec!["END.", "NEXT line"]);
+    assert_eq!(editor.cursor_pos(), (4, 1));
+}
+
+#[test]
+fn test_case_change_skips_words_without_case() {
+    let mut editor = create_editor_with_content("日本語 text");
+    press_alt(&mut editor, 'u');
+    assert_eq!(editor.document.lines()[0], "日本語 text");
+    assert_eq!(editor.cursor_pos(), ("日本語".len(), 0));
+    assert!(editor.undo_redo.undo_stack.is_empty());
+    press_alt(&mut editor, 'u');
+    assert_eq!(editor.document.lines()[0], "日本語 TEXT");
+}
+
+#[test]
+fn test_upcase_selection_across_lines_is_one_undo_step() {
+    let mut editor = create_editor_with_content("straße und\ncafé 東京 end");
+    editor.set_cursor_pos(0, 0);
+    editor.set_marker_action();
+    editor.set_cursor_pos("café".len(), 1);
+    press_alt(&mut editor, 'u');
+    assert_eq!(
+        editor.document.lines(),
+        vec!["STRASSE UND", "CAFÉ 東京 end"]
+    );
+    assert_eq!(editor.cursor_pos(), ("CAFÉ".len(), 1));
+    assert_eq!(editor.selection.marker_pos, Some((0, 0)));
+    assert_eq!(editor.undo_redo.undo_stack.len(), 1);
+    assert_eq!(editor.undo_redo.undo_stack[0].len(), 1);
+
+    editor.undo();
+    assert_eq!(editor.document.lines(), vec!["straße und", "café 東京 end"]);
+    assert_eq!(editor.cursor_pos(), ("café".len(), 1));
+    editor.redo();
+    assert_eq!(
+        editor.document.lines(),
+        vec!["STRASSE UND", "CAFÉ 東京 end"]
+    );
+}
+
+#[test]
+fn test_downcase_selection_keeps_the_marker_at_its_end() {
+    let mut editor = create_editor_with_content("say HELLO THERE");
+    editor.set_cursor_pos(9, 0);
+    editor.set_marker_action();
+    editor.set_cursor_pos(4, 0);
+    press_alt(&mut editor, 'l');
+    assert_eq!(editor.document.lines()[0], "say hello THERE");
+    assert_eq!(editor.cursor_pos(), (4, 0));
+    assert_eq!(editor.selection.marker_pos, Some((9, 0)));
+}
+
+#[test]
+fn test_case_change_without_a_word() {
+    let mut editor = create_editor_with_content("hello ...");
+    editor.set_cursor_pos(6, 0);
+    press_alt(&mut editor, 'c');
+    assert_eq!(editor.document.lines()[0], "hello ...");
+    assert_eq!(editor.status_message, "No word to change.");
+}
@@ -13,7 +13,7 @@ fn test_toggle_checkbox_add() {
     editor.insert_text("Hello world").unwrap();
     editor.go_to_start_of_line();
     simulate_ctrl_t(&mut editor);
-    assert_eq!(editor.document.lines[0], "- Hello world");
+    assert_eq!(editor.document.lines()[0], "- Hello world");
     assert_eq!(editor.cursor_pos(), (2, 0));
     assert_eq!(editor.status_message, "Toggled to ListItem.");
 }
@@ -24,7 +24,7 @@ fn test_toggle_checkbox_check() {
     editor.insert_text("- [ ] Hello world").unwrap();
     editor.go_to_start_of_line();
     simulate_ctrl_t(&mut editor);
-    assert_eq!(editor.document.lines[0], "- [x] Hello world");
+    assert_eq!(editor.document.lines()[0], "- [x] Hello world");
     assert_eq!(editor.cursor_pos(), (0, 0));
     assert_eq!(editor.status_message, "Toggled to Checked.");
 }
@@ -35,7 +35,7 @@ fn test_toggle_checkbox_uncheck() {
     editor.insert_text("- [x] Hello world").unwrap();
     editor.go_to_start_of_line();
     simulate_ctrl_t(&mut editor);
-    assert_eq!(editor.document.lines[0], "Hello world");
+    assert_eq!(editor.document.lines()[0], "Hello world");
     assert_eq!(editor.cursor_pos(), (0, 0));
     assert_eq!(editor.status_message, "Toggled to Plain.");
 }
@@ -49,62 +49,62 @@ fn test_toggle_checkbox_undo_redo() {
 
     // Add list item
     simulate_ctrl_t(&mut editor);
-    assert_eq!(editor.document.lines[0], "- Hello world");
+    assert_eq!(editor.document.lines()[0], "- Hello world");
     let after_list_item_pos = editor.cursor_pos();
 
     // Add checkbox
     simulate_ctrl_t(&mut editor);
-    assert_eq!(editor.document.lines[0], "- [ ] Hello world");
+    assert_eq!(editor.document.lines()[0], "- [ ] Hello world");
     let after_add_pos = editor.cursor_pos();
 
     // Check it
     simulate_ctrl_t(&mut editor);
-    assert_eq!(editor.document.lines[0], "- [x] Hello world");
+    assert_eq!(editor.document.lines()[0], "- [x] Hello world");
     let after_check_pos = editor.cursor_pos();
 
     // Uncheck it
     simulate_ctrl_t(&mut editor);
-    assert_eq!(editor.document.lines[0], "Hello world");
+    assert_eq!(editor.document.lines()[0], "Hello world");
     let after_uncheck_pos = editor.cursor_pos();
 
     // Undo uncheck
     editor.undo();
-    assert_eq!(editor.document.lines[0], "- [x] Hello world");
+    assert_eq!(editor.document.lines()[0], "- [x] Hello world");
     assert_eq!(editor.cursor_pos(), after_check_pos);
 
     // Undo check
     editor.undo();
-    assert_eq!(editor.document.lines[0], "- [ ] Hello world");
+    assert_eq!(editor.document.lines()[0], "- [ ] Hello world");
     assert_eq!(editor.cursor_pos(), after_add_pos);
 
     // Undo add checkbox
     editor.undo();
-    assert_eq!(editor.document.lines[0], "- Hello world");
+    assert_eq!(editor.document.lines()[0], "- Hello world");
     assert_eq!(editor.cursor_pos(), after_list_item_pos);
 
     // Undo add list item
     editor.undo();
-    assert_eq!(editor.document.lines[0], "Hello world");
+    assert_eq!(editor.document.lines()[0], "Hello world");
     assert_eq!(editor.cursor_pos(), initial_pos);
 
     // Redo add list item
     editor.redo();
-    assert_eq!(editor.document.lines[0], "- Hello world");
+    assert_eq!(editor.document.lines()[0], "- Hello world");
     assert_eq!(editor.cursor_pos(), after_list_item_pos);
 
     // Redo add checkbox
     editor.redo();
-    assert_eq!(editor.document.lines[0], "- [ ] Hello world");
+    assert_eq!(editor.document.lines()[0], "- [ ] Hello world");
     assert_eq!(editor.cursor_pos(), after_add_pos);
 
     // Redo check
     editor.redo();
-    assert_eq!(editor.document.lines[0], "- [x] Hello world");
+    assert_eq!(editor.document.lines()[0], "- [x] Hello world");
     assert_eq!(editor.cursor_pos(), after_check_pos);
 
     // Redo uncheck
     editor.redo();
-    assert_eq!(editor.document.lines[0], "Hello world");
+    assert_eq!(editor.document.lines()[0], "Hello world");
     assert_eq!(editor.cursor_pos(), after_uncheck_pos);
 }
 
@@ -114,7 +114,7 @@ fn test_toggle_indented_checkbox_add() {
     editor.insert_text("  Hello world").unwrap();
     editor.go_to_start_of_line();
     simulate_ctrl_t(&mut editor);
-    assert_eq!(editor.document.lines[0], "  - Hello world");
+    assert_eq!(editor.document.lines()[0], "  - Hello world");
     assert_eq!(editor.cursor_pos(), (4, 0));
 }
 
@@ -124,7 +124,7 @@ fn test_toggle_indented_checkbox_check() {
     editor.insert_text("  - [ ] Hello world").unwrap();
     editor.go_to_start_of_line();
     simulate_ctrl_t(&mut editor);
-    assert_eq!(editor.document.lines[0], "  - [x] Hello world");
+    assert_eq!(editor.document.lines()[0], "  - [x] Hello world");
     assert_eq!(editor.cursor_pos(), (0, 0));
 }
 
@@ -134,7 +134,7 @@ fn test_toggle_indented_checkbox_uncheck() {
     editor.insert_text("  - [x] Hello world").unwrap();
     editor.go_to_start_of_line();
     simulate_ctrl_t(&mut editor);
-    assert_eq!(editor.document.lines[0], "  Hello world");
+    assert_eq!(editor.document.lines()[0], "  Hello world");
     assert_eq!(editor.cursor_pos(), (0, 0));
 }
 
@@ -144,62 +144,68 @@ fn test_toggle_indented_checkbox_add_cursor_middle() {
     editor.insert_text("  Hello world").unwrap();
     editor.set_cursor_pos(4, 0); // "  He|llo world"
     simulate_ctrl_t(&mut editor);
-    assert_eq!(editor.document.lines[0], "  - Hello world");
+    assert_eq!(editor.document.lines()[0], "  - Hello world");
     assert_eq!(editor.cursor_pos(), (6, 0)); // "  - He|llo world"
 }
 
 #[test]
 fn test_toggle_checkbox_selection_mixed_to_list() {
     let mut editor = Editor::new(None, None, None);
-    editor.document.lines = vec![
+    editor.document.set_lines(vec![
         "Plain text".to_string(),
         "- List item".to_string(),
         "- [ ] Unchecked".to_string(),
         "- [x] Checked".to_string(),
         "Another plain".to_string(),
-    ];
+    ]);
     editor.set_cursor_pos(0, 0);
     editor.set_marker_action();
     editor.set_cursor_pos(5, 4); // Select all lines
 
     simulate_ctrl_t(&mut editor);
 
-    assert_eq!(editor.document.lines[0], "- Plain text");
-    assert_eq!(editor.document.lines[1], "- List item");
-    assert_eq!(editor.document.lines[2], "- Unchecked");
-    assert_eq!(editor.document.lines[3], "- Checked");
-    assert_eq!(editor.document.lines[4], "- Another plain");
+    assert_eq!(editor.document.lines()[0], "- Plain text");
+    assert_eq!(editor.document.lines()[1], "- List item");
+    assert_eq!(editor.document.lines()[2], "- Unchecked");
+    assert_eq!(editor.document.lines()[3], "- Checked");
+    assert_eq!(editor.document.lines()[4], "- Another plain");
     assert_eq!(editor.status_message, "Toggled selection to ListItem.");
 
     // Test that selection is not cleared
     assert!(editor.selection.is_selection_active());
 
     editor.undo();
-    assert_eq!(editor.document.lines[0], "Plain text");
-    assert_eq!(editor.document.lines[1], "- List item");
-    assert_eq!(editor.document.lines[2], "- [ ] Unchecked");
-    assert_eq!(editor.document.lines[3], "- [x] Checked");
+    assert_eq!(editor.document.lines()[0], "Plain text");
+    assert_eq!(editor.document.lines()[1], "- List item");
+    assert_eq!(editor.document.lines()[2], "- [ ] Unchecked");
+    assert_eq!(editor.document.lines()[3], "- [x] Checked");
 }
 
 #[test]
 fn test_toggle_checkbox_selection_ignores_empty_lines() {
     let mut editor = Editor::new(None, None, None);
-    editor.document.lines = vec!["Line 1".to_string(), "".to_string(), "Line 3".to_string()];
+    editor.document.set_lines(vec![
+        "Line 1".to_string(),
+        "".to_string(),
+        "Line 3".to_string(),
+    ]);
     editor.set_cursor_pos(0, 0);
     editor.set_marker_action();
     editor.set_cursor_pos(6, 2);
 
     simulate_ctrl_t(&mut editor);
 
-    assert_eq!(editor.document.lines[0], "- Line 1");
-    assert_eq!(editor.document.lines[1], "");
-    assert_eq!(editor.document.lines[2], "- Line 3");
+    assert_eq!(editor.document.lines()[0], "- Line 1");
+    assert_eq!(editor.document.lines()[1], "");
+    assert_eq!(editor.document.lines()[2], "- Line 3");
 }
 
 #[test]
 fn test_toggle_checkbox_selection_excludes_last_line_if_cursor_x_is_zero() {
     let mut editor = Editor::new(None, None, None);
-    editor.document.lines = vec!["Line 1".to_string(), "Line 2".to_string()];
+    editor
+        .document
+        .set_lines(vec!["Line 1".to_string(), "Line 2".to_string()]);
     editor.set_cursor_pos(1, 0); // Mark start of selection
     editor.set_marker_action();
     editor.set_cursor_pos(0, 1); // Move cursor to x=0 on last line
@@ -207,6 +213,6 @@ fn test_toggle_checkbox_selection_excludes_last_line_if_cursor_x_is_zero() {
     simulate_ctrl_t(&mut editor);
 
     // Only line 1 should be changed
-    assert_eq!(editor.document.lines[0], "- Line 1");
-    assert_eq!(editor.document.lines[1], "Line 2");
+    assert_eq!(editor.document.lines()[0], "- Line 1");
+    assert_eq!(editor.document.lines()[1], "Line 2");
 }
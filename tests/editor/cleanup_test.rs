@@ -0,0 +1,43 @@
+use crate::editor_with_lines;
+use pancurses::Input;
+
+#[test]
+fn test_cleanup_command() {
+    let mut editor = editor_with_lines(&["* a  ", "", "", "", "b", "/cleanup", "c", "", ""]);
+    editor.set_cursor_pos(8, 5);
+    editor.process_input(Input::Character('\n'), false).unwrap();
+
+    assert_eq!(editor.document.lines(), vec!["- a", "", "b", "c"]);
+    assert_eq!(editor.cursor_pos(), (0, 3));
+    assert_eq!(
+        editor.status_message,
+        "Cleanup: trimmed 1 line, normalized 1 bullet, removed 4 blank lines."
+    );
+
+    editor.undo();
+    assert_eq!(
+        editor.document.lines(),
+        vec!["* a  ", "", "", "", "b", "/cleanup", "c", "", ""]
+    );
+}
+
+#[test]
+fn test_cleanup_with_configured_rules() {
+    let mut editor = editor_with_lines(&["+ a ", "/cleanup"]);
+    editor.settings.cleanup = Some(vec!["trailing_whitespace".to_string()]);
+    editor.set_cursor_pos(8, 1);
+    editor.process_input(Input::Character('\n'), false).unwrap();
+
+    assert_eq!(editor.document.lines(), vec!["+ a"]);
+    assert_eq!(editor.cursor_pos(), (0, 0));
+    assert_eq!(editor.status_message, "Cleanup: trimmed 1 line.");
+}
+
+#[test]
+fn test_cleanup_of_clean_document_only_removes_command() {
+    let mut editor = editor_with_lines(&["- a", "/cleanup"]);
+    editor.set_cursor_pos(8, 1);
+    editor.process_input(Input::Character('\n'), false).unwrap();
+    assert_eq!(editor.document.lines(), vec!["- a"]);
+    assert_eq!(editor.status_message, "Cleanup: nothing to change.");
+}
@@ -0,0 +1,62 @@
+use dmacs::editor::actions::{Action, PALETTE_ACTIONS};
+use dmacs::editor::command_palette::action_name;
+use dmacs::editor::{Editor, EditorMode};
+use pancurses::Input;
+
+fn type_text(editor: &mut Editor, text: &str) {
+    for c in text.chars() {
+        editor.process_input(Input::Character(c), false).unwrap();
+    }
+}
+
+#[test]
+fn test_palette_lists_every_action() {
+    let mut editor = Editor::new(None, None, None);
+    editor.execute_action(Action::CommandPalette).unwrap();
+    assert_eq!(editor.mode, EditorMode::CommandPalette);
+    assert_eq!(editor.command_palette.matches.len(), PALETTE_ACTIONS.len());
+    assert_eq!(action_name(&Action::ToggleCheckbox), "ToggleCheckbox");
+}
+
+#[test]
+fn test_palette_runs_filtered_action() {
+    let mut editor = Editor::new(None, None, None);
+    editor.document.set_lines(vec!["- [ ] task".to_string()]);
+    editor.execute_action(Action::CommandPalette).unwrap();
+    type_text(&mut editor, "togglecheck");
+    assert_eq!(editor.command_palette.matches[0], Action::ToggleCheckbox);
+
+    editor.process_input(Input::Character('\n'), false).unwrap();
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert_eq!(editor.document.lines(), vec!["- [x] task"]);
+}
+
+#[test]
+fn test_palette_filter_and_cancel() {
+    let mut editor = Editor::new(None, None, None);
+    editor.execute_action(Action::CommandPalette).unwrap();
+    type_text(&mut editor, "zzzq");
+    assert!(editor.command_palette.matches.is_empty());
+    editor.process_input(Input::KeyBackspace, false).unwrap();
+    assert_eq!(editor.command_palette.query, "zzz");
+
+    // Enter with nothing to run keeps the palette open
+    editor.process_input(Input::Character('\n'), false).unwrap();
+    assert_eq!(editor.mode, EditorMode::CommandPalette);
+    editor
+        .process_input(Input::Character('\x1b'), false)
+        .unwrap();
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert!(editor.command_palette.query.is_empty());
+}
+
+#[test]
+fn test_palette_action_is_recorded_in_macro() {
+    let mut editor = Editor::new(None, None, None);
+    editor.execute_action(Action::StartMacroRecording).unwrap();
+    editor.execute_action(Action::CommandPalette).unwrap();
+    type_text(&mut editor, "GoToEndOfFile");
+    editor.process_input(Input::Character('\n'), false).unwrap();
+    editor.execute_action(Action::StopMacroRecording).unwrap();
+    assert_eq!(editor.macros.last_macro, vec![Action::GoToEndOfFile]);
+}
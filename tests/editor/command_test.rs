@@ -1,4 +1,5 @@
 use chrono::Local;
+use dmacs::config::EditorSettings;
 use dmacs::editor::Editor;
 
 #[test]
@@ -8,12 +9,12 @@ fn test_today_command() {
     editor.insert_newline().unwrap();
 
     let expected_date = Local::now().format("%Y-%m-%d").to_string();
-    assert_eq!(editor.document.lines.len(), 2);
-    assert_eq!(editor.document.lines[0], expected_date);
-    assert_eq!(editor.document.lines[1], "");
+    assert_eq!(editor.document.lines().len(), 2);
+    assert_eq!(editor.document.lines()[0], expected_date);
+    assert_eq!(editor.document.lines()[1], "");
     assert_eq!(editor.status_message, "/today");
-    assert_eq!(editor.cursor_y, 1);
-    assert_eq!(editor.cursor_x, 0);
+    assert_eq!(editor.cursor.y, 1);
+    assert_eq!(editor.cursor.x, 0);
 }
 
 #[test]
@@ -23,10 +24,174 @@ fn test_now_command() {
     editor.insert_newline().unwrap();
 
     let expected_date = Local::now().format("%Y-%m-%d %H:%M").to_string();
-    assert_eq!(editor.document.lines.len(), 2);
-    assert_eq!(editor.document.lines[0], expected_date);
-    assert_eq!(editor.document.lines[1], "");
+    assert_eq!(editor.document.lines().len(), 2);
+    assert_eq!(editor.document.lines()[0], expected_date);
+    assert_eq!(editor.document.lines()[1], "");
     assert_eq!(editor.status_message, "/now");
-    assert_eq!(editor.cursor_y, 1);
-    assert_eq!(editor.cursor_x, 0);
+    assert_eq!(editor.cursor.y, 1);
+    assert_eq!(editor.cursor.x, 0);
+}
+
+#[test]
+fn test_time_command() {
+    let mut editor = Editor::new(None, None, None);
+    editor.insert_text("/time").unwrap();
+    editor.insert_newline().unwrap();
+
+    let expected_time = Local::now().format("%H:%M").to_string();
+    assert_eq!(editor.document.lines(), vec![expected_time, "".to_string()]);
+    assert_eq!(editor.status_message, "/time");
+}
+
+#[test]
+fn test_timestamp_commands_use_configured_formats() {
+    let mut editor = Editor::new(None, None, None);
+    editor.set_settings(EditorSettings {
+        date_format: Some("%d/%m/%Y".to_string()),
+        datetime_format: Some("%Y%m%dT%H%M".to_string()),
+        ..EditorSettings::default()
+    });
+    editor.insert_text("/today").unwrap();
+    editor.insert_newline().unwrap();
+    editor.insert_text("/now").unwrap();
+    editor.insert_newline().unwrap();
+
+    let now = Local::now();
+    assert_eq!(
+        editor.document.lines()[0],
+        now.format("%d/%m/%Y").to_string()
+    );
+    assert_eq!(
+        editor.document.lines()[1],
+        now.format("%Y%m%dT%H%M").to_string()
+    );
+}
+
+#[test]
+fn test_timestamp_command_with_invalid_format() {
+    let mut editor = Editor::new(None, None, None);
+    editor.set_settings(EditorSettings {
+        date_format: Some("%Q".to_string()),
+        ..EditorSettings::default()
+    });
+    editor.insert_text("/today").unwrap();
+    editor.insert_newline().unwrap();
+
+    assert_eq!(editor.document.lines(), vec!["/today"]);
+    assert_eq!(editor.status_message, "Invalid format for /today: %Q");
+}
+
+#[test]
+fn test_insert_timestamp_at_cursor() {
+    let mut editor = Editor::new(None, None, None);
+    editor.set_settings(EditorSettings {
+        datetime_format: Some("%Y-%m-%d".to_string()),
+        ..EditorSettings::default()
+    });
+    editor.insert_text("Met on ").unwrap();
+    editor.insert_timestamp().unwrap();
+
+    let expected = format!("Met on {}", Local::now().format("%Y-%m-%d"));
+    assert_eq!(editor.document.lines(), vec![expected.clone()]);
+    assert_eq!(editor.cursor.x, expected.len());
+}
+
+#[test]
+fn test_inbox_command_appends_task_to_inbox() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let inbox_path = dir.path().join("inbox.md");
+    std::fs::write(&inbox_path, "- [ ] existing\n").unwrap();
+
+    let mut editor = Editor::new(None, None, None);
+    editor.set_settings(EditorSettings {
+        inbox_file: Some(inbox_path.to_str().unwrap().to_string()),
+        ..EditorSettings::default()
+    });
+    editor.insert_text("/inbox review PR").unwrap();
+    editor.insert_newline().unwrap();
+
+    assert_eq!(
+        std::fs::read_to_string(&inbox_path).unwrap(),
+        "- [ ] existing\n- [ ] review PR\n"
+    );
+    assert_eq!(editor.document.lines(), vec!["# /inbox review PR", ""]);
+    assert!(editor.status_message.starts_with("/inbox: added to "));
+}
+
+#[test]
+fn test_inbox_command_without_inbox_file() {
+    let mut editor = Editor::new(None, None, None);
+    editor.insert_text("/inbox something").unwrap();
+    editor.insert_newline().unwrap();
+
+    assert_eq!(editor.document.lines(), vec!["/inbox something"]);
+    assert_eq!(
+        editor.status_message,
+        "No inbox file configured. Set inbox_file under [editor]."
+    );
+}
+
+fn wait_for_shell(editor: &mut Editor) {
+    for _ in 0..500 {
+        editor.shell_tick();
+        if !editor.is_shell_command_running() {
+            return;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    panic!("shell command did not finish");
+}
+
+#[test]
+fn test_shell_command_inserts_output_below() {
+    let mut editor = Editor::new(None, None, None);
+    editor.document.set_lines(vec![
+        "/sh printf 'a\\nb\\n'".to_string(),
+        "after".to_string(),
+    ]);
+    editor.set_cursor_pos(editor.document.lines()[0].len(), 0);
+    editor.insert_newline().unwrap();
+    assert!(editor.is_shell_command_running());
+    wait_for_shell(&mut editor);
+
+    assert_eq!(
+        editor.document.lines(),
+        vec!["/sh printf 'a\\nb\\n'", "a", "b", "after"]
+    );
+    assert_eq!(editor.status_message, "/sh: 2 line(s) of output");
+    editor.undo();
+    assert_eq!(
+        editor.document.lines(),
+        vec!["/sh printf 'a\\nb\\n'", "after"]
+    );
+}
+
+#[test]
+fn test_shell_command_replaces_line() {
+    let mut editor = Editor::new(None, None, None);
+    editor
+        .insert_text("/sh! echo hello; echo warning >&2")
+        .unwrap();
+    editor.insert_newline().unwrap();
+    wait_for_shell(&mut editor);
+
+    assert_eq!(editor.document.lines(), vec!["hello"]);
+    assert_eq!(editor.status_message, "/sh: 1 line(s) of output (warning)");
+}
+
+#[test]
+fn test_shell_command_failure_and_timeout() {
+    let mut editor = Editor::new(None, None, None);
+    editor.insert_text("/sh echo oops >&2; exit 3").unwrap();
+    editor.insert_newline().unwrap();
+    wait_for_shell(&mut editor);
+    assert_eq!(editor.document.lines(), vec!["/sh echo oops >&2; exit 3"]);
+    assert_eq!(editor.status_message, "/sh exit 3: oops");
+
+    let mut editor = Editor::new(None, None, None);
+    editor.settings.shell_timeout_secs = Some(1);
+    editor.insert_text("/sh sleep 5").unwrap();
+    editor.insert_newline().unwrap();
+    wait_for_shell(&mut editor);
+    assert_eq!(editor.status_message, "/sh timed out after 1s");
 }
@@ -1,25 +1,28 @@
 use dmacs::editor::Editor;
+use dmacs::editor::cursor::Cursor;
 use pancurses::Input;
 
 fn create_editor_with_content(content: &str) -> Editor {
     let mut editor = Editor::new(None, None, None);
-    editor.document.lines = content.lines().map(|s| s.to_string()).collect();
-    if editor.document.lines.is_empty() {
-        editor.document.lines.push(String::new());
+    editor
+        .document
+        .set_lines(content.lines().map(|s| s.to_string()).collect());
+    if editor.document.lines().is_empty() {
+        editor.document.set_lines(vec![String::new()]);
     }
     editor
 }
 
-fn simulate_alt_slash(editor: &mut Editor) {
-    editor.process_input(Input::Character('/'), true).unwrap();
+fn simulate_alt_question_mark(editor: &mut Editor) {
+    editor.process_input(Input::Character('?'), true).unwrap();
 }
 
 #[test]
 fn test_toggle_comment_on_single_line() {
     let mut editor = create_editor_with_content("hello world");
     editor.set_cursor_pos(5, 0);
-    simulate_alt_slash(&mut editor);
-    assert_eq!(editor.document.lines[0], "# hello world");
+    simulate_alt_question_mark(&mut editor);
+    assert_eq!(editor.document.lines()[0], "# hello world");
     assert_eq!(editor.cursor_pos(), (7, 0));
     assert_eq!(editor.status_message, "Commented line.");
 }
@@ -28,8 +31,8 @@ fn test_toggle_comment_on_single_line() {
 fn test_toggle_comment_off_single_line() {
     let mut editor = create_editor_with_content("# hello world");
     editor.set_cursor_pos(7, 0);
-    simulate_alt_slash(&mut editor);
-    assert_eq!(editor.document.lines[0], "hello world");
+    simulate_alt_question_mark(&mut editor);
+    assert_eq!(editor.document.lines()[0], "hello world");
     assert_eq!(editor.cursor_pos(), (5, 0));
     assert_eq!(editor.status_message, "Uncommented line.");
 }
@@ -38,8 +41,8 @@ fn test_toggle_comment_off_single_line() {
 fn test_toggle_comment_on_indented_line() {
     let mut editor = create_editor_with_content("  hello world");
     editor.set_cursor_pos(7, 0);
-    simulate_alt_slash(&mut editor);
-    assert_eq!(editor.document.lines[0], "  # hello world");
+    simulate_alt_question_mark(&mut editor);
+    assert_eq!(editor.document.lines()[0], "  # hello world");
     assert_eq!(editor.cursor_pos(), (9, 0));
 }
 
@@ -47,8 +50,8 @@ fn test_toggle_comment_on_indented_line() {
 fn test_toggle_comment_off_indented_line() {
     let mut editor = create_editor_with_content("  # hello world");
     editor.set_cursor_pos(9, 0);
-    simulate_alt_slash(&mut editor);
-    assert_eq!(editor.document.lines[0], "  hello world");
+    simulate_alt_question_mark(&mut editor);
+    assert_eq!(editor.document.lines()[0], "  hello world");
     assert_eq!(editor.cursor_pos(), (7, 0));
 }
 
@@ -59,10 +62,13 @@ fn test_toggle_comment_on_selection() {
 line2
 line3",
     );
-    editor.selection.set_marker((0, 0));
+    editor.selection.set_marker(&Cursor::new(0, 0));
     editor.set_cursor_pos(5, 2);
-    simulate_alt_slash(&mut editor);
-    assert_eq!(editor.document.lines, vec!["# line1", "# line2", "# line3"]);
+    simulate_alt_question_mark(&mut editor);
+    assert_eq!(
+        editor.document.lines(),
+        vec!["# line1", "# line2", "# line3"]
+    );
     assert!(editor.selection.is_selection_active());
 }
 
@@ -73,10 +79,10 @@ fn test_toggle_comment_off_selection() {
 # line2
 # line3",
     );
-    editor.selection.set_marker((0, 0));
+    editor.selection.set_marker(&Cursor::new(0, 0));
     editor.set_cursor_pos(7, 2);
-    simulate_alt_slash(&mut editor);
-    assert_eq!(editor.document.lines, vec!["line1", "line2", "line3"]);
+    simulate_alt_question_mark(&mut editor);
+    assert_eq!(editor.document.lines(), vec!["line1", "line2", "line3"]);
     assert!(editor.selection.is_selection_active());
 }
 
@@ -87,11 +93,11 @@ fn test_toggle_comment_on_mixed_selection() {
 # line2
 line3",
     );
-    editor.selection.set_marker((0, 0));
+    editor.selection.set_marker(&Cursor::new(0, 0));
     editor.set_cursor_pos(5, 2);
-    simulate_alt_slash(&mut editor);
+    simulate_alt_question_mark(&mut editor);
     assert_eq!(
-        editor.document.lines,
+        editor.document.lines(),
         vec!["# line1", "# # line2", "# line3"]
     );
 }
@@ -103,10 +109,10 @@ fn test_toggle_comment_selection_ignores_empty_lines() {
 
 line3",
     );
-    editor.selection.set_marker((0, 0));
+    editor.selection.set_marker(&Cursor::new(0, 0));
     editor.set_cursor_pos(5, 2);
-    simulate_alt_slash(&mut editor);
-    assert_eq!(editor.document.lines, vec!["# line1", "", "# line3"]);
+    simulate_alt_question_mark(&mut editor);
+    assert_eq!(editor.document.lines(), vec!["# line1", "", "# line3"]);
 }
 
 #[test]
@@ -115,28 +121,28 @@ fn test_toggle_comment_selection_excludes_last_line_if_cursor_x_is_zero() {
         "line1
 line2",
     );
-    editor.selection.set_marker((0, 0));
+    editor.selection.set_marker(&Cursor::new(0, 0));
     editor.set_cursor_pos(0, 1);
-    simulate_alt_slash(&mut editor);
-    assert_eq!(editor.document.lines, vec!["# line1", "line2"]);
+    simulate_alt_question_mark(&mut editor);
+    assert_eq!(editor.document.lines(), vec!["# line1", "line2"]);
 }
 
 #[test]
 fn test_toggle_comment_undo_redo() {
     let mut editor = create_editor_with_content("hello");
-    let original_content = editor.document.lines.clone();
+    let original_content = editor.document.lines().clone();
     let original_cursor = editor.cursor_pos();
 
-    simulate_alt_slash(&mut editor);
-    let commented_content = editor.document.lines.clone();
+    simulate_alt_question_mark(&mut editor);
+    let commented_content = editor.document.lines().clone();
     let commented_cursor = editor.cursor_pos();
     assert_eq!(commented_content[0], "# hello");
 
     editor.undo();
-    assert_eq!(editor.document.lines, original_content);
+    assert_eq!(editor.document.lines(), original_content);
     assert_eq!(editor.cursor_pos(), original_cursor);
 
     editor.redo();
-    assert_eq!(editor.document.lines, commented_content);
+    assert_eq!(editor.document.lines(), commented_content);
     assert_eq!(editor.cursor_pos(), commented_cursor);
 }
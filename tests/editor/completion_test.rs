@@ -0,0 +1,148 @@
+use dmacs::editor::{Editor, EditorMode};
+use pancurses::Input;
+use std::fs;
+use tempfile::TempDir;
+
+fn create_editor_with_content(content: &str) -> Editor {
+    let mut editor = Editor::new(None, None, None);
+    editor
+        .document
+        .set_lines(content.lines().map(|s| s.to_string()).collect());
+    if editor.document.lines().is_empty() {
+        editor.document.set_lines(vec![String::new()]);
+    }
+    editor
+}
+
+fn press_alt_slash(editor: &mut Editor) {
+    editor.process_input(Input::Character('/'), true).unwrap();
+}
+
+fn type_text(editor: &mut Editor, text: &str) {
+    for c in text.chars() {
+        editor.process_input(Input::Character(c), false).unwrap();
+    }
+}
+
+fn candidates(editor: &Editor) -> Vec<&str> {
+    editor
+        .completion
+        .popup
+        .as_ref()
+        .map(|popup| popup.candidates.iter().map(String::as_str).collect())
+        .unwrap_or_default()
+}
+
+#[test]
+fn test_single_candidate_is_inserted() {
+    let mut editor = create_editor_with_content("dependency graph\ndep");
+    editor.set_cursor_pos(3, 1);
+    press_alt_slash(&mut editor);
+    assert_eq!(editor.document.lines()[1], "dependency");
+    assert_eq!(editor.cursor_pos(), (10, 1));
+    assert_eq!(editor.mode, EditorMode::Normal);
+}
+
+#[test]
+fn test_candidates_nearest_first() {
+    let mut editor =
+        create_editor_with_content("parsers far away\nparse\n\nthe pa\npartial result\n\n\n");
+    editor.set_cursor_pos(6, 3);
+    press_alt_slash(&mut editor);
+    assert_eq!(editor.mode, EditorMode::Completion);
+    assert_eq!(candidates(&editor), vec!["partial", "parse", "parsers"]);
+}
+
+#[test]
+fn test_tab_and_arrows_select_and_enter_inserts() {
+    let mut editor = create_editor_with_content("alpha alpine\nal");
+    editor.set_cursor_pos(2, 1);
+    press_alt_slash(&mut editor);
+    assert_eq!(candidates(&editor), vec!["alpine", "alpha"]);
+
+    editor.process_input(Input::Character('\t'), false).unwrap();
+    assert_eq!(editor.completion.popup.as_ref().unwrap().selected, 1);
+    editor.process_input(Input::KeyDown, false).unwrap();
+    assert_eq!(editor.completion.popup.as_ref().unwrap().selected, 0);
+    editor.process_input(Input::KeyUp, false).unwrap();
+    press_alt_slash(&mut editor);
+    press_alt_slash(&mut editor);
+    assert_eq!(editor.completion.popup.as_ref().unwrap().selected, 1);
+    assert_eq!(editor.document.lines()[1], "al");
+
+    editor.process_input(Input::Character('\n'), false).unwrap();
+    assert_eq!(editor.document.lines()[1], "alpha");
+    assert_eq!(editor.cursor_pos(), (5, 1));
+    assert_eq!(editor.mode, EditorMode::Normal);
+
+    editor.undo();
+    assert_eq!(editor.document.lines()[1], "al");
+}
+
+#[test]
+fn test_escape_leaves_the_word_and_other_keys_pass_through() {
+    let mut editor = create_editor_with_content("alpha alpine\nal");
+    editor.set_cursor_pos(2, 1);
+    press_alt_slash(&mut editor);
+    editor
+        .process_input(Input::Character('\x1b'), false)
+        .unwrap();
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert_eq!(editor.document.lines()[1], "al");
+
+    press_alt_slash(&mut editor);
+    type_text(&mut editor, "p");
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert_eq!(editor.document.lines()[1], "alp");
+}
+
+#[test]
+fn test_index_follows_edits() {
+    let mut editor = create_editor_with_content("first line\n");
+    editor.set_cursor_pos(0, 1);
+    type_text(&mut editor, "lin");
+    press_alt_slash(&mut editor);
+    assert_eq!(editor.document.lines()[1], "line");
+
+    type_text(&mut editor, " lengthy\nlen");
+    press_alt_slash(&mut editor);
+    assert_eq!(editor.document.lines()[2], "lengthy");
+
+    // Killed words are no longer offered
+    editor.set_cursor_pos(0, 0);
+    editor
+        .process_input(Input::Character('\x0b'), false)
+        .unwrap();
+    editor.set_cursor_pos(12, 1);
+    type_text(&mut editor, " fir");
+    press_alt_slash(&mut editor);
+    assert_eq!(editor.document.lines()[1], "line lengthy fir");
+    assert_eq!(editor.status_message, "No completion for fir.");
+}
+
+#[test]
+fn test_words_of_the_other_buffer_come_last() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("notes.md");
+    fs::write(&path, "retrospective notes\n").unwrap();
+    let mut editor = Editor::new(Some(path.to_string_lossy().to_string()), None, None);
+    editor.toggle_scratch_buffer();
+    type_text(&mut editor, "retro\nret");
+    press_alt_slash(&mut editor);
+    assert_eq!(candidates(&editor), vec!["retro", "retrospective"]);
+}
+
+#[test]
+fn test_no_completion() {
+    let mut editor = create_editor_with_content("nothing here\nzq");
+    editor.set_cursor_pos(2, 1);
+    press_alt_slash(&mut editor);
+    assert_eq!(editor.status_message, "No completion for zq.");
+
+    editor.set_cursor_pos(0, 1);
+    press_alt_slash(&mut editor);
+    assert_eq!(
+        editor.status_message,
+        "No word before the cursor to complete."
+    );
+}
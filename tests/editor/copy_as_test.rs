@@ -0,0 +1,57 @@
+use crate::editor_with_lines;
+use dmacs::editor::actions::Action;
+use dmacs::editor::{Editor, EditorMode};
+use pancurses::Input;
+
+fn type_text(editor: &mut Editor, text: &str) {
+    for c in text.chars() {
+        editor.process_input(Input::Character(c), false).unwrap();
+    }
+}
+
+#[test]
+fn test_copy_selection_as_jira() {
+    let mut editor = editor_with_lines(&["# Plan", "- [ ] pack", "rest"]);
+    editor.set_marker_action();
+    editor.set_cursor_pos(0, 2);
+
+    editor.execute_action(Action::CopyAs).unwrap();
+    assert_eq!(editor.mode, EditorMode::Minibuffer);
+    type_text(&mut editor, "jira\n");
+
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert_eq!(editor.clipboard.kill_buffer, "h1. Plan\n* (x) pack\n");
+    assert_eq!(editor.status_message, "Selection copied as Jira markup.");
+    assert!(!editor.selection.is_selection_active());
+    assert_eq!(
+        editor.document.lines(),
+        vec!["# Plan", "- [ ] pack", "rest"]
+    );
+}
+
+#[test]
+fn test_copy_as_requires_selection() {
+    let mut editor = editor_with_lines(&["text"]);
+    editor.execute_action(Action::CopyAs).unwrap();
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert_eq!(editor.status_message, "No selection to copy.");
+}
+
+#[test]
+fn test_copy_as_unknown_format_keeps_selection() {
+    let mut editor = editor_with_lines(&["- [x] done"]);
+    editor.set_marker_action();
+    editor.set_cursor_pos(10, 0);
+    editor.execute_action(Action::CopyAs).unwrap();
+    type_text(&mut editor, "rtf\n");
+
+    assert_eq!(
+        editor.status_message,
+        "Unknown format: rtf (use text, html, jira, slack)"
+    );
+    assert!(editor.selection.is_selection_active());
+
+    editor.execute_action(Action::CopyAs).unwrap();
+    type_text(&mut editor, "text\n");
+    assert_eq!(editor.clipboard.kill_buffer, "✓ done");
+}
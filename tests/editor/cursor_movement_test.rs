@@ -1,10 +1,13 @@
 use dmacs::editor::Editor;
+use dmacs::editor::actions::Action;
 use pancurses::Input;
 
 #[test]
 fn test_editor_move_cursor() {
     let mut editor = Editor::new(None, None, None);
-    editor.document.lines = vec!["one".to_string(), "two".to_string()];
+    editor
+        .document
+        .set_lines(vec!["one".to_string(), "two".to_string()]);
     editor.process_input(Input::KeyDown, false).unwrap();
     assert_eq!(editor.cursor_pos(), (0, 1));
     editor.process_input(Input::KeyRight, false).unwrap();
@@ -18,7 +21,7 @@ fn test_editor_move_cursor() {
 #[test]
 fn test_editor_go_to_line_boundaries() {
     let mut editor = Editor::new(None, None, None);
-    editor.document.lines[0] = "hello".to_string();
+    editor.document.set_line(0, "hello".to_string());
     editor.process_input(Input::KeyRight, false).unwrap();
     editor.process_input(Input::KeyRight, false).unwrap();
     assert_eq!(editor.cursor_pos(), (2, 0));
@@ -35,7 +38,9 @@ fn test_editor_go_to_line_boundaries() {
 #[test]
 fn test_editor_move_cursor_up_at_top_line() {
     let mut editor = Editor::new(None, None, None);
-    editor.document.lines = vec!["line1".to_string(), "line2".to_string()];
+    editor
+        .document
+        .set_lines(vec!["line1".to_string(), "line2".to_string()]);
     editor.set_cursor_pos(3, 0); // Set cursor to (3, 0)
     editor.process_input(Input::KeyUp, false).unwrap();
     assert_eq!(editor.cursor_pos(), (0, 0)); // Should move to (0, 0)
@@ -44,7 +49,9 @@ fn test_editor_move_cursor_up_at_top_line() {
 #[test]
 fn test_editor_move_cursor_down_at_bottom_line() {
     let mut editor = Editor::new(None, None, None);
-    editor.document.lines = vec!["line1".to_string(), "line2".to_string()];
+    editor
+        .document
+        .set_lines(vec!["line1".to_string(), "line2".to_string()]);
     editor.set_cursor_pos(0, 1); // Set cursor to (0, 1)
     editor.process_input(Input::KeyDown, false).unwrap();
     assert_eq!(editor.cursor_pos(), (5, 1)); // Should move to (end of line, 1)
@@ -53,7 +60,9 @@ fn test_editor_move_cursor_down_at_bottom_line() {
 #[test]
 fn test_editor_move_cursor_left_across_lines() {
     let mut editor = Editor::new(None, None, None);
-    editor.document.lines = vec!["line1".to_string(), "line2".to_string()];
+    editor
+        .document
+        .set_lines(vec!["line1".to_string(), "line2".to_string()]);
     editor.set_cursor_pos(0, 1); // Start at beginning of line2
     editor.process_input(Input::KeyLeft, false).unwrap();
     assert_eq!(editor.cursor_pos(), (5, 0)); // Should move to end of line1
@@ -62,7 +71,9 @@ fn test_editor_move_cursor_left_across_lines() {
 #[test]
 fn test_editor_move_cursor_right_across_lines() {
     let mut editor = Editor::new(None, None, None);
-    editor.document.lines = vec!["line1".to_string(), "line2".to_string()];
+    editor
+        .document
+        .set_lines(vec!["line1".to_string(), "line2".to_string()]);
     editor.set_cursor_pos(5, 0); // Start at end of line1
     editor.process_input(Input::KeyRight, false).unwrap();
     assert_eq!(editor.cursor_pos(), (0, 1)); // Should move to beginning of line2
@@ -71,7 +82,9 @@ fn test_editor_move_cursor_right_across_lines() {
 #[test]
 fn test_editor_move_cursor_word_left() {
     let mut editor = Editor::new(None, None, None);
-    editor.document.lines = vec!["word1 word2 word3".to_string()];
+    editor
+        .document
+        .set_lines(vec!["word1 word2 word3".to_string()]);
     editor.set_cursor_pos(17, 0); // End of "word3"
 
     editor
@@ -90,7 +103,9 @@ fn test_editor_move_cursor_word_left() {
     assert_eq!(editor.cursor_pos(), (0, 0)); // Should move to beginning of line
 
     // Test with leading/trailing spaces
-    editor.document.lines = vec!["  word1  word2  ".to_string()];
+    editor
+        .document
+        .set_lines(vec!["  word1  word2  ".to_string()]);
     editor.set_cursor_pos(16, 0); // End of line
 
     editor
@@ -107,7 +122,9 @@ fn test_editor_move_cursor_word_left() {
 #[test]
 fn test_editor_move_cursor_word_right() {
     let mut editor = Editor::new(None, None, None);
-    editor.document.lines = vec!["word1 word2 word3".to_string()];
+    editor
+        .document
+        .set_lines(vec!["word1 word2 word3".to_string()]);
     editor.set_cursor_pos(0, 0); // Beginning of "word1"
 
     editor.process_input(Input::Character('f'), true).unwrap(); // Alt-f
@@ -120,7 +137,9 @@ fn test_editor_move_cursor_word_right() {
     assert_eq!(editor.cursor_pos(), (17, 0)); // Should move to end of line
 
     // Test with leading/trailing spaces
-    editor.document.lines = vec!["  word1  word2  ".to_string()];
+    editor
+        .document
+        .set_lines(vec!["  word1  word2  ".to_string()]);
     editor.set_cursor_pos(0, 0); // Beginning of line
 
     editor.process_input(Input::Character('f'), true).unwrap(); // Alt-f
@@ -164,7 +183,9 @@ fn test_go_to_end_of_file() {
 #[test]
 fn test_editor_move_cursor_word_right_japanese() {
     let mut editor = Editor::new(None, None, None);
-    editor.document.lines = vec!["漢字とひらがなとカタカナと英字123。".to_string()];
+    editor
+        .document
+        .set_lines(vec!["漢字とひらがなとカタカナと英字123。".to_string()]);
     editor.set_cursor_pos(0, 0);
 
     // Move to end of "漢字"
@@ -205,7 +226,7 @@ fn test_editor_move_cursor_word_left_japanese() {
     let mut editor = Editor::new(None, None, None);
     let line = "漢字とひらがなとカタカナと英字123。".to_string();
     let line_len = line.len();
-    editor.document.lines = vec![line];
+    editor.document.set_lines(vec![line]);
     editor.set_cursor_pos(line_len, 0);
 
     // Move to beginning of "。"
@@ -256,3 +277,67 @@ fn test_editor_move_cursor_word_left_japanese() {
         .unwrap(); // Ctrl-B
     assert_eq!(editor.cursor_pos(), (0, 0));
 }
+
+#[test]
+fn test_word_movement_with_configured_classes() {
+    let mut editor = Editor::new(None, None, None);
+    editor
+        .document
+        .set_lines(vec!["well-known a => b".to_string()]);
+    editor.move_cursor_word_right().unwrap();
+    assert_eq!(editor.cursor_pos(), (4, 0));
+
+    // `-` joins words, and `=>` is a word of its own
+    editor.settings.word_chars = Some("-".to_string());
+    editor.settings.word_classes = Some(vec!["=<>".to_string()]);
+    editor.set_cursor_pos(0, 0);
+    editor.move_cursor_word_right().unwrap();
+    assert_eq!(editor.cursor_pos(), (10, 0));
+    editor.move_cursor_word_right().unwrap();
+    assert_eq!(editor.cursor_pos(), (12, 0));
+    editor.move_cursor_word_right().unwrap();
+    assert_eq!(editor.cursor_pos(), (15, 0));
+    editor.move_cursor_word_left().unwrap();
+    assert_eq!(editor.cursor_pos(), (13, 0));
+
+    editor.set_cursor_pos(10, 0);
+    editor.hungry_delete().unwrap();
+    assert_eq!(editor.document.lines()[0], " a => b");
+}
+
+#[test]
+fn test_subword_movement() {
+    let mut editor = Editor::new(None, None, None);
+    editor
+        .document
+        .set_lines(vec!["parseHTTPServer foo_bar".to_string()]);
+    editor.move_cursor_word_right().unwrap();
+    assert_eq!(editor.cursor_pos(), (15, 0));
+
+    editor.execute_action(Action::ToggleSubwordMode).unwrap();
+    assert_eq!(editor.status_message, "Subword movement on.");
+    editor.set_cursor_pos(0, 0);
+    let mut stops = Vec::new();
+    for _ in 0..6 {
+        editor.move_cursor_word_right().unwrap();
+        stops.push(editor.cursor.x);
+    }
+    assert_eq!(stops, vec![5, 9, 15, 19, 20, 23]);
+
+    editor.move_cursor_word_left().unwrap();
+    assert_eq!(editor.cursor_pos(), (20, 0));
+    editor.set_cursor_pos(15, 0);
+    editor.hungry_delete().unwrap();
+    assert_eq!(editor.document.lines()[0], "parseHTTP foo_bar");
+}
+
+#[test]
+fn test_subword_mode_follows_filetype() {
+    let mut editor = Editor::new(None, None, None);
+    editor.settings.subword_filetypes = Some(vec!["rs".to_string()]);
+    assert!(!editor.is_subword_mode());
+    editor.document.filename = Some("main.rs".to_string());
+    assert!(editor.is_subword_mode());
+    editor.toggle_subword_mode();
+    assert!(!editor.is_subword_mode());
+}
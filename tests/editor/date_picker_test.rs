@@ -0,0 +1,73 @@
+use crate::editor_with_lines;
+use chrono::{Days, Local};
+use dmacs::editor::EditorMode;
+use dmacs::editor::actions::Action;
+use dmacs::editor::date_picker::parse_due;
+use pancurses::Input;
+
+#[test]
+fn test_pick_date_inserts_due_annotation() {
+    let mut editor = editor_with_lines(&["- [ ] pay rent"]);
+    editor.set_cursor_pos(3, 0);
+    editor.execute_action(Action::OpenDatePicker).unwrap();
+    assert_eq!(editor.mode, EditorMode::DatePicker);
+
+    let today = Local::now().date_naive();
+    assert_eq!(editor.date_picker.as_ref().unwrap().selected, today);
+
+    editor.process_input(Input::KeyRight, false).unwrap();
+    editor.process_input(Input::KeyDown, false).unwrap();
+    editor.process_input(Input::Character('\n'), false).unwrap();
+
+    let expected = today.checked_add_days(Days::new(8)).unwrap();
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert_eq!(
+        editor.document.lines()[0],
+        format!("- [ ] pay rent @due({})", expected.format("%Y-%m-%d"))
+    );
+    assert_eq!(editor.cursor_pos(), (3, 0));
+
+    editor.undo();
+    assert_eq!(editor.document.lines()[0], "- [ ] pay rent");
+}
+
+#[test]
+fn test_picker_starts_from_existing_due_date_and_replaces_it() {
+    let mut editor = editor_with_lines(&["- [ ] report @due(2024-01-31) #work"]);
+    editor.open_date_picker();
+    assert_eq!(
+        editor.date_picker.as_ref().unwrap().selected.to_string(),
+        "2024-01-31"
+    );
+
+    editor.process_input(Input::KeyNPage, false).unwrap();
+    editor.process_input(Input::Character('\n'), false).unwrap();
+    assert_eq!(
+        editor.document.lines()[0],
+        "- [ ] report @due(2024-02-29) #work"
+    );
+}
+
+#[test]
+fn test_cancel_date_picker() {
+    let mut editor = editor_with_lines(&["- [ ] task"]);
+    editor.open_date_picker();
+    editor.process_input(Input::KeyLeft, false).unwrap();
+    editor
+        .process_input(Input::Character('\x07'), false)
+        .unwrap();
+
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert!(editor.date_picker.is_none());
+    assert_eq!(editor.document.lines()[0], "- [ ] task");
+}
+
+#[test]
+fn test_parse_due() {
+    assert_eq!(
+        parse_due("- [ ] x @due(2025-12-01)").map(|d| d.to_string()),
+        Some("2025-12-01".to_string())
+    );
+    assert_eq!(parse_due("- [ ] x @due(tomorrow)"), None);
+    assert_eq!(parse_due("- [ ] x"), None);
+}
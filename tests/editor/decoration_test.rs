@@ -0,0 +1,144 @@
+use crate::editor_with_lines;
+use chrono::NaiveDate;
+use dmacs::editor::Editor;
+use dmacs::editor::decoration::{
+    DecorationContext, LineDecorations, LineTransformer, Replacement, Span,
+};
+use dmacs::editor::ui::{KEYWORD_COLOR_PAIRS, OVERDUE_COLOR_PAIR, RenderAttributes};
+use pancurses::{A_BOLD, A_NORMAL, A_UNDERLINE};
+
+// Lines are decorated as when the cursor is elsewhere, on the last one
+fn editor_on_last_line(lines: &[&str]) -> Editor {
+    let mut editor = editor_with_lines(lines);
+    editor.cursor.y = lines.len() - 1;
+    editor
+}
+
+fn decorate(editor: &Editor, y: usize, monochrome: bool) -> LineDecorations {
+    let todo_keywords = editor.todo_keywords();
+    let context = DecorationContext {
+        attributes: RenderAttributes::new(monochrome),
+        today: NaiveDate::from_ymd_opt(2025, 6, 11).unwrap(),
+        todo_keywords: &todo_keywords,
+        monochrome,
+    };
+    editor.decorate_line(y, &context)
+}
+
+#[test]
+fn test_default_transformers_style_tasks_and_comments() {
+    let editor = editor_on_last_line(&["# Notes", "- [ ] ship @due(2025-06-01)", "- [x] done", ""]);
+    let attributes = RenderAttributes::new(false);
+
+    assert_eq!(decorate(&editor, 0, false).line_attr, attributes.comment);
+    assert_eq!(decorate(&editor, 2, false).line_attr, attributes.checked);
+
+    let task = decorate(&editor, 1, false);
+    assert_eq!(task.line_attr, attributes.unchecked);
+    assert_eq!(task.line_color_pair, Some(3));
+    assert_eq!(
+        task.spans,
+        vec![Span {
+            start: 11,
+            end: 27,
+            attr: attributes.overdue,
+            color_pair: Some(OVERDUE_COLOR_PAIR),
+        }]
+    );
+    assert_eq!(decorate(&editor, 1, true).line_color_pair, None);
+}
+
+#[test]
+fn test_separator_is_a_rule() {
+    let editor = editor_on_last_line(&["---", "--- not a rule", ""]);
+    assert!(decorate(&editor, 0, false).rule);
+    assert!(!decorate(&editor, 1, false).rule);
+}
+
+#[test]
+fn test_todo_keyword_span() {
+    let editor = editor_on_last_line(&["## TODO write", ""]);
+    let decorations = decorate(&editor, 0, false);
+    assert_eq!(
+        decorations.span_at(3).unwrap().color_pair,
+        Some(KEYWORD_COLOR_PAIRS[0])
+    );
+    assert_eq!(decorations.span_at(8), None);
+}
+
+#[test]
+fn test_placeholders_are_replacements_off_the_cursor_line() {
+    let editor = editor_on_last_line(&["![cat](data:image/png;base64,AAAA)", ""]);
+    let decorations = decorate(&editor, 0, false);
+    assert_eq!(decorations.replacement_at(0).unwrap().label, "[img: cat]");
+    assert!(decorate(&editor, 1, false).replacements.is_empty());
+}
+
+/// Underlines `@mentions`.
+struct Mentions;
+
+impl LineTransformer for Mentions {
+    fn decorate(
+        &self,
+        _editor: &Editor,
+        _y: usize,
+        line: &str,
+        _context: &DecorationContext,
+        decorations: &mut LineDecorations,
+    ) {
+        if let Some(start) = line.find('@') {
+            let end = line[start..]
+                .find(' ')
+                .map_or(line.len(), |offset| start + offset);
+            decorations.spans.push(Span {
+                start,
+                end,
+                attr: A_UNDERLINE,
+                color_pair: None,
+            });
+        }
+    }
+}
+
+/// Hides everything after `//`.
+struct HideRemarks;
+
+impl LineTransformer for HideRemarks {
+    fn decorate(
+        &self,
+        _editor: &Editor,
+        _y: usize,
+        line: &str,
+        _context: &DecorationContext,
+        decorations: &mut LineDecorations,
+    ) {
+        if let Some(start) = line.find("//") {
+            decorations.replacements.push(Replacement {
+                start,
+                end: line.len(),
+                label: "…".to_string(),
+                attr: A_BOLD,
+            });
+        }
+    }
+}
+
+#[test]
+fn test_added_transformers_run_after_the_defaults() {
+    let mut editor = editor_on_last_line(&["ask @ana // later", "## TODO @bob", ""]);
+    editor.add_line_transformer(Box::new(Mentions));
+    editor.add_line_transformer(Box::new(HideRemarks));
+
+    let decorations = decorate(&editor, 0, false);
+    assert_eq!(decorations.line_attr, A_NORMAL);
+    assert_eq!(decorations.span_at(5).unwrap().attr, A_UNDERLINE);
+    assert_eq!(decorations.replacement_at(10).unwrap().label, "…");
+
+    // Spans of the defaults and of added transformers side by side
+    let heading = decorate(&editor, 1, false);
+    assert_eq!(heading.span_at(8).unwrap().attr, A_UNDERLINE);
+    assert_eq!(
+        heading.span_at(3).unwrap().attr,
+        RenderAttributes::new(false).todo_keyword
+    );
+}
@@ -2,7 +2,9 @@ use dmacs::editor::Editor;
 
 fn setup_editor_with_content(content: Vec<&str>) -> Editor {
     let mut editor = Editor::new(None, None, None);
-    editor.document.lines = content.iter().map(|&s| s.to_string()).collect();
+    editor
+        .document
+        .set_lines(content.iter().map(|&s| s.to_string()).collect());
     editor.scroll.screen_rows = 20; // Set a reasonable screen size for testing
     editor.scroll.screen_cols = 80;
     editor
@@ -11,209 +13,209 @@ fn setup_editor_with_content(content: Vec<&str>) -> Editor {
 #[test]
 fn test_move_to_next_delimiter_no_delimiters() {
     let mut editor = setup_editor_with_content(vec!["line 1", "line 2", "line 3"]);
-    editor.cursor_y = 1; // Start in the middle
+    editor.cursor.y = 1; // Start in the middle
 
     editor.move_to_next_delimiter();
     assert_eq!(
-        editor.cursor_y, 1,
+        editor.cursor.y, 1,
         "Cursor should remain in original position if no delimiters"
     );
-    assert_eq!(editor.cursor_x, 0);
+    assert_eq!(editor.cursor.x, 0);
 }
 
 #[test]
 fn test_move_to_next_delimiter_after_current_position() {
     let mut editor = setup_editor_with_content(vec!["line 1", "---", "line 3", "---", "line 5"]);
-    editor.cursor_y = 0; // Start at the beginning
+    editor.cursor.y = 0; // Start at the beginning
 
     editor.move_to_next_delimiter();
     assert_eq!(
-        editor.cursor_y, 2,
+        editor.cursor.y, 2,
         "Cursor should move to line after first delimiter"
     );
-    assert_eq!(editor.cursor_x, 0);
+    assert_eq!(editor.cursor.x, 0);
 
     editor.move_to_next_delimiter();
     assert_eq!(
-        editor.cursor_y, 4,
+        editor.cursor.y, 4,
         "Cursor should move to line after second delimiter"
     );
-    assert_eq!(editor.cursor_x, 0);
+    assert_eq!(editor.cursor.x, 0);
 }
 
 #[test]
 fn test_move_to_next_delimiter_from_delimiter_line() {
     let mut editor = setup_editor_with_content(vec!["line 1", "---", "line 3", "line 4"]);
-    editor.cursor_y = 1; // Start on the delimiter
+    editor.cursor.y = 1; // Start on the delimiter
 
     editor.move_to_next_delimiter();
     assert_eq!(
-        editor.cursor_y, 2,
+        editor.cursor.y, 2,
         "Cursor should move to line after the current delimiter"
     );
-    assert_eq!(editor.cursor_x, 0);
+    assert_eq!(editor.cursor.x, 0);
 }
 
 #[test]
 fn test_move_to_next_delimiter_multiple_delimiters() {
     let mut editor =
         setup_editor_with_content(vec!["---", "line 1", "---", "line 2", "---", "line 3"]);
-    editor.cursor_y = 0;
+    editor.cursor.y = 0;
 
     editor.move_to_next_delimiter();
-    assert_eq!(editor.cursor_y, 1);
+    assert_eq!(editor.cursor.y, 1);
 
     editor.move_to_next_delimiter();
-    assert_eq!(editor.cursor_y, 3);
+    assert_eq!(editor.cursor.y, 3);
 
     editor.move_to_next_delimiter();
-    assert_eq!(editor.cursor_y, 5);
+    assert_eq!(editor.cursor.y, 5);
 
     // No more delimiters, cursor should not move
     editor.move_to_next_delimiter();
-    assert_eq!(editor.cursor_y, 5);
+    assert_eq!(editor.cursor.y, 5);
 }
 
 #[test]
 fn test_move_to_next_delimiter_at_end_of_file() {
     let mut editor = setup_editor_with_content(vec!["line 1", "line 2", "---"]);
-    editor.cursor_y = 0;
+    editor.cursor.y = 0;
 
     editor.move_to_next_delimiter();
     assert_eq!(
-        editor.cursor_y, 0,
+        editor.cursor.y, 0,
         "Cursor should wrap around to beginning if delimiter is last line"
     );
-    assert_eq!(editor.cursor_x, 0);
+    assert_eq!(editor.cursor.x, 0);
 }
 
 #[test]
 fn test_move_to_next_delimiter_empty_document() {
     let mut editor = setup_editor_with_content(vec![]);
-    editor.cursor_y = 0;
+    editor.cursor.y = 0;
 
     editor.move_to_next_delimiter();
     assert_eq!(
-        editor.cursor_y, 0,
+        editor.cursor.y, 0,
         "Cursor should remain at 0,0 in empty document"
     );
-    assert_eq!(editor.cursor_x, 0);
+    assert_eq!(editor.cursor.x, 0);
 }
 
 #[test]
 fn test_move_to_next_delimiter_no_further_delimiters() {
     let mut editor = setup_editor_with_content(vec!["line 1", "line 2", "---", "line 4"]);
-    editor.cursor_y = 3; // Start after the last delimiter
+    editor.cursor.y = 3; // Start after the last delimiter
 
     editor.move_to_next_delimiter();
     assert_eq!(
-        editor.cursor_y, 3,
+        editor.cursor.y, 3,
         "Cursor should not move if no further delimiters"
     );
-    assert_eq!(editor.cursor_x, 0);
+    assert_eq!(editor.cursor.x, 0);
 }
 
 #[test]
 fn test_move_to_previous_delimiter_no_delimiters() {
     let mut editor = setup_editor_with_content(vec!["line 1", "line 2", "line 3"]);
-    editor.cursor_y = 1; // Start in the middle
+    editor.cursor.y = 1; // Start in the middle
 
     editor.move_to_previous_delimiter();
     assert_eq!(
-        editor.cursor_y, 0,
+        editor.cursor.y, 0,
         "Cursor should move to page 0 if no delimiters above"
     );
-    assert_eq!(editor.cursor_x, 0);
+    assert_eq!(editor.cursor.x, 0);
 }
 
 #[test]
 fn test_move_to_previous_delimiter_before_current_position() {
     let mut editor = setup_editor_with_content(vec!["line 1", "---", "line 3", "---", "line 5"]);
-    editor.cursor_y = 4; // Start at line 5
+    editor.cursor.y = 4; // Start at line 5
 
     editor.move_to_previous_delimiter();
     assert_eq!(
-        editor.cursor_y, 2,
+        editor.cursor.y, 2,
         "Cursor should move to line after previous delimiter"
     );
-    assert_eq!(editor.cursor_x, 0);
+    assert_eq!(editor.cursor.x, 0);
 
     editor.move_to_previous_delimiter();
     assert_eq!(
-        editor.cursor_y, 0,
+        editor.cursor.y, 0,
         "Cursor should move to page 0 after previous delimiter"
     );
-    assert_eq!(editor.cursor_x, 0);
+    assert_eq!(editor.cursor.x, 0);
 }
 
 #[test]
 fn test_move_to_previous_delimiter_from_delimiter_line() {
     let mut editor = setup_editor_with_content(vec!["line 1", "---", "line 3", "line 4"]);
-    editor.cursor_y = 1; // Start on the delimiter
+    editor.cursor.y = 1; // Start on the delimiter
 
     editor.move_to_previous_delimiter();
     assert_eq!(
-        editor.cursor_y, 0,
+        editor.cursor.y, 0,
         "Cursor should move to line before the current delimiter"
     );
-    assert_eq!(editor.cursor_x, 0);
+    assert_eq!(editor.cursor.x, 0);
 }
 
 #[test]
 fn test_move_to_previous_delimiter_multiple_delimiters() {
     let mut editor =
         setup_editor_with_content(vec!["---", "line 1", "---", "line 2", "---", "line 3"]);
-    editor.cursor_y = 5;
+    editor.cursor.y = 5;
 
     editor.move_to_previous_delimiter();
-    assert_eq!(editor.cursor_y, 3);
+    assert_eq!(editor.cursor.y, 3);
 
     editor.move_to_previous_delimiter();
-    assert_eq!(editor.cursor_y, 1);
+    assert_eq!(editor.cursor.y, 1);
 
     editor.move_to_previous_delimiter();
-    assert_eq!(editor.cursor_y, 0);
+    assert_eq!(editor.cursor.y, 0);
 
     // No more delimiters, cursor should move to page 0
     editor.move_to_previous_delimiter();
-    assert_eq!(editor.cursor_y, 0);
+    assert_eq!(editor.cursor.y, 0);
 }
 
 #[test]
 fn test_move_to_previous_delimiter_at_beginning_of_file() {
     let mut editor = setup_editor_with_content(vec!["---", "line 1", "line 2"]);
-    editor.cursor_y = 0;
+    editor.cursor.y = 0;
 
     editor.move_to_previous_delimiter();
     assert_eq!(
-        editor.cursor_y, 0,
+        editor.cursor.y, 0,
         "Cursor should remain at 0,0 if at beginning of file"
     );
-    assert_eq!(editor.cursor_x, 0);
+    assert_eq!(editor.cursor.x, 0);
 }
 
 #[test]
 fn test_move_to_previous_delimiter_empty_document() {
     let mut editor = setup_editor_with_content(vec![]);
-    editor.cursor_y = 0;
+    editor.cursor.y = 0;
 
     editor.move_to_previous_delimiter();
     assert_eq!(
-        editor.cursor_y, 0,
+        editor.cursor.y, 0,
         "Cursor should remain at 0,0 in empty document"
     );
-    assert_eq!(editor.cursor_x, 0);
+    assert_eq!(editor.cursor.x, 0);
 }
 
 #[test]
 fn test_move_to_previous_delimiter_no_previous_delimiters() {
     let mut editor = setup_editor_with_content(vec!["line 1", "---", "line 3", "line 4"]);
-    editor.cursor_y = 1; // Start before the first delimiter
+    editor.cursor.y = 1; // Start before the first delimiter
 
     editor.move_to_previous_delimiter();
     assert_eq!(
-        editor.cursor_y, 0,
+        editor.cursor.y, 0,
         "Cursor should move to page 0 if no previous delimiters"
     );
-    assert_eq!(editor.cursor_x, 0);
+    assert_eq!(editor.cursor.x, 0);
 }
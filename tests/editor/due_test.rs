@@ -0,0 +1,116 @@
+use chrono::NaiveDate;
+use dmacs::document::LineBuffer;
+use dmacs::editor::actions::Action;
+use dmacs::editor::due::{overdue_annotation, section_range, sort_tasks_by_due};
+use dmacs::editor::{Editor, EditorMode};
+
+fn lines(lines: &[&str]) -> Vec<String> {
+    lines.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn test_overdue_annotation() {
+    let today = NaiveDate::from_ymd_opt(2025, 6, 12).unwrap();
+    assert_eq!(
+        overdue_annotation("- [ ] pay @due(2025-06-11)", today),
+        Some((10, 26))
+    );
+    assert_eq!(
+        overdue_annotation("- [ ] pay @due(2025-06-12)", today),
+        None
+    );
+    assert_eq!(
+        overdue_annotation("- [x] paid @due(2025-06-01)", today),
+        None
+    );
+    assert_eq!(overdue_annotation("note @due(2025-06-01)", today), None);
+}
+
+#[test]
+fn test_section_range() {
+    let doc = LineBuffer::from(lines(&["# A", "a", "## B", "b1", "b2", "---", "c"]));
+    assert_eq!(section_range(&doc, 3), 3..5);
+    assert_eq!(section_range(&doc, 2), 3..5);
+    assert_eq!(section_range(&doc, 6), 6..7);
+}
+
+#[test]
+fn test_sort_tasks_by_due_keeps_nested_lines() {
+    let doc = LineBuffer::from(lines(&[
+        "# Tasks",
+        "- [ ] later @due(2025-07-01)",
+        "  - detail",
+        "Some note",
+        "- [ ] undated",
+        "- [x] sooner @due(2025-06-01)",
+    ]));
+    let (sorted, moved_to) = sort_tasks_by_due(&doc, 1..6);
+    assert_eq!(
+        sorted,
+        lines(&[
+            "# Tasks",
+            "- [x] sooner @due(2025-06-01)",
+            "Some note",
+            "- [ ] later @due(2025-07-01)",
+            "  - detail",
+            "- [ ] undated",
+        ])
+    );
+    assert_eq!(moved_to, vec![3, 4, 2, 5, 1]);
+}
+
+#[test]
+fn test_sort_tasks_action_is_one_undo_step() {
+    let original = [
+        "- [ ] b @due(2025-06-02)",
+        "- [ ] a @due(2025-06-01)",
+        "## Next",
+        "- [ ] z @due(2025-01-01)",
+    ];
+    let mut editor = Editor::new(None, None, None);
+    editor.document.set_lines(lines(&original));
+    editor.set_cursor_pos(6, 0);
+    editor.execute_action(Action::SortTasksByDue).unwrap();
+    assert_eq!(
+        editor.document.lines(),
+        lines(&[
+            "- [ ] a @due(2025-06-01)",
+            "- [ ] b @due(2025-06-02)",
+            "## Next",
+            "- [ ] z @due(2025-01-01)",
+        ])
+    );
+    // Still on the "b" task
+    assert_eq!(editor.cursor_pos(), (6, 1));
+
+    editor.undo();
+    assert_eq!(editor.document.lines(), lines(&original));
+}
+
+#[test]
+fn test_task_selection_lists_soonest_due_first() {
+    let mut editor = Editor::new(None, None, None);
+    editor.document.set_lines(lines(&[
+        "/task",
+        "- [ ] first",
+        "- [ ] due later @due(2025-09-01)",
+        "- [ ] due soon @due(2025-06-01)",
+    ]));
+    editor.set_cursor_pos(5, 0);
+    editor.insert_newline().unwrap();
+    assert_eq!(editor.mode, EditorMode::TaskSelection);
+    let order: Vec<&str> = editor
+        .task
+        .tasks
+        .iter()
+        .map(|(_, task)| task.as_str())
+        .collect();
+    assert_eq!(
+        order,
+        vec![
+            "- [ ] due soon @due(2025-06-01)",
+            "- [ ] due later @due(2025-09-01)",
+            "- [ ] first",
+        ]
+    );
+}
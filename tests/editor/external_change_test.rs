@@ -0,0 +1,136 @@
+use dmacs::editor::actions::Action;
+use dmacs::editor::cursor::Cursor;
+use dmacs::editor::{Editor, EditorMode};
+use pancurses::Input;
+use std::fs;
+use tempfile::TempDir;
+
+fn editor_for_file(dir: &TempDir, content: &str) -> (Editor, String) {
+    let path = dir.path().join("notes.md");
+    fs::write(&path, content).unwrap();
+    let path = path.to_string_lossy().to_string();
+    (Editor::new(Some(path.clone()), None, None), path)
+}
+
+fn type_text(editor: &mut Editor, text: &str) {
+    for c in text.chars() {
+        editor.process_input(Input::Character(c), false).unwrap();
+    }
+}
+
+#[test]
+fn test_change_on_disk_is_reported() {
+    let dir = TempDir::new().unwrap();
+    let (mut editor, path) = editor_for_file(&dir, "one\n");
+
+    fs::write(&path, "one\ntwo\n").unwrap();
+    editor.external_change_tick();
+    assert!(editor.external_change.is_changed());
+    assert!(editor.status_message.contains("changed on disk"));
+    // The buffer is left alone
+    assert_eq!(editor.document.lines(), vec!["one"]);
+}
+
+#[test]
+fn test_save_over_change_on_disk_asks_first() {
+    let dir = TempDir::new().unwrap();
+    let (mut editor, path) = editor_for_file(&dir, "one\n");
+    editor.set_cursor_pos(3, 0);
+    type_text(&mut editor, "!");
+    fs::write(&path, "one\ntwo\n").unwrap();
+
+    editor.execute_action(Action::Save).unwrap();
+    assert_eq!(editor.mode, EditorMode::SaveConflict);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "one\ntwo\n");
+
+    // Anything else keeps asking
+    editor.process_input(Input::Character('x'), false).unwrap();
+    assert_eq!(editor.mode, EditorMode::SaveConflict);
+
+    editor.process_input(Input::Character('c'), false).unwrap();
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "one\ntwo\n");
+    assert_eq!(editor.document.lines(), vec!["one!"]);
+}
+
+#[test]
+fn test_overwrite_saves_buffer() {
+    let dir = TempDir::new().unwrap();
+    let (mut editor, path) = editor_for_file(&dir, "one\n");
+    editor.set_cursor_pos(3, 0);
+    type_text(&mut editor, "!");
+    fs::write(&path, "one\ntwo\n").unwrap();
+
+    editor.execute_action(Action::Save).unwrap();
+    editor.process_input(Input::Character('o'), false).unwrap();
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "one!\n");
+    assert!(!editor.external_change.is_changed());
+
+    // Our own save is not a change on disk
+    editor.execute_action(Action::Save).unwrap();
+    assert_eq!(editor.mode, EditorMode::Normal);
+}
+
+#[test]
+fn test_reload_replaces_buffer_as_one_undo_step() {
+    let dir = TempDir::new().unwrap();
+    let (mut editor, path) = editor_for_file(&dir, "one\nthree\n");
+    editor.set_cursor_pos(5, 1);
+    type_text(&mut editor, "!");
+    fs::write(&path, "one\ntwo\nthree\n").unwrap();
+
+    editor.execute_action(Action::Save).unwrap();
+    editor.process_input(Input::Character('r'), false).unwrap();
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert_eq!(editor.document.lines(), vec!["one", "two", "three"]);
+    assert!(!editor.document.is_dirty());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "one\ntwo\nthree\n");
+
+    editor.undo();
+    assert_eq!(editor.document.lines(), vec!["one", "three!"]);
+}
+
+#[test]
+fn test_unchanged_content_with_new_modification_time_is_ignored() {
+    let dir = TempDir::new().unwrap();
+    let (mut editor, path) = editor_for_file(&dir, "one\n");
+    filetime::set_file_mtime(&path, filetime::FileTime::from_unix_time(0, 0)).unwrap();
+
+    editor.execute_action(Action::Save).unwrap();
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert!(!editor.external_change.is_changed());
+}
+
+#[test]
+fn test_reload_keeps_cursor_mark_and_undo_history() {
+    let dir = TempDir::new().unwrap();
+    let (mut editor, path) = editor_for_file(&dir, "a\nb\nc\nd\ne\n");
+    editor.set_undo_debounce_threshold(0);
+    editor.set_cursor_pos(1, 3);
+    type_text(&mut editor, "!");
+    editor.selection.set_marker(&Cursor::new(0, 4));
+    editor.set_cursor_pos(2, 3);
+    fs::write(&path, "new\na\nb\nc\nd!\nE\n").unwrap();
+
+    editor.execute_action(Action::ReloadFromDisk).unwrap();
+    assert_eq!(
+        editor.document.lines(),
+        vec!["new", "a", "b", "c", "d!", "E"]
+    );
+    assert_eq!(editor.cursor_pos(), (2, 4));
+    assert_eq!(editor.selection.marker_pos, Some((0, 5)));
+    let depth = editor.undo_redo.undo_stack.len();
+    assert_eq!(depth, 2);
+
+    editor.undo();
+    assert_eq!(editor.document.lines(), vec!["a", "b", "c", "d!", "e"]);
+    editor.undo();
+    assert_eq!(editor.document.lines(), vec!["a", "b", "c", "d", "e"]);
+    editor.redo();
+    editor.redo();
+    assert_eq!(
+        editor.document.lines(),
+        vec!["new", "a", "b", "c", "d!", "E"]
+    );
+}
@@ -0,0 +1,131 @@
+use dmacs::editor::actions::Action;
+use dmacs::editor::{Editor, EditorMode};
+use pancurses::Input;
+use std::fs;
+use tempfile::TempDir;
+
+fn setup_dir() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    fs::create_dir(dir.path().join("daily")).unwrap();
+    fs::write(dir.path().join("daily").join("today.md"), "in daily\n").unwrap();
+    fs::write(dir.path().join("notes.md"), "notes\n").unwrap();
+    fs::write(dir.path().join("todo.md"), "todo\n").unwrap();
+    fs::write(dir.path().join(".hidden"), "").unwrap();
+    dir
+}
+
+fn open_browser_in(dir: &TempDir) -> Editor {
+    let start = dir.path().join("todo.md");
+    let mut editor = Editor::new(Some(start.to_string_lossy().to_string()), None, None);
+    editor.execute_action(Action::OpenFile).unwrap();
+    editor
+}
+
+fn type_text(editor: &mut Editor, text: &str) {
+    for c in text.chars() {
+        editor.process_input(Input::Character(c), false).unwrap();
+    }
+}
+
+fn listed(editor: &Editor) -> Vec<String> {
+    let browser = &editor.file_browser;
+    browser
+        .matches
+        .iter()
+        .map(|&i| browser.entries[i].name.clone())
+        .collect()
+}
+
+#[test]
+fn test_lists_directories_first_without_hidden_files() {
+    let dir = setup_dir();
+    let editor = open_browser_in(&dir);
+    assert_eq!(editor.mode, EditorMode::FileBrowser);
+    assert_eq!(editor.file_browser.dir, dir.path());
+    assert_eq!(listed(&editor), vec!["daily", "notes.md", "todo.md"]);
+}
+
+#[test]
+fn test_fuzzy_filter_and_open() {
+    let dir = setup_dir();
+    let mut editor = open_browser_in(&dir);
+    type_text(&mut editor, "nts");
+    assert_eq!(listed(&editor), vec!["notes.md"]);
+
+    type_text(&mut editor, "\n");
+    assert_eq!(editor.mode, EditorMode::Normal);
+    let expected = dir.path().join("notes.md");
+    assert_eq!(
+        editor.document.filename.as_deref(),
+        Some(expected.to_string_lossy().as_ref())
+    );
+    assert_eq!(editor.document.lines(), vec!["notes"]);
+}
+
+#[test]
+fn test_descend_and_go_up() {
+    let dir = setup_dir();
+    let mut editor = open_browser_in(&dir);
+    type_text(&mut editor, "\n");
+    assert_eq!(editor.file_browser.dir, dir.path().join("daily"));
+    assert_eq!(listed(&editor), vec!["today.md"]);
+
+    // Backspace removes the query first, then goes up
+    type_text(&mut editor, "x");
+    editor.process_input(Input::KeyBackspace, false).unwrap();
+    assert_eq!(editor.file_browser.dir, dir.path().join("daily"));
+    editor.process_input(Input::KeyBackspace, false).unwrap();
+    assert_eq!(editor.file_browser.dir, dir.path());
+}
+
+#[test]
+fn test_create_new_file() {
+    let dir = setup_dir();
+    let mut editor = open_browser_in(&dir);
+    editor.document.set_lines(vec!["todo edited".to_string()]);
+    type_text(&mut editor, "zzz.md\n");
+
+    let expected = dir.path().join("zzz.md");
+    assert_eq!(
+        editor.document.filename.as_deref(),
+        Some(expected.to_string_lossy().as_ref())
+    );
+    assert_eq!(editor.document.lines(), vec![""]);
+    // The file that was open is saved before it is replaced
+    assert_eq!(
+        fs::read_to_string(dir.path().join("todo.md")).unwrap(),
+        "todo edited\n"
+    );
+    assert!(editor.alternate_buffer.is_none());
+}
+
+#[test]
+fn test_open_from_scratch_buffer_keeps_it() {
+    let dir = setup_dir();
+    let mut editor = Editor::new(None, None, None);
+    editor.document.set_lines(vec!["scratch text".to_string()]);
+    let path = dir.path().join("notes.md");
+    editor.open_file(&path.to_string_lossy()).unwrap();
+    assert_eq!(editor.document.lines(), vec!["notes"]);
+
+    editor.execute_action(Action::ToggleScratchBuffer).unwrap();
+    assert!(editor.is_scratch_buffer());
+    assert_eq!(editor.document.lines(), vec!["scratch text"]);
+}
+
+#[test]
+fn test_escape_clears_query_then_closes() {
+    let dir = setup_dir();
+    let mut editor = open_browser_in(&dir);
+    type_text(&mut editor, "no");
+    editor
+        .process_input(Input::Character('\x1b'), false)
+        .unwrap();
+    assert_eq!(editor.mode, EditorMode::FileBrowser);
+    assert_eq!(editor.file_browser.query, "");
+    editor
+        .process_input(Input::Character('\x1b'), false)
+        .unwrap();
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert!(editor.document.filename.unwrap().ends_with("todo.md"));
+}
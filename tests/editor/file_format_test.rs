@@ -0,0 +1,72 @@
+use dmacs::document::LineEnding;
+use dmacs::editor::Editor;
+use dmacs::editor::actions::Action;
+use pancurses::Input;
+use std::fs;
+use tempfile::TempDir;
+
+fn open(dir: &TempDir, content: &str) -> (Editor, std::path::PathBuf) {
+    let path = dir.path().join("notes.md");
+    fs::write(&path, content).unwrap();
+    let editor = Editor::new(Some(path.to_string_lossy().to_string()), None, None);
+    (editor, path)
+}
+
+#[test]
+fn test_warns_about_long_lines() {
+    let dir = TempDir::new().unwrap();
+    let (mut editor, _) = open(&dir, "short\nthis line is long\nalso much too long\n");
+    assert!(editor.file_format_warnings().is_empty());
+
+    editor.settings.max_line_bytes = Some(10);
+    editor.warn_about_file_format();
+    assert_eq!(
+        editor.status_message,
+        "Line 2 is longer than 10 bytes (2 lines in total)."
+    );
+
+    editor.execute_action(Action::Save).unwrap();
+    assert_eq!(
+        editor.status_message,
+        "File saved! Line 2 is longer than 10 bytes (2 lines in total)."
+    );
+}
+
+#[test]
+fn test_line_ending_mismatch_is_fixed_by_cleanup() {
+    let dir = TempDir::new().unwrap();
+    let (mut editor, path) = open(&dir, "a\r\nb\r\n");
+    editor.settings.line_ending = Some("lf".to_string());
+    editor.warn_about_file_format();
+    assert_eq!(
+        editor.status_message,
+        "File uses CRLF line endings; /cleanup converts them to LF."
+    );
+    // Nothing changes until asked
+    editor.execute_action(Action::Save).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "a\r\nb\r\n");
+
+    editor.set_cursor_pos(1, 1);
+    editor.process_input(Input::Character('\n'), false).unwrap();
+    for c in "/cleanup\n".chars() {
+        editor.process_input(Input::Character(c), false).unwrap();
+    }
+    assert_eq!(
+        editor.status_message,
+        "Cleanup: nothing to change. Line endings will be saved as LF."
+    );
+    assert_eq!(editor.document.line_ending, LineEnding::Lf);
+    editor.execute_action(Action::Save).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "a\nb\n");
+    assert_eq!(editor.status_message, "File saved!");
+}
+
+#[test]
+fn test_warns_about_mixed_line_endings() {
+    let dir = TempDir::new().unwrap();
+    let (editor, _) = open(&dir, "a\r\nb\nc\n");
+    assert_eq!(
+        editor.file_format_warnings(),
+        vec!["File mixes LF and CRLF line endings; saving writes LF."]
+    );
+}
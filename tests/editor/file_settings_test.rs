@@ -0,0 +1,125 @@
+use dmacs::config::EditorSettings;
+use dmacs::document::LineBuffer;
+use dmacs::editor::Editor;
+use dmacs::file_settings::FileSettings;
+use pancurses::Input;
+use std::fs;
+use tempfile::TempDir;
+
+fn lines(content: &str) -> LineBuffer {
+    content.lines().map(str::to_string).collect()
+}
+
+fn open_with_settings(dir: &TempDir, content: &str, settings: EditorSettings) -> Editor {
+    let path = dir.path().join("notes.md");
+    fs::write(&path, content).unwrap();
+    let mut editor = Editor::new(Some(path.to_string_lossy().to_string()), None, None);
+    editor.set_settings(settings);
+    editor
+}
+
+#[test]
+fn test_parse_front_matter() {
+    let settings = FileSettings::parse(&lines(
+        "---\ntitle: Notes\ndmacs:\n  tab_width: 4\n  fill_column: 72\n  list_continuation: no\n  theme: \"light\"\n  abbrevs: off\n---\n# Notes",
+    ));
+    assert_eq!(
+        settings,
+        FileSettings {
+            indent_width: Some(4),
+            ruler_column: Some(72),
+            list_continuation: Some(false),
+            theme: Some("light".to_string()),
+            abbrevs: Some(false),
+        }
+    );
+}
+
+#[test]
+fn test_front_matter_must_start_on_the_first_line() {
+    let settings = FileSettings::parse(&lines("# Notes\n---\nindent_width: 4\n---"));
+    assert!(settings.is_empty());
+}
+
+#[test]
+fn test_parse_modeline() {
+    let settings = FileSettings::parse(&lines(
+        "# Notes\n<!-- dmacs: indent_width=3; ruler_column=60, list_continuation=off -->",
+    ));
+    assert_eq!(settings.indent_width, Some(3));
+    assert_eq!(settings.ruler_column, Some(60));
+    assert_eq!(settings.list_continuation, Some(false));
+    assert_eq!(settings.theme, None);
+}
+
+#[test]
+fn test_modeline_below_the_first_lines_is_ignored() {
+    let settings = FileSettings::parse(&lines("1\n2\n3\n4\n5\n# dmacs: indent_width=8"));
+    assert!(settings.is_empty());
+}
+
+#[test]
+fn test_invalid_values_are_ignored() {
+    let settings = FileSettings::parse(&lines(
+        "# dmacs: indent_width=0 ruler_column=wide list_continuation=maybe",
+    ));
+    assert!(settings.is_empty());
+}
+
+#[test]
+fn test_file_settings_override_the_user_settings() {
+    let dir = TempDir::new().unwrap();
+    let editor = open_with_settings(
+        &dir,
+        "# dmacs: indent_width=4 ruler_column=72\n- item",
+        EditorSettings {
+            indent_width: Some(2),
+            ruler_column: Some(80),
+            ..Default::default()
+        },
+    );
+    assert_eq!(editor.settings.indent_width(), 4);
+    assert_eq!(editor.settings.ruler_column, Some(72));
+}
+
+#[test]
+fn test_list_continuation_off_keeps_only_the_indentation() {
+    let dir = TempDir::new().unwrap();
+    let mut editor = open_with_settings(
+        &dir,
+        "<!-- dmacs: list_continuation=off -->\n  - item",
+        EditorSettings::default(),
+    );
+    editor.set_cursor_pos(8, 1);
+    editor.process_input(Input::Character('\n'), false).unwrap();
+    assert_eq!(editor.document.lines()[1], "  - item");
+    assert_eq!(editor.document.lines()[2], "  ");
+}
+
+#[test]
+fn test_settings_are_read_again_on_save() {
+    let dir = TempDir::new().unwrap();
+    let mut editor = open_with_settings(
+        &dir,
+        "# dmacs: indent_width=4\n- item",
+        EditorSettings {
+            indent_width: Some(2),
+            ..Default::default()
+        },
+    );
+    assert_eq!(editor.settings.indent_width(), 4);
+
+    editor
+        .document
+        .set_line(0, "# dmacs: indent_width=6 theme=light".to_string());
+    editor.save_document().unwrap();
+    assert_eq!(editor.settings.indent_width(), 6);
+    assert_eq!(editor.settings.theme.as_deref(), Some("light"));
+    assert!(editor.pending_colors.is_some());
+
+    // Without the modeline the user's own values come back
+    editor.document.set_line(0, "# Notes".to_string());
+    editor.save_document().unwrap();
+    assert_eq!(editor.settings.indent_width(), 2);
+    assert_eq!(editor.settings.theme, None);
+}
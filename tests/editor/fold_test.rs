@@ -0,0 +1,99 @@
+use crate::editor_with_lines;
+use dmacs::document::LineBuffer;
+use dmacs::editor::actions::Action;
+use dmacs::editor::fold::fold_range;
+
+fn lines(lines: &[&str]) -> LineBuffer {
+    lines.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn test_fold_range() {
+    let doc = lines(&[
+        "# One", "text", "## Sub", "more", "# Two", "---", "page", "---", "# Empty",
+    ]);
+    assert_eq!(fold_range(&doc, 0), Some(1..4));
+    assert_eq!(fold_range(&doc, 2), Some(3..4));
+    // Sections stop at a delimiter, and pages at the next one
+    assert_eq!(fold_range(&doc, 4), None);
+    assert_eq!(fold_range(&doc, 5), Some(6..7));
+    assert_eq!(fold_range(&doc, 7), Some(8..9));
+    assert_eq!(fold_range(&doc, 8), None);
+    assert_eq!(fold_range(&doc, 1), None);
+}
+
+#[test]
+fn test_toggle_fold_hides_section_and_cursor_skips_it() {
+    let mut editor = editor_with_lines(&["# One", "a", "b", "# Two", "c"]);
+    editor.set_cursor_pos(0, 2);
+    editor.execute_action(Action::ToggleFold).unwrap();
+    // Folding from inside the section moves to its heading
+    assert_eq!(editor.cursor_pos(), (0, 0));
+    assert!(editor.is_line_hidden(1));
+    assert!(editor.is_line_hidden(2));
+    assert_eq!(editor.visible_row(3), 1);
+    assert_eq!(editor.line_at_visible_row(1), 3);
+
+    editor.execute_action(Action::MoveDown).unwrap();
+    assert_eq!(editor.cursor_pos().1, 3);
+    editor.execute_action(Action::MoveUp).unwrap();
+    assert_eq!(editor.cursor_pos().1, 0);
+    editor.execute_action(Action::GoToEndOfLine).unwrap();
+    editor.execute_action(Action::MoveRight).unwrap();
+    assert_eq!(editor.cursor_pos(), (0, 3));
+    editor.execute_action(Action::MoveLeft).unwrap();
+    assert_eq!(editor.cursor_pos(), (5, 0));
+
+    editor.execute_action(Action::ToggleFold).unwrap();
+    assert!(!editor.is_line_hidden(1));
+}
+
+#[test]
+fn test_fold_at_end_of_file_keeps_cursor_on_heading() {
+    let mut editor = editor_with_lines(&["intro", "---", "page"]);
+    editor.set_cursor_pos(0, 1);
+    editor.execute_action(Action::ToggleFold).unwrap();
+    editor.execute_action(Action::MoveDown).unwrap();
+    assert_eq!(editor.cursor_pos(), (3, 1));
+    editor.execute_action(Action::GoToEndOfFile).unwrap();
+    assert_eq!(editor.cursor_pos(), (3, 1));
+}
+
+#[test]
+fn test_fold_all_and_unfold_all() {
+    let mut editor = editor_with_lines(&["# One", "## Sub", "a", "# Two", "b"]);
+    editor.set_cursor_pos(0, 2);
+    editor.execute_action(Action::FoldAll).unwrap();
+    assert_eq!(editor.status_message, "Folded 3 sections.");
+    assert_eq!(editor.cursor_pos().1, 0);
+    assert_eq!(editor.hidden_ranges(), vec![1..3, 4..5]);
+
+    editor.execute_action(Action::UnfoldAll).unwrap();
+    assert!(editor.hidden_ranges().is_empty());
+}
+
+#[test]
+fn test_folds_follow_edits_above() {
+    let mut editor = editor_with_lines(&["intro", "# One", "a"]);
+    editor.set_cursor_pos(0, 1);
+    editor.execute_action(Action::ToggleFold).unwrap();
+    editor.set_cursor_pos(5, 0);
+    editor.execute_action(Action::InsertNewline).unwrap();
+    assert_eq!(editor.hidden_ranges(), vec![3..4]);
+}
+
+#[test]
+fn test_cursor_in_folded_section_unfolds_it() {
+    let mut editor = editor_with_lines(&["# One", "needle", "# Two"]);
+    editor.execute_action(Action::ToggleFold).unwrap();
+    editor.set_cursor_pos(0, 1);
+    editor.scroll();
+    assert!(editor.hidden_ranges().is_empty());
+}
+
+#[test]
+fn test_nothing_to_fold() {
+    let mut editor = editor_with_lines(&["plain", "text"]);
+    editor.execute_action(Action::ToggleFold).unwrap();
+    assert_eq!(editor.status_message, "Nothing to fold here.");
+}
@@ -0,0 +1,93 @@
+use dmacs::editor::Editor;
+use dmacs::editor::actions::Action;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+fn follow(dir: &TempDir, content: &str) -> (Editor, PathBuf) {
+    let path = dir.path().join("app.log");
+    fs::write(&path, content).unwrap();
+    let mut editor = Editor::new(Some(path.to_string_lossy().to_string()), None, None);
+    editor.enable_follow();
+    (editor, path)
+}
+
+fn append(path: &Path, text: &str) {
+    let mut file = OpenOptions::new().append(true).open(path).unwrap();
+    file.write_all(text.as_bytes()).unwrap();
+}
+
+#[test]
+fn test_follow_appends_lines_and_scrolls_to_end() {
+    let dir = TempDir::new().unwrap();
+    let (mut editor, path) = follow(&dir, "one\ntwo\n");
+    assert_eq!(editor.cursor_pos(), (0, 1));
+
+    append(&path, "three\nfour\n");
+    editor.poll_followed_file();
+    assert_eq!(editor.document.lines(), vec!["one", "two", "three", "four"]);
+    assert_eq!(editor.cursor_pos(), (0, 3));
+    assert!(!editor.document.is_dirty());
+}
+
+#[test]
+fn test_follow_waits_for_complete_lines() {
+    let dir = TempDir::new().unwrap();
+    let (mut editor, path) = follow(&dir, "one\ntw");
+
+    append(&path, "o\nthr");
+    editor.poll_followed_file();
+    assert_eq!(editor.document.lines(), vec!["one", "two"]);
+
+    append(&path, "ee\n");
+    editor.poll_followed_file();
+    assert_eq!(editor.document.lines(), vec!["one", "two", "three"]);
+}
+
+#[test]
+fn test_follow_pauses_when_cursor_leaves_the_end() {
+    let dir = TempDir::new().unwrap();
+    let (mut editor, path) = follow(&dir, "one\ntwo\n");
+    editor.execute_action(Action::MoveUp).unwrap();
+
+    append(&path, "three\n");
+    editor.poll_followed_file();
+    assert_eq!(editor.cursor_pos(), (0, 0));
+    assert_eq!(
+        editor.status_message,
+        "1 new line below. Go to the end of the file to follow again."
+    );
+
+    editor.execute_action(Action::GoToEndOfFile).unwrap();
+    append(&path, "four\n");
+    editor.poll_followed_file();
+    assert_eq!(editor.cursor_pos(), (0, 3));
+}
+
+#[test]
+fn test_follow_reloads_truncated_file() {
+    let dir = TempDir::new().unwrap();
+    let (mut editor, path) = follow(&dir, "old one\nold two\n");
+
+    fs::write(&path, "new\n").unwrap();
+    editor.poll_followed_file();
+    assert_eq!(editor.document.lines(), vec!["new"]);
+    assert!(editor.status_message.ends_with("was truncated; reloaded."));
+}
+
+#[test]
+fn test_followed_buffer_is_read_only() {
+    let dir = TempDir::new().unwrap();
+    let (mut editor, path) = follow(&dir, "one\n");
+
+    editor.execute_action(Action::InsertChar('x')).unwrap();
+    assert_eq!(editor.document.lines(), vec!["one"]);
+    assert_eq!(editor.status_message, "Buffer is read-only.");
+
+    // Lines written while following must not be overwritten on quit
+    append(&path, "two\n");
+    editor.execute_action(Action::Quit).unwrap();
+    assert!(editor.should_quit);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "one\ntwo\n");
+}
@@ -0,0 +1,78 @@
+use crate::editor_with_lines;
+use dmacs::document::LineBuffer;
+use dmacs::editor::actions::Action;
+use dmacs::editor::footnote::{definition, next_number, references};
+
+#[test]
+fn test_references_and_definitions() {
+    assert_eq!(
+        references("a[^1] b[^note] [^] [^two words] [x]"),
+        vec![(1, 5, "1"), (7, 14, "note")]
+    );
+    assert!(references("[^1]: defined").is_empty());
+    assert_eq!(definition("[^1]: The text "), Some(("1", "The text")));
+    assert_eq!(definition("[^1] not a definition"), None);
+
+    let lines: LineBuffer = ["a[^2]", "[^note]: x", "[^5]: y"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    assert_eq!(next_number(&lines), 6);
+    assert_eq!(next_number(&LineBuffer::new()), 1);
+}
+
+#[test]
+fn test_follow_footnote_both_ways() {
+    let mut editor = editor_with_lines(&["Text[^a] here", "", "[^a]: Note"]);
+    editor.set_cursor_pos(5, 0);
+    editor.execute_action(Action::FollowFootnote).unwrap();
+    assert_eq!(editor.cursor_pos(), (6, 2));
+
+    editor.execute_action(Action::FollowFootnote).unwrap();
+    assert_eq!(editor.cursor_pos(), (4, 0));
+
+    editor.set_cursor_pos(0, 0);
+    editor.execute_action(Action::FollowFootnote).unwrap();
+    assert_eq!(editor.cursor_pos(), (0, 0));
+    assert_eq!(editor.status_message, "No footnote at the cursor.");
+}
+
+#[test]
+fn test_footnote_at_cursor() {
+    let mut editor = editor_with_lines(&["One[^1] two[^2]", "[^1]: First"]);
+    editor.set_cursor_pos(4, 0);
+    assert_eq!(editor.footnote_at_cursor().as_deref(), Some("[^1]: First"));
+    // Not defined
+    editor.set_cursor_pos(12, 0);
+    assert_eq!(editor.footnote_at_cursor(), None);
+}
+
+#[test]
+fn test_insert_footnote_numbers_and_defines() {
+    let mut editor = editor_with_lines(&["Claim[^1] and more", "", "[^1]: Source"]);
+    editor.set_cursor_pos(18, 0);
+    editor.execute_action(Action::InsertFootnote).unwrap();
+    assert_eq!(
+        editor.document.lines(),
+        vec!["Claim[^1] and more[^2]", "", "[^1]: Source", "[^2]: "]
+    );
+    assert_eq!(editor.cursor_pos(), (6, 3));
+
+    // Back to just after the reference
+    editor.execute_action(Action::JumpBack).unwrap();
+    assert_eq!(editor.cursor_pos(), (22, 0));
+
+    editor.undo();
+    assert_eq!(
+        editor.document.lines(),
+        vec!["Claim[^1] and more", "", "[^1]: Source"]
+    );
+}
+
+#[test]
+fn test_insert_first_footnote_separates_definitions() {
+    let mut editor = editor_with_lines(&["Text"]);
+    editor.set_cursor_pos(4, 0);
+    editor.execute_action(Action::InsertFootnote).unwrap();
+    assert_eq!(editor.document.lines(), vec!["Text[^1]", "", "[^1]: "]);
+}
@@ -4,7 +4,9 @@ use pancurses::Input;
 #[test]
 fn test_enter_and_exit_fuzzy_search_mode() {
     let mut editor = Editor::new(None, None, None);
-    editor.document.lines = vec!["line one".to_string(), "line two".to_string()];
+    editor
+        .document
+        .set_lines(vec!["line one".to_string(), "line two".to_string()]);
 
     // Enter fuzzy search mode
     editor
@@ -22,11 +24,11 @@ fn test_enter_and_exit_fuzzy_search_mode() {
 #[test]
 fn test_fuzzy_search_and_select() {
     let mut editor = Editor::new(None, None, None);
-    editor.document.lines = vec![
+    editor.document.set_lines(vec![
         "apple".to_string(),
         "banana".to_string(),
         "apricot".to_string(),
-    ];
+    ]);
 
     // Enter fuzzy search mode
     editor
@@ -52,14 +54,18 @@ fn test_fuzzy_search_and_select() {
         .unwrap();
 
     // Check cursor position
-    assert_eq!(editor.cursor_y, 2); // apricot is on line 2
+    assert_eq!(editor.cursor.y, 2); // apricot is on line 2
     assert_eq!(editor.mode, EditorMode::Normal);
 }
 
 #[test]
 fn test_fuzzy_search_navigation() {
     let mut editor = Editor::new(None, None, None);
-    editor.document.lines = vec!["one".to_string(), "two".to_string(), "three".to_string()];
+    editor.document.set_lines(vec![
+        "one".to_string(),
+        "two".to_string(),
+        "three".to_string(),
+    ]);
 
     // Enter fuzzy search mode
     editor
@@ -85,7 +91,9 @@ fn test_fuzzy_search_navigation() {
 #[test]
 fn test_fuzzy_search_no_match() {
     let mut editor = Editor::new(None, None, None);
-    editor.document.lines = vec!["abc".to_string(), "def".to_string()];
+    editor
+        .document
+        .set_lines(vec!["abc".to_string(), "def".to_string()]);
 
     // Enter fuzzy search mode
     editor
@@ -105,18 +113,18 @@ fn test_fuzzy_search_no_match() {
         .unwrap();
 
     // Cursor should not have moved
-    assert_eq!(editor.cursor_y, 0);
+    assert_eq!(editor.cursor.y, 0);
     assert_eq!(editor.mode, EditorMode::Normal);
 }
 
 #[test]
 fn test_fuzzy_search_backspace() {
     let mut editor = Editor::new(None, None, None);
-    editor.document.lines = vec![
+    editor.document.set_lines(vec![
         "apple".to_string(),
         "banana".to_string(),
         "apricot".to_string(),
-    ];
+    ]);
 
     // Enter fuzzy search mode
     editor
@@ -138,11 +146,11 @@ fn test_fuzzy_search_backspace() {
 #[test]
 fn test_fuzzy_search_reset() {
     let mut editor = Editor::new(None, None, None);
-    editor.document.lines = vec![
+    editor.document.set_lines(vec![
         "apple".to_string(),
         "banana".to_string(),
         "apricot".to_string(),
-    ];
+    ]);
 
     // Enter fuzzy search mode
     editor
@@ -0,0 +1,112 @@
+use dmacs::editor::actions::Action;
+use dmacs::editor::minibuffer::PromptKind;
+use dmacs::editor::{Editor, EditorMode};
+use pancurses::Input;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+fn git(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "git {args:?} failed");
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn editor_in_repo() -> (TempDir, Editor) {
+    let dir = TempDir::new().unwrap();
+    git(dir.path(), &["init", "-q"]);
+    git(dir.path(), &["config", "user.name", "dmacs"]);
+    git(dir.path(), &["config", "user.email", "dmacs@example.com"]);
+    let path = dir.path().join("notes.md");
+    fs::write(&path, "- [ ] water plants\n").unwrap();
+    let editor = Editor::new(Some(path.to_str().unwrap().to_string()), None, None);
+    (dir, editor)
+}
+
+fn type_text(editor: &mut Editor, text: &str) {
+    for c in text.chars() {
+        editor.process_input(Input::Character(c), false).unwrap();
+    }
+}
+
+#[test]
+fn test_commit_file_with_default_message() {
+    let (dir, mut editor) = editor_in_repo();
+    editor.settings.datetime_format = Some("%Y".to_string());
+
+    editor.execute_action(Action::GitCommitFile).unwrap();
+    assert_eq!(editor.mode, EditorMode::Minibuffer);
+    assert_eq!(editor.minibuffer.prompt, Some(PromptKind::GitCommit));
+    let year = chrono::Local::now().format("%Y").to_string();
+    assert_eq!(editor.minibuffer.input, format!("Update notes.md {year}"));
+
+    type_text(&mut editor, "\n");
+    assert!(editor.status_message.starts_with("Committed: ["));
+    assert_eq!(
+        git(dir.path(), &["log", "--format=%s"]).trim(),
+        format!("Update notes.md {year}")
+    );
+    assert_eq!(git(dir.path(), &["status", "--porcelain"]), "");
+}
+
+#[test]
+fn test_commit_only_the_current_file() {
+    let (dir, mut editor) = editor_in_repo();
+    fs::write(dir.path().join("other.md"), "other\n").unwrap();
+    git(dir.path(), &["add", "other.md"]);
+
+    editor.execute_action(Action::GitCommitFile).unwrap();
+    editor
+        .process_input(Input::Character('\x01'), false)
+        .unwrap();
+    editor
+        .process_input(Input::Character('\x0b'), false)
+        .unwrap();
+    type_text(&mut editor, "Plants\n");
+
+    assert_eq!(git(dir.path(), &["log", "--format=%s"]).trim(), "Plants");
+    assert_eq!(
+        git(dir.path(), &["show", "--name-only", "--format="]).trim(),
+        "notes.md"
+    );
+    assert_eq!(git(dir.path(), &["status", "--porcelain"]), "A  other.md\n");
+}
+
+#[test]
+fn test_nothing_to_commit_is_reported() {
+    let (_dir, mut editor) = editor_in_repo();
+    editor.execute_action(Action::GitCommitFile).unwrap();
+    type_text(&mut editor, "\n");
+
+    editor.execute_action(Action::GitCommitFile).unwrap();
+    type_text(&mut editor, "\n");
+    assert!(
+        editor
+            .status_message
+            .starts_with("Commit failed: git: nothing to commit"),
+        "{}",
+        editor.status_message
+    );
+}
+
+#[test]
+fn test_unsaved_or_untracked_buffer_is_not_committed() {
+    let (_dir, mut editor) = editor_in_repo();
+    editor.execute_action(Action::InsertChar('x')).unwrap();
+    editor.execute_action(Action::GitCommitFile).unwrap();
+    assert_eq!(editor.status_message, "Save the file before committing it.");
+    assert_eq!(editor.mode, EditorMode::Normal);
+
+    let outside = TempDir::new().unwrap();
+    let path = outside.path().join("notes.md");
+    fs::write(&path, "notes\n").unwrap();
+    let mut editor = Editor::new(Some(path.to_str().unwrap().to_string()), None, None);
+    editor.execute_action(Action::GitCommitFile).unwrap();
+    assert_eq!(editor.status_message, "Not in a git repository.");
+}
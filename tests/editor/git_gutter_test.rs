@@ -0,0 +1,202 @@
+use dmacs::editor::Editor;
+use dmacs::editor::actions::Action;
+use dmacs::editor::git_gutter::HunkMark;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::thread;
+use std::time::Duration;
+use tempfile::TempDir;
+
+fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args([
+            "-c",
+            "user.name=dmacs",
+            "-c",
+            "user.email=dmacs@example.com",
+        ])
+        .args(args)
+        .output()
+        .unwrap()
+        .status;
+    assert!(status.success(), "git {args:?} failed");
+}
+
+/// An editor on `notes.md`, committed with `committed` in a new repository.
+fn editor_on_committed_file(committed: &str) -> (TempDir, Editor) {
+    let dir = TempDir::new().unwrap();
+    git(dir.path(), &["init", "-q"]);
+    let path = dir.path().join("notes.md");
+    fs::write(&path, committed).unwrap();
+    git(dir.path(), &["add", "notes.md"]);
+    git(dir.path(), &["commit", "-q", "-m", "notes"]);
+    let mut editor = Editor::new(Some(path.to_str().unwrap().to_string()), None, None);
+    editor.settings.git_gutter = true;
+    (dir, editor)
+}
+
+/// Waits for the file at HEAD to be read in the background.
+fn read_committed_file(editor: &mut Editor) {
+    editor.update_git_gutter();
+    for _ in 0..500 {
+        if !editor.git_gutter.is_reading() {
+            break;
+        }
+        thread::sleep(Duration::from_millis(10));
+        editor.update_git_gutter();
+    }
+}
+
+fn set_lines(editor: &mut Editor, lines: &[&str]) {
+    editor
+        .document
+        .set_lines(lines.iter().map(|line| line.to_string()).collect());
+    editor.update_git_gutter();
+}
+
+fn marks(editor: &Editor) -> Vec<Option<HunkMark>> {
+    (0..editor.document.lines().len())
+        .map(|y| editor.hunk_mark(y))
+        .collect()
+}
+
+#[test]
+fn test_marks_added_changed_and_deleted_lines() {
+    let (_dir, mut editor) = editor_on_committed_file("one\ntwo\nthree\nfour\n");
+    read_committed_file(&mut editor);
+    assert_eq!(editor.gutter_width(), 1);
+    assert_eq!(marks(&editor), vec![None; 4]);
+
+    set_lines(&mut editor, &["one", "new", "TWO", "four"]);
+    assert_eq!(
+        marks(&editor),
+        vec![None, Some(HunkMark::Changed), Some(HunkMark::Changed), None]
+    );
+
+    set_lines(&mut editor, &["zero", "one", "three", "four"]);
+    assert_eq!(
+        marks(&editor),
+        vec![Some(HunkMark::Added), None, Some(HunkMark::Deleted), None]
+    );
+}
+
+#[test]
+fn test_no_gutter_for_uncommitted_file() {
+    let dir = TempDir::new().unwrap();
+    git(dir.path(), &["init", "-q"]);
+    let path = dir.path().join("draft.md");
+    fs::write(&path, "draft\n").unwrap();
+    let mut editor = Editor::new(Some(path.to_str().unwrap().to_string()), None, None);
+    editor.settings.git_gutter = true;
+
+    read_committed_file(&mut editor);
+    assert_eq!(editor.gutter_width(), 0);
+
+    editor.execute_action(Action::NextHunk).unwrap();
+    assert_eq!(
+        editor.status_message,
+        "Not a committed file in a git repository."
+    );
+}
+
+#[test]
+fn test_next_and_prev_hunk_wrap_around() {
+    let (_dir, mut editor) = editor_on_committed_file("a\nb\nc\nd\ne\n");
+    read_committed_file(&mut editor);
+    set_lines(&mut editor, &["A", "b", "c", "D", "e"]);
+    editor.set_cursor_pos(0, 0);
+
+    editor.execute_action(Action::NextHunk).unwrap();
+    assert_eq!(editor.cursor_pos(), (0, 3));
+    assert_eq!(editor.status_message, "Hunk 2/2");
+
+    editor.execute_action(Action::NextHunk).unwrap();
+    assert_eq!(editor.cursor_pos(), (0, 0));
+
+    editor.execute_action(Action::PrevHunk).unwrap();
+    assert_eq!(editor.cursor_pos(), (0, 3));
+}
+
+#[test]
+fn test_revert_hunk_restores_committed_lines_in_one_undo_step() {
+    let (_dir, mut editor) = editor_on_committed_file("a\nb\nc\nd\n");
+    read_committed_file(&mut editor);
+    editor.set_cursor_pos(0, 1);
+    editor.execute_action(Action::DeleteForwardChar).unwrap();
+    editor.execute_action(Action::InsertChar('x')).unwrap();
+    assert_eq!(editor.document.lines(), vec!["a", "x", "c", "d"]);
+
+    editor.execute_action(Action::RevertHunk).unwrap();
+    assert_eq!(editor.document.lines(), vec!["a", "b", "c", "d"]);
+    assert_eq!(editor.status_message, "Hunk reverted.");
+    assert_eq!(marks(&editor), vec![None; 4]);
+
+    editor.execute_action(Action::Undo).unwrap();
+    assert_eq!(editor.document.lines(), vec!["a", "x", "c", "d"]);
+}
+
+#[test]
+fn test_revert_removed_lines() {
+    let (_dir, mut editor) = editor_on_committed_file("a\nb\nc\n");
+    read_committed_file(&mut editor);
+    editor.set_cursor_pos(0, 1);
+    editor.execute_action(Action::KillLine).unwrap();
+    editor.execute_action(Action::KillLine).unwrap();
+    assert_eq!(editor.document.lines(), vec!["a", "c"]);
+    editor.update_git_gutter();
+    assert_eq!(editor.hunk_mark(1), Some(HunkMark::Deleted));
+
+    editor.execute_action(Action::RevertHunk).unwrap();
+    assert_eq!(editor.document.lines(), vec!["a", "b", "c"]);
+}
+
+#[test]
+fn test_edits_update_only_the_hunks_they_touch() {
+    let (_dir, mut editor) = editor_on_committed_file("a\nb\nc\nd\ne\n");
+    read_committed_file(&mut editor);
+    editor.set_cursor_pos(0, 3);
+    editor.execute_action(Action::InsertChar('x')).unwrap();
+    editor.update_git_gutter();
+    editor.set_cursor_pos(0, 0);
+    editor.insert_newline().unwrap();
+    editor.update_git_gutter();
+    assert_eq!(
+        marks(&editor),
+        vec![
+            Some(HunkMark::Added),
+            None,
+            None,
+            None,
+            Some(HunkMark::Changed),
+            None
+        ]
+    );
+
+    // Typing the committed text back clears the mark
+    editor.set_cursor_pos(0, 4);
+    editor.execute_action(Action::DeleteForwardChar).unwrap();
+    editor.update_git_gutter();
+    assert_eq!(
+        marks(&editor),
+        vec![Some(HunkMark::Added), None, None, None, None, None]
+    );
+}
+
+#[test]
+fn test_gutter_tick_reads_the_committed_file_in_the_background() {
+    let (_dir, mut editor) = editor_on_committed_file("a\nb\n");
+    editor.set_cursor_pos(0, 1);
+    editor.execute_action(Action::InsertChar('x')).unwrap();
+    editor.git_gutter_tick();
+    for _ in 0..500 {
+        if editor.git_gutter.is_tracked() {
+            break;
+        }
+        thread::sleep(Duration::from_millis(10));
+        editor.git_gutter_tick();
+    }
+    assert_eq!(marks(&editor), vec![None, Some(HunkMark::Changed)]);
+}
@@ -0,0 +1,93 @@
+use crate::editor_with_lines;
+use dmacs::editor::Editor;
+use dmacs::editor::actions::Action;
+use dmacs::editor::heading::{heading_level, section_headings};
+
+fn outline() -> Editor {
+    editor_with_lines(&[
+        "## Project",
+        "notes",
+        "### Design",
+        "#### Details",
+        "## Next",
+        "### Other",
+    ])
+}
+
+#[test]
+fn test_promote_and_demote_single_heading() {
+    let mut editor = outline();
+    editor.set_cursor_pos(5, 0);
+
+    editor.execute_action(Action::DemoteHeading).unwrap();
+    assert_eq!(editor.document.lines()[0], "### Project");
+    assert_eq!(editor.document.lines()[2], "### Design");
+    assert_eq!(editor.cursor_pos(), (6, 0));
+
+    editor.execute_action(Action::PromoteHeading).unwrap();
+    editor.execute_action(Action::PromoteHeading).unwrap();
+    assert_eq!(editor.document.lines()[0], "# Project");
+    assert_eq!(editor.cursor_pos(), (4, 0));
+
+    editor.execute_action(Action::PromoteHeading).unwrap();
+    assert_eq!(editor.document.lines()[0], "# Project");
+    assert_eq!(editor.status_message, "Already a top-level heading.");
+}
+
+#[test]
+fn test_demote_subtree_is_one_undo_step() {
+    let mut editor = outline();
+    editor.execute_action(Action::DemoteSubtree).unwrap();
+    assert_eq!(
+        editor.document.lines(),
+        vec![
+            "### Project",
+            "notes",
+            "#### Design",
+            "##### Details",
+            "## Next",
+            "### Other",
+        ]
+    );
+    assert_eq!(editor.status_message, "Demoted 3 headings.");
+
+    editor.undo();
+    assert_eq!(editor.document.lines(), outline().document.lines());
+}
+
+#[test]
+fn test_promote_subtree() {
+    let mut editor = outline();
+    editor.set_cursor_pos(0, 2);
+    editor.execute_action(Action::PromoteSubtree).unwrap();
+    assert_eq!(editor.document.lines()[2], "## Design");
+    assert_eq!(editor.document.lines()[3], "### Details");
+    assert_eq!(editor.document.lines()[5], "### Other");
+}
+
+#[test]
+fn test_refuses_outside_heading_or_beyond_level_six() {
+    let mut editor = editor_with_lines(&["notes", "##### Deep", "###### Deepest"]);
+    editor.execute_action(Action::DemoteHeading).unwrap();
+    assert_eq!(editor.document.lines()[0], "notes");
+    assert_eq!(editor.status_message, "Not on a heading.");
+
+    editor.set_cursor_pos(0, 1);
+    editor.execute_action(Action::DemoteSubtree).unwrap();
+    assert_eq!(editor.document.lines()[1], "##### Deep");
+    assert_eq!(editor.status_message, "Headings go down to level 6.");
+}
+
+#[test]
+fn test_heading_level_and_sections() {
+    assert_eq!(heading_level("### Title"), Some(3));
+    assert_eq!(heading_level("#"), Some(1));
+    assert_eq!(heading_level("#tag"), None);
+    assert_eq!(heading_level("####### Too deep"), None);
+
+    let editor = outline();
+    let lines = editor.document.lines();
+    assert_eq!(section_headings(lines, 0), vec![0, 2, 3]);
+    assert_eq!(section_headings(lines, 4), vec![4, 5]);
+    assert!(section_headings(lines, 1).is_empty());
+}
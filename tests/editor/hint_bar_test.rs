@@ -0,0 +1,52 @@
+use dmacs::editor::Editor;
+use dmacs::editor::actions::Action;
+use pancurses::Input;
+
+#[test]
+fn test_normal_mode_hints_follow_keymap() {
+    let mut editor = Editor::new(None, None, None);
+    let hints = editor.hints();
+    assert_eq!(hints[0], "M-s save");
+    assert!(hints.contains(&"C-x exit".to_string()));
+    assert!(hints.contains(&"C-SPC mark".to_string()));
+
+    editor.keymap.bindings.remove("ctrl-x");
+    editor
+        .keymap
+        .bindings
+        .insert("ctrl-q".to_string(), Action::Quit);
+    assert!(editor.hints().contains(&"C-q exit".to_string()));
+}
+
+#[test]
+fn test_hints_for_unbound_actions_are_left_out() {
+    let mut editor = Editor::new(None, None, None);
+    editor
+        .keymap
+        .bindings
+        .retain(|_, action| *action != Action::Undo);
+    assert!(!editor.hints().iter().any(|hint| hint.ends_with(" undo")));
+}
+
+#[test]
+fn test_selection_and_task_hints() {
+    let mut editor = Editor::new(None, None, None);
+    editor
+        .document
+        .set_lines(vec!["- [ ] task".to_string(), String::new()]);
+    editor.execute_action(Action::SetMarker).unwrap();
+    assert_eq!(
+        editor.hints(),
+        vec!["C-w cut", "M-w copy", "M-r rectangle", "C-g cancel"]
+    );
+    editor.execute_action(Action::ClearMarker).unwrap();
+
+    editor.set_cursor_pos(0, 1);
+    for c in "/task\n".chars() {
+        editor.process_input(Input::Character(c), false).unwrap();
+    }
+    assert_eq!(
+        editor.hints(),
+        vec!["↑↓ select", "SPACE move", "# comment", "ESC exit"]
+    );
+}
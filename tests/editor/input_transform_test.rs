@@ -0,0 +1,60 @@
+use dmacs::editor::Editor;
+use dmacs::editor::actions::Action;
+use dmacs::editor::input_transform::InputTransform;
+use std::fs;
+use tempfile::TempDir;
+
+fn type_text(editor: &mut Editor, text: &str) {
+    for c in text.chars() {
+        editor.execute_action(Action::InsertChar(c)).unwrap();
+    }
+}
+
+#[test]
+fn test_transforms() {
+    let alphanumerics = InputTransform::Alphanumerics;
+    assert_eq!(alphanumerics.apply('Ａ'), 'A');
+    assert_eq!(alphanumerics.apply('ｚ'), 'z');
+    assert_eq!(alphanumerics.apply('９'), '9');
+    assert_eq!(alphanumerics.apply('！'), '！');
+    assert_eq!(alphanumerics.apply('あ'), 'あ');
+    assert_eq!(InputTransform::Punctuation.apply('，'), '、');
+    assert_eq!(InputTransform::Punctuation.apply('．'), '。');
+    assert_eq!(InputTransform::Punctuation.apply('１'), '１');
+}
+
+#[test]
+fn test_configured_transform_applies_to_typing() {
+    let mut editor = Editor::new(None, None, None);
+    editor.settings.input_transforms = Some(vec!["alphanumerics".to_string()]);
+    type_text(&mut editor, "第２章，ＡＢＣ");
+    assert_eq!(editor.document.lines()[0], "第2章，ABC");
+
+    // Pasted text is left as it is
+    editor
+        .execute_action(Action::Paste("１".to_string()))
+        .unwrap();
+    assert_eq!(editor.document.lines()[0], "第2章，ABC１");
+}
+
+#[test]
+fn test_toggle_per_buffer() {
+    let state_dir = TempDir::new().unwrap();
+    let path = state_dir.path().join("notes.md");
+    fs::write(&path, "\n").unwrap();
+    let mut editor = Editor::new(Some(path.to_str().unwrap().to_string()), None, None);
+    editor.enable_scratch_autosave_with_base_dir(Some(state_dir.path().to_path_buf()));
+    type_text(&mut editor, "１");
+    assert_eq!(editor.document.lines()[0], "１");
+
+    // Without a setting, toggling on applies every transform
+    editor.execute_action(Action::ToggleInputTransform).unwrap();
+    assert_eq!(editor.status_message, "Input transform on.");
+    type_text(&mut editor, "２．");
+    assert_eq!(editor.document.lines()[0], "１2。");
+
+    // Another buffer starts from the setting again
+    editor.execute_action(Action::ToggleScratchBuffer).unwrap();
+    assert!(editor.is_scratch_buffer());
+    assert!(!editor.is_input_transform_on());
+}
@@ -0,0 +1,87 @@
+use crate::editor_with_lines;
+use dmacs::editor::actions::Action;
+use pancurses::Input;
+
+#[test]
+fn test_jump_back_and_forward() {
+    let mut editor = editor_with_lines(&["one", "two", "three"]);
+    editor.set_cursor_pos(1, 1);
+    editor.execute_action(Action::GoToEndOfFile).unwrap();
+    assert_eq!(editor.cursor_pos(), (5, 2));
+
+    // Ctrl-O
+    editor
+        .process_input(Input::Character('\x0f'), false)
+        .unwrap();
+    assert_eq!(editor.cursor_pos(), (1, 1));
+
+    editor.execute_action(Action::JumpForward).unwrap();
+    assert_eq!(editor.cursor_pos(), (5, 2));
+    editor.execute_action(Action::JumpForward).unwrap();
+    assert_eq!(editor.status_message, "No later position in the jump list.");
+}
+
+#[test]
+fn test_search_records_jump() {
+    let mut editor = editor_with_lines(&["one", "two", "three apple"]);
+    // Ctrl-S, the query, then Enter
+    editor
+        .process_input(Input::Character('\x13'), false)
+        .unwrap();
+    for c in "apple".chars() {
+        editor.process_input(Input::Character(c), false).unwrap();
+    }
+    editor.process_input(Input::Character('\n'), false).unwrap();
+    assert_eq!(editor.cursor_pos().1, 2);
+
+    editor.execute_action(Action::JumpBack).unwrap();
+    assert_eq!(editor.cursor_pos(), (0, 0));
+    editor.execute_action(Action::JumpBack).unwrap();
+    assert_eq!(
+        editor.status_message,
+        "No earlier position in the jump list."
+    );
+}
+
+#[test]
+fn test_jump_list_is_capped() {
+    let mut editor = editor_with_lines(&["one", "---", "two", "---", "three"]);
+    editor.settings.jump_list_size = Some(2);
+    for _ in 0..3 {
+        editor.execute_action(Action::MoveToNextDelimiter).unwrap();
+    }
+    assert_eq!(editor.jumps.len(), 2);
+
+    editor.execute_action(Action::JumpBack).unwrap();
+    editor.execute_action(Action::JumpBack).unwrap();
+    editor.execute_action(Action::JumpBack).unwrap();
+    assert_eq!(
+        editor.status_message,
+        "No earlier position in the jump list."
+    );
+}
+
+#[test]
+fn test_new_jump_forgets_positions_ahead() {
+    let mut editor = editor_with_lines(&["one", "two", "three"]);
+    editor.set_cursor_pos(0, 1);
+    editor.execute_action(Action::GoToEndOfFile).unwrap();
+    editor.execute_action(Action::JumpBack).unwrap();
+    editor.execute_action(Action::GoToStartOfFile).unwrap();
+
+    editor.execute_action(Action::JumpForward).unwrap();
+    assert_eq!(editor.status_message, "No later position in the jump list.");
+    editor.execute_action(Action::JumpBack).unwrap();
+    assert_eq!(editor.cursor_pos(), (0, 1));
+}
+
+#[test]
+fn test_jump_after_edits_stays_in_buffer() {
+    let mut editor = editor_with_lines(&["one", "two", "three"]);
+    editor.set_cursor_pos(5, 2);
+    editor.execute_action(Action::GoToStartOfFile).unwrap();
+    editor.document.replace_lines(1..3, Vec::new());
+
+    editor.execute_action(Action::JumpBack).unwrap();
+    assert_eq!(editor.cursor_pos(), (3, 0));
+}
@@ -1,4 +1,6 @@
 use dmacs::editor::Editor;
+use dmacs::editor::EditorMode;
+use dmacs::editor::clipboard::{Clipboard, ClipboardBackend, KILL_RING_SIZE, osc52_sequence};
 use pancurses::Input;
 
 fn editor_with_clipboard_disabled() -> Editor {
@@ -10,12 +12,12 @@ fn editor_with_clipboard_disabled() -> Editor {
 #[test]
 fn test_editor_kill_line_middle_of_line() {
     let mut editor = editor_with_clipboard_disabled();
-    editor.document.lines = vec!["hello world".to_string()];
+    editor.document.set_lines(vec!["hello world".to_string()]);
     editor.set_cursor_pos(6, 0); // Cursor at 'w' in "world"
     editor
         .process_input(Input::Character('\x0b'), false)
         .unwrap();
-    assert_eq!(editor.document.lines[0], "hello ");
+    assert_eq!(editor.document.lines()[0], "hello ");
     assert_eq!(editor.clipboard.kill_buffer, "world");
     assert_eq!(editor.cursor_pos(), (6, 0));
 }
@@ -23,13 +25,15 @@ fn test_editor_kill_line_middle_of_line() {
 #[test]
 fn test_editor_kill_line_end_of_line_not_last_line() {
     let mut editor = editor_with_clipboard_disabled();
-    editor.document.lines = vec!["hello".to_string(), "world".to_string()];
+    editor
+        .document
+        .set_lines(vec!["hello".to_string(), "world".to_string()]);
     editor.set_cursor_pos(5, 0); // Cursor at end of "hello"
     editor
         .process_input(Input::Character('\x0b'), false)
         .unwrap();
-    assert_eq!(editor.document.lines.len(), 1);
-    assert_eq!(editor.document.lines[0], "helloworld");
+    assert_eq!(editor.document.lines().len(), 1);
+    assert_eq!(editor.document.lines()[0], "helloworld");
     assert_eq!(editor.clipboard.kill_buffer, "\n"); // Newline
     assert_eq!(editor.cursor_pos(), (5, 0));
 }
@@ -37,14 +41,18 @@ fn test_editor_kill_line_end_of_line_not_last_line() {
 #[test]
 fn test_editor_kill_line_empty_line_not_last_line() {
     let mut editor = editor_with_clipboard_disabled();
-    editor.document.lines = vec!["line1".to_string(), "".to_string(), "line3".to_string()];
+    editor.document.set_lines(vec![
+        "line1".to_string(),
+        "".to_string(),
+        "line3".to_string(),
+    ]);
     editor.set_cursor_pos(0, 1); // Cursor at beginning of empty line
     editor
         .process_input(Input::Character('\x0b'), false)
         .unwrap();
-    assert_eq!(editor.document.lines.len(), 2);
-    assert_eq!(editor.document.lines[0], "line1");
-    assert_eq!(editor.document.lines[1], "line3");
+    assert_eq!(editor.document.lines().len(), 2);
+    assert_eq!(editor.document.lines()[0], "line1");
+    assert_eq!(editor.document.lines()[1], "line3");
     assert_eq!(editor.clipboard.kill_buffer, "\n"); // Only newline killed
     assert_eq!(editor.cursor_pos(), (0, 1));
 }
@@ -52,12 +60,12 @@ fn test_editor_kill_line_empty_line_not_last_line() {
 #[test]
 fn test_editor_kill_line_last_line() {
     let mut editor = editor_with_clipboard_disabled();
-    editor.document.lines = vec!["last line".to_string()];
+    editor.document.set_lines(vec!["last line".to_string()]);
     editor.set_cursor_pos(0, 0);
     editor
         .process_input(Input::Character('\x0b'), false)
         .unwrap();
-    assert_eq!(editor.document.lines[0], "");
+    assert_eq!(editor.document.lines()[0], "");
     assert_eq!(editor.clipboard.kill_buffer, "last line");
     assert_eq!(editor.cursor_pos(), (0, 0));
 }
@@ -66,12 +74,14 @@ fn test_editor_kill_line_last_line() {
 fn test_editor_yank_single_line() {
     let mut editor = editor_with_clipboard_disabled();
     editor.clipboard.kill_buffer = "yanked text".to_string();
-    editor.document.lines = vec!["start ".to_string(), "end".to_string()];
+    editor
+        .document
+        .set_lines(vec!["start ".to_string(), "end".to_string()]);
     editor.set_cursor_pos(6, 0); // After "start "
     editor
         .process_input(Input::Character('\x19'), false)
         .unwrap();
-    assert_eq!(editor.document.lines[0], "start yanked text");
+    assert_eq!(editor.document.lines()[0], "start yanked text");
     assert_eq!(editor.cursor_pos(), (17, 0)); // Cursor after yanked text
 }
 
@@ -79,27 +89,29 @@ fn test_editor_yank_single_line() {
 fn test_editor_yank_multiple_lines() {
     let mut editor = editor_with_clipboard_disabled();
     editor.clipboard.kill_buffer = "line1\nline2\nline3".to_string();
-    editor.document.lines = vec!["start".to_string(), "end".to_string()];
+    editor
+        .document
+        .set_lines(vec!["start".to_string(), "end".to_string()]);
     editor.set_cursor_pos(5, 0); // After "start"
     editor
         .process_input(Input::Character('\x19'), false)
         .unwrap();
-    assert_eq!(editor.document.lines.len(), 4);
-    assert_eq!(editor.document.lines[0], "startline1");
-    assert_eq!(editor.document.lines[1], "line2");
-    assert_eq!(editor.document.lines[2], "line3");
-    assert_eq!(editor.document.lines[3], "end");
+    assert_eq!(editor.document.lines().len(), 4);
+    assert_eq!(editor.document.lines()[0], "startline1");
+    assert_eq!(editor.document.lines()[1], "line2");
+    assert_eq!(editor.document.lines()[2], "line3");
+    assert_eq!(editor.document.lines()[3], "end");
     assert_eq!(editor.cursor_pos(), (5, 2)); // Cursor at end of last yanked line
 }
 
 #[test]
 fn test_editor_consecutive_kill_line() {
     let mut editor = editor_with_clipboard_disabled();
-    editor.document.lines = vec![
+    editor.document.set_lines(vec![
         "line one".to_string(),
         "line two".to_string(),
         "line three".to_string(),
-    ];
+    ]);
 
     // Kill "line one"
     editor.set_cursor_pos(0, 0);
@@ -107,16 +119,16 @@ fn test_editor_consecutive_kill_line() {
         .process_input(Input::Character('\x0b'), false)
         .unwrap(); // Ctrl-K
     assert_eq!(editor.clipboard.kill_buffer, "line one");
-    assert_eq!(editor.document.lines.len(), 3);
-    assert_eq!(editor.document.lines[0], ""); // "line one" should be removed
+    assert_eq!(editor.document.lines().len(), 3);
+    assert_eq!(editor.document.lines()[0], ""); // "line one" should be removed
 
     editor.set_cursor_pos(0, 0);
     editor
         .process_input(Input::Character('\x0b'), false)
         .unwrap(); // Ctrl-K
     assert_eq!(editor.clipboard.kill_buffer, "line one\n");
-    assert_eq!(editor.document.lines.len(), 2);
-    assert_eq!(editor.document.lines[0], "line two"); // "line one\n" should be removed
+    assert_eq!(editor.document.lines().len(), 2);
+    assert_eq!(editor.document.lines()[0], "line two"); // "line one\n" should be removed
 
     // Kill "line two" immediately after
     editor.set_cursor_pos(0, 0); // Cursor is now at the start of "line two"
@@ -124,37 +136,152 @@ fn test_editor_consecutive_kill_line() {
         .process_input(Input::Character('\x0b'), false)
         .unwrap(); // Ctrl-K
     assert_eq!(editor.clipboard.kill_buffer, "line one\nline two"); // Should append
-    assert_eq!(editor.document.lines.len(), 2);
-    assert_eq!(editor.document.lines[0], ""); // "line two" should be removed
+    assert_eq!(editor.document.lines().len(), 2);
+    assert_eq!(editor.document.lines()[0], ""); // "line two" should be removed
 
     editor.set_cursor_pos(0, 0);
     editor
         .process_input(Input::Character('\x0b'), false)
         .unwrap(); // Ctrl-K
     assert_eq!(editor.clipboard.kill_buffer, "line one\nline two\n"); // Should append
-    assert_eq!(editor.document.lines.len(), 1);
-    assert_eq!(editor.document.lines[0], "line three"); // "line two" should be removed
+    assert_eq!(editor.document.lines().len(), 1);
+    assert_eq!(editor.document.lines()[0], "line three"); // "line two" should be removed
 
     // Yank the accumulated content
     editor.set_cursor_pos(0, 0);
     editor
         .process_input(Input::Character('\x19'), false)
         .unwrap(); // Ctrl-Y
-    assert_eq!(editor.document.lines.len(), 3);
-    assert_eq!(editor.document.lines[0], "line one");
-    assert_eq!(editor.document.lines[1], "line two");
-    assert_eq!(editor.document.lines[2], "line three");
+    assert_eq!(editor.document.lines().len(), 3);
+    assert_eq!(editor.document.lines()[0], "line one");
+    assert_eq!(editor.document.lines()[1], "line two");
+    assert_eq!(editor.document.lines()[2], "line three");
 }
 
 #[test]
 fn test_editor_yank_empty_kill_buffer() {
     let mut editor = editor_with_clipboard_disabled();
     editor.clipboard.kill_buffer = "".to_string();
-    editor.document.lines = vec!["original".to_string()];
+    editor.document.set_lines(vec!["original".to_string()]);
     editor.set_cursor_pos(0, 0);
     editor
         .process_input(Input::Character('\x19'), false)
         .unwrap();
-    assert_eq!(editor.document.lines[0], "original"); // Document should be unchanged
+    assert_eq!(editor.document.lines()[0], "original"); // Document should be unchanged
     assert_eq!(editor.cursor_pos(), (0, 0));
 }
+
+#[test]
+fn test_osc52_sequence_encodes_text_as_base64() {
+    assert_eq!(osc52_sequence("hello", false), "\x1b]52;c;aGVsbG8=\x07");
+    assert_eq!(osc52_sequence("", false), "\x1b]52;c;\x07");
+    assert_eq!(osc52_sequence("日本", false), "\x1b]52;c;5pel5pys\x07");
+    assert_eq!(osc52_sequence("ab\n", false), "\x1b]52;c;YWIK\x07");
+}
+
+#[test]
+fn test_osc52_sequence_passes_through_tmux() {
+    assert_eq!(
+        osc52_sequence("hi", true),
+        "\x1bPtmux;\x1b\x1b]52;c;aGk=\x07\x1b\\"
+    );
+}
+
+#[test]
+fn test_clipboard_backend_from_setting() {
+    assert_eq!(ClipboardBackend::from_setting(None), ClipboardBackend::Auto);
+    assert_eq!(
+        ClipboardBackend::from_setting(Some("osc52")),
+        ClipboardBackend::Osc52
+    );
+    assert_eq!(
+        ClipboardBackend::from_setting(Some("system")),
+        ClipboardBackend::System
+    );
+    assert_eq!(
+        ClipboardBackend::from_setting(Some("off")),
+        ClipboardBackend::Off
+    );
+    assert_eq!(
+        ClipboardBackend::from_setting(Some("clipboard.exe")),
+        ClipboardBackend::Auto
+    );
+}
+
+#[test]
+fn test_clipboard_off_yanks_from_kill_buffer() {
+    let mut editor = Editor::new(None, None, None);
+    editor.clipboard.set_backend(ClipboardBackend::Off);
+    editor.document.set_lines(vec!["kill me".to_string()]);
+    editor.kill_line().unwrap();
+    assert_eq!(editor.clipboard.get_clipboard_text(), None);
+    editor.yank().unwrap();
+    assert_eq!(editor.document.lines(), vec!["kill me"]);
+}
+
+#[test]
+fn test_kill_ring_is_bounded_and_lists_each_kill_once() {
+    let mut clipboard = Clipboard::new();
+    for i in 0..KILL_RING_SIZE + 5 {
+        clipboard.push_kill(format!("kill {i}"));
+    }
+    assert_eq!(clipboard.kill_ring.len(), KILL_RING_SIZE);
+    assert_eq!(clipboard.kill_buffer, "kill 34");
+    assert_eq!(clipboard.kill_ring[0], "kill 33");
+
+    clipboard.push_kill("kill 20".to_string());
+    let kills: Vec<&String> = clipboard.kills().take(3).collect();
+    assert_eq!(kills, vec!["kill 20", "kill 34", "kill 33"]);
+    assert_eq!(
+        clipboard.kills().filter(|kill| *kill == "kill 20").count(),
+        1
+    );
+}
+
+#[test]
+fn test_yank_from_history() {
+    let mut editor = editor_with_clipboard_disabled();
+    editor
+        .document
+        .set_lines(vec!["one".to_string(), "two".to_string(), String::new()]);
+    editor.kill_line().unwrap();
+    editor.process_input(Input::KeyDown, false).unwrap();
+    editor.kill_line().unwrap();
+    editor.process_input(Input::KeyDown, false).unwrap();
+
+    editor.process_input(Input::Character('Y'), true).unwrap();
+    assert_eq!(editor.mode, EditorMode::KillRing);
+    assert_eq!(editor.kill_ring_browser.entries, vec!["two", "one"]);
+
+    editor.process_input(Input::KeyDown, false).unwrap();
+    editor.process_input(Input::Character('\n'), false).unwrap();
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert_eq!(editor.document.lines(), vec!["", "", "one"]);
+
+    // The picked kill is the one yanked next
+    assert_eq!(editor.clipboard.kill_buffer, "one");
+    editor
+        .process_input(Input::Character('\x19'), false)
+        .unwrap();
+    assert_eq!(editor.document.lines()[2], "oneone");
+
+    editor.undo();
+    editor.undo();
+    assert_eq!(editor.document.lines()[2], "");
+}
+
+#[test]
+fn test_yank_from_empty_history() {
+    let mut editor = editor_with_clipboard_disabled();
+    editor.process_input(Input::Character('Y'), true).unwrap();
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert_eq!(editor.status_message, "Kill ring is empty.");
+
+    editor.clipboard.kill_buffer = "text".to_string();
+    editor.process_input(Input::Character('Y'), true).unwrap();
+    editor
+        .process_input(Input::Character('\x1b'), false)
+        .unwrap();
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert_eq!(editor.document.lines(), vec![""]);
+}
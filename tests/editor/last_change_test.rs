@@ -0,0 +1,67 @@
+use dmacs::config::EditorSettings;
+use dmacs::editor::Editor;
+use pancurses::Input;
+
+fn editor_with_highlight() -> Editor {
+    let mut editor = Editor::new(None, None, None);
+    editor.set_settings(EditorSettings {
+        highlight_last_change: true,
+        ..EditorSettings::default()
+    });
+    editor
+}
+
+#[test]
+fn test_last_change_not_tracked_by_default() {
+    let mut editor = Editor::new(None, None, None);
+    editor.process_input(Input::Character('a'), false).unwrap();
+    assert_eq!(editor.last_change, None);
+}
+
+#[test]
+fn test_last_change_grows_while_typing() {
+    let mut editor = editor_with_highlight();
+    for c in "abc".chars() {
+        editor.process_input(Input::Character(c), false).unwrap();
+    }
+    assert_eq!(editor.last_change, Some(((0, 0), (3, 0))));
+}
+
+#[test]
+fn test_last_change_covers_list_continuation() {
+    let mut editor = editor_with_highlight();
+    editor.insert_text("- item").unwrap();
+    editor.process_input(Input::KeyLeft, false).unwrap();
+    editor.go_to_end_of_line();
+    editor.insert_newline().unwrap();
+    assert_eq!(editor.document.lines()[1], "- ");
+    assert_eq!(editor.last_change, Some(((6, 0), (2, 1))));
+}
+
+#[test]
+fn test_last_change_covers_arrow_expansion() {
+    let mut editor = editor_with_highlight();
+    editor.insert_text("a ").unwrap();
+    editor.process_input(Input::KeyLeft, false).unwrap();
+    editor.process_input(Input::KeyRight, false).unwrap();
+    editor.process_input(Input::Character(' '), false).unwrap();
+    assert_eq!(editor.document.lines()[0], "a -> ");
+    assert_eq!(editor.last_change, Some(((2, 0), (5, 0))));
+}
+
+#[test]
+fn test_last_change_cleared_by_cursor_movement() {
+    let mut editor = editor_with_highlight();
+    editor.process_input(Input::Character('a'), false).unwrap();
+    assert!(editor.last_change.is_some());
+    editor.process_input(Input::KeyLeft, false).unwrap();
+    assert_eq!(editor.last_change, None);
+}
+
+#[test]
+fn test_last_change_cleared_by_deletion() {
+    let mut editor = editor_with_highlight();
+    editor.process_input(Input::Character('a'), false).unwrap();
+    editor.process_input(Input::KeyBackspace, false).unwrap();
+    assert_eq!(editor.last_change, None);
+}
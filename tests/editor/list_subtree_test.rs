@@ -0,0 +1,167 @@
+use crate::editor_with_lines;
+use dmacs::document::LineBuffer;
+use dmacs::editor::list_subtree::{next_sibling, previous_sibling};
+use pancurses::Input;
+
+fn lines(lines: &[&str]) -> LineBuffer {
+    lines.iter().map(|line| line.to_string()).collect()
+}
+
+#[test]
+fn test_siblings_pass_over_nested_lines() {
+    let lines = lines(&[
+        "- a", "  - a1", "", "    more", "- b", "  - b1", "- c", "text", "- d",
+    ]);
+    assert_eq!(previous_sibling(&lines, 4), Some(0));
+    assert_eq!(next_sibling(&lines, 0), Some(4));
+    assert_eq!(next_sibling(&lines, 4), Some(6));
+    assert_eq!(previous_sibling(&lines, 5), None);
+    assert_eq!(next_sibling(&lines, 6), None);
+    assert_eq!(previous_sibling(&lines, 8), None);
+}
+
+#[test]
+fn test_move_subtree_keeps_children_together() {
+    let mut editor = editor_with_lines(&[
+        "- [ ] a",
+        "  - [ ] a1",
+        "  - [x] a2",
+        "- [ ] b",
+        "  - [ ] b1",
+        "after",
+    ]);
+    editor.set_cursor_pos(6, 3);
+    editor.move_subtree_up();
+    assert_eq!(
+        editor.document.lines(),
+        vec![
+            "- [ ] b",
+            "  - [ ] b1",
+            "- [ ] a",
+            "  - [ ] a1",
+            "  - [x] a2",
+            "after",
+        ]
+    );
+    assert_eq!(editor.cursor_pos(), (6, 0));
+
+    // From a nested line its own item moves, among its siblings only
+    editor.set_cursor_pos(8, 3);
+    editor.move_subtree_down();
+    assert_eq!(editor.document.lines()[3], "  - [x] a2");
+    assert_eq!(editor.document.lines()[4], "  - [ ] a1");
+    assert_eq!(editor.cursor_pos(), (8, 4));
+    editor.move_subtree_down();
+    assert_eq!(editor.status_message, "No list item below at this level.");
+}
+
+#[test]
+fn test_move_subtree_renumbers_and_keeps_gap() {
+    let mut editor = editor_with_lines(&["1. one", "   - detail", "", "2. two", "3. three"]);
+    editor.set_cursor_pos(3, 3);
+    editor.move_subtree_up();
+    assert_eq!(
+        editor.document.lines(),
+        vec!["1. two", "", "2. one", "   - detail", "3. three"]
+    );
+    assert_eq!(editor.cursor_pos(), (3, 0));
+    editor.move_subtree_up();
+    assert_eq!(editor.status_message, "No list item above at this level.");
+}
+
+#[test]
+fn test_promote_and_demote_subtree() {
+    let mut editor = editor_with_lines(&["- a", "- b", "  - b1", "    - b2", "- c"]);
+    editor.set_cursor_pos(3, 1);
+    editor.process_input(Input::Character('}'), true).unwrap();
+    assert_eq!(
+        editor.document.lines(),
+        vec!["- a", "  - b", "    - b1", "      - b2", "- c"]
+    );
+    assert_eq!(editor.cursor_pos(), (5, 1));
+    assert_eq!(
+        editor.status_message,
+        "Demoted list item and 2 lines under it."
+    );
+
+    // From a nested line, the whole item moves back as one undo step
+    editor.set_cursor_pos(8, 2);
+    editor.process_input(Input::Character('{'), true).unwrap();
+    assert_eq!(
+        editor.document.lines(),
+        vec!["- a", "  - b", "  - b1", "    - b2", "- c"]
+    );
+    assert_eq!(editor.cursor_pos(), (6, 2));
+    editor.undo();
+    assert_eq!(editor.document.lines()[3], "      - b2");
+
+    editor.set_cursor_pos(0, 0);
+    editor.process_input(Input::Character('{'), true).unwrap();
+    assert_eq!(editor.status_message, "Already a top-level list item.");
+}
+
+#[test]
+fn test_demote_subtree_renumbers_both_lists() {
+    let mut editor = editor_with_lines(&["1. one", "2. two", "   - note", "3. three"]);
+    editor.set_cursor_pos(0, 1);
+    editor.process_input(Input::Character('}'), true).unwrap();
+    assert_eq!(
+        editor.document.lines(),
+        vec!["1. one", "  1. two", "     - note", "2. three"]
+    );
+}
+
+#[test]
+fn test_kill_and_yank_subtree() {
+    let mut editor = editor_with_lines(&["- a", "  - [ ] a1", "    - a1x", "  - [ ] a2", "- b"]);
+    editor.set_cursor_pos(4, 1);
+    editor.kill_subtree().unwrap();
+    assert_eq!(editor.document.lines(), vec!["- a", "  - [ ] a2", "- b"]);
+    assert_eq!(editor.clipboard.kill_buffer, "  - [ ] a1\n    - a1x\n");
+    assert_eq!(
+        editor.status_message,
+        "Killed list item and the line under it."
+    );
+
+    // Yanked onto a top-level item, the subtree becomes its sibling
+    editor.set_cursor_pos(1, 2);
+    editor.yank_subtree().unwrap();
+    assert_eq!(
+        editor.document.lines(),
+        vec!["- a", "  - [ ] a2", "- [ ] a1", "  - a1x", "- b"]
+    );
+    assert_eq!(editor.cursor_pos(), (0, 2));
+
+    editor.undo();
+    editor.undo();
+    assert_eq!(
+        editor.document.lines(),
+        vec!["- a", "  - [ ] a1", "    - a1x", "  - [ ] a2", "- b"]
+    );
+}
+
+#[test]
+fn test_kill_last_subtree_of_the_buffer() {
+    let mut editor = editor_with_lines(&["1. one", "2. two", "3. three", "   - c"]);
+    editor.set_cursor_pos(0, 2);
+    editor.kill_subtree().unwrap();
+    assert_eq!(editor.document.lines(), vec!["1. one", "2. two"]);
+
+    editor.set_cursor_pos(0, 0);
+    editor.yank_subtree().unwrap();
+    assert_eq!(
+        editor.document.lines(),
+        vec!["1. three", "   - c", "2. one", "3. two"]
+    );
+}
+
+#[test]
+fn test_outside_a_list() {
+    let mut editor = editor_with_lines(&["plain text", "- item"]);
+    editor.move_subtree_down();
+    assert_eq!(editor.status_message, "Not in a list item.");
+    editor.kill_subtree().unwrap();
+    assert_eq!(editor.document.lines(), vec!["plain text", "- item"]);
+    editor.process_input(Input::Character('}'), true).unwrap();
+    assert_eq!(editor.status_message, "Not on a heading.");
+}
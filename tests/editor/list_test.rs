@@ -0,0 +1,129 @@
+use crate::editor_with_lines;
+use dmacs::editor::indent::ordered_list_number;
+use pancurses::Input;
+
+#[test]
+fn test_ordered_list_number() {
+    assert_eq!(ordered_list_number("1. one"), Some((1, 0, 1)));
+    assert_eq!(ordered_list_number("  12) twelve"), Some((12, 2, 4)));
+    assert_eq!(ordered_list_number("3. "), Some((3, 0, 1)));
+    assert_eq!(ordered_list_number("2024.10 report"), None);
+    assert_eq!(ordered_list_number("- item"), None);
+}
+
+#[test]
+fn test_newline_continues_ordered_list_and_renumbers() {
+    let mut editor = editor_with_lines(&["1. one", "2. two", "3. three"]);
+    editor.set_cursor_pos(6, 0);
+    editor.insert_newline().unwrap();
+    assert_eq!(
+        editor.document.lines(),
+        vec!["1. one", "2. ", "3. two", "4. three"]
+    );
+    assert_eq!(editor.cursor_pos(), (3, 1));
+
+    // One undo step takes back the new item and the renumbering
+    editor.undo();
+    assert_eq!(
+        editor.document.lines(),
+        vec!["1. one", "2. two", "3. three"]
+    );
+}
+
+#[test]
+fn test_newline_continues_nested_ordered_item() {
+    let mut editor = editor_with_lines(&["1. one", "  1) first", "  2) second", "2. two"]);
+    editor.set_cursor_pos(10, 1);
+    editor.insert_newline().unwrap();
+    assert_eq!(
+        editor.document.lines(),
+        vec!["1. one", "  1) first", "  2) ", "  3) second", "2. two"]
+    );
+}
+
+#[test]
+fn test_newline_on_empty_ordered_item_ends_list() {
+    let mut editor = editor_with_lines(&["1. one", "2. "]);
+    editor.set_cursor_pos(3, 1);
+    editor.insert_newline().unwrap();
+    assert_eq!(editor.document.lines(), vec!["1. one", ""]);
+    assert_eq!(editor.cursor_pos(), (0, 1));
+}
+
+#[test]
+fn test_tab_moves_list_item_to_next_level() {
+    let mut editor = editor_with_lines(&["- one", " - two"]);
+    editor.settings.indent_width = Some(4);
+    editor.set_cursor_pos(3, 1);
+    editor.process_input(Input::Character('\t'), false).unwrap();
+    assert_eq!(editor.document.lines()[1], "    - two");
+    assert_eq!(editor.cursor_pos(), (6, 1));
+
+    editor.process_input(Input::KeySTab, false).unwrap();
+    assert_eq!(editor.document.lines()[1], "- two");
+    assert_eq!(editor.cursor_pos(), (2, 1));
+}
+
+#[test]
+fn test_indent_renumbers_both_levels() {
+    let mut editor = editor_with_lines(&["1. one", "2. two", "3. three"]);
+    editor.set_cursor_pos(3, 1);
+    editor.indent_line().unwrap();
+    assert_eq!(
+        editor.document.lines(),
+        vec!["1. one", "  1. two", "2. three"]
+    );
+    assert_eq!(editor.cursor_pos(), (5, 1));
+
+    editor.outdent_line().unwrap();
+    assert_eq!(
+        editor.document.lines(),
+        vec!["1. one", "2. two", "3. three"]
+    );
+
+    editor.indent_line().unwrap();
+    editor.undo();
+    assert_eq!(
+        editor.document.lines(),
+        vec!["1. one", "2. two", "3. three"]
+    );
+}
+
+#[test]
+fn test_move_line_renumbers_ordered_list() {
+    let mut editor = editor_with_lines(&["1. one", "2. two", "  - detail", "3. three"]);
+    editor.set_cursor_pos(4, 3);
+    editor.move_line_up();
+    editor.move_line_up();
+    assert_eq!(
+        editor.document.lines(),
+        vec!["1. one", "2. three", "3. two", "  - detail"]
+    );
+    assert_eq!(editor.cursor_pos(), (4, 1));
+
+    editor.move_line_down();
+    assert_eq!(
+        editor.document.lines(),
+        vec!["1. one", "2. two", "3. three", "  - detail"]
+    );
+}
+
+#[test]
+fn test_removing_item_renumbers_ordered_list() {
+    let mut editor = editor_with_lines(&["1. one", "2. two", "3. three", "4. four"]);
+    // Kill the text of the second item, then the line itself
+    editor.set_cursor_pos(0, 1);
+    editor.kill_line().unwrap();
+    editor.kill_line().unwrap();
+    assert_eq!(
+        editor.document.lines(),
+        vec!["1. one", "2. three", "3. four"]
+    );
+
+    // Cut the first item
+    editor.set_cursor_pos(0, 0);
+    editor.set_marker_action();
+    editor.set_cursor_pos(0, 1);
+    editor.cut_selection_action().unwrap();
+    assert_eq!(editor.document.lines(), vec!["1. three", "2. four"]);
+}
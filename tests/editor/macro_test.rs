@@ -0,0 +1,67 @@
+use crate::editor_with_lines;
+use dmacs::editor::actions::Action;
+use pancurses::Input;
+
+#[test]
+fn test_record_and_play_macro() {
+    let mut editor = editor_with_lines(&["a", "b", "c"]);
+    editor.execute_action(Action::StartMacroRecording).unwrap();
+    assert!(editor.macros.is_recording());
+    editor.process_input(Input::Character('-'), false).unwrap();
+    editor.process_input(Input::Character(' '), false).unwrap();
+    editor.execute_action(Action::GoToStartOfLine).unwrap();
+    editor.execute_action(Action::MoveDown).unwrap();
+    editor.execute_action(Action::StopMacroRecording).unwrap();
+    assert!(!editor.macros.is_recording());
+    assert_eq!(editor.macros.last_macro.len(), 4);
+
+    editor.execute_action(Action::PlayMacro(2)).unwrap();
+    assert_eq!(editor.document.lines(), vec!["- a", "- b", "- c"]);
+    assert_eq!(editor.status_message, "Macro played 2 times.");
+}
+
+#[test]
+fn test_macro_playback_is_one_undo_step() {
+    let mut editor = editor_with_lines(&["x"]);
+    editor.undo_redo.set_undo_debounce_threshold(0);
+    editor.execute_action(Action::StartMacroRecording).unwrap();
+    editor.process_input(Input::Character('a'), false).unwrap();
+    editor.execute_action(Action::InsertNewline).unwrap();
+    editor.execute_action(Action::StopMacroRecording).unwrap();
+
+    editor.execute_action(Action::PlayMacro(3)).unwrap();
+    assert_eq!(editor.document.lines(), vec!["a", "a", "a", "a", "x"]);
+
+    editor.undo();
+    assert_eq!(editor.document.lines(), vec!["a", "x"]);
+    editor.redo();
+    assert_eq!(editor.document.lines(), vec!["a", "a", "a", "a", "x"]);
+}
+
+#[test]
+fn test_play_without_macro() {
+    let mut editor = editor_with_lines(&["x"]);
+    editor.execute_action(Action::PlayMacro(1)).unwrap();
+    assert_eq!(editor.status_message, "No macro recorded.");
+    assert_eq!(editor.document.lines(), vec!["x"]);
+}
+
+#[test]
+fn test_playing_while_recording_records_expansion() {
+    let mut editor = editor_with_lines(&[""]);
+    editor.execute_action(Action::StartMacroRecording).unwrap();
+    editor.process_input(Input::Character('a'), false).unwrap();
+    editor.execute_action(Action::StopMacroRecording).unwrap();
+
+    editor.execute_action(Action::StartMacroRecording).unwrap();
+    editor.execute_action(Action::PlayMacro(1)).unwrap();
+    editor.process_input(Input::Character('b'), false).unwrap();
+    editor.execute_action(Action::StopMacroRecording).unwrap();
+    assert_eq!(
+        editor.macros.last_macro,
+        vec![Action::InsertChar('a'), Action::InsertChar('b')]
+    );
+
+    editor.execute_action(Action::PlayMacro(1)).unwrap();
+    assert_eq!(editor.document.lines(), vec!["aabab"]);
+}
@@ -0,0 +1,119 @@
+use crate::editor_with_lines;
+use dmacs::editor::actions::Action;
+use dmacs::editor::minibuffer::PromptKind;
+use dmacs::editor::{Editor, EditorMode};
+use pancurses::Input;
+
+fn type_text(editor: &mut Editor, text: &str) {
+    for c in text.chars() {
+        editor.process_input(Input::Character(c), false).unwrap();
+    }
+}
+
+#[test]
+fn test_go_to_line() {
+    let mut editor = editor_with_lines(&["one", "two", "three"]);
+    editor.execute_action(Action::GoToLine).unwrap();
+    assert_eq!(editor.mode, EditorMode::Minibuffer);
+    assert_eq!(editor.minibuffer.prompt, Some(PromptKind::GoToLine));
+
+    type_text(&mut editor, "3\n");
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert_eq!(editor.minibuffer.prompt, None);
+    assert_eq!(editor.cursor_pos(), (0, 2));
+}
+
+#[test]
+fn test_go_to_line_and_column() {
+    let mut editor = editor_with_lines(&["one", "あいう"]);
+    editor.execute_action(Action::GoToLine).unwrap();
+    type_text(&mut editor, "2:3\n");
+    assert_eq!(editor.cursor_pos(), (6, 1));
+}
+
+#[test]
+fn test_go_to_line_past_end() {
+    let mut editor = editor_with_lines(&["one", "two"]);
+    editor.execute_action(Action::GoToLine).unwrap();
+    type_text(&mut editor, "99\n");
+    assert_eq!(editor.cursor_pos(), (3, 1));
+}
+
+#[test]
+fn test_go_to_line_invalid_input() {
+    let mut editor = editor_with_lines(&["one", "two"]);
+    editor.set_cursor_pos(1, 1);
+    editor.execute_action(Action::GoToLine).unwrap();
+    type_text(&mut editor, "abc\n");
+    assert_eq!(editor.cursor_pos(), (1, 1));
+    assert_eq!(editor.status_message, "Invalid line number: abc");
+}
+
+#[test]
+fn test_minibuffer_editing() {
+    let mut editor = editor_with_lines(&["one", "two", "three"]);
+    editor.execute_action(Action::GoToLine).unwrap();
+    type_text(&mut editor, "13");
+    editor.process_input(Input::KeyLeft, false).unwrap();
+    editor.process_input(Input::KeyBackspace, false).unwrap();
+    assert_eq!(editor.minibuffer.input, "3");
+    type_text(&mut editor, "2");
+    assert_eq!(editor.minibuffer.input, "23");
+    // Ctrl-A then Ctrl-K clears the input
+    editor
+        .process_input(Input::Character('\x01'), false)
+        .unwrap();
+    editor
+        .process_input(Input::Character('\x0b'), false)
+        .unwrap();
+    assert_eq!(editor.minibuffer.input, "");
+    type_text(&mut editor, "2\n");
+    assert_eq!(editor.cursor_pos(), (0, 1));
+}
+
+#[test]
+fn test_minibuffer_cancel() {
+    let mut editor = editor_with_lines(&["one", "two"]);
+    editor.execute_action(Action::GoToLine).unwrap();
+    type_text(&mut editor, "2");
+    // Ctrl-G
+    editor
+        .process_input(Input::Character('\x07'), false)
+        .unwrap();
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert_eq!(editor.minibuffer.prompt, None);
+    assert_eq!(editor.cursor_pos(), (0, 0));
+}
+
+#[test]
+fn test_minibuffer_history() {
+    let mut editor = editor_with_lines(&["one", "two", "three"]);
+    for input in ["2\n", "3\n"] {
+        editor.execute_action(Action::GoToLine).unwrap();
+        type_text(&mut editor, input);
+    }
+    assert_eq!(
+        editor.minibuffer.history(PromptKind::GoToLine),
+        &["2".to_string(), "3".to_string()]
+    );
+
+    editor.execute_action(Action::GoToLine).unwrap();
+    editor.process_input(Input::KeyUp, false).unwrap();
+    assert_eq!(editor.minibuffer.input, "3");
+    editor.process_input(Input::KeyUp, false).unwrap();
+    assert_eq!(editor.minibuffer.input, "2");
+    editor.process_input(Input::KeyDown, false).unwrap();
+    assert_eq!(editor.minibuffer.input, "3");
+    editor.process_input(Input::KeyDown, false).unwrap();
+    assert_eq!(editor.minibuffer.input, "");
+
+    editor.process_input(Input::KeyUp, false).unwrap();
+    editor.process_input(Input::KeyUp, false).unwrap();
+    type_text(&mut editor, "\n");
+    assert_eq!(editor.cursor_pos(), (0, 1));
+    // Reusing an entry moves it to the end instead of duplicating it
+    assert_eq!(
+        editor.minibuffer.history(PromptKind::GoToLine),
+        &["3".to_string(), "2".to_string()]
+    );
+}
@@ -8,8 +8,8 @@ use tempfile::NamedTempFile;
 fn test_editor_initial_state_no_file() {
     let editor = Editor::new(None, None, None);
     assert!(!editor.should_quit);
-    assert_eq!(editor.document.lines.len(), 1);
-    assert_eq!(editor.document.lines[0], "");
+    assert_eq!(editor.document.lines().len(), 1);
+    assert_eq!(editor.document.lines()[0], "");
 }
 
 #[test]
@@ -17,12 +17,12 @@ fn test_editor_with_wide_chars() {
     let mut editor = Editor::new(None, None, None);
     editor.process_input(Input::Character('あ'), false).unwrap();
     editor.process_input(Input::Character('い'), false).unwrap();
-    assert_eq!(editor.document.lines[0], "あい");
+    assert_eq!(editor.document.lines()[0], "あい");
     assert_eq!(editor.cursor_pos(), (6, 0)); // "あ" and "い" are 3 bytes each
     editor.process_input(Input::KeyLeft, false).unwrap();
     assert_eq!(editor.cursor_pos(), (3, 0));
     editor.process_input(Input::KeyBackspace, false).unwrap();
-    assert_eq!(editor.document.lines[0], "い");
+    assert_eq!(editor.document.lines()[0], "い");
     assert_eq!(editor.cursor_pos(), (0, 0));
 }
 
@@ -137,3 +137,56 @@ fn test_ctrl_x_no_exit_on_save() {
 
     // Clean up the temporary file (done automatically by NamedTempFile drop)
 }
+
+#[test]
+fn test_monochrome_render_attributes_are_distinct() {
+    use dmacs::editor::ui::RenderAttributes;
+
+    let attrs = RenderAttributes::new(true);
+    let all = [
+        attrs.comment,
+        attrs.checked,
+        attrs.unchecked,
+        attrs.selection,
+        attrs.search_match,
+        attrs.last_change,
+        attrs.misspelled,
+    ];
+    for (i, a) in all.iter().enumerate() {
+        assert_ne!(*a, pancurses::A_NORMAL);
+        for b in all.iter().skip(i + 1) {
+            assert_ne!(a, b);
+        }
+    }
+}
+
+#[test]
+fn test_indent_guide_columns() {
+    use dmacs::editor::ui::indent_guide_columns;
+
+    assert_eq!(indent_guide_columns("- top"), Vec::<usize>::new());
+    assert_eq!(indent_guide_columns("  - child"), vec![0]);
+    assert_eq!(indent_guide_columns("     - odd"), vec![0, 2, 4]);
+    // A tab reaches the next tab stop
+    assert_eq!(indent_guide_columns("\t- tabbed"), vec![0, 2]);
+    assert_eq!(indent_guide_columns("    "), vec![0, 2]);
+}
+
+#[test]
+fn test_cursor_line_pairs_keep_foreground_pairs_apart() {
+    use dmacs::editor::ui::{GUIDE_COLOR_PAIR, KEYWORD_COLOR_PAIRS, cursor_line_pair};
+
+    let mut pairs = vec![cursor_line_pair(1), cursor_line_pair(3)];
+    for pair in KEYWORD_COLOR_PAIRS {
+        pairs.push(cursor_line_pair(pair));
+    }
+    pairs.push(cursor_line_pair(GUIDE_COLOR_PAIR));
+    for (i, pair) in pairs.iter().enumerate() {
+        assert!(*pair > GUIDE_COLOR_PAIR);
+        assert!(!pairs[i + 1..].contains(pair));
+    }
+    // Unstyled text is drawn like the default pair
+    assert_eq!(cursor_line_pair(0), cursor_line_pair(1));
+    // The selection highlight stays as it is
+    assert_eq!(cursor_line_pair(2), 2);
+}
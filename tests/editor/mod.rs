@@ -1,16 +1,91 @@
+use dmacs::editor::Editor;
+
+mod abbrev_test;
+mod agenda_test;
+mod auto_pair_test;
+mod autosave_test;
+mod backup_browser_test;
+mod bookmarks_test;
+mod case_test;
 mod checkbox_test;
+mod cleanup_test;
+mod command_palette_test;
 mod command_test;
 mod comment_test;
+mod completion_test;
+mod copy_as_test;
 mod cursor_movement_test;
+mod date_picker_test;
+mod decoration_test;
 mod delimiter_movement_test;
+mod due_test;
+mod external_change_test;
+mod file_browser_test;
+mod file_format_test;
+mod file_settings_test;
+mod fold_test;
+mod follow_test;
+mod footnote_test;
 mod fuzzy_search_test;
+mod git_commit_test;
+mod git_gutter_test;
+mod heading_test;
+mod hint_bar_test;
 mod indent_test;
+mod input_transform_test;
 mod insertion_deletion_test;
+mod jump_list_test;
 mod kill_yank_test;
+mod last_change_test;
 mod line_movement_test;
+mod list_subtree_test;
+mod list_test;
+mod macro_test;
+mod minibuffer_test;
 mod misc_test;
+mod mouse_test;
+mod outline_test;
+mod page_test;
+mod paste_test;
+mod placeholder_test;
+mod pomodoro_test;
+mod project_search_test;
+mod recent_files_test;
+mod recovery_test;
+mod rectangle_test;
+mod repeat_test;
+mod revision_test;
+mod save_as_test;
+mod save_hooks_test;
+mod scratch_test;
 mod scrolling_test;
 mod search_test;
 mod selection_test;
+mod session_test;
+#[cfg(feature = "virtual-terminal")]
+mod snapshot_test;
+mod snippet_test;
+mod spelling_test;
+mod stats_test;
+mod status_line_test;
+mod structure_test;
+mod tags_test;
+mod task_archive_test;
 mod task_command_test;
+mod theme_test;
+mod time_tracking_test;
+mod todo_keyword_test;
+mod todotxt_test;
+mod undo_snapshot_test;
 mod undo_test;
+
+/// An editor on an unnamed buffer holding `lines`, which leaves the system
+/// clipboard alone.
+fn editor_with_lines(lines: &[&str]) -> Editor {
+    let mut editor = Editor::new(None, None, None);
+    editor._set_clipboard_enabled_for_test(false);
+    editor
+        .document
+        .set_lines(lines.iter().map(|line| line.to_string()).collect());
+    editor
+}
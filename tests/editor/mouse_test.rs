@@ -0,0 +1,130 @@
+use crate::editor_with_lines;
+use dmacs::editor::Editor;
+use dmacs::editor::mouse::MouseEvent;
+use dmacs::editor::ui::STATUS_BAR_HEIGHT;
+
+// Clicks are given in screen coordinates
+fn mouse_editor(lines: &[&str]) -> Editor {
+    let mut editor = editor_with_lines(lines);
+    editor.update_screen_size(10, 40);
+    editor
+}
+
+#[test]
+fn test_mouse_click_moves_cursor() {
+    let mut editor = mouse_editor(&["hello", "world"]);
+    editor.handle_mouse_event(MouseEvent::Press {
+        row: STATUS_BAR_HEIGHT + 1,
+        col: 3,
+    });
+    editor.handle_mouse_event(MouseEvent::Release {
+        row: STATUS_BAR_HEIGHT + 1,
+        col: 3,
+    });
+    assert_eq!(editor.cursor_pos(), (3, 1));
+    assert!(!editor.selection.is_selection_active());
+}
+
+#[test]
+fn test_mouse_click_past_end_of_line_clamps() {
+    let mut editor = mouse_editor(&["hi", "there"]);
+    editor.handle_mouse_event(MouseEvent::Press {
+        row: STATUS_BAR_HEIGHT,
+        col: 20,
+    });
+    assert_eq!(editor.cursor_pos(), (2, 0));
+}
+
+#[test]
+fn test_mouse_click_below_last_line_goes_to_last_line() {
+    let mut editor = mouse_editor(&["one", "two"]);
+    editor.handle_mouse_event(MouseEvent::Press {
+        row: STATUS_BAR_HEIGHT + 5,
+        col: 1,
+    });
+    assert_eq!(editor.cursor_pos(), (1, 1));
+}
+
+#[test]
+fn test_mouse_click_on_status_bar_is_ignored() {
+    let mut editor = mouse_editor(&["one", "two"]);
+    editor.set_cursor_pos(2, 1);
+    editor.handle_mouse_event(MouseEvent::Press { row: 0, col: 1 });
+    assert_eq!(editor.cursor_pos(), (2, 1));
+}
+
+#[test]
+fn test_mouse_click_on_wide_characters() {
+    let mut editor = mouse_editor(&["あいう"]);
+    // Column 3 is the right half of "い", which starts at byte 3.
+    editor.handle_mouse_event(MouseEvent::Press {
+        row: STATUS_BAR_HEIGHT,
+        col: 3,
+    });
+    assert_eq!(editor.cursor_pos(), (3, 0));
+}
+
+#[test]
+fn test_mouse_drag_creates_selection() {
+    let mut editor = mouse_editor(&["hello world", "second line"]);
+    editor.handle_mouse_event(MouseEvent::Press {
+        row: STATUS_BAR_HEIGHT,
+        col: 6,
+    });
+    editor.handle_mouse_event(MouseEvent::Drag {
+        row: STATUS_BAR_HEIGHT + 1,
+        col: 6,
+    });
+    editor.handle_mouse_event(MouseEvent::Release {
+        row: STATUS_BAR_HEIGHT + 1,
+        col: 6,
+    });
+    assert_eq!(editor.selection.marker_pos, Some((6, 0)));
+    assert_eq!(editor.cursor_pos(), (6, 1));
+    editor.copy_selection_action().unwrap();
+    assert_eq!(editor.clipboard.kill_buffer, "world\nsecond");
+}
+
+#[test]
+fn test_mouse_click_with_horizontal_scroll_and_prefix() {
+    let mut editor = mouse_editor(&["- 0123456789abcdefghijklmnopqrstuvwxyz"]);
+    editor.update_screen_size(10, 20);
+    editor.go_to_end_of_line();
+    editor.scroll();
+    let col_offset = editor.scroll.col_offset;
+    assert!(col_offset > 0);
+
+    // Clicking inside the "- " prefix is not affected by horizontal scroll.
+    editor.handle_mouse_event(MouseEvent::Press {
+        row: STATUS_BAR_HEIGHT,
+        col: 1,
+    });
+    assert_eq!(editor.cursor_pos(), (1, 0));
+
+    editor.go_to_end_of_line();
+    editor.scroll();
+    // Prefix (2 columns) + ellipsis (1 column), then content starts at col_offset.
+    editor.handle_mouse_event(MouseEvent::Press {
+        row: STATUS_BAR_HEIGHT,
+        col: 3,
+    });
+    assert_eq!(editor.cursor_pos(), (2 + col_offset, 0));
+}
+
+#[test]
+fn test_mouse_wheel_scrolls_viewport() {
+    let lines: Vec<String> = (0..50).map(|i| format!("line {i}")).collect();
+    let refs: Vec<&str> = lines.iter().map(|s| s.as_str()).collect();
+    let mut editor = mouse_editor(&refs);
+
+    editor.handle_mouse_event(MouseEvent::ScrollDown);
+    assert_eq!(editor.scroll.row_offset, 3);
+    assert_eq!(editor.cursor_pos().1, 3);
+
+    editor.handle_mouse_event(MouseEvent::ScrollUp);
+    assert_eq!(editor.scroll.row_offset, 0);
+    assert_eq!(editor.cursor_pos().1, 0);
+
+    editor.handle_mouse_event(MouseEvent::ScrollUp);
+    assert_eq!(editor.scroll.row_offset, 0);
+}
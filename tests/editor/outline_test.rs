@@ -0,0 +1,88 @@
+use crate::editor_with_lines;
+use dmacs::document::LineBuffer;
+use dmacs::editor::actions::Action;
+use dmacs::editor::outline::outline_entries;
+use dmacs::editor::{Editor, EditorMode};
+use pancurses::Input;
+
+fn type_keys(editor: &mut Editor, text: &str) {
+    for c in text.chars() {
+        editor.process_input(Input::Character(c), false).unwrap();
+    }
+}
+
+#[test]
+fn test_outline_entries_are_indented_by_level() {
+    let lines: LineBuffer = [
+        "# Intro",
+        "text",
+        "## Details",
+        "---",
+        "",
+        "Next page",
+        "### Deep",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect();
+    let labels: Vec<String> = outline_entries(&lines, false)
+        .iter()
+        .map(|entry| entry.label())
+        .collect();
+    assert_eq!(labels, vec!["Intro", "  Details", "    Deep"]);
+
+    let labels: Vec<String> = outline_entries(&lines, true)
+        .iter()
+        .map(|entry| entry.label())
+        .collect();
+    assert_eq!(
+        labels,
+        vec!["Intro", "  Details", "--- Next page", "    Deep"]
+    );
+}
+
+#[test]
+fn test_outline_starts_at_current_section_and_jumps() {
+    let mut editor = editor_with_lines(&["# One", "a", "# Two", "b", "## Three", "c"]);
+    editor.set_cursor_pos(0, 3);
+    editor.execute_action(Action::ShowOutline).unwrap();
+    assert_eq!(editor.mode, EditorMode::Outline);
+    assert_eq!(editor.outline.selected_entry().unwrap().title, "Two");
+
+    editor.process_input(Input::KeyDown, false).unwrap();
+    editor.process_input(Input::Character('\n'), false).unwrap();
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert_eq!(editor.cursor_pos(), (0, 4));
+}
+
+#[test]
+fn test_outline_filters_by_fuzzy_query() {
+    let mut editor = editor_with_lines(&["# Shopping", "# Meeting notes", "# Misc"]);
+    editor.execute_action(Action::ShowOutline).unwrap();
+    type_keys(&mut editor, "mtg");
+    assert_eq!(editor.outline.matches.len(), 1);
+    assert_eq!(
+        editor.outline.selected_entry().unwrap().title,
+        "Meeting notes"
+    );
+
+    editor.process_input(Input::KeyBackspace, false).unwrap();
+    editor.process_input(Input::KeyBackspace, false).unwrap();
+    assert_eq!(editor.outline.query, "m");
+    assert!(editor.outline.matches.len() > 1);
+
+    // Cancelling leaves the cursor where it was
+    editor
+        .process_input(Input::Character('\x07'), false)
+        .unwrap();
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert_eq!(editor.cursor_pos(), (0, 0));
+}
+
+#[test]
+fn test_outline_without_headings() {
+    let mut editor = editor_with_lines(&["plain", "text"]);
+    editor.execute_action(Action::ShowOutline).unwrap();
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert_eq!(editor.status_message, "No headings.");
+}
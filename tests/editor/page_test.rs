@@ -0,0 +1,127 @@
+use dmacs::document::Document;
+use dmacs::editor::Editor;
+use dmacs::editor::page::PageIndex;
+use dmacs::editor::status_line::StatusSegment;
+use pancurses::Input;
+
+fn create_editor_with_content(content: &str) -> Editor {
+    let mut editor = Editor::new(None, None, None);
+    editor
+        .document
+        .set_lines(content.lines().map(|s| s.to_string()).collect());
+    if editor.document.lines().is_empty() {
+        editor.document.set_lines(vec![String::new()]);
+    }
+    editor
+}
+
+fn type_text(editor: &mut Editor, text: &str) {
+    for c in text.chars() {
+        editor.process_input(Input::Character(c), false).unwrap();
+    }
+}
+
+fn page_status(editor: &mut Editor) -> Option<String> {
+    editor
+        .status_part(StatusSegment::Page)
+        .map(|part| part.text)
+}
+
+const THREE_PAGES: &str = "one\n---\ntwo\n2b\n---\nthree";
+
+#[test]
+fn test_status_shows_the_page_of_the_cursor() {
+    let mut editor = create_editor_with_content(THREE_PAGES);
+    assert_eq!(page_status(&mut editor).as_deref(), Some("page 1/3"));
+    // A delimiter ends the page above it
+    editor.set_cursor_pos(0, 4);
+    assert_eq!(page_status(&mut editor).as_deref(), Some("page 2/3"));
+    editor.set_cursor_pos(0, 5);
+    assert_eq!(page_status(&mut editor).as_deref(), Some("page 3/3"));
+
+    let mut editor = create_editor_with_content("no\npages");
+    assert_eq!(page_status(&mut editor), None);
+}
+
+#[test]
+fn test_goto_page_from_the_prompt() {
+    let mut editor = create_editor_with_content(THREE_PAGES);
+    editor.process_input(Input::Character('P'), true).unwrap();
+    type_text(&mut editor, "2\n");
+    assert_eq!(editor.cursor_pos(), (0, 2));
+    assert_eq!(editor.scroll.row_offset, 2);
+    assert_eq!(editor.status_message, "Page 2/3");
+
+    // The jump list brings the cursor back
+    editor
+        .process_input(Input::Character('\x0f'), false)
+        .unwrap(); // Ctrl-O
+    assert_eq!(editor.cursor_pos(), (0, 0));
+}
+
+#[test]
+fn test_goto_page_out_of_range() {
+    let mut editor = create_editor_with_content(THREE_PAGES);
+    editor.go_to_page(4);
+    assert_eq!(editor.status_message, "No page 4 (1-3).");
+    editor.go_to_page(0);
+    assert_eq!(editor.status_message, "No page 0 (1-3).");
+    assert_eq!(editor.cursor_pos(), (0, 0));
+}
+
+#[test]
+fn test_repeat_count_picks_the_page() {
+    let mut editor = create_editor_with_content(THREE_PAGES);
+    editor.process_input(Input::Character('3'), true).unwrap();
+    editor.process_input(Input::Character('P'), true).unwrap();
+    assert_eq!(editor.cursor_pos(), (0, 5));
+}
+
+#[test]
+fn test_trailing_delimiter_starts_no_page() {
+    let mut editor = create_editor_with_content("one\n---\ntwo\n---");
+    editor.set_cursor_pos(0, 3);
+    assert_eq!(page_status(&mut editor).as_deref(), Some("page 2/2"));
+}
+
+#[test]
+fn test_pages_follow_edits_and_undo() {
+    let mut editor = create_editor_with_content(THREE_PAGES);
+    assert_eq!(editor.current_page(), (1, 3));
+
+    editor.set_cursor_pos(3, 0);
+    type_text(&mut editor, "\n---\n");
+    assert_eq!(editor.document.lines()[1], "---");
+    assert_eq!(editor.current_page(), (2, 4));
+
+    editor.go_to_page(4);
+    assert_eq!(editor.cursor_pos(), (0, 7));
+
+    while !editor.undo_redo.undo_stack.is_empty() {
+        editor.undo();
+    }
+    assert_eq!(editor.document.lines().len(), 6);
+    assert_eq!(editor.current_page(), (1, 3));
+}
+
+#[test]
+fn test_index_updated_from_changes_matches_a_fresh_one() {
+    let mut document = Document::new_empty();
+    document.set_lines(THREE_PAGES.lines().map(str::to_string).collect());
+    let mut index = PageIndex::default();
+    index.update(&document);
+
+    // Remove the first delimiter, and add two new ones further down
+    document.replace_lines(1..2, Vec::new());
+    document.replace_lines(
+        3..3,
+        vec!["---".to_string(), "x".to_string(), "---".to_string()],
+    );
+    index.update(&document);
+
+    let mut fresh = PageIndex::default();
+    fresh.update(&document);
+    let line_count = document.lines().len();
+    assert_eq!(index.page_starts(line_count), fresh.page_starts(line_count));
+    assert_eq!(index.page_starts(line_count), vec![0, 4, 6, 7]);
+}
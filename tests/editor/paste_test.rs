@@ -0,0 +1,77 @@
+use dmacs::editor::Editor;
+use dmacs::editor::actions::Action;
+use pancurses::Input;
+
+#[test]
+fn test_yank_normalized_is_one_undo_step() {
+    let mut editor = Editor::new(None, None, None);
+    editor._set_clipboard_enabled_for_test(false);
+    editor
+        .document
+        .set_lines(vec!["Notes:".to_string(), String::new()]);
+    editor.set_cursor_pos(0, 1);
+    editor.clipboard.kill_buffer = "• “milk”\n◦ 2\u{a0}l".to_string();
+
+    editor.execute_action(Action::YankNormalized).unwrap();
+    assert_eq!(
+        editor.document.lines(),
+        vec!["Notes:", "- \"milk\"", "  - 2 l"]
+    );
+    assert_eq!(editor.cursor_pos(), (7, 2));
+    // The kill buffer keeps the original text for a plain yank
+    assert_eq!(editor.clipboard.kill_buffer, "• “milk”\n◦ 2\u{a0}l");
+
+    editor.undo();
+    assert_eq!(editor.document.lines(), vec!["Notes:", ""]);
+}
+
+#[test]
+fn test_bracketed_paste_is_inserted_verbatim_as_one_undo_step() {
+    let mut editor = Editor::new(None, None, None);
+    editor.document.set_lines(vec!["- item".to_string()]);
+    editor.set_cursor_pos(6, 0);
+    editor.process_input(Input::Character('!'), false).unwrap();
+
+    editor
+        .process_paste("\r\n  indented\r\n- [ ] task".to_string())
+        .unwrap();
+    // No list prefix or indentation is added to the pasted lines
+    assert_eq!(
+        editor.document.lines(),
+        vec!["- item!", "  indented", "- [ ] task"]
+    );
+    assert_eq!(editor.cursor_pos(), (10, 2));
+
+    editor.undo();
+    assert_eq!(editor.document.lines(), vec!["- item!"]);
+    // Typing right after a paste is undone on its own
+    editor.redo();
+    editor.process_input(Input::Character('x'), false).unwrap();
+    editor.undo();
+    assert_eq!(
+        editor.document.lines(),
+        vec!["- item!", "  indented", "- [ ] task"]
+    );
+}
+
+#[test]
+fn test_bracketed_paste_into_search_query() {
+    let mut editor = Editor::new(None, None, None);
+    editor
+        .document
+        .set_lines(vec!["one".to_string(), "two".to_string()]);
+    editor.execute_action(Action::EnterSearchMode).unwrap();
+
+    editor.process_paste("two".to_string()).unwrap();
+    assert_eq!(editor.document.lines(), vec!["one", "two"]);
+    assert_eq!(editor.cursor_pos(), (0, 1));
+}
+
+#[test]
+fn test_bracketed_paste_refused_in_read_only_buffer() {
+    let mut editor = Editor::new(None, None, None);
+    editor.read_only = true;
+
+    editor.process_paste("text".to_string()).unwrap();
+    assert_eq!(editor.document.lines(), vec![""]);
+}
@@ -0,0 +1,108 @@
+use dmacs::editor::Editor;
+use dmacs::editor::mouse::MouseEvent;
+use dmacs::editor::placeholder::{Placeholder, byte_at_column, placeholders, url_placeholders};
+
+const DATA_URL: &str =
+    "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk";
+
+#[test]
+fn test_placeholders() {
+    let line = format!("See ![a diagram]({DATA_URL}) and ![](x.png)");
+    let found = placeholders(&line);
+    assert_eq!(
+        found.iter().map(|p| p.label.as_str()).collect::<Vec<_>>(),
+        vec!["[img: a diagram]", "[img]"]
+    );
+    assert_eq!(found[0].start, 4);
+    assert_eq!(&line[found[0].end..found[1].start], " and ");
+    assert_eq!(found[1].end, line.len());
+
+    // Only links with a long target are shortened
+    let line = format!("[short](https://example.com) [long](https://example.com/{DATA_URL})");
+    assert_eq!(
+        placeholders(&line),
+        vec![Placeholder {
+            start: 29,
+            end: line.len(),
+            label: "[link: long]".to_string(),
+        }]
+    );
+
+    assert!(placeholders("- [ ] task [x]").is_empty());
+    assert!(placeholders("![unclosed](").is_empty());
+}
+
+#[test]
+fn test_byte_at_column() {
+    let line = format!("ab![x]({DATA_URL})cd");
+    let found = placeholders(&line);
+    // Drawn as "ab[img: x]cd"
+    assert_eq!(byte_at_column(&line, &found, 1), 1);
+    assert_eq!(byte_at_column(&line, &found, 5), 2);
+    assert_eq!(byte_at_column(&line, &found, 10), line.len() - 2);
+    assert_eq!(byte_at_column(&line, &found, 99), line.len());
+}
+
+#[test]
+fn test_cursor_line_shows_link_in_full() {
+    let mut editor = Editor::new(None, None, None);
+    editor
+        .document
+        .set_lines(vec!["text".to_string(), format!("![x]({DATA_URL}) end")]);
+    assert_eq!(editor.line_placeholders(1).len(), 1);
+    editor.set_cursor_pos(0, 1);
+    assert!(editor.line_placeholders(1).is_empty());
+
+    editor.settings.link_placeholders = Some(false);
+    editor.set_cursor_pos(0, 0);
+    assert!(editor.line_placeholders(1).is_empty());
+}
+
+#[test]
+fn test_click_after_placeholder() {
+    let mut editor = Editor::new(None, None, None);
+    editor
+        .document
+        .set_lines(vec!["text".to_string(), format!("![x]({DATA_URL}) end")]);
+    editor.update_screen_size(10, 80);
+    // "[img: x] end" on the second row of text; click on the "e"
+    editor.handle_mouse_event(MouseEvent::Press { row: 3, col: 9 });
+    let line = &editor.document.lines()[1];
+    assert_eq!(editor.cursor_pos(), (line.len() - 3, 1));
+}
+
+#[test]
+fn test_url_placeholders() {
+    let line = "See https://github.com/DevMassive/dmacs/issues/1, https://example.com/ and (http://a.org?q=1)";
+    let found = url_placeholders(line, &[]);
+    assert_eq!(
+        found.iter().map(|p| p.label.as_str()).collect::<Vec<_>>(),
+        vec!["github.com…", "a.org…"]
+    );
+    assert_eq!(
+        &line[found[0].start..found[0].end],
+        "https://github.com/DevMassive/dmacs/issues/1"
+    );
+    assert_eq!(&line[found[1].start..found[1].end], "http://a.org?q=1");
+
+    // Link targets are not bare URLs
+    let line = "[docs](https://example.com/docs) https://example.com/more";
+    let found = url_placeholders(line, &placeholders(line));
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].start, 33);
+}
+
+#[test]
+fn test_shorten_urls_setting() {
+    let mut editor = Editor::new(None, None, None);
+    editor.document.set_lines(vec![
+        "text".to_string(),
+        "https://example.com/a/b c".to_string(),
+    ]);
+    assert!(editor.line_placeholders(1).is_empty());
+
+    editor.toggle_shorten_urls();
+    assert_eq!(editor.line_placeholders(1)[0].label, "example.com…");
+    editor.set_cursor_pos(0, 1);
+    assert!(editor.line_placeholders(1).is_empty());
+}
@@ -0,0 +1,77 @@
+use dmacs::config::EditorSettings;
+use dmacs::editor::Editor;
+use dmacs::editor::actions::Action;
+use dmacs::editor::pomodoro::PomodoroPhase;
+use pancurses::Input;
+use std::time::{Duration, Instant};
+
+fn expire_period(editor: &mut Editor) {
+    editor.pomodoro.ends_at = Some(Instant::now() - Duration::from_secs(1));
+}
+
+#[test]
+fn test_pomodoro_work_then_break() {
+    let mut editor = Editor::new(None, None, None);
+    editor.set_settings(EditorSettings {
+        pomodoro_work_mins: Some(50),
+        pomodoro_break_mins: Some(10),
+        pomodoro_bell: true,
+        ..EditorSettings::default()
+    });
+    editor.execute_action(Action::StartPomodoro).unwrap();
+    assert_eq!(editor.pomodoro.phase, Some(PomodoroPhase::Work));
+    assert!(editor.pomodoro.status().unwrap().starts_with("work 49:5"));
+
+    // Nothing happens before the period is over
+    assert!(!editor.pomodoro_tick());
+
+    expire_period(&mut editor);
+    assert!(editor.pomodoro_tick());
+    assert_eq!(editor.pomodoro.phase, Some(PomodoroPhase::Break));
+    assert_eq!(editor.pomodoro.completed, 1);
+    assert_eq!(
+        editor.pomodoro.alert.as_deref(),
+        Some("Pomodoro done! Take a 10 minute break.")
+    );
+
+    // The alert stays until the next key press
+    editor.process_input(Input::Character('a'), false).unwrap();
+    assert!(editor.pomodoro.alert.is_none());
+
+    expire_period(&mut editor);
+    assert!(editor.pomodoro_tick());
+    assert!(!editor.pomodoro.is_running());
+    assert_eq!(
+        editor.pomodoro.alert.as_deref(),
+        Some("Break is over. Back to work!")
+    );
+}
+
+#[test]
+fn test_pomodoro_without_bell() {
+    let mut editor = Editor::new(None, None, None);
+    editor.start_pomodoro();
+    expire_period(&mut editor);
+    assert!(!editor.pomodoro_tick());
+    assert!(editor.pomodoro.alert.is_some());
+}
+
+#[test]
+fn test_pomodoro_stop_and_status() {
+    let mut editor = Editor::new(None, None, None);
+    editor.execute_action(Action::PomodoroStatus).unwrap();
+    assert_eq!(
+        editor.status_message,
+        "No pomodoro running, 0 completed this session."
+    );
+
+    editor.execute_action(Action::StartPomodoro).unwrap();
+    editor.execute_action(Action::PomodoroStatus).unwrap();
+    assert!(editor.status_message.starts_with("Pomodoro: work 2"));
+
+    editor.execute_action(Action::StopPomodoro).unwrap();
+    assert!(!editor.pomodoro.is_running());
+    assert!(editor.pomodoro.status().is_none());
+    editor.execute_action(Action::StopPomodoro).unwrap();
+    assert_eq!(editor.status_message, "No pomodoro running.");
+}
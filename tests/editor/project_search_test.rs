@@ -0,0 +1,175 @@
+use dmacs::editor::fuzzy_search::{grep_project, project_files};
+use dmacs::editor::{Editor, EditorMode};
+use pancurses::Input;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+use std::thread;
+use std::time::Duration;
+use tempfile::TempDir;
+
+fn write(root: &Path, path: &str, content: &str) {
+    let path = root.join(path);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+fn relative_files(root: &Path) -> Vec<String> {
+    project_files(root)
+        .iter()
+        .map(|path| {
+            path.strip_prefix(root)
+                .unwrap()
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect()
+}
+
+fn editor_in_project(dir: &TempDir) -> Editor {
+    let path = dir.path().join("notes.md");
+    let mut editor = Editor::new(Some(path.to_string_lossy().to_string()), None, None);
+    editor.settings.grep_root = Some(dir.path().to_string_lossy().to_string());
+    editor
+}
+
+fn grep(editor: &mut Editor, query: &str) {
+    editor.process_input(Input::Character('F'), true).unwrap();
+    for c in query.chars() {
+        editor.process_input(Input::Character(c), false).unwrap();
+    }
+    editor.process_input(Input::Character('\n'), false).unwrap();
+    while editor.project_search.is_scanning() {
+        thread::sleep(Duration::from_millis(5));
+        editor.project_search_tick();
+    }
+}
+
+#[test]
+fn test_project_files_respect_gitignore() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    write(
+        root,
+        ".gitignore",
+        "*.log\n!keep.log\ntarget/\n/top.md\n# comment\n",
+    );
+    write(root, ".git/config", "");
+    write(root, "a.md", "");
+    write(root, "debug.log", "");
+    write(root, "keep.log", "");
+    write(root, "top.md", "");
+    write(root, "target/out.md", "");
+    write(root, "docs/top.md", "");
+    write(root, "docs/.gitignore", "draft-*\n");
+    write(root, "docs/draft-1.md", "");
+    write(root, "docs/deep/trace.log", "");
+    write(root, "other/draft-2.md", "");
+
+    assert_eq!(
+        relative_files(root),
+        vec![
+            ".gitignore",
+            "a.md",
+            "docs/.gitignore",
+            "docs/top.md",
+            "keep.log",
+            "other/draft-2.md",
+        ]
+    );
+}
+
+#[test]
+fn test_grep_project_orders_best_first() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path();
+    write(root, "a.md", "nothing here\nmeeting notes\n");
+    write(root, "b/c.md", "- [ ] book the meeting room\n");
+    write(root, "bin.dat", "meeting\0");
+
+    let matches = grep_project(root, "meeting", &AtomicBool::new(false));
+    let found: Vec<(String, usize, usize)> = matches
+        .iter()
+        .map(|found| {
+            (
+                found
+                    .path
+                    .strip_prefix(root)
+                    .unwrap()
+                    .to_string_lossy()
+                    .into_owned(),
+                found.line_number,
+                found.column,
+            )
+        })
+        .collect();
+    assert_eq!(
+        found,
+        vec![("a.md".to_string(), 1, 0), ("b/c.md".to_string(), 0, 15)]
+    );
+}
+
+#[test]
+fn test_selecting_a_match_opens_its_file() {
+    let dir = TempDir::new().unwrap();
+    write(dir.path(), "notes.md", "# Notes\n");
+    write(dir.path(), "sub/plans.md", "first\n\n  quarterly plans\n");
+    let mut editor = editor_in_project(&dir);
+
+    grep(&mut editor, "quarterly");
+    assert_eq!(editor.mode, EditorMode::ProjectSearch);
+    assert_eq!(editor.project_search.matches.len(), 1);
+
+    editor.process_input(Input::Character('\n'), false).unwrap();
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert!(
+        editor
+            .document
+            .filename
+            .as_deref()
+            .is_some_and(|filename| filename.ends_with("plans.md"))
+    );
+    assert_eq!(editor.cursor_pos(), (2, 2));
+}
+
+#[test]
+fn test_match_in_the_current_file_moves_the_cursor() {
+    let dir = TempDir::new().unwrap();
+    write(dir.path(), "notes.md", "# Notes\nsome text\nlast line\n");
+    let mut editor = editor_in_project(&dir);
+
+    grep(&mut editor, "last");
+    editor.process_input(Input::Character('\n'), false).unwrap();
+    assert!(
+        editor
+            .document
+            .filename
+            .as_deref()
+            .is_some_and(|filename| filename.ends_with("notes.md"))
+    );
+    assert_eq!(editor.cursor_pos(), (0, 2));
+
+    // The jump list takes the cursor back
+    editor
+        .process_input(Input::Character('\x0f'), false)
+        .unwrap();
+    assert_eq!(editor.cursor_pos(), (0, 0));
+}
+
+#[test]
+fn test_escape_and_empty_query() {
+    let dir = TempDir::new().unwrap();
+    write(dir.path(), "notes.md", "alpha\n");
+    let mut editor = editor_in_project(&dir);
+
+    grep(&mut editor, "alpha");
+    editor
+        .process_input(Input::Character('\x1b'), false)
+        .unwrap();
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert!(editor.project_search.matches.is_empty());
+
+    grep(&mut editor, "  ");
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert_eq!(editor.status_message, "Nothing to search for.");
+}
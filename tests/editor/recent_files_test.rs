@@ -0,0 +1,77 @@
+use dmacs::editor::actions::Action;
+use dmacs::editor::{Editor, EditorMode};
+use dmacs::persistence;
+use pancurses::Input;
+use std::fs;
+use tempfile::TempDir;
+
+fn path_string(dir: &TempDir, name: &str) -> String {
+    dir.path().join(name).to_string_lossy().to_string()
+}
+
+#[test]
+fn test_open_recent_file() {
+    let dir = TempDir::new().unwrap();
+    let first = path_string(&dir, "first.md");
+    let second = path_string(&dir, "second.md");
+    fs::write(&first, "first\n").unwrap();
+    fs::write(&second, "second\n").unwrap();
+
+    let mut editor = Editor::new(Some(first.clone()), None, None);
+    editor.enable_recent_files_with_base_dir(Some(dir.path().to_path_buf()));
+    editor.open_file(&second).unwrap();
+
+    let recent_path = persistence::recent_files_path(Some(dir.path().to_path_buf())).unwrap();
+    let recorded: Vec<String> = persistence::load_recent_files(&recent_path)
+        .into_iter()
+        .map(|file| file.file_path)
+        .collect();
+    assert_eq!(recorded, vec![second.clone(), first.clone()]);
+
+    // The picker leaves out the file that is already open
+    editor.execute_action(Action::OpenRecent).unwrap();
+    assert_eq!(editor.mode, EditorMode::RecentFiles);
+    assert_eq!(editor.recent_files.entries.len(), 1);
+    editor.process_input(Input::Character('\n'), false).unwrap();
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert_eq!(editor.document.filename.as_deref(), Some(first.as_str()));
+    assert_eq!(editor.document.lines(), vec!["first"]);
+}
+
+#[test]
+fn test_recent_files_skip_missing_files() {
+    let dir = TempDir::new().unwrap();
+    let gone = path_string(&dir, "gone.md");
+    fs::write(&gone, "").unwrap();
+
+    let mut editor = Editor::new(Some(gone.clone()), None, None);
+    editor.enable_recent_files_with_base_dir(Some(dir.path().to_path_buf()));
+    editor.open_file(&path_string(&dir, "new.md")).unwrap();
+    fs::remove_file(&gone).unwrap();
+
+    editor.execute_action(Action::OpenRecent).unwrap();
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert_eq!(editor.status_message, "No recent files.");
+}
+
+#[test]
+fn test_reopen_last_closed_file() {
+    let dir = TempDir::new().unwrap();
+    let first = path_string(&dir, "first.md");
+    let second = path_string(&dir, "second.md");
+    fs::write(&first, "first\n").unwrap();
+    fs::write(&second, "second\n").unwrap();
+
+    let mut editor = Editor::new(Some(first.clone()), None, None);
+    editor.enable_recent_files_with_base_dir(Some(dir.path().to_path_buf()));
+    editor.execute_action(Action::ReopenLastClosed).unwrap();
+    assert_eq!(editor.status_message, "No closed files to reopen.");
+
+    editor.open_file(&second).unwrap();
+    editor.execute_action(Action::ReopenLastClosed).unwrap();
+    assert_eq!(editor.document.filename.as_deref(), Some(first.as_str()));
+
+    // Switching back and forth, like the last two tabs of a browser
+    editor.execute_action(Action::ReopenLastClosed).unwrap();
+    assert_eq!(editor.document.filename.as_deref(), Some(second.as_str()));
+}
@@ -0,0 +1,164 @@
+use dmacs::config::EditorSettings;
+use dmacs::editor::{Editor, EditorMode};
+use dmacs::journal::Journal;
+use pancurses::Input;
+use std::fs;
+use std::time::{Duration, Instant};
+use tempfile::{NamedTempFile, TempDir};
+
+fn editor_with_journal(path: &str, journal_dir: &TempDir) -> Editor {
+    let mut editor = Editor::new(Some(path.to_string()), None, None);
+    editor.enable_journal_with_base_dir(Some(journal_dir.path().to_path_buf()));
+    editor
+}
+
+fn type_text(editor: &mut Editor, text: &str) {
+    for c in text.chars() {
+        editor.process_input(Input::Character(c), false).unwrap();
+    }
+}
+
+#[test]
+fn test_recover_unsaved_changes_after_crash() {
+    let file = NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap().to_string();
+    fs::write(&path, "hello\nworld\n").unwrap();
+    let journal_dir = TempDir::new().unwrap();
+
+    {
+        let mut editor = editor_with_journal(&path, &journal_dir);
+        type_text(&mut editor, "ab");
+        editor.insert_newline().unwrap();
+        type_text(&mut editor, "c");
+        // Dropped without saving, as if the process had crashed
+    }
+
+    let mut editor = editor_with_journal(&path, &journal_dir);
+    assert_eq!(editor.mode, EditorMode::Recovery);
+    assert_eq!(editor.document.lines(), vec!["hello", "world"]);
+
+    editor.process_input(Input::Character('y'), false).unwrap();
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert_eq!(editor.document.lines(), vec!["ab", "chello", "world"]);
+    assert_eq!(editor.cursor_pos(), (1, 1));
+    assert!(editor.document.is_dirty());
+}
+
+#[test]
+fn test_recovery_replays_undo_and_redo() {
+    let file = NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap().to_string();
+    fs::write(&path, "one\n").unwrap();
+    let journal_dir = TempDir::new().unwrap();
+
+    let expected = {
+        let mut editor = editor_with_journal(&path, &journal_dir);
+        editor.undo_redo.set_undo_debounce_threshold(0);
+        type_text(&mut editor, "xy");
+        editor.undo();
+        editor.undo();
+        editor.redo();
+        editor.document.lines().clone()
+    };
+    assert_eq!(expected, vec!["xone"]);
+
+    let mut editor = editor_with_journal(&path, &journal_dir);
+    editor.process_input(Input::Character('y'), false).unwrap();
+    assert_eq!(editor.document.lines(), expected);
+}
+
+#[test]
+fn test_declining_recovery_discards_journal() {
+    let file = NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap().to_string();
+    fs::write(&path, "hello\n").unwrap();
+    let journal_dir = TempDir::new().unwrap();
+
+    {
+        let mut editor = editor_with_journal(&path, &journal_dir);
+        type_text(&mut editor, "x");
+    }
+
+    let mut editor = editor_with_journal(&path, &journal_dir);
+    assert_eq!(editor.mode, EditorMode::Recovery);
+    // Unrelated keys keep the prompt open
+    editor.process_input(Input::Character('q'), false).unwrap();
+    assert_eq!(editor.mode, EditorMode::Recovery);
+    editor.process_input(Input::Character('n'), false).unwrap();
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert_eq!(editor.document.lines(), vec!["hello"]);
+
+    let editor = editor_with_journal(&path, &journal_dir);
+    assert_eq!(editor.mode, EditorMode::Normal);
+}
+
+#[test]
+fn test_saving_clears_journal() {
+    let file = NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap().to_string();
+    fs::write(&path, "hello\n").unwrap();
+    let journal_dir = TempDir::new().unwrap();
+
+    {
+        let mut editor = editor_with_journal(&path, &journal_dir);
+        type_text(&mut editor, "x");
+        editor.save_document().unwrap();
+    }
+
+    let editor = editor_with_journal(&path, &journal_dir);
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert_eq!(editor.document.lines(), vec!["xhello"]);
+}
+
+#[test]
+fn test_journal_ignored_when_file_changed_on_disk() {
+    let file = NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap().to_string();
+    fs::write(&path, "hello\n").unwrap();
+    let journal_dir = TempDir::new().unwrap();
+
+    {
+        let mut editor = editor_with_journal(&path, &journal_dir);
+        type_text(&mut editor, "x");
+    }
+    fs::write(&path, "changed elsewhere\n").unwrap();
+
+    let editor = editor_with_journal(&path, &journal_dir);
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert_eq!(editor.document.lines(), vec!["changed elsewhere"]);
+}
+
+#[test]
+fn test_edits_made_during_autosave_are_journaled_as_changed_lines() {
+    let file = NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap().to_string();
+    fs::write(&path, "one\ntwo\nthree\n").unwrap();
+    let journal_dir = TempDir::new().unwrap();
+
+    {
+        let mut editor = editor_with_journal(&path, &journal_dir);
+        editor.set_settings(EditorSettings {
+            autosave_interval_secs: Some(1),
+            ..EditorSettings::default()
+        });
+        type_text(&mut editor, "X");
+        editor.autosave.last_run = Instant::now() - Duration::from_secs(2);
+        editor.autosave_tick();
+        // Edit the next line while the snapshot is being written
+        editor.process_input(Input::KeyDown, false).unwrap();
+        type_text(&mut editor, "Y");
+        editor.finish_pending_autosave();
+    }
+
+    let saved = fs::read_to_string(&path).unwrap();
+    assert_eq!(saved, "Xone\ntwo\nthree\n");
+    let journal = Journal::new_with_base_dir(Some(journal_dir.path().to_path_buf()), &path, &saved);
+    let entries = journal.recoverable_entries().unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].diff.old, vec!["two"]);
+    assert_eq!(entries[0].diff.new, vec!["tYwo"]);
+
+    let mut editor = editor_with_journal(&path, &journal_dir);
+    editor.process_input(Input::Character('y'), false).unwrap();
+    assert_eq!(editor.document.lines(), vec!["Xone", "tYwo", "three"]);
+}
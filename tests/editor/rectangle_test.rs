@@ -0,0 +1,100 @@
+use crate::editor_with_lines;
+use dmacs::editor::Editor;
+use dmacs::editor::actions::Action;
+use dmacs::editor::rectangle::Rectangle;
+use pancurses::Input;
+
+fn select_rectangle(editor: &mut Editor, from: (usize, usize), to: (usize, usize)) {
+    editor.set_cursor_pos(from.0, from.1);
+    editor.execute_action(Action::ToggleRectangleMode).unwrap();
+    editor.set_cursor_pos(to.0, to.1);
+}
+
+#[test]
+fn test_rectangle_bounds() {
+    let mut editor = editor_with_lines(&["abcdef", "ghijkl", "mnopqr"]);
+    select_rectangle(&mut editor, (4, 2), (1, 0));
+    assert_eq!(
+        editor.rectangle_bounds(),
+        Some(Rectangle {
+            top: 0,
+            bottom: 2,
+            left: 1,
+            right: 4
+        })
+    );
+
+    // Switching back gives a regular selection
+    editor.execute_action(Action::ToggleRectangleMode).unwrap();
+    assert!(editor.rectangle_bounds().is_none());
+    assert!(editor.selection.is_selection_active());
+}
+
+#[test]
+fn test_cut_rectangle_and_undo() {
+    let mut editor = editor_with_lines(&["abcdef", "gh", "mnopqr"]);
+    select_rectangle(&mut editor, (1, 0), (4, 2));
+    editor.execute_action(Action::CutSelection).unwrap();
+
+    assert_eq!(editor.document.lines(), vec!["aef", "g", "mqr"]);
+    assert_eq!(
+        editor.clipboard.rectangle,
+        Some(vec!["bcd".to_string(), "h".to_string(), "nop".to_string()])
+    );
+    assert_eq!(editor.cursor_pos(), (1, 0));
+    assert!(!editor.selection.is_selection_active());
+
+    editor.undo();
+    assert_eq!(editor.document.lines(), vec!["abcdef", "gh", "mnopqr"]);
+}
+
+#[test]
+fn test_copy_and_yank_rectangle_pads_short_lines() {
+    let mut editor = editor_with_lines(&["| a | b |", "| c | d |", "x", ""]);
+    select_rectangle(&mut editor, (0, 0), (4, 1));
+    editor.execute_action(Action::CopySelection).unwrap();
+    assert_eq!(editor.document.lines()[0], "| a | b |");
+
+    editor.set_cursor_pos(1, 2);
+    editor.execute_action(Action::YankRectangle).unwrap();
+    assert_eq!(
+        editor.document.lines(),
+        vec!["| a | b |", "| c | d |", "x| a ", " | c "]
+    );
+}
+
+#[test]
+fn test_yank_rectangle_extends_document() {
+    let mut editor = editor_with_lines(&["ab", "cd"]);
+    select_rectangle(&mut editor, (0, 0), (1, 1));
+    editor.execute_action(Action::CopySelection).unwrap();
+
+    editor.set_cursor_pos(2, 1);
+    editor.execute_action(Action::YankRectangle).unwrap();
+    assert_eq!(editor.document.lines(), vec!["ab", "cda", "  c"]);
+
+    editor.undo();
+    assert_eq!(editor.document.lines(), vec!["ab", "cd"]);
+}
+
+#[test]
+fn test_typing_inserts_on_every_line() {
+    let mut editor = editor_with_lines(&["task one", "task two", "t"]);
+    select_rectangle(&mut editor, (0, 0), (0, 2));
+    for c in "- [ ] ".chars() {
+        editor.process_input(Input::Character(c), false).unwrap();
+    }
+    assert_eq!(
+        editor.document.lines(),
+        vec!["- [ ] task one", "- [ ] task two", "- [ ] t"]
+    );
+    assert_eq!(editor.cursor_pos(), (6, 2));
+    assert_eq!(editor.selection.marker_pos, Some((6, 0)));
+}
+
+#[test]
+fn test_yank_rectangle_without_rectangle() {
+    let mut editor = editor_with_lines(&["abc"]);
+    editor.execute_action(Action::YankRectangle).unwrap();
+    assert_eq!(editor.status_message, "No rectangle to yank.");
+}
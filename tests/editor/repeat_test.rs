@@ -0,0 +1,132 @@
+use dmacs::editor::Editor;
+use pancurses::Input;
+
+fn create_editor_with_content(content: &str) -> Editor {
+    let mut editor = Editor::new(None, None, None);
+    editor
+        .document
+        .set_lines(content.lines().map(|s| s.to_string()).collect());
+    if editor.document.lines().is_empty() {
+        editor.document.set_lines(vec![String::new()]);
+    }
+    editor
+}
+
+fn press(editor: &mut Editor, key: Input, alt: bool) {
+    editor.process_input(key, alt).unwrap();
+}
+
+fn type_chars(editor: &mut Editor, text: &str) {
+    for c in text.chars() {
+        press(editor, Input::Character(c), false);
+    }
+}
+
+#[test]
+fn test_alt_digit_repeats_kill_line_as_one_undo_step() {
+    let content = (1..=8).map(|n| format!("line {n}\n")).collect::<String>();
+    let mut editor = create_editor_with_content(&content);
+    press(&mut editor, Input::Character('5'), true);
+    assert_eq!(editor.status_message, "Repeat 5×");
+    press(&mut editor, Input::Character('\x0b'), false); // Ctrl-K
+    // Every Ctrl-K takes the text of a line and then its line break
+    assert_eq!(editor.document.lines()[0], "");
+    assert_eq!(editor.document.lines()[1], "line 4");
+    assert_eq!(editor.clipboard.kill_buffer, "line 1\nline 2\nline 3");
+    assert_eq!(editor.undo_redo.undo_stack.len(), 1);
+
+    editor.undo();
+    assert_eq!(editor.document.lines().len(), 8);
+    assert_eq!(editor.document.lines()[0], "line 1");
+}
+
+#[test]
+fn test_alt_digits_combine_into_one_count() {
+    let content = (0..20).map(|n| format!("{n}\n")).collect::<String>();
+    let mut editor = create_editor_with_content(&content);
+    press(&mut editor, Input::Character('1'), true);
+    press(&mut editor, Input::Character('0'), true);
+    press(&mut editor, Input::KeyDown, false);
+    assert_eq!(editor.cursor_pos(), (0, 10));
+
+    // The count applies to one command only
+    press(&mut editor, Input::KeyDown, false);
+    assert_eq!(editor.cursor_pos(), (0, 11));
+}
+
+#[test]
+fn test_universal_argument_repeats_four_times() {
+    let mut editor = create_editor_with_content("");
+    press(&mut editor, Input::Character('\x15'), false); // Ctrl-U
+    type_chars(&mut editor, "a");
+    assert_eq!(editor.document.lines()[0], "aaaa");
+
+    press(&mut editor, Input::Character('\x15'), false);
+    press(&mut editor, Input::Character('\x15'), false);
+    assert_eq!(editor.status_message, "Repeat 16×");
+    type_chars(&mut editor, "b");
+    assert_eq!(
+        editor.document.lines()[0],
+        format!("aaaa{}", "b".repeat(16))
+    );
+}
+
+#[test]
+fn test_digits_after_universal_argument_set_the_count() {
+    let mut editor = create_editor_with_content("");
+    press(&mut editor, Input::Character('\x15'), false);
+    type_chars(&mut editor, "12-");
+    assert_eq!(editor.document.lines()[0], "-".repeat(12));
+    assert_eq!(editor.undo_redo.undo_stack.len(), 1);
+
+    // Without a pending count digits are typed as usual
+    type_chars(&mut editor, "3");
+    assert_eq!(editor.document.lines()[0], format!("{}3", "-".repeat(12)));
+}
+
+#[test]
+fn test_ctrl_g_cancels_the_count() {
+    let mut editor = create_editor_with_content("");
+    press(&mut editor, Input::Character('7'), true);
+    press(&mut editor, Input::Character('\x07'), false); // Ctrl-G
+    assert_eq!(editor.status_message, "Repeat count cancelled.");
+    type_chars(&mut editor, "x");
+    assert_eq!(editor.document.lines()[0], "x");
+}
+
+#[test]
+fn test_repeated_grouped_edit_is_one_undo_step() {
+    let mut editor = create_editor_with_content("one two three four");
+    press(&mut editor, Input::Character('3'), true);
+    press(&mut editor, Input::Character('u'), true);
+    assert_eq!(editor.document.lines()[0], "ONE TWO THREE four");
+    assert_eq!(editor.undo_redo.undo_stack.len(), 1);
+
+    editor.undo();
+    assert_eq!(editor.document.lines()[0], "one two three four");
+}
+
+#[test]
+fn test_count_multiplies_macro_playback() {
+    let mut editor = create_editor_with_content("");
+    press(&mut editor, Input::Character('('), true);
+    type_chars(&mut editor, "ab");
+    press(&mut editor, Input::Character(')'), true);
+    press(&mut editor, Input::Character('3'), true);
+    press(&mut editor, Input::Character('e'), true);
+    assert_eq!(editor.document.lines()[0], "abababab");
+    assert_eq!(editor.status_message, "Macro played 3 times.");
+}
+
+#[test]
+fn test_macro_records_each_repetition() {
+    let mut editor = create_editor_with_content("");
+    press(&mut editor, Input::Character('('), true);
+    press(&mut editor, Input::Character('2'), true);
+    type_chars(&mut editor, "z");
+    press(&mut editor, Input::Character(')'), true);
+    assert_eq!(editor.document.lines()[0], "zz");
+
+    press(&mut editor, Input::Character('e'), true);
+    assert_eq!(editor.document.lines()[0], "zzzz");
+}
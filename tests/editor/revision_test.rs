@@ -0,0 +1,60 @@
+use crate::editor_with_lines;
+use dmacs::editor::Editor;
+
+/// The line numbers changed since `revision`.
+fn changed_lines(editor: &Editor, revision: u64) -> Option<Vec<usize>> {
+    editor
+        .document
+        .changed_lines_since(revision)
+        .map(|ranges| ranges.into_iter().flatten().collect())
+}
+
+#[test]
+fn test_every_edit_bumps_the_revision() {
+    let mut editor = editor_with_lines(&["one", "two"]);
+    let start = editor.document.revision();
+
+    editor.insert_text("x").unwrap();
+    assert_eq!(editor.document.revision(), start + 1);
+    editor.undo();
+    assert_eq!(editor.document.revision(), start + 2);
+    assert_eq!(changed_lines(&editor, start), Some(vec![0]));
+    assert_eq!(
+        changed_lines(&editor, editor.document.revision()),
+        Some(vec![])
+    );
+}
+
+#[test]
+fn test_changed_lines_follow_inserted_and_removed_lines() {
+    let mut editor = editor_with_lines(&["a", "b", "c", "d", "e"]);
+    let start = editor.document.revision();
+
+    editor.set_cursor_pos(1, 3);
+    editor.insert_text("!").unwrap();
+    // Two new lines above move the change on `d` down
+    editor.set_cursor_pos(1, 0);
+    editor.insert_newline().unwrap();
+    editor.insert_newline().unwrap();
+    assert_eq!(editor.document.lines()[5], "d!");
+    assert_eq!(changed_lines(&editor, start), Some(vec![0, 1, 2, 5]));
+
+    // Joining them back moves it up again
+    editor.delete_char().unwrap();
+    editor.delete_char().unwrap();
+    assert_eq!(changed_lines(&editor, start), Some(vec![0, 3]));
+}
+
+#[test]
+fn test_changed_lines_of_a_forgotten_revision() {
+    let mut editor = editor_with_lines(&[""]);
+    for _ in 0..1100 {
+        editor.insert_text("x").unwrap();
+    }
+    assert_eq!(changed_lines(&editor, 0), None);
+    assert_eq!(
+        changed_lines(&editor, editor.document.revision() - 10),
+        Some(vec![0])
+    );
+    assert_eq!(changed_lines(&editor, editor.document.revision() + 1), None);
+}
@@ -0,0 +1,97 @@
+use dmacs::editor::actions::Action;
+use dmacs::editor::{Editor, EditorMode};
+use pancurses::Input;
+use std::fs;
+use tempfile::TempDir;
+
+fn type_text(editor: &mut Editor, text: &str) {
+    for c in text.chars() {
+        editor.process_input(Input::Character(c), false).unwrap();
+    }
+}
+
+fn clear_minibuffer(editor: &mut Editor) {
+    // Ctrl-A, Ctrl-K
+    editor
+        .process_input(Input::Character('\x01'), false)
+        .unwrap();
+    editor
+        .process_input(Input::Character('\x0b'), false)
+        .unwrap();
+}
+
+#[test]
+fn test_save_as_writes_new_file() {
+    let dir = TempDir::new().unwrap();
+    let old_path = dir.path().join("old.md");
+    fs::write(&old_path, "hello\n").unwrap();
+    let new_path = dir.path().join("new.md");
+
+    let mut editor = Editor::new(Some(old_path.to_string_lossy().to_string()), None, None);
+    editor.document.set_lines(vec!["hello world".to_string()]);
+    editor.execute_action(Action::SaveAs).unwrap();
+    assert_eq!(editor.mode, EditorMode::Minibuffer);
+    assert_eq!(editor.minibuffer.input, old_path.to_string_lossy());
+
+    clear_minibuffer(&mut editor);
+    type_text(&mut editor, &format!("{}\n", new_path.display()));
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert_eq!(
+        editor.document.filename.as_deref(),
+        Some(new_path.to_string_lossy().as_ref())
+    );
+    assert!(!editor.document.is_dirty());
+    assert_eq!(fs::read_to_string(&new_path).unwrap(), "hello world\n");
+    // The original file is left as it was
+    assert_eq!(fs::read_to_string(&old_path).unwrap(), "hello\n");
+}
+
+#[test]
+fn test_save_as_from_unnamed_buffer() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("notes.md");
+
+    let mut editor = Editor::new(None, None, None);
+    editor.document.set_lines(vec!["draft".to_string()]);
+    editor.execute_action(Action::SaveAs).unwrap();
+    assert_eq!(editor.minibuffer.input, "");
+    type_text(&mut editor, &format!("{}\n", path.display()));
+    assert_eq!(fs::read_to_string(&path).unwrap(), "draft\n");
+    assert!(!editor.is_scratch_buffer());
+}
+
+#[test]
+fn test_save_as_rejects_directory() {
+    let dir = TempDir::new().unwrap();
+    let mut editor = Editor::new(None, None, None);
+    editor.save_document_as(&dir.path().to_string_lossy());
+    assert!(editor.document.filename.is_none());
+    assert!(editor.status_message.ends_with("is a directory."));
+}
+
+#[test]
+fn test_save_as_completes_directories() {
+    let dir = TempDir::new().unwrap();
+    fs::create_dir(dir.path().join("notes")).unwrap();
+    fs::create_dir(dir.path().join("projects-2024")).unwrap();
+    fs::create_dir(dir.path().join("projects-2025")).unwrap();
+    fs::write(dir.path().join("note.md"), "").unwrap();
+    let base = dir.path().to_string_lossy().to_string();
+
+    let mut editor = Editor::new(None, None, None);
+    editor.execute_action(Action::SaveAs).unwrap();
+    type_text(&mut editor, &format!("{base}/no\t"));
+    assert_eq!(editor.minibuffer.input, format!("{base}/notes/"));
+
+    clear_minibuffer(&mut editor);
+    type_text(&mut editor, &format!("{base}/pro\t"));
+    assert_eq!(editor.minibuffer.input, format!("{base}/projects-202"));
+
+    // Tab completion is only for paths
+    editor
+        .process_input(Input::Character('\x07'), false)
+        .unwrap();
+    editor.execute_action(Action::GoToLine).unwrap();
+    type_text(&mut editor, "1\t");
+    assert_eq!(editor.minibuffer.input, "1");
+}
@@ -0,0 +1,80 @@
+use dmacs::editor::Editor;
+use dmacs::editor::actions::Action;
+use std::fs;
+use tempfile::TempDir;
+
+fn editor_for_file(dir: &TempDir, content: &str) -> (Editor, String) {
+    let path = dir.path().join("notes.md");
+    fs::write(&path, content).unwrap();
+    let path = path.to_string_lossy().to_string();
+    let mut editor = Editor::new(Some(path.clone()), None, None);
+    editor.settings.trim_trailing_whitespace = true;
+    editor.settings.ensure_final_newline = true;
+    editor.settings.tabs_to_spaces = true;
+    (editor, path)
+}
+
+#[test]
+fn test_save_cleans_buffer_and_file() {
+    let dir = TempDir::new().unwrap();
+    let (mut editor, path) = editor_for_file(&dir, "");
+    editor.document.set_lines(
+        ["title  ", "\titem ", "", ""]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+    );
+    editor.set_cursor_pos(5, 1);
+
+    editor.execute_action(Action::Save).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "title\n    item\n");
+    assert_eq!(editor.document.lines(), vec!["title", "    item"]);
+    assert_eq!(editor.cursor_pos(), (8, 1));
+    assert!(!editor.document.is_dirty());
+
+    editor.undo();
+    assert_eq!(editor.document.lines(), vec!["title  ", "\titem ", "", ""]);
+}
+
+#[test]
+fn test_cursor_past_removed_lines_moves_to_last_line() {
+    let dir = TempDir::new().unwrap();
+    let (mut editor, path) = editor_for_file(&dir, "");
+    editor
+        .document
+        .set_lines(vec!["end".to_string(), String::new(), String::new()]);
+    editor.set_cursor_pos(0, 2);
+
+    editor.execute_action(Action::Save).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "end\n");
+    assert_eq!(editor.cursor_pos(), (3, 0));
+}
+
+#[test]
+fn test_save_without_hooks_keeps_text() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("plain.md");
+    fs::write(&path, "").unwrap();
+    let mut editor = Editor::new(Some(path.to_string_lossy().to_string()), None, None);
+    editor
+        .document
+        .set_lines(vec!["a  ".to_string(), String::new()]);
+
+    editor.execute_action(Action::Save).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "a  \n\n");
+}
+
+#[test]
+fn test_restored_cursor_lands_on_cleaned_text() {
+    let dir = TempDir::new().unwrap();
+    let (mut editor, path) = editor_for_file(&dir, "");
+    editor
+        .document
+        .set_lines(vec!["\t\tdeep  ".to_string(), "next".to_string()]);
+    editor.set_cursor_pos(6, 0);
+    editor.quit().unwrap();
+
+    let reopened = Editor::new(Some(path), None, None);
+    assert_eq!(reopened.document.lines(), vec!["        deep", "next"]);
+    assert_eq!(reopened.cursor_pos(), (12, 0));
+}
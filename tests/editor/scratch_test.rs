@@ -0,0 +1,117 @@
+use dmacs::editor::Editor;
+use dmacs::editor::actions::Action;
+use pancurses::Input;
+use std::fs;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+fn scratch_editor(state_dir: &TempDir) -> Editor {
+    let mut editor = Editor::new(None, None, None);
+    editor.enable_scratch_autosave_with_base_dir(Some(state_dir.path().to_path_buf()));
+    editor
+}
+
+fn scratch_file(state_dir: &TempDir) -> std::path::PathBuf {
+    state_dir.path().join(".dmacs").join("scratch.md")
+}
+
+#[test]
+fn test_unnamed_buffer_saved_after_idle_delay() {
+    let state_dir = TempDir::new().unwrap();
+    let mut editor = scratch_editor(&state_dir);
+    editor.process_input(Input::Character('a'), false).unwrap();
+
+    // Still typing
+    editor.autosave_tick();
+    assert!(!scratch_file(&state_dir).exists());
+
+    editor.autosave.last_edit = Instant::now() - Duration::from_secs(3);
+    editor.autosave_tick();
+    assert_eq!(fs::read_to_string(scratch_file(&state_dir)).unwrap(), "a\n");
+    assert!(!editor.autosave.scratch_dirty);
+}
+
+#[test]
+fn test_scratch_restored_on_next_start() {
+    let state_dir = TempDir::new().unwrap();
+    {
+        let mut editor = scratch_editor(&state_dir);
+        editor.process_input(Input::Character('x'), false).unwrap();
+        editor.insert_newline().unwrap();
+        editor.process_input(Input::Character('y'), false).unwrap();
+        editor.quit().unwrap();
+    }
+
+    let editor = scratch_editor(&state_dir);
+    assert_eq!(editor.document.lines(), vec!["x", "y"]);
+    assert_eq!(editor.cursor_pos(), (0, 0));
+    assert_eq!(editor.status_message, "Restored scratch buffer.");
+}
+
+#[test]
+fn test_named_buffer_does_not_touch_scratch() {
+    let state_dir = TempDir::new().unwrap();
+    fs::create_dir_all(state_dir.path().join(".dmacs")).unwrap();
+    fs::write(scratch_file(&state_dir), "old notes\n").unwrap();
+
+    let file = tempfile::NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap().to_string();
+    fs::write(&path, "content\n").unwrap();
+    let mut editor = Editor::new(Some(path), None, None);
+    editor.enable_scratch_autosave_with_base_dir(Some(state_dir.path().to_path_buf()));
+    assert_eq!(editor.document.lines(), vec!["content"]);
+
+    editor.process_input(Input::Character('z'), false).unwrap();
+    editor.autosave.last_edit = Instant::now() - Duration::from_secs(3);
+    editor.autosave_tick();
+    assert_eq!(
+        fs::read_to_string(scratch_file(&state_dir)).unwrap(),
+        "old notes\n"
+    );
+}
+
+#[test]
+fn test_toggle_scratch_buffer_preserves_file() {
+    let state_dir = TempDir::new().unwrap();
+    let file = tempfile::NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap().to_string();
+    fs::write(&path, "first\nsecond\n").unwrap();
+
+    let mut editor = Editor::new(Some(path.clone()), None, None);
+    editor.enable_scratch_autosave_with_base_dir(Some(state_dir.path().to_path_buf()));
+    editor.set_cursor_pos(3, 1);
+    editor.process_input(Input::Character('X'), false).unwrap();
+
+    editor.execute_action(Action::ToggleScratchBuffer).unwrap();
+    assert!(editor.is_scratch_buffer());
+    assert_eq!(editor.document.lines(), vec![""]);
+    editor.process_input(Input::Character('n'), false).unwrap();
+
+    editor.execute_action(Action::ToggleScratchBuffer).unwrap();
+    assert_eq!(editor.document.filename.as_deref(), Some(path.as_str()));
+    assert_eq!(editor.document.lines(), vec!["first", "secXond"]);
+    assert_eq!(editor.cursor_pos(), (4, 1));
+    assert_eq!(fs::read_to_string(scratch_file(&state_dir)).unwrap(), "n\n");
+
+    // Undo history stays with its buffer
+    editor.undo();
+    assert_eq!(editor.document.lines(), vec!["first", "second"]);
+}
+
+#[test]
+fn test_quit_from_scratch_buffer_saves_file() {
+    let state_dir = TempDir::new().unwrap();
+    let file = tempfile::NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap().to_string();
+    fs::write(&path, "file\n").unwrap();
+
+    let mut editor = Editor::new(Some(path.clone()), None, None);
+    editor.enable_scratch_autosave_with_base_dir(Some(state_dir.path().to_path_buf()));
+    editor.process_input(Input::Character('a'), false).unwrap();
+    editor.execute_action(Action::ToggleScratchBuffer).unwrap();
+    editor.process_input(Input::Character('b'), false).unwrap();
+
+    editor.quit().unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "afile\n");
+    assert_eq!(fs::read_to_string(scratch_file(&state_dir)).unwrap(), "b\n");
+}
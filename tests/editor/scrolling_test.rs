@@ -1,11 +1,12 @@
 use dmacs::editor::Editor;
+use dmacs::editor::actions::Action;
 use dmacs::editor::ui::STATUS_BAR_HEIGHT;
 use pancurses::Input;
 
 #[test]
 fn test_editor_horizontal_scroll_right() {
     let mut editor = Editor::new(None, None, None);
-    editor.document.lines[0] = "0123456789abcdef".to_string();
+    editor.document.set_line(0, "0123456789abcdef".to_string());
     let screen_cols = 20;
     let scroll_margin = 10;
     editor.update_screen_size(10, screen_cols);
@@ -33,7 +34,7 @@ fn test_editor_horizontal_scroll_right() {
 #[test]
 fn test_editor_horizontal_scroll_left() {
     let mut editor = Editor::new(None, None, None);
-    editor.document.lines[0] = "0123456789abcdef".to_string();
+    editor.document.set_line(0, "0123456789abcdef".to_string());
     let screen_cols = 20;
     let scroll_margin = 10;
     editor.update_screen_size(10, screen_cols);
@@ -62,10 +63,10 @@ fn test_editor_horizontal_scroll_left() {
 #[test]
 fn test_editor_horizontal_scroll_line_change() {
     let mut editor = Editor::new(None, None, None);
-    editor.document.lines = vec![
+    editor.document.set_lines(vec![
         "a very long line to test scrolling".to_string(), // len = 34
         "short line".to_string(),                         // len = 10
-    ];
+    ]);
     let screen_cols = 20;
     let scroll_margin = 10;
     editor.update_screen_size(15, screen_cols);
@@ -91,11 +92,8 @@ fn test_editor_horizontal_scroll_line_change() {
 #[test]
 fn test_editor_scroll_page_down() {
     let mut editor = Editor::new(None, None, None);
-    editor.document.lines.clear();
-    for _ in 0..50 {
-        // Create 50 lines
-        editor.document.lines.push("test line".to_string());
-    }
+    // Create 50 lines
+    editor.document.set_lines(vec!["test line".to_string(); 50]);
     editor.update_screen_size(25, 80); // screen_rows = 25, usable height = 25 - STATUS_BAR_HEIGHT
 
     // Initial state
@@ -130,11 +128,8 @@ fn test_editor_scroll_page_down() {
 #[test]
 fn test_editor_scroll_page_up() {
     let mut editor = Editor::new(None, None, None);
-    editor.document.lines.clear();
-    for _ in 0..50 {
-        // Create 50 lines
-        editor.document.lines.push("test line".to_string());
-    }
+    // Create 50 lines
+    editor.document.set_lines(vec!["test line".to_string(); 50]);
     editor.update_screen_size(25, 80); // screen_rows = 25, usable height = 25 - STATUS_BAR_HEIGHT
 
     let usable_height = editor.scroll.screen_rows.saturating_sub(STATUS_BAR_HEIGHT);
@@ -169,10 +164,7 @@ fn test_editor_scroll_page_up() {
 #[test]
 fn test_editor_vertical_scroll_down() {
     let mut editor = Editor::new(None, None, None);
-    editor.document.lines.clear();
-    for _ in 0..50 {
-        editor.document.lines.push("test line".to_string());
-    }
+    editor.document.set_lines(vec!["test line".to_string(); 50]);
     editor.update_screen_size(10, 80); // screen_rows = 10, usable height = 10 - STATUS_BAR_HEIGHT
 
     // Initial state
@@ -208,10 +200,7 @@ fn test_editor_vertical_scroll_down() {
 #[test]
 fn test_editor_vertical_scroll_up() {
     let mut editor = Editor::new(None, None, None);
-    editor.document.lines.clear();
-    for _ in 0..50 {
-        editor.document.lines.push("test line".to_string());
-    }
+    editor.document.set_lines(vec!["test line".to_string(); 50]);
     editor.update_screen_size(10, 80); // screen_rows = 10, usable height = 10 - STATUS_BAR_HEIGHT
 
     let usable_height = editor.scroll.screen_rows.saturating_sub(STATUS_BAR_HEIGHT);
@@ -237,106 +226,93 @@ fn test_editor_vertical_scroll_up() {
     assert_eq!(editor.scroll.row_offset, 10 - scroll_margin);
 }
 
+fn editor_with_numbered_lines(count: usize, text_rows: usize) -> Editor {
+    let mut editor = Editor::new(None, None, None);
+    editor
+        .document
+        .set_lines((0..count).map(|n| format!("line {n}")).collect());
+    editor.update_screen_size(text_rows + STATUS_BAR_HEIGHT, 80);
+    editor
+}
+
 #[test]
-#[ignore = "This test interacts with the terminal and is best run manually"]
-fn test_horizontal_scroll_visual_and_cursor_pinning() {
-    // This test initializes a pancurses window to check the actual visual output.
-    // It might interfere with your terminal if it panics.
+fn test_recenter_view_cycles_center_top_bottom() {
+    // 20 rows of text keep a margin of 5 around the cursor
+    let mut editor = editor_with_numbered_lines(100, 20);
+    editor.set_cursor_pos(0, 50);
+    editor.scroll();
 
-    // 1. Setup
-    let window = pancurses::initscr();
-    window.keypad(true);
-    pancurses::noecho();
-    pancurses::curs_set(0); // Hide cursor for stable testing
+    let ctrl_l = Input::Character('\x0c');
+    editor.process_input(ctrl_l, false).unwrap();
+    editor.scroll();
+    assert_eq!(editor.scroll.row_offset, 40);
+    editor.process_input(ctrl_l, false).unwrap();
+    editor.scroll();
+    assert_eq!(editor.scroll.row_offset, 45);
+    editor.process_input(ctrl_l, false).unwrap();
+    editor.scroll();
+    assert_eq!(editor.scroll.row_offset, 36);
+    editor.process_input(ctrl_l, false).unwrap();
+    editor.scroll();
+    assert_eq!(editor.scroll.row_offset, 40);
+    assert_eq!(editor.cursor_pos(), (0, 50));
 
-    let mut editor = Editor::new(None, None, None);
-    let screen_rows = 10;
-    let screen_cols = 40;
-    let scroll_margin = 10;
-    editor.update_screen_size(screen_rows, screen_cols);
-    editor.document.lines[0] =
-        "This is a very long line of text to test the horizontal scrolling behavior of the editor."
-            .to_string();
-
-    // 2. Action: Move cursor to trigger scrolling
-    let move_count = 45;
-    for _ in 0..move_count {
-        editor.process_input(Input::KeyRight, false).unwrap();
-    }
-    editor.draw(&window);
-
-    // 3. Assertions for scrolled state
-    assert_eq!(editor.cursor_pos().0, move_count);
-    let expected_cursor_x = screen_cols - scroll_margin;
-
-    // Check cursor position on screen
-    let (cury, curx) = window.get_cur_yx();
-    assert_eq!(
-        cury, STATUS_BAR_HEIGHT as i32,
-        "Cursor Y position is incorrect"
-    );
-    assert_eq!(
-        curx, expected_cursor_x as i32,
-        "Cursor should be pinned to the right margin"
-    );
-
-    // Check displayed text on the line
-    let line_y = STATUS_BAR_HEIGHT as i32;
-    let mut displayed_line = String::new();
-    for x in 0..screen_cols {
-        let ch = window.mvinch(line_y, x as i32);
-        displayed_line.push((ch & pancurses::A_CHARTEXT) as u8 as char);
-    }
+    // Moving starts the cycle over at the center
+    editor.process_input(ctrl_l, false).unwrap();
+    editor.process_input(Input::KeyDown, false).unwrap();
+    editor.process_input(ctrl_l, false).unwrap();
+    editor.scroll();
+    assert_eq!(editor.scroll.row_offset, 41);
+}
 
-    let expected_col_offset = move_count - expected_cursor_x;
-    assert_eq!(editor.scroll.col_offset, expected_col_offset);
-    let expected_line_slice =
-        &editor.document.lines[0][expected_col_offset..expected_col_offset + screen_cols];
-    assert_eq!(
-        displayed_line, expected_line_slice,
-        "Displayed text is not scrolled correctly"
-    );
-
-    // 4. Action: Move cursor left, still in scrolled mode
-    editor.process_input(Input::KeyLeft, false).unwrap();
-    editor.draw(&window);
-
-    // 5. Assertions for continued scrolled state
-    let (cury, curx) = window.get_cur_yx();
-    assert_eq!(cury, STATUS_BAR_HEIGHT as i32, "Cursor Y should not change");
-    assert_eq!(
-        curx, expected_cursor_x as i32,
-        "Cursor should remain pinned"
-    );
-
-    // 6. Action: Move cursor left until scrolling stops
-    // Current cursor_x is `move_count - 1`.
-    // We need to move left until cursor_x becomes smaller than `expected_cursor_x`.
-    let moves_to_unpin = (move_count - 1) - (expected_cursor_x - 1);
-    for _ in 0..moves_to_unpin {
-        editor.process_input(Input::KeyLeft, false).unwrap();
-    }
-    assert_eq!(editor.cursor_pos().0, expected_cursor_x - 1); // e.g. 29
-    editor.draw(&window);
-
-    // 7. Assertions for unscrolled state
-    assert_eq!(editor.scroll.col_offset, 0, "col_offset should be 0");
-    let (cury, curx) = window.get_cur_yx();
-    assert_eq!(cury, STATUS_BAR_HEIGHT as i32);
-    assert_eq!(
-        curx,
-        (expected_cursor_x - 1) as i32,
-        "Cursor should now move freely"
-    );
-
-    let mut displayed_line_unscrolled = String::new();
-    for x in 0..screen_cols {
-        let ch = window.mvinch(line_y, x as i32);
-        displayed_line_unscrolled.push((ch & pancurses::A_CHARTEXT) as u8 as char);
+#[test]
+fn test_recenter_view_near_the_start_of_the_file() {
+    let mut editor = editor_with_numbered_lines(100, 20);
+    editor.set_cursor_pos(0, 3);
+    editor.process_input(Input::Character('\x0c'), false).unwrap();
+    editor.scroll();
+    assert_eq!(editor.scroll.row_offset, 0);
+    assert_eq!(editor.cursor_pos(), (0, 3));
+}
+
+#[test]
+fn test_scroll_view_moves_the_cursor_only_at_the_margin() {
+    let mut editor = editor_with_numbered_lines(100, 20);
+    editor.set_cursor_pos(3, 10);
+    editor.cursor.desired_x = 3;
+    editor.scroll();
+    assert_eq!(editor.scroll.row_offset, 0);
+
+    for _ in 0..5 {
+        editor.execute_action(Action::ScrollViewDown).unwrap();
     }
-    let expected_line_slice_unscrolled = &editor.document.lines[0][0..screen_cols];
-    assert_eq!(displayed_line_unscrolled, expected_line_slice_unscrolled);
+    editor.scroll();
+    assert_eq!(editor.scroll.row_offset, 5);
+    assert_eq!(editor.cursor_pos(), (3, 10));
 
-    // 8. Teardown
-    pancurses::endwin();
-}
\ No newline at end of file
+    // The cursor is now at the top margin and gets pushed along
+    editor.execute_action(Action::ScrollViewDown).unwrap();
+    editor.scroll();
+    assert_eq!(editor.scroll.row_offset, 6);
+    assert_eq!(editor.cursor_pos(), (3, 11));
+
+    editor.execute_action(Action::ScrollViewUp).unwrap();
+    editor.scroll();
+    assert_eq!(editor.scroll.row_offset, 5);
+    assert_eq!(editor.cursor_pos(), (3, 11));
+}
+
+#[test]
+fn test_scroll_view_stops_at_the_ends_of_the_file() {
+    let mut editor = editor_with_numbered_lines(30, 20);
+    editor.execute_action(Action::ScrollViewUp).unwrap();
+    assert_eq!(editor.scroll.row_offset, 0);
+
+    for _ in 0..40 {
+        editor.execute_action(Action::ScrollViewDown).unwrap();
+        editor.scroll();
+    }
+    // The last line stays clear of the top margin
+    assert_eq!(editor.scroll.row_offset, 24);
+    assert_eq!(editor.cursor_pos(), (0, 29));
+}
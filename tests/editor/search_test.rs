@@ -1,25 +1,31 @@
 use dmacs::editor::Editor;
+use dmacs::editor::search::{CaseMode, find_matches};
 use pancurses::Input;
 
+const OPTIONS: &str = "  [M-c smart, M-w parts]";
+
 #[test]
 fn test_editor_search_mode_enter_and_exit() {
     let mut editor = Editor::new(None, None, None);
-    editor.document.lines = vec!["test line one".to_string(), "test line two".to_string()];
+    editor.document.set_lines(vec![
+        "test line one".to_string(),
+        "test line two".to_string(),
+    ]);
 
     // Enter search mode
     editor
         .process_input(Input::Character('\x13'), false)
         .unwrap(); // Ctrl+S
     assert!(editor.search.mode);
-    assert_eq!(editor.status_message, "Search: ");
+    assert_eq!(editor.status_message, format!("Search: {OPTIONS}"));
 
     // Type a query
     editor.process_input(Input::Character('t'), false).unwrap();
     assert_eq!(editor.search.query, "t");
-    assert_eq!(editor.status_message, "Search: t");
+    assert_eq!(editor.status_message, format!("Search: t{OPTIONS}"));
     editor.process_input(Input::Character('e'), false).unwrap();
     assert_eq!(editor.search.query, "te");
-    assert_eq!(editor.status_message, "Search: te");
+    assert_eq!(editor.status_message, format!("Search: te{OPTIONS}"));
 
     // Exit with Enter
     editor.process_input(Input::Character('\n'), false).unwrap();
@@ -31,19 +37,19 @@ fn test_editor_search_mode_enter_and_exit() {
 #[test]
 fn test_editor_search_mode_escape_exit() {
     let mut editor = Editor::new(None, None, None);
-    editor.document.lines = vec!["test line one".to_string()];
+    editor.document.set_lines(vec!["test line one".to_string()]);
 
     // Enter search mode
     editor
         .process_input(Input::Character('\x13'), false)
         .unwrap(); // Ctrl+S
     assert!(editor.search.mode);
-    assert_eq!(editor.status_message, "Search: ");
+    assert_eq!(editor.status_message, format!("Search: {OPTIONS}"));
 
     // Type a query
     editor.process_input(Input::Character('e'), false).unwrap();
     assert_eq!(editor.search.query, "e");
-    assert_eq!(editor.status_message, "Search: e");
+    assert_eq!(editor.status_message, format!("Search: e{OPTIONS}"));
 
     // Exit with Escape
     editor
@@ -57,10 +63,10 @@ fn test_editor_search_mode_escape_exit() {
 #[test]
 fn test_editor_search_next_and_previous_match() {
     let mut editor = Editor::new(None, None, None);
-    editor.document.lines = vec![
+    editor.document.set_lines(vec![
         "apple banana apple".to_string(),
         "orange apple grape".to_string(),
-    ];
+    ]);
 
     // Enter search mode and search for "apple"
     editor
@@ -114,7 +120,9 @@ fn test_editor_search_next_and_previous_match() {
 #[test]
 fn test_editor_search_no_match() {
     let mut editor = Editor::new(None, None, None);
-    editor.document.lines = vec!["line one".to_string(), "line two".to_string()];
+    editor
+        .document
+        .set_lines(vec!["line one".to_string(), "line two".to_string()]);
 
     // Enter search mode and search for "xyz" (no match)
     editor
@@ -125,7 +133,10 @@ fn test_editor_search_no_match() {
     editor.process_input(Input::Character('z'), false).unwrap();
 
     assert_eq!(editor.search.query, "xyz");
-    assert_eq!(editor.status_message, "Search: xyz (No match)");
+    assert_eq!(
+        editor.status_message,
+        format!("Search: xyz (No match){OPTIONS}")
+    );
     assert!(editor.search.results.is_empty());
     assert_eq!(editor.search.current_match_index, None);
 
@@ -138,23 +149,23 @@ fn test_editor_search_no_match() {
 #[test]
 fn test_editor_search_empty_query() {
     let mut editor = Editor::new(None, None, None);
-    editor.document.lines = vec!["some text".to_string()];
+    editor.document.set_lines(vec!["some text".to_string()]);
 
     // Enter search mode
     editor
         .process_input(Input::Character('\x13'), false)
         .unwrap(); // Ctrl+S
-    assert_eq!(editor.status_message, "Search: ");
+    assert_eq!(editor.status_message, format!("Search: {OPTIONS}"));
 
     // Backspace to empty query
     editor.process_input(Input::Character('e'), false).unwrap();
     assert_eq!(editor.search.query, "e");
-    assert_eq!(editor.status_message, "Search: e");
+    assert_eq!(editor.status_message, format!("Search: e{OPTIONS}"));
     editor
         .process_input(Input::Character('\x7f'), false)
         .unwrap();
     assert_eq!(editor.search.query, "");
-    assert_eq!(editor.status_message, "Search: ");
+    assert_eq!(editor.status_message, format!("Search: {OPTIONS}"));
     assert!(editor.search.results.is_empty());
     assert_eq!(editor.search.current_match_index, None);
 
@@ -163,3 +174,146 @@ fn test_editor_search_empty_query() {
     assert!(!editor.search.mode);
     assert_eq!(editor.status_message, "");
 }
+
+fn search_for(editor: &mut Editor, query: &str) {
+    editor
+        .process_input(Input::Character('\x13'), false)
+        .unwrap();
+    for c in query.chars() {
+        editor.process_input(Input::Character(c), false).unwrap();
+    }
+}
+
+fn match_starts(editor: &Editor) -> Vec<(usize, usize)> {
+    editor
+        .search
+        .results
+        .iter()
+        .map(|&(row, col, _)| (row, col))
+        .collect()
+}
+
+#[test]
+fn test_find_matches() {
+    assert_eq!(
+        find_matches("Foo foo FOO", "foo", false, false),
+        vec![(4, 3)]
+    );
+    assert_eq!(
+        find_matches("Foo foo FOO", "foo", true, false),
+        vec![(0, 3), (4, 3), (8, 3)]
+    );
+    assert_eq!(
+        find_matches("cat concat cat_x cat.", "cat", false, true),
+        vec![(0, 3), (17, 3)]
+    );
+    // Letters whose lower case is a different length still line up
+    assert_eq!(
+        find_matches("Ärger ärger", "ärger", true, true),
+        vec![(0, 6), (7, 6)]
+    );
+}
+
+#[test]
+fn test_smart_case() {
+    let mut editor = Editor::new(None, None, None);
+    editor
+        .document
+        .set_lines(vec!["Apple apple APPLE".to_string()]);
+
+    search_for(&mut editor, "apple");
+    assert_eq!(match_starts(&editor), vec![(0, 0), (0, 6), (0, 12)]);
+
+    // An upper-case letter makes the search case-sensitive
+    editor
+        .process_input(Input::Character('\x7f'), false)
+        .unwrap();
+    editor
+        .process_input(Input::Character('\x7f'), false)
+        .unwrap();
+    editor
+        .process_input(Input::Character('\x7f'), false)
+        .unwrap();
+    editor
+        .process_input(Input::Character('\x7f'), false)
+        .unwrap();
+    editor
+        .process_input(Input::Character('\x7f'), false)
+        .unwrap();
+    for c in "Apple".chars() {
+        editor.process_input(Input::Character(c), false).unwrap();
+    }
+    assert_eq!(match_starts(&editor), vec![(0, 0)]);
+}
+
+#[test]
+fn test_case_mode_toggle_mid_search() {
+    let mut editor = Editor::new(None, None, None);
+    editor
+        .document
+        .set_lines(vec!["Apple apple APPLE".to_string()]);
+
+    search_for(&mut editor, "apple");
+    editor.process_input(Input::Character('c'), true).unwrap();
+    assert_eq!(editor.search.case_mode, CaseMode::Sensitive);
+    assert_eq!(match_starts(&editor), vec![(0, 6)]);
+    assert_eq!(editor.cursor_pos(), (6, 0));
+    assert_eq!(
+        editor.status_message,
+        "Search: apple  [M-c exact, M-w parts]"
+    );
+
+    editor.process_input(Input::Character('c'), true).unwrap();
+    assert_eq!(editor.search.case_mode, CaseMode::Insensitive);
+    editor
+        .process_input(Input::Character('\x7f'), false)
+        .unwrap();
+    editor
+        .process_input(Input::Character('\x7f'), false)
+        .unwrap();
+    editor
+        .process_input(Input::Character('\x7f'), false)
+        .unwrap();
+    for c in "PLE".chars() {
+        editor.process_input(Input::Character(c), false).unwrap();
+    }
+    assert_eq!(match_starts(&editor), vec![(0, 0), (0, 6), (0, 12)]);
+
+    editor.process_input(Input::Character('c'), true).unwrap();
+    assert_eq!(editor.search.case_mode, CaseMode::Smart);
+    // The query has upper-case letters, so nothing matches it exactly
+    assert!(editor.search.results.is_empty());
+    assert_eq!(
+        editor.status_message,
+        "Search: apPLE (No match)  [M-c smart, M-w parts]"
+    );
+}
+
+#[test]
+fn test_whole_word_toggle() {
+    let mut editor = Editor::new(None, None, None);
+    editor
+        .document
+        .set_lines(vec!["concat cat".to_string(), "cats and cat.".to_string()]);
+
+    search_for(&mut editor, "cat");
+    assert_eq!(match_starts(&editor), vec![(0, 3), (0, 7), (1, 0), (1, 9)]);
+
+    editor.process_input(Input::Character('w'), true).unwrap();
+    assert!(editor.search.whole_word);
+    assert_eq!(match_starts(&editor), vec![(0, 7), (1, 9)]);
+    assert_eq!(editor.status_message, "Search: cat  [M-c smart, M-w words]");
+
+    editor
+        .process_input(Input::Character('\x13'), false)
+        .unwrap();
+    assert_eq!(editor.cursor_pos(), (9, 1));
+
+    // The options are kept for the next search
+    editor.process_input(Input::Character('\n'), false).unwrap();
+    search_for(&mut editor, "cat");
+    assert_eq!(match_starts(&editor), vec![(0, 7), (1, 9)]);
+    editor.process_input(Input::Character('w'), true).unwrap();
+    assert!(!editor.search.whole_word);
+    assert_eq!(editor.search.results.len(), 4);
+}
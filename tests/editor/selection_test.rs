@@ -10,7 +10,7 @@ fn editor_with_clipboard_disabled() -> Editor {
 #[test]
 fn test_set_marker() {
     let mut editor = Editor::new(None, None, None);
-    editor.document.lines = vec!["hello world".to_string()];
+    editor.document.set_lines(vec!["hello world".to_string()]);
     editor.set_cursor_pos(0, 0);
 
     // Set marker at (0,0)
@@ -23,7 +23,7 @@ fn test_set_marker() {
 #[test]
 fn test_clear_marker() {
     let mut editor = Editor::new(None, None, None);
-    editor.document.lines = vec!["hello world".to_string()];
+    editor.document.set_lines(vec!["hello world".to_string()]);
     editor.set_cursor_pos(0, 0);
     editor.selection.marker_pos = Some((0, 0)); // Manually set marker for testing
 
@@ -37,7 +37,7 @@ fn test_clear_marker() {
 #[test]
 fn test_cut_selection() {
     let mut editor = editor_with_clipboard_disabled();
-    editor.document.lines = vec!["hello world".to_string()];
+    editor.document.set_lines(vec!["hello world".to_string()]);
     editor.set_cursor_pos(11, 0); // Cursor at end of "world"
     editor.selection.marker_pos = Some((6, 0)); // Marker at 'w'
 
@@ -45,7 +45,7 @@ fn test_cut_selection() {
     editor
         .process_input(Input::Character('\x17'), true)
         .unwrap(); // Ctrl-W
-    assert_eq!(editor.document.lines[0], "hello ");
+    assert_eq!(editor.document.lines()[0], "hello ");
     assert_eq!(editor.clipboard.kill_buffer, "world");
     assert_eq!(editor.selection.marker_pos, None); // Marker should be cleared
     assert_eq!(editor.cursor_pos(), (6, 0)); // Cursor should be at the start of the cut
@@ -54,13 +54,13 @@ fn test_cut_selection() {
 #[test]
 fn test_copy_selection() {
     let mut editor = editor_with_clipboard_disabled();
-    editor.document.lines = vec!["hello world".to_string()];
+    editor.document.set_lines(vec!["hello world".to_string()]);
     editor.set_cursor_pos(11, 0); // Cursor at end of "world"
     editor.selection.marker_pos = Some((6, 0)); // Marker at 'w'
 
     // Copy "world"
     editor.process_input(Input::Character('w'), true).unwrap(); // Option-W
-    assert_eq!(editor.document.lines[0], "hello world"); // Document unchanged
+    assert_eq!(editor.document.lines()[0], "hello world"); // Document unchanged
     assert_eq!(editor.clipboard.kill_buffer, "world");
     assert_eq!(editor.selection.marker_pos, None); // Marker should be cleared
     assert_eq!(editor.cursor_pos(), (11, 0)); // Cursor should remain
@@ -69,14 +69,14 @@ fn test_copy_selection() {
 #[test]
 fn test_highlight_selection() {
     let mut editor = Editor::new(None, None, None);
-    editor.document.lines = vec!["hello world".to_string()];
+    editor.document.set_lines(vec!["hello world".to_string()]);
     editor.set_cursor_pos(0, 0);
     editor.selection.marker_pos = Some((6, 0)); // Marker at 'w'
 
     // Check if selection is active
     assert!(editor.selection.is_selection_active());
     assert_eq!(
-        editor.selection.get_selection_range(editor.cursor_pos()),
+        editor.selection.get_selection_range(&editor.cursor),
         Some(((0, 0), (6, 0)))
     ); // Cursor to marker
 }
@@ -84,7 +84,7 @@ fn test_highlight_selection() {
 #[test]
 fn test_cut_selection_from_start_of_line() {
     let mut editor = editor_with_clipboard_disabled();
-    editor.document.lines = vec!["hello world".to_string()];
+    editor.document.set_lines(vec!["hello world".to_string()]);
     editor.set_cursor_pos(5, 0); // Cursor at end of "hello"
     editor.selection.marker_pos = Some((0, 0)); // Marker at 'h'
 
@@ -92,7 +92,7 @@ fn test_cut_selection_from_start_of_line() {
     editor
         .process_input(Input::Character('\x17'), true)
         .unwrap(); // Ctrl-W
-    assert_eq!(editor.document.lines[0], " world");
+    assert_eq!(editor.document.lines()[0], " world");
     assert_eq!(editor.clipboard.kill_buffer, "hello");
     assert_eq!(editor.selection.marker_pos, None);
     assert_eq!(editor.cursor_pos(), (0, 0));
@@ -101,7 +101,7 @@ fn test_cut_selection_from_start_of_line() {
 #[test]
 fn test_cut_selection_to_end_of_line() {
     let mut editor = editor_with_clipboard_disabled();
-    editor.document.lines = vec!["hello world".to_string()];
+    editor.document.set_lines(vec!["hello world".to_string()]);
     editor.set_cursor_pos(11, 0); // Cursor at end of "world"
     editor.selection.marker_pos = Some((6, 0)); // Marker at 'w'
 
@@ -109,7 +109,7 @@ fn test_cut_selection_to_end_of_line() {
     editor
         .process_input(Input::Character('\x17'), true)
         .unwrap(); // Ctrl-W
-    assert_eq!(editor.document.lines[0], "hello ");
+    assert_eq!(editor.document.lines()[0], "hello ");
     assert_eq!(editor.clipboard.kill_buffer, "world");
     assert_eq!(editor.selection.marker_pos, None);
     assert_eq!(editor.cursor_pos(), (6, 0));
@@ -118,7 +118,7 @@ fn test_cut_selection_to_end_of_line() {
 #[test]
 fn test_cut_entire_line() {
     let mut editor = editor_with_clipboard_disabled();
-    editor.document.lines = vec!["hello world".to_string()];
+    editor.document.set_lines(vec!["hello world".to_string()]);
     editor.set_cursor_pos(11, 0); // Cursor at end of line
     editor.selection.marker_pos = Some((0, 0)); // Marker at start of line
 
@@ -126,7 +126,7 @@ fn test_cut_entire_line() {
     editor
         .process_input(Input::Character('\x17'), true)
         .unwrap(); // Ctrl-W
-    assert_eq!(editor.document.lines, vec!["".to_string()]); // Line should be empty
+    assert_eq!(editor.document.lines(), vec!["".to_string()]); // Line should be empty
     assert_eq!(editor.clipboard.kill_buffer, "hello world");
     assert_eq!(editor.selection.marker_pos, None);
     assert_eq!(editor.cursor_pos(), (0, 0));
@@ -135,11 +135,11 @@ fn test_cut_entire_line() {
 #[test]
 fn test_cut_multiple_lines() {
     let mut editor = editor_with_clipboard_disabled();
-    editor.document.lines = vec![
+    editor.document.set_lines(vec![
         "line one".to_string(),
         "line two".to_string(),
         "line three".to_string(),
-    ];
+    ]);
     editor.set_cursor_pos(10, 2); // Cursor at end of "line three"
     editor.selection.marker_pos = Some((0, 0)); // Marker at 'l' in "line one"
 
@@ -147,7 +147,7 @@ fn test_cut_multiple_lines() {
     editor
         .process_input(Input::Character('\x17'), true)
         .unwrap(); // Ctrl-W
-    assert_eq!(editor.document.lines, vec!["".to_string()]); // All lines should be cut
+    assert_eq!(editor.document.lines(), vec!["".to_string()]); // All lines should be cut
     assert_eq!(
         editor.clipboard.kill_buffer,
         "line one\nline two\nline three"
@@ -159,7 +159,7 @@ fn test_cut_multiple_lines() {
 #[test]
 fn test_cut_selection_marker_after_cursor() {
     let mut editor = editor_with_clipboard_disabled();
-    editor.document.lines = vec!["hello world".to_string()];
+    editor.document.set_lines(vec!["hello world".to_string()]);
     editor.set_cursor_pos(6, 0); // Cursor at 'w'
     editor.selection.marker_pos = Some((11, 0)); // Marker at end of "world"
 
@@ -167,7 +167,7 @@ fn test_cut_selection_marker_after_cursor() {
     editor
         .process_input(Input::Character('\x17'), true)
         .unwrap(); // Ctrl-W
-    assert_eq!(editor.document.lines[0], "hello ");
+    assert_eq!(editor.document.lines()[0], "hello ");
     assert_eq!(editor.clipboard.kill_buffer, "world");
     assert_eq!(editor.selection.marker_pos, None);
     assert_eq!(editor.cursor_pos(), (6, 0));
@@ -0,0 +1,88 @@
+use dmacs::editor::actions::Action;
+use dmacs::editor::{Editor, EditorMode};
+use dmacs::persistence;
+use pancurses::Input;
+use std::fs;
+use tempfile::TempDir;
+
+fn open(dir: &TempDir, filename: &str) -> Editor {
+    let mut editor = Editor::new(Some(filename.to_string()), None, None);
+    editor.sessions.base_dir = Some(dir.path().to_path_buf());
+    editor.update_screen_size(20, 80);
+    editor
+}
+
+fn load(dir: &TempDir, name: &str) -> persistence::Session {
+    let path = persistence::session_path(Some(dir.path().to_path_buf()), name).unwrap();
+    persistence::load_session(&path).unwrap()
+}
+
+#[test]
+fn test_save_and_restore_session() {
+    let dir = TempDir::new().unwrap();
+    let notes = dir.path().join("notes.md").to_string_lossy().to_string();
+    fs::write(&notes, "# One\ntext\n# Two\nmore text\n").unwrap();
+
+    let mut editor = open(&dir, &notes);
+    editor.execute_action(Action::ToggleFold).unwrap();
+    editor.set_cursor_pos(5, 3);
+    editor.execute_action(Action::ToggleHintBar).unwrap();
+    editor.execute_action(Action::SaveSession).unwrap();
+    assert_eq!(editor.mode, EditorMode::Minibuffer);
+    for c in "project".chars() {
+        editor.process_input(Input::Character(c), false).unwrap();
+    }
+    editor.process_input(Input::Character('\n'), false).unwrap();
+    assert_eq!(editor.status_message, "Session project saved.");
+
+    let session = load(&dir, "project");
+    assert_eq!(session.file_path.as_deref(), Some(notes.as_str()));
+    assert_eq!((session.cursor_x, session.cursor_y), (5, 3));
+    assert_eq!(session.folds, vec![0]);
+    assert!(!session.scratch);
+
+    let mut restored = open(&dir, &notes);
+    let hint_bar = restored.settings.hint_bar;
+    restored.restore_session("project", Some(&session));
+    assert_eq!(restored.cursor_pos(), (5, 3));
+    assert!(restored.folds.is_folded(0));
+    assert_eq!(restored.settings.hint_bar, !hint_bar);
+
+    // Saving again offers the session's name
+    restored.execute_action(Action::SaveSession).unwrap();
+    assert_eq!(restored.minibuffer.input, "project");
+}
+
+#[test]
+fn test_session_over_scratch_buffer() {
+    let dir = TempDir::new().unwrap();
+    let notes = dir.path().join("notes.md").to_string_lossy().to_string();
+    fs::write(&notes, "one\ntwo\n").unwrap();
+
+    let mut editor = open(&dir, &notes);
+    editor.set_cursor_pos(2, 1);
+    editor.toggle_scratch_buffer();
+    editor.save_session_as("scratch");
+
+    let session = load(&dir, "scratch");
+    assert_eq!(session.file_path.as_deref(), Some(notes.as_str()));
+    assert_eq!((session.cursor_x, session.cursor_y), (2, 1));
+    assert!(session.scratch);
+
+    let mut restored = open(&dir, &notes);
+    restored.restore_session("scratch", Some(&session));
+    assert!(restored.is_scratch_buffer());
+    restored.toggle_scratch_buffer();
+    assert_eq!(restored.cursor_pos(), (2, 1));
+}
+
+#[test]
+fn test_invalid_session_name() {
+    let dir = TempDir::new().unwrap();
+    let notes = dir.path().join("notes.md").to_string_lossy().to_string();
+    let mut editor = open(&dir, &notes);
+    editor.save_session_as("../elsewhere");
+    assert_eq!(editor.status_message, "Invalid session name: ../elsewhere");
+    assert!(persistence::is_valid_session_name("work"));
+    assert!(!persistence::is_valid_session_name(""));
+}
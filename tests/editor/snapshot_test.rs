@@ -0,0 +1,344 @@
+use crate::editor_with_lines;
+use dmacs::editor::Editor;
+use dmacs::editor::actions::Action;
+use dmacs::editor::ui::STATUS_BAR_HEIGHT;
+use dmacs::screen::virtual_screen::VirtualScreen;
+use pancurses::{A_REVERSE, Input};
+use std::fs;
+use std::path::Path;
+
+fn render(editor: &mut Editor, rows: usize, cols: usize) -> VirtualScreen {
+    let screen = VirtualScreen::new(rows, cols);
+    editor.update_screen_size(rows, cols);
+    editor.draw(&screen);
+    screen
+}
+
+// Compares the screen, with its styles, against tests/snapshots/<name>.txt.
+// Run with UPDATE_SNAPSHOTS=1 to write the files after an intended change,
+// then review their diff.
+fn assert_golden(name: &str, screen: &VirtualScreen) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{name}.txt"));
+    let actual = format!("{}\n", screen.styled_text());
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, &actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!(
+            "No golden file {}; run with UPDATE_SNAPSHOTS=1",
+            path.display()
+        )
+    });
+    assert_eq!(
+        actual,
+        expected,
+        "the screen differs from {}",
+        path.display()
+    );
+}
+
+#[test]
+fn test_scrolled_cursor_line_starts_with_ellipsis() {
+    let mut editor = editor_with_lines(&["0123456789abcdefghijklmnopqrstuvwxyz", "short"]);
+    editor.set_cursor_pos(30, 0);
+    let screen = render(&mut editor, 5, 20);
+    assert_eq!(
+        screen.text(),
+        "[No Name]* - 2 lines\n\n…klmnopqrstuvwxyz\nshort\n"
+    );
+    assert_eq!(screen.cursor(), (2, 11));
+}
+
+#[test]
+fn test_wide_chars_at_right_edge() {
+    let mut editor = editor_with_lines(&["x", "あいうえおかきくけこさしすせそ", "next"]);
+    editor.set_cursor_pos(0, 1);
+    // The eleventh character does not fit in the last column and must not wrap
+    let screen = render(&mut editor, 5, 21);
+    assert_eq!(screen.row_text(3), "あいうえおかきくけこ");
+    assert_eq!(screen.row_text(4), "next");
+    assert_eq!(screen.cell(3, 20).ch, ' ');
+}
+
+#[test]
+fn test_wide_chars_scrolled_past_half_a_character() {
+    let mut editor = editor_with_lines(&["x", "あいうえおかきくけこさしすせそ"]);
+    editor.set_cursor_pos(27, 1);
+    let screen = render(&mut editor, 5, 21);
+    // A character cut in half by the scroll is replaced by a space
+    assert_eq!(screen.row_text(3), "… おかきくけこさしす");
+    assert_eq!(screen.cursor(), (3, 12));
+
+    editor.go_to_end_of_line();
+    let screen = render(&mut editor, 5, 21);
+    assert_eq!(screen.row_text(3), "… さしすせそ");
+    assert_eq!(screen.cursor(), (3, 12));
+}
+
+#[test]
+fn test_horizontal_scroll_pins_cursor_to_right_margin() {
+    let line =
+        "This is a very long line of text to test the horizontal scrolling behavior of the editor.";
+    let mut editor = editor_with_lines(&[line]);
+    let (rows, cols) = (10, 40);
+    // The cursor stays this far from the right edge while scrolling
+    let pinned_col = cols - 10;
+    let line_row = STATUS_BAR_HEIGHT;
+
+    let move_count = 45;
+    for _ in 0..move_count {
+        editor.process_input(Input::KeyRight, false).unwrap();
+    }
+    let screen = render(&mut editor, rows, cols);
+    assert_eq!(editor.cursor_pos().0, move_count);
+    let col_offset = move_count - pinned_col;
+    assert_eq!(editor.scroll.col_offset, col_offset);
+    // The ellipsis marking the scrolled line takes the first column
+    assert_eq!(screen.cursor(), (line_row, pinned_col + 1));
+    assert_eq!(
+        screen.row_text(line_row),
+        format!("…{}", &line[col_offset..col_offset + cols - 1])
+    );
+
+    // Moving left keeps the cursor pinned while the line is scrolled
+    editor.process_input(Input::KeyLeft, false).unwrap();
+    let screen = render(&mut editor, rows, cols);
+    assert_eq!(screen.cursor(), (line_row, pinned_col + 1));
+
+    // Until the cursor is left of the margin and the line scrolls back
+    for _ in 0..move_count - pinned_col {
+        editor.process_input(Input::KeyLeft, false).unwrap();
+    }
+    assert_eq!(editor.cursor_pos().0, pinned_col - 1);
+    let screen = render(&mut editor, rows, cols);
+    assert_eq!(editor.scroll.col_offset, 0);
+    assert_eq!(screen.cursor(), (line_row, pinned_col - 1));
+    assert_eq!(screen.row_text(line_row), &line[..cols]);
+}
+
+#[test]
+fn test_task_panel() {
+    let mut editor =
+        editor_with_lines(&["", "# Notes", "- [ ] first", "- [x] done", "  - [ ] nested"]);
+    for c in "/task\n".chars() {
+        editor.process_input(Input::Character(c), false).unwrap();
+    }
+    let screen = render(&mut editor, 10, 120);
+    let rows: Vec<String> = (2..9).map(|row| screen.row_text(row)).collect();
+    assert_eq!(
+        rows,
+        vec![
+            "",
+            "# Notes",
+            "- [ ] first",
+            "",
+            "- [ ] first",
+            "  - [ ] nested",
+            ""
+        ]
+    );
+    assert_eq!(
+        screen.columns_with(6, A_REVERSE),
+        (0..11).collect::<Vec<_>>()
+    );
+    assert!(screen.columns_with(7, A_REVERSE).is_empty());
+}
+
+#[test]
+fn test_hint_bar_replaces_status_line_rule() {
+    let mut editor = editor_with_lines(&["text"]);
+    editor.settings.hint_bar = true;
+    let screen = render(&mut editor, 4, 40);
+    // Column 0 keeps the rule
+    assert_eq!(screen.row_text(1), "  M-s save · C-x exit · C-_ undo");
+
+    editor.execute_action(Action::ToggleHintBar).unwrap();
+    let screen = render(&mut editor, 4, 40);
+    assert_eq!(screen.row_text(1), "");
+}
+
+#[test]
+fn test_image_placeholder_away_from_cursor_line() {
+    let image = format!("- ![chart](data:image/png;base64,{})", "A".repeat(200));
+    let mut editor = editor_with_lines(&["notes", &image, "end"]);
+    let screen = render(&mut editor, 6, 30);
+    assert_eq!(screen.row_text(3), "- [img: chart]");
+    assert_eq!(screen.row_text(4), "end");
+
+    editor.set_cursor_pos(0, 1);
+    let screen = render(&mut editor, 6, 30);
+    assert_eq!(screen.row_text(3), "- ![chart](data:image/png;base");
+}
+
+#[test]
+fn test_command_palette_shows_bound_keys() {
+    let mut editor = editor_with_lines(&["text"]);
+    editor.execute_action(Action::CommandPalette).unwrap();
+    for c in "togglecheck".chars() {
+        editor.process_input(Input::Character(c), false).unwrap();
+    }
+    let screen = render(&mut editor, 10, 40);
+    assert!(screen.row_text(2).contains("Command: togglecheck"));
+    let first = screen.row_text(3);
+    assert!(first.contains("ToggleCheckbox"));
+    assert!(first.trim_end().ends_with("C-t"));
+}
+
+#[test]
+fn test_status_bar_shows_footnote_under_cursor() {
+    let mut editor = editor_with_lines(&["See this[^1].", "", "[^1]: The source."]);
+    editor.set_cursor_pos(9, 0);
+    let screen = render(&mut editor, 6, 60);
+    assert!(screen.row_text(0).ends_with("[^1]: The source."));
+
+    editor.set_cursor_pos(0, 0);
+    let screen = render(&mut editor, 6, 60);
+    assert!(!screen.row_text(0).contains("The source."));
+}
+
+#[test]
+fn test_task_selection_shows_due_dates() {
+    let mut editor =
+        editor_with_lines(&["/task", "- [ ] undated", "- [ ] overdue @due(2000-01-01)"]);
+    editor.set_cursor_pos(5, 0);
+    editor.insert_newline().unwrap();
+    let screen = render(&mut editor, 10, 60);
+    assert_eq!(
+        screen.row_text(6),
+        "2000-01-01  - [ ] overdue @due(2000-01-01)"
+    );
+    assert_eq!(screen.row_text(7), "            - [ ] undated");
+}
+
+#[test]
+fn test_overdue_date_is_highlighted() {
+    let mut editor = editor_with_lines(&["text", "- [ ] pay @due(2000-01-01)"]);
+    let screen = render(&mut editor, 5, 40);
+    assert_eq!(screen.row_text(3), "- [ ] pay @due(2000-01-01)");
+    let attrs = |col| screen.cell(3, col).attributes;
+    assert_ne!(
+        attrs(12) & pancurses::A_COLOR,
+        attrs(4) & pancurses::A_COLOR
+    );
+}
+
+#[test]
+fn test_show_whitespace_marks_tabs_and_trailing_spaces() {
+    let mut editor = editor_with_lines(&["a\tb  ", "c d", ""]);
+    editor.set_cursor_pos(0, 2);
+    editor.execute_action(Action::ToggleWhitespace).unwrap();
+    let screen = render(&mut editor, 6, 20);
+    assert_eq!(screen.row_text(2), "a→  b··");
+    assert_eq!(screen.row_text(3), "c d");
+    assert_ne!(screen.cell(2, 5).attributes & A_REVERSE, 0);
+    assert_eq!(screen.cell(2, 4).attributes & A_REVERSE, 0);
+
+    editor.execute_action(Action::ToggleWhitespace).unwrap();
+    let screen = render(&mut editor, 6, 20);
+    assert_eq!(screen.row_text(2), "a   b");
+}
+
+#[test]
+fn test_toggle_ruler() {
+    let mut editor = editor_with_lines(&["text", ""]);
+    editor.execute_action(Action::ToggleRuler).unwrap();
+    assert_eq!(editor.status_message, "Ruler at column 80.");
+    let screen = render(&mut editor, 5, 100);
+    let ruler = screen.cell(2, 80).attributes;
+    assert_ne!(ruler, screen.cell(2, 79).attributes);
+    assert_eq!(screen.cell(4, 80).attributes, ruler);
+
+    editor.execute_action(Action::ToggleRuler).unwrap();
+    assert_eq!(editor.status_message, "Ruler off.");
+    let screen = render(&mut editor, 5, 100);
+    assert_eq!(screen.cell(2, 80).attributes, screen.cell(2, 79).attributes);
+}
+
+#[test]
+fn test_git_gutter_marks_changed_lines() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let git = |args: &[&str]| {
+        let output = std::process::Command::new("git")
+            .arg("-C")
+            .arg(dir.path())
+            .args([
+                "-c",
+                "user.name=dmacs",
+                "-c",
+                "user.email=dmacs@example.com",
+            ])
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+    };
+    git(&["init", "-q"]);
+    let path = dir.path().join("notes.md");
+    std::fs::write(&path, "one\ntwo\n").unwrap();
+    git(&["add", "notes.md"]);
+    git(&["commit", "-q", "-m", "notes"]);
+
+    let mut editor = Editor::new(Some(path.to_str().unwrap().to_string()), None, None);
+    editor.settings.git_gutter = true;
+    editor.set_cursor_pos(3, 1);
+    editor.execute_action(Action::InsertChar('!')).unwrap();
+    // The committed file is read in the background
+    editor.update_git_gutter();
+    while editor.git_gutter.is_reading() {
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        editor.update_git_gutter();
+    }
+    let screen = render(&mut editor, 5, 20);
+    assert_eq!(screen.row_text(2), " one");
+    assert_eq!(screen.row_text(3), "~two!");
+    assert_eq!(screen.cursor(), (3, 5));
+}
+
+#[test]
+fn test_golden_highlighting() {
+    let mut editor = editor_with_lines(&[
+        "# Plan",
+        "",
+        "Some **bold** text and a https://example.com link.",
+        "- [ ] open task",
+        "- [x] done task",
+        "TODO call back",
+        "<!-- a comment -->",
+    ]);
+    editor.set_cursor_pos(0, 3);
+    let screen = render(&mut editor, 10, 60);
+    assert_golden("highlighting", &screen);
+}
+
+#[test]
+fn test_golden_scrolling() {
+    let lines: Vec<String> = (1..=40).map(|n| format!("line {n}")).collect();
+    let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+    let mut editor = editor_with_lines(&lines);
+    editor.set_cursor_pos(2, 30);
+    let screen = render(&mut editor, 8, 30);
+    assert_golden("scrolling", &screen);
+}
+
+#[test]
+fn test_golden_search_overlay() {
+    let mut editor = editor_with_lines(&["apple pie", "banana", "apple tart"]);
+    editor.execute_action(Action::EnterSearchMode).unwrap();
+    for c in "apple".chars() {
+        editor.process_input(Input::Character(c), false).unwrap();
+    }
+    let screen = render(&mut editor, 6, 40);
+    assert_golden("search_overlay", &screen);
+}
+
+#[test]
+fn test_golden_outline_overlay() {
+    let mut editor = editor_with_lines(&["# One", "text", "## Two", "more", "# Three"]);
+    editor.execute_action(Action::ShowOutline).unwrap();
+    let screen = render(&mut editor, 8, 40);
+    assert_golden("outline_overlay", &screen);
+}
@@ -0,0 +1,257 @@
+use chrono::{Local, TimeZone};
+use dmacs::editor::Editor;
+use dmacs::editor::snippet::{Field, Snippet, expand, tab_stops};
+use pancurses::Input;
+use std::collections::HashMap;
+
+#[test]
+fn test_expand_placeholders() {
+    let now = Local.with_ymd_and_hms(2025, 6, 12, 9, 5, 0).unwrap();
+    let snippet = expand(
+        "# {file} {date}\n- {time} {cursor}notes {other}\n",
+        now,
+        Some("/notes/daily.md"),
+    );
+    assert_eq!(
+        snippet,
+        Snippet {
+            lines: vec![
+                "# daily 2025-06-12".to_string(),
+                "- 09:05 notes {other}".to_string(),
+            ],
+            cursor: (8, 1),
+        }
+    );
+
+    // Without `{cursor}`, the cursor goes to the end
+    let snippet = expand("a\nbc", now, None);
+    assert_eq!(snippet.cursor, (2, 1));
+}
+
+fn editor_with_templates(lines: &[&str]) -> Editor {
+    let mut editor = Editor::new(None, None, None);
+    editor
+        .document
+        .set_lines(lines.iter().map(|s| s.to_string()).collect());
+    let mut templates = HashMap::new();
+    templates.insert("task".to_string(), "- [ ] {cursor}\n  - notes".to_string());
+    editor.settings.templates = Some(templates);
+    editor
+}
+
+#[test]
+fn test_template_command_replaces_line() {
+    let mut editor = editor_with_templates(&["before", "/template task", "after"]);
+    editor.set_cursor_pos(14, 1);
+    editor.process_input(Input::Character('\n'), false).unwrap();
+
+    assert_eq!(
+        editor.document.lines(),
+        vec!["before", "- [ ] ", "  - notes", "after"]
+    );
+    assert_eq!(editor.cursor_pos(), (6, 1));
+    assert_eq!(editor.status_message, "/template task");
+
+    editor.undo();
+    assert_eq!(
+        editor.document.lines(),
+        vec!["before", "/template task", "after"]
+    );
+}
+
+#[test]
+fn test_unknown_template() {
+    let mut editor = editor_with_templates(&["/template nope"]);
+    editor.set_cursor_pos(14, 0);
+    editor.process_input(Input::Character('\n'), false).unwrap();
+    assert_eq!(editor.document.lines(), vec!["/template nope"]);
+    assert_eq!(editor.status_message, "No template named nope.");
+}
+
+fn editor_with_snippets(content: &str) -> Editor {
+    let mut editor = Editor::new(None, None, None);
+    editor
+        .document
+        .set_lines(content.lines().map(|s| s.to_string()).collect());
+    if editor.document.lines().is_empty() {
+        editor.document.set_lines(vec![String::new()]);
+    }
+    editor.settings.snippets = Some(HashMap::from([
+        ("link".to_string(), "[${1:text}](${2:url})$0".to_string()),
+        (
+            "post".to_string(),
+            "---\ntitle: $1\n---\n# $1\n$0\n".to_string(),
+        ),
+        ("sig".to_string(), "-- {file}".to_string()),
+        ("!img".to_string(), "![$1]($2)".to_string()),
+    ]));
+    editor
+}
+
+fn press_expand_key(editor: &mut Editor) {
+    editor.process_input(Input::Character('\''), true).unwrap();
+}
+
+fn type_text(editor: &mut Editor, text: &str) {
+    for c in text.chars() {
+        editor.process_input(Input::Character(c), false).unwrap();
+    }
+}
+
+fn tab(editor: &mut Editor) {
+    editor.process_input(Input::Character('\t'), false).unwrap();
+}
+
+#[test]
+fn test_tab_stops_are_taken_out_of_the_snippet() {
+    let mut snippet = Snippet {
+        lines: vec![
+            "[${1:text}](${2:url}) $$ $x".to_string(),
+            "$1{}".to_string(),
+        ],
+        cursor: (3, 1),
+    };
+    let fields = tab_stops(&mut snippet);
+    assert_eq!(snippet.lines, vec!["[text](url) $$ $x", "{}"]);
+    assert_eq!(
+        fields,
+        vec![
+            Field {
+                number: 1,
+                y: 0,
+                start: 1,
+                end: 5
+            },
+            Field {
+                number: 2,
+                y: 0,
+                start: 7,
+                end: 10
+            },
+            Field {
+                number: 1,
+                y: 1,
+                start: 0,
+                end: 0
+            },
+            // Without a `$0` the snippet is left where its cursor goes
+            Field {
+                number: 0,
+                y: 1,
+                start: 1,
+                end: 1
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_expand_moves_through_the_fields() {
+    let mut editor = editor_with_snippets("See link");
+    editor.set_cursor_pos(8, 0);
+    press_expand_key(&mut editor);
+    assert_eq!(editor.document.lines()[0], "See [text](url)");
+    assert_eq!(editor.cursor_pos(), (9, 0));
+
+    type_text(&mut editor, "s");
+    tab(&mut editor);
+    assert_eq!(editor.cursor_pos(), (15, 0));
+    editor
+        .process_input(Input::Character('\x7f'), false)
+        .unwrap();
+    type_text(&mut editor, "i");
+    assert_eq!(editor.document.lines()[0], "See [texts](uri)");
+
+    editor.process_input(Input::KeyBTab, false).unwrap();
+    assert_eq!(editor.cursor_pos(), (10, 0));
+    tab(&mut editor);
+    tab(&mut editor);
+    assert_eq!(editor.cursor_pos(), (16, 0));
+    assert!(editor.snippet_stops.is_none());
+
+    // Tab indents again once the snippet is left
+    tab(&mut editor);
+    assert!(editor.document.lines()[0].starts_with("  See"));
+}
+
+#[test]
+fn test_mirrored_fields_follow_typing() {
+    let mut editor = editor_with_snippets("  post");
+    editor.set_cursor_pos(6, 0);
+    press_expand_key(&mut editor);
+    assert_eq!(
+        editor.document.lines(),
+        vec!["  ---", "  title: ", "  ---", "  # ", "  "]
+    );
+    assert_eq!(editor.cursor_pos(), (9, 1));
+
+    type_text(&mut editor, "Hello");
+    assert_eq!(editor.document.lines()[1], "  title: Hello");
+    assert_eq!(editor.document.lines()[3], "  # Hello");
+    editor
+        .process_input(Input::Character('\x7f'), false)
+        .unwrap();
+    assert_eq!(editor.document.lines()[3], "  # Hell");
+    assert_eq!(editor.cursor_pos(), (13, 1));
+
+    tab(&mut editor);
+    assert_eq!(editor.cursor_pos(), (2, 4));
+    assert!(editor.snippet_stops.is_none());
+}
+
+#[test]
+fn test_expansion_is_one_undo_step() {
+    let mut editor = editor_with_snippets("link");
+    editor.set_cursor_pos(4, 0);
+    press_expand_key(&mut editor);
+    assert_eq!(editor.document.lines()[0], "[text](url)");
+    editor.undo();
+    assert_eq!(editor.document.lines()[0], "link");
+    assert_eq!(editor.cursor_pos(), (4, 0));
+}
+
+#[test]
+fn test_longest_trigger_ending_at_the_cursor() {
+    let mut editor = editor_with_snippets("(!img");
+    editor.set_cursor_pos(5, 0);
+    press_expand_key(&mut editor);
+    assert_eq!(editor.document.lines()[0], "(![]()");
+    assert_eq!(editor.cursor_pos(), (3, 0));
+}
+
+#[test]
+fn test_snippet_without_fields() {
+    let mut editor = editor_with_snippets("sig");
+    editor.set_cursor_pos(3, 0);
+    press_expand_key(&mut editor);
+    assert_eq!(editor.document.lines()[0], "-- ");
+    assert_eq!(editor.cursor_pos(), (3, 0));
+    assert!(editor.snippet_stops.is_none());
+}
+
+#[test]
+fn test_editing_outside_the_field_ends_the_snippet() {
+    let mut editor = editor_with_snippets("link");
+    editor.set_cursor_pos(4, 0);
+    press_expand_key(&mut editor);
+    editor
+        .process_input(Input::Character('\x05'), false)
+        .unwrap();
+    assert!(editor.snippet_stops.is_some());
+    type_text(&mut editor, "!");
+    assert!(editor.snippet_stops.is_none());
+}
+
+#[test]
+fn test_unknown_trigger() {
+    let mut editor = editor_with_snippets("nothing");
+    editor.set_cursor_pos(7, 0);
+    press_expand_key(&mut editor);
+    assert_eq!(editor.status_message, "No snippet for nothing.");
+    editor.set_cursor_pos(0, 0);
+    press_expand_key(&mut editor);
+    assert_eq!(
+        editor.status_message,
+        "No snippet trigger before the cursor."
+    );
+}
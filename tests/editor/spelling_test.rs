@@ -0,0 +1,77 @@
+use dmacs::editor::actions::Action;
+use dmacs::editor::{Editor, EditorMode};
+use dmacs::spellcheck::SpellChecker;
+use pancurses::Input;
+use tempfile::TempDir;
+
+fn editor_with_text(lines: &[&str]) -> Editor {
+    let mut editor = Editor::new(None, None, None);
+    editor
+        .document
+        .set_lines(lines.iter().map(|line| line.to_string()).collect());
+    editor.set_spell_checker(Some(SpellChecker::from_words([
+        "the", "quick", "brown", "fox", "jumps",
+    ])));
+    editor
+}
+
+#[test]
+fn test_next_misspelling_wraps_around() {
+    let mut editor = editor_with_text(&["the quikc fox", "brown fxo jumps"]);
+    editor.execute_action(Action::NextMisspelling).unwrap();
+    assert_eq!(editor.cursor_pos(), (4, 0));
+    editor.execute_action(Action::NextMisspelling).unwrap();
+    assert_eq!(editor.cursor_pos(), (6, 1));
+    editor.execute_action(Action::NextMisspelling).unwrap();
+    assert_eq!(editor.cursor_pos(), (4, 0));
+}
+
+#[test]
+fn test_correct_word_at_cursor() {
+    let mut editor = editor_with_text(&["the quikc fox"]);
+    editor.set_cursor_pos(6, 0);
+    editor.execute_action(Action::CorrectSpelling).unwrap();
+    assert_eq!(editor.mode, EditorMode::SpellingSuggestions);
+    assert_eq!(
+        editor.spelling.picker.as_ref().unwrap().suggestions,
+        vec!["quick"]
+    );
+
+    editor.process_input(Input::Character('\n'), false).unwrap();
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert_eq!(editor.document.lines(), vec!["the quick fox"]);
+    assert_eq!(editor.cursor_pos(), (9, 0));
+
+    editor.undo();
+    assert_eq!(editor.document.lines(), vec!["the quikc fox"]);
+}
+
+#[test]
+fn test_add_word_to_user_dictionary() {
+    let dir = TempDir::new().unwrap();
+    let user_dictionary = dir.path().join("user.txt");
+    let mut editor = editor_with_text(&["the dmacs fox"]);
+    editor.set_spell_checker(Some(
+        SpellChecker::from_words(["the", "fox"]).with_user_dictionary(user_dictionary.clone()),
+    ));
+    editor.set_cursor_pos(4, 0);
+    editor.execute_action(Action::CorrectSpelling).unwrap();
+    // The last entry adds the word
+    editor.process_input(Input::KeyUp, false).unwrap();
+    editor.process_input(Input::Character('\n'), false).unwrap();
+
+    assert_eq!(editor.document.lines(), vec!["the dmacs fox"]);
+    assert!(editor.spelling.misspellings("the dmacs fox").is_empty());
+    assert_eq!(
+        std::fs::read_to_string(&user_dictionary).unwrap(),
+        "dmacs\n"
+    );
+}
+
+#[test]
+fn test_correctly_spelled_word_opens_nothing() {
+    let mut editor = editor_with_text(&["the fox"]);
+    editor.execute_action(Action::CorrectSpelling).unwrap();
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert_eq!(editor.status_message, "No misspelled word at the cursor.");
+}
@@ -0,0 +1,57 @@
+use dmacs::editor::Editor;
+use dmacs::editor::actions::Action;
+use dmacs::editor::stats::DocumentStats;
+
+fn editor_with_text(lines: &[&str]) -> Editor {
+    let mut editor = Editor::new(None, None, None);
+    editor
+        .document
+        .set_lines(lines.iter().map(|line| line.to_string()).collect());
+    editor
+}
+
+#[test]
+fn test_stats_of_buffer() {
+    let mut editor = editor_with_text(&[
+        "# Plan",
+        "- [x] write the draft",
+        "- [ ] review it",
+        "- [ ] send it",
+    ]);
+    editor.execute_action(Action::ShowStats).unwrap();
+    assert_eq!(
+        editor.status_message,
+        "Buffer: 8 words, 55 characters, 4 lines, 1/3 tasks done, 1 min read"
+    );
+}
+
+#[test]
+fn test_characters_are_counted_as_graphemes() {
+    let stats = DocumentStats::of_lines(["café 👍🏽", "日本語"]);
+    assert_eq!(stats.characters, 9);
+    assert_eq!(stats.lines, 2);
+}
+
+#[test]
+fn test_reading_time_rounds_up() {
+    let line = "word ".repeat(201);
+    let stats = DocumentStats::of_lines(&[line]);
+    assert_eq!(stats.words, 201);
+    assert_eq!(stats.reading_minutes(), 2);
+    assert_eq!(DocumentStats::default().reading_minutes(), 0);
+}
+
+#[test]
+fn test_stats_of_selection() {
+    let mut editor = editor_with_text(&["one two three", "- [ ] four five", "six"]);
+    editor.set_cursor_pos(4, 0);
+    editor.execute_action(Action::SetMarker).unwrap();
+    editor.set_cursor_pos(0, 2);
+    editor.execute_action(Action::ShowStats).unwrap();
+    assert_eq!(
+        editor.status_message,
+        "Selection: 4 words, 24 characters, 2 lines, 0/1 tasks done, 1 min read"
+    );
+    // The selection is kept
+    assert!(editor.selection.is_selection_active());
+}
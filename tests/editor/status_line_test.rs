@@ -0,0 +1,89 @@
+use dmacs::editor::Editor;
+use dmacs::editor::status_line::StatusSegment;
+use pancurses::Input;
+use std::fs;
+use tempfile::TempDir;
+
+fn text(editor: &mut Editor, segment: StatusSegment) -> Option<String> {
+    editor.status_part(segment).map(|part| part.text)
+}
+
+#[test]
+fn test_segments_follow_setting_and_skip_unknown_names() {
+    let mut editor = Editor::new(None, None, None);
+    assert_eq!(editor.status_segments(), StatusSegment::DEFAULT.to_vec());
+
+    editor.settings.status_segments = Some(vec![
+        "branch".to_string(),
+        "clock".to_string(),
+        "file".to_string(),
+    ]);
+    assert_eq!(
+        editor.status_segments(),
+        vec![StatusSegment::Branch, StatusSegment::File]
+    );
+}
+
+#[test]
+fn test_position_percent_and_format() {
+    let mut editor = Editor::new(None, None, None);
+    editor.document.set_lines(vec![
+        "one".to_string(),
+        "日本語".to_string(),
+        "".to_string(),
+    ]);
+    editor.cursor.y = 1;
+    editor.cursor.x = "日本".len();
+
+    assert_eq!(
+        text(&mut editor, StatusSegment::File).unwrap(),
+        "[No Name]*"
+    );
+    assert_eq!(text(&mut editor, StatusSegment::Lines).unwrap(), "3 lines");
+    assert_eq!(text(&mut editor, StatusSegment::Position).unwrap(), "2:5");
+    assert_eq!(text(&mut editor, StatusSegment::Percent).unwrap(), "66%");
+    assert_eq!(
+        text(&mut editor, StatusSegment::Format).unwrap(),
+        "LF UTF-8"
+    );
+}
+
+#[test]
+fn test_matches_only_while_searching() {
+    let mut editor = Editor::new(None, None, None);
+    editor
+        .document
+        .set_lines(vec!["test one".to_string(), "test two".to_string()]);
+    assert_eq!(text(&mut editor, StatusSegment::Matches), None);
+
+    editor
+        .process_input(Input::Character('\x13'), false)
+        .unwrap();
+    editor.process_input(Input::Character('t'), false).unwrap();
+    editor.process_input(Input::Character('w'), false).unwrap();
+    assert_eq!(
+        text(&mut editor, StatusSegment::Matches).unwrap(),
+        "match 1/1"
+    );
+
+    editor.process_input(Input::Character('\n'), false).unwrap();
+    assert_eq!(text(&mut editor, StatusSegment::Matches), None);
+}
+
+#[test]
+fn test_branch_of_file_in_git_repository() {
+    let dir = TempDir::new().unwrap();
+    fs::create_dir(dir.path().join(".git")).unwrap();
+    fs::write(dir.path().join(".git/HEAD"), "ref: refs/heads/drafts\n").unwrap();
+    let file = dir.path().join("notes.md");
+    fs::write(&file, "notes\n").unwrap();
+
+    let mut editor = Editor::new(Some(file.to_str().unwrap().to_string()), None, None);
+    assert_eq!(
+        text(&mut editor, StatusSegment::Branch).unwrap(),
+        "⎇ drafts"
+    );
+
+    let mut scratch = Editor::new(None, None, None);
+    assert_eq!(text(&mut scratch, StatusSegment::Branch), None);
+}
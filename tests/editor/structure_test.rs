@@ -0,0 +1,128 @@
+use dmacs::document::LineBuffer;
+use dmacs::editor::Editor;
+use dmacs::editor::cursor::Cursor;
+use dmacs::editor::structure::{enclosing_list_item, sentence_span, subtree_end};
+use pancurses::Input;
+
+fn create_editor_with_content(content: &str) -> Editor {
+    let mut editor = Editor::new(None, None, None);
+    editor
+        .document
+        .set_lines(content.lines().map(|s| s.to_string()).collect());
+    if editor.document.lines().is_empty() {
+        editor.document.set_lines(vec![String::new()]);
+    }
+    editor
+}
+
+fn expand(editor: &mut Editor) {
+    editor.process_input(Input::Character('+'), true).unwrap();
+}
+
+fn shrink(editor: &mut Editor) {
+    editor.process_input(Input::Character('-'), true).unwrap();
+}
+
+fn selection(editor: &Editor) -> Option<((usize, usize), (usize, usize))> {
+    editor.selection.get_selection_range(&editor.cursor)
+}
+
+const PLAN: &str = "# Plan\n- [ ] Pack bags. Check the tickets now!\n  - [ ] passports\n\n  - [ ] cash\n- [ ] Leave\n---\nPage two";
+
+#[test]
+fn test_sentence_span() {
+    let line = "- [ ] Pack bags. Check the tickets now!  ";
+    assert_eq!(sentence_span(line, 8), Some((6, 16)));
+    assert_eq!(sentence_span(line, 16), Some((17, 39)));
+    assert_eq!(sentence_span(line, 40), Some((17, 39)));
+    assert_eq!(sentence_span("v1.2 is out", 0), Some((0, 11)));
+    assert_eq!(sentence_span("   ", 1), None);
+}
+
+#[test]
+fn test_list_items_and_their_children() {
+    let lines: LineBuffer = PLAN.lines().map(str::to_string).collect();
+    assert_eq!(enclosing_list_item(&lines, 0), None);
+    assert_eq!(enclosing_list_item(&lines, 2), Some(2));
+    assert_eq!(enclosing_list_item(&lines, 3), Some(1));
+    assert_eq!(enclosing_list_item(&lines, 7), None);
+    assert_eq!(subtree_end(&lines, 1), 4);
+    assert_eq!(subtree_end(&lines, 2), 2);
+    assert_eq!(subtree_end(&lines, 5), 5);
+
+    let lines: LineBuffer = ["- item", "  continued text", "    more"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    assert_eq!(enclosing_list_item(&lines, 2), Some(0));
+}
+
+#[test]
+fn test_expand_and_shrink_through_the_units() {
+    let mut editor = create_editor_with_content(PLAN);
+    editor.set_cursor_pos(28, 1);
+
+    expand(&mut editor);
+    assert_eq!(selection(&editor), Some(((27, 1), (34, 1))));
+    assert_eq!(editor.status_message, "Selected word.");
+    expand(&mut editor);
+    assert_eq!(selection(&editor), Some(((17, 1), (39, 1))));
+    assert_eq!(editor.status_message, "Selected sentence.");
+    expand(&mut editor);
+    assert_eq!(selection(&editor), Some(((0, 1), (0, 5))));
+    assert_eq!(editor.status_message, "Selected list item.");
+    expand(&mut editor);
+    assert_eq!(selection(&editor), Some(((0, 0), (0, 6))));
+    assert_eq!(editor.status_message, "Selected page.");
+    expand(&mut editor);
+    assert_eq!(selection(&editor), Some(((0, 0), (8, 7))));
+    expand(&mut editor);
+    assert_eq!(editor.status_message, "The whole buffer is selected.");
+
+    for expected in [
+        ((0, 0), (0, 6)),
+        ((0, 1), (0, 5)),
+        ((17, 1), (39, 1)),
+        ((27, 1), (34, 1)),
+    ] {
+        shrink(&mut editor);
+        assert_eq!(selection(&editor), Some(expected));
+    }
+    shrink(&mut editor);
+    assert!(!editor.selection.is_selection_active());
+    assert_eq!(editor.cursor_pos(), (28, 1));
+}
+
+#[test]
+fn test_child_item_grows_to_its_parent() {
+    let mut editor = create_editor_with_content(PLAN);
+    editor.set_cursor_pos(8, 2);
+
+    // The sentence is the word itself, so it is skipped
+    expand(&mut editor);
+    expand(&mut editor);
+    assert_eq!(selection(&editor), Some(((0, 2), (0, 3))));
+    expand(&mut editor);
+    assert_eq!(selection(&editor), Some(((0, 1), (0, 5))));
+
+    editor.process_input(Input::Character('w'), true).unwrap();
+    assert_eq!(
+        editor.clipboard.kill_buffer,
+        "- [ ] Pack bags. Check the tickets now!\n  - [ ] passports\n\n  - [ ] cash\n"
+    );
+}
+
+#[test]
+fn test_shrink_a_selection_made_by_hand() {
+    let mut editor = create_editor_with_content(PLAN);
+    editor.selection.set_marker(&Cursor::new(0, 0));
+    editor.set_cursor_pos(0, 6);
+
+    shrink(&mut editor);
+    assert_eq!(editor.status_message, "Selected list item.");
+    assert_eq!(selection(&editor), Some(((0, 5), (0, 6))));
+
+    editor.selection.clear_marker();
+    shrink(&mut editor);
+    assert_eq!(editor.status_message, "No selection to shrink.");
+}
@@ -0,0 +1,106 @@
+use crate::editor_with_lines;
+use dmacs::document::LineBuffer;
+use dmacs::editor::actions::Action;
+use dmacs::editor::tags::{TagIndex, tags_in_line};
+use dmacs::editor::{Editor, EditorMode};
+use pancurses::Input;
+
+fn counts(editor: &mut Editor) -> Vec<(String, usize)> {
+    editor.tag_index().counts()
+}
+
+#[test]
+fn test_tags_in_line() {
+    let tags: Vec<&str> = tags_in_line("# Heading #work, see x.com/#anchor (#home) #1 #v2 #a/b-")
+        .into_iter()
+        .map(|(_, _, tag)| tag)
+        .collect();
+    assert_eq!(tags, vec!["work", "home", "v2", "a/b"]);
+    assert_eq!(tags_in_line("#idea first"), vec![(0, 5, "idea")]);
+}
+
+#[test]
+fn test_index_counts_lines_per_tag() {
+    let lines: LineBuffer = ["#a #b", "#a #a", "none", "#b"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    assert_eq!(
+        TagIndex::build(&lines).counts(),
+        vec![("a".to_string(), 2), ("b".to_string(), 2)]
+    );
+}
+
+#[test]
+fn test_index_follows_edits_and_undo() {
+    let mut editor = editor_with_lines(&["#a one", "two", "#b three"]);
+    editor.set_undo_debounce_threshold(0);
+    assert_eq!(counts(&mut editor).len(), 2);
+
+    editor.set_cursor_pos(3, 1);
+    for c in " #a".chars() {
+        editor.process_input(Input::Character(c), false).unwrap();
+    }
+    editor.process_input(Input::Character('\n'), false).unwrap();
+    for c in "#c".chars() {
+        editor.process_input(Input::Character(c), false).unwrap();
+    }
+    // Join the new line back onto "two #a"
+    editor.go_to_start_of_line();
+    editor.process_input(Input::KeyBackspace, false).unwrap();
+    let incremental = counts(&mut editor);
+    assert_eq!(
+        incremental,
+        TagIndex::build(editor.document.lines()).counts()
+    );
+    assert!(incremental.contains(&("a".to_string(), 2)));
+
+    for _ in 0..4 {
+        editor.undo();
+        assert_eq!(
+            counts(&mut editor),
+            TagIndex::build(editor.document.lines()).counts()
+        );
+    }
+    editor.redo();
+    assert_eq!(
+        counts(&mut editor),
+        TagIndex::build(editor.document.lines()).counts()
+    );
+}
+
+#[test]
+fn test_list_tags_and_follow_selected_tag() {
+    let mut editor = editor_with_lines(&["#home", "x #work", "#home again", "#work #home"]);
+    editor.execute_action(Action::ListTags).unwrap();
+    assert_eq!(editor.mode, EditorMode::Tags);
+    assert_eq!(editor.tag_list.entries[0], ("home".to_string(), 3));
+
+    editor.process_input(Input::Character('w'), false).unwrap();
+    editor.process_input(Input::Character('\n'), false).unwrap();
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert_eq!(editor.tag_filter.as_deref(), Some("work"));
+    assert_eq!(editor.cursor_pos(), (2, 1));
+    assert_eq!(editor.status_message, "#work 1/2");
+
+    editor.execute_action(Action::NextTaggedLine).unwrap();
+    assert_eq!(editor.cursor_pos(), (0, 3));
+    editor.execute_action(Action::NextTaggedLine).unwrap();
+    assert_eq!(editor.cursor_pos(), (2, 1));
+    editor.execute_action(Action::PreviousTaggedLine).unwrap();
+    assert_eq!(editor.cursor_pos(), (0, 3));
+}
+
+#[test]
+fn test_list_tags_without_tags() {
+    let mut editor = editor_with_lines(&["# Heading", "text"]);
+    editor.execute_action(Action::ListTags).unwrap();
+    assert_eq!(editor.mode, EditorMode::Normal);
+    assert_eq!(editor.status_message, "No #tags in this buffer.");
+
+    editor.execute_action(Action::NextTaggedLine).unwrap();
+    assert_eq!(
+        editor.status_message,
+        "No tag to follow. Pick one with ListTags."
+    );
+}
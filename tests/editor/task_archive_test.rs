@@ -0,0 +1,93 @@
+use crate::editor_with_lines;
+use dmacs::document::LineBuffer;
+use dmacs::editor::actions::Action;
+use dmacs::editor::task::archive_done_tasks;
+use pancurses::Input;
+
+fn lines(lines: &[&str]) -> Vec<String> {
+    lines.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn test_archive_creates_section_at_end() {
+    let (archived, count) = archive_done_tasks(
+        &LineBuffer::from(lines(&["# Todo", "- [x] a", "- [ ] b", "  - [x] c", ""])),
+        1,
+        "2025-06-12 09:30",
+    );
+    assert_eq!(count, 2);
+    assert_eq!(
+        archived,
+        lines(&[
+            "# Todo",
+            "- [ ] b",
+            "",
+            "## Archive",
+            "- [x] a @archived(2025-06-12 09:30)",
+            "- [x] c @archived(2025-06-12 09:30)",
+        ])
+    );
+}
+
+#[test]
+fn test_archive_appends_to_existing_section() {
+    let (archived, count) = archive_done_tasks(
+        &LineBuffer::from(lines(&[
+            "- [x] above",
+            "- [x] new",
+            "## Archive",
+            "- [x] old @archived(2025-06-01 10:00)",
+            "",
+            "## Notes",
+            "- [x] after",
+        ])),
+        1,
+        "2025-06-12 09:30",
+    );
+    assert_eq!(count, 2);
+    assert_eq!(
+        archived,
+        lines(&[
+            "- [x] above",
+            "## Archive",
+            "- [x] old @archived(2025-06-01 10:00)",
+            "- [x] new @archived(2025-06-12 09:30)",
+            "- [x] after @archived(2025-06-12 09:30)",
+            "",
+            "## Notes",
+        ])
+    );
+}
+
+#[test]
+fn test_archive_command_is_one_undo_step() {
+    let original = ["- [x] above", "/archive", "- [x] done", "- [ ] open"];
+    let mut editor = editor_with_lines(&original);
+    editor.set_cursor_pos(8, 1);
+    editor.process_input(Input::Character('\n'), false).unwrap();
+
+    assert_eq!(
+        editor.document.lines().range(..4).collect::<Vec<_>>(),
+        ["- [x] above", "- [ ] open", "", "## Archive"]
+    );
+    assert!(editor.document.lines()[4].starts_with("- [x] done @archived("));
+    assert_eq!(editor.cursor_pos(), (0, 1));
+    assert_eq!(editor.status_message, "Archived 1 task.");
+
+    editor.undo();
+    assert_eq!(editor.document.lines(), lines(&original));
+}
+
+#[test]
+fn test_archive_action_without_done_tasks() {
+    let mut editor = editor_with_lines(&["- [x] above", "- [ ] open"]);
+    editor.execute_action(Action::ArchiveDoneTasks).unwrap();
+    assert_eq!(
+        editor.document.lines(),
+        lines(&["- [x] above", "- [ ] open"])
+    );
+    assert_eq!(
+        editor.status_message,
+        "No completed tasks below the cursor."
+    );
+}
@@ -4,9 +4,11 @@ use pancurses::Input;
 // Helper to create an editor with some initial content
 fn setup_editor(content: &[&str]) -> Editor {
     let mut editor = Editor::new(None, None, None);
-    editor.document.lines = content.iter().map(|&s| s.to_string()).collect();
-    editor.cursor_y = 0;
-    editor.cursor_x = 0;
+    editor
+        .document
+        .set_lines(content.iter().map(|&s| s.to_string()).collect());
+    editor.cursor.y = 0;
+    editor.cursor.x = 0;
     editor
 }
 
@@ -20,13 +22,15 @@ fn test_task_command_enter_mode_and_find_tasks() {
         "- [x] Done Task",
         "- [ ] Task 3",
     ]);
-    editor.cursor_y = 0; // Cursor at "Task list:"
-    editor.cursor_x = 0;
+    editor.cursor.y = 0; // Cursor at "Task list:"
+    editor.cursor.x = 0;
 
     // Simulate typing "/task" and pressing Enter
-    editor.document.lines.insert(0, "/task".to_string()); // Insert /task at the beginning
-    editor.cursor_y = 0; // Cursor on the /task line
-    editor.cursor_x = 5; // Cursor at the end of /task
+    editor
+        .document
+        .replace_lines(0..0, vec!["/task".to_string()]); // Insert /task at the beginning
+    editor.cursor.y = 0; // Cursor on the /task line
+    editor.cursor.x = 5; // Cursor at the end of /task
     editor.insert_newline().unwrap(); // Now call insert_newline
 
     assert_eq!(editor.mode, EditorMode::TaskSelection);
@@ -41,19 +45,21 @@ fn test_task_command_enter_mode_and_find_tasks() {
     );
 
     // Ensure "/task" command is removed
-    assert_eq!(editor.document.lines.len(), 7); // Original 6 lines + 1
-    assert_eq!(editor.document.lines[0], "");
+    assert_eq!(editor.document.lines().len(), 7); // Original 6 lines + 1
+    assert_eq!(editor.document.lines()[0], "");
 }
 
 #[test]
 fn test_task_command_no_tasks_found() {
     let mut editor = setup_editor(&["No tasks here", "Another line", "- [x] Done Task"]);
-    editor.cursor_y = 0;
-    editor.cursor_x = 0;
+    editor.cursor.y = 0;
+    editor.cursor.x = 0;
 
-    editor.document.lines.insert(0, "/task".to_string()); // Insert /task at the beginning
-    editor.cursor_y = 0; // Cursor on the /task line
-    editor.cursor_x = 5; // Cursor at the end of /task
+    editor
+        .document
+        .replace_lines(0..0, vec!["/task".to_string()]); // Insert /task at the beginning
+    editor.cursor.y = 0; // Cursor on the /task line
+    editor.cursor.x = 5; // Cursor at the end of /task
     editor.insert_newline().unwrap(); // Now call insert_newline
 
     assert_eq!(editor.mode, EditorMode::TaskSelection); // Still enters mode
@@ -68,11 +74,13 @@ fn test_task_command_no_tasks_found() {
 #[test]
 fn test_task_command_navigate_tasks() {
     let mut editor = setup_editor(&["Start", "- [ ] A", "- [ ] B", "- [ ] C"]);
-    editor.cursor_y = 0;
-    editor.cursor_x = 0;
-    editor.document.lines.insert(0, "/task".to_string()); // Insert /task at the beginning
-    editor.cursor_y = 0; // Cursor on the /task line
-    editor.cursor_x = 5; // Cursor at the end of /task
+    editor.cursor.y = 0;
+    editor.cursor.x = 0;
+    editor
+        .document
+        .replace_lines(0..0, vec!["/task".to_string()]); // Insert /task at the beginning
+    editor.cursor.y = 0; // Cursor on the /task line
+    editor.cursor.x = 5; // Cursor at the end of /task
     editor.insert_newline().unwrap(); // Now call insert_newline // Enter task selection mode
 
     assert_eq!(editor.task.selected_task_index, Some(0)); // Task A
@@ -105,21 +113,23 @@ fn test_task_command_move_task() {
         "- [ ] Task 2",
         "End line",
     ]);
-    editor.cursor_y = 0; // Cursor at "Current line"
-    editor.cursor_x = 0;
+    editor.cursor.y = 0; // Cursor at "Current line"
+    editor.cursor.x = 0;
 
-    editor.document.lines.insert(0, "/task".to_string()); // Insert /task at the beginning
-    editor.cursor_y = 0; // Cursor on the /task line
-    editor.cursor_x = 5; // Cursor at the end of /task
+    editor
+        .document
+        .replace_lines(0..0, vec!["/task".to_string()]); // Insert /task at the beginning
+    editor.cursor.y = 0; // Cursor on the /task line
+    editor.cursor.x = 5; // Cursor at the end of /task
     editor.insert_newline().unwrap(); // Now call insert_newline // Enter task selection mode
 
-    assert_eq!(editor.document.lines.len(), 6);
-    assert_eq!(editor.document.lines[0], ""); // Empty line
-    assert_eq!(editor.document.lines[1], "Current line");
-    assert_eq!(editor.document.lines[2], "- [ ] Task 1");
-    assert_eq!(editor.document.lines[3], "Middle line");
-    assert_eq!(editor.document.lines[4], "- [ ] Task 2"); // Original Task 1 removed, so Task 2 is now at index 3
-    assert_eq!(editor.document.lines[5], "End line");
+    assert_eq!(editor.document.lines().len(), 6);
+    assert_eq!(editor.document.lines()[0], ""); // Empty line
+    assert_eq!(editor.document.lines()[1], "Current line");
+    assert_eq!(editor.document.lines()[2], "- [ ] Task 1");
+    assert_eq!(editor.document.lines()[3], "Middle line");
+    assert_eq!(editor.document.lines()[4], "- [ ] Task 2"); // Original Task 1 removed, so Task 2 is now at index 3
+    assert_eq!(editor.document.lines()[5], "End line");
 
     assert_eq!(editor.task.tasks.len(), 2);
     assert_eq!(editor.task.tasks[0].1, "- [ ] Task 1");
@@ -129,13 +139,13 @@ fn test_task_command_move_task() {
     // Move Task 1
     editor.handle_task_selection_input(Input::Character(' ')); // Press SPACE
 
-    assert_eq!(editor.document.lines.len(), 6);
-    assert_eq!(editor.document.lines[0], "- [ ] Task 1"); // Task 1 moved here
-    assert_eq!(editor.document.lines[1], "");
-    assert_eq!(editor.document.lines[2], "Current line");
-    assert_eq!(editor.document.lines[3], "Middle line");
-    assert_eq!(editor.document.lines[4], "- [ ] Task 2"); // Original Task 1 removed, so Task 2 is now at index 3
-    assert_eq!(editor.document.lines[5], "End line");
+    assert_eq!(editor.document.lines().len(), 6);
+    assert_eq!(editor.document.lines()[0], "- [ ] Task 1"); // Task 1 moved here
+    assert_eq!(editor.document.lines()[1], "");
+    assert_eq!(editor.document.lines()[2], "Current line");
+    assert_eq!(editor.document.lines()[3], "Middle line");
+    assert_eq!(editor.document.lines()[4], "- [ ] Task 2"); // Original Task 1 removed, so Task 2 is now at index 3
+    assert_eq!(editor.document.lines()[5], "End line");
 
     assert_eq!(editor.task.tasks.len(), 1); // Task 1 moved, only Task 2 remains
     assert_eq!(editor.task.tasks[0].1, "- [ ] Task 2");
@@ -144,13 +154,13 @@ fn test_task_command_move_task() {
     // Move Task 2
     editor.handle_task_selection_input(Input::Character(' ')); // Press SPACE again
 
-    assert_eq!(editor.document.lines.len(), 6);
-    assert_eq!(editor.document.lines[0], "- [ ] Task 1");
-    assert_eq!(editor.document.lines[1], "- [ ] Task 2"); // Task 2 moved here
-    assert_eq!(editor.document.lines[2], "");
-    assert_eq!(editor.document.lines[3], "Current line");
-    assert_eq!(editor.document.lines[4], "Middle line");
-    assert_eq!(editor.document.lines[5], "End line");
+    assert_eq!(editor.document.lines().len(), 6);
+    assert_eq!(editor.document.lines()[0], "- [ ] Task 1");
+    assert_eq!(editor.document.lines()[1], "- [ ] Task 2"); // Task 2 moved here
+    assert_eq!(editor.document.lines()[2], "");
+    assert_eq!(editor.document.lines()[3], "Current line");
+    assert_eq!(editor.document.lines()[4], "Middle line");
+    assert_eq!(editor.document.lines()[5], "End line");
 
     assert!(editor.task.tasks.is_empty()); // All tasks moved
     assert_eq!(editor.mode, EditorMode::Normal); // Should exit mode
@@ -163,11 +173,13 @@ fn test_task_command_move_task() {
 #[test]
 fn test_task_command_exit_mode() {
     let mut editor = setup_editor(&["Start", "- [ ] A", "- [ ] B"]);
-    editor.cursor_y = 0;
-    editor.cursor_x = 0;
-    editor.document.lines.insert(0, "/task".to_string()); // Insert /task at the beginning
-    editor.cursor_y = 0; // Cursor on the /task line
-    editor.cursor_x = 5; // Cursor at the end of /task
+    editor.cursor.y = 0;
+    editor.cursor.x = 0;
+    editor
+        .document
+        .replace_lines(0..0, vec!["/task".to_string()]); // Insert /task at the beginning
+    editor.cursor.y = 0; // Cursor on the /task line
+    editor.cursor.x = 5; // Cursor at the end of /task
     editor.insert_newline().unwrap(); // Now call insert_newline // Enter task selection mode
 
     assert_eq!(editor.mode, EditorMode::TaskSelection);
@@ -179,9 +191,11 @@ fn test_task_command_exit_mode() {
     assert_eq!(editor.status_message, "Exited task selection mode.");
 
     // Re-enter mode
-    editor.document.lines.insert(0, "/task".to_string()); // Insert /task at the beginning
-    editor.cursor_y = 0; // Cursor on the /task line
-    editor.cursor_x = 5; // Cursor at the end of /task
+    editor
+        .document
+        .replace_lines(0..0, vec!["/task".to_string()]); // Insert /task at the beginning
+    editor.cursor.y = 0; // Cursor on the /task line
+    editor.cursor.x = 5; // Cursor at the end of /task
     editor.insert_newline().unwrap(); // Now call insert_newline
     assert_eq!(editor.mode, EditorMode::TaskSelection);
 
@@ -207,11 +221,13 @@ fn test_task_command_scroll_tasks() {
         "- [ ] Task 8",
         "- [ ] Task 9",
     ]);
-    editor.cursor_y = 0;
-    editor.cursor_x = 0;
-    editor.document.lines.insert(0, "/task".to_string()); // Insert /task at the beginning
-    editor.cursor_y = 0; // Cursor on the /task line
-    editor.cursor_x = 5; // Cursor at the end of /task
+    editor.cursor.y = 0;
+    editor.cursor.x = 0;
+    editor
+        .document
+        .replace_lines(0..0, vec!["/task".to_string()]); // Insert /task at the beginning
+    editor.cursor.y = 0; // Cursor on the /task line
+    editor.cursor.x = 5; // Cursor at the end of /task
     editor.insert_newline().unwrap(); // Now call insert_newline // Enter task selection mode
 
     // Set screen size for the test
@@ -268,18 +284,20 @@ fn test_task_command_scroll_tasks() {
 #[test]
 fn test_task_command_move_task_bug() {
     let mut editor = setup_editor(&["- [ ] Task 1", "- [ ] Task 2"]);
-    editor.cursor_y = 0; // Cursor at "Current line"
-    editor.cursor_x = 0;
+    editor.cursor.y = 0; // Cursor at "Current line"
+    editor.cursor.x = 0;
 
-    editor.document.lines.insert(0, "/task".to_string()); // Insert /task at the beginning
-    editor.cursor_y = 0; // Cursor on the /task line
-    editor.cursor_x = 5; // Cursor at the end of /task
+    editor
+        .document
+        .replace_lines(0..0, vec!["/task".to_string()]); // Insert /task at the beginning
+    editor.cursor.y = 0; // Cursor on the /task line
+    editor.cursor.x = 5; // Cursor at the end of /task
     editor.insert_newline().unwrap(); // Now call insert_newline // Enter task selection mode
 
-    assert_eq!(editor.document.lines.len(), 3);
-    assert_eq!(editor.document.lines[0], ""); // Empty line
-    assert_eq!(editor.document.lines[1], "- [ ] Task 1");
-    assert_eq!(editor.document.lines[2], "- [ ] Task 2"); // Original Task 1 removed, so Task 2 is now at index 3
+    assert_eq!(editor.document.lines().len(), 3);
+    assert_eq!(editor.document.lines()[0], ""); // Empty line
+    assert_eq!(editor.document.lines()[1], "- [ ] Task 1");
+    assert_eq!(editor.document.lines()[2], "- [ ] Task 2"); // Original Task 1 removed, so Task 2 is now at index 3
 
     assert_eq!(editor.task.tasks.len(), 2);
     assert_eq!(editor.task.tasks[0].1, "- [ ] Task 1");
@@ -290,10 +308,10 @@ fn test_task_command_move_task_bug() {
     editor.handle_task_selection_input(Input::KeyDown); // Press DOWN
     editor.handle_task_selection_input(Input::Character(' ')); // Press SPACE
 
-    assert_eq!(editor.document.lines.len(), 3);
-    assert_eq!(editor.document.lines[0], "- [ ] Task 2");
-    assert_eq!(editor.document.lines[1], ""); // Empty line
-    assert_eq!(editor.document.lines[2], "- [ ] Task 1");
+    assert_eq!(editor.document.lines().len(), 3);
+    assert_eq!(editor.document.lines()[0], "- [ ] Task 2");
+    assert_eq!(editor.document.lines()[1], ""); // Empty line
+    assert_eq!(editor.document.lines()[2], "- [ ] Task 1");
 
     assert_eq!(editor.task.tasks.len(), 1); // Task 2 moved, only Task 1 remains
     assert_eq!(editor.task.tasks[0].1, "- [ ] Task 1");
@@ -302,10 +320,10 @@ fn test_task_command_move_task_bug() {
     // Move Task 1
     editor.handle_task_selection_input(Input::Character(' ')); // Press SPACE again
 
-    assert_eq!(editor.document.lines.len(), 3);
-    assert_eq!(editor.document.lines[0], "- [ ] Task 2");
-    assert_eq!(editor.document.lines[1], "- [ ] Task 1");
-    assert_eq!(editor.document.lines[2], "");
+    assert_eq!(editor.document.lines().len(), 3);
+    assert_eq!(editor.document.lines()[0], "- [ ] Task 2");
+    assert_eq!(editor.document.lines()[1], "- [ ] Task 1");
+    assert_eq!(editor.document.lines()[2], "");
 
     assert!(editor.task.tasks.is_empty()); // All tasks moved
     assert_eq!(editor.mode, EditorMode::Normal); // Should exit mode
@@ -318,13 +336,15 @@ fn test_task_command_move_task_bug() {
 #[test]
 fn test_task_command_comment_out_task() {
     let mut editor = setup_editor(&["Task list:", "- [ ] Task 1", "- [ ] Task 2", "- [ ] Task 3"]);
-    editor.cursor_y = 0;
-    editor.cursor_x = 0;
+    editor.cursor.y = 0;
+    editor.cursor.x = 0;
 
     // Enter task selection mode
-    editor.document.lines.insert(0, "/task".to_string());
-    editor.cursor_y = 0;
-    editor.cursor_x = 5;
+    editor
+        .document
+        .replace_lines(0..0, vec!["/task".to_string()]);
+    editor.cursor.y = 0;
+    editor.cursor.x = 5;
     editor.insert_newline().unwrap();
 
     // Initial state: 3 tasks found
@@ -341,7 +361,7 @@ fn test_task_command_comment_out_task() {
 
     // Assertions for commenting out Task 2
     // The original line index of Task 2 is 3 (after /task line processing)
-    assert_eq!(editor.document.lines[3], "# - [ ] Task 2");
+    assert_eq!(editor.document.lines()[3], "# - [ ] Task 2");
     assert_eq!(editor.task.tasks.len(), 2);
     assert_eq!(editor.task.tasks[0].1, "- [ ] Task 1");
     assert_eq!(editor.task.tasks[1].1, "- [ ] Task 3");
@@ -355,7 +375,7 @@ fn test_task_command_comment_out_task() {
     editor.handle_task_selection_input(Input::Character('#'));
 
     // Assertions for commenting out Task 3
-    assert_eq!(editor.document.lines[4], "# - [ ] Task 3");
+    assert_eq!(editor.document.lines()[4], "# - [ ] Task 3");
     assert_eq!(editor.task.tasks.len(), 1);
     assert_eq!(editor.task.tasks[0].1, "- [ ] Task 1");
     assert_eq!(editor.task.selected_task_index, Some(0)); // Selection moves to last item
@@ -364,7 +384,7 @@ fn test_task_command_comment_out_task() {
     editor.handle_task_selection_input(Input::Character('#'));
 
     // Assertions for commenting out the last task
-    assert_eq!(editor.document.lines[2], "# - [ ] Task 1");
+    assert_eq!(editor.document.lines()[2], "# - [ ] Task 1");
     assert!(editor.task.tasks.is_empty());
     assert_eq!(editor.task.selected_task_index, None);
     assert_eq!(editor.mode, EditorMode::Normal); // Should exit mode
@@ -377,13 +397,15 @@ fn test_task_command_comment_out_task() {
 #[test]
 fn test_task_command_comment_out_undo_redo() {
     let mut editor = setup_editor(&["Task list:", "- [ ] The only task"]);
-    editor.cursor_y = 0;
-    editor.cursor_x = 0;
+    editor.cursor.y = 0;
+    editor.cursor.x = 0;
 
     // Enter task selection mode
-    editor.document.lines.insert(0, "/task".to_string());
-    editor.cursor_y = 0;
-    editor.cursor_x = 5;
+    editor
+        .document
+        .replace_lines(0..0, vec!["/task".to_string()]);
+    editor.cursor.y = 0;
+    editor.cursor.x = 5;
     editor.insert_newline().unwrap();
 
     // Initial state: 1 task found
@@ -396,7 +418,7 @@ fn test_task_command_comment_out_undo_redo() {
 
     // Assertions for commenting out the task
     assert_eq!(editor.mode, EditorMode::Normal);
-    assert_eq!(editor.document.lines[2], "# - [ ] The only task");
+    assert_eq!(editor.document.lines()[2], "# - [ ] The only task");
     assert_eq!(
         editor.status_message,
         "All tasks handled. Exiting task selection mode."
@@ -406,14 +428,14 @@ fn test_task_command_comment_out_undo_redo() {
     editor.undo();
 
     // Assertions after undo
-    assert_eq!(editor.document.lines[2], "- [ ] The only task");
+    assert_eq!(editor.document.lines()[2], "- [ ] The only task");
     assert_eq!(editor.mode, EditorMode::Normal);
 
     // Redo the change
     editor.redo();
 
     // Assertions after redo
-    assert_eq!(editor.document.lines[2], "# - [ ] The only task");
+    assert_eq!(editor.document.lines()[2], "# - [ ] The only task");
     assert_eq!(editor.mode, EditorMode::Normal);
 }
 
@@ -428,13 +450,15 @@ fn test_task_command_fuzzy_search() {
         "- [x] Done Task",
         "- [ ] Avocado",
     ]);
-    editor.cursor_y = 0;
-    editor.cursor_x = 0;
+    editor.cursor.y = 0;
+    editor.cursor.x = 0;
 
     // Enter task selection mode
-    editor.document.lines.insert(0, "/task".to_string());
-    editor.cursor_y = 0;
-    editor.cursor_x = 5;
+    editor
+        .document
+        .replace_lines(0..0, vec!["/task".to_string()]);
+    editor.cursor.y = 0;
+    editor.cursor.x = 5;
     editor.insert_newline().unwrap();
 
     assert_eq!(editor.mode, EditorMode::TaskSelection);
@@ -500,13 +524,15 @@ fn test_task_command_fuzzy_search() {
 #[test]
 fn test_task_command_fuzzy_search_ctrl_g_exit() {
     let mut editor = setup_editor(&["- [ ] Task A", "- [ ] Task B"]);
-    editor.cursor_y = 0;
-    editor.cursor_x = 0;
+    editor.cursor.y = 0;
+    editor.cursor.x = 0;
 
     // Enter task mode
-    editor.document.lines.insert(0, "/task".to_string());
-    editor.cursor_y = 0;
-    editor.cursor_x = 5;
+    editor
+        .document
+        .replace_lines(0..0, vec!["/task".to_string()]);
+    editor.cursor.y = 0;
+    editor.cursor.x = 5;
     editor.insert_newline().unwrap();
     assert_eq!(editor.mode, EditorMode::TaskSelection);
     assert_eq!(editor.task.tasks.len(), 2);
@@ -516,9 +542,11 @@ fn test_task_command_fuzzy_search_ctrl_g_exit() {
     assert_eq!(editor.mode, EditorMode::Normal);
 
     // Re-enter task mode
-    editor.document.lines.insert(0, "/task".to_string());
-    editor.cursor_y = 0;
-    editor.cursor_x = 5;
+    editor
+        .document
+        .replace_lines(0..0, vec!["/task".to_string()]);
+    editor.cursor.y = 0;
+    editor.cursor.x = 5;
     editor.insert_newline().unwrap();
     assert_eq!(editor.mode, EditorMode::TaskSelection);
 
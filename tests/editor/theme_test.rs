@@ -0,0 +1,28 @@
+use dmacs::config::Colors;
+use dmacs::editor::Editor;
+use dmacs::editor::actions::Action;
+
+#[test]
+fn test_cycle_theme_hands_colors_to_terminal_once() {
+    let mut editor = Editor::new(None, None, None);
+
+    editor.execute_action(Action::CycleTheme).unwrap();
+    assert_eq!(editor.settings.theme.as_deref(), Some("light"));
+    assert_eq!(editor.status_message, "Theme light.");
+    assert_eq!(editor.take_pending_colors(), Colors::builtin("light"));
+    assert_eq!(editor.take_pending_colors(), None);
+}
+
+#[test]
+fn test_cycle_theme_in_monochrome_says_so() {
+    let mut editor = Editor::new(None, None, None);
+    editor.set_monochrome(true);
+    editor.settings.theme = Some("unknown".to_string());
+
+    editor.execute_action(Action::CycleTheme).unwrap();
+    assert_eq!(editor.settings.theme.as_deref(), Some("dark"));
+    assert_eq!(
+        editor.status_message,
+        "Theme dark (this terminal shows no colors)."
+    );
+}
@@ -0,0 +1,123 @@
+use crate::editor_with_lines;
+use dmacs::editor::Editor;
+use dmacs::editor::actions::Action;
+use dmacs::task_timer;
+use std::time::{Duration, SystemTime};
+use tempfile::TempDir;
+
+fn rewind_timer(editor: &mut Editor, duration: Duration) {
+    let running = editor.task_timer.running.as_mut().unwrap();
+    running.started_at = SystemTime::now() - duration;
+}
+
+#[test]
+fn test_start_and_stop_timer_appends_spent() {
+    let mut editor = editor_with_lines(&["- [ ] write report", "- [ ] other"]);
+    editor.execute_action(Action::StartTaskTimer).unwrap();
+    assert!(editor.task_timer.running.is_some());
+    assert_eq!(editor.status_message, "Timer started.");
+
+    rewind_timer(&mut editor, Duration::from_secs(83 * 60 + 10));
+    editor.execute_action(Action::StopTaskTimer).unwrap();
+
+    assert!(editor.task_timer.running.is_none());
+    assert_eq!(
+        editor.document.lines()[0],
+        "- [ ] write report @spent(1h23m)"
+    );
+    assert_eq!(editor.status_message, "Timer stopped: 1h23m (total 1h23m).");
+
+    editor.undo();
+    assert_eq!(editor.document.lines()[0], "- [ ] write report");
+}
+
+#[test]
+fn test_stop_timer_adds_to_existing_spent() {
+    let mut editor = editor_with_lines(&["- [ ] review @spent(45m) #work"]);
+    editor.start_task_timer();
+    rewind_timer(&mut editor, Duration::from_secs(20 * 60));
+    editor.stop_task_timer();
+
+    assert_eq!(
+        editor.document.lines()[0],
+        "- [ ] review @spent(1h5m) #work"
+    );
+}
+
+#[test]
+fn test_timer_follows_moved_task() {
+    let mut editor = editor_with_lines(&["- [ ] a", "- [ ] b"]);
+    editor.set_cursor_pos(0, 1);
+    editor.start_task_timer();
+    editor.set_cursor_pos(0, 0);
+    editor.execute_action(Action::MoveLineDown).unwrap();
+    assert_eq!(editor.document.lines(), vec!["- [ ] b", "- [ ] a"]);
+
+    editor.stop_task_timer();
+    assert_eq!(
+        editor.document.lines(),
+        vec!["- [ ] b @spent(0m)", "- [ ] a"]
+    );
+}
+
+#[test]
+fn test_start_timer_requires_task() {
+    let mut editor = editor_with_lines(&["plain text"]);
+    editor.start_task_timer();
+    assert!(editor.task_timer.running.is_none());
+    assert_eq!(editor.status_message, "Not a task.");
+
+    editor.stop_task_timer();
+    assert_eq!(editor.status_message, "No timer running.");
+}
+
+#[test]
+fn test_starting_another_task_stops_previous_timer() {
+    let mut editor = editor_with_lines(&["- [ ] a", "- [ ] b"]);
+    editor.start_task_timer();
+    rewind_timer(&mut editor, Duration::from_secs(5 * 60));
+    editor.set_cursor_pos(0, 1);
+    editor.start_task_timer();
+
+    assert_eq!(editor.document.lines()[0], "- [ ] a @spent(5m)");
+    assert_eq!(editor.task_timer.running.as_ref().unwrap().task, "- [ ] b");
+}
+
+#[test]
+fn test_running_timer_persists_across_sessions() {
+    let state_dir = TempDir::new().unwrap();
+    let base_dir = Some(state_dir.path().to_path_buf());
+
+    let mut editor = editor_with_lines(&["- [ ] long task"]);
+    editor.enable_task_timer_persistence_with_base_dir(base_dir.clone());
+    editor.start_task_timer();
+    let started = editor.task_timer.running.clone();
+
+    let mut editor = editor_with_lines(&["- [ ] long task"]);
+    editor.enable_task_timer_persistence_with_base_dir(base_dir.clone());
+    assert_eq!(editor.task_timer.running, started);
+
+    editor.stop_task_timer();
+    let editor = {
+        let mut editor = editor_with_lines(&[""]);
+        editor.enable_task_timer_persistence_with_base_dir(base_dir);
+        editor
+    };
+    assert!(editor.task_timer.running.is_none());
+}
+
+#[test]
+fn test_format_and_parse_spent() {
+    assert_eq!(task_timer::format_spent(Duration::from_secs(59)), "0m");
+    assert_eq!(task_timer::format_spent(Duration::from_secs(3600)), "1h0m");
+    assert_eq!(
+        task_timer::parse_spent("2h5m"),
+        Some(Duration::from_secs(125 * 60))
+    );
+    assert_eq!(
+        task_timer::parse_spent("3h"),
+        Some(Duration::from_secs(180 * 60))
+    );
+    assert_eq!(task_timer::parse_spent("abc"), None);
+    assert_eq!(task_timer::parse_spent("12"), None);
+}
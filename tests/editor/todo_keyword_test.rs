@@ -0,0 +1,80 @@
+use crate::editor_with_lines;
+use dmacs::editor::actions::Action;
+use dmacs::editor::todo_keyword::find_keyword;
+
+#[test]
+fn test_cycle_keyword_on_list_item() {
+    let mut editor = editor_with_lines(&["- write report"]);
+    editor.set_cursor_pos(8, 0);
+
+    editor.execute_action(Action::CycleTodoKeyword).unwrap();
+    assert_eq!(editor.document.lines()[0], "- TODO write report");
+    assert_eq!(editor.cursor_pos(), (13, 0));
+
+    editor.execute_action(Action::CycleTodoKeyword).unwrap();
+    assert_eq!(editor.document.lines()[0], "- DOING write report");
+    editor.execute_action(Action::CycleTodoKeyword).unwrap();
+    assert_eq!(editor.document.lines()[0], "- DONE write report");
+    editor.execute_action(Action::CycleTodoKeyword).unwrap();
+    assert_eq!(editor.document.lines()[0], "- WAIT write report");
+    editor.execute_action(Action::CycleTodoKeyword).unwrap();
+    assert_eq!(editor.document.lines()[0], "- write report");
+    assert_eq!(editor.cursor_pos(), (8, 0));
+}
+
+#[test]
+fn test_cycle_keyword_on_heading_and_undo() {
+    let mut editor = editor_with_lines(&["  ## Release"]);
+    editor.execute_action(Action::CycleTodoKeyword).unwrap();
+    assert_eq!(editor.document.lines()[0], "  ## TODO Release");
+    editor.execute_action(Action::CycleTodoKeyword).unwrap();
+    assert_eq!(editor.document.lines()[0], "  ## DOING Release");
+
+    editor.undo();
+    assert_eq!(editor.document.lines()[0], "  ## TODO Release");
+    editor.undo();
+    assert_eq!(editor.document.lines()[0], "  ## Release");
+}
+
+#[test]
+fn test_cycle_keyword_on_empty_list_item() {
+    let mut editor = editor_with_lines(&["- "]);
+    editor.execute_action(Action::CycleTodoKeyword).unwrap();
+    assert_eq!(editor.document.lines()[0], "- TODO");
+    editor.execute_action(Action::CycleTodoKeyword).unwrap();
+    assert_eq!(editor.document.lines()[0], "- DOING");
+}
+
+#[test]
+fn test_cycle_keyword_ignores_plain_lines() {
+    let mut editor = editor_with_lines(&["just text", "#hashtag"]);
+    editor.execute_action(Action::CycleTodoKeyword).unwrap();
+    assert_eq!(editor.document.lines()[0], "just text");
+    assert_eq!(
+        editor.status_message,
+        "TODO keywords go on heading or list lines."
+    );
+
+    editor.set_cursor_pos(0, 1);
+    editor.execute_action(Action::CycleTodoKeyword).unwrap();
+    assert_eq!(editor.document.lines()[1], "#hashtag");
+}
+
+#[test]
+fn test_configured_keywords() {
+    let mut editor = editor_with_lines(&["# NEXT plan trip"]);
+    editor.settings.todo_keywords = Some(vec!["NEXT".to_string(), "DONE".to_string()]);
+    editor.execute_action(Action::CycleTodoKeyword).unwrap();
+    assert_eq!(editor.document.lines()[0], "# DONE plan trip");
+    editor.execute_action(Action::CycleTodoKeyword).unwrap();
+    assert_eq!(editor.document.lines()[0], "# plan trip");
+}
+
+#[test]
+fn test_find_keyword() {
+    let keywords = ["TODO", "DONE"];
+    assert_eq!(find_keyword("- DONE x", &keywords), Some((2, 6, 1)));
+    assert_eq!(find_keyword("## TODO", &keywords), Some((3, 7, 0)));
+    assert_eq!(find_keyword("- TODOS x", &keywords), None);
+    assert_eq!(find_keyword("TODO x", &keywords), None);
+}
@@ -0,0 +1,69 @@
+use crate::editor_with_lines;
+use dmacs::editor::Editor;
+use dmacs::editor::actions::Action;
+use pancurses::Input;
+
+fn run_command_on_selection(editor: &mut Editor, start_y: usize, command_y: usize) {
+    editor.set_cursor_pos(0, start_y);
+    editor.execute_action(Action::SetMarker).unwrap();
+    let len = editor.document.lines()[command_y].len();
+    editor.set_cursor_pos(len, command_y);
+    editor.process_input(Input::Character('\n'), false).unwrap();
+}
+
+#[test]
+fn test_export_selection_to_todotxt() {
+    let mut editor = editor_with_lines(&[
+        "# Today",
+        "- [ ] (A) Call mom @due(2025-06-12)",
+        "some notes",
+        "- [x] Pay rent",
+        "/export-todotxt",
+        "after",
+    ]);
+    run_command_on_selection(&mut editor, 1, 4);
+    assert_eq!(
+        editor.document.lines(),
+        vec![
+            "# Today",
+            "(A) Call mom due:2025-06-12",
+            "some notes",
+            "x Pay rent",
+            "after"
+        ]
+    );
+    assert!(!editor.selection.is_selection_active());
+    assert_eq!(editor.status_message, "Converted 3 lines to todo.txt.");
+
+    editor.undo();
+    assert_eq!(
+        editor.document.lines()[1],
+        "- [ ] (A) Call mom @due(2025-06-12)"
+    );
+    assert_eq!(editor.document.lines()[4], "/export-todotxt");
+}
+
+#[test]
+fn test_import_selection_from_todotxt() {
+    let mut editor = editor_with_lines(&["(B) Buy milk +shop", "", "/import-todotxt"]);
+    run_command_on_selection(&mut editor, 0, 2);
+    assert_eq!(
+        editor.document.lines(),
+        vec!["- [ ] (B) Buy milk +shop", ""]
+    );
+}
+
+#[test]
+fn test_todotxt_command_needs_selection() {
+    let mut editor = editor_with_lines(&["- [ ] task", "/export-todotxt"]);
+    editor.set_cursor_pos(15, 1);
+    editor.process_input(Input::Character('\n'), false).unwrap();
+    assert_eq!(
+        editor.document.lines(),
+        vec!["- [ ] task", "/export-todotxt"]
+    );
+    assert_eq!(
+        editor.status_message,
+        "Select the lines to convert, ending with the command line."
+    );
+}
@@ -0,0 +1,87 @@
+use dmacs::clock::ManualClock;
+use dmacs::editor::Editor;
+use dmacs::editor::actions::Action;
+use pancurses::Input;
+use std::time::Duration;
+
+const MINUTE: Duration = Duration::from_secs(60);
+
+fn type_text(editor: &mut Editor, clock: &ManualClock, text: &str) {
+    for c in text.chars() {
+        editor.process_input(Input::Character(c), false).unwrap();
+        clock.advance(Duration::from_millis(100));
+    }
+}
+
+fn editor_with_clock() -> (Editor, ManualClock) {
+    let clock = ManualClock::new();
+    let mut editor = Editor::new(None, None, None);
+    editor.set_clock(clock.clone());
+    (editor, clock)
+}
+
+#[test]
+fn test_snapshot_waits_for_interval_and_pause() {
+    let (mut editor, clock) = editor_with_clock();
+    type_text(&mut editor, &clock, "a");
+    clock.advance(4 * MINUTE);
+    editor.undo_snapshot_tick();
+    assert!(editor.undo_redo.snapshots.is_empty());
+
+    clock.advance(MINUTE);
+    type_text(&mut editor, &clock, "b");
+    // Still typing
+    editor.undo_snapshot_tick();
+    assert!(editor.undo_redo.snapshots.is_empty());
+
+    clock.advance(Duration::from_secs(2));
+    editor.undo_snapshot_tick();
+    assert_eq!(editor.undo_redo.snapshots.len(), 1);
+    assert_eq!(editor.undo_redo.snapshots[0].depth, 2);
+
+    // Nothing edited since
+    clock.advance(10 * MINUTE);
+    editor.undo_snapshot_tick();
+    assert_eq!(editor.undo_redo.snapshots.len(), 1);
+}
+
+#[test]
+fn test_undo_to_snapshot_and_redo_back() {
+    let (mut editor, clock) = editor_with_clock();
+    type_text(&mut editor, &clock, "first");
+    editor.undo_redo.take_snapshot();
+    type_text(&mut editor, &clock, " second");
+    clock.advance(MINUTE);
+    type_text(&mut editor, &clock, " third");
+
+    editor.execute_action(Action::UndoToSnapshot).unwrap();
+    assert_eq!(editor.document.lines()[0], "first");
+    assert!(
+        editor
+            .status_message
+            .starts_with("Back to the snapshot of ")
+    );
+
+    editor.execute_action(Action::UndoToSnapshot).unwrap();
+    assert_eq!(editor.status_message, "No earlier snapshot.");
+    assert_eq!(editor.document.lines()[0], "first");
+
+    editor.redo();
+    editor.redo();
+    assert_eq!(editor.document.lines()[0], "first second third");
+}
+
+#[test]
+fn test_snapshot_past_redo_is_dropped_by_new_edit() {
+    let (mut editor, clock) = editor_with_clock();
+    type_text(&mut editor, &clock, "a");
+    editor.undo_redo.take_snapshot();
+    type_text(&mut editor, &clock, "b");
+    editor.undo_redo.take_snapshot();
+    assert_eq!(editor.undo_redo.snapshots.len(), 2);
+
+    editor.undo();
+    type_text(&mut editor, &clock, "c");
+    let depths: Vec<usize> = editor.undo_redo.snapshots.iter().map(|s| s.depth).collect();
+    assert_eq!(depths, vec![1]);
+}
@@ -1,5 +1,7 @@
+use dmacs::clock::ManualClock;
 use dmacs::editor::Editor;
 use pancurses::Input;
+use std::time::Duration;
 
 fn editor_with_clipboard_disabled() -> Editor {
     let mut editor = Editor::new(None, None, None);
@@ -14,7 +16,7 @@ fn test_debounced_undo_insertion() {
 
     // Type 'a' - should create a new undo entry
     editor.process_input(Input::Character('a'), false).unwrap();
-    assert_eq!(editor.document.lines[0], "a");
+    assert_eq!(editor.document.lines()[0], "a");
     assert_eq!(
         editor.undo_redo.undo_stack.len(),
         1,
@@ -23,7 +25,7 @@ fn test_debounced_undo_insertion() {
 
     // Type 'b' within debounce threshold - should group with 'a'
     editor.process_input(Input::Character('b'), false).unwrap();
-    assert_eq!(editor.document.lines[0], "ab");
+    assert_eq!(editor.document.lines()[0], "ab");
     assert_eq!(
         editor.undo_redo.undo_stack.len(),
         1,
@@ -32,7 +34,7 @@ fn test_debounced_undo_insertion() {
 
     // Type 'c' within debounce threshold - should group with 'a' and 'b'
     editor.process_input(Input::Character('c'), false).unwrap();
-    assert_eq!(editor.document.lines[0], "abc");
+    assert_eq!(editor.document.lines()[0], "abc");
     assert_eq!(
         editor.undo_redo.undo_stack.len(),
         1,
@@ -42,7 +44,7 @@ fn test_debounced_undo_insertion() {
     // Type 'd' after debounce threshold - should create a new undo entry
     editor.set_undo_debounce_threshold(0);
     editor.process_input(Input::Character('d'), false).unwrap();
-    assert_eq!(editor.document.lines[0], "abcd");
+    assert_eq!(editor.document.lines()[0], "abcd");
     assert_eq!(
         editor.undo_redo.undo_stack.len(),
         2,
@@ -51,12 +53,12 @@ fn test_debounced_undo_insertion() {
 
     // Undo 'd'
     editor.undo();
-    assert_eq!(editor.document.lines[0], "abc");
+    assert_eq!(editor.document.lines()[0], "abc");
     assert_eq!(editor.undo_redo.undo_stack.len(), 1, "Undo 'd'");
 
     // Undo 'abc' (grouped)
     editor.undo();
-    assert_eq!(editor.document.lines[0], "");
+    assert_eq!(editor.document.lines()[0], "");
     assert_eq!(editor.undo_redo.undo_stack.len(), 0, "Undo 'abc'");
 }
 
@@ -74,7 +76,7 @@ fn test_debounced_undo_deletion() {
 
     // Delete 'd' - should create a new undo entry
     editor.process_input(Input::KeyBackspace, false).unwrap();
-    assert_eq!(editor.document.lines[0], "abc");
+    assert_eq!(editor.document.lines()[0], "abc");
     assert_eq!(
         editor.undo_redo.undo_stack.len(),
         3,
@@ -84,7 +86,7 @@ fn test_debounced_undo_deletion() {
     // Delete 'c' within debounce threshold - should group with 'd' deletion
     editor.set_undo_debounce_threshold(1);
     editor.process_input(Input::KeyBackspace, false).unwrap();
-    assert_eq!(editor.document.lines[0], "ab");
+    assert_eq!(editor.document.lines()[0], "ab");
     assert_eq!(
         editor.undo_redo.undo_stack.len(),
         3,
@@ -94,7 +96,7 @@ fn test_debounced_undo_deletion() {
     // Delete 'b' within debounce threshold - should group with 'd' and 'c' deletions
     editor.set_undo_debounce_threshold(1);
     editor.process_input(Input::KeyBackspace, false).unwrap();
-    assert_eq!(editor.document.lines[0], "a");
+    assert_eq!(editor.document.lines()[0], "a");
     assert_eq!(
         editor.undo_redo.undo_stack.len(),
         3,
@@ -104,7 +106,7 @@ fn test_debounced_undo_deletion() {
     // Delete 'a' after debounce threshold
     editor.set_undo_debounce_threshold(0);
     editor.process_input(Input::KeyBackspace, false).unwrap();
-    assert_eq!(editor.document.lines[0], "");
+    assert_eq!(editor.document.lines()[0], "");
     assert_eq!(
         editor.undo_redo.undo_stack.len(),
         4,
@@ -113,12 +115,12 @@ fn test_debounced_undo_deletion() {
 
     // Undo 'a'
     editor.undo();
-    assert_eq!(editor.document.lines[0], "a");
+    assert_eq!(editor.document.lines()[0], "a");
     assert_eq!(editor.undo_redo.undo_stack.len(), 3, "Undo 'a'");
 
     // Undo 'bcd' (grouped)
     editor.undo();
-    assert_eq!(editor.document.lines[0], "abcd");
+    assert_eq!(editor.document.lines()[0], "abcd");
     assert_eq!(editor.undo_redo.undo_stack.len(), 2, "Undo 'bcd'");
 }
 
@@ -132,13 +134,13 @@ fn test_debounced_undo_newline() {
 
     // Insert first newline
     editor.process_input(Input::Character('\n'), false).unwrap();
-    assert_eq!(editor.document.lines.len(), 2);
+    assert_eq!(editor.document.lines().len(), 2);
     assert_eq!(editor.undo_redo.undo_stack.len(), 2);
 
     // Insert second newline within debounce threshold
     editor.set_undo_debounce_threshold(1);
     editor.process_input(Input::Character('\n'), false).unwrap();
-    assert_eq!(editor.document.lines.len(), 3);
+    assert_eq!(editor.document.lines().len(), 3);
     assert_eq!(
         editor.undo_redo.undo_stack.len(),
         2,
@@ -148,7 +150,7 @@ fn test_debounced_undo_newline() {
     // Insert third newline after debounce threshold
     editor.set_undo_debounce_threshold(0);
     editor.process_input(Input::Character('\n'), false).unwrap();
-    assert_eq!(editor.document.lines.len(), 4);
+    assert_eq!(editor.document.lines().len(), 4);
     assert_eq!(
         editor.undo_redo.undo_stack.len(),
         3,
@@ -157,12 +159,12 @@ fn test_debounced_undo_newline() {
 
     // Undo third newline
     editor.undo();
-    assert_eq!(editor.document.lines.len(), 3);
+    assert_eq!(editor.document.lines().len(), 3);
     assert_eq!(editor.undo_redo.undo_stack.len(), 2);
 
     // Undo first and second newlines (grouped)
     editor.undo();
-    assert_eq!(editor.document.lines.len(), 1);
+    assert_eq!(editor.document.lines().len(), 1);
     assert_eq!(editor.undo_redo.undo_stack.len(), 1);
 }
 
@@ -195,8 +197,8 @@ fn test_debounced_undo_mixed_actions() {
 
     // Undo sequence
     editor.undo(); // Undo deletion
-    assert_eq!(editor.document.lines[0], "ab");
-    assert_eq!(editor.document.lines[1], "c");
+    assert_eq!(editor.document.lines()[0], "ab");
+    assert_eq!(editor.document.lines()[1], "c");
     assert_eq!(editor.undo_redo.undo_stack.len(), 3);
 }
 
@@ -210,7 +212,7 @@ fn test_initial_state_undo() {
         0,
         "Initial state should not be saved"
     );
-    assert_eq!(editor.document.lines[0], "");
+    assert_eq!(editor.document.lines()[0], "");
 
     editor.undo();
     assert_eq!(
@@ -218,7 +220,7 @@ fn test_initial_state_undo() {
         0,
         "Undo stack should be empty after trying to undo empty state"
     );
-    assert_eq!(editor.document.lines[0], ""); // Document should remain empty
+    assert_eq!(editor.document.lines()[0], ""); // Document should remain empty
     assert_eq!(editor.status_message, "Nothing to undo.");
 
     editor.undo(); // Try to undo again
@@ -240,96 +242,96 @@ fn test_redo() {
     editor.process_input(Input::Character('\n'), false).unwrap();
     editor.process_input(Input::Character('e'), false).unwrap();
 
-    assert_eq!(editor.document.lines[0], "abcd");
-    assert_eq!(editor.document.lines[1], "e");
+    assert_eq!(editor.document.lines()[0], "abcd");
+    assert_eq!(editor.document.lines()[1], "e");
     assert_eq!(editor.undo_redo.undo_stack.len(), 4); // 'abc', 'd', newline, 'e'
     assert_eq!(editor.undo_redo.redo_stack.len(), 0);
-    assert_eq!(editor.cursor_y, 1);
-    assert_eq!(editor.cursor_x, 1);
+    assert_eq!(editor.cursor.y, 1);
+    assert_eq!(editor.cursor.x, 1);
 
     // Undo 'e'
     editor.undo();
-    assert_eq!(editor.document.lines[0], "abcd");
-    assert_eq!(editor.document.lines.len(), 2); // Document should have 2 lines after undoing 'e'
+    assert_eq!(editor.document.lines()[0], "abcd");
+    assert_eq!(editor.document.lines().len(), 2); // Document should have 2 lines after undoing 'e'
     assert_eq!(editor.undo_redo.undo_stack.len(), 3);
     assert_eq!(editor.undo_redo.redo_stack.len(), 1); // 'e' should be in redo stack
-    assert_eq!(editor.cursor_y, 1);
-    assert_eq!(editor.cursor_x, 0);
+    assert_eq!(editor.cursor.y, 1);
+    assert_eq!(editor.cursor.x, 0);
 
     // Undo newline
     editor.undo();
-    assert_eq!(editor.document.lines[0], "abcd");
-    assert_eq!(editor.document.lines.len(), 1);
+    assert_eq!(editor.document.lines()[0], "abcd");
+    assert_eq!(editor.document.lines().len(), 1);
     assert_eq!(editor.undo_redo.undo_stack.len(), 2);
     assert_eq!(editor.undo_redo.redo_stack.len(), 2); // newline should be in redo stack
-    assert_eq!(editor.cursor_y, 0);
-    assert_eq!(editor.cursor_x, 4);
+    assert_eq!(editor.cursor.y, 0);
+    assert_eq!(editor.cursor.x, 4);
 
     // Undo 'd'
     editor.undo();
-    assert_eq!(editor.document.lines[0], "abc");
+    assert_eq!(editor.document.lines()[0], "abc");
     assert_eq!(editor.undo_redo.undo_stack.len(), 1);
     assert_eq!(editor.undo_redo.redo_stack.len(), 3); // 'd' should be in redo stack
-    assert_eq!(editor.cursor_y, 0);
-    assert_eq!(editor.cursor_x, 3);
+    assert_eq!(editor.cursor.y, 0);
+    assert_eq!(editor.cursor.x, 3);
 
     // Redo 'd'
     editor.redo();
-    assert_eq!(editor.document.lines[0], "abcd");
+    assert_eq!(editor.document.lines()[0], "abcd");
     assert_eq!(editor.undo_redo.undo_stack.len(), 2);
     assert_eq!(editor.undo_redo.redo_stack.len(), 2); // newline should be in redo stack
-    assert_eq!(editor.cursor_y, 0);
-    assert_eq!(editor.cursor_x, 4);
+    assert_eq!(editor.cursor.y, 0);
+    assert_eq!(editor.cursor.x, 4);
 
     // Redo newline
     editor.redo();
-    assert_eq!(editor.document.lines[0], "abcd");
-    assert_eq!(editor.document.lines.len(), 2);
+    assert_eq!(editor.document.lines()[0], "abcd");
+    assert_eq!(editor.document.lines().len(), 2);
     assert_eq!(editor.undo_redo.undo_stack.len(), 3);
     assert_eq!(editor.undo_redo.redo_stack.len(), 1); // 'e' should be in redo stack
-    assert_eq!(editor.cursor_y, 1);
-    assert_eq!(editor.cursor_x, 0);
+    assert_eq!(editor.cursor.y, 1);
+    assert_eq!(editor.cursor.x, 0);
 
     // Redo 'e'
     editor.redo();
-    assert_eq!(editor.document.lines[0], "abcd");
-    assert_eq!(editor.document.lines[1], "e");
+    assert_eq!(editor.document.lines()[0], "abcd");
+    assert_eq!(editor.document.lines()[1], "e");
     assert_eq!(editor.undo_redo.undo_stack.len(), 4);
     assert_eq!(editor.undo_redo.redo_stack.len(), 0);
-    assert_eq!(editor.cursor_y, 1);
-    assert_eq!(editor.cursor_x, 1);
+    assert_eq!(editor.cursor.y, 1);
+    assert_eq!(editor.cursor.x, 1);
 
     // Try to redo when redo stack is empty
     editor.redo();
     assert_eq!(editor.status_message, "Nothing to redo.");
     assert_eq!(editor.undo_redo.undo_stack.len(), 4);
     assert_eq!(editor.undo_redo.redo_stack.len(), 0);
-    assert_eq!(editor.cursor_y, 1);
-    assert_eq!(editor.cursor_x, 1);
+    assert_eq!(editor.cursor.y, 1);
+    assert_eq!(editor.cursor.x, 1);
 
     // Perform a new action after undoing, then try to redo (should not work)
     editor.undo(); // Undo 'e'
-    assert_eq!(editor.document.lines[0], "abcd");
-    assert_eq!(editor.document.lines.len(), 2);
+    assert_eq!(editor.document.lines()[0], "abcd");
+    assert_eq!(editor.document.lines().len(), 2);
     assert_eq!(editor.undo_redo.undo_stack.len(), 3);
     assert_eq!(editor.undo_redo.redo_stack.len(), 1);
-    assert_eq!(editor.cursor_y, 1);
-    assert_eq!(editor.cursor_x, 0);
+    assert_eq!(editor.cursor.y, 1);
+    assert_eq!(editor.cursor.x, 0);
 
     editor.process_input(Input::Character('f'), false).unwrap(); // New action
-    assert_eq!(editor.document.lines[0], "abcd");
-    assert_eq!(editor.document.lines[1], "f");
+    assert_eq!(editor.document.lines()[0], "abcd");
+    assert_eq!(editor.document.lines()[1], "f");
     assert_eq!(editor.undo_redo.undo_stack.len(), 4);
     assert_eq!(editor.undo_redo.redo_stack.len(), 0); // Redo stack should be cleared
-    assert_eq!(editor.cursor_y, 1);
-    assert_eq!(editor.cursor_x, 1);
+    assert_eq!(editor.cursor.y, 1);
+    assert_eq!(editor.cursor.x, 1);
 
     editor.redo(); // Should not redo 'e'
     assert_eq!(editor.status_message, "Nothing to redo.");
-    assert_eq!(editor.document.lines[0], "abcd");
-    assert_eq!(editor.document.lines[1], "f");
-    assert_eq!(editor.cursor_y, 1);
-    assert_eq!(editor.cursor_x, 1);
+    assert_eq!(editor.document.lines()[0], "abcd");
+    assert_eq!(editor.document.lines()[1], "f");
+    assert_eq!(editor.cursor.y, 1);
+    assert_eq!(editor.cursor.x, 1);
 }
 
 #[test]
@@ -342,27 +344,27 @@ fn test_redo_simple() {
     editor.process_input(Input::Character('b'), false).unwrap();
     editor.process_input(Input::Character('c'), false).unwrap();
 
-    assert_eq!(editor.document.lines[0], "abc");
+    assert_eq!(editor.document.lines()[0], "abc");
     assert_eq!(editor.undo_redo.undo_stack.len(), 1); // 'abc'
     assert_eq!(editor.undo_redo.redo_stack.len(), 0);
-    assert_eq!(editor.cursor_y, 0);
-    assert_eq!(editor.cursor_x, 3);
+    assert_eq!(editor.cursor.y, 0);
+    assert_eq!(editor.cursor.x, 3);
 
     // Undo 'abc'
     editor.undo();
-    assert_eq!(editor.document.lines[0], "");
+    assert_eq!(editor.document.lines()[0], "");
     assert_eq!(editor.undo_redo.undo_stack.len(), 0);
     assert_eq!(editor.undo_redo.redo_stack.len(), 1); // 'abc' should be in redo stack
-    assert_eq!(editor.cursor_y, 0);
-    assert_eq!(editor.cursor_x, 0);
+    assert_eq!(editor.cursor.y, 0);
+    assert_eq!(editor.cursor.x, 0);
 
     // Redo 'abc'
     editor.redo();
-    assert_eq!(editor.document.lines[0], "abc");
+    assert_eq!(editor.document.lines()[0], "abc");
     assert_eq!(editor.undo_redo.undo_stack.len(), 1);
     assert_eq!(editor.undo_redo.redo_stack.len(), 0);
-    assert_eq!(editor.cursor_y, 0);
-    assert_eq!(editor.cursor_x, 3);
+    assert_eq!(editor.cursor.y, 0);
+    assert_eq!(editor.cursor.x, 3);
 }
 
 #[test]
@@ -371,54 +373,54 @@ fn test_undo_redo_kill_line() {
     editor.set_undo_debounce_threshold(0); // Disable debouncing for clear test cases
 
     editor.insert_text("Hello World").unwrap();
-    assert_eq!(editor.cursor_x, 11);
-    assert_eq!(editor.cursor_y, 0);
+    assert_eq!(editor.cursor.x, 11);
+    assert_eq!(editor.cursor.y, 0);
 
     editor.insert_newline().unwrap();
-    assert_eq!(editor.cursor_x, 0);
-    assert_eq!(editor.cursor_y, 1);
+    assert_eq!(editor.cursor.x, 0);
+    assert_eq!(editor.cursor.y, 1);
 
     editor.insert_text("Another Line").unwrap();
-    assert_eq!(editor.cursor_x, 12);
-    assert_eq!(editor.cursor_y, 1);
+    assert_eq!(editor.cursor.x, 12);
+    assert_eq!(editor.cursor.y, 1);
 
     editor.go_to_start_of_file();
-    assert_eq!(editor.cursor_x, 0);
-    assert_eq!(editor.cursor_y, 0);
+    assert_eq!(editor.cursor.x, 0);
+    assert_eq!(editor.cursor.y, 0);
 
     editor.move_cursor_down(); // Move to "Another Line"
-    assert_eq!(editor.cursor_x, 0);
-    assert_eq!(editor.cursor_y, 1);
+    assert_eq!(editor.cursor.x, 0);
+    assert_eq!(editor.cursor.y, 1);
 
     // Kill "Another Line"
     editor.kill_line().unwrap();
-    assert_eq!(editor.document.lines.len(), 2);
-    assert_eq!(editor.document.lines[0], "Hello World");
-    assert_eq!(editor.document.lines[1], "");
+    assert_eq!(editor.document.lines().len(), 2);
+    assert_eq!(editor.document.lines()[0], "Hello World");
+    assert_eq!(editor.document.lines()[1], "");
     assert_eq!(editor.undo_redo.undo_stack.len(), 4); // Insertion, Newline, Insertion, Kill Line
     assert_eq!(editor.undo_redo.redo_stack.len(), 0);
-    assert_eq!(editor.cursor_x, 0);
-    assert_eq!(editor.cursor_y, 1);
+    assert_eq!(editor.cursor.x, 0);
+    assert_eq!(editor.cursor.y, 1);
 
     // Undo kill_line
     editor.undo();
-    assert_eq!(editor.document.lines.len(), 2);
-    assert_eq!(editor.document.lines[0], "Hello World");
-    assert_eq!(editor.document.lines[1], "Another Line");
+    assert_eq!(editor.document.lines().len(), 2);
+    assert_eq!(editor.document.lines()[0], "Hello World");
+    assert_eq!(editor.document.lines()[1], "Another Line");
     assert_eq!(editor.undo_redo.undo_stack.len(), 3);
     assert_eq!(editor.undo_redo.redo_stack.len(), 1);
-    assert_eq!(editor.cursor_x, 0);
-    assert_eq!(editor.cursor_y, 1);
+    assert_eq!(editor.cursor.x, 0);
+    assert_eq!(editor.cursor.y, 1);
 
     // Redo kill_line
     editor.redo();
-    assert_eq!(editor.document.lines.len(), 2);
-    assert_eq!(editor.document.lines[0], "Hello World");
-    assert_eq!(editor.document.lines[1], "");
+    assert_eq!(editor.document.lines().len(), 2);
+    assert_eq!(editor.document.lines()[0], "Hello World");
+    assert_eq!(editor.document.lines()[1], "");
     assert_eq!(editor.undo_redo.undo_stack.len(), 4);
     assert_eq!(editor.undo_redo.redo_stack.len(), 0);
-    assert_eq!(editor.cursor_x, 0);
-    assert_eq!(editor.cursor_y, 1);
+    assert_eq!(editor.cursor.x, 0);
+    assert_eq!(editor.cursor.y, 1);
 }
 
 #[test]
@@ -427,59 +429,59 @@ fn test_undo_redo_yank() {
     editor.set_undo_debounce_threshold(0);
 
     editor.insert_text("Yank Me").unwrap();
-    assert_eq!(editor.cursor_x, 7);
-    assert_eq!(editor.cursor_y, 0);
+    assert_eq!(editor.cursor.x, 7);
+    assert_eq!(editor.cursor.y, 0);
 
     editor.go_to_start_of_file();
-    assert_eq!(editor.cursor_x, 0);
-    assert_eq!(editor.cursor_y, 0);
+    assert_eq!(editor.cursor.x, 0);
+    assert_eq!(editor.cursor.y, 0);
 
     editor.set_marker_action();
-    assert_eq!(editor.cursor_x, 0);
-    assert_eq!(editor.cursor_y, 0);
+    assert_eq!(editor.cursor.x, 0);
+    assert_eq!(editor.cursor.y, 0);
 
     editor.go_to_end_of_line();
-    assert_eq!(editor.cursor_x, 7);
-    assert_eq!(editor.cursor_y, 0);
+    assert_eq!(editor.cursor.x, 7);
+    assert_eq!(editor.cursor.y, 0);
 
     editor.copy_selection_action().unwrap(); // Copy "Yank Me" to kill buffer
-    assert_eq!(editor.cursor_x, 7);
-    assert_eq!(editor.cursor_y, 0);
+    assert_eq!(editor.cursor.x, 7);
+    assert_eq!(editor.cursor.y, 0);
 
     editor.delete_char().unwrap(); // Delete "e" to make a change
-    assert_eq!(editor.cursor_x, 6);
-    assert_eq!(editor.cursor_y, 0);
+    assert_eq!(editor.cursor.x, 6);
+    assert_eq!(editor.cursor.y, 0);
 
     editor.delete_char().unwrap(); // Delete "M"
-    assert_eq!(editor.cursor_x, 5);
-    assert_eq!(editor.cursor_y, 0);
+    assert_eq!(editor.cursor.x, 5);
+    assert_eq!(editor.cursor.y, 0);
 
     editor.delete_char().unwrap(); // Delete " "
-    assert_eq!(editor.cursor_x, 4);
-    assert_eq!(editor.cursor_y, 0);
+    assert_eq!(editor.cursor.x, 4);
+    assert_eq!(editor.cursor.y, 0);
 
     editor.yank().unwrap(); // Yank "Yank Me"
-    assert_eq!(editor.document.lines[0], "YankYank Me");
+    assert_eq!(editor.document.lines()[0], "YankYank Me");
     assert_eq!(editor.undo_redo.undo_stack.len(), 5); // Insertion, Del, Del, Del, Yank
     assert_eq!(editor.undo_redo.redo_stack.len(), 0);
-    assert_eq!(editor.cursor_x, 11);
-    assert_eq!(editor.cursor_y, 0);
+    assert_eq!(editor.cursor.x, 11);
+    assert_eq!(editor.cursor.y, 0);
 
     // Undo yank
     editor.undo();
-    assert_eq!(editor.document.lines[0], "Yank");
+    assert_eq!(editor.document.lines()[0], "Yank");
     assert_eq!(editor.undo_redo.undo_stack.len(), 4);
     assert_eq!(editor.undo_redo.redo_stack.len(), 1);
-    assert_eq!(editor.cursor_x, 4);
-    assert_eq!(editor.cursor_y, 0);
+    assert_eq!(editor.cursor.x, 4);
+    assert_eq!(editor.cursor.y, 0);
 
     // Redo yank
     editor.redo();
-    assert_eq!(editor.document.lines[0], "YankYank Me");
+    assert_eq!(editor.document.lines()[0], "YankYank Me");
     assert_eq!(editor.undo_redo.undo_stack.len(), 5);
     assert_eq!(editor.undo_redo.redo_stack.len(), 0);
-    assert_eq!(editor.cursor_x, 11);
-    assert_eq!(editor.cursor_y, 0);
+    assert_eq!(editor.cursor.x, 11);
+    assert_eq!(editor.cursor.y, 0);
 }
 
 #[test]
@@ -488,79 +490,79 @@ fn test_undo_redo_cut_selection() {
     editor.set_undo_debounce_threshold(0);
 
     editor.insert_text("Line One").unwrap();
-    assert_eq!(editor.cursor_x, 8);
-    assert_eq!(editor.cursor_y, 0);
+    assert_eq!(editor.cursor.x, 8);
+    assert_eq!(editor.cursor.y, 0);
 
     editor.insert_newline().unwrap();
-    assert_eq!(editor.cursor_x, 0);
-    assert_eq!(editor.cursor_y, 1);
+    assert_eq!(editor.cursor.x, 0);
+    assert_eq!(editor.cursor.y, 1);
 
     editor.insert_text("Line Two").unwrap();
-    assert_eq!(editor.cursor_x, 8);
-    assert_eq!(editor.cursor_y, 1);
+    assert_eq!(editor.cursor.x, 8);
+    assert_eq!(editor.cursor.y, 1);
 
     editor.insert_newline().unwrap();
-    assert_eq!(editor.cursor_x, 0);
-    assert_eq!(editor.cursor_y, 2);
+    assert_eq!(editor.cursor.x, 0);
+    assert_eq!(editor.cursor.y, 2);
 
     editor.insert_text("Line Three").unwrap();
-    assert_eq!(editor.cursor_x, 10);
-    assert_eq!(editor.cursor_y, 2);
+    assert_eq!(editor.cursor.x, 10);
+    assert_eq!(editor.cursor.y, 2);
 
     editor.go_to_start_of_file();
-    assert_eq!(editor.cursor_x, 0);
-    assert_eq!(editor.cursor_y, 0);
+    assert_eq!(editor.cursor.x, 0);
+    assert_eq!(editor.cursor.y, 0);
 
     editor.move_cursor_down(); // Move to "Line Two"
-    assert_eq!(editor.cursor_x, 0);
-    assert_eq!(editor.cursor_y, 1);
+    assert_eq!(editor.cursor.x, 0);
+    assert_eq!(editor.cursor.y, 1);
 
     editor.set_marker_action();
-    assert_eq!(editor.cursor_x, 0);
-    assert_eq!(editor.cursor_y, 1);
+    assert_eq!(editor.cursor.x, 0);
+    assert_eq!(editor.cursor.y, 1);
 
     editor.go_to_end_of_line();
-    assert_eq!(editor.cursor_x, 8);
-    assert_eq!(editor.cursor_y, 1);
+    assert_eq!(editor.cursor.x, 8);
+    assert_eq!(editor.cursor.y, 1);
 
     editor.move_cursor_down(); // Move to "Line Three"
-    assert_eq!(editor.cursor_x, 8);
-    assert_eq!(editor.cursor_y, 2);
+    assert_eq!(editor.cursor.x, 8);
+    assert_eq!(editor.cursor.y, 2);
 
     editor.move_cursor_right(); // Select "Line Thre"
-    assert_eq!(editor.cursor_x, 9);
-    assert_eq!(editor.cursor_y, 2);
+    assert_eq!(editor.cursor.x, 9);
+    assert_eq!(editor.cursor.y, 2);
 
     // Cut "Line Two\nLine Thre"
     editor.cut_selection_action().unwrap();
-    assert_eq!(editor.document.lines.len(), 2);
-    assert_eq!(editor.document.lines[0], "Line One");
-    assert_eq!(editor.document.lines[1], "e");
+    assert_eq!(editor.document.lines().len(), 2);
+    assert_eq!(editor.document.lines()[0], "Line One");
+    assert_eq!(editor.document.lines()[1], "e");
     assert_eq!(editor.undo_redo.undo_stack.len(), 6); // Insertion, Newline, Insertion, Newline, Insertion, Cut
     assert_eq!(editor.undo_redo.redo_stack.len(), 0);
-    assert_eq!(editor.cursor_x, 0);
-    assert_eq!(editor.cursor_y, 1);
+    assert_eq!(editor.cursor.x, 0);
+    assert_eq!(editor.cursor.y, 1);
 
     // Undo cut
     editor.undo();
-    assert_eq!(editor.document.lines.len(), 3);
-    assert_eq!(editor.document.lines[0], "Line One");
-    assert_eq!(editor.document.lines[1], "Line Two");
-    assert_eq!(editor.document.lines[2], "Line Three");
+    assert_eq!(editor.document.lines().len(), 3);
+    assert_eq!(editor.document.lines()[0], "Line One");
+    assert_eq!(editor.document.lines()[1], "Line Two");
+    assert_eq!(editor.document.lines()[2], "Line Three");
     assert_eq!(editor.undo_redo.undo_stack.len(), 5);
     assert_eq!(editor.undo_redo.redo_stack.len(), 1);
-    assert_eq!(editor.cursor_x, 9);
-    assert_eq!(editor.cursor_y, 2);
+    assert_eq!(editor.cursor.x, 9);
+    assert_eq!(editor.cursor.y, 2);
 
     // Redo cut
     editor.redo();
-    assert_eq!(editor.document.lines.len(), 2);
-    assert_eq!(editor.document.lines[0], "Line One");
-    assert_eq!(editor.document.lines[1], "e");
+    assert_eq!(editor.document.lines().len(), 2);
+    assert_eq!(editor.document.lines()[0], "Line One");
+    assert_eq!(editor.document.lines()[1], "e");
     assert_eq!(editor.undo_redo.undo_stack.len(), 6);
     assert_eq!(editor.undo_redo.redo_stack.len(), 0);
-    assert_eq!(editor.cursor_x, 0);
-    assert_eq!(editor.cursor_y, 1);
+    assert_eq!(editor.cursor.x, 0);
+    assert_eq!(editor.cursor.y, 1);
 }
 
 #[test]
@@ -569,30 +571,97 @@ fn test_undo_redo_move_line() {
     editor.set_undo_debounce_threshold(0);
 
     editor.insert_text("Line 1").unwrap();
-    assert_eq!(editor.cursor_x, 6);
-    assert_eq!(editor.cursor_y, 0);
+    assert_eq!(editor.cursor.x, 6);
+    assert_eq!(editor.cursor.y, 0);
 
     editor.insert_newline().unwrap();
-    assert_eq!(editor.cursor_x, 0);
-    assert_eq!(editor.cursor_y, 1);
+    assert_eq!(editor.cursor.x, 0);
+    assert_eq!(editor.cursor.y, 1);
 
     editor.insert_text("Line Two").unwrap();
-    assert_eq!(editor.cursor_x, 8);
-    assert_eq!(editor.cursor_y, 1);
+    assert_eq!(editor.cursor.x, 8);
+    assert_eq!(editor.cursor.y, 1);
 
     editor.go_to_start_of_file();
-    assert_eq!(editor.cursor_x, 0);
-    assert_eq!(editor.cursor_y, 0);
+    assert_eq!(editor.cursor.x, 0);
+    assert_eq!(editor.cursor.y, 0);
 
     editor.move_line_down(); // Swap "Line 1" with "Line Two"
-    assert_eq!(editor.cursor_x, 0);
-    assert_eq!(editor.cursor_y, 1);
-    assert_eq!(editor.document.lines[0], "Line Two");
-    assert_eq!(editor.document.lines[1], "Line 1");
+    assert_eq!(editor.cursor.x, 0);
+    assert_eq!(editor.cursor.y, 1);
+    assert_eq!(editor.document.lines()[0], "Line Two");
+    assert_eq!(editor.document.lines()[1], "Line 1");
 
     editor.undo();
-    assert_eq!(editor.cursor_x, 0);
-    assert_eq!(editor.cursor_y, 0);
-    assert_eq!(editor.document.lines[0], "Line 1");
-    assert_eq!(editor.document.lines[1], "Line Two");
+    assert_eq!(editor.cursor.x, 0);
+    assert_eq!(editor.cursor.y, 0);
+    assert_eq!(editor.document.lines()[0], "Line 1");
+    assert_eq!(editor.document.lines()[1], "Line Two");
+}
+
+#[test]
+fn test_typing_after_undo_starts_new_undo_step() {
+    let mut editor = editor_with_clipboard_disabled();
+    editor.insert_text("abc").unwrap();
+    editor.undo();
+    assert_eq!(editor.document.lines()[0], "");
+
+    editor.insert_text("x").unwrap();
+    assert_eq!(editor.document.lines()[0], "x");
+    assert!(editor.undo_redo.redo_stack.is_empty());
+
+    editor.undo();
+    assert_eq!(editor.document.lines()[0], "");
+}
+
+#[test]
+fn test_depth_indicator_counts_undo_and_redo_steps() {
+    let mut editor = Editor::new(None, None, None);
+    assert_eq!(editor.undo_redo.depth_indicator(), None);
+
+    editor.process_input(Input::Character('a'), false).unwrap();
+    editor.process_input(Input::Character('\n'), false).unwrap();
+    editor.process_input(Input::Character('b'), false).unwrap();
+    assert_eq!(
+        editor.undo_redo.depth_indicator().as_deref(),
+        Some("u:3 r:0")
+    );
+
+    editor.undo();
+    editor.undo();
+    assert_eq!(
+        editor.undo_redo.depth_indicator().as_deref(),
+        Some("u:1 r:2")
+    );
+
+    // A new edit drops the redo steps
+    editor.process_input(Input::Character('c'), false).unwrap();
+    assert_eq!(
+        editor.undo_redo.depth_indicator().as_deref(),
+        Some("u:2 r:0")
+    );
+}
+
+#[test]
+fn test_undo_grouping_follows_the_clock() {
+    let clock = ManualClock::new();
+    let mut editor = Editor::new(None, None, None);
+    editor.set_clock(clock.clone());
+
+    editor.process_input(Input::Character('a'), false).unwrap();
+    clock.advance(Duration::from_millis(499));
+    editor.process_input(Input::Character('b'), false).unwrap();
+    assert_eq!(editor.undo_redo.undo_stack.len(), 1);
+
+    // The threshold is measured from the last edit, not the first
+    clock.advance(Duration::from_millis(499));
+    editor.process_input(Input::Character('c'), false).unwrap();
+    assert_eq!(editor.undo_redo.undo_stack.len(), 1);
+
+    clock.advance(Duration::from_millis(500));
+    editor.process_input(Input::Character('d'), false).unwrap();
+    assert_eq!(editor.undo_redo.undo_stack.len(), 2);
+
+    editor.undo();
+    assert_eq!(editor.document.lines()[0], "abc");
 }
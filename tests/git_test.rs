@@ -0,0 +1,60 @@
+use dmacs::git;
+use std::fs;
+use tempfile::TempDir;
+
+fn repo_with_head(head: &str) -> TempDir {
+    let dir = TempDir::new().unwrap();
+    fs::create_dir(dir.path().join(".git")).unwrap();
+    fs::write(dir.path().join(".git/HEAD"), head).unwrap();
+    dir
+}
+
+#[test]
+fn test_current_branch_of_file_in_subdirectory() {
+    let dir = repo_with_head("ref: refs/heads/notes/2025\n");
+    fs::create_dir(dir.path().join("journal")).unwrap();
+    let file = dir.path().join("journal/today.md");
+
+    assert_eq!(
+        git::git_dir(&file),
+        Some(dir.path().canonicalize().unwrap().join(".git"))
+    );
+    assert_eq!(git::current_branch(&file), Some("notes/2025".to_string()));
+}
+
+#[test]
+fn test_current_branch_when_head_is_detached() {
+    let dir = repo_with_head("3f9a2c41d0be7e9f5a1b2c3d4e5f60718293a4b5\n");
+
+    assert_eq!(
+        git::current_branch(&dir.path().join("notes.md")),
+        Some("3f9a2c4".to_string())
+    );
+}
+
+#[test]
+fn test_git_file_points_to_git_dir() {
+    let dir = TempDir::new().unwrap();
+    let git_dir = dir.path().join("modules/notes");
+    fs::create_dir_all(&git_dir).unwrap();
+    fs::write(git_dir.join("HEAD"), "ref: refs/heads/main\n").unwrap();
+    let worktree = dir.path().join("notes");
+    fs::create_dir(&worktree).unwrap();
+    fs::write(
+        worktree.join(".git"),
+        format!("gitdir: {}\n", git_dir.display()),
+    )
+    .unwrap();
+
+    assert_eq!(
+        git::current_branch(&worktree.join("notes.md")),
+        Some("main".to_string())
+    );
+}
+
+#[test]
+fn test_no_branch_outside_a_repository() {
+    let dir = TempDir::new().unwrap();
+
+    assert_eq!(git::current_branch(&dir.path().join("notes.md")), None);
+}
@@ -0,0 +1,69 @@
+use chrono::{TimeZone, Utc};
+use dmacs::ical::export;
+
+fn now() -> chrono::DateTime<Utc> {
+    Utc.with_ymd_and_hms(2025, 6, 11, 9, 30, 0).unwrap()
+}
+
+#[test]
+fn test_export_tasks_with_due_dates() {
+    let content = "# Tasks\n- [ ] (A) pay rent @due(2025-06-12) +home\n- [ ] no date\n  - [x] send report @due(2025-06-10)\n";
+    let ics = export(content, now());
+
+    assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+    assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    assert_eq!(ics.matches("BEGIN:VTODO").count(), 2);
+    assert!(ics.contains("DTSTAMP:20250611T093000Z\r\n"));
+    assert!(ics.contains(
+        "SUMMARY:pay rent +home\r\nDUE;VALUE=DATE:20250612\r\nSTATUS:NEEDS-ACTION\r\nPRIORITY:1\r\n"
+    ));
+    assert!(ics.contains("SUMMARY:send report\r\nDUE;VALUE=DATE:20250610\r\nSTATUS:COMPLETED\r\n"));
+    assert!(!ics.contains("no date"));
+}
+
+#[test]
+fn test_uid_is_stable_across_check_off() {
+    let uid = |content: &str| {
+        export(content, now())
+            .lines()
+            .find(|line| line.starts_with("UID:"))
+            .unwrap()
+            .to_string()
+    };
+    assert_eq!(
+        uid("- [ ] call mom @due(2025-06-13)"),
+        uid("- [x] call mom @due(2025-06-13)")
+    );
+    assert_ne!(
+        uid("- [ ] call mom @due(2025-06-13)"),
+        uid("- [ ] call dad @due(2025-06-13)")
+    );
+}
+
+#[test]
+fn test_escapes_and_folds_long_lines() {
+    let text = "buy milk, eggs; bread ".repeat(5);
+    let ics = export(&format!("- [ ] {text}@due(2025-06-12)"), now());
+
+    assert!(ics.contains("SUMMARY:buy milk\\, eggs\\; bread"));
+    for line in ics.split("\r\n") {
+        assert!(line.len() <= 75, "line too long: {line}");
+    }
+    let unfolded = ics.replace("\r\n ", "");
+    assert!(unfolded.contains(&format!(
+        "SUMMARY:{}\r\n",
+        text.trim_end().replace(',', "\\,").replace(';', "\\;")
+    )));
+}
+
+#[test]
+fn test_folding_keeps_multibyte_characters_intact() {
+    let ics = export(
+        &format!("- [ ] {} @due(2025-06-12)", "日本語".repeat(10)),
+        now(),
+    );
+    for line in ics.split("\r\n") {
+        assert!(line.len() <= 75);
+    }
+    assert!(ics.replace("\r\n ", "").contains(&"日本語".repeat(10)));
+}
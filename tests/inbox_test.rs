@@ -0,0 +1,41 @@
+use dmacs::inbox;
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn test_capture_appends_timestamped_line() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("notes").join("inbox.md");
+
+    let first = inbox::capture(&path, "  buy milk ").unwrap();
+    inbox::capture(&path, "call back").unwrap();
+
+    assert!(first.starts_with("- "));
+    assert!(first.ends_with(" buy milk"));
+    let content = fs::read_to_string(&path).unwrap();
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0], first);
+    assert!(lines[1].ends_with(" call back"));
+}
+
+#[test]
+fn test_append_line_starts_on_new_line() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("inbox.md");
+    fs::write(&path, "# Inbox").unwrap();
+
+    inbox::append_line(&path, "- item").unwrap();
+
+    assert_eq!(fs::read_to_string(&path).unwrap(), "# Inbox\n- item\n");
+}
+
+#[test]
+fn test_resolve_path_expands_home() {
+    let home = dirs::home_dir().unwrap();
+    assert_eq!(inbox::resolve_path("~/inbox.md"), home.join("inbox.md"));
+    assert_eq!(
+        inbox::resolve_path("/tmp/inbox.md"),
+        std::path::PathBuf::from("/tmp/inbox.md")
+    );
+}
@@ -0,0 +1,186 @@
+use dmacs::editor::{Editor, EditorMode};
+use pancurses::Input;
+
+// Runs are short by default so that `cargo test` stays fast; raise them for a
+// longer session, e.g. DMACS_FUZZ_RUNS=100000, and replay a failure with the
+// seed it reports via DMACS_FUZZ_SEED.
+const DEFAULT_RUNS: u64 = 200;
+const KEYS_PER_RUN: usize = 150;
+
+// Text pieces documents and typing are made of, biased towards the markup and
+// multi-byte characters that byte-index bugs show up with
+const PIECES: &[&str] = &[
+    "a",
+    "Z",
+    "1",
+    " ",
+    "  ",
+    "-",
+    "_",
+    "#",
+    "# ",
+    "- [ ] ",
+    "- [x] ",
+    "---",
+    "()",
+    "「」",
+    "あ",
+    "漢字",
+    "カタカナ",
+    "。",
+    "、",
+    "😀",
+    "é",
+    "\t",
+    "TODO ",
+    "@due(2025-01-01)",
+    "`",
+];
+
+// Typed characters: printable ones and the control keys of the keymap that
+// stay inside the buffer (no saving, quitting, opening or switching files)
+const CHARACTERS: &[char] = &[
+    'a', 'b', 'x', ' ', '-', '[', ']', '#', '(', ')', '"', 'あ', '漢', '。', '😀', '\n', '\t',
+    '\x00', '\x01', '\x02', '\x04', '\x05', '\x06', '\x07', '\x0b', '\x0e', '\x10', '\x12', '\x13',
+    '\x14', '\x16', '\x17', '\x19', '\x1b', '\x1f', '\x7f',
+];
+
+// Alt combinations, leaving out save (s, S), open (o, O) and the scratch buffer (n)
+const ALT_CHARACTERS: &[char] = &[
+    'f', 'b', 'v', 'g', 'h', 'p', 'k', ',', '.', '{', '}', 'z', 'Z', 'U', 't', 'T', 'd', 'a', 'w',
+    'W', 'r', 'y', 'e', '(', ')', '/', '_', '<', '>',
+];
+
+const SPECIAL_KEYS: &[Input] = &[
+    Input::KeyUp,
+    Input::KeyDown,
+    Input::KeyLeft,
+    Input::KeyRight,
+    Input::KeyHome,
+    Input::KeyEnd,
+    Input::KeyBackspace,
+    Input::KeyDC,
+    Input::KeyBTab,
+    Input::KeyPPage,
+    Input::KeyNPage,
+];
+
+/// xorshift64*, so that a seed always replays the same run.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+}
+
+fn random_document(rng: &mut Rng) -> Vec<String> {
+    (0..1 + rng.below(12))
+        .map(|_| {
+            (0..rng.below(6))
+                .map(|_| *rng.pick(PIECES))
+                .collect::<String>()
+        })
+        .collect()
+}
+
+fn random_key(rng: &mut Rng) -> (Input, bool) {
+    match rng.below(10) {
+        0..=4 => (Input::Character(*rng.pick(CHARACTERS)), false),
+        5..=7 => (*rng.pick(SPECIAL_KEYS), rng.below(4) == 0),
+        _ => (Input::Character(*rng.pick(ALT_CHARACTERS)), true),
+    }
+}
+
+fn check_cursor(editor: &Editor, context: &str) {
+    let (x, y) = editor.cursor_pos();
+    let lines = editor.document.lines();
+    assert!(!lines.is_empty(), "{context}: document has no lines");
+    assert!(
+        y < lines.len(),
+        "{context}: cursor line {y} of {}",
+        lines.len()
+    );
+    assert!(
+        lines[y].is_char_boundary(x),
+        "{context}: cursor column {x} is not a character boundary of {:?}",
+        lines[y]
+    );
+}
+
+// Names the seed of a run that panics outside of the checks, e.g. in the editor
+struct ReportSeed(u64);
+
+impl Drop for ReportSeed {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            eprintln!("fuzz run failed with seed {}", self.0);
+        }
+    }
+}
+
+fn run(seed: u64) {
+    let _report = ReportSeed(seed);
+    let mut rng = Rng(seed.max(1));
+    let mut editor = Editor::new(None, None, None);
+    editor._set_clipboard_enabled_for_test(false);
+    editor.update_screen_size(12, 30);
+    let original = random_document(&mut rng);
+    editor.document.set_lines(original.clone());
+
+    let mut keys = Vec::new();
+    for _ in 0..KEYS_PER_RUN {
+        let (key, alt) = random_key(&mut rng);
+        keys.push((key, alt));
+        let context = format!("seed {seed}, keys {keys:?}");
+        editor.process_input(key, alt).unwrap();
+        check_cursor(&editor, &context);
+        editor.scroll();
+        #[cfg(feature = "virtual-terminal")]
+        editor.draw(&dmacs::screen::virtual_screen::VirtualScreen::new(12, 30));
+    }
+
+    // Everything typed undoes back to the original text and redoes to the result
+    let context = format!("seed {seed}, keys {keys:?}");
+    editor.mode = EditorMode::Normal;
+    let edited = editor.document.lines().clone();
+    let steps = editor.undo_redo.undo_stack.len();
+    for _ in 0..steps {
+        editor.undo();
+        check_cursor(&editor, &context);
+    }
+    assert_eq!(editor.document.lines(), original, "undo: {context}");
+    for _ in 0..steps {
+        editor.redo();
+        check_cursor(&editor, &context);
+    }
+    assert_eq!(editor.document.lines(), edited, "redo: {context}");
+}
+
+fn env_u64(name: &str) -> Option<u64> {
+    std::env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+}
+
+#[test]
+fn fuzz_input_pipeline() {
+    if let Some(seed) = env_u64("DMACS_FUZZ_SEED") {
+        run(seed);
+        return;
+    }
+    for seed in 1..=env_u64("DMACS_FUZZ_RUNS").unwrap_or(DEFAULT_RUNS) {
+        run(seed);
+    }
+}
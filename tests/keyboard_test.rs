@@ -0,0 +1,354 @@
+use dmacs::editor::Editor;
+use dmacs::editor::actions::Action;
+use dmacs::keyboard::{ChordKey, KeyChord, KeyInput, KeyParser, Utf8Assembler};
+use pancurses::Input;
+
+fn chord(params: &str, final_byte: char) -> KeyChord {
+    KeyChord::from_csi(params, final_byte).unwrap()
+}
+
+#[test]
+fn test_kitty_and_modify_other_keys_sequences() {
+    let ctrl_enter = KeyChord {
+        key: ChordKey::Enter,
+        ctrl: true,
+        alt: false,
+        shift: false,
+    };
+    assert_eq!(chord("13;5", 'u'), ctrl_enter);
+    assert_eq!(chord("27;5;13", '~'), ctrl_enter);
+    // Kitty reports the base key with shift, xterm the shifted letter
+    assert_eq!(chord("107;6", 'u').name(), "ctrl-shift-k");
+    assert_eq!(chord("27;6;75", '~').name(), "ctrl-shift-k");
+    assert_eq!(chord("107:75;6:1", 'u').name(), "ctrl-shift-k");
+    assert_eq!(chord("27", 'u').name(), "esc");
+    assert_eq!(chord("13;2", 'u').name(), "shift-enter");
+    assert_eq!(chord("115;4", 'u').name(), "alt-S");
+    assert_eq!(chord("49;4", 'u').name(), "alt-shift-1");
+    assert_eq!(chord("32;5", 'u').name(), "ctrl-space");
+    // Caps lock is ignored
+    assert_eq!(chord("97;69", 'u').name(), "ctrl-a");
+}
+
+#[test]
+fn test_unknown_sequences_are_not_chords() {
+    assert_eq!(KeyChord::from_csi("", 'I'), None);
+    assert_eq!(KeyChord::from_csi("200", '~'), None);
+    assert_eq!(KeyChord::from_csi("x;5", 'u'), None);
+    // Super is left to the window manager
+    assert_eq!(KeyChord::from_csi("97;9", 'u'), None);
+    // Function keys of kitty's private use area
+    assert_eq!(KeyChord::from_csi("57399;5", 'u'), None);
+}
+
+#[test]
+fn test_legacy_keys_for_chords_it_can_express() {
+    assert_eq!(
+        chord("120;5", 'u').legacy(),
+        Some((Input::Character('\x18'), false))
+    );
+    assert_eq!(
+        chord("115;3", 'u').legacy(),
+        Some((Input::Character('s'), true))
+    );
+    assert_eq!(
+        chord("115;4", 'u').legacy(),
+        Some((Input::Character('S'), true))
+    );
+    assert_eq!(chord("9;2", 'u').legacy(), Some((Input::KeyBTab, false)));
+    assert_eq!(
+        chord("127;3", 'u').legacy(),
+        Some((Input::KeyBackspace, true))
+    );
+    assert_eq!(
+        chord("45;6", 'u').legacy(),
+        Some((Input::Character('\x1f'), false))
+    );
+    assert_eq!(chord("13;5", 'u').legacy(), None);
+    assert_eq!(chord("107;6", 'u').legacy(), None);
+    assert_eq!(chord("120;7", 'u').legacy(), None);
+}
+
+#[test]
+fn test_fallback_drops_modifiers() {
+    assert_eq!(
+        chord("13;5", 'u').fallback(),
+        (Input::Character('\r'), false)
+    );
+    assert_eq!(
+        chord("107;6", 'u').fallback(),
+        (Input::Character('\x0b'), false)
+    );
+    assert_eq!(
+        chord("120;7", 'u').fallback(),
+        (Input::Character('x'), true)
+    );
+    assert_eq!(chord("49;4", 'u').fallback(), (Input::Character('1'), true));
+}
+
+#[test]
+fn test_chord_runs_its_binding() {
+    let mut editor = Editor::new(None, None, None);
+    editor
+        .document
+        .set_lines(vec!["one".to_string(), "two".to_string()]);
+    editor
+        .keymap
+        .bindings
+        .insert("ctrl-shift-k".to_string(), Action::GoToEndOfFile);
+    editor.process_chord(chord("107;6", 'u')).unwrap();
+    assert_eq!(editor.cursor_pos(), (3, 1));
+}
+
+#[test]
+fn test_unbound_chord_acts_as_plain_key() {
+    let mut editor = Editor::new(None, None, None);
+    editor.document.set_lines(vec!["ab".to_string()]);
+    editor.set_cursor_pos(1, 0);
+    editor.process_chord(chord("13;5", 'u')).unwrap();
+    assert_eq!(editor.document.lines(), vec!["a", "b"]);
+}
+
+fn no_key_name(_: i32) -> Option<String> {
+    None
+}
+
+fn parse(inputs: &[Input]) -> Vec<KeyInput> {
+    let mut parser = KeyParser::new(no_key_name);
+    for &input in inputs {
+        parser.feed(input);
+    }
+    parser.timeout();
+    std::iter::from_fn(|| parser.next_key()).collect()
+}
+
+fn chars(text: &str) -> Vec<Input> {
+    text.chars().map(Input::Character).collect()
+}
+
+fn names(keys: &[KeyInput]) -> Vec<String> {
+    keys.iter()
+        .map(|key| match key {
+            KeyInput::Chord(chord) => chord.name(),
+            other => format!("{other:?}"),
+        })
+        .collect()
+}
+
+#[test]
+fn test_parser_decodes_sequences_curses_does_not_know() {
+    let keys = parse(&chars(
+        "\x1b[1;5C\x1b[1;3A\x1b[7~\x1b[4;2~\x1b[15;5~\x1bOH\x1bOQ\x1b[Z",
+    ));
+    assert_eq!(
+        names(&keys),
+        vec![
+            "ctrl-right",
+            "alt-up",
+            "home",
+            "shift-end",
+            "ctrl-f5",
+            "home",
+            "f2",
+            "shift-tab"
+        ]
+    );
+    assert_eq!(
+        parse(&chars("\x1b[1;5D"))[0],
+        KeyInput::Chord(KeyChord {
+            key: ChordKey::Left,
+            ctrl: true,
+            alt: false,
+            shift: false,
+        })
+    );
+}
+
+#[test]
+fn test_parser_keeps_bare_escape_and_alt_keys() {
+    assert_eq!(
+        parse(&chars("\x1b")),
+        vec![KeyInput::Plain(Input::Character('\x1b'), false)]
+    );
+    assert_eq!(
+        parse(&[Input::Character('\x1b'), Input::KeyHome]),
+        vec![KeyInput::Plain(Input::KeyHome, true)]
+    );
+    assert_eq!(
+        parse(&chars("\x1bx\x1b\x7f")),
+        vec![
+            KeyInput::Plain(Input::Character('x'), true),
+            KeyInput::Plain(Input::KeyBackspace, true),
+        ]
+    );
+    // Alt with a key that is itself a sequence
+    assert_eq!(names(&parse(&chars("\x1b\x1b[1;5A"))), vec!["ctrl-alt-up"]);
+    assert_eq!(
+        parse(&chars("\x1b\x1b\x1b")),
+        vec![KeyInput::Plain(Input::Character('\x1b'), false); 3]
+    );
+}
+
+#[test]
+fn test_parser_gives_back_sequences_cut_short() {
+    assert_eq!(
+        parse(&chars("\x1b[1")),
+        vec![
+            KeyInput::Plain(Input::Character('['), true),
+            KeyInput::Plain(Input::Character('1'), false),
+        ]
+    );
+    assert_eq!(
+        parse(&chars("\x1bOx")),
+        vec![
+            KeyInput::Plain(Input::Character('O'), true),
+            KeyInput::Plain(Input::Character('x'), false),
+        ]
+    );
+    // Unknown sequences are dropped whole
+    assert_eq!(parse(&chars("\x1b[I\x1b[<0;3;4Ma")).len(), 1);
+}
+
+#[test]
+fn test_parser_passes_curses_keys_through() {
+    assert_eq!(
+        parse(&[Input::KeyResize, Input::Character('a'), Input::KeyUp]),
+        vec![
+            KeyInput::Plain(Input::KeyResize, false),
+            KeyInput::Plain(Input::Character('a'), false),
+            KeyInput::Plain(Input::KeyUp, false),
+        ]
+    );
+    assert_eq!(names(&parse(&[Input::KeySLeft])), vec!["shift-left"]);
+    assert_eq!(parse(&chars("\x1b[200~")), vec![KeyInput::PasteStart]);
+}
+
+#[test]
+fn test_parser_names_extended_curses_keys() {
+    fn key_name(code: i32) -> Option<String> {
+        match code {
+            565 => Some("kUP5".to_string()),
+            566 => Some("kDC3".to_string()),
+            289 => Some("KEY_F(25)".to_string()),
+            _ => None,
+        }
+    }
+    let mut parser = KeyParser::new(key_name);
+    for code in [565, 566, 289, 999] {
+        parser.feed(Input::Unknown(code));
+    }
+    let keys: Vec<KeyInput> = std::iter::from_fn(|| parser.next_key()).collect();
+    assert_eq!(
+        names(&keys),
+        vec![
+            "ctrl-up",
+            "alt-delete",
+            "ctrl-f1",
+            "Plain(Unknown(999), false)"
+        ]
+    );
+}
+
+#[test]
+fn test_legacy_keys_of_special_keys() {
+    assert_eq!(
+        KeyChord::from_csi("1;3", 'D').unwrap().legacy(),
+        Some((Input::KeyLeft, true))
+    );
+    assert_eq!(
+        KeyChord::from_csi("6", '~').unwrap().legacy(),
+        Some((Input::KeyNPage, false))
+    );
+    assert_eq!(KeyChord::from_csi("1;5", 'D').unwrap().legacy(), None);
+    assert_eq!(
+        KeyChord::from_csi("1;5", 'D').unwrap().fallback(),
+        (Input::KeyLeft, false)
+    );
+}
+
+#[test]
+fn test_ctrl_arrow_runs_its_binding() {
+    let mut editor = Editor::new(None, None, None);
+    editor.document.set_lines(vec!["one two".to_string()]);
+    editor
+        .keymap
+        .bindings
+        .insert("ctrl-right".to_string(), Action::GoToEndOfLine);
+    editor
+        .process_chord(KeyChord::from_csi("1;5", 'C').unwrap())
+        .unwrap();
+    assert_eq!(editor.cursor_pos(), (7, 0));
+}
+
+fn feed_bytes(parser: &mut KeyParser, bytes: &[u8]) -> Vec<KeyInput> {
+    for &byte in bytes {
+        parser.feed_byte(byte);
+    }
+    std::iter::from_fn(|| parser.next_key()).collect()
+}
+
+fn typed(text: &str) -> Vec<KeyInput> {
+    text.chars()
+        .map(|c| KeyInput::Plain(Input::Character(c), false))
+        .collect()
+}
+
+#[test]
+fn test_characters_split_across_reads_are_reassembled() {
+    let bytes = "日本語".as_bytes();
+    let mut parser = KeyParser::new(no_key_name);
+    // Split in the middle of each character, as an input method may write
+    let mut keys = feed_bytes(&mut parser, &bytes[..2]);
+    assert!(keys.is_empty());
+    assert!(parser.is_pending());
+    parser.timeout();
+    keys.extend(feed_bytes(&mut parser, &bytes[2..4]));
+    parser.timeout();
+    keys.extend(feed_bytes(&mut parser, &bytes[4..]));
+    assert_eq!(keys, typed("日本語"));
+    assert!(!parser.is_pending());
+}
+
+#[test]
+fn test_alt_with_a_composed_character() {
+    let mut parser = KeyParser::new(no_key_name);
+    let mut bytes = vec![0x1b];
+    bytes.extend("é".as_bytes());
+    assert_eq!(
+        feed_bytes(&mut parser, &bytes),
+        vec![KeyInput::Plain(Input::Character('é'), true)]
+    );
+}
+
+#[test]
+fn test_incomplete_characters_are_dropped() {
+    let mut parser = KeyParser::new(no_key_name);
+    let bytes = "あ".as_bytes();
+    // A character cut short by the next one
+    let mut stream = bytes[..2].to_vec();
+    stream.extend("a😀".as_bytes());
+    // A stray continuation byte
+    stream.push(0x80);
+    stream.push(b'b');
+    assert_eq!(feed_bytes(&mut parser, &stream), typed("a😀b"));
+
+    // Given up after waiting for the rest long enough
+    feed_bytes(&mut parser, &bytes[..1]);
+    for _ in 0..20 {
+        parser.timeout();
+    }
+    assert!(!parser.is_pending());
+    assert_eq!(feed_bytes(&mut parser, b"c"), typed("c"));
+}
+
+#[test]
+fn test_utf8_assembler_rejects_invalid_sequences() {
+    let mut utf8 = Utf8Assembler::default();
+    // An overlong encoding of '/'
+    assert_eq!(utf8.push(0xe0), None);
+    assert_eq!(utf8.push(0x80), None);
+    assert_eq!(utf8.push(0xaf), None);
+    assert!(!utf8.is_pending());
+    assert_eq!(utf8.push(0xc3), None);
+    assert_eq!(utf8.push(0xa9), Some('é'));
+}
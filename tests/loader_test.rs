@@ -0,0 +1,79 @@
+use dmacs::editor::Editor;
+use dmacs::loader::{FileLoader, describe_progress, read_with_progress};
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use tempfile::NamedTempFile;
+
+#[test]
+fn test_read_with_progress_counts_bytes() {
+    let content = "line\n".repeat(50_000);
+    let progress = AtomicU64::new(0);
+    let cancelled = AtomicBool::new(false);
+    let bytes = read_with_progress(content.as_bytes(), &progress, &cancelled)
+        .unwrap()
+        .unwrap();
+    assert_eq!(bytes, content.as_bytes());
+    assert_eq!(progress.load(Ordering::SeqCst), content.len() as u64);
+}
+
+#[test]
+fn test_read_with_progress_stops_when_cancelled() {
+    let progress = AtomicU64::new(0);
+    let cancelled = AtomicBool::new(true);
+    assert!(read_with_progress("text".as_bytes(), &progress, &cancelled).is_none());
+    assert_eq!(progress.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn test_file_loader_reads_file() {
+    let mut file = NamedTempFile::new().unwrap();
+    write!(file, "first\r\nsecond\r\n").unwrap();
+    let filename = file.path().to_str().unwrap().to_string();
+
+    let loader = FileLoader::start(&filename);
+    let content = loader.finish().unwrap();
+    let editor = Editor::from_loaded(filename, content, Some(2), None);
+    assert_eq!(editor.document.lines(), vec!["first", "second"]);
+    assert_eq!(editor.document.line_ending.name(), "CRLF");
+    assert_eq!(editor.cursor_pos(), (0, 1));
+}
+
+#[test]
+fn test_file_loader_missing_file_opens_empty_document() {
+    let dir = tempfile::tempdir().unwrap();
+    let filename = dir.path().join("new.md").to_str().unwrap().to_string();
+
+    let content = FileLoader::start(&filename).finish().unwrap();
+    assert!(content.is_err());
+    let editor = Editor::from_loaded(filename.clone(), content, None, None);
+    assert_eq!(editor.document.lines(), vec![""]);
+    assert_eq!(editor.document.filename, Some(filename));
+}
+
+#[test]
+fn test_fresh_editor_starts_at_requested_line() {
+    let mut file = NamedTempFile::new().unwrap();
+    write!(file, "first\nsecond\n").unwrap();
+    let filename = file.path().to_str().unwrap().to_string();
+
+    let content = FileLoader::start(&filename).finish().unwrap();
+    let editor = Editor::from_loaded_fresh(filename.clone(), content, None, None);
+    assert_eq!(editor.document.lines(), vec!["first", "second"]);
+    assert_eq!(editor.cursor_pos(), (0, 0));
+
+    let content = FileLoader::start(&filename).finish().unwrap();
+    let editor = Editor::from_loaded_fresh(filename, content, Some(2), None);
+    assert_eq!(editor.cursor_pos(), (0, 1));
+}
+
+#[test]
+fn test_describe_progress() {
+    assert_eq!(
+        describe_progress("/mnt/share/notes.md", 1024 * 1024, 4 * 1024 * 1024),
+        "Loading notes.md... 1.0 MB / 4.0 MB (25%)"
+    );
+    assert_eq!(
+        describe_progress("notes.md", 512 * 1024, 0),
+        "Loading notes.md... 0.5 MB"
+    );
+}
@@ -0,0 +1,33 @@
+use dmacs::paste::normalize;
+
+#[test]
+fn test_bullets_become_list_items() {
+    assert_eq!(
+        normalize("• milk\r\n◦ whole\r\n\t• eggs\r\n* bread"),
+        "- milk\n  - whole\n  - eggs\n- bread"
+    );
+}
+
+#[test]
+fn test_checkbox_glyphs() {
+    assert_eq!(normalize("☐ call\n☑ write"), "- [ ] call\n- [x] write");
+}
+
+#[test]
+fn test_quotes_and_spaces() {
+    assert_eq!(
+        normalize("“It’s\u{a0}fine,”\u{200b} she said.   "),
+        "\"It's fine,\" she said."
+    );
+}
+
+#[test]
+fn test_collapses_blank_lines() {
+    assert_eq!(normalize("a\n\n\n\nb"), "a\n\nb");
+}
+
+#[test]
+fn test_leaves_markdown_alone() {
+    let text = "## Title\n- [ ] task\n  - item\n2 * 3";
+    assert_eq!(normalize(text), text);
+}
@@ -192,3 +192,46 @@ fn test_get_cursor_position_with_scroll_restoration() {
 
     teardown_test_env(&temp_dir);
 }
+
+#[test]
+fn test_record_recent_files() {
+    let temp_dir = setup_test_env();
+    let path = persistence::recent_files_path(Some(temp_dir.clone())).unwrap();
+    assert!(persistence::load_recent_files(&path).is_empty());
+
+    persistence::record_recent_file(&path, "/notes/a.md");
+    persistence::record_recent_file(&path, "/notes/b.md");
+    persistence::record_recent_file(&path, "/notes/a.md");
+
+    let files: Vec<String> = persistence::load_recent_files(&path)
+        .into_iter()
+        .map(|file| file.file_path)
+        .collect();
+    assert_eq!(files, vec!["/notes/a.md", "/notes/b.md"]);
+
+    teardown_test_env(&temp_dir);
+}
+
+#[test]
+fn test_take_last_closed_file() {
+    let temp_dir = setup_test_env();
+    let path = persistence::closed_files_path(Some(temp_dir.clone())).unwrap();
+    let a = temp_dir.join("a.md").to_string_lossy().to_string();
+    let b = temp_dir.join("b.md").to_string_lossy().to_string();
+    let gone = temp_dir.join("gone.md").to_string_lossy().to_string();
+    fs::write(&a, "").unwrap();
+    fs::write(&b, "").unwrap();
+    persistence::record_closed_file(&path, &a);
+    persistence::record_closed_file(&path, &b);
+    persistence::record_closed_file(&path, &gone);
+
+    // Missing files and the file already open are passed over
+    assert_eq!(
+        persistence::take_last_closed_file(&path, Some(&b)),
+        Some(a.clone())
+    );
+    assert_eq!(persistence::take_last_closed_file(&path, None), Some(b));
+    assert_eq!(persistence::take_last_closed_file(&path, None), None);
+
+    teardown_test_env(&temp_dir);
+}
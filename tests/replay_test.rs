@@ -0,0 +1,143 @@
+use dmacs::Event;
+use dmacs::editor::Editor;
+use dmacs::editor::mouse::MouseEvent;
+use dmacs::keyboard::{ChordKey, KeyChord};
+use dmacs::replay::{InputRecorder, InputReplay};
+use pancurses::Input;
+use std::fs;
+use tempfile::TempDir;
+
+fn session() -> Vec<Event> {
+    vec![
+        Event::Key(Input::Character('h'), false),
+        Event::Key(Input::Character('é'), false),
+        Event::Key(Input::Character('\n'), false),
+        Event::Key(Input::Character('x'), false),
+        Event::Mouse(MouseEvent::Press { row: 0, col: 1 }),
+        Event::Key(Input::KeyBackspace, true),
+        Event::Key(Input::KeyDown, false),
+        Event::Key(Input::Character('\x1f'), false),
+        Event::Chord(KeyChord {
+            key: ChordKey::Enter,
+            ctrl: true,
+            alt: false,
+            shift: false,
+        }),
+        Event::Quit,
+        Event::Resize,
+    ]
+}
+
+fn apply(editor: &mut Editor, event: Event) {
+    match event {
+        Event::Key(key, alt) => editor.process_input(key, alt).unwrap(),
+        Event::Chord(chord) => editor.process_chord(chord).unwrap(),
+        Event::Mouse(mouse_event) => editor.handle_mouse_event(mouse_event),
+        _ => {}
+    }
+}
+
+#[test]
+fn test_replay_reproduces_recorded_session() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("session.jsonl");
+
+    let mut recorded = Editor::new(None, None, None);
+    recorded.update_screen_size(20, 60);
+    let mut recorder = InputRecorder::create(&path).unwrap();
+    recorder.start((20, 60));
+    for event in session() {
+        recorder.record(&event, (24, 80));
+        apply(&mut recorded, event);
+    }
+    drop(recorder);
+
+    let mut replay = InputReplay::load(&path).unwrap();
+    replay.start();
+    let mut replayed = Editor::new(None, None, None);
+    replayed.update_screen_size(20, 60);
+    while !replay.is_finished() {
+        if let Some(event) = replay.next_event() {
+            apply(&mut replayed, event);
+        }
+    }
+
+    assert_eq!(replayed.document.lines(), recorded.document.lines());
+    assert_eq!(
+        (replayed.cursor.x, replayed.cursor.y),
+        (recorded.cursor.x, recorded.cursor.y)
+    );
+    // Everything but the Ctrl-C, plus the initial screen size
+    assert_eq!(replay.replayed(), 11);
+    assert_eq!(replay.screen_size(), Some((24, 80)));
+}
+
+#[test]
+fn test_replay_waits_for_recorded_time() {
+    let mut replay = InputReplay::parse(
+        r#"{"ms":0,"type":"key","key":"a","alt":false}
+{"ms":60000,"type":"key","key":"KeyUp","alt":true}"#,
+    )
+    .unwrap();
+    replay.start();
+
+    assert!(matches!(
+        replay.next_event(),
+        Some(Event::Key(Input::Character('a'), false))
+    ));
+    assert!(replay.next_event().is_none());
+    assert!(!replay.is_finished());
+}
+
+#[test]
+fn test_replay_skips_unknown_keys() {
+    let mut replay = InputReplay::parse(
+        r#"{"ms":0,"type":"key","key":"KeyFromTheFuture","alt":false}
+{"ms":0,"type":"key","key":"KeyUp","alt":true}"#,
+    )
+    .unwrap();
+
+    assert!(matches!(
+        replay.next_event(),
+        Some(Event::Key(Input::KeyUp, true))
+    ));
+    assert!(replay.is_finished());
+    assert_eq!(replay.replayed(), 1);
+}
+
+#[test]
+fn test_replay_rejects_malformed_lines() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("broken.jsonl");
+    fs::write(
+        &path,
+        "{\"ms\":0,\"type\":\"key\",\"key\":\"a\",\"alt\":false}\nnot json\n",
+    )
+    .unwrap();
+
+    let error = InputReplay::load(&path).err().unwrap();
+    assert!(error.to_string().contains("line 2"));
+}
+
+#[test]
+fn test_replay_groups_undo_steps_by_recorded_time() {
+    let mut replay = InputReplay::parse(
+        r#"{"ms":0,"type":"key","key":"a","alt":false}
+{"ms":100,"type":"key","key":"b","alt":false}
+{"ms":2000,"type":"key","key":"c","alt":false}"#,
+    )
+    .unwrap();
+    let mut editor = Editor::new(None, None, None);
+    editor.set_clock(replay.clock());
+    replay.start();
+    while !replay.is_finished() {
+        if let Some(event) = replay.next_event() {
+            apply(&mut editor, event);
+        }
+    }
+
+    assert_eq!(editor.document.lines()[0], "abc");
+    assert_eq!(editor.undo_redo.undo_stack.len(), 2);
+    editor.undo();
+    assert_eq!(editor.document.lines()[0], "ab");
+}
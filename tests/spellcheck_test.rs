@@ -0,0 +1,72 @@
+use dmacs::spellcheck::{SpellChecker, checked_words};
+use std::fs;
+use tempfile::TempDir;
+
+fn words_of(line: &str) -> Vec<&str> {
+    checked_words(line)
+        .into_iter()
+        .map(|(start, end)| &line[start..end])
+        .collect()
+}
+
+#[test]
+fn test_checked_words_skip_code_links_and_annotations() {
+    assert_eq!(
+        words_of("- [ ] Call Bob about the `cargo_build` flag @due(2025-01-01)"),
+        vec!["Call", "Bob", "about", "the", "flag"]
+    );
+    assert_eq!(
+        words_of("see https://example.com and ~/notes/todo.md #work"),
+        vec!["see", "and"]
+    );
+    // Acronyms, camelCase, words with digits and non-Latin text are left alone
+    assert_eq!(
+        words_of("NASA uses camelCase 2nd 日本語 don't"),
+        vec!["uses", "don't"]
+    );
+}
+
+#[test]
+fn test_misspellings_with_english_endings() {
+    let checker = SpellChecker::from_words(["make", "the", "cat", "happy", "London"]);
+    let line = "The cats making London hapy";
+    let misspelled: Vec<&str> = checker
+        .misspellings(line)
+        .into_iter()
+        .map(|(start, end)| &line[start..end])
+        .collect();
+    assert_eq!(misspelled, vec!["hapy"]);
+    // Capitals in the dictionary are required
+    assert!(!checker.is_correct("london"));
+}
+
+#[test]
+fn test_suggestions_closest_first() {
+    let checker = SpellChecker::from_words(["receive", "relieve", "deceive", "recipe"]);
+    // Ties go to words starting with the same letter
+    assert_eq!(
+        checker.suggestions("recieve", 3),
+        vec!["receive", "relieve", "recipe"]
+    );
+    assert_eq!(checker.suggestions("Recieve", 1), vec!["Receive"]);
+    assert!(checker.suggestions("xyzzy", 3).is_empty());
+}
+
+#[test]
+fn test_load_dictionary_and_user_words() {
+    let dir = TempDir::new().unwrap();
+    let dictionaries = dir.path().join(".dmacs").join("dictionaries");
+    fs::create_dir_all(&dictionaries).unwrap();
+    // hunspell format: a word count, then words with affix flags
+    fs::write(dictionaries.join("xx.dic"), "2\nhello/MS\nworld\n").unwrap();
+
+    let mut checker = SpellChecker::load("xx", Some(dir.path().to_path_buf())).unwrap();
+    assert!(checker.is_correct("hello"));
+    assert!(!checker.is_correct("dmacs"));
+    checker.add_word("dmacs").unwrap();
+    assert!(checker.is_correct("dmacs"));
+
+    let reloaded = SpellChecker::load("xx", Some(dir.path().to_path_buf())).unwrap();
+    assert!(reloaded.is_correct("dmacs"));
+    assert!(SpellChecker::load("yy", Some(dir.path().to_path_buf())).is_err());
+}
@@ -0,0 +1,25 @@
+use dmacs::terminal::nearest_palette_color;
+
+#[test]
+fn test_nearest_palette_color_exact_matches() {
+    assert_eq!(nearest_palette_color("#000000").unwrap(), 16);
+    assert_eq!(nearest_palette_color("#ffffff").unwrap(), 231);
+    assert_eq!(nearest_palette_color("#ff0000").unwrap(), 196);
+    assert_eq!(nearest_palette_color("#5f87af").unwrap(), 67);
+    assert_eq!(nearest_palette_color("#808080").unwrap(), 244);
+}
+
+#[test]
+fn test_nearest_palette_color_of_default_colors() {
+    // Dark grays go to the gray ramp, the amber bold color to the cube
+    assert_eq!(nearest_palette_color("#33302d").unwrap(), 236);
+    assert_eq!(nearest_palette_color("#d0d0d0").unwrap(), 252);
+    assert_eq!(nearest_palette_color("#f5c373").unwrap(), 215);
+}
+
+#[test]
+fn test_nearest_palette_color_rejects_invalid_colors() {
+    assert!(nearest_palette_color("#fff").is_err());
+    assert!(nearest_palette_color("#gg0000").is_err());
+    assert!(nearest_palette_color("#ffé000").is_err());
+}
@@ -0,0 +1,46 @@
+use dmacs::todotxt::{export, from_todotxt, import, to_todotxt};
+
+#[test]
+fn test_to_todotxt() {
+    assert_eq!(
+        to_todotxt("- [ ] (A) Call mom +family @phone @due(2025-06-12)").as_deref(),
+        Some("(A) Call mom +family @phone due:2025-06-12")
+    );
+    assert_eq!(
+        to_todotxt("  - [x] (B) Water plants @done(2025-06-10) +garden").as_deref(),
+        Some("x 2025-06-10 Water plants +garden pri:B")
+    );
+    assert_eq!(to_todotxt("- just a list item"), None);
+    assert_eq!(to_todotxt("# heading"), None);
+}
+
+#[test]
+fn test_from_todotxt() {
+    assert_eq!(
+        from_todotxt("(A) Call mom +family @phone due:2025-06-12").as_deref(),
+        Some("- [ ] (A) Call mom +family @phone @due(2025-06-12)")
+    );
+    assert_eq!(
+        from_todotxt("x 2025-06-10 2025-06-01 Water plants +garden pri:B").as_deref(),
+        Some("- [x] (B) 2025-06-01 Water plants +garden @done(2025-06-10)")
+    );
+    // URLs are not tags
+    assert_eq!(
+        from_todotxt("Read https://example.com").as_deref(),
+        Some("- [ ] Read https://example.com")
+    );
+    assert_eq!(from_todotxt("   "), None);
+}
+
+#[test]
+fn test_round_trip() {
+    let markdown = "- [ ] (A) Call mom +family @phone @due(2025-06-12)\n\
+                    - [x] (C) Pay rent @spent(15m) @done(2025-06-01)\n";
+    assert_eq!(import(&export(markdown)), markdown);
+}
+
+#[test]
+fn test_export_skips_non_tasks() {
+    let markdown = "# Today\n- [ ] one\nnotes\n\n- [x] two\n";
+    assert_eq!(export(markdown), "one\nx two\n");
+}
@@ -0,0 +1,81 @@
+use dmacs::error::DmacsError;
+use dmacs::trash::Trash;
+use std::fs;
+use tempfile::TempDir;
+
+fn setup() -> (TempDir, Trash) {
+    let dir = TempDir::new().unwrap();
+    let trash = Trash::new_with_base_dir(Some(dir.path().to_path_buf())).unwrap();
+    (dir, trash)
+}
+
+#[test]
+fn test_move_to_trash_removes_file_and_lists_it() {
+    let (dir, trash) = setup();
+    let path = dir.path().join("notes.md");
+    fs::write(&path, "keep me").unwrap();
+
+    let trashed = trash.move_to_trash(&path).unwrap();
+
+    assert!(!path.exists());
+    assert!(trashed.name.ends_with("-notes.md"));
+    assert_eq!(
+        trashed.original_path,
+        dir.path().canonicalize().unwrap().join("notes.md")
+    );
+    assert_eq!(trash.list().unwrap(), vec![trashed]);
+}
+
+#[test]
+fn test_same_file_trashed_twice_keeps_both() {
+    let (dir, trash) = setup();
+    let path = dir.path().join("notes.md");
+    fs::write(&path, "first").unwrap();
+    let first = trash.move_to_trash(&path).unwrap();
+    fs::write(&path, "second").unwrap();
+    let second = trash.move_to_trash(&path).unwrap();
+
+    assert_ne!(first.name, second.name);
+    assert_eq!(trash.list().unwrap().len(), 2);
+}
+
+#[test]
+fn test_restore_moves_file_back() {
+    let (dir, trash) = setup();
+    let path = dir.path().join("sub").join("notes.md");
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(&path, "keep me").unwrap();
+    let trashed = trash.move_to_trash(&path).unwrap();
+    fs::remove_dir(path.parent().unwrap()).unwrap();
+
+    let restored = trash.restore(&trashed.name).unwrap();
+
+    assert_eq!(restored, trashed.original_path);
+    assert_eq!(fs::read_to_string(&restored).unwrap(), "keep me");
+    assert!(trash.list().unwrap().is_empty());
+}
+
+#[test]
+fn test_restore_does_not_overwrite_existing_file() {
+    let (dir, trash) = setup();
+    let path = dir.path().join("notes.md");
+    fs::write(&path, "old").unwrap();
+    let trashed = trash.move_to_trash(&path).unwrap();
+    fs::write(&path, "new").unwrap();
+
+    assert!(matches!(
+        trash.restore(&trashed.name),
+        Err(DmacsError::Io(_))
+    ));
+    assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+    assert_eq!(trash.list().unwrap().len(), 1);
+}
+
+#[test]
+fn test_restore_unknown_name() {
+    let (_dir, trash) = setup();
+    assert!(matches!(
+        trash.restore("missing.md"),
+        Err(DmacsError::NotInTrash(name)) if name == "missing.md"
+    ));
+}